@@ -0,0 +1,338 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::graph::Graph;
+use crate::error::Error;
+use crate::process::patch;
+use crate::schema::Schema;
+use crate::tools::{pointer_escape, pointer_unescape, HTTP_METHODS};
+
+// change kinds breaking existing clients unless allowed by policy
+static BREAKING: &[&str] = &[
+    "path-removed",
+    "operation-removed",
+    "property-removed",
+    "required-added",
+    "type-changed",
+    "enum-added",
+    "enum-value-removed",
+    "enum-value-changed",
+    "parameter-removed",
+    "required-parameter-added",
+    "response-removed",
+];
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Change {
+    pub kind: String,
+    pub path: String,
+    pub breaking: bool,
+    pub audiences: Vec<String>,
+    pub allowed: bool,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Audience {
+    pub name: String,
+
+    // operations with any of tags belong to audience, audience without tags gets everything else
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    // change kinds allowed even if breaking, * allows everything
+    #[serde(default)]
+    pub allow: Vec<String>,
+
+    // change kinds failing even if not breaking
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Policy {
+    pub audiences: Vec<Audience>,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            audiences: vec![Audience {
+                name: "default".to_string(),
+                ..Audience::default()
+            }],
+        }
+    }
+}
+
+impl Policy {
+    // every change has to be judged by some audience, otherwise it would pass unchecked
+    pub fn from_schema(schema: &Schema) -> Result<Self, Error> {
+        let policy: Self =
+            serde_json::from_value(schema.get_body().clone()).map_err(Error::SerdeJsonError)?;
+
+        if !policy.audiences.iter().any(|a| a.tags.is_empty()) {
+            return Err(Error::AnalyzeInvalidPolicy(
+                "default audience without tags is required".to_string(),
+            ));
+        }
+
+        Ok(policy)
+    }
+
+    fn audiences(&self, tags: &HashSet<String>) -> Vec<&Audience> {
+        let mut matched = self
+            .audiences
+            .iter()
+            .filter(|a| a.tags.iter().any(|t| tags.contains(t)))
+            .collect::<Vec<_>>();
+
+        // untagged or not matched operations belong to default audience
+        if matched.is_empty() || tags.is_empty() {
+            matched.extend(self.audiences.iter().filter(|a| a.tags.is_empty()));
+        }
+
+        matched
+    }
+}
+
+impl Audience {
+    fn allows(&self, kind: &str, breaking: bool) -> bool {
+        if self.deny.iter().any(|d| d == kind) {
+            return false;
+        }
+
+        !breaking || self.allow.iter().any(|a| a == kind || a == "*")
+    }
+}
+
+// kind of change based on json patch operation
+fn classify(op: &str, parts: &[String], value: Option<&Value>) -> String {
+    let len = parts.len();
+    let last = parts.last().map(|s| s.as_str()).unwrap_or("");
+    let parent = if len > 1 { parts[len - 2].as_str() } else { "" };
+
+    let kind = match (op, parent, last) {
+        ("add", "paths", _) if len == 2 => "path-added",
+        ("remove", "paths", _) if len == 2 => "path-removed",
+        ("add", _, m) if len == 3 && parts[0] == "paths" && HTTP_METHODS.contains(&m) => {
+            "operation-added"
+        }
+        ("remove", _, m) if len == 3 && parts[0] == "paths" && HTTP_METHODS.contains(&m) => {
+            "operation-removed"
+        }
+        ("add", "properties", _) => "property-added",
+        ("remove", "properties", _) => "property-removed",
+        ("add", "required", _) => "required-added",
+        ("remove", "required", _) => "required-removed",
+        ("add", _, "required") if value.map(|v| v.is_array()).unwrap_or(false) => "required-added",
+        ("remove", _, "required") if parent != "properties" => "required-removed",
+        (_, _, "type") if op != "remove" => "type-changed",
+        ("add", _, "enum") => "enum-added",
+        ("remove", _, "enum") => "enum-removed",
+        ("add", "enum", _) => "enum-value-added",
+        ("remove", "enum", _) => "enum-value-removed",
+        ("replace", "enum", _) => "enum-value-changed",
+        ("add", "parameters", _)
+            if value.and_then(|v| v.get("required")) == Some(&Value::Bool(true)) =>
+        {
+            "required-parameter-added"
+        }
+        ("add", "parameters", _) => "parameter-added",
+        ("remove", "parameters", _) => "parameter-removed",
+        ("add", "responses", _) => "response-added",
+        ("remove", "responses", _) => "response-removed",
+        (_, _, "deprecated") => "deprecated",
+        _ => "other",
+    };
+
+    kind.to_string()
+}
+
+// tags of operations affected by change at given pointer
+fn affected_tags(root: &Value, graph: &Graph, parts: &[String]) -> HashSet<String> {
+    let mut operations: Vec<(String, String)> = vec![];
+
+    match parts.first().map(|s| s.as_str()) {
+        Some("paths") if parts.len() >= 3 && HTTP_METHODS.contains(&parts[2].as_str()) => {
+            operations.push((parts[1].clone(), parts[2].clone()))
+        }
+        Some("paths") if parts.len() >= 2 => {
+            for method in HTTP_METHODS {
+                operations.push((parts[1].clone(), method.to_string()))
+            }
+        }
+        Some("components") if parts.len() >= 3 => {
+            let id = format!(
+                "#/components/{}/{}",
+                pointer_escape(&parts[1]),
+                pointer_escape(&parts[2])
+            );
+
+            // every operation transitively using component
+            let mut visited: HashSet<String> = HashSet::new();
+            let mut queue = vec![id];
+            while let Some(current) = queue.pop() {
+                for dependent in graph.dependents(&current) {
+                    if visited.insert(dependent.to_string()) {
+                        queue.push(dependent.to_string());
+                    }
+                }
+            }
+
+            for id in visited {
+                if let Some((method, path)) = id.split_once(' ') {
+                    operations.push((path.to_string(), method.to_lowercase()));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    operations
+        .iter()
+        .filter_map(|(path, method)| {
+            root.pointer(&format!("/paths/{}/{}/tags", pointer_escape(path), method))
+        })
+        .filter_map(|tags| tags.as_array())
+        .flatten()
+        .filter_map(|t| t.as_str().map(|s| s.to_string()))
+        .collect()
+}
+
+// changes between specifications evaluated against policy
+pub fn check(original: &Value, modified: &Value, policy: &Policy) -> Vec<Change> {
+    let original_graph = Graph::new(original);
+    let modified_graph = Graph::new(modified);
+
+    let operations = match patch::diff(original, modified) {
+        Value::Array(list) => list,
+        _ => vec![],
+    };
+
+    operations
+        .iter()
+        .map(|operation| {
+            let op = operation["op"].as_str().unwrap_or("");
+            let path = operation["path"].as_str().unwrap_or("").to_string();
+            let parts = path
+                .split('/')
+                .skip(1)
+                .map(pointer_unescape)
+                .collect::<Vec<_>>();
+
+            let kind = classify(op, &parts, operation.get("value"));
+            let breaking = BREAKING.contains(&kind.as_str());
+
+            let mut tags = affected_tags(original, &original_graph, &parts);
+            tags.extend(affected_tags(modified, &modified_graph, &parts));
+
+            let audiences = policy.audiences(&tags);
+
+            Change {
+                allowed: !audiences.is_empty()
+                    && audiences.iter().all(|a| a.allows(&kind, breaking)),
+                audiences: audiences.iter().map(|a| a.name.clone()).collect(),
+                kind,
+                path,
+                breaking,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec(user_enum: Value, internal_required: Value) -> Value {
+        json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "tags": ["users"],
+                        "responses": {
+                            "200": { "$ref": "#/components/responses/Users" }
+                        }
+                    }
+                },
+                "/admin": {
+                    "post": {
+                        "tags": ["internal"],
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "type": "object", "required": internal_required }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "responses": {
+                    "Users": {
+                        "content": {
+                            "application/json": {
+                                "schema": { "$ref": "#/components/schemas/Role" }
+                            }
+                        }
+                    }
+                },
+                "schemas": {
+                    "Role": { "type": "string", "enum": user_enum }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_policy_per_audience() {
+        let policy: Policy = serde_json::from_value(json!({
+            "audiences": [
+                { "name": "internal", "tags": ["internal"], "allow": ["*"] },
+                { "name": "public", "allow": ["enum-value-added"] }
+            ]
+        }))
+        .unwrap();
+
+        let original = spec(json!(["admin", "user"]), json!(["a"]));
+
+        let changes = check(
+            &original,
+            &spec(json!(["admin", "user", "guest"]), json!(["a", "b"])),
+            &policy,
+        );
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].kind, "required-added");
+        assert_eq!(changes[0].audiences, vec!["internal"]);
+        assert_eq!(changes[1].kind, "enum-value-added");
+        assert_eq!(changes[1].audiences, vec!["public"]);
+        assert_eq!(true, changes.iter().all(|c| c.allowed));
+
+        let changes = check(&original, &spec(json!(["admin"]), json!(["a"])), &policy);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, "enum-value-removed");
+        assert_eq!(changes[0].allowed, false);
+    }
+
+    #[test]
+    fn test_policy_requires_default_audience() {
+        for audiences in &[
+            json!([]),
+            json!([{ "name": "internal", "tags": ["internal"], "allow": ["*"] }]),
+        ] {
+            let result = Policy::from_schema(&Schema::from_json(json!({ "audiences": audiences })));
+
+            assert_eq!(true, matches!(result, Err(Error::AnalyzeInvalidPolicy(_))));
+        }
+
+        let policy = Policy { audiences: vec![] };
+        let original = spec(json!(["admin", "user"]), json!(["a"]));
+        let changes = check(&original, &spec(json!(["admin"]), json!(["a"])), &policy);
+
+        assert_eq!(changes[0].allowed, false);
+    }
+}
@@ -0,0 +1,246 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::tools::{pointer_escape, HTTP_METHODS};
+
+pub static GRAPH_FORMATS: &[&str] = &["dot", "json"];
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Node {
+    // local pointer of component or METHOD /path of operation
+    pub id: String,
+    pub kind: String,
+    pub name: String,
+    pub uses: usize,
+    pub used_by: usize,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+}
+
+// reference graph between reusable components and operations
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct Graph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl Graph {
+    pub fn new(root: &Value) -> Self {
+        let mut graph = Self::default();
+        let mut sources: Vec<(String, &Value)> = vec![];
+
+        if let Some(Value::Object(components)) = root.get("components") {
+            for (kind, entries) in components {
+                if let Value::Object(entries) = entries {
+                    for (name, node) in entries {
+                        let id = format!("#/components/{}/{}", kind, pointer_escape(name));
+                        graph.add_node(&id, kind, name);
+                        sources.push((id, node));
+                    }
+                }
+            }
+        }
+
+        for container in &["definitions", "$defs"] {
+            if let Some(Value::Object(entries)) = root.get(*container) {
+                for (name, node) in entries {
+                    let id = format!("#/{}/{}", container, pointer_escape(name));
+                    graph.add_node(&id, "schemas", name);
+                    sources.push((id, node));
+                }
+            }
+        }
+
+        if let Some(Value::Object(paths)) = root.get("paths") {
+            for (path, item) in paths {
+                for method in HTTP_METHODS {
+                    if let Some(operation) = item.get(*method) {
+                        let id = format!("{} {}", method.to_uppercase(), path);
+                        let name = operation
+                            .get("operationId")
+                            .and_then(|o| o.as_str())
+                            .unwrap_or(&id)
+                            .to_string();
+                        graph.add_node(&id, "operation", &name);
+
+                        // path level parameters are shared by all operations
+                        if let Some(parameters) = item.get("parameters") {
+                            sources.push((id.clone(), parameters));
+                        }
+                        sources.push((id, operation));
+                    }
+                }
+            }
+        }
+
+        for (from, node) in sources {
+            let mut references = vec![];
+            collect_references(node, &mut references);
+
+            for reference in references {
+                if let Some(to) = graph.target(&reference) {
+                    graph.add_edge(from.clone(), to);
+                }
+            }
+        }
+
+        for i in 0..graph.nodes.len() {
+            let id = graph.nodes[i].id.clone();
+            graph.nodes[i].uses = graph.edges.iter().filter(|e| e.from == id).count();
+            graph.nodes[i].used_by = graph.edges.iter().filter(|e| e.to == id).count();
+        }
+
+        graph
+    }
+
+    fn add_node(&mut self, id: &str, kind: &str, name: &str) {
+        self.nodes.push(Node {
+            id: id.to_string(),
+            kind: kind.to_string(),
+            name: name.to_string(),
+            uses: 0,
+            used_by: 0,
+        });
+    }
+
+    fn add_edge(&mut self, from: String, to: String) {
+        let edge = Edge { from, to };
+
+        if !self.edges.contains(&edge) {
+            self.edges.push(edge);
+        }
+    }
+
+    // node containing referenced location, ex. #/components/schemas/User/properties/id
+    fn target(&self, reference: &str) -> Option<String> {
+        self.nodes
+            .iter()
+            .filter(|n| n.kind != "operation")
+            .find(|n| {
+                reference == n.id
+                    || reference
+                        .strip_prefix(&n.id)
+                        .map(|rest| rest.starts_with('/'))
+                        .unwrap_or(false)
+            })
+            .map(|n| n.id.clone())
+    }
+
+    // nodes using given node directly
+    pub fn dependents(&self, id: &str) -> Vec<&str> {
+        self.edges
+            .iter()
+            .filter(|e| e.to == id)
+            .map(|e| e.from.as_str())
+            .collect()
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut lines = vec!["digraph schema {".to_string()];
+
+        for node in &self.nodes {
+            lines.push(format!(
+                "  {:?} [label={:?}, shape={}];",
+                node.id,
+                node.name,
+                if node.kind == "operation" {
+                    "box"
+                } else {
+                    "ellipse"
+                }
+            ));
+        }
+
+        for edge in &self.edges {
+            lines.push(format!("  {:?} -> {:?};", edge.from, edge.to));
+        }
+
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+}
+
+fn collect_references(node: &Value, references: &mut Vec<String>) {
+    match node {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get("$ref") {
+                references.push(reference.clone());
+            }
+
+            for value in map.values() {
+                collect_references(value, references);
+            }
+        }
+        Value::Array(list) => {
+            for value in list {
+                collect_references(value, references);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_reference_graph() {
+        let root = json!({
+            "paths": {
+                "/users/{id}": {
+                    "parameters": [{ "$ref": "#/components/parameters/Id" }],
+                    "get": {
+                        "operationId": "getUser",
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/User" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "parameters": { "Id": { "name": "id", "in": "path", "schema": { "type": "string" } } },
+                "schemas": {
+                    "User": {
+                        "properties": {
+                            "group": { "$ref": "#/components/schemas/Group/properties/id" },
+                            "parent": { "$ref": "#/components/schemas/User" }
+                        }
+                    },
+                    "Group": { "properties": { "id": { "type": "string" } } }
+                }
+            }
+        });
+
+        let graph = Graph::new(&root);
+
+        assert_eq!(graph.nodes.len(), 4);
+        assert_eq!(graph.edges.len(), 4);
+        assert_eq!(
+            graph.dependents("#/components/schemas/User"),
+            vec!["#/components/schemas/User", "GET /users/{id}"]
+        );
+
+        let group = graph.nodes.iter().find(|n| n.name == "Group").unwrap();
+        assert_eq!(group.used_by, 1);
+        assert_eq!(group.uses, 0);
+
+        assert_eq!(
+            true,
+            graph
+                .to_dot()
+                .contains("\"GET /users/{id}\" -> \"#/components/parameters/Id\";")
+        );
+    }
+}
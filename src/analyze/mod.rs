@@ -0,0 +1,6 @@
+pub mod compat;
+pub mod graph;
+pub mod payload;
+pub mod replay;
+pub mod sensitive;
+pub mod usage;
@@ -0,0 +1,422 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::tools::HTTP_METHODS;
+
+pub static BUDGET_MODES: &[&str] = &["typical", "worst"];
+
+// assumed sizes when schema does not constrain value
+static TYPICAL_STRING: u64 = 16;
+static TYPICAL_ITEMS: u64 = 3;
+
+// serialized size in bytes, worst case is None when unbounded
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+pub struct Size {
+    pub typical: u64,
+    pub worst: Option<u64>,
+}
+
+impl Size {
+    fn fixed(size: u64) -> Self {
+        Self {
+            typical: size,
+            worst: Some(size),
+        }
+    }
+
+    fn add(self, other: Size) -> Self {
+        Self {
+            typical: self.typical.saturating_add(other.typical),
+            worst: self
+                .worst
+                .and_then(|a| other.worst.map(|b| a.saturating_add(b))),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PayloadEstimate {
+    pub operation: String,
+
+    // request or status code of response
+    pub payload: String,
+    pub content_type: String,
+    pub size: Size,
+    pub exceeded: bool,
+}
+
+pub struct PayloadAnalyzer;
+
+pub struct PayloadAnalyzerOptions {
+    pub budget: Option<u64>,
+    pub budget_on_worst: bool,
+}
+
+impl PayloadAnalyzer {
+    pub fn options() -> PayloadAnalyzerOptions {
+        PayloadAnalyzerOptions {
+            budget: None,
+            budget_on_worst: false,
+        }
+    }
+}
+
+impl PayloadAnalyzerOptions {
+    pub fn with_budget(&mut self, value: Option<u64>) -> &mut Self {
+        self.budget = value;
+        self
+    }
+
+    pub fn with_budget_on_worst(&mut self, value: bool) -> &mut Self {
+        self.budget_on_worst = value;
+        self
+    }
+
+    pub fn process(&self, root: &Value) -> Vec<PayloadEstimate> {
+        let mut result = vec![];
+
+        let paths = match root.get("paths") {
+            Some(Value::Object(paths)) => paths,
+            _ => return result,
+        };
+
+        for (path, item) in paths {
+            for method in HTTP_METHODS {
+                let operation = match item.get(*method) {
+                    Some(operation) => operation,
+                    None => continue,
+                };
+
+                let name = format!("{} {}", method.to_uppercase(), path);
+
+                let mut payloads = vec![];
+                if let Some(body) = operation.get("requestBody") {
+                    payloads.push(("request".to_string(), resolve(root, body)));
+                }
+                if let Some(Value::Object(responses)) = operation.get("responses") {
+                    for (status, response) in responses {
+                        payloads.push((status.clone(), resolve(root, response)));
+                    }
+                }
+
+                for (payload, node) in payloads {
+                    if let Some(Value::Object(content)) = node.get("content") {
+                        for (content_type, media) in content {
+                            if let Some(schema) = media.get("schema") {
+                                let size = estimate(root, schema, &mut vec![]);
+
+                                result.push(PayloadEstimate {
+                                    operation: name.clone(),
+                                    payload: payload.clone(),
+                                    content_type: content_type.clone(),
+                                    exceeded: self.exceeds(&size),
+                                    size,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    // unbounded worst case exceeds any budget
+    fn exceeds(&self, size: &Size) -> bool {
+        match self.budget {
+            Some(budget) if self.budget_on_worst => size.worst.map(|w| w > budget).unwrap_or(true),
+            Some(budget) => size.typical > budget,
+            None => false,
+        }
+    }
+}
+
+fn resolve<'a>(root: &'a Value, node: &'a Value) -> &'a Value {
+    node.get("$ref")
+        .and_then(|r| r.as_str())
+        .and_then(|r| r.strip_prefix('#'))
+        .and_then(|pointer| root.pointer(pointer))
+        .unwrap_or(node)
+}
+
+fn number(node: &Value, key: &str) -> Option<u64> {
+    node.get(key).and_then(|v| v.as_u64())
+}
+
+// estimates json serialized size of value described by schema, stack guards recursive references
+pub fn estimate(root: &Value, node: &Value, stack: &mut Vec<String>) -> Size {
+    if let Some(Value::String(reference)) = node.get("$ref") {
+        if stack.contains(reference) {
+            return Size {
+                typical: 2,
+                worst: None,
+            };
+        }
+
+        let target = match reference.strip_prefix('#').and_then(|p| root.pointer(p)) {
+            Some(target) => target,
+            None => return Size::fixed(0),
+        };
+
+        stack.push(reference.clone());
+        let size = estimate(root, target, stack);
+        stack.pop();
+
+        return size;
+    }
+
+    if let Some(Value::Array(values)) = node.get("enum") {
+        let sizes = values
+            .iter()
+            .map(|v| v.to_string().len() as u64)
+            .collect::<Vec<_>>();
+
+        return Size {
+            typical: sizes.iter().fold(0u64, |acc, s| acc.saturating_add(*s))
+                / (sizes.len().max(1) as u64),
+            worst: sizes.iter().max().cloned(),
+        };
+    }
+
+    if let Some(value) = node.get("const") {
+        return Size::fixed(value.to_string().len() as u64);
+    }
+
+    for junctor in &["oneOf", "anyOf"] {
+        if let Some(Value::Array(list)) = node.get(*junctor) {
+            let sizes = list
+                .iter()
+                .map(|s| estimate(root, s, stack))
+                .collect::<Vec<_>>();
+
+            return Size {
+                typical: sizes.iter().map(|s| s.typical).max().unwrap_or(0),
+                worst: sizes
+                    .iter()
+                    .map(|s| s.worst)
+                    .collect::<Option<Vec<_>>>()
+                    .and_then(|w| w.into_iter().max()),
+            };
+        }
+    }
+
+    if let Some(Value::Array(list)) = node.get("allOf") {
+        // parts are merged into one object, braces are counted once
+        return list
+            .iter()
+            .map(|s| estimate(root, s, stack))
+            .fold(Size::fixed(2), |acc, s| {
+                acc.add(Size {
+                    typical: s.typical.saturating_sub(2),
+                    worst: s.worst.map(|w| w.saturating_sub(2)),
+                })
+            });
+    }
+
+    let type_ = match node.get("type") {
+        Some(Value::String(t)) => t.as_str(),
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(|t| t.as_str())
+            .find(|t| *t != "null")
+            .unwrap_or("null"),
+        _ if node.get("properties").is_some() => "object",
+        _ if node.get("items").is_some() => "array",
+        _ => "any",
+    };
+
+    match type_ {
+        "string" => {
+            let typical = match node.get("format").and_then(|f| f.as_str()) {
+                Some("date-time") => 24,
+                Some("uuid") => 36,
+                Some("date") => 10,
+                _ => TYPICAL_STRING
+                    .max(number(node, "minLength").unwrap_or(0))
+                    .min(number(node, "maxLength").unwrap_or(u64::MAX)),
+            };
+
+            Size {
+                typical: typical.saturating_add(2),
+                worst: number(node, "maxLength").map(|m| m.saturating_add(2)),
+            }
+        }
+        "integer" => Size {
+            typical: 4,
+            worst: Some(20),
+        },
+        "number" => Size {
+            typical: 8,
+            worst: Some(24),
+        },
+        "boolean" => Size {
+            typical: 5,
+            worst: Some(5),
+        },
+        "null" => Size::fixed(4),
+        "array" => {
+            let item = node
+                .get("items")
+                .map(|i| estimate(root, i, stack))
+                .unwrap_or_else(|| Size::fixed(4));
+            let count = TYPICAL_ITEMS
+                .max(number(node, "minItems").unwrap_or(0))
+                .min(number(node, "maxItems").unwrap_or(u64::MAX));
+
+            // "[item,item]", schema limits may be close to u64::MAX
+            let total = |item: u64, count: u64| {
+                item.saturating_add(1)
+                    .saturating_mul(count)
+                    .saturating_add(2)
+            };
+
+            Size {
+                typical: total(item.typical, count),
+                worst: number(node, "maxItems").and_then(|max| item.worst.map(|w| total(w, max))),
+            }
+        }
+        "object" => {
+            let mut size = Size::fixed(2);
+
+            if let Some(Value::Object(properties)) = node.get("properties") {
+                for (name, property) in properties {
+                    // "name": value,
+                    size = size
+                        .add(Size::fixed((name.len() as u64).saturating_add(4)))
+                        .add(estimate(root, property, stack));
+                }
+            }
+
+            let additional = match node.get("additionalProperties") {
+                Some(Value::Bool(false)) => None,
+                Some(Value::Object(schema)) if !schema.is_empty() => {
+                    Some(estimate(root, &node["additionalProperties"], stack))
+                }
+                _ if node.get("properties").is_some() => None,
+                _ => Some(Size {
+                    typical: 4,
+                    worst: None,
+                }),
+            };
+
+            // keys of additional properties are not constrained, one entry is typical
+            if let Some(value) = additional {
+                size = size.add(Size {
+                    typical: (TYPICAL_STRING + 4).saturating_add(value.typical),
+                    worst: None,
+                });
+            }
+
+            size
+        }
+        _ => Size {
+            typical: 4,
+            worst: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_bounded_estimate() {
+        let root = json!({});
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "maxLength": 10 },
+                "tags": { "type": "array", "maxItems": 2, "items": { "type": "boolean" } }
+            }
+        });
+
+        // {"id":"..........","tags":[false,false]}
+        assert_eq!(
+            estimate(&root, &schema, &mut vec![]),
+            Size {
+                typical: 2 + 6 + 12 + 8 + (2 + 6 * 2),
+                worst: Some(2 + 6 + 12 + 8 + (2 + 6 * 2)),
+            }
+        );
+    }
+
+    #[test]
+    fn test_huge_limits_saturate() {
+        let root = json!({});
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "ids": {
+                    "type": "array",
+                    "minItems": u64::MAX,
+                    "maxItems": u64::MAX,
+                    "items": { "type": "string", "maxLength": u64::MAX }
+                },
+                "flag": { "type": "boolean" }
+            }
+        });
+
+        assert_eq!(
+            estimate(&root, &schema, &mut vec![]),
+            Size {
+                typical: u64::MAX,
+                worst: Some(u64::MAX),
+            }
+        );
+    }
+
+    #[test]
+    fn test_operations_over_budget() {
+        let root = json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "type": "array",
+                                            "items": { "$ref": "#/components/schemas/User" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "properties": {
+                            "name": { "type": "string" },
+                            "friends": {
+                                "type": "array",
+                                "items": { "$ref": "#/components/schemas/User" }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        let result = PayloadAnalyzer::options()
+            .with_budget(Some(1000))
+            .with_budget_on_worst(true)
+            .process(&root);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].size.worst, None);
+        assert_eq!(result[0].exceeded, true);
+
+        let result = PayloadAnalyzer::options()
+            .with_budget(Some(1000))
+            .process(&root);
+
+        assert_eq!(result[0].exceeded, false);
+    }
+}
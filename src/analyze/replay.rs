@@ -0,0 +1,519 @@
+use jsonschema::{Draft, JSONSchema};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::error::Error;
+use crate::tools::{pointer_escape, SchemaWalk};
+
+// messages and sources kept per field, enough to see what broke without flooding output
+static SAMPLES: usize = 5;
+
+// recorded payload with its origin, ex. `orders.ndjson:12`
+pub struct Payload {
+    pub source: String,
+    pub value: Result<Value, String>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct FieldFailures {
+    // $.items[].price
+    pub field: String,
+    pub failures: usize,
+    pub messages: Vec<String>,
+    pub payloads: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ReplayReport {
+    pub payloads: usize,
+    pub failed: usize,
+    pub fields: Vec<FieldFailures>,
+}
+
+// .json and .yaml files hold one payload, .ndjson and .jsonl files one payload per line
+pub fn corpus(path: &Path) -> Result<Vec<Payload>, Error> {
+    let mut files = walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .collect::<Vec<_>>();
+    files.sort();
+
+    let mut result = vec![];
+    for file in files {
+        let extension = file
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if !["json", "ndjson", "jsonl", "yaml", "yml"].contains(&extension.as_str()) {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(&file)
+            .map_err(|e| Error::AnalyzeReplayCorpus(format!("{}: {}", file.display(), e)))?;
+        let source = file.display().to_string();
+
+        match extension.as_str() {
+            "ndjson" | "jsonl" => {
+                for (i, line) in content.lines().enumerate() {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+
+                    result.push(Payload {
+                        source: format!("{}:{}", source, i + 1),
+                        value: serde_json::from_str(line).map_err(|e| e.to_string()),
+                    });
+                }
+            }
+            "json" => result.push(Payload {
+                source,
+                value: serde_json::from_str(&content).map_err(|e| e.to_string()),
+            }),
+            _ => result.push(Payload {
+                source,
+                value: serde_yaml::from_str(&content).map_err(|e| e.to_string()),
+            }),
+        }
+    }
+
+    Ok(result)
+}
+
+// validators of subschema and of its own constraints, compiled once for whole corpus
+struct Validators {
+    full: JSONSchema,
+    shallow: JSONSchema,
+}
+
+pub struct Replayer {
+    schema: Value,
+
+    // keyed by pointer of subschema in inlined schema
+    validators: HashMap<String, Validators>,
+}
+
+impl Replayer {
+    // schema under pointer of document, local references are inlined
+    pub fn new(root: &Value, pointer: &str) -> Result<Self, Error> {
+        let schema = root
+            .pointer(pointer)
+            .ok_or_else(|| Error::AnalyzeReplayInvalidSchema(format!("{} not found", pointer)))?;
+
+        // openapi 3.0 schemas are closest to draft 4
+        let draft = match root.get("openapi") {
+            Some(_) => Draft::Draft4,
+            None => Draft::Draft7,
+        };
+
+        let schema = inline(root, schema, SchemaWalk::default(), &mut vec![])?;
+
+        let mut validators = HashMap::new();
+        compile(&schema, "", draft, &mut validators)?;
+
+        Ok(Self { schema, validators })
+    }
+
+    pub fn replay(&self, payloads: &[Payload]) -> ReplayReport {
+        let mut report = ReplayReport::default();
+        let mut fields: BTreeMap<String, FieldFailures> = BTreeMap::new();
+
+        for payload in payloads {
+            report.payloads += 1;
+
+            let mut failures = vec![];
+            match &payload.value {
+                Ok(value) => self.walk(&self.schema, "", value, "$", &mut failures),
+                Err(e) => failures.push(("$".to_string(), format!("cannot parse: {}", e))),
+            }
+
+            if failures.is_empty() {
+                continue;
+            }
+            report.failed += 1;
+
+            for (field, message) in failures {
+                let entry = fields
+                    .entry(field.clone())
+                    .or_insert_with(|| FieldFailures {
+                        field,
+                        failures: 0,
+                        messages: vec![],
+                        payloads: vec![],
+                    });
+
+                entry.failures += 1;
+                if !entry.messages.contains(&message) && entry.messages.len() < SAMPLES {
+                    entry.messages.push(message);
+                }
+                if !entry.payloads.contains(&payload.source) && entry.payloads.len() < SAMPLES {
+                    entry.payloads.push(payload.source.clone());
+                }
+            }
+        }
+
+        report.fields = fields.into_values().collect();
+        report
+            .fields
+            .sort_by(|a, b| b.failures.cmp(&a.failures).then(a.field.cmp(&b.field)));
+
+        report
+    }
+
+    // failures are attributed to deepest field breaking its own constraints
+    fn walk(
+        &self,
+        schema: &Value,
+        pointer: &str,
+        value: &Value,
+        field: &str,
+        failures: &mut Vec<(String, String)>,
+    ) {
+        // every subschema walked into was compiled upfront
+        let validators = &self.validators[pointer];
+        if validators.full.is_valid(value) {
+            return;
+        }
+
+        if let Value::Object(map) = value {
+            for name in required(schema) {
+                if !map.contains_key(&name) {
+                    failures.push((
+                        format!("{}.{}", field, name),
+                        "required property is missing".to_string(),
+                    ));
+                }
+            }
+        }
+
+        if let Err(errors) = validators.shallow.validate(value) {
+            for error in errors {
+                failures.push((field.to_string(), error.to_string()));
+            }
+        }
+
+        match value {
+            Value::Object(map) => {
+                for (name, item) in map {
+                    if let Some((child, property)) = property(schema, pointer, name) {
+                        self.walk(
+                            property,
+                            &child,
+                            item,
+                            &format!("{}.{}", field, name),
+                            failures,
+                        );
+                    }
+                }
+            }
+            Value::Array(list) => {
+                if let Some(items) = schema.get("items").filter(|i| i.is_object()) {
+                    let child = format!("{}/items", pointer);
+                    for item in list {
+                        self.walk(items, &child, item, &format!("{}[]", field), failures);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// every subschema replay may walk into, properties are found like in walk
+fn compile(
+    schema: &Value,
+    pointer: &str,
+    draft: Draft,
+    validators: &mut HashMap<String, Validators>,
+) -> Result<(), Error> {
+    let build = |schema: &Value| {
+        JSONSchema::options()
+            .with_draft(draft)
+            .compile(schema)
+            .map_err(|e| Error::AnalyzeReplayInvalidSchema(format!("{}: {}", pointer, e)))
+    };
+
+    validators.insert(
+        pointer.to_string(),
+        Validators {
+            full: build(schema)?,
+            shallow: build(&shallow(schema))?,
+        },
+    );
+
+    let names = all_of(schema, pointer)
+        .into_iter()
+        .filter_map(|(_, s)| s.get("properties").and_then(|p| p.as_object()))
+        .flat_map(|p| p.keys())
+        .collect::<Vec<_>>();
+
+    for name in names {
+        if let Some((child, property)) = property(schema, pointer, name) {
+            if !validators.contains_key(&child) {
+                compile(property, &child, draft, validators)?;
+            }
+        }
+    }
+
+    if let Some(items) = schema.get("items").filter(|i| i.is_object()) {
+        compile(items, &format!("{}/items", pointer), draft, validators)?;
+    }
+
+    Ok(())
+}
+
+// schema with members of its allOf, each with own pointer
+fn all_of<'a>(schema: &'a Value, pointer: &str) -> Vec<(String, &'a Value)> {
+    let mut result = vec![(pointer.to_string(), schema)];
+
+    if let Some(Value::Array(list)) = schema.get("allOf") {
+        for (i, member) in list.iter().enumerate() {
+            result.extend(all_of(member, &format!("{}/allOf/{}", pointer, i)));
+        }
+    }
+
+    result
+}
+
+fn required(schema: &Value) -> Vec<String> {
+    all_of(schema, "")
+        .into_iter()
+        .filter_map(|(_, s)| s.get("required").and_then(|r| r.as_array()))
+        .flatten()
+        .filter_map(|r| r.as_str().map(|r| r.to_string()))
+        .collect()
+}
+
+fn property<'a>(schema: &'a Value, pointer: &str, name: &str) -> Option<(String, &'a Value)> {
+    all_of(schema, pointer).into_iter().find_map(|(p, s)| {
+        s.get("properties")
+            .and_then(|ps| ps.get(name))
+            .map(|property| {
+                (
+                    format!("{}/properties/{}", p, pointer_escape(name)),
+                    property,
+                )
+            })
+    })
+}
+
+// constraints of node itself, properties and items are checked by walking into them
+fn shallow(schema: &Value) -> Value {
+    let mut schema = match schema {
+        Value::Object(map) => map.clone(),
+        other => return other.clone(),
+    };
+
+    schema.remove("required");
+
+    if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+        for property in properties.values_mut() {
+            *property = json!({});
+        }
+    }
+
+    if let Some(items) = schema.get_mut("items").filter(|i| i.is_object()) {
+        *items = json!({});
+    }
+
+    if let Some(Value::Array(list)) = schema.get_mut("allOf") {
+        for member in list.iter_mut() {
+            *member = shallow(member);
+        }
+    }
+
+    Value::Object(schema)
+}
+
+// schema without references, recursive references accept any value, nullable of openapi 3.0 becomes null type,
+// references which cannot be inlined fail, payloads would pass them unchecked
+fn inline(
+    root: &Value,
+    node: &Value,
+    walk: SchemaWalk,
+    stack: &mut Vec<String>,
+) -> Result<Value, Error> {
+    match node {
+        Value::Object(map) => {
+            if let (false, Some(Value::String(reference))) = (walk.has_names(), map.get("$ref")) {
+                if stack.contains(reference) {
+                    return Ok(json!({}));
+                }
+
+                let target = reference
+                    .strip_prefix('#')
+                    .and_then(|p| root.pointer(p))
+                    .ok_or_else(|| {
+                        Error::AnalyzeReplayInvalidSchema(format!(
+                            "{}: only local references are inlined, this one is {}",
+                            reference,
+                            if reference.starts_with('#') {
+                                "unresolved"
+                            } else {
+                                "external"
+                            }
+                        ))
+                    })?;
+
+                stack.push(reference.clone());
+                let value = inline(root, target, SchemaWalk::default(), stack);
+                stack.pop();
+
+                return value;
+            }
+
+            let mut result = Map::new();
+            for (key, value) in map {
+                let value = if walk.is_instance(key) {
+                    value.clone()
+                } else {
+                    inline(root, value, walk.enter(key), stack)?
+                };
+
+                result.insert(key.clone(), value);
+            }
+
+            if !walk.has_names() && result.remove("nullable") == Some(Value::Bool(true)) {
+                if let Some(Value::String(kind)) = result.get("type") {
+                    let kind = kind.clone();
+                    result.insert("type".to_string(), json!([kind, "null"]));
+                }
+            }
+
+            Ok(Value::Object(result))
+        }
+        Value::Array(list) => list
+            .iter()
+            .map(|v| inline(root, v, SchemaWalk::default(), stack))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        value => Ok(value.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay() {
+        let root = json!({
+            "openapi": "3.0.0",
+            "components": {
+                "schemas": {
+                    "Order": {
+                        "type": "object",
+                        "required": ["id", "currency"],
+                        "additionalProperties": false,
+                        "properties": {
+                            "id": { "type": "integer" },
+                            "currency": { "type": "string", "enum": ["EUR", "USD"] },
+                            "note": { "type": "string", "nullable": true },
+                            "items": { "type": "array", "items": { "$ref": "#/components/schemas/Item" } }
+                        }
+                    },
+                    "Item": {
+                        "type": "object",
+                        "properties": { "price": { "type": "number", "minimum": 0 } }
+                    }
+                }
+            }
+        });
+
+        let payload = |source: &str, value: Value| Payload {
+            source: source.to_string(),
+            value: Ok(value),
+        };
+
+        let payloads = vec![
+            payload(
+                "a.json",
+                json!({ "id": 1, "currency": "EUR", "note": null, "items": [{ "price": 1 }] }),
+            ),
+            payload(
+                "b.ndjson:1",
+                json!({ "id": 2, "currency": "PLN", "items": [{ "price": -1 }, { "price": -2 }] }),
+            ),
+            payload("b.ndjson:2", json!({ "id": "3", "legacy": true })),
+            Payload {
+                source: "b.ndjson:3".to_string(),
+                value: Err("EOF".to_string()),
+            },
+        ];
+
+        let report = Replayer::new(&root, "/components/schemas/Order")
+            .unwrap()
+            .replay(&payloads);
+
+        assert_eq!(report.payloads, 4);
+        assert_eq!(report.failed, 3);
+        assert_eq!(
+            report
+                .fields
+                .iter()
+                .map(|f| (f.field.as_str(), f.failures, f.payloads.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "$",
+                    2,
+                    vec!["b.ndjson:2".to_string(), "b.ndjson:3".to_string()]
+                ),
+                (
+                    "$.currency",
+                    2,
+                    vec!["b.ndjson:1".to_string(), "b.ndjson:2".to_string()]
+                ),
+                ("$.items[].price", 2, vec!["b.ndjson:1".to_string()]),
+                ("$.id", 1, vec!["b.ndjson:2".to_string()]),
+            ]
+        );
+
+        assert!(matches!(
+            Replayer::new(&root, "/components/schemas/Missing"),
+            Err(Error::AnalyzeReplayInvalidSchema(_))
+        ));
+    }
+
+    #[test]
+    fn test_replay_rejects_uninlined_references() {
+        for reference in &["#/components/schemas/Missing", "common.yaml#/Money"] {
+            let root = json!({
+                "openapi": "3.0.0",
+                "components": {
+                    "schemas": {
+                        "Order": {
+                            "type": "object",
+                            "properties": { "total": { "$ref": reference } }
+                        }
+                    }
+                }
+            });
+
+            assert!(matches!(
+                Replayer::new(&root, "/components/schemas/Order"),
+                Err(Error::AnalyzeReplayInvalidSchema(_))
+            ));
+        }
+
+        // properties named like keywords are schemas, examples are data
+        let root = json!({
+            "type": "object",
+            "properties": {
+                "default": { "$ref": "#/definitions/Flag" },
+                "example": { "type": "string" }
+            },
+            "example": { "$ref": "not a reference" },
+            "definitions": { "Flag": { "type": "boolean" } }
+        });
+
+        let report = Replayer::new(&root, "").unwrap().replay(&[Payload {
+            source: "a.json".to_string(),
+            value: Ok(json!({ "default": "yes" })),
+        }]);
+        assert_eq!(report.fields[0].field, "$.default");
+    }
+}
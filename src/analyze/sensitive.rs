@@ -0,0 +1,191 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::process::annotate::annotations;
+use crate::tools::HTTP_METHODS;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SensitiveField {
+    pub operation: String,
+    pub response: String,
+    pub content_type: String,
+
+    // $.data[].email
+    pub field: String,
+    pub annotations: Map<String, Value>,
+}
+
+// every annotated field of every response of every operation
+pub fn report(root: &Value, extensions: &[String]) -> Vec<SensitiveField> {
+    let mut result = vec![];
+
+    let paths = match root.get("paths") {
+        Some(Value::Object(paths)) => paths,
+        _ => return result,
+    };
+
+    for (path, item) in paths {
+        for method in HTTP_METHODS {
+            let responses = match item.pointer(&format!("/{}/responses", method)) {
+                Some(Value::Object(responses)) => responses,
+                _ => continue,
+            };
+
+            for (status, response) in responses {
+                let response = response
+                    .get("$ref")
+                    .and_then(|r| r.as_str())
+                    .and_then(|r| r.strip_prefix('#'))
+                    .and_then(|p| root.pointer(p))
+                    .unwrap_or(response);
+
+                if let Some(Value::Object(content)) = response.get("content") {
+                    for (content_type, media) in content {
+                        let mut fields = vec![];
+                        if let Some(schema) = media.get("schema") {
+                            walk(root, schema, "$", extensions, &mut vec![], &mut fields);
+                        }
+
+                        for (field, annotations) in fields {
+                            result.push(SensitiveField {
+                                operation: format!("{} {}", method.to_uppercase(), path),
+                                response: status.clone(),
+                                content_type: content_type.clone(),
+                                field,
+                                annotations,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+fn walk(
+    root: &Value,
+    node: &Value,
+    field: &str,
+    extensions: &[String],
+    stack: &mut Vec<String>,
+    result: &mut Vec<(String, Map<String, Value>)>,
+) {
+    let found = annotations(root, node, extensions, &mut stack.clone());
+    if !found.is_empty() && !result.iter().any(|(f, _)| f == field) {
+        result.push((field.to_string(), found));
+    }
+
+    if let Some(Value::String(reference)) = node.get("$ref") {
+        if stack.contains(reference) {
+            return;
+        }
+
+        if let Some(target) = reference.strip_prefix('#').and_then(|p| root.pointer(p)) {
+            stack.push(reference.clone());
+            walk(root, target, field, extensions, stack, result);
+            stack.pop();
+        }
+    }
+
+    for junctor in &["allOf", "oneOf", "anyOf"] {
+        if let Some(Value::Array(parts)) = node.get(*junctor) {
+            for part in parts {
+                walk(root, part, field, extensions, stack, result);
+            }
+        }
+    }
+
+    if let Some(Value::Object(properties)) = node.get("properties") {
+        for (name, property) in properties {
+            walk(
+                root,
+                property,
+                &format!("{}.{}", field, name),
+                extensions,
+                stack,
+                result,
+            );
+        }
+    }
+
+    if let Some(items) = node.get("items") {
+        walk(
+            root,
+            items,
+            &format!("{}[]", field),
+            extensions,
+            stack,
+            result,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_report_sensitive_responses() {
+        let root = json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "type": "array",
+                                            "items": { "$ref": "#/components/schemas/User" }
+                                        }
+                                    }
+                                }
+                            },
+                            "404": { "description": "Not found" }
+                        }
+                    }
+                },
+                "/health": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": { "schema": { "type": "string" } }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Email": { "type": "string", "x-pii": "contact" },
+                    "User": {
+                        "type": "object",
+                        "properties": {
+                            "email": { "$ref": "#/components/schemas/Email" },
+                            "manager": { "$ref": "#/components/schemas/User" },
+                            "token": { "type": "string", "x-secret": true }
+                        }
+                    }
+                }
+            }
+        });
+
+        // recursive manager reference is reported once
+        let result = report(&root, &["x-pii".to_string(), "x-secret".to_string()]);
+
+        assert_eq!(
+            result.iter().map(|f| f.field.as_str()).collect::<Vec<_>>(),
+            vec!["$[].email", "$[].token"]
+        );
+        assert_eq!(result[0].operation, "GET /users");
+        assert_eq!(
+            result[0].annotations,
+            *json!({ "x-pii": "contact" }).as_object().unwrap()
+        );
+    }
+}
@@ -0,0 +1,299 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::tools::{pointer_escape, SchemaWalk, HTTP_METHODS};
+
+pub static USAGE_EXTENSION: &str = "x-usage-count";
+
+// number of calls by json pointer of operation or property, keyed as given or by `METHOD /path` of operation
+#[derive(Debug, Clone, Default)]
+pub struct Usage {
+    pub counts: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct UsageEntry {
+    pub pointer: String,
+
+    // operation or property
+    pub kind: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct UsageReport {
+    pub entries: Vec<UsageEntry>,
+
+    // pointers of usage report missing in specification, report is older than specification
+    pub unknown: Vec<String>,
+}
+
+impl Usage {
+    pub fn from_schema(schema: &Schema) -> Result<Self, Error> {
+        let map = schema.get_body().as_object().ok_or_else(|| {
+            Error::UsageInvalidReport("map of pointer to count expected".to_string())
+        })?;
+
+        let mut counts = BTreeMap::new();
+        for (key, value) in map {
+            let count = value
+                .as_u64()
+                .ok_or_else(|| Error::UsageInvalidReport(format!("{}: {}", key, value)))?;
+
+            *counts.entry(pointer(key)?).or_insert(0) += count;
+        }
+
+        Ok(Self { counts })
+    }
+
+    // explicit count, otherwise sum of counts of fields below, so parent of used field is used too
+    fn count(&self, pointer: &str) -> u64 {
+        if let Some(count) = self.counts.get(pointer) {
+            return *count;
+        }
+
+        let prefix = format!("{}/", pointer);
+        self.counts
+            .iter()
+            .filter(|(p, _)| p.starts_with(&prefix))
+            .map(|(_, c)| c)
+            .sum()
+    }
+}
+
+// `GET /users/{id}` is shorthand of `/paths/~1users~1{id}/get`
+fn pointer(key: &str) -> Result<String, Error> {
+    if key.starts_with('/') {
+        return Ok(key.to_string());
+    }
+
+    match key.split_once(' ') {
+        Some((method, path))
+            if HTTP_METHODS.contains(&method.to_lowercase().as_str()) && path.starts_with('/') =>
+        {
+            Ok(format!(
+                "/paths/{}/{}",
+                pointer_escape(path),
+                method.to_lowercase()
+            ))
+        }
+        _ => Err(Error::UsageInvalidReport(key.to_string())),
+    }
+}
+
+// every operation and every property of specification, in order of document, properties are keyed
+// by pointer of schema declaring them, `$ref`s are not followed
+pub fn elements(root: &Value) -> Vec<(String, String)> {
+    let mut result = vec![];
+
+    if let Some(Value::Object(paths)) = root.get("paths") {
+        for (path, item) in paths {
+            for method in HTTP_METHODS {
+                if item.get(*method).is_some() {
+                    result.push((
+                        format!("/paths/{}/{}", pointer_escape(path), method),
+                        "operation".to_string(),
+                    ));
+                }
+            }
+        }
+    }
+
+    properties(root, "", SchemaWalk::default(), &mut result);
+
+    result
+}
+
+fn properties(node: &Value, pointer: &str, walk: SchemaWalk, result: &mut Vec<(String, String)>) {
+    match node {
+        Value::Object(map) => {
+            for (key, value) in map {
+                if walk.is_instance(key) || (!walk.has_names() && key.starts_with("x-")) {
+                    continue;
+                }
+
+                let child = format!("{}/{}", pointer, pointer_escape(key));
+                if key == "properties" && !walk.has_names() {
+                    if let Value::Object(items) = value {
+                        for (name, property) in items {
+                            let child = format!("{}/{}", child, pointer_escape(name));
+                            result.push((child.clone(), "property".to_string()));
+                            properties(property, &child, SchemaWalk::default(), result);
+                        }
+                        continue;
+                    }
+                }
+
+                properties(value, &child, walk.enter(key), result);
+            }
+        }
+        Value::Array(list) => {
+            for (i, value) in list.iter().enumerate() {
+                properties(
+                    value,
+                    &format!("{}/{}", pointer, i),
+                    SchemaWalk::default(),
+                    result,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn report(root: &Value, usage: &Usage) -> UsageReport {
+    let elements = elements(root);
+
+    UsageReport {
+        unknown: usage
+            .counts
+            .keys()
+            .filter(|p| root.pointer(p).is_none())
+            .cloned()
+            .collect(),
+        entries: elements
+            .into_iter()
+            .map(|(pointer, kind)| UsageEntry {
+                count: usage.count(&pointer),
+                pointer,
+                kind,
+            })
+            .collect(),
+    }
+}
+
+// sets x-usage-count of every operation and property, returns number of annotated elements
+pub fn annotate(root: &mut Value, usage: &Usage) -> usize {
+    let report = report(root, usage);
+
+    for pointer in &report.unknown {
+        log::warn!("{}: not found in specification", pointer);
+    }
+
+    let mut annotated = 0;
+    for entry in report.entries {
+        if let Some(Value::Object(node)) = root.pointer_mut(&entry.pointer) {
+            node.insert(USAGE_EXTENSION.to_string(), Value::from(entry.count));
+            annotated += 1;
+        }
+    }
+
+    annotated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_usage_report() {
+        let mut root = json!({
+            "openapi": "3.0.0",
+            "paths": {
+                "/users/{id}": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": { "application/json": { "schema": { "$ref": "#/components/schemas/User" } } }
+                            }
+                        }
+                    },
+                    "delete": { "responses": { "204": { "description": "deleted" } } }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "address": {
+                                "type": "object",
+                                "properties": { "city": { "type": "string" }, "zip": { "type": "string" } }
+                            },
+                            "fax": { "type": "string" }
+                        }
+                    }
+                }
+            }
+        });
+
+        let usage = Usage::from_schema(&Schema::from_json(json!({
+            "GET /users/{id}": 1200,
+            "/components/schemas/User/properties/name": 900,
+            "/components/schemas/User/properties/address/properties/city": 40,
+            "/components/schemas/User/properties/nickname": 5
+        })))
+        .unwrap();
+
+        let report = report(&root, &usage);
+        assert_eq!(
+            report
+                .entries
+                .iter()
+                .map(|e| (e.pointer.as_str(), e.kind.as_str(), e.count))
+                .collect::<Vec<_>>(),
+            vec![
+                ("/paths/~1users~1{id}/get", "operation", 1200),
+                ("/paths/~1users~1{id}/delete", "operation", 0),
+                ("/components/schemas/User/properties/name", "property", 900),
+                (
+                    "/components/schemas/User/properties/address",
+                    "property",
+                    40
+                ),
+                (
+                    "/components/schemas/User/properties/address/properties/city",
+                    "property",
+                    40
+                ),
+                (
+                    "/components/schemas/User/properties/address/properties/zip",
+                    "property",
+                    0
+                ),
+                ("/components/schemas/User/properties/fax", "property", 0),
+            ]
+        );
+        assert_eq!(
+            report.unknown,
+            vec!["/components/schemas/User/properties/nickname"]
+        );
+
+        assert_eq!(annotate(&mut root, &usage), 7);
+        assert_eq!(
+            root.pointer("/paths/~1users~1{id}/delete/x-usage-count"),
+            Some(&json!(0))
+        );
+
+        assert!(matches!(
+            Usage::from_schema(&Schema::from_json(json!({ "users": 1 }))),
+            Err(Error::UsageInvalidReport(_))
+        ));
+
+        // properties named like keywords are listed, example data is not
+        let root = json!({
+            "components": {
+                "schemas": {
+                    "default": {
+                        "type": "object",
+                        "properties": { "enum": { "type": "string" } },
+                        "example": { "properties": { "fake": {} } }
+                    }
+                }
+            }
+        });
+        assert_eq!(
+            elements(&root),
+            vec![(
+                "/components/schemas/default/properties/enum".to_string(),
+                "property".to_string()
+            )]
+        );
+    }
+}
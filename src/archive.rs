@@ -0,0 +1,271 @@
+use flate2::read::GzDecoder;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use tempfile::TempDir;
+
+use crate::error::Error;
+
+// file inside of archive is given after `!/` (ex. `specs.tar.gz!/orders/openapi.yaml`)
+pub static SEPARATOR: &str = "!/";
+
+static EXTENSIONS: &[&str] = &[".tar", ".tar.gz", ".tgz"];
+
+// root documents looked up when archive path does not name one
+static ROOTS: &[&str] = &[
+    "openapi.yaml",
+    "openapi.yml",
+    "openapi.json",
+    "schema.yaml",
+    "schema.yml",
+    "schema.json",
+];
+
+lazy_static! {
+    // directories of archives unpacked during run, keyed by archive path
+    static ref UNPACKED: Mutex<HashMap<PathBuf, TempDir>> = Mutex::new(HashMap::new());
+}
+
+pub fn is_gzip(content: &[u8]) -> bool {
+    content.starts_with(&[0x1f, 0x8b])
+}
+
+pub fn gunzip(content: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut decompressed = vec![];
+
+    GzDecoder::new(content)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| Error::ArchiveError(format!("gzip: {}", e)))?;
+
+    Ok(decompressed)
+}
+
+// splits `archive!/path/in/archive` when it points to tar archive
+pub fn split(path: &str) -> Option<(&str, Option<&str>)> {
+    let (archive, inner) = match path.split_once(SEPARATOR) {
+        Some((archive, inner)) => (archive, Some(inner)),
+        None => (path, None),
+    };
+
+    if EXTENSIONS.iter().any(|e| archive.ends_with(e)) {
+        Some((archive, inner))
+    } else {
+        None
+    }
+}
+
+// unpacks archive to its own temporary directory, so relative references between files
+// of multi-file specification resolve like in working tree, returns root file,
+// archive is unpacked once per run and its directory is kept until `cleanup`
+pub fn unpack(archive: &Path, inner: Option<&str>) -> Result<PathBuf, Error> {
+    let failed =
+        |e: &dyn std::fmt::Display| Error::ArchiveError(format!("{}: {}", archive.display(), e));
+
+    let inner = inner.map(|i| Path::new(i.trim_start_matches('/')));
+    if let Some(inner) = inner {
+        if inner
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(failed(&format!(
+                "{} points outside of archive",
+                inner.display()
+            )));
+        }
+    }
+
+    let key = archive.canonicalize().map_err(|e| failed(&e))?;
+    let mut unpacked = UNPACKED.lock().unwrap();
+
+    let directory = match unpacked.get(&key) {
+        Some(directory) => directory.path().to_path_buf(),
+        None => {
+            let mut content = std::fs::read(archive).map_err(|e| failed(&e))?;
+            if is_gzip(&content) {
+                content = gunzip(&content)?;
+            }
+
+            // directory is private to current user
+            let directory = tempfile::Builder::new()
+                .prefix("schema-tools-archive-")
+                .tempdir()
+                .map_err(|e| failed(&e))?;
+
+            extract(&content, directory.path()).map_err(|e| failed(&e))?;
+
+            let path = directory.path().to_path_buf();
+            unpacked.insert(key, directory);
+            path
+        }
+    };
+
+    let root = match inner {
+        Some(inner) => directory.join(inner),
+        None => root(&directory).ok_or_else(|| {
+            failed(&format!(
+                "no root document ({}), name it with {}",
+                ROOTS.join(", "),
+                SEPARATOR
+            ))
+        })?,
+    };
+
+    if root.is_file() {
+        Ok(root)
+    } else {
+        Err(failed(&format!(
+            "{} not found",
+            inner.map(|i| i.display().to_string()).unwrap_or_default()
+        )))
+    }
+}
+
+// removes directories of unpacked archives, documents are loaded by then
+pub fn cleanup() {
+    UNPACKED.lock().unwrap().clear();
+}
+
+// root document at top level or in the only top level directory
+fn root(directory: &Path) -> Option<PathBuf> {
+    let find = |directory: &Path| {
+        ROOTS
+            .iter()
+            .map(|r| directory.join(r))
+            .find(|p| p.is_file())
+    };
+
+    find(directory).or_else(|| {
+        let children = std::fs::read_dir(directory)
+            .ok()?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .collect::<Vec<_>>();
+
+        match &children[..] {
+            [only] if only.is_dir() => find(only),
+            _ => None,
+        }
+    })
+}
+
+// regular files and directories only, entries escaping directory are skipped
+fn extract(content: &[u8], directory: &Path) -> std::io::Result<()> {
+    let mut archive = tar::Archive::new(content);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let kind = entry.header().entry_type();
+        if !kind.is_file() && !kind.is_dir() {
+            continue;
+        }
+
+        if !entry.unpack_in(directory)? {
+            log::warn!(
+                "{}: entry outside of archive skipped",
+                entry.path()?.display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    fn tar(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(vec![]);
+
+        for (name, data) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_entry_type(tar::EntryType::Regular);
+
+            // builder refuses to write entries escaping archive, name is set directly
+            if name.starts_with("..") {
+                header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+                header.set_cksum();
+                builder.append(&header, data.as_bytes()).unwrap();
+            } else {
+                builder
+                    .append_data(&mut header, name, data.as_bytes())
+                    .unwrap();
+            }
+        }
+
+        builder.into_inner().unwrap()
+    }
+
+    static LONG_NAME: &str = "api/schemas/very/deeply/nested/directory/of/schemas/shared/between/services/of/whole/company/address.json";
+
+    #[test]
+    fn test_unpack_archive() {
+        let content = tar(&[
+            ("api/openapi.yaml", "openapi: 3.0.0\n"),
+            ("api/schemas/user.json", "{}"),
+            (LONG_NAME, "{}"),
+            ("../escape.yaml", "x: 1\n"),
+        ]);
+
+        let mut encoder = GzEncoder::new(vec![], flate2::Compression::default());
+        encoder.write_all(&content).unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert!(is_gzip(&compressed));
+        assert_eq!(gunzip(&compressed).unwrap(), content);
+
+        let mut archive = std::env::temp_dir();
+        archive.push(format!(
+            "schema-tools-archive-test-{}.tgz",
+            std::process::id()
+        ));
+        std::fs::write(&archive, compressed).unwrap();
+
+        let root = unpack(&archive, None).unwrap();
+        assert!(root.ends_with("api/openapi.yaml"));
+        assert!(root.parent().unwrap().join("schemas/user.json").is_file());
+        assert!(!root
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join("../escape.yaml")
+            .exists());
+
+        // names longer than 100 bytes are kept in gnu long name entries
+        assert!(root
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join(LONG_NAME)
+            .is_file());
+
+        // archive is unpacked once, inner paths cannot leave its directory
+        let user = unpack(&archive, Some("/api/schemas/user.json")).unwrap();
+        assert_eq!(std::fs::read_to_string(&user).unwrap(), "{}");
+        assert!(user.starts_with(root.parent().unwrap().parent().unwrap()));
+        assert!(matches!(
+            unpack(&archive, Some("missing.yaml")),
+            Err(Error::ArchiveError(_))
+        ));
+        assert!(matches!(
+            unpack(&archive, Some("api/../../escape.yaml")),
+            Err(Error::ArchiveError(_))
+        ));
+
+        assert_eq!(
+            split("specs.tgz!/api/openapi.yaml"),
+            Some(("specs.tgz", Some("api/openapi.yaml")))
+        );
+        assert_eq!(split("specs.tar"), Some(("specs.tar", None)));
+        assert_eq!(split("openapi.yaml"), None);
+
+        std::fs::remove_file(&archive).unwrap();
+        cleanup();
+        assert!(!root.exists());
+    }
+}
@@ -0,0 +1,188 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::error::Error;
+
+// files are rendered next to target directory which is swapped with it only when whole codegen
+// succeeds, failed or interrupted run leaves target directory untouched
+pub struct Staging {
+    target: PathBuf,
+    directory: Option<PathBuf>,
+}
+
+impl Staging {
+    pub fn new(atomic: bool, target_dir: &str) -> Result<Self, Error> {
+        let target = PathBuf::from(target_dir);
+        if !atomic {
+            return Ok(Self {
+                target,
+                directory: None,
+            });
+        }
+
+        // sibling directory, so files are moved within the same filesystem
+        let absolute = fs::canonicalize(&target).unwrap_or_else(|_| target.clone());
+        let name = absolute.file_name().ok_or_else(|| {
+            Error::CodegenFileError(format!("cannot stage target directory: {}", target_dir))
+        })?;
+
+        let directory = absolute.with_file_name(format!(
+            ".{}.staging-{}",
+            name.to_string_lossy(),
+            std::process::id()
+        ));
+
+        if directory.exists() {
+            fs::remove_dir_all(&directory).map_err(|e| Error::CodegenFileError(e.to_string()))?;
+        }
+        fs::create_dir_all(&directory).map_err(|e| Error::CodegenFileError(e.to_string()))?;
+
+        log::info!("Staging: {:?}", directory);
+
+        Ok(Self {
+            target,
+            directory: Some(directory),
+        })
+    }
+
+    // directory templates are rendered into
+    pub fn dir(&self) -> String {
+        self.directory
+            .as_ref()
+            .unwrap_or(&self.target)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    pub fn commit(mut self) -> Result<(), Error> {
+        let directory = match self.directory.take() {
+            Some(d) => d,
+            None => return Ok(()),
+        };
+
+        let result = swap(&directory, &self.target);
+
+        // staging is not moved when swap fails
+        if directory.exists() {
+            if let Err(e) = fs::remove_dir_all(&directory) {
+                log::warn!("cannot remove staging directory {:?}: {}", directory, e);
+            }
+        }
+
+        result
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        if let Some(directory) = &self.directory {
+            log::info!("Discarding staged files: {:?}", directory);
+
+            if let Err(e) = fs::remove_dir_all(directory) {
+                log::warn!("cannot remove staging directory {:?}: {}", directory, e);
+            }
+        }
+    }
+}
+
+// whole directories are renamed, so files not produced by codegen do not remain in target
+fn swap(staging: &Path, target: &Path) -> Result<(), Error> {
+    let backup = staging.with_file_name(format!(
+        "{}.backup",
+        staging.file_name().unwrap().to_string_lossy()
+    ));
+
+    let previous = if target.exists() {
+        fs::rename(target, &backup).map_err(|e| Error::CodegenFileError(e.to_string()))?;
+        Some(backup)
+    } else {
+        None
+    };
+
+    if let Err(e) = fs::rename(staging, target) {
+        if let Some(backup) = &previous {
+            fs::rename(backup, target).map_err(|r| {
+                Error::CodegenFileError(format!(
+                    "{}, previous files are kept in {:?}: {}",
+                    e, backup, r
+                ))
+            })?;
+        }
+
+        return Err(Error::CodegenFileError(e.to_string()));
+    }
+
+    if let Some(backup) = previous {
+        log::info!("Replaced: {:?}", target);
+
+        if let Err(e) = fs::remove_dir_all(&backup) {
+            log::warn!("cannot remove previous files {:?}: {}", backup, e);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_staging() {
+        let mut target = std::env::temp_dir();
+        target.push(format!("schema-tools-atomic-test-{}", std::process::id()));
+        fs::create_dir_all(target.join("src")).unwrap();
+        fs::write(target.join("src/lib.rs"), "old").unwrap();
+        fs::write(target.join("src/stale.rs"), "stale").unwrap();
+
+        let target_dir = target.to_string_lossy().to_string();
+
+        // failed run
+        let staging = Staging::new(true, &target_dir).unwrap();
+        let staged = PathBuf::from(staging.dir());
+        fs::create_dir_all(staged.join("src")).unwrap();
+        fs::write(staged.join("src/lib.rs"), "half").unwrap();
+        drop(staging);
+
+        assert_eq!(staged.exists(), false);
+        assert_eq!(
+            fs::read_to_string(target.join("src/lib.rs")).unwrap(),
+            "old"
+        );
+
+        // successful run
+        let staging = Staging::new(true, &target_dir).unwrap();
+        let staged = PathBuf::from(staging.dir());
+        fs::create_dir_all(staged.join("src/models")).unwrap();
+        fs::write(staged.join("src/lib.rs"), "new").unwrap();
+        fs::write(staged.join("src/models/user.rs"), "user").unwrap();
+        staging.commit().unwrap();
+
+        assert_eq!(staged.exists(), false);
+        assert_eq!(
+            fs::read_to_string(target.join("src/lib.rs")).unwrap(),
+            "new"
+        );
+        assert_eq!(
+            fs::read_to_string(target.join("src/models/user.rs")).unwrap(),
+            "user"
+        );
+        assert_eq!(target.join("src/stale.rs").exists(), false);
+
+        // failed swap restores previous files
+        let staging = Staging::new(true, &target_dir).unwrap();
+        fs::remove_dir_all(staging.dir()).unwrap();
+        assert_eq!(staging.commit().is_err(), true);
+        assert_eq!(
+            fs::read_to_string(target.join("src/lib.rs")).unwrap(),
+            "new"
+        );
+
+        // not atomic renders directly
+        assert_eq!(Staging::new(false, &target_dir).unwrap().dir(), target_dir);
+
+        fs::remove_dir_all(target).unwrap();
+    }
+}
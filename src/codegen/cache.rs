@@ -0,0 +1,204 @@
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::error::Error;
+use crate::storage::SchemaStorage;
+
+// extraction results stored between runs, keyed by loaded documents and extraction options
+pub struct Cache {
+    directory: Option<PathBuf>,
+}
+
+impl Cache {
+    pub fn new(enabled: bool, directory: &Option<String>) -> Self {
+        let directory = match directory {
+            Some(d) => Some(PathBuf::from(d)),
+            None if enabled => {
+                let mut d = std::env::temp_dir();
+                d.push("schema-tools");
+                d.push("codegen");
+                Some(d)
+            }
+            None => None,
+        };
+
+        Self { directory }
+    }
+
+    pub fn extract<T, O, F>(
+        &self,
+        storage: &SchemaStorage,
+        kind: &str,
+        options: O,
+        extract: F,
+    ) -> Result<T, Error>
+    where
+        T: Serialize + DeserializeOwned,
+        O: std::fmt::Debug,
+        F: FnOnce(O) -> Result<T, Error>,
+    {
+        let directory = match &self.directory {
+            Some(d) => d,
+            None => return extract(options),
+        };
+
+        let mut path = directory.clone();
+        path.push(format!("{}.json", key(storage, kind, &options)));
+
+        if let Some(cached) = read(&path) {
+            log::info!("using cached extraction: {:?}", path);
+            return Ok(cached);
+        }
+
+        let result = extract(options)?;
+
+        // cache is an optimization only, failed write does not break codegen
+        if let Err(e) = write(&path, &result) {
+            log::warn!("cannot write extraction cache {:?}: {}", path, e);
+        }
+
+        Ok(result)
+    }
+}
+
+// order of keys is significant for extraction, documents are hashed as loaded
+pub fn key<O: std::fmt::Debug>(storage: &SchemaStorage, kind: &str, options: &O) -> String {
+    let mut documents = storage.schemas.iter().collect::<Vec<_>>();
+    documents.sort_by_key(|(url, _)| url.to_string());
+
+    let mut hash = Sha256::new();
+    hash.update(env!("CARGO_PKG_VERSION"));
+    hash.update(kind);
+    hash.update(format!("{:?}", options));
+
+    for (url, schema) in documents {
+        hash.update(url.as_str());
+        hash.update(serde_json::to_string(schema.get_body()).unwrap());
+    }
+
+    format!("{:x}", hash.finalize())
+}
+
+fn read<T: DeserializeOwned>(path: &PathBuf) -> Option<T> {
+    let content = fs::read_to_string(path).ok()?;
+
+    match serde_json::from_str(&content) {
+        Ok(result) => Some(result),
+        Err(e) => {
+            log::warn!("ignoring invalid extraction cache {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+fn write<T: Serialize>(path: &PathBuf, data: &T) -> Result<(), Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| Error::CodegenFileError(e.to_string()))?;
+    }
+
+    let content =
+        serde_json::to_string(data).map_err(|e| Error::CodegenFileError(e.to_string()))?;
+
+    fs::write(path, content).map_err(|e| Error::CodegenFileError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::openapi::{extract, Openapi, OpenapiExtractOptions};
+    use crate::schema::Schema;
+    use serde_json::json;
+
+    fn options() -> OpenapiExtractOptions {
+        OpenapiExtractOptions {
+            wrappers: false,
+            nested_arrays_as_models: false,
+            optional_and_nullable_as_models: false,
+            keep_schema: crate::tools::Filter::new(&[]).unwrap(),
+            single_enum_as_const: false,
+            extension_prefix: None,
+            inflections: Default::default(),
+            identifier_policy: Default::default(),
+            decimal_formats: vec![],
+            formats: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_cached_extraction() {
+        let schema = Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "tags": [{ "name": "users" }],
+            "paths": {
+                "/users/{id}": {
+                    "get": {
+                        "operationId": "getUser",
+                        "tags": ["users"],
+                        "parameters": [
+                            { "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }
+                        ],
+                        "responses": {
+                            "200": {
+                                "description": "user",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/User" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "required": ["id"],
+                        "properties": {
+                            "id": { "type": "string", "format": "uuid" },
+                            "role": { "type": "string", "enum": ["admin", "user"] }
+                        }
+                    }
+                }
+            }
+        }));
+
+        let client = reqwest::blocking::Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        let mut directory = std::env::temp_dir();
+        directory.push(format!("schema-tools-cache-test-{}", std::process::id()));
+        let cache = Cache::new(true, &Some(directory.to_string_lossy().to_string()));
+
+        let extracted: Openapi = cache
+            .extract(&storage, "openapi", options(), |o| {
+                extract(&schema, &storage, o)
+            })
+            .unwrap();
+
+        // second run is served from cache
+        let cached: Openapi = cache
+            .extract(&storage, "openapi", options(), |_| {
+                Err(Error::CodegenFileError("extracted again".to_string()))
+            })
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&cached).unwrap(),
+            serde_json::to_value(&extracted).unwrap()
+        );
+
+        // different options are different entry
+        let mut other = options();
+        other.single_enum_as_const = true;
+        assert_ne!(
+            key(&storage, "openapi", &options()),
+            key(&storage, "openapi", &other)
+        );
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
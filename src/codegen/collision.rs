@@ -0,0 +1,138 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::{error::Error, tools};
+
+pub static COLLISION_POLICIES: &[&str] = &["error", "suffix"];
+
+// what to do when template resolves to file already produced during render
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum OnCollision {
+    #[default]
+    Error,
+    Suffix,
+}
+
+impl std::str::FromStr for OnCollision {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<OnCollision, Self::Err> {
+        match input {
+            "error" => Ok(Self::Error),
+            "suffix" => Ok(Self::Suffix),
+            s => Err(Error::CodegenInvalidCollisionPolicy(s.to_string())),
+        }
+    }
+}
+
+// files produced during render with templates they come from, shared by clones
+#[derive(Debug, Clone, Default)]
+pub struct Outputs {
+    pub on_collision: OnCollision,
+    claimed: Rc<RefCell<HashMap<PathBuf, String>>>,
+}
+
+impl Outputs {
+    pub fn claim(&self, target: PathBuf, source: &str) -> Result<PathBuf, Error> {
+        let mut claimed = self.claimed.borrow_mut();
+
+        let target = match claimed.get(&target) {
+            None => target,
+            Some(first) => match self.on_collision {
+                OnCollision::Error => {
+                    return Err(Error::CodegenFileCollision(
+                        target.to_string_lossy().to_string(),
+                        first.clone(),
+                        source.to_string(),
+                    ))
+                }
+                OnCollision::Suffix => {
+                    let mut candidate = target.clone();
+                    while claimed.contains_key(&candidate) {
+                        candidate = suffixed(&candidate);
+                    }
+
+                    log::warn!(
+                        "{:?} of {} already rendered by {}, using {:?}",
+                        target,
+                        source,
+                        first,
+                        candidate
+                    );
+
+                    candidate
+                }
+            },
+        };
+
+        claimed.insert(target.clone(), source.to_string());
+
+        Ok(target)
+    }
+}
+
+// models/user.rs -> models/user2.rs -> models/user3.rs
+fn suffixed(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let filename = match path.extension() {
+        Some(extension) => format!(
+            "{}.{}",
+            tools::bump_suffix_number(&stem),
+            extension.to_string_lossy()
+        ),
+        None => tools::bump_suffix_number(&stem),
+    };
+
+    path.with_file_name(filename)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collisions() {
+        let outputs = Outputs::default();
+
+        let target = PathBuf::from("out/models/user.rs");
+        assert_eq!(outputs.claim(target.clone(), "models.j2").unwrap(), target);
+
+        match outputs.claim(target.clone(), "user.j2") {
+            Err(Error::CodegenFileCollision(file, first, second)) => {
+                assert_eq!(file, "out/models/user.rs");
+                assert_eq!(first, "models.j2");
+                assert_eq!(second, "user.j2");
+            }
+            r => panic!("expected collision, got {:?}", r),
+        }
+
+        let outputs = Outputs {
+            on_collision: OnCollision::Suffix,
+            ..outputs
+        };
+
+        assert_eq!(
+            outputs.claim(target.clone(), "user.j2").unwrap(),
+            PathBuf::from("out/models/user2.rs")
+        );
+        assert_eq!(
+            outputs.claim(target, "user.j2").unwrap(),
+            PathBuf::from("out/models/user3.rs")
+        );
+        assert_eq!(
+            outputs
+                .claim(PathBuf::from("out/Makefile"), "a.j2")
+                .and_then(|_| outputs.claim(PathBuf::from("out/Makefile"), "b.j2"))
+                .unwrap(),
+            PathBuf::from("out/Makefile2")
+        );
+    }
+}
@@ -0,0 +1,288 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::example;
+use crate::formats::Formats;
+use crate::tools::HTTP_METHODS;
+
+pub static CONTRACT_FRAMEWORKS: &[&str] = &["pytest", "jest"];
+
+// request and expectations of single operation, rendered into contract test
+#[derive(Debug, Serialize, Clone)]
+pub struct ContractCase {
+    // path with parameters replaced by examples
+    pub url: String,
+    pub query: Map<String, Value>,
+    pub headers: Map<String, Value>,
+    pub cookies: Map<String, Value>,
+    pub body: Option<Value>,
+    pub content_type: Option<String>,
+    pub statuses: Vec<u32>,
+    pub success: Option<u32>,
+
+    // json schema of response by status code, references inlined
+    pub schemas: Map<String, Value>,
+}
+
+// contract cases keyed by lowercase method and path (ex. `get /users/{id}`) of endpoint
+pub fn cases(root: &Value, formats: &Formats) -> Map<String, Value> {
+    let mut result = Map::new();
+
+    let paths = match root.get("paths") {
+        Some(Value::Object(paths)) => paths,
+        _ => return result,
+    };
+
+    for (path, item) in paths {
+        let item = resolve(root, item);
+
+        for method in HTTP_METHODS {
+            let operation = match item.get(*method) {
+                Some(operation) => operation,
+                None => continue,
+            };
+
+            let case = case(root, path, item, operation, formats);
+            result.insert(
+                format!("{} {}", method, path),
+                serde_json::to_value(case).unwrap(),
+            );
+        }
+    }
+
+    result
+}
+
+fn case(
+    root: &Value,
+    path: &str,
+    item: &Value,
+    operation: &Value,
+    formats: &Formats,
+) -> ContractCase {
+    let mut url = path.to_string();
+    let mut query = Map::new();
+    let mut headers = Map::new();
+    let mut cookies = Map::new();
+
+    // operation parameters take precedence over path item ones
+    let parameters = [operation, item]
+        .iter()
+        .filter_map(|n| n.get("parameters").and_then(|p| p.as_array()))
+        .flatten()
+        .map(|p| resolve(root, p))
+        .collect::<Vec<_>>();
+
+    for parameter in parameters {
+        let name = parameter.get("name").and_then(|n| n.as_str()).unwrap_or("");
+        let required = parameter.get("required") == Some(&Value::Bool(true));
+        let value = parameter
+            .get("example")
+            .cloned()
+            .or_else(|| {
+                parameter
+                    .get("schema")
+                    .map(|s| example::generate(root, s, formats))
+            })
+            .unwrap_or(Value::Null);
+
+        match parameter.get("in").and_then(|i| i.as_str()) {
+            Some("path") => {
+                let value = match value {
+                    Value::String(s) => s,
+                    value => value.to_string(),
+                };
+                url = url.replace(&format!("{{{}}}", name), &value);
+            }
+            Some("query") if required => {
+                query.entry(name).or_insert(value);
+            }
+            Some("header") if required => {
+                headers.entry(name).or_insert(value);
+            }
+            Some("cookie") if required => {
+                cookies.entry(name).or_insert(value);
+            }
+            _ => {}
+        }
+    }
+
+    let (content_type, body) = match operation
+        .get("requestBody")
+        .map(|b| resolve(root, b))
+        .and_then(|b| b.get("content"))
+    {
+        Some(Value::Object(content)) => {
+            let media = content
+                .iter()
+                .find(|(t, _)| t.as_str() == "application/json")
+                .or_else(|| content.iter().next());
+
+            match media {
+                Some((content_type, media)) => (
+                    Some(content_type.clone()),
+                    Some(
+                        media
+                            .get("example")
+                            .cloned()
+                            .or_else(|| {
+                                media
+                                    .get("schema")
+                                    .map(|s| example::generate(root, s, formats))
+                            })
+                            .unwrap_or(Value::Null),
+                    ),
+                ),
+                None => (None, None),
+            }
+        }
+        _ => (None, None),
+    };
+
+    let mut statuses = vec![];
+    let mut schemas = Map::new();
+    if let Some(Value::Object(responses)) = operation.get("responses") {
+        for (status, response) in responses {
+            let code = match status.parse::<u32>() {
+                Ok(code) => code,
+                // default and ranges (2XX) cannot be asserted exactly
+                Err(_) => continue,
+            };
+            statuses.push(code);
+
+            if let Some(schema) =
+                resolve(root, response).pointer("/content/application~1json/schema")
+            {
+                schemas.insert(status.clone(), inline(root, schema, &mut vec![]));
+            }
+        }
+    }
+
+    ContractCase {
+        url,
+        query,
+        headers,
+        cookies,
+        body,
+        content_type,
+        success: statuses.iter().find(|s| (200..300).contains(*s)).cloned(),
+        statuses,
+        schemas,
+    }
+}
+
+pub(super) fn resolve<'a>(root: &'a Value, node: &'a Value) -> &'a Value {
+    node.get("$ref")
+        .and_then(|r| r.as_str())
+        .and_then(|r| r.strip_prefix('#'))
+        .and_then(|pointer| root.pointer(pointer))
+        .unwrap_or(node)
+}
+
+// schema without references, recursive references accept any value
+pub(super) fn inline(root: &Value, node: &Value, stack: &mut Vec<String>) -> Value {
+    match node {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get("$ref") {
+                if stack.contains(reference) {
+                    return Value::Object(Map::new());
+                }
+
+                return match reference.strip_prefix('#').and_then(|p| root.pointer(p)) {
+                    Some(target) => {
+                        stack.push(reference.clone());
+                        let value = inline(root, target, stack);
+                        stack.pop();
+                        value
+                    }
+                    None => Value::Object(Map::new()),
+                };
+            }
+
+            Value::Object(
+                map.iter()
+                    .map(|(key, value)| match key.as_str() {
+                        "enum" | "const" | "default" | "example" | "examples" => {
+                            (key.clone(), value.clone())
+                        }
+                        _ => (key.clone(), inline(root, value, stack)),
+                    })
+                    .collect(),
+            )
+        }
+        Value::Array(list) => Value::Array(list.iter().map(|v| inline(root, v, stack)).collect()),
+        value => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_contract_cases() {
+        let root = json!({
+            "paths": {
+                "/users/{id}": {
+                    "parameters": [{ "$ref": "#/components/parameters/Id" }],
+                    "put": {
+                        "parameters": [
+                            { "name": "dry", "in": "query", "required": true, "schema": { "type": "boolean" } },
+                            { "name": "page", "in": "query", "schema": { "type": "integer" } },
+                            { "name": "session", "in": "cookie", "required": true, "schema": { "type": "string" }, "example": "abc" },
+                            { "name": "theme", "in": "cookie", "schema": { "type": "string" } }
+                        ],
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/User" }
+                                }
+                            }
+                        },
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/User" }
+                                    }
+                                }
+                            },
+                            "404": { "description": "Not found" },
+                            "default": { "description": "Error" }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "Id": { "name": "id", "in": "path", "required": true, "schema": { "type": "integer" }, "example": 7 }
+                },
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "properties": { "name": { "type": "string", "example": "John" } }
+                    }
+                }
+            }
+        });
+
+        let result = cases(&root, &Formats::default());
+        let case = &result["put /users/{id}"];
+
+        assert_eq!(case["url"], json!("/users/7"));
+        assert_eq!(case["query"], json!({ "dry": true }));
+        assert_eq!(case["cookies"], json!({ "session": "abc" }));
+        assert_eq!(case["body"], json!({ "name": "John" }));
+        assert_eq!(case["content_type"], json!("application/json"));
+        assert_eq!(case["statuses"], json!([200, 404]));
+        assert_eq!(case["success"], json!(200));
+        assert_eq!(
+            case["schemas"]["200"],
+            json!({
+                "type": "object",
+                "properties": { "name": { "type": "string", "example": "John" } }
+            })
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+static LINE_WIDTH: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Default)]
+pub struct Docs {
+    #[serde(rename = "title")]
+    pub title: Option<String>,
+
+    #[serde(rename = "description")]
+    pub description: Option<String>,
+
+    #[serde(rename = "description_lines")]
+    pub description_lines: Vec<String>,
+
+    #[serde(rename = "example")]
+    pub example: Option<Value>,
+
+    #[serde(rename = "external_docs")]
+    pub external_docs: Option<ExternalDocs>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct ExternalDocs {
+    #[serde(rename = "url")]
+    pub url: String,
+
+    #[serde(rename = "description")]
+    pub description: Option<String>,
+}
+
+impl Docs {
+    // summary is used as a title of endpoints
+    pub fn new(data: &Map<String, Value>) -> Self {
+        let title = data
+            .get("title")
+            .or_else(|| data.get("summary"))
+            .and_then(|v| v.as_str())
+            .map(|s| escape(s.trim()));
+
+        let description = data
+            .get("description")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let example = data.get("example").cloned().or_else(|| {
+            data.get("examples").and_then(|e| match e {
+                Value::Array(a) => a.first().cloned(),
+                // openapi examples object
+                Value::Object(o) => o.values().next().and_then(|e| e.get("value").cloned()),
+                _ => None,
+            })
+        });
+
+        let external_docs = data.get("externalDocs").and_then(|d| {
+            d.get("url")
+                .and_then(|u| u.as_str())
+                .map(|url| ExternalDocs {
+                    url: url.to_string(),
+                    description: d
+                        .get("description")
+                        .and_then(|v| v.as_str())
+                        .map(|s| escape(s.trim())),
+                })
+        });
+
+        Self {
+            title,
+            description_lines: description
+                .as_deref()
+                .map(description_lines)
+                .unwrap_or_default(),
+            description,
+            example,
+            external_docs,
+        }
+    }
+}
+
+// lines safe to be placed in any kind of comment, wrapped and without trailing blank lines
+pub fn description_lines(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+
+    for line in text.lines() {
+        let line = escape(line.trim_end());
+
+        if line.len() <= LINE_WIDTH || line.starts_with(char::is_whitespace) {
+            lines.push(line);
+            continue;
+        }
+
+        let mut current = String::new();
+        for word in line.split(' ') {
+            if !current.is_empty() && current.len() + word.len() + 1 > LINE_WIDTH {
+                lines.push(std::mem::take(&mut current));
+            }
+
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+        lines.push(current);
+    }
+
+    while lines.last().map(|l| l.is_empty()).unwrap_or(false) {
+        lines.pop();
+    }
+
+    let leading = lines.iter().take_while(|l| l.is_empty()).count();
+    lines.drain(..leading);
+
+    lines
+}
+
+// comment terminators and carriage returns break generated code
+fn escape(text: &str) -> String {
+    text.replace('\r', "").replace("*/", "*\\/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_description_lines() {
+        let long = "word ".repeat(30);
+
+        let lines = description_lines(&format!("\nFirst line */\r\n\n  code block\n{}\n\n", long));
+
+        assert_eq!(lines[0], "First line *\\/");
+        assert_eq!(lines[1], "");
+        assert_eq!(lines[2], "  code block");
+        assert_eq!(lines.len(), 5);
+        assert_eq!(true, lines[3].len() <= LINE_WIDTH);
+    }
+
+    #[test]
+    fn test_docs_from_schema() {
+        let data = json!({
+            "summary": "List users",
+            "description": "Returns\nall users",
+            "examples": { "default": { "value": { "id": 1 } } },
+            "externalDocs": { "url": "https://example.com/users" }
+        });
+
+        let docs = Docs::new(data.as_object().unwrap());
+
+        assert_eq!(docs.title, Some("List users".to_string()));
+        assert_eq!(docs.description_lines, vec!["Returns", "all users"]);
+        assert_eq!(docs.example, Some(json!({ "id": 1 })));
+        assert_eq!(
+            docs.external_docs.unwrap().url,
+            "https://example.com/users".to_string()
+        );
+    }
+}
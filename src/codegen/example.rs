@@ -0,0 +1,214 @@
+use serde_json::{json, Map, Value};
+
+use crate::formats::Formats;
+
+// example value satisfying schema, explicit examples, defaults and enums are preferred
+pub fn generate(root: &Value, node: &Value, formats: &Formats) -> Value {
+    example(root, node, formats, &mut vec![])
+}
+
+fn example(root: &Value, node: &Value, formats: &Formats, stack: &mut Vec<String>) -> Value {
+    let map = match node {
+        Value::Object(map) => map,
+        _ => return Value::Null,
+    };
+
+    if let Some(Value::String(reference)) = map.get("$ref") {
+        if stack.contains(reference) {
+            return Value::Null;
+        }
+
+        return match reference.strip_prefix('#').and_then(|p| root.pointer(p)) {
+            Some(target) => {
+                stack.push(reference.clone());
+                let value = example(root, target, formats, stack);
+                stack.pop();
+                value
+            }
+            None => Value::Null,
+        };
+    }
+
+    if let Some(value) = map.get("example").or_else(|| map.get("const")) {
+        return value.clone();
+    }
+    if let Some(Value::Array(examples)) = map.get("examples") {
+        if let Some(value) = examples.first() {
+            return value.clone();
+        }
+    }
+    if let Some(value) = map.get("default") {
+        return value.clone();
+    }
+    if let Some(Value::Array(values)) = map.get("enum") {
+        if let Some(value) = values.first() {
+            return value.clone();
+        }
+    }
+
+    for junctor in &["oneOf", "anyOf"] {
+        if let Some(Value::Array(list)) = map.get(*junctor) {
+            if let Some(first) = list.first() {
+                return example(root, first, formats, stack);
+            }
+        }
+    }
+
+    if let Some(Value::Array(list)) = map.get("allOf") {
+        let mut result = Map::new();
+        for part in list {
+            match example(root, part, formats, stack) {
+                Value::Object(object) => result.extend(object),
+                value if list.len() == 1 => return value,
+                _ => {}
+            }
+        }
+        return Value::Object(result);
+    }
+
+    let type_ = match map.get("type") {
+        Some(Value::String(t)) => t.as_str(),
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(|t| t.as_str())
+            .find(|t| *t != "null")
+            .unwrap_or("null"),
+        _ if map.contains_key("properties") => "object",
+        _ if map.contains_key("items") => "array",
+        _ => "any",
+    };
+
+    match type_ {
+        "string" => string(map, formats),
+        "integer" => {
+            let minimum = map.get("minimum").and_then(|m| m.as_i64());
+            let exclusive = map
+                .get("exclusiveMinimum")
+                .and_then(|m| m.as_i64())
+                .map(|m| m + 1);
+
+            json!(minimum.or(exclusive).unwrap_or(1))
+        }
+        "number" => json!(map.get("minimum").and_then(|m| m.as_f64()).unwrap_or(1.5)),
+        "boolean" => json!(true),
+        "null" => Value::Null,
+        "array" => {
+            let item = map
+                .get("items")
+                .map(|i| example(root, i, formats, stack))
+                .unwrap_or(Value::Null);
+            let count = map
+                .get("minItems")
+                .and_then(|m| m.as_u64())
+                .unwrap_or(1)
+                .max(1);
+
+            Value::Array((0..count).map(|_| item.clone()).collect())
+        }
+        "object" => {
+            let mut result = Map::new();
+
+            if let Some(Value::Object(properties)) = map.get("properties") {
+                for (name, property) in properties {
+                    // read only properties are not sent in requests
+                    if property.get("readOnly") == Some(&Value::Bool(true)) {
+                        continue;
+                    }
+
+                    result.insert(name.clone(), example(root, property, formats, stack));
+                }
+            }
+
+            Value::Object(result)
+        }
+        _ => Value::Null,
+    }
+}
+
+fn string(map: &Map<String, Value>, formats: &Formats) -> Value {
+    let example = map
+        .get("format")
+        .and_then(|f| f.as_str())
+        .and_then(|f| formats.example(f));
+
+    let value = match example {
+        Some(example) => example,
+        _ => {
+            let min = map.get("minLength").and_then(|m| m.as_u64()).unwrap_or(0) as usize;
+            let max = map.get("maxLength").and_then(|m| m.as_u64()).unwrap_or(7) as usize;
+
+            "example"
+                .chars()
+                .cycle()
+                .take(min.max(7.min(max)))
+                .collect()
+        }
+    };
+
+    Value::String(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::Format;
+
+    #[test]
+    fn test_generate_example() {
+        let root = json!({
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "properties": {
+                            "id": { "type": "integer", "readOnly": true },
+                            "name": { "type": "string", "maxLength": 3 },
+                            "email": { "type": "string", "format": "email" },
+                            "role": { "type": "string", "enum": ["admin", "user"] },
+                            "age": { "type": "integer", "minimum": 18 },
+                            "tags": { "type": "array", "items": { "type": "string", "example": "a" } },
+                            "manager": { "$ref": "#/components/schemas/User" }
+                        }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(
+            generate(
+                &root,
+                &json!({ "$ref": "#/components/schemas/User" }),
+                &Formats::default()
+            ),
+            json!({
+                "name": "exa",
+                "email": "user@example.com",
+                "role": "admin",
+                "age": 18,
+                "tags": ["a"],
+                "manager": null
+            })
+        );
+
+        let mut formats = Formats::default();
+        formats
+            .add(
+                "iban",
+                Format {
+                    kind: "iban".to_string(),
+                    pattern: None,
+                    example: Some("DE89370400440532013000".to_string()),
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            generate(
+                &root,
+                &json!({ "type": "string", "format": "iban" }),
+                &formats
+            ),
+            json!("DE89370400440532013000")
+        );
+    }
+}
@@ -7,6 +7,8 @@ use serde_json::Value;
 use tera::Tera;
 use tera::{try_get_value, Result as TeraResult};
 
+use crate::process::name::word::Inflections;
+
 pub fn register(tera: &mut Tera) {
     tera.register_filter("camelcase", camelcase);
     tera.register_filter("pascalcase", pascalcase);
@@ -25,6 +27,31 @@ pub fn register(tera: &mut Tera) {
     tera.register_filter("filter_startswith", filter_startswith);
     tera.register_filter("filter_inarray", filter_inarray);
     tera.register_filter("filter_not_inarray", filter_not_inarray);
+
+    register_inflections(tera, Inflections::default());
+}
+
+// pluralize and singularize filters consult user defined irregular words
+pub fn register_inflections(tera: &mut Tera, inflections: Inflections) {
+    let singular = inflections.clone();
+
+    tera.register_filter(
+        "pluralize",
+        move |value: &Value, _: &HashMap<String, Value>| {
+            let s = try_get_value!("pluralize", "value", String, value);
+
+            Ok(to_value(inflections.pluralize(s)).unwrap())
+        },
+    );
+
+    tera.register_filter(
+        "singularize",
+        move |value: &Value, _: &HashMap<String, Value>| {
+            let s = try_get_value!("singularize", "value", String, value);
+
+            Ok(to_value(singular.singularize(s)).unwrap())
+        },
+    );
 }
 
 pub fn pascalcase(value: &Value, _: &HashMap<String, Value>) -> TeraResult<Value> {
@@ -121,7 +148,7 @@ pub fn when_numeric(value: &Value, args: &HashMap<String, Value>) -> TeraResult<
             None => return Err(tera::Error::msg("Please provide prefix parameter")),
         };
 
-        Ok(to_value(&format!("{}{}", prefix, value)).unwrap())
+        Ok(to_value(format!("{}{}", prefix, value)).unwrap())
     } else {
         Ok(to_value(&value).unwrap())
     }
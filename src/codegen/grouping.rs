@@ -0,0 +1,254 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::error::Error;
+use crate::scope::Space;
+
+use super::openapi::{endpoint::Endpoint, Openapi};
+
+// group of operations without value of grouping key
+pub static DEFAULT_GROUP: &str = "default";
+
+// key grouping endpoints: `tag`, extension of operation (ex. `x-service`) or `path-prefix:N`
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroupKey {
+    Tag,
+    Extension(String),
+    PathPrefix(usize),
+}
+
+#[derive(Serialize, Clone)]
+pub struct EndpointGroup {
+    pub key: String,
+    pub operations: Vec<Endpoint>,
+    pub models_used: Vec<String>,
+}
+
+impl std::str::FromStr for GroupKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "tag" {
+            return Ok(Self::Tag);
+        }
+
+        if s.starts_with("x-") && s.len() > 2 {
+            return Ok(Self::Extension(s.to_string()));
+        }
+
+        match s
+            .strip_prefix("path-prefix:")
+            .and_then(|n| n.parse::<usize>().ok())
+        {
+            Some(n) if n > 0 => Ok(Self::PathPrefix(n)),
+            _ => Err(Error::CodegenNotAllowedGroupBy(s.to_string())),
+        }
+    }
+}
+
+impl GroupKey {
+    // endpoint tagged with many tags (or extension holding list) belongs to many groups
+    pub fn keys(&self, endpoint: &Endpoint) -> Vec<String> {
+        let keys = match self {
+            Self::Tag => endpoint.get_tags().clone(),
+            Self::Extension(name) => match endpoint.get_extension(name) {
+                Some(Value::String(s)) => vec![s.clone()],
+                Some(Value::Array(list)) => list
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .collect(),
+                Some(Value::Null) | None => vec![],
+                Some(value) => vec![value.to_string()],
+            },
+            Self::PathPrefix(n) => {
+                let prefix = endpoint
+                    .get_path()
+                    .split('/')
+                    .filter(|s| !s.is_empty())
+                    .take(*n)
+                    .collect::<Vec<_>>();
+
+                vec![prefix.join("/")]
+            }
+        };
+
+        // repeated keys are dropped keeping order of first occurrences
+        let mut seen = HashSet::new();
+        let keys = keys
+            .into_iter()
+            .filter(|k| !k.is_empty() && seen.insert(k.clone()))
+            .collect::<Vec<_>>();
+
+        if keys.is_empty() {
+            vec![DEFAULT_GROUP.to_string()]
+        } else {
+            keys
+        }
+    }
+
+    // groups sorted by key, models are used by group when any of its operations reaches them
+    pub fn groups(&self, openapi: &Openapi) -> Vec<EndpointGroup> {
+        let mut groups: BTreeMap<String, Vec<Endpoint>> = BTreeMap::new();
+
+        for endpoint in &openapi.endpoints {
+            for key in self.keys(endpoint) {
+                groups.entry(key).or_default().push(endpoint.clone());
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(key, operations)| {
+                let models_used = openapi
+                    .models
+                    .models()
+                    .iter()
+                    .filter(|m| {
+                        m.spaces.list.iter().any(|s| match s {
+                            Space::Operation(o) => {
+                                operations.iter().any(|e| e.get_operation() == o)
+                            }
+                            _ => false,
+                        })
+                    })
+                    .filter_map(|m| m.name().ok().map(|n| n.to_string()))
+                    .collect();
+
+                EndpointGroup {
+                    key,
+                    operations,
+                    models_used,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_groups() {
+        let schema = crate::schema::Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "info": { "title": "api", "version": "1.0.0" },
+            "paths": {
+                "/v1/users/{id}": {
+                    "get": {
+                        "operationId": "getUser",
+                        "tags": ["users", "admin", "users"],
+                        "x-service": "identity",
+                        "parameters": [{ "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }],
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": { "application/json": { "schema": { "$ref": "#/components/schemas/User" } } }
+                            }
+                        }
+                    }
+                },
+                "/v1/orders": {
+                    "get": {
+                        "operationId": "listOrders",
+                        "x-service": "shop",
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Order" } } }
+                            }
+                        }
+                    }
+                },
+                "/health": {
+                    "get": {
+                        "operationId": "health",
+                        "responses": { "204": { "description": "ok" } }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": { "type": "object", "properties": { "name": { "type": "string" } } },
+                    "Order": { "type": "object", "properties": { "total": { "type": "number" } } }
+                }
+            }
+        }));
+        let client = reqwest::blocking::Client::new();
+        let storage = crate::storage::SchemaStorage::new(&schema, &client);
+
+        let openapi = crate::codegen::openapi::extract(
+            &schema,
+            &storage,
+            crate::codegen::openapi::OpenapiExtractOptions {
+                wrappers: false,
+                optional_and_nullable_as_models: false,
+                nested_arrays_as_models: false,
+                keep_schema: crate::tools::Filter::new(&[]).unwrap(),
+                single_enum_as_const: false,
+                extension_prefix: None,
+                inflections: Default::default(),
+                identifier_policy: Default::default(),
+                decimal_formats: vec![],
+                formats: Default::default(),
+            },
+        )
+        .unwrap();
+
+        let summary = |key: &str| {
+            key.parse::<GroupKey>()
+                .unwrap()
+                .groups(&openapi)
+                .into_iter()
+                .map(|g| {
+                    (
+                        g.key,
+                        g.operations
+                            .iter()
+                            .map(|e| e.get_operation().to_string())
+                            .collect::<Vec<_>>(),
+                        g.models_used,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let s = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            summary("x-service"),
+            vec![
+                ("default".to_string(), s(&["health"]), s(&[])),
+                ("identity".to_string(), s(&["getUser"]), s(&["User"])),
+                ("shop".to_string(), s(&["listOrders"]), s(&["Order"])),
+            ]
+        );
+        assert_eq!(
+            summary("path-prefix:2")
+                .into_iter()
+                .map(|g| g.0)
+                .collect::<Vec<_>>(),
+            s(&["health", "v1/orders", "v1/users"])
+        );
+        assert_eq!(
+            summary("tag")
+                .into_iter()
+                .map(|g| (g.0, g.1))
+                .collect::<Vec<_>>(),
+            vec![
+                ("admin".to_string(), s(&["getUser"])),
+                ("default".to_string(), s(&["listOrders", "health"])),
+                ("users".to_string(), s(&["getUser"])),
+            ]
+        );
+
+        assert!(matches!(
+            "path-prefix:0".parse::<GroupKey>(),
+            Err(Error::CodegenNotAllowedGroupBy(_))
+        ));
+        assert!("service".parse::<GroupKey>().is_err());
+    }
+}
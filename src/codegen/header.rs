@@ -0,0 +1,196 @@
+use std::{path::Path, time::SystemTime};
+
+use serde::Serialize;
+
+use super::output::Output;
+use crate::{error::Error, schema::Schema, tools};
+
+static DEFAULT_TEMPLATE: &str = "Code generated by schematools %version%. DO NOT EDIT.
+source: %spec.url%
+spec version: %spec.version%
+spec hash: %spec.hash%
+generated at: %timestamp%";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Provenance {
+    pub version: String,
+    pub spec: SpecProvenance,
+    pub timestamp: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SpecProvenance {
+    pub url: String,
+    pub version: Option<String>,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Header {
+    template: String,
+    provenance: Provenance,
+}
+
+impl Provenance {
+    pub fn new(schema: &Schema, skip_volatile: bool) -> Self {
+        let body = schema.get_body();
+
+        let hash = crate::hash::fingerprint(body);
+
+        let timestamp = if skip_volatile {
+            None
+        } else {
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .ok()
+        };
+
+        Self {
+            version: crate::VERSION.to_string(),
+            spec: SpecProvenance {
+                url: schema.get_url().to_string(),
+                version: body
+                    .pointer("/info/version")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                hash,
+            },
+            timestamp,
+        }
+    }
+}
+
+impl Header {
+    pub fn new(schema: &Schema, template: Option<String>, skip_volatile: bool) -> Self {
+        Self {
+            template: template
+                .map(|t| t.replace("\\n", "\n"))
+                .unwrap_or_else(|| DEFAULT_TEMPLATE.to_string()),
+            provenance: Provenance::new(schema, skip_volatile),
+        }
+    }
+
+    // lines referencing missing values (ex. skipped volatile fields) are omitted
+    pub fn lines(&self) -> Vec<String> {
+        self.template
+            .lines()
+            .filter_map(|line| tools::fill_parameters(line, &self.provenance).ok())
+            .collect()
+    }
+
+    pub fn render(&self, filename: &str) -> Option<String> {
+        let extension = Path::new(filename)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        let (prefix, suffix) = comment_syntax(&extension)?;
+
+        Some(
+            self.lines()
+                .iter()
+                .map(|line| {
+                    format!("{}{}{}", prefix, line, suffix)
+                        .trim_end()
+                        .to_string()
+                        + "\n"
+                })
+                .collect::<String>(),
+        )
+    }
+
+    pub fn inject(&self, files: &[String], output: &dyn Output) -> Result<(), Error> {
+        for file in files {
+            let header = match self.render(file) {
+                Some(h) => h,
+                None => {
+                    log::debug!("Header skipped, unknown comment syntax: {}", file);
+                    continue;
+                }
+            };
+
+            let content = String::from_utf8(output.read(Path::new(file))?)
+                .map_err(|e| Error::CodegenFileError(e.to_string()))?;
+
+            let result = if content.starts_with("#!") {
+                let pos = content.find('\n').map(|p| p + 1).unwrap_or(content.len());
+                format!("{}{}{}", &content[..pos], header, &content[pos..])
+            } else {
+                format!("{}{}", header, content)
+            };
+
+            output.write(Path::new(file), result.as_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+fn comment_syntax(extension: &str) -> Option<(&'static str, &'static str)> {
+    match extension {
+        "rs" | "go" | "java" | "kt" | "kts" | "scala" | "swift" | "c" | "h" | "cc" | "cpp"
+        | "hpp" | "cs" | "js" | "jsx" | "ts" | "tsx" | "dart" | "php" | "proto" => {
+            Some(("// ", ""))
+        }
+        "py" | "rb" | "sh" | "bash" | "yaml" | "yml" | "toml" | "r" | "pl" | "ex" | "exs"
+        | "tf" | "cfg" | "ini" | "properties" => Some(("# ", "")),
+        "sql" | "lua" | "hs" | "elm" => Some(("-- ", "")),
+        "html" | "xml" | "md" | "vue" | "svelte" => Some(("<!-- ", " -->")),
+        "css" | "scss" | "less" => Some(("/* ", " */")),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_header_contains_provenance() {
+        let schema = Schema::from_json(json!({"info": {"version": "1.2.3"}}));
+        let header = Header::new(&schema, None, false);
+
+        let lines = header.lines();
+
+        assert_eq!(lines.len(), 5);
+        assert_eq!(
+            lines[0],
+            format!(
+                "Code generated by schematools {}. DO NOT EDIT.",
+                crate::VERSION
+            )
+        );
+        assert_eq!(lines[1], "source: schema://inline");
+        assert_eq!(lines[2], "spec version: 1.2.3");
+        assert_eq!(true, lines[4].starts_with("generated at: "));
+    }
+
+    #[test]
+    fn test_header_skip_volatile_is_reproducible() {
+        let schema = Schema::from_json(json!({"type": "object"}));
+
+        let first = Header::new(&schema, None, true).render("models.rs");
+        let second = Header::new(&schema, None, true).render("models.rs");
+
+        assert_eq!(first, second);
+        assert_eq!(false, first.clone().unwrap().contains("generated at"));
+        assert_eq!(false, first.unwrap().contains("spec version"));
+    }
+
+    #[test]
+    fn test_header_comment_syntax() {
+        let schema = Schema::from_json(json!({}));
+        let header = Header::new(&schema, Some("generated".to_string()), true);
+
+        assert_eq!(header.render("a/b.go"), Some("// generated\n".to_string()));
+        assert_eq!(header.render("b.py"), Some("# generated\n".to_string()));
+        assert_eq!(
+            header.render("b.md"),
+            Some("<!-- generated -->\n".to_string())
+        );
+        assert_eq!(header.render("b.unknown"), None);
+        assert_eq!(header.render("Makefile"), None);
+    }
+}
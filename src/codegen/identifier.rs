@@ -0,0 +1,142 @@
+use crate::error::Error;
+
+pub static IDENTIFIER_POLICIES: &[&str] = &["keep", "strip", "transliterate", "error"];
+
+// what to do with non ascii characters in names used as identifiers
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum IdentifierPolicy {
+    #[default]
+    Keep,
+    Strip,
+    Transliterate,
+    Error,
+}
+
+impl std::str::FromStr for IdentifierPolicy {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<IdentifierPolicy, Self::Err> {
+        match input {
+            "keep" => Ok(Self::Keep),
+            "strip" => Ok(Self::Strip),
+            "transliterate" => Ok(Self::Transliterate),
+            "error" => Ok(Self::Error),
+            s => Err(Error::CodegenInvalidIdentifier(s.to_string())),
+        }
+    }
+}
+
+impl IdentifierPolicy {
+    pub fn apply(&self, name: &str) -> Result<String, Error> {
+        if name.is_ascii() {
+            return Ok(name.to_string());
+        }
+
+        let identifier = match self {
+            Self::Keep => name.to_string(),
+            Self::Strip => name.chars().filter(|c| c.is_ascii()).collect(),
+            Self::Transliterate => transliterate(name),
+            Self::Error => return Err(Error::CodegenInvalidIdentifier(name.to_string())),
+        };
+
+        // nothing left of name written entirely in other scripts (ex. CJK stripped)
+        if !identifier.chars().any(|c| c.is_alphanumeric()) {
+            return Err(Error::CodegenInvalidIdentifier(name.to_string()));
+        }
+
+        Ok(identifier)
+    }
+}
+
+pub fn transliterate(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+
+    for c in name.chars() {
+        if c.is_ascii() {
+            result.push(c);
+            continue;
+        }
+
+        match latin(c) {
+            Some(s) => result.push_str(s),
+            // scripts without latin equivalent (ex. CJK) are kept unique
+            None if c.is_alphanumeric() => result.push_str(&format!("U{:x}", c as u32)),
+            None => {}
+        }
+    }
+
+    result
+}
+
+fn latin(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ä' | 'æ' => "ae",
+        'Ä' | 'Æ' => "Ae",
+        'ö' | 'œ' => "oe",
+        'Ö' | 'Œ' => "Oe",
+        'ü' => "ue",
+        'Ü' => "Ue",
+        'ß' => "ss",
+        'à' | 'á' | 'â' | 'ã' | 'å' | 'ā' | 'ą' => "a",
+        'À' | 'Á' | 'Â' | 'Ã' | 'Å' | 'Ā' | 'Ą' => "A",
+        'ç' | 'ć' | 'č' => "c",
+        'Ç' | 'Ć' | 'Č' => "C",
+        'ď' | 'đ' | 'ð' => "d",
+        'Ď' | 'Đ' | 'Ð' => "D",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ę' | 'ě' => "e",
+        'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ę' | 'Ě' => "E",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' => "i",
+        'Ì' | 'Í' | 'Î' | 'Ï' | 'Ī' => "I",
+        'ł' => "l",
+        'Ł' => "L",
+        'ñ' | 'ń' | 'ň' => "n",
+        'Ñ' | 'Ń' | 'Ň' => "N",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ø' | 'ō' => "o",
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ø' | 'Ō' => "O",
+        'ř' => "r",
+        'Ř' => "R",
+        'ś' | 'š' => "s",
+        'Ś' | 'Š' => "S",
+        'ť' => "t",
+        'Ť' => "T",
+        'ù' | 'ú' | 'û' | 'ū' | 'ů' => "u",
+        'Ù' | 'Ú' | 'Û' | 'Ū' | 'Ů' => "U",
+        'ý' | 'ÿ' => "y",
+        'Ý' | 'Ÿ' => "Y",
+        'ź' | 'ż' | 'ž' => "z",
+        'Ź' | 'Ż' | 'Ž' => "Z",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case( "größe", "groesse"; "german" )]
+    #[test_case( "Żółw", "Zolw"; "polish" )]
+    #[test_case( "名前", "U540dU524d"; "cjk" )]
+    #[test_case( "plain_name", "plain_name"; "ascii" )]
+    fn test_transliterate(name: &str, expected: &str) {
+        assert_eq!(
+            IdentifierPolicy::Transliterate.apply(name).unwrap(),
+            expected.to_string()
+        );
+    }
+
+    #[test]
+    fn test_strip_and_error() {
+        assert_eq!(
+            IdentifierPolicy::Strip.apply("größe").unwrap(),
+            "gre".to_string()
+        );
+        assert_eq!(true, IdentifierPolicy::Error.apply("größe").is_err());
+        assert_eq!(true, IdentifierPolicy::Error.apply("size").is_ok());
+        assert_eq!(true, IdentifierPolicy::Strip.apply("名前").is_err());
+        assert_eq!(
+            IdentifierPolicy::default().apply("größe").unwrap(),
+            "größe".to_string()
+        );
+    }
+}
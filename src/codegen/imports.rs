@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use tera::Tera;
+
+use super::symbols::SymbolTable;
+use crate::discovery::Discovered;
+use crate::error::Error;
+use crate::schema::{path_to_url, Schema};
+
+// how file of symbol becomes import path of target language
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportStyle {
+    separator: String,
+
+    // leading segment, ex. crate or module path of go project
+    prefix: String,
+
+    // leading directories not present in import paths, ex. src/
+    strip: String,
+
+    // file names standing for their directory, ex. mod or __init__
+    index: Vec<String>,
+
+    // package is directory of file
+    directory: bool,
+
+    // path relative to directory of importing file
+    relative: bool,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct Manifest {
+    #[serde(default)]
+    imports: HashMap<String, Map<String, Value>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Imports {
+    styles: HashMap<String, ImportStyle>,
+}
+
+fn builtin() -> HashMap<String, ImportStyle> {
+    let styles = json!({
+        "rust": { "separator": "::", "prefix": "crate", "strip": "src/", "index": ["mod", "lib"] },
+        "ts": { "separator": "/", "index": ["index"], "relative": true },
+        "go": { "separator": "/", "directory": true },
+        "python": { "separator": ".", "index": ["__init__"] },
+    });
+
+    serde_json::from_value(styles).unwrap()
+}
+
+impl Default for Imports {
+    fn default() -> Self {
+        Self { styles: builtin() }
+    }
+}
+
+impl Imports {
+    // `imports` of templates manifest override keys of built-in styles or add new ones
+    pub fn load(discovered: &Discovered) -> Result<Self, Error> {
+        let mut imports = Self::default();
+
+        for path in &discovered.manifests {
+            let source = path.to_string_lossy().to_string();
+            let schema = Schema::load_url(path_to_url(source.clone())?)?;
+            let manifest: Manifest = serde_json::from_value(schema.get_body().clone())
+                .map_err(|e| Error::CodegenInvalidImports(format!("{}: {}", source, e)))?;
+
+            for (name, overrides) in manifest.imports {
+                let mut style = imports
+                    .styles
+                    .get(&name)
+                    .map(|s| serde_json::to_value(s).unwrap())
+                    .unwrap_or_else(|| json!({}));
+                style.as_object_mut().unwrap().extend(overrides);
+
+                let style = serde_json::from_value(style)
+                    .map_err(|e| Error::CodegenInvalidImports(format!("{}: {}", source, e)))?;
+                imports.styles.insert(name, style);
+            }
+        }
+
+        Ok(imports)
+    }
+
+    // file is relative to target directory, so is importing file
+    pub fn path(&self, style: &str, file: &str, from: Option<&str>) -> Result<String, Error> {
+        let style = self
+            .styles
+            .get(style)
+            .ok_or_else(|| Error::CodegenUnknownImportStyle(style.to_string()))?;
+
+        let file = Path::new(file);
+        let target = if style.directory {
+            file.parent().map(|p| p.to_path_buf()).unwrap_or_default()
+        } else {
+            file.with_extension("")
+        };
+
+        let mut parts = segments(&target);
+        if !style.directory
+            && parts.len() > 1
+            && style.index.iter().any(|i| Some(i) == parts.last())
+        {
+            parts.pop();
+        }
+
+        if style.relative {
+            let base = from
+                .and_then(|f| Path::new(f).parent())
+                .map(segments)
+                .unwrap_or_default();
+
+            let common = base.iter().zip(&parts).take_while(|(a, b)| a == b).count();
+
+            let mut result = match base.len() - common {
+                0 => vec![".".to_string()],
+                up => vec!["..".to_string(); up],
+            };
+            result.extend(parts[common..].iter().cloned());
+
+            return Ok(result.join(&style.separator));
+        }
+
+        let joined = format!("{}/", parts.join("/"));
+        let stripped = joined.strip_prefix(&style.strip).unwrap_or(&joined);
+
+        Ok(std::iter::once(style.prefix.as_str())
+            .chain(stripped.split('/'))
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(&style.separator))
+    }
+
+    // import_path(model="User", style="rust") or import_path(endpoint="listUsers", style="ts", from="src/api/users.ts")
+    pub fn register(self, tera: &mut Tera, symbols: Arc<RwLock<SymbolTable>>) {
+        tera.register_function("import_path", move |args: &HashMap<String, Value>| {
+            let arg = |name: &str| args.get(name).and_then(|v| v.as_str());
+
+            let style =
+                arg("style").ok_or_else(|| tera::Error::msg("import_path: style required"))?;
+
+            let symbols = symbols.read().unwrap();
+            let symbol = match (arg("model"), arg("endpoint")) {
+                (Some(name), _) => symbols.models.get(name).map(|s| (name, s)),
+                (None, Some(name)) => symbols.endpoints.get(name).map(|s| (name, s)),
+                (None, None) => {
+                    return Err(tera::Error::msg("import_path: model or endpoint required"))
+                }
+            };
+
+            match symbol {
+                Some((_, symbol)) => self
+                    .path(style, &symbol.file, arg("from"))
+                    .map(Value::String)
+                    .map_err(|e| tera::Error::msg(e.to_string())),
+                None => Err(tera::Error::msg(format!(
+                    "import_path: unknown symbol {:?}, is symbol table enabled with --symbols?",
+                    arg("model").or_else(|| arg("endpoint"))
+                ))),
+            }
+        });
+    }
+}
+
+fn segments(path: &Path) -> Vec<String> {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_paths() {
+        let mut imports = Imports::default();
+        imports.styles.get_mut("go").unwrap().prefix = "github.com/acme/api".to_string();
+
+        let path = |style, file, from| imports.path(style, file, from).unwrap();
+
+        assert_eq!(
+            path("rust", "src/models/common.rs", None),
+            "crate::models::common"
+        );
+        assert_eq!(path("rust", "src/models/mod.rs", None), "crate::models");
+        assert_eq!(
+            path("ts", "src/models/common.ts", Some("src/api/users.ts")),
+            "../models/common"
+        );
+        assert_eq!(
+            path("ts", "src/models/index.ts", Some("src/client.ts")),
+            "./models"
+        );
+        assert_eq!(
+            path("go", "models/user.go", None),
+            "github.com/acme/api/models"
+        );
+        assert_eq!(
+            path("python", "client/models/__init__.py", None),
+            "client.models"
+        );
+
+        assert!(matches!(
+            imports.path("cobol", "a", None),
+            Err(Error::CodegenUnknownImportStyle(_))
+        ));
+
+        let mut table = SymbolTable::default();
+        table.models.insert(
+            "User".to_string(),
+            super::super::symbols::Symbol {
+                file: "src/models/common.rs".to_string(),
+                module: "src/models/common".to_string(),
+            },
+        );
+
+        let mut tera = Tera::default();
+        imports.register(&mut tera, Arc::new(RwLock::new(table)));
+
+        let result = tera
+            .render_str(
+                "use {{ import_path(model=\"User\", style=\"rust\") }}::User;",
+                &tera::Context::new(),
+            )
+            .unwrap();
+        assert_eq!(result, "use crate::models::common::User;");
+        assert!(tera
+            .render_str(
+                "{{ import_path(model=\"Order\", style=\"rust\") }}",
+                &tera::Context::new()
+            )
+            .is_err());
+    }
+}
@@ -34,6 +34,7 @@ pub fn from_object_with_additional_properties(
                 name,
                 properties: vec![],
                 additional: true,
+                additional_properties: None,
             }))),
             _ => Err(Error::SchemaInvalidProperty(
                 "additionalProperties".to_string(),
@@ -43,6 +44,7 @@ pub fn from_object_with_additional_properties(
             name,
             properties: vec![],
             additional: true,
+            additional_properties: None,
         }))),
     }
 }
@@ -76,6 +78,7 @@ mod tests {
                 name: "TestName".to_string(),
                 properties: vec![],
                 additional: true,
+                additional_properties: None,
             }))
         );
     }
@@ -103,6 +106,7 @@ mod tests {
                 name: "TestName".to_string(),
                 properties: vec![],
                 additional: true,
+                additional_properties: None,
             }))
         );
     }
@@ -47,6 +47,7 @@ pub fn from_allof(
                     name: scope.namer().simple()?,
                     models: models?,
                     kind: WrapperTypeKind::AllOf,
+                    ..WrapperType::default()
                 })))
             }
             _ => Err(Error::SchemaInvalidProperty("allOf".to_string())),
@@ -57,7 +58,7 @@ pub fn from_allof(
 
 #[cfg(test)]
 mod tests {
-    use crate::codegen::jsonschema::types::FlatModel;
+    use crate::codegen::jsonschema::types::{Attributes, FlatModel};
 
     use super::*;
     use serde_json::json;
@@ -92,10 +93,15 @@ mod tests {
                     FlatModel {
                         name: Some("Variant1".to_string()),
                         type_: "number".to_string(),
+                        attributes: Attributes {
+                            numeric: Some("f64".to_string()),
+                            ..Attributes::default()
+                        },
                         ..FlatModel::default()
                     }
                 ],
                 kind: WrapperTypeKind::AllOf,
+                ..WrapperType::default()
             }))
         );
     }
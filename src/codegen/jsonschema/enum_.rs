@@ -1,16 +1,43 @@
 use serde_json::{Map, Value};
 
 use super::{
-    types::{EnumType, Model, ModelType},
+    types::{ConstType, EnumType, Model, ModelType, PrimitiveType},
     JsonSchemaExtractOptions,
 };
-use crate::scope::SchemaScope;
+use crate::{error::Error, scope::SchemaScope};
+
+// enum without type (common in 3.1 specs), type is guessed from values
+pub fn from_enum(
+    schema: &Map<String, Value>,
+    scope: &mut SchemaScope,
+    options: &JsonSchemaExtractOptions,
+) -> Result<Model, Error> {
+    let values = schema
+        .get("enum")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| Error::SchemaInvalidProperty("enum".to_string()))?;
+
+    let type_ = if values.iter().all(|v| v.is_string()) {
+        "string"
+    } else if values.iter().all(|v| v.is_number()) {
+        "number"
+    } else {
+        return Err(Error::SchemaInvalidProperty("enum".to_string()));
+    };
+
+    let model = Model::new(ModelType::PrimitiveType(PrimitiveType {
+        name: None,
+        type_: type_.to_string(),
+    }));
+
+    Ok(convert_to_enum(model, schema, scope, options))
+}
 
 pub fn convert_to_enum(
     model: Model,
     schema: &Map<String, Value>,
     scope: &mut SchemaScope,
-    _options: &JsonSchemaExtractOptions,
+    options: &JsonSchemaExtractOptions,
 ) -> Model {
     match schema.get("enum") {
         Some(value) => match value {
@@ -39,7 +66,21 @@ pub fn convert_to_enum(
                         }
                     }
 
-                    if !partitioned.0.is_empty() {
+                    if options.single_enum_as_const && values.len() == 1 {
+                        let (type_, value) = match partitioned {
+                            (s, _) if !s.is_empty() => ("string", s[0].clone()),
+                            (_, n) if !n.is_empty() => ("number", n[0].to_string()),
+                            _ => return Model::new(ModelType::PrimitiveType(primitive.clone())),
+                        };
+
+                        log::trace!("{}: single value enum folded to const", scope);
+
+                        Model::new(ModelType::ConstType(ConstType {
+                            name: name.unwrap(),
+                            type_: type_.to_string(),
+                            value,
+                        }))
+                    } else if !partitioned.0.is_empty() {
                         Model::new(ModelType::EnumType(EnumType {
                             name: name.unwrap(),
                             type_: "string".to_string(),
@@ -75,8 +116,6 @@ pub fn convert_to_enum(
 
 #[cfg(test)]
 mod tests {
-    use crate::codegen::jsonschema::types::PrimitiveType;
-
     use super::*;
     use serde_json::json;
 
@@ -123,4 +162,26 @@ mod tests {
 
         assert_eq!(result, model);
     }
+
+    #[test]
+    fn test_should_fold_single_value_enum_to_const() {
+        let schema = json!({"enum": ["only"]});
+        let mut scope = SchemaScope::default();
+        let options = JsonSchemaExtractOptions {
+            single_enum_as_const: true,
+            ..JsonSchemaExtractOptions::default()
+        };
+
+        scope.entity("TestName");
+        let result = from_enum(schema.as_object().unwrap(), &mut scope, &options);
+
+        assert_eq!(
+            result.unwrap(),
+            Model::new(ModelType::ConstType(ConstType {
+                name: "TestName".to_string(),
+                type_: "string".to_string(),
+                value: "only".to_string(),
+            }))
+        );
+    }
 }
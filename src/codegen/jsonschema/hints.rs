@@ -0,0 +1,135 @@
+use inflector::Inflector;
+use serde_json::Value;
+
+use super::types::FlatModel;
+
+// serde attributes of rust struct field, templates join them into #[serde(...)]
+pub fn property(model: &FlatModel) -> Vec<String> {
+    let mut hints = vec![];
+
+    if model.attributes.flatten {
+        hints.push("flatten".to_string());
+        return hints;
+    }
+
+    if let Some(name) = &model.name {
+        let identifier = model.attributes.alias.as_ref().unwrap_or(name);
+
+        if &identifier.to_snake_case() != name {
+            hints.push(format!("rename = {:?}", name));
+        }
+    }
+
+    if model.attributes.default.is_some() {
+        hints.push("default".to_string());
+    }
+
+    if !model.attributes.required {
+        hints.push("skip_serializing_if = \"Option::is_none\"".to_string());
+    }
+
+    hints
+}
+
+// variants discriminated by the same const property become internally tagged enum
+pub fn union(variants: &mut [FlatModel]) -> Vec<String> {
+    let discriminator = |v: &FlatModel| {
+        v.attributes
+            .x
+            .get("property")
+            .and_then(|p| p.as_str())
+            .map(|p| p.to_string())
+    };
+
+    let tag = match variants.first().and_then(discriminator) {
+        Some(tag)
+            if variants
+                .iter()
+                .all(|v| discriminator(v).as_ref() == Some(&tag)) =>
+        {
+            tag
+        }
+        _ => return vec!["untagged".to_string()],
+    };
+
+    for variant in variants.iter_mut() {
+        if let Some(Value::String(value)) = variant.attributes.x.get("value") {
+            variant.attributes.serde = vec![format!("rename = {:?}", value)];
+        }
+    }
+
+    vec![format!("tag = {:?}", tag)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::jsonschema::types::Attributes;
+    use serde_json::json;
+
+    #[test]
+    fn test_property_hints() {
+        let model = |name: &str, attributes: Attributes| FlatModel {
+            name: Some(name.to_string()),
+            type_: "string".to_string(),
+            attributes,
+            ..FlatModel::default()
+        };
+
+        assert_eq!(
+            property(&model("id", Attributes::default())),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            property(&model(
+                "userId",
+                Attributes {
+                    required: false,
+                    default: Some(json!("none")),
+                    ..Attributes::default()
+                }
+            )),
+            vec![
+                "rename = \"userId\"",
+                "default",
+                "skip_serializing_if = \"Option::is_none\""
+            ]
+        );
+        assert_eq!(
+            property(&model(
+                "größe",
+                Attributes {
+                    alias: Some("groesse".to_string()),
+                    ..Attributes::default()
+                }
+            )),
+            vec!["rename = \"größe\""]
+        );
+    }
+
+    #[test]
+    fn test_union_hints() {
+        let variant = |property: &str, value: &str| FlatModel {
+            attributes: Attributes {
+                x: vec![
+                    ("property".to_string(), json!(property)),
+                    ("value".to_string(), json!(value)),
+                ]
+                .into_iter()
+                .collect(),
+                ..Attributes::default()
+            },
+            ..FlatModel::default()
+        };
+
+        let mut variants = vec![variant("kind", "card"), variant("kind", "bank_transfer")];
+        assert_eq!(union(&mut variants), vec!["tag = \"kind\""]);
+        assert_eq!(
+            variants[1].attributes.serde,
+            vec!["rename = \"bank_transfer\""]
+        );
+
+        let mut variants = vec![variant("kind", "card"), variant("type", "transfer")];
+        assert_eq!(union(&mut variants), vec!["untagged"]);
+    }
+}
@@ -40,7 +40,7 @@ pub fn from_array(
 
 #[cfg(test)]
 mod tests {
-    use crate::codegen::jsonschema::types::FlatModel;
+    use crate::codegen::jsonschema::types::{Attributes, FlatModel};
 
     use super::*;
     use serde_json::json;
@@ -69,6 +69,10 @@ mod tests {
                 model: Box::new(FlatModel {
                     name: Some("TestName".to_string()),
                     type_: "number".to_string(),
+                    attributes: Attributes {
+                        numeric: Some("f64".to_string()),
+                        ..Attributes::default()
+                    },
                     ..FlatModel::default()
                 }),
             }))
@@ -2,14 +2,16 @@
 
 use std::collections::HashMap;
 
-use serde::{ser::SerializeStruct, Serialize};
+use serde::{ser::SerializeStruct, Deserialize, Serialize};
 use serde_json::{Map, Value};
 
 pub mod additionalproperties;
 pub mod allof;
 pub mod const_;
 pub mod enum_;
+pub mod hints;
 pub mod items;
+pub mod numeric;
 pub mod oneof;
 pub mod patternproperties;
 pub mod properties;
@@ -22,11 +24,32 @@ use crate::{
     storage::SchemaStorage, tools,
 };
 
+// constraints which generated types do not enforce
+pub static UNSUPPORTED_KEYWORDS: &[&str] = &[
+    "not",
+    "propertyNames",
+    "minProperties",
+    "maxProperties",
+    "dependentRequired",
+    "dependentSchemas",
+    "dependencies",
+    "if",
+    "then",
+    "else",
+    "contains",
+    "minContains",
+    "maxContains",
+    "multipleOf",
+    "unevaluatedProperties",
+    "unevaluatedItems",
+];
+
 #[derive(Clone)]
 pub struct ModelContainer {
     regexps: Vec<types::RegexpType>,
     formats: Vec<String>,
     models: Vec<types::Model>,
+    warnings: Vec<types::Warning>,
     mapping: HashMap<String, u32>,
     any: types::Model,
 }
@@ -36,20 +59,50 @@ impl Serialize for ModelContainer {
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_struct("container", 2)?;
+        let mut state = serializer.serialize_struct("container", 4)?;
         state.serialize_field("regexps", &self.regexps)?;
         state.serialize_field("formats", &self.formats)?;
         state.serialize_field("models", &self.models)?;
+        state.serialize_field("warnings", &self.warnings)?;
         state.end()
     }
 }
 
+// restores container of already extracted models, ex. from cache
+impl<'de> Deserialize<'de> for ModelContainer {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Serialized {
+            regexps: Vec<types::RegexpType>,
+            formats: Vec<String>,
+            models: Vec<types::Model>,
+
+            #[serde(default)]
+            warnings: Vec<types::Warning>,
+        }
+
+        let s = Serialized::deserialize(deserializer)?;
+
+        Ok(Self {
+            regexps: s.regexps,
+            formats: s.formats,
+            models: s.models,
+            warnings: s.warnings,
+            ..Self::default()
+        })
+    }
+}
+
 impl Default for ModelContainer {
     fn default() -> Self {
         Self {
             regexps: vec![],
             formats: vec![],
             models: vec![],
+            warnings: vec![],
             mapping: HashMap::new(),
             any: types::Model::new(types::ModelType::AnyType(types::AnyType {})),
         }
@@ -151,9 +204,69 @@ impl ModelContainer {
     pub fn formats(&self) -> &Vec<String> {
         &self.formats
     }
+
+    pub fn models(&self) -> &Vec<types::Model> {
+        &self.models
+    }
+
+    pub fn warnings(&self) -> &Vec<types::Warning> {
+        &self.warnings
+    }
+
+    // referenced schemas are extracted many times, each constraint is reported once
+    pub fn warn(&mut self, scope: &str, keyword: &str, value: &Value) {
+        if self
+            .warnings
+            .iter()
+            .any(|w| w.scope == scope && w.keyword == keyword)
+        {
+            return;
+        }
+
+        log::warn!("{}: {} is not enforced by generated code", scope, keyword);
+
+        self.warnings.push(types::Warning {
+            scope: scope.to_string(),
+            keyword: keyword.to_string(),
+            value: value.clone(),
+        });
+    }
+
+    // models used by exactly one tag belong to its module, remaining ones to common module
+    pub fn split_by_tag(&self, common: &str) -> Vec<(String, ModelContainer)> {
+        let tag_of = |model: &types::Model| {
+            let tags = model
+                .spaces
+                .list
+                .iter()
+                .filter_map(|s| match s {
+                    Space::Tag(tag) => Some(tag.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            match &tags[..] {
+                [tag] => tag.clone(),
+                _ => common.to_string(),
+            }
+        };
+
+        let mut tags = self.models.iter().map(tag_of).collect::<Vec<_>>();
+        tags.sort();
+        tags.dedup();
+
+        tags.into_iter()
+            .map(|tag| {
+                let mut container = self.clone();
+                container.models.retain(|m| tag_of(m) == tag);
+
+                (tag, container)
+            })
+            .collect()
+    }
 }
 
-#[derive(Default)]
+#[derive(Debug, Default)]
 pub struct JsonSchemaExtractOptions {
     pub wrappers: bool,
     pub nested_arrays_as_models: bool,
@@ -161,6 +274,21 @@ pub struct JsonSchemaExtractOptions {
     pub base_name: Option<String>,
     pub allow_list: bool,
     pub keep_schema: tools::Filter,
+    pub single_enum_as_const: bool,
+    pub extension_prefix: Option<String>,
+    pub inflections: crate::process::name::word::Inflections,
+    pub identifier_policy: crate::codegen::identifier::IdentifierPolicy,
+    pub decimal_formats: Vec<String>,
+    pub formats: crate::formats::Formats,
+}
+
+impl JsonSchemaExtractOptions {
+    // name forced by user with x-schematools-name extension
+    pub fn name_override(&self, data: &Map<String, Value>) -> Option<String> {
+        data.get(&tools::extension(&self.extension_prefix, "name"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    }
 }
 
 pub fn extract(
@@ -285,7 +413,6 @@ pub fn extract_type(
 
                                 // enum is mostly used for validation
                                 // only simple type enums can be used model building
-                                // todo: from_const
                                 Ok(enum_::convert_to_enum(model, schema, scope, options))
                             }
                             Value::Array(_) => extract_type(
@@ -310,6 +437,7 @@ pub fn extract_type(
                         .or_else(|_| {
                             const_::from_const(schema, container, scope, resolver, options)
                         })
+                        .or_else(|_| enum_::from_enum(schema, scope, options))
                         .or_else(|_| Ok(types::AnyType::model(schema, scope))),
                 };
 
@@ -327,7 +455,9 @@ pub fn extract_type(
                     with_spaces?,
                     schema,
                     container,
+                    &scope.path(),
                     options.keep_schema.check(node, false),
+                    options,
                 ))
             }
             _ => {
@@ -343,8 +473,19 @@ fn add_validation_and_nullable(
     model: types::Model,
     schema: &Map<String, Value>,
     mcontainer: &mut ModelContainer,
+    scope: &str,
     keep_schema: bool,
+    options: &JsonSchemaExtractOptions,
 ) -> types::Model {
+    let unsupported = schema
+        .iter()
+        .filter(|(key, _)| UNSUPPORTED_KEYWORDS.contains(&key.as_str()))
+        .map(|(key, val)| {
+            mcontainer.warn(scope, key, val);
+            (key.clone(), val.clone())
+        })
+        .collect::<HashMap<String, Value>>();
+
     if model.attributes.validation.is_some() {
         return model;
     }
@@ -425,6 +566,15 @@ fn add_validation_and_nullable(
         nullable,
         validation,
         x,
+        docs: crate::codegen::docs::Docs::new(schema),
+        xml: schema.get("xml").and_then(types::Xml::new),
+        unsupported,
+        constraints: types::Constraints::new(schema),
+        numeric: numeric::infer(schema, &options.decimal_formats),
+        format_kind: schema
+            .get("format")
+            .and_then(|f| f.as_str())
+            .and_then(|f| options.formats.kind(f)),
         schema: if keep_schema {
             Some(Value::Object(schema.clone()))
         } else {
@@ -688,4 +838,131 @@ mod tests {
             "Testing"
         );
     }
+
+    #[test]
+    fn test_unsupported_keywords_are_reported() {
+        let schema = Schema::from_json(json!({
+            "definitions": {
+                "code": {
+                    "type": "string",
+                    "not": {"const": "none"}
+                }
+            },
+            "title": "Order",
+            "type": "object",
+            "minProperties": 1,
+            "propertyNames": {"pattern": "^[a-z]+$"},
+            "properties": {
+                "first": {"$ref": "#/definitions/code"},
+                "second": {"$ref": "#/definitions/code"}
+            }
+        }));
+
+        let client = reqwest::blocking::Client::new();
+        let container = extract(
+            &schema,
+            &SchemaStorage::new(&schema, &client),
+            JsonSchemaExtractOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            container
+                .warnings()
+                .iter()
+                .map(|w| (w.scope.as_str(), w.keyword.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("/definitions/code", "not"),
+                ("/", "minProperties"),
+                ("/", "propertyNames"),
+            ]
+        );
+
+        let value = serde_json::to_value(container).unwrap();
+        assert_eq!(
+            value.pointer("/models/0/object/properties/0/unsupported"),
+            Some(&json!({"not": {"const": "none"}}))
+        );
+        assert_eq!(
+            value.pointer("/warnings/0/value"),
+            Some(&json!({"const": "none"}))
+        );
+    }
+
+    #[test]
+    fn test_constraints_are_normalized() {
+        let schema = Schema::from_json(json!({
+            "title": "Product",
+            "type": "object",
+            "properties": {
+                "price": {
+                    "type": "number",
+                    "minimum": 0,
+                    "exclusiveMinimum": true,
+                    "exclusiveMaximum": 1000,
+                    "multipleOf": 0.01
+                },
+                "code": {"type": "string", "pattern": "^[A-Z]+$", "minLength": 2},
+                "tags": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "maxItems": 5,
+                    "uniqueItems": true
+                },
+                "name": {"type": "string"},
+                "quantity": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "exclusiveMinimum": 0,
+                    "maximum": 10,
+                    "exclusiveMaximum": 10
+                }
+            }
+        }));
+
+        let client = reqwest::blocking::Client::new();
+        let container = extract(
+            &schema,
+            &SchemaStorage::new(&schema, &client),
+            JsonSchemaExtractOptions::default(),
+        )
+        .unwrap();
+
+        let value = serde_json::to_value(container).unwrap();
+        let constraints = |i: usize| {
+            value
+                .pointer(&format!("/models/0/object/properties/{}/constraints", i))
+                .cloned()
+        };
+
+        assert_eq!(
+            constraints(0),
+            Some(json!({
+                "minimum": {"value": 0, "exclusive": true},
+                "maximum": {"value": 1000, "exclusive": true},
+                "multiple_of": 0.01,
+                "pattern": null,
+                "min_length": null,
+                "max_length": null,
+                "min_items": null,
+                "max_items": null,
+                "unique_items": false
+            }))
+        );
+        assert_eq!(
+            constraints(1).unwrap().get("pattern"),
+            Some(&json!("^[A-Z]+$"))
+        );
+        assert_eq!(
+            constraints(2).unwrap().get("unique_items"),
+            Some(&json!(true))
+        );
+        assert_eq!(constraints(3), Some(Value::Null));
+
+        // stricter of numeric bounds is kept
+        let quantity = constraints(4).unwrap();
+        assert_eq!(quantity["minimum"], json!({"value": 1, "exclusive": false}));
+        assert_eq!(quantity["maximum"], json!({"value": 10, "exclusive": true}));
+    }
 }
@@ -0,0 +1,95 @@
+use serde_json::{Map, Value};
+
+// concrete type of integer or number: i32, i64, u64, f32, f64 or decimal
+pub fn infer(schema: &Map<String, Value>, decimal_formats: &[String]) -> Option<String> {
+    let type_ = schema
+        .get("type")
+        .and_then(|t| t.as_str())
+        .filter(|t| *t == "integer" || *t == "number")?;
+    let format = schema.get("format").and_then(|f| f.as_str());
+
+    // money like formats lose precision as floats
+    if let Some(format) = format {
+        if format == "decimal" || decimal_formats.iter().any(|f| f == format) {
+            return Some("decimal".to_string());
+        }
+    }
+
+    let number = |key: &str| schema.get(key).and_then(|v| v.as_f64());
+    let inferred = if type_ == "integer" {
+        match format {
+            Some("int32") => "i32",
+            Some("int64") => "i64",
+            Some("uint32") | Some("uint64") => "u64",
+            _ => integer(number("minimum"), number("maximum")),
+        }
+    } else {
+        match (format, number("multipleOf")) {
+            (_, Some(step)) if step.fract() != 0.0 => "decimal",
+            (_, Some(_)) => integer(number("minimum"), number("maximum")),
+            (Some("float"), None) => "f32",
+            _ => "f64",
+        }
+    };
+
+    Some(inferred.to_string())
+}
+
+// smallest type holding both bounds, unbounded values are i64 (u64 when not negative)
+fn integer(minimum: Option<f64>, maximum: Option<f64>) -> &'static str {
+    let fits = |min: f64, max: f64| {
+        minimum.map(|v| v >= min).unwrap_or(false) && maximum.map(|v| v <= max).unwrap_or(false)
+    };
+
+    if fits(i32::MIN as f64, i32::MAX as f64) {
+        "i32"
+    } else if fits(i64::MIN as f64, i64::MAX as f64) {
+        "i64"
+    } else if minimum.map(|v| v >= 0.0).unwrap_or(false) {
+        "u64"
+    } else {
+        "i64"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_infer_numeric_types() {
+        let infer = |schema: Value| {
+            super::infer(schema.as_object().unwrap(), &["money".to_string()]).unwrap_or_default()
+        };
+
+        assert_eq!(infer(json!({"type": "integer", "format": "int32"})), "i32");
+        assert_eq!(infer(json!({"type": "integer", "format": "int64"})), "i64");
+        assert_eq!(infer(json!({"type": "integer"})), "i64");
+        assert_eq!(
+            infer(json!({"type": "integer", "minimum": 0, "maximum": 100})),
+            "i32"
+        );
+        assert_eq!(
+            infer(json!({"type": "integer", "minimum": -1, "maximum": 3000000000u64})),
+            "i64"
+        );
+        assert_eq!(infer(json!({"type": "integer", "minimum": 0})), "u64");
+        assert_eq!(infer(json!({"type": "number"})), "f64");
+        assert_eq!(infer(json!({"type": "number", "format": "float"})), "f32");
+        assert_eq!(
+            infer(json!({"type": "number", "multipleOf": 0.01})),
+            "decimal"
+        );
+        assert_eq!(
+            infer(json!({"type": "number", "format": "money"})),
+            "decimal"
+        );
+        assert_eq!(infer(json!({"type": "string", "format": "money"})), "");
+        assert_eq!(
+            infer(json!({"type": "number", "format": "decimal"})),
+            "decimal"
+        );
+        assert_eq!(infer(json!({"type": "string"})), "");
+    }
+}
@@ -65,10 +65,14 @@ pub fn from_oneof(
 
                 scope.pop();
 
+                let mut models = models?;
+                let serde = super::hints::union(&mut models);
+
                 // todo: wrapper to only flattened
                 Ok(Model::new(ModelType::WrapperType(WrapperType {
                     name: scope.namer().decorate(vec!["Variant".to_string()]),
-                    models: models?,
+                    models,
+                    serde,
                     ..WrapperType::default()
                 })))
             }
@@ -135,7 +139,9 @@ fn simplify_one_of(
                             m,
                             node.as_object().unwrap(),
                             container,
+                            &scope.path(),
                             options.keep_schema.check(node, false),
+                            options,
                         )
                         .with_attributes(&attributes)
                     }),
@@ -149,6 +155,7 @@ fn simplify_one_of(
 mod tests {
     use std::collections::HashMap;
 
+    use crate::codegen::docs::Docs;
     use crate::codegen::jsonschema::types::{Attributes, FlatModel, ObjectType};
 
     use super::*;
@@ -198,6 +205,10 @@ mod tests {
                             .iter()
                             .cloned()
                             .collect::<HashMap<String, Value>>(),
+                            docs: Docs {
+                                title: Some("a".to_string()),
+                                ..Docs::default()
+                            },
                             reference: true,
                             ..Attributes::default()
                         },
@@ -220,6 +231,10 @@ mod tests {
                             .iter()
                             .cloned()
                             .collect::<HashMap<String, Value>>(),
+                            docs: Docs {
+                                title: Some("b".to_string()),
+                                ..Docs::default()
+                            },
                             reference: true,
                             ..Attributes::default()
                         },
@@ -227,6 +242,7 @@ mod tests {
                         ..FlatModel::default()
                     }
                 ],
+                serde: vec!["untagged".to_string()],
                 ..WrapperType::default()
             }))
         );
@@ -276,6 +292,11 @@ mod tests {
                             .iter()
                             .cloned()
                             .collect::<HashMap<String, Value>>(),
+                            serde: vec!["rename = \"value1\"".to_string()],
+                            docs: Docs {
+                                title: Some("a".to_string()),
+                                ..Docs::default()
+                            },
                             reference: true,
                             ..Attributes::default()
                         },
@@ -298,6 +319,11 @@ mod tests {
                             .iter()
                             .cloned()
                             .collect::<HashMap<String, Value>>(),
+                            serde: vec!["rename = \"value2\"".to_string()],
+                            docs: Docs {
+                                title: Some("b".to_string()),
+                                ..Docs::default()
+                            },
                             reference: true,
                             ..Attributes::default()
                         },
@@ -305,6 +331,7 @@ mod tests {
                         ..FlatModel::default()
                     }
                 ],
+                serde: vec!["tag = \"type\"".to_string()],
                 ..WrapperType::default()
             }))
         );
@@ -336,6 +363,7 @@ mod tests {
                     type_: "string".to_string(),
                     attributes: Attributes {
                         required: false,
+                        serde: vec!["skip_serializing_if = \"Option::is_none\"".to_string()],
                         ..Attributes::default()
                     },
                     ..FlatModel::default()
@@ -380,9 +408,14 @@ mod tests {
                     FlatModel {
                         name: Some("Variant1".to_string()),
                         type_: "number".to_string(),
+                        attributes: Attributes {
+                            numeric: Some("f64".to_string()),
+                            ..Attributes::default()
+                        },
                         ..FlatModel::default()
                     }
                 ],
+                serde: vec!["untagged".to_string()],
                 ..WrapperType::default()
             }))
         );
@@ -4,7 +4,7 @@ use super::{
 };
 use crate::{
     codegen::jsonschema::types::Attributes, error::Error, resolver::SchemaResolver,
-    scope::SchemaScope,
+    scope::SchemaScope, tools,
 };
 use serde_json::{Map, Value};
 
@@ -25,7 +25,23 @@ pub fn from_object_with_properties(
             let properties = props
                 .iter()
                 .map(|(name, property)| {
-                    scope.property(name);
+                    // original name is kept for serialization
+                    let identifier = options.identifier_policy.apply(name)?;
+
+                    scope.property(&identifier);
+
+                    // name forced on property renames field, not type of its value
+                    let alias = property.as_object().and_then(|p| options.name_override(p));
+                    let stripped;
+                    let property = match property {
+                        Value::Object(p) if alias.is_some() => {
+                            let mut p = p.clone();
+                            p.remove(&tools::extension(&options.extension_prefix, "name"));
+                            stripped = Value::Object(p);
+                            &stripped
+                        }
+                        _ => property,
+                    };
 
                     let mut model =
                         super::extract_type(property, container, scope, resolver, options)
@@ -38,7 +54,20 @@ pub fn from_object_with_properties(
                     model.name = Some(name.clone());
                     model.attributes.required = required.contains(name);
 
-                    let model = if model.attributes.nullable
+                    model.attributes.alias = alias;
+
+                    if let Value::Object(p) = property {
+                        // xml hints of property win over hints of referenced schema
+                        if let Some(xml) = p.get("xml").and_then(super::types::Xml::new) {
+                            model.attributes.xml = Some(xml);
+                        }
+                    }
+
+                    if model.attributes.alias.is_none() && &identifier != name {
+                        model.attributes.alias = Some(identifier);
+                    }
+
+                    let mut model = if model.attributes.nullable
                         && !model.attributes.required
                         && options.optional_and_nullable_as_models
                     {
@@ -52,6 +81,8 @@ pub fn from_object_with_properties(
                         model
                     };
 
+                    model.attributes.serde = super::hints::property(&model);
+
                     scope.pop();
 
                     Ok(model)
@@ -60,6 +91,13 @@ pub fn from_object_with_properties(
 
             scope.pop();
 
+            let additional_properties = match schema.get("additionalProperties") {
+                Some(additional @ Value::Object(_)) => Some(additional_properties(
+                    additional, container, scope, resolver, options,
+                )?),
+                _ => None,
+            };
+
             Ok(Model::new(ModelType::ObjectType(ObjectType {
                 name,
                 properties,
@@ -70,12 +108,43 @@ pub fn from_object_with_properties(
                         _ => true,
                     })
                     .unwrap_or(true),
+                additional_properties,
             })))
         }
         _ => Err(Error::SchemaInvalidProperty("properties".to_string())),
     }
 }
 
+// map of properties not listed in properties, flattened into object next to them
+fn additional_properties(
+    node: &Value,
+    container: &mut ModelContainer,
+    scope: &mut SchemaScope,
+    resolver: &SchemaResolver,
+    options: &JsonSchemaExtractOptions,
+) -> Result<FlatModel, Error> {
+    scope.form("additionalProperties");
+    let model = super::extract_type(node, container, scope, resolver, options)
+        .and_then(|s| s.flatten(container, scope));
+    scope.pop();
+
+    let model = model?;
+    let mut map = FlatModel {
+        name: Some("additionalProperties".to_string()),
+        type_: "map".to_string(),
+        original: model.original,
+        model: Some(Box::new(model)),
+        attributes: Attributes {
+            flatten: true,
+            ..Attributes::default()
+        },
+        ..FlatModel::default()
+    };
+    map.attributes.serde = super::hints::property(&map);
+
+    Ok(map)
+}
+
 fn convert_to_nullable_optional_wrapper(
     mut model: FlatModel,
     container: &mut ModelContainer,
@@ -168,13 +237,16 @@ mod tests {
                         name: Some("b".to_string()),
                         type_: "number".to_string(),
                         attributes: Attributes {
+                            numeric: Some("f64".to_string()),
                             required: false,
+                            serde: vec!["skip_serializing_if = \"Option::is_none\"".to_string()],
                             ..Attributes::default()
                         },
                         ..FlatModel::default()
                     }
                 ],
                 additional: true,
+                additional_properties: None,
             }))
         );
     }
@@ -218,13 +290,16 @@ mod tests {
                         name: Some("b".to_string()),
                         type_: "number".to_string(),
                         attributes: Attributes {
+                            numeric: Some("f64".to_string()),
                             required: false,
+                            serde: vec!["skip_serializing_if = \"Option::is_none\"".to_string()],
                             ..Attributes::default()
                         },
                         ..FlatModel::default()
                     }
                 ],
                 additional: false,
+                additional_properties: None,
             }))
         );
     }
@@ -267,13 +342,16 @@ mod tests {
                         name: Some("b".to_string()),
                         type_: "number".to_string(),
                         attributes: Attributes {
+                            numeric: Some("f64".to_string()),
                             required: false,
+                            serde: vec!["skip_serializing_if = \"Option::is_none\"".to_string()],
                             ..Attributes::default()
                         },
                         ..FlatModel::default()
                     }
                 ],
                 additional: true,
+                additional_properties: None,
             }))
         );
     }
@@ -315,6 +393,8 @@ mod tests {
                         type_: "string".to_string(),
                         attributes: Attributes {
                             required: true,
+                            // identifier of snakecase filter is property_1
+                            serde: vec!["rename = \"property1\"".to_string()],
                             ..Attributes::default()
                         },
                         ..FlatModel::default()
@@ -325,18 +405,27 @@ mod tests {
                         model: Some(Box::new(FlatModel {
                             name: Some("TestNameProperty2Optional".to_string()),
                             type_: "number".to_string(),
+                            attributes: Attributes {
+                                numeric: Some("f64".to_string()),
+                                ..Attributes::default()
+                            },
                             ..FlatModel::default()
                         })),
                         original: Some(0),
                         attributes: Attributes {
                             required: false,
                             nullable: false,
+                            serde: vec![
+                                "rename = \"property2\"".to_string(),
+                                "skip_serializing_if = \"Option::is_none\"".to_string()
+                            ],
                             ..Attributes::default()
                         },
                         ..FlatModel::default()
                     }
                 ],
                 additional: true,
+                additional_properties: None,
             }))
         );
 
@@ -353,6 +442,10 @@ mod tests {
                     model: FlatModel {
                         name: Some("property2".to_string()),
                         type_: "number".to_string(),
+                        attributes: Attributes {
+                            numeric: Some("f64".to_string()),
+                            ..Attributes::default()
+                        },
                         ..FlatModel::default()
                     }
                 }
@@ -395,6 +488,7 @@ mod tests {
                     type_: "number".to_string(),
                     model: None,
                     attributes: Attributes {
+                        numeric: Some("f64".to_string()),
                         required: true,
                         ..Attributes::default()
                     },
@@ -410,4 +504,81 @@ mod tests {
             }))
         );
     }
+
+    #[test]
+    fn test_should_flatten_additional_properties_schema() {
+        let schema = json!({
+            "required": ["a"],
+            "properties": {
+                "a": { "type": "string"}
+            },
+            "additionalProperties": { "type": "number" },
+        });
+
+        let mut container = ModelContainer::default();
+        let mut scope = SchemaScope::default();
+        let resolver = SchemaResolver::empty();
+        let options = JsonSchemaExtractOptions::default();
+
+        scope.entity("TestName");
+        let result = from_object_with_properties(
+            schema.as_object().unwrap(),
+            &mut container,
+            &mut scope,
+            &resolver,
+            &options,
+        )
+        .unwrap();
+
+        match result.inner() {
+            ModelType::ObjectType(o) => {
+                let additional = o.additional_properties.as_ref().unwrap();
+
+                assert_eq!(o.properties.len(), 1);
+                assert_eq!(additional.type_, "map");
+                assert_eq!(additional.model.as_ref().unwrap().type_, "number");
+                assert_eq!(additional.attributes.serde, vec!["flatten"]);
+            }
+            _ => panic!("object expected"),
+        }
+    }
+
+    #[test]
+    fn test_property_name_override_sets_alias_only() {
+        let schema = json!({
+            "properties": {
+                "user": {
+                    "type": "object",
+                    "x-schematools-name": "owner",
+                    "properties": { "id": { "type": "string" } }
+                }
+            }
+        });
+
+        let mut container = ModelContainer::default();
+        let mut scope = SchemaScope::default();
+        let resolver = SchemaResolver::empty();
+        let options = JsonSchemaExtractOptions::default();
+
+        scope.entity("TestName");
+        let result = from_object_with_properties(
+            schema.as_object().unwrap(),
+            &mut container,
+            &mut scope,
+            &resolver,
+            &options,
+        )
+        .unwrap();
+
+        match result.inner() {
+            ModelType::ObjectType(o) => {
+                let user = &o.properties[0];
+
+                assert_eq!(user.name, Some("user".to_string()));
+                assert_eq!(user.attributes.alias, Some("owner".to_string()));
+                assert_eq!(user.model.as_ref().unwrap().type_, "TestNameUser");
+            }
+            _ => panic!("object expected"),
+        }
+    }
 }
@@ -6,11 +6,17 @@ use super::JsonSchemaExtractOptions;
 pub fn extract_title(
     data: &Map<String, Value>,
     scope: &mut SchemaScope,
-    _options: &JsonSchemaExtractOptions,
+    options: &JsonSchemaExtractOptions,
 ) -> Result<String, Error> {
+    if let Some(name) = options.name_override(data) {
+        return Ok(name);
+    }
+
     match data.get("title") {
         Some(v) => match v {
-            Value::String(title) => Ok(scope.namer().convert(title)),
+            Value::String(title) => Ok(scope
+                .namer()
+                .convert(&options.identifier_policy.apply(title)?)),
             _ => {
                 log::error!("{}: Incorrect format of title", scope);
 
@@ -54,4 +60,18 @@ mod tests {
 
         assert_eq!(result.unwrap(), "MySecretTitle".to_string());
     }
+
+    #[test]
+    fn test_should_return_name_override() {
+        let data = json!({"title": "MyTitle", "x-schematools-name": "Custom"});
+        let scope = &mut SchemaScope::default();
+
+        let result = extract_title(
+            data.as_object().unwrap(),
+            scope,
+            &JsonSchemaExtractOptions::default(),
+        );
+
+        assert_eq!(result.unwrap(), "Custom".to_string());
+    }
 }
@@ -1,11 +1,11 @@
-use serde::{ser::SerializeStruct, Serialize};
-use serde_json::{Map, Value};
+use serde::{ser::SerializeStruct, Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
 
 use crate::{error::Error, resolver::SchemaResolver, scope::SchemaScope, scope::Space};
 
 use super::{title, JsonSchemaExtractOptions, ModelContainer};
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Model {
     #[serde(flatten)]
     inner: ModelType,
@@ -41,7 +41,7 @@ impl Model {
     }
 }
 
-#[derive(Debug, Serialize, Clone, Eq, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
 pub enum ModelType {
     // common types
     #[serde(rename = "primitive")]
@@ -73,7 +73,7 @@ pub enum ModelType {
     MapType(MapType),
 
     // flat type
-    #[serde(skip_serializing)]
+    #[serde(skip)]
     FlatModel(FlatModel),
 }
 
@@ -94,7 +94,7 @@ impl From<&FlatModel> for String {
     }
 }
 
-#[derive(Debug, Eq, Serialize, Clone, Default)]
+#[derive(Debug, Eq, Serialize, Deserialize, Clone, Default)]
 pub struct SpacesContainer {
     #[serde(rename = "spaces")]
     pub list: Vec<Space>,
@@ -117,7 +117,7 @@ impl SpacesContainer {
     }
 }
 
-#[derive(Debug, Serialize, Clone, Eq, PartialEq, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Default)]
 pub struct PrimitiveType {
     #[serde(rename = "name")]
     pub name: Option<String>,
@@ -126,14 +126,17 @@ pub struct PrimitiveType {
     pub type_: String,
 }
 
-#[derive(Debug, Serialize, Clone, Eq, PartialEq, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Default)]
 pub struct ObjectType {
     pub name: String,
     pub properties: Vec<FlatModel>,
     pub additional: bool,
+
+    // schema of additionalProperties next to properties, map flattened into object
+    pub additional_properties: Option<FlatModel>,
 }
 
-#[derive(Debug, Serialize, Clone, Eq, PartialEq, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Default)]
 pub struct ArrayType {
     #[serde(rename = "name")]
     pub name: Option<String>,
@@ -142,7 +145,7 @@ pub struct ArrayType {
     pub model: Box<FlatModel>,
 }
 
-#[derive(Debug, Serialize, Clone, Eq, PartialEq, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Default)]
 pub struct EnumType {
     #[serde(rename = "name")]
     pub name: String,
@@ -154,7 +157,7 @@ pub struct EnumType {
     pub variants: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Clone, Eq, PartialEq, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Default)]
 pub struct ConstType {
     #[serde(rename = "name")]
     pub name: String,
@@ -166,16 +169,16 @@ pub struct ConstType {
     pub value: String,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Default, Serialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
 pub struct MapType {
     pub name: Option<String>,
     pub model: Box<FlatModel>,
 }
 
-#[derive(Debug, Serialize, Clone, Eq, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
 pub struct AnyType {}
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RegexpType {
     #[serde(rename = "name")]
     pub name: String,
@@ -184,7 +187,7 @@ pub struct RegexpType {
     pub pattern: String,
 }
 
-#[derive(Debug, Serialize, Clone, Eq, PartialEq, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Default)]
 pub struct WrapperType {
     #[serde(rename = "name")]
     pub name: String,
@@ -194,9 +197,13 @@ pub struct WrapperType {
 
     #[serde(rename = "kind")]
     pub kind: WrapperTypeKind,
+
+    // serde attributes of rust enum (tag or untagged) when kind is oneOf
+    #[serde(rename = "serde", default)]
+    pub serde: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Eq, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub enum WrapperTypeKind {
     AllOf,
     OneOf,
@@ -208,7 +215,7 @@ impl Default for WrapperTypeKind {
     }
 }
 
-#[derive(Debug, Serialize, Clone, Eq, PartialEq, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Default)]
 pub struct NullableOptionalWrapperType {
     #[serde(rename = "name")]
     pub name: String,
@@ -217,7 +224,7 @@ pub struct NullableOptionalWrapperType {
     pub model: FlatModel,
 }
 
-#[derive(Debug, Serialize, Clone, Eq, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
 pub struct Attributes {
     #[serde(rename = "description")]
     pub description: Option<String>,
@@ -242,6 +249,155 @@ pub struct Attributes {
 
     #[serde(rename = "x")]
     pub x: std::collections::HashMap<String, Value>,
+
+    #[serde(rename = "docs")]
+    pub docs: crate::codegen::docs::Docs,
+
+    // identifier used in generated code when different than serialized name
+    #[serde(rename = "alias")]
+    pub alias: Option<String>,
+
+    #[serde(rename = "xml")]
+    pub xml: Option<Xml>,
+
+    // raw constraints which generated types do not enforce
+    #[serde(rename = "unsupported", default)]
+    pub unsupported: std::collections::HashMap<String, Value>,
+
+    #[serde(rename = "constraints", default)]
+    pub constraints: Option<Constraints>,
+
+    // serde attributes of rust field, ready to join
+    #[serde(rename = "serde", default)]
+    pub serde: Vec<String>,
+
+    // fields of value are merged into parent, ex. additionalProperties next to properties
+    #[serde(rename = "flatten", default)]
+    pub flatten: bool,
+
+    // concrete type of integer or number (i32, i64, u64, f32, f64, decimal)
+    #[serde(rename = "numeric", default)]
+    pub numeric: Option<String>,
+
+    // semantic kind of string format from registry (ex. uuid, email, iban)
+    #[serde(rename = "format_kind", default)]
+    pub format_kind: Option<String>,
+}
+
+// validation keywords in one shape for all drafts, used to generate runtime validators
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Default)]
+pub struct Constraints {
+    pub minimum: Option<Bound>,
+    pub maximum: Option<Bound>,
+    pub multiple_of: Option<serde_json::Number>,
+    pub pattern: Option<String>,
+    pub min_length: Option<u64>,
+    pub max_length: Option<u64>,
+    pub min_items: Option<u64>,
+    pub max_items: Option<u64>,
+    pub unique_items: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct Bound {
+    pub value: serde_json::Number,
+    pub exclusive: bool,
+}
+
+impl Constraints {
+    pub fn new(schema: &Map<String, Value>) -> Option<Self> {
+        let number = |key: &str| match schema.get(key) {
+            Some(Value::Number(n)) => Some(n.clone()),
+            _ => None,
+        };
+        let unsigned = |key: &str| schema.get(key).and_then(|v| v.as_u64());
+
+        // draft 4 and openapi 3.0 use boolean exclusive flags, draft 6+ numbers,
+        // when both numeric bounds are given the stricter one applies
+        let bound = |inclusive: &str, exclusive: &str, lower: bool| match schema.get(exclusive) {
+            Some(Value::Number(n)) => {
+                let stricter = |value: &Number| match (value.as_f64(), n.as_f64()) {
+                    (Some(a), Some(b)) if lower => a > b,
+                    (Some(a), Some(b)) => a < b,
+                    _ => false,
+                };
+
+                Some(match number(inclusive) {
+                    Some(value) if stricter(&value) => Bound {
+                        value,
+                        exclusive: false,
+                    },
+                    _ => Bound {
+                        value: n.clone(),
+                        exclusive: true,
+                    },
+                })
+            }
+            Some(Value::Bool(flag)) => number(inclusive).map(|value| Bound {
+                value,
+                exclusive: *flag,
+            }),
+            _ => number(inclusive).map(|value| Bound {
+                value,
+                exclusive: false,
+            }),
+        };
+
+        let constraints = Self {
+            minimum: bound("minimum", "exclusiveMinimum", true),
+            maximum: bound("maximum", "exclusiveMaximum", false),
+            multiple_of: number("multipleOf"),
+            pattern: schema
+                .get("pattern")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            min_length: unsigned("minLength"),
+            max_length: unsigned("maxLength"),
+            min_items: unsigned("minItems"),
+            max_items: unsigned("maxItems"),
+            unique_items: schema.get("uniqueItems") == Some(&Value::Bool(true)),
+        };
+
+        if constraints == Self::default() {
+            None
+        } else {
+            Some(constraints)
+        }
+    }
+}
+
+// constraint keyword of schema not enforced by generated types
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Warning {
+    pub scope: String,
+    pub keyword: String,
+    pub value: Value,
+}
+
+// xml serialization hints of openapi schema object
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Default)]
+pub struct Xml {
+    pub name: Option<String>,
+    pub namespace: Option<String>,
+    pub prefix: Option<String>,
+    pub attribute: bool,
+    pub wrapped: bool,
+}
+
+impl Xml {
+    pub fn new(node: &Value) -> Option<Self> {
+        let map = node.as_object()?;
+        let string = |key: &str| map.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+        let flag = |key: &str| map.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+
+        Some(Self {
+            name: string("name"),
+            namespace: string("namespace"),
+            prefix: string("prefix"),
+            attribute: flag("attribute"),
+            wrapped: flag("wrapped"),
+        })
+    }
 }
 
 impl Model {
@@ -253,7 +409,12 @@ impl Model {
             ModelType::MapType(s) => {
                 vec![s.model.original]
             }
-            ModelType::ObjectType(o) => o.properties.iter().map(|p| p.original).collect(),
+            ModelType::ObjectType(o) => o
+                .properties
+                .iter()
+                .chain(o.additional_properties.iter())
+                .map(|p| p.original)
+                .collect(),
             ModelType::WrapperType(w) => w.models.iter().map(|p| p.original).collect(),
             ModelType::NullableOptionalWrapperType(s) => {
                 vec![s.model.original]
@@ -395,7 +556,7 @@ impl Serialize for FlatModel {
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_struct("FlattenedType", 9)?;
+        let mut state = serializer.serialize_struct("FlattenedType", 18)?;
         state.serialize_field("name", &self.name)?;
         state.serialize_field("type", &self.type_)?;
         state.serialize_field("model", &self.model)?;
@@ -405,11 +566,84 @@ impl Serialize for FlatModel {
         state.serialize_field("x", &self.attributes.x)?;
         state.serialize_field("description", &self.attributes.description)?;
         state.serialize_field("default", &self.attributes.default)?;
+        state.serialize_field("docs", &self.attributes.docs)?;
+        state.serialize_field("alias", &self.attributes.alias)?;
+        state.serialize_field("xml", &self.attributes.xml)?;
+        state.serialize_field("unsupported", &self.attributes.unsupported)?;
+        state.serialize_field("constraints", &self.attributes.constraints)?;
+        state.serialize_field("serde", &self.attributes.serde)?;
+        state.serialize_field("flatten", &self.attributes.flatten)?;
+        state.serialize_field("numeric", &self.attributes.numeric)?;
+        state.serialize_field("format_kind", &self.attributes.format_kind)?;
         // state.serialize_field("spaces", &self.spaces)?; // todo: ???
         state.end()
     }
 }
 
+// counterpart of serialization, fields used only during extraction are not restored
+#[derive(Deserialize)]
+struct SerializedFlatModel {
+    name: Option<String>,
+    #[serde(rename = "type")]
+    type_: String,
+    model: Option<Box<FlatModel>>,
+    required: bool,
+    nullable: bool,
+    validation: Option<std::collections::HashMap<String, Value>>,
+    x: std::collections::HashMap<String, Value>,
+    description: Option<String>,
+    default: Option<Value>,
+    docs: crate::codegen::docs::Docs,
+    alias: Option<String>,
+    xml: Option<Xml>,
+    #[serde(default)]
+    unsupported: std::collections::HashMap<String, Value>,
+    #[serde(default)]
+    constraints: Option<Constraints>,
+    #[serde(default)]
+    serde: Vec<String>,
+    #[serde(default)]
+    flatten: bool,
+    #[serde(default)]
+    numeric: Option<String>,
+    #[serde(default)]
+    format_kind: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for FlatModel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = SerializedFlatModel::deserialize(deserializer)?;
+
+        Ok(Self {
+            name: s.name,
+            type_: s.type_,
+            model: s.model,
+            attributes: Attributes {
+                required: s.required,
+                nullable: s.nullable,
+                validation: s.validation,
+                x: s.x,
+                description: s.description,
+                default: s.default,
+                docs: s.docs,
+                alias: s.alias,
+                xml: s.xml,
+                unsupported: s.unsupported,
+                constraints: s.constraints,
+                serde: s.serde,
+                flatten: s.flatten,
+                numeric: s.numeric,
+                format_kind: s.format_kind,
+                ..Attributes::default()
+            },
+            ..Self::default()
+        })
+    }
+}
+
 impl Default for FlatModel {
     fn default() -> Self {
         Self {
@@ -568,6 +802,15 @@ impl Default for Attributes {
             reference: false,
             schema: None,
             x: std::collections::HashMap::new(),
+            docs: crate::codegen::docs::Docs::default(),
+            alias: None,
+            xml: None,
+            unsupported: std::collections::HashMap::new(),
+            constraints: None,
+            serde: vec![],
+            flatten: false,
+            numeric: None,
+            format_kind: None,
         }
     }
 }
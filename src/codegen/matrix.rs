@@ -0,0 +1,95 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::error::Error;
+use crate::schema::Schema;
+
+// single output of codegen matrix declared in project config
+#[derive(Debug, Deserialize, Clone)]
+pub struct Target {
+    pub template: Vec<String>,
+    pub target_dir: String,
+
+    #[serde(default)]
+    pub profile: Vec<String>,
+
+    #[serde(default)]
+    pub options: Map<String, Value>,
+
+    pub format: Option<String>,
+}
+
+impl Target {
+    pub fn options(&self) -> Vec<(String, Value)> {
+        self.options
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+}
+
+// targets in order of declaration, all of them when none is selected
+pub fn targets(config: &Schema, selected: &[String]) -> Result<Vec<(String, Target)>, Error> {
+    let declared = match config.get_body().get("targets") {
+        Some(Value::Object(targets)) => targets.clone(),
+        Some(_) => {
+            return Err(Error::CodegenInvalidProfiles(
+                "targets has to be an object".to_string(),
+            ))
+        }
+        None => Map::new(),
+    };
+
+    for name in selected {
+        if !declared.contains_key(name) {
+            return Err(Error::CodegenUnknownTarget(name.clone()));
+        }
+    }
+
+    declared
+        .into_iter()
+        .filter(|(name, _)| selected.is_empty() || selected.contains(name))
+        .map(|(name, target)| {
+            serde_json::from_value::<Target>(target)
+                .map(|t| (name.clone(), t))
+                .map_err(|e| Error::CodegenInvalidProfiles(format!("target {}: {}", name, e)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_matrix_targets() {
+        let config = Schema::from_json(json!({
+            "targets": {
+                "rust": {
+                    "template": ["templates/rust"],
+                    "target_dir": "gen/rust",
+                    "profile": ["async"],
+                    "options": { "crate": "api" }
+                },
+                "docs": { "template": ["templates/docs"], "target_dir": "gen/docs" }
+            }
+        }));
+
+        let all = targets(&config, &[]).unwrap();
+        assert_eq!(
+            all.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>(),
+            vec!["rust", "docs"]
+        );
+        assert_eq!(
+            all[0].1.options(),
+            vec![("crate".to_string(), json!("api"))]
+        );
+
+        let selected = targets(&config, &["docs".to_string()]).unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].1.target_dir, "gen/docs");
+
+        assert_eq!(targets(&config, &["go".to_string()]).is_err(), true);
+    }
+}
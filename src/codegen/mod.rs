@@ -3,11 +3,30 @@ use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashMap;
 
+pub mod atomic;
+pub mod cache;
+pub mod collision;
+pub mod contract;
+pub mod docs;
+pub mod example;
 pub mod filters;
+pub mod grouping;
+pub mod header;
+pub mod identifier;
+pub mod imports;
 pub mod jsonschema;
+pub mod matrix;
 pub mod openapi;
+pub mod output;
+pub mod ownership;
+pub mod pack;
+pub mod profiles;
 pub mod renderer;
+pub mod samples;
+pub mod strict;
+pub mod symbols;
 pub mod templates;
+pub mod validation;
 
 #[derive(Default, Debug, Clone, Serialize)]
 pub struct CodegenContainer {
@@ -15,6 +34,19 @@ pub struct CodegenContainer {
 
     #[serde(flatten)]
     pub data: HashMap<String, Value>,
+
+    #[serde(skip)]
+    pub strict: Option<strict::Strictness>,
+
+    // files not written because rendered content was empty, shared by clones
+    #[serde(skip)]
+    pub skipped: std::rc::Rc<std::cell::RefCell<Vec<String>>>,
+
+    #[serde(skip)]
+    pub outputs: collision::Outputs,
+
+    #[serde(skip)]
+    pub output: output::Backend,
 }
 
 pub fn create_container(options: &[(String, serde_json::Value)]) -> CodegenContainer {
@@ -23,6 +55,10 @@ pub fn create_container(options: &[(String, serde_json::Value)]) -> CodegenConta
     CodegenContainer {
         options,
         data: HashMap::new(),
+        strict: None,
+        skipped: Default::default(),
+        outputs: Default::default(),
+        output: Default::default(),
     }
 }
 
@@ -1,37 +1,115 @@
-use serde::Serialize;
-use serde_json::Value;
+use inflector::Inflector;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 
 use crate::{
-    codegen::jsonschema::{JsonSchemaExtractOptions, ModelContainer},
+    codegen::{
+        docs::Docs,
+        jsonschema::{JsonSchemaExtractOptions, ModelContainer},
+    },
     error::Error,
     process::name::endpoint,
     resolver::SchemaResolver,
     scope::{SchemaScope, Space},
+    tools,
 };
 
 use super::{
-    parameters::{self, Parameters},
-    requestbody, responses, security,
+    grpc,
+    parameters::{self, Parameter, Parameters},
+    policies, requestbody, responses,
+    router::{self, Route},
+    security,
 };
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Endpoint {
     security: Vec<security::SecurityScheme>,
     path: String,
+    route: Route,
+    path_format: String,
+    path_parameters: Vec<Parameter>,
+    query: LocationParameters,
+    cookies: LocationParameters,
+    cookie_security: Vec<security::SecurityScheme>,
     method: String,
     operation: String,
     description: Option<String>,
+    docs: Docs,
     tags: Vec<String>,
     parameters: parameters::Parameters,
     pub requestbody: Option<requestbody::RequestBody>,
     pub responses: responses::Responses,
+    is_binary_response: bool,
+    binary_content_type: Option<String>,
+    streaming: bool,
+    stream: Option<super::MediaModel>,
+    grpc: Option<grpc::GrpcMapping>,
+    policies: policies::Policies,
     x: std::collections::HashMap<String, Value>,
 }
 
+// description of struct holding all parameters of operation in one location (query, cookie)
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LocationParameters {
+    name: String,
+    parameters: Vec<Parameter>,
+    has_required: bool,
+}
+
+impl LocationParameters {
+    fn new(operation: &str, suffix: &str, parameters: &[Parameter]) -> Self {
+        Self {
+            name: format!("{}{}", operation.to_pascal_case(), suffix),
+            parameters: parameters.to_vec(),
+            has_required: parameters.iter().any(|p| p.required),
+        }
+    }
+}
+
 impl Endpoint {
     pub fn get_tags(&self) -> &Vec<String> {
         &self.tags
     }
+
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn get_route(&self) -> &Route {
+        &self.route
+    }
+
+    pub fn get_method(&self) -> &str {
+        &self.method
+    }
+
+    pub fn get_operation(&self) -> &str {
+        &self.operation
+    }
+
+    // extension of operation, name with or without `x-` prefix
+    pub fn get_extension(&self, name: &str) -> Option<&Value> {
+        self.x.get(name.strip_prefix("x-").unwrap_or(name))
+    }
+
+    pub fn set_default_backend(&mut self, backend: &grpc::Backend) {
+        if self.grpc.is_none() {
+            self.grpc = Some(grpc::GrpcMapping::infer(
+                &self.method,
+                &self.path,
+                &self.operation,
+                &self.tags,
+                self.requestbody.is_some(),
+            ));
+        }
+
+        if let Some(mapping) = self.grpc.as_mut() {
+            if mapping.backend.is_none() {
+                mapping.backend = Some(backend.clone());
+            }
+        }
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -57,9 +135,7 @@ pub fn extract_endpoints(
             scope.pop();
 
             let mut endpoints = vec![];
-            for method in &[
-                "get", "put", "post", "delete", "options", "head", "patch", "trace",
-            ] {
+            for method in tools::HTTP_METHODS {
                 if let Some(method_details) = details.get(*method) {
                     scope.any(method);
                     endpoints.push(new_endpoint(
@@ -105,12 +181,16 @@ fn new_endpoint(
                 .map_or(Ok(None), |v| v.map(Some))?
                 .unwrap_or_else(|| scontainer.default.clone());
 
-            let operation = data
-                .get("operationId")
-                .map(|v| v.as_str().unwrap().to_string())
+            let operation = options
+                .name_override(data)
+                .or_else(|| {
+                    data.get("operationId")
+                        .map(|v| v.as_str().unwrap().to_string())
+                })
                 .unwrap_or_else(|| {
                     endpoint::Endpoint::new(method.to_string(), path.to_string())
                         .unwrap()
+                        .with_inflections(&options.inflections)
                         .get_operation_id(true)
                 });
 
@@ -151,14 +231,110 @@ fn new_endpoint(
                 endpoint_parameters.merge(shared)
             }
 
+            let route = Route::new(path);
+
+            // placeholders of path without declaration are still needed to build url
+            for name in &route.parameters {
+                if !endpoint_parameters.path.iter().any(|p| &p.name == name) {
+                    log::warn!("{}: path parameter {} is not declared", scope, name);
+
+                    let declaration = json!({
+                        "in": "path",
+                        "name": name,
+                        "required": true,
+                        "schema": { "type": "string" }
+                    });
+
+                    endpoint_parameters.add(parameters::extract_parameter(
+                        &declaration,
+                        scope,
+                        mcontainer,
+                        resolver,
+                        options,
+                    )?);
+                }
+            }
+
+            // ordered as they appear in path so they can be passed to path_format
+            let path_parameters = route
+                .parameters
+                .iter()
+                .filter_map(|name| {
+                    endpoint_parameters
+                        .path
+                        .iter()
+                        .find(|p| &p.name == name)
+                        .cloned()
+                })
+                .collect::<Vec<_>>();
+
+            let responses = responses::extract(data, scope, mcontainer, resolver, options)?
+                .with_headers_models(&operation);
+
+            // file downloads should not be decoded by clients
+            let binary_content_type = responses
+                .success
+                .as_ref()
+                .and_then(|r| r.models.as_ref())
+                .and_then(|m| {
+                    m.list
+                        .iter()
+                        .find(|mm| mm.content_type == m.default_content_type)
+                        .or_else(|| m.list.first())
+                })
+                .filter(|mm| mm.is_binary)
+                .map(|mm| mm.content_type.clone());
+
+            // media of success response sent as stream of events
+            let stream = responses
+                .success
+                .as_ref()
+                .and_then(|r| r.models.as_ref())
+                .and_then(|m| m.list.iter().find(|mm| mm.is_streaming))
+                .cloned();
+
+            let grpc = grpc::extract(
+                data,
+                method,
+                path,
+                &operation,
+                &tags,
+                &options.extension_prefix,
+                scope,
+            )?;
+
+            // session based auth (apiKey in cookie) is managed by clients next to cookie parameters
+            let cookie_security = security
+                .iter()
+                .filter(|s| s.in_.as_deref() == Some("cookie"))
+                .cloned()
+                .collect();
+
             let endpoint = Endpoint {
                 security,
                 description,
+                docs: Docs::new(data),
+                query: LocationParameters::new(&operation, "Query", &endpoint_parameters.query),
+                cookies: LocationParameters::new(
+                    &operation,
+                    "Cookies",
+                    &endpoint_parameters.cookie,
+                ),
+                cookie_security,
                 operation,
                 method: method.to_string(),
                 path: path.to_string(),
+                route,
+                path_format: router::path_format(path),
+                path_parameters,
                 tags,
-                responses: responses::extract(data, scope, mcontainer, resolver, options)?,
+                responses,
+                is_binary_response: binary_content_type.is_some(),
+                binary_content_type,
+                streaming: stream.is_some(),
+                stream,
+                grpc,
+                policies: policies::extract(data, method, scope)?,
                 requestbody: requestbody::extract(data, scope, mcontainer, resolver, options)?,
                 parameters: endpoint_parameters,
                 x,
@@ -243,6 +419,224 @@ mod tests {
         assert_eq!(get_endpoint.parameters.all.len(), 2);
         assert_eq!(get_endpoint.parameters.query.len(), 1);
         assert_eq!(get_endpoint.parameters.path.len(), 1);
+        assert_eq!(get_endpoint.path_format, "/users/{}".to_string());
+        assert_eq!(get_endpoint.path_parameters[0].name, "userId".to_string());
+        assert_eq!(get_endpoint.query.parameters.len(), 1);
+        assert_eq!(get_endpoint.query.has_required, false);
+    }
+
+    #[test]
+    fn test_operation_parameters_override_path_parameters() {
+        let schema = json!({
+            "parameters": [{
+                "in": "path",
+                "name": "userId",
+                "description": "shared",
+                "required": true,
+                "schema": { "type": "string" }
+            }, {
+                "in": "header",
+                "name": "X-Request-Id",
+                "required": false,
+                "schema": { "type": "string" }
+            }],
+            "get": {
+                "parameters": [{
+                    "in": "path",
+                    "name": "userId",
+                    "description": "overridden",
+                    "required": true,
+                    "schema": { "type": "integer" }
+                }],
+                "responses": {}
+            }
+        });
+
+        let mut mcontainer = ModelContainer::default();
+        let mut scontainer = super::security::SecuritySchemes::new();
+        let mut scope = SchemaScope::default();
+        let resolver = SchemaResolver::empty();
+        let options = JsonSchemaExtractOptions::default();
+
+        let endpoints = extract_endpoints(
+            &schema,
+            "/users/{userId}",
+            &mut scope,
+            &mut mcontainer,
+            &mut scontainer,
+            &resolver,
+            &options,
+        )
+        .unwrap();
+
+        let get_endpoint = endpoints.iter().find(|e| e.method == "get").unwrap();
+        assert_eq!(get_endpoint.parameters.all.len(), 2);
+        assert_eq!(get_endpoint.parameters.path.len(), 1);
+        assert_eq!(get_endpoint.parameters.header.len(), 1);
+        assert_eq!(
+            get_endpoint.parameters.path[0].description,
+            Some("overridden".to_string())
+        );
+        assert_eq!(
+            get_endpoint.parameters.path[0]
+                .model
+                .as_ref()
+                .unwrap()
+                .type_,
+            "integer"
+        );
+    }
+
+    #[test]
+    fn test_path_format_and_query() {
+        let schema = json!({
+            "parameters": [{
+                "in": "path",
+                "name": "groupId",
+                "required": true,
+                "schema": { "type": "integer" }
+            }],
+            "get": {
+                "operationId": "getUserGroup",
+                "parameters": [{
+                    "in": "path",
+                    "name": "userId",
+                    "required": true,
+                    "schema": { "type": "string" }
+                }, {
+                    "in": "query",
+                    "name": "expand",
+                    "required": true,
+                    "schema": { "type": "string" }
+                }, {
+                    "in": "query",
+                    "name": "fields",
+                    "schema": { "type": "string" }
+                }],
+                "responses": {}
+            },
+            "delete": {
+                "operationId": "deleteUserGroup",
+                "responses": {}
+            }
+        });
+
+        let mut mcontainer = ModelContainer::default();
+        let mut scontainer = super::security::SecuritySchemes::new();
+        let mut scope = SchemaScope::default();
+        let resolver = SchemaResolver::empty();
+        let options = JsonSchemaExtractOptions::default();
+
+        let endpoints = extract_endpoints(
+            &schema,
+            "/users/{userId}/groups/{groupId}",
+            &mut scope,
+            &mut mcontainer,
+            &mut scontainer,
+            &resolver,
+            &options,
+        )
+        .unwrap();
+
+        // path parameters follow path, not declaration order
+        let get_endpoint = endpoints.iter().find(|e| e.method == "get").unwrap();
+        assert_eq!(get_endpoint.path_format, "/users/{}/groups/{}");
+        assert_eq!(
+            get_endpoint
+                .path_parameters
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["userId", "groupId"]
+        );
+        assert_eq!(get_endpoint.query.name, "GetUserGroupQuery");
+        assert_eq!(get_endpoint.query.parameters.len(), 2);
+        assert!(get_endpoint.query.has_required);
+
+        // undeclared path parameter is added as required string, operation without query gets empty struct
+        let delete_endpoint = endpoints.iter().find(|e| e.method == "delete").unwrap();
+        assert_eq!(delete_endpoint.path_format, "/users/{}/groups/{}");
+        assert_eq!(delete_endpoint.path_parameters.len(), 2);
+        assert_eq!(delete_endpoint.path_parameters[0].name, "userId");
+        assert!(delete_endpoint.path_parameters[0].required);
+        assert_eq!(
+            delete_endpoint.path_parameters[0]
+                .model
+                .as_ref()
+                .map(|m| m.type_.as_str()),
+            Some("string")
+        );
+        assert_eq!(delete_endpoint.parameters.path.len(), 2);
+        assert_eq!(delete_endpoint.query.name, "DeleteUserGroupQuery");
+        assert!(delete_endpoint.query.parameters.is_empty());
+        assert!(!delete_endpoint.query.has_required);
+    }
+
+    #[test]
+    fn test_path_format_of_partial_segments() {
+        assert_eq!(
+            super::router::path_format("/files/{name}.{extension}"),
+            "/files/{}.{}"
+        );
+        assert_eq!(super::router::path_format("/health"), "/health");
+    }
+
+    #[test]
+    fn test_cookie_parameters_and_security() {
+        let schema = json!({
+            "get": {
+                "operationId": "getProfile",
+                "security": [{ "session": [], "csrf": [] }],
+                "parameters": [{
+                    "in": "cookie",
+                    "name": "session_id",
+                    "required": true,
+                    "schema": { "type": "string" }
+                }, {
+                    "in": "cookie",
+                    "name": "theme",
+                    "schema": { "type": "string" }
+                }],
+                "responses": {}
+            }
+        });
+
+        let mut mcontainer = ModelContainer::default();
+        let mut scontainer = super::security::SecuritySchemes::new();
+        let mut scope = SchemaScope::default();
+        let resolver = SchemaResolver::empty();
+        let options = JsonSchemaExtractOptions::default();
+
+        for (name, in_) in [("session", "cookie"), ("csrf", "header")] {
+            scontainer.add(super::security::SecurityScheme {
+                scheme_name: name.to_string(),
+                type_: "apiKey".to_string(),
+                scheme: None,
+                in_: Some(in_.to_string()),
+                name: Some(name.to_string()),
+            });
+        }
+
+        let endpoints = extract_endpoints(
+            &schema,
+            "/profile",
+            &mut scope,
+            &mut mcontainer,
+            &mut scontainer,
+            &resolver,
+            &options,
+        )
+        .unwrap();
+
+        let endpoint = &endpoints[0];
+        assert_eq!(endpoint.cookies.name, "GetProfileCookies");
+        assert_eq!(endpoint.cookies.parameters.len(), 2);
+        assert!(endpoint.cookies.has_required);
+        assert_eq!(endpoint.query.parameters.len(), 0);
+
+        assert_eq!(endpoint.security.len(), 2);
+        assert_eq!(endpoint.cookie_security.len(), 1);
+        assert_eq!(endpoint.cookie_security[0].scheme_name, "session");
     }
 
     #[test]
@@ -0,0 +1,251 @@
+use inflector::Inflector;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{error::Error, scope::SchemaScope, tools};
+
+// backend extension of google api gateway / cloud endpoints
+pub static GOOGLE_BACKEND: &str = "x-google-backend";
+
+// http rule of operation with matching grpc method, ex. rpc GetUser(GetUserRequest)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GrpcMapping {
+    pub package: Option<String>,
+    pub service: String,
+    pub method: String,
+    pub request: String,
+    pub response: String,
+
+    // google.api.http body and response_body, * means whole request message
+    pub body: Option<String>,
+    pub response_body: Option<String>,
+
+    pub http: HttpRule,
+    pub backend: Option<Backend>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HttpRule {
+    pub method: String,
+    pub path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Backend {
+    pub address: String,
+    pub protocol: Option<String>,
+    pub deadline: Option<f64>,
+    pub path_translation: Option<String>,
+}
+
+impl Backend {
+    pub fn new(node: &Value, scope: &SchemaScope) -> Result<Self, Error> {
+        let invalid =
+            || Error::CodegenInvalidEndpointProperty(GOOGLE_BACKEND.to_string(), scope.to_string());
+
+        let string = |key: &str| {
+            node.get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        };
+
+        Ok(Self {
+            address: string("address").ok_or_else(invalid)?,
+            protocol: string("protocol"),
+            deadline: node.get("deadline").and_then(|v| v.as_f64()),
+            path_translation: string("path_translation"),
+        })
+    }
+}
+
+impl GrpcMapping {
+    // grpc-gateway names operations Service_Method, otherwise first tag is a service
+    pub fn infer(
+        method: &str,
+        path: &str,
+        operation: &str,
+        tags: &[String],
+        has_body: bool,
+    ) -> Self {
+        let (service, rpc) = match operation.split_once('_') {
+            Some((service, rpc)) if !service.is_empty() && !rpc.is_empty() => {
+                (service.to_string(), rpc.to_pascal_case())
+            }
+            _ => (
+                tags.first()
+                    .map(|t| t.to_pascal_case())
+                    .unwrap_or_else(|| "Api".to_string()),
+                operation.to_pascal_case(),
+            ),
+        };
+
+        Self {
+            package: None,
+            service,
+            request: format!("{}Request", rpc),
+            response: format!("{}Response", rpc),
+            method: rpc,
+            body: if has_body {
+                Some("*".to_string())
+            } else {
+                None
+            },
+            response_body: None,
+            http: HttpRule {
+                method: method.to_string(),
+                path: path.to_string(),
+            },
+            backend: None,
+        }
+    }
+}
+
+pub fn extract(
+    data: &Map<String, Value>,
+    method: &str,
+    path: &str,
+    operation: &str,
+    tags: &[String],
+    prefix: &Option<String>,
+    scope: &SchemaScope,
+) -> Result<Option<GrpcMapping>, Error> {
+    let name = tools::extension(prefix, "grpc");
+
+    let annotation = match data.get(&name) {
+        Some(Value::Object(annotation)) => Some(annotation),
+        Some(_) => {
+            return Err(Error::CodegenInvalidEndpointProperty(
+                name,
+                scope.to_string(),
+            ))
+        }
+        None => None,
+    };
+
+    let backend = data
+        .get(GOOGLE_BACKEND)
+        .map(|b| Backend::new(b, scope))
+        .map_or(Ok(None), |v| v.map(Some))?;
+
+    if annotation.is_none() && backend.is_none() {
+        return Ok(None);
+    }
+
+    let mut mapping = GrpcMapping::infer(
+        method,
+        path,
+        operation,
+        tags,
+        data.contains_key("requestBody"),
+    );
+    mapping.backend = backend;
+
+    if let Some(annotation) = annotation {
+        let string = |key: &str| -> Result<Option<String>, Error> {
+            match annotation.get(key) {
+                Some(Value::String(s)) => Ok(Some(s.clone())),
+                Some(_) => Err(Error::CodegenInvalidEndpointProperty(
+                    format!("{}:{}", name, key),
+                    scope.to_string(),
+                )),
+                None => Ok(None),
+            }
+        };
+
+        if let Some(rpc) = string("method")? {
+            mapping.request = format!("{}Request", rpc);
+            mapping.response = format!("{}Response", rpc);
+            mapping.method = rpc;
+        }
+        if let Some(service) = string("service")? {
+            mapping.service = service;
+        }
+        if let Some(request) = string("request")? {
+            mapping.request = request;
+        }
+        if let Some(response) = string("response")? {
+            mapping.response = response;
+        }
+        mapping.package = string("package")?;
+        mapping.body = string("body")?.or(mapping.body);
+        mapping.response_body = string("response_body")?;
+    }
+
+    Ok(Some(mapping))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_extract_grpc_mapping() {
+        let scope = SchemaScope::default();
+        let tags = vec!["users".to_string()];
+
+        let operation = json!({
+            "requestBody": {},
+            "x-schematools-grpc": { "package": "users.v1", "method": "CreateUser", "body": "user" },
+            "x-google-backend": { "address": "grpc://users:50051", "deadline": 5.0 }
+        });
+        let mapping = extract(
+            operation.as_object().unwrap(),
+            "post",
+            "/users",
+            "createUser",
+            &tags,
+            &None,
+            &scope,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(mapping.package, Some("users.v1".to_string()));
+        assert_eq!(mapping.service, "Users");
+        assert_eq!(mapping.method, "CreateUser");
+        assert_eq!(mapping.request, "CreateUserRequest");
+        assert_eq!(mapping.body, Some("user".to_string()));
+        assert_eq!(mapping.http.path, "/users");
+        assert_eq!(
+            mapping.backend.unwrap().address,
+            "grpc://users:50051".to_string()
+        );
+
+        let inferred =
+            GrpcMapping::infer("get", "/users/{id}", "UserService_GetUser", &tags, false);
+        assert_eq!(inferred.service, "UserService");
+        assert_eq!(inferred.method, "GetUser");
+        assert_eq!(inferred.body, None);
+
+        assert_eq!(
+            extract(
+                json!({}).as_object().unwrap(),
+                "get",
+                "/users",
+                "listUsers",
+                &tags,
+                &None,
+                &scope
+            )
+            .unwrap()
+            .is_none(),
+            true
+        );
+        assert_eq!(
+            extract(
+                json!({ "x-google-backend": { "protocol": "h2" } })
+                    .as_object()
+                    .unwrap(),
+                "get",
+                "/users",
+                "listUsers",
+                &tags,
+                &None,
+                &scope
+            )
+            .is_err(),
+            true
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error::Error, resolver::SchemaResolver, scope::SchemaScope};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Link {
+    pub name: String,
+    pub operation_id: Option<String>,
+    pub operation_ref: Option<String>,
+    pub parameters: Vec<LinkParameter>,
+    pub request_body: Option<Expression>,
+    pub description: Option<String>,
+    pub server: Option<Value>,
+    pub reference: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LinkParameter {
+    // may be prefixed with location, ex. path.id
+    pub name: String,
+    pub expression: Expression,
+}
+
+// parsed runtime expression, ex. $response.body#/id
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Expression {
+    pub raw: Value,
+
+    // url, method, statusCode, request, response or constant
+    pub source: String,
+
+    // header, query, path or body of request/response
+    pub location: Option<String>,
+
+    // name of header, query or path parameter
+    pub name: Option<String>,
+
+    // json pointer into body, empty when whole body is used
+    pub pointer: Option<String>,
+}
+
+impl Expression {
+    pub fn parse(value: &Value) -> Option<Self> {
+        let expression = match value {
+            Value::String(s) if s.starts_with('$') => s,
+            // anything else is passed as is
+            _ => {
+                return Some(Self {
+                    raw: value.clone(),
+                    source: "constant".to_string(),
+                    location: None,
+                    name: None,
+                    pointer: None,
+                })
+            }
+        };
+
+        let new =
+            |source: &str, location: Option<&str>, name: Option<&str>, pointer: Option<&str>| {
+                Some(Self {
+                    raw: value.clone(),
+                    source: source.to_string(),
+                    location: location.map(|s| s.to_string()),
+                    name: name.map(|s| s.to_string()),
+                    pointer: pointer.map(|s| s.to_string()),
+                })
+            };
+
+        match expression.as_str() {
+            "$url" => new("url", None, None, None),
+            "$method" => new("method", None, None, None),
+            "$statusCode" => new("statusCode", None, None, None),
+            _ => {
+                let (source, rest) = expression[1..].split_once('.')?;
+                if source != "request" && source != "response" {
+                    return None;
+                }
+
+                if let Some(pointer) = rest.strip_prefix("body") {
+                    return match pointer {
+                        "" => new(source, Some("body"), None, Some("")),
+                        _ => new(source, Some("body"), None, Some(pointer.strip_prefix('#')?)),
+                    };
+                }
+
+                let (location, name) = rest.split_once('.')?;
+                match location {
+                    "header" | "query" | "path" if !name.is_empty() => {
+                        new(source, Some(location), Some(name), None)
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+pub fn extract(
+    node: &Value,
+    scope: &mut SchemaScope,
+    resolver: &SchemaResolver,
+) -> Result<Vec<Link>, Error> {
+    match node {
+        Value::Object(links) => links
+            .iter()
+            .map(|(name, link)| {
+                scope.property(name);
+                let link = extract_link(name, link, scope, resolver);
+                scope.pop();
+
+                link
+            })
+            .collect(),
+        _ => Err(Error::CodegenInvalidEndpointProperty(
+            "links".to_string(),
+            scope.to_string(),
+        )),
+    }
+}
+
+fn extract_link(
+    name: &str,
+    node: &Value,
+    scope: &mut SchemaScope,
+    resolver: &SchemaResolver,
+) -> Result<Link, Error> {
+    let reference = super::get_reference(node);
+
+    resolver.resolve(node, scope, |node, scope| {
+        let invalid = |property: &str| {
+            Error::CodegenInvalidEndpointProperty(
+                format!("link:{}:{}", name, property),
+                scope.to_string(),
+            )
+        };
+
+        let data = node.as_object().ok_or_else(|| invalid("definition"))?;
+
+        let parameters = match data.get("parameters") {
+            Some(Value::Object(parameters)) => parameters
+                .iter()
+                .map(|(name, value)| {
+                    Expression::parse(value)
+                        .map(|expression| LinkParameter {
+                            name: name.clone(),
+                            expression,
+                        })
+                        .ok_or_else(|| invalid(name))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            Some(_) => return Err(invalid("parameters")),
+            None => vec![],
+        };
+
+        let request_body = data
+            .get("requestBody")
+            .map(|value| Expression::parse(value).ok_or_else(|| invalid("requestBody")))
+            .map_or(Ok(None), |v| v.map(Some))?;
+
+        Ok(Link {
+            name: name.to_string(),
+            operation_id: data
+                .get("operationId")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            operation_ref: data
+                .get("operationRef")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            parameters,
+            request_body,
+            description: data
+                .get("description")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            server: data.get("server").cloned(),
+            reference: reference.clone(),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_runtime_expressions() {
+        let body = Expression::parse(&json!("$response.body#/id")).unwrap();
+        assert_eq!(body.source, "response");
+        assert_eq!(body.location, Some("body".to_string()));
+        assert_eq!(body.pointer, Some("/id".to_string()));
+
+        let header = Expression::parse(&json!("$request.header.X-Request-Id")).unwrap();
+        assert_eq!(header.source, "request");
+        assert_eq!(header.location, Some("header".to_string()));
+        assert_eq!(header.name, Some("X-Request-Id".to_string()));
+
+        assert_eq!(
+            Expression::parse(&json!("$statusCode")).unwrap().source,
+            "statusCode"
+        );
+        assert_eq!(Expression::parse(&json!(42)).unwrap().source, "constant");
+        assert_eq!(Expression::parse(&json!("$response.cookie.a")), None);
+        assert_eq!(Expression::parse(&json!("$request.query.")), None);
+    }
+
+    #[test]
+    fn test_extract_links() {
+        let node = json!({
+            "GetUser": {
+                "operationId": "getUser",
+                "parameters": { "userId": "$response.body#/id", "expand": true },
+                "description": "Fetch created user"
+            }
+        });
+
+        let mut scope = SchemaScope::default();
+        let resolver = SchemaResolver::empty();
+
+        let links = extract(&node, &mut scope, &resolver).unwrap();
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].operation_id, Some("getUser".to_string()));
+        assert_eq!(links[0].parameters[0].name, "userId");
+        assert_eq!(
+            links[0].parameters[0].expression.pointer,
+            Some("/id".to_string())
+        );
+        assert_eq!(links[0].parameters[1].name, "expand");
+        assert_eq!(links[0].parameters[1].expression.source, "constant");
+    }
+}
@@ -1,23 +1,34 @@
 use crate::storage::SchemaStorage;
 use crate::{error::Error, resolver::SchemaResolver, schema::Schema, scope::SchemaScope, tools};
 use serde::ser::SerializeMap;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Map;
 use serde_json::Value;
 
 use super::jsonschema::{add_types, extract_type, JsonSchemaExtractOptions, ModelContainer};
 
 pub mod endpoint;
+pub mod grpc;
+pub mod links;
 pub mod parameters;
+pub mod policies;
 pub mod requestbody;
 pub mod responses;
+pub mod router;
 pub mod security;
 
+#[derive(Debug)]
 pub struct OpenapiExtractOptions {
     pub wrappers: bool,
     pub nested_arrays_as_models: bool,
     pub optional_and_nullable_as_models: bool,
     pub keep_schema: tools::Filter,
+    pub single_enum_as_const: bool,
+    pub extension_prefix: Option<String>,
+    pub inflections: crate::process::name::word::Inflections,
+    pub identifier_policy: crate::codegen::identifier::IdentifierPolicy,
+    pub decimal_formats: Vec<String>,
+    pub formats: crate::formats::Formats,
 }
 #[derive(Default)]
 pub struct EndpointContainer {
@@ -34,7 +45,7 @@ impl EndpointContainer {
     }
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MediaModel {
     pub model: crate::codegen::jsonschema::types::FlatModel,
@@ -42,6 +53,31 @@ pub struct MediaModel {
     pub content_type: String,
 
     pub is_unique: bool,
+
+    pub is_binary: bool,
+
+    pub is_xml: bool,
+
+    // model describes single event of stream
+    pub is_streaming: bool,
+
+    pub files: Vec<String>,
+
+    pub encoding: Vec<Encoding>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Encoding {
+    pub property: String,
+
+    pub content_type: Option<String>,
+
+    pub style: Option<String>,
+
+    pub explode: Option<bool>,
+
+    pub allow_reserved: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -100,10 +136,35 @@ impl Serialize for MediaModelsContainer {
     }
 }
 
-#[derive(Serialize, Clone)]
+// all models are restored, default one is recognized by its content type
+impl<'de> Deserialize<'de> for MediaModelsContainer {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Serialized {
+            default: Option<MediaModel>,
+            all: Vec<MediaModel>,
+        }
+
+        let s = Serialized::deserialize(deserializer)?;
+
+        Ok(Self {
+            default_content_type: s
+                .default
+                .map(|m| m.content_type)
+                .unwrap_or_else(|| "application/json".to_string()),
+            list: s.all,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Openapi {
     pub models: ModelContainer,
     pub endpoints: Vec<endpoint::Endpoint>,
+    pub routes: Vec<router::RouteGroup>,
     pub security: security::SecuritySchemes,
     pub tags: Vec<String>,
 }
@@ -124,6 +185,12 @@ pub fn extract(
     let options = &JsonSchemaExtractOptions {
         optional_and_nullable_as_models: options.optional_and_nullable_as_models,
         keep_schema: options.keep_schema,
+        single_enum_as_const: options.single_enum_as_const,
+        extension_prefix: options.extension_prefix,
+        inflections: options.inflections,
+        identifier_policy: options.identifier_policy,
+        decimal_formats: options.decimal_formats,
+        formats: options.formats,
         ..Default::default()
     };
 
@@ -200,6 +267,24 @@ pub fn extract(
         },
     )?;
 
+    // components/headers
+    tools::each_node(
+        root,
+        &mut scope,
+        "/any:components/any:headers/definition:*/any:schema",
+        |node, parts, scope| {
+            if let [key] = parts {
+                scope.glue(key).glue("header");
+
+                add_types(node, &mut mcontainer, scope, resolver, options)?;
+
+                scope.reduce(2);
+            }
+
+            Ok(())
+        },
+    )?;
+
     // components/responses
     tools::each_node(
         root,
@@ -262,23 +347,44 @@ pub fn extract(
         },
     )?;
 
+    // backend defined at root level is used by every operation without its own
+    if let Some(backend) = root.get(grpc::GOOGLE_BACKEND) {
+        let backend = grpc::Backend::new(backend, &scope)?;
+
+        for endpoint in econtainer.endpoints.iter_mut() {
+            endpoint.set_default_backend(&backend);
+        }
+    }
+
     tags.sort();
     tags.dedup();
 
     Ok(Openapi {
         models: mcontainer,
+        routes: router::group(&econtainer.endpoints),
         endpoints: econtainer.endpoints,
         security: scontainer,
         tags,
     })
 }
 
+// fragment of $ref (ex. /components/parameters/Page), exposed to templates
+pub fn get_reference(node: &Value) -> Option<String> {
+    node.get("$ref").and_then(|r| r.as_str()).map(|r| {
+        r.split_once('#')
+            .map(|(_, fragment)| fragment.to_string())
+            .unwrap_or_else(|| r.to_string())
+    })
+}
+
+// file media types are passed as is only in responses, request bodies keep their models
 pub fn get_content(
     data: &Map<String, Value>,
     scope: &mut SchemaScope,
     mcontainer: &mut ModelContainer,
     resolver: &SchemaResolver,
     options: &JsonSchemaExtractOptions,
+    binary_media: bool,
 ) -> Option<Result<MediaModelsContainer, Error>> {
     data.get("content").and_then(|content| match content {
         Value::Object(o) => {
@@ -288,23 +394,56 @@ pub fn get_content(
                     .filter_map(|(content_type, s)| {
                         scope.any(content_type);
                         let result = match s {
-                            Value::Object(o) => o.get("schema").and_then(|s| {
-                                scope.any("schema");
-
-                                let result = Some(
-                                    extract_type(s, mcontainer, scope, resolver, options)
-                                        .and_then(|m| m.flatten(mcontainer, scope))
-                                        .map(|model| MediaModel {
-                                            model,
-                                            content_type: content_type.to_string(),
-                                            is_unique: false,
+                            Value::Object(o) => {
+                                let binary = if binary_media {
+                                    is_binary_media(content_type, o, scope, resolver)
+                                } else {
+                                    Ok(false)
+                                };
+
+                                match binary {
+                                    // files are passed as is, model is not synthesized
+                                    Ok(true) => Some(Ok(MediaModel {
+                                        model: binary_model(),
+                                        content_type: content_type.to_string(),
+                                        is_unique: false,
+                                        is_binary: true,
+                                        is_xml: false,
+                                        is_streaming: false,
+                                        files: vec![],
+                                        encoding: get_encoding(o),
+                                    })),
+                                    Err(e) => Some(Err(e)),
+                                    Ok(false) => stream_schema(content_type, o)
+                                        .or_else(|| o.get("schema").map(|s| ("schema", s)))
+                                        .and_then(|(keyword, s)| {
+                                            scope.any(keyword);
+
+                                            let result = Some(
+                                                extract_type(
+                                                    s, mcontainer, scope, resolver, options,
+                                                )
+                                                .and_then(|m| m.flatten(mcontainer, scope))
+                                                .and_then(|model| {
+                                                    Ok(MediaModel {
+                                                        model,
+                                                        content_type: content_type.to_string(),
+                                                        is_unique: false,
+                                                        is_binary: is_binary(s, scope, resolver)?,
+                                                        is_xml: is_xml(content_type),
+                                                        is_streaming: is_streaming(content_type),
+                                                        files: get_files(s, scope, resolver)?,
+                                                        encoding: get_encoding(o),
+                                                    })
+                                                }),
+                                            );
+
+                                            scope.pop();
+
+                                            result
                                         }),
-                                );
-
-                                scope.pop();
-
-                                result
-                            }),
+                                }
+                            }
                             _ => None,
                         };
                         scope.pop();
@@ -312,8 +451,8 @@ pub fn get_content(
                     })
                     .collect::<Result<Vec<_>, _>>()
                     .map(|list| MediaModelsContainer {
+                        default_content_type: default_content_type(&list),
                         list,
-                        default_content_type: "application/json".to_string(),
                     }),
             );
             scope.pop();
@@ -323,6 +462,142 @@ pub fn get_content(
     })
 }
 
+// application/xml, text/xml and structured syntax suffix (ex. application/atom+xml)
+fn is_xml(content_type: &str) -> bool {
+    let essence = content_type.split(';').next().unwrap_or("").trim();
+
+    essence == "application/xml" || essence == "text/xml" || essence.ends_with("+xml")
+}
+
+// server-sent events and newline delimited json
+fn is_streaming(content_type: &str) -> bool {
+    let essence = content_type.split(';').next().unwrap_or("").trim();
+
+    essence == "text/event-stream" || essence == "application/x-ndjson"
+}
+
+// schema of single event of stream, itemSchema (openapi 3.2) is preferred over schema
+fn stream_schema<'a>(
+    content_type: &str,
+    media: &'a Map<String, Value>,
+) -> Option<(&'static str, &'a Value)> {
+    if !is_streaming(content_type) {
+        return None;
+    }
+
+    media.get("itemSchema").map(|s| ("itemSchema", s))
+}
+
+// json is preferred, xml is used by apis which does not offer json at all
+fn default_content_type(list: &[MediaModel]) -> String {
+    if list.iter().any(|m| m.content_type == "application/json") {
+        return "application/json".to_string();
+    }
+
+    list.iter()
+        .find(|m| m.is_xml)
+        .map(|m| m.content_type.clone())
+        .unwrap_or_else(|| "application/json".to_string())
+}
+
+// file downloads and uploads: application/octet-stream, image/* or binary schema
+fn is_binary_media(
+    content_type: &str,
+    media: &Map<String, Value>,
+    scope: &mut SchemaScope,
+    resolver: &SchemaResolver,
+) -> Result<bool, Error> {
+    let essence = content_type.split(';').next().unwrap_or("").trim();
+    if essence == "application/octet-stream" || essence.starts_with("image/") {
+        return Ok(true);
+    }
+
+    match media.get("schema") {
+        Some(schema) => resolver.resolve(schema, scope, |node, _| {
+            Ok(node.get("format").and_then(|f| f.as_str()) == Some("binary"))
+        }),
+        None => Ok(false),
+    }
+}
+
+fn binary_model() -> crate::codegen::jsonschema::types::FlatModel {
+    crate::codegen::jsonschema::types::FlatModel {
+        type_: "binary".to_string(),
+        ..Default::default()
+    }
+}
+
+// binary payload: `format: binary` (3.0) or `contentMediaType` without encoding (3.1)
+fn is_binary(
+    node: &Value,
+    scope: &mut SchemaScope,
+    resolver: &SchemaResolver,
+) -> Result<bool, Error> {
+    resolver.resolve(node, scope, |node, scope| match node {
+        Value::Object(o) => {
+            if o.get("format").and_then(|f| f.as_str()) == Some("binary")
+                || (o.contains_key("contentMediaType") && !o.contains_key("contentEncoding"))
+            {
+                return Ok(true);
+            }
+
+            match o.get("items") {
+                Some(items) if o.get("type").and_then(|t| t.as_str()) == Some("array") => {
+                    is_binary(items, scope, resolver)
+                }
+                _ => Ok(false),
+            }
+        }
+        _ => Ok(false),
+    })
+}
+
+// names of properties which should be sent as files (ex. multipart uploads)
+fn get_files(
+    node: &Value,
+    scope: &mut SchemaScope,
+    resolver: &SchemaResolver,
+) -> Result<Vec<String>, Error> {
+    resolver.resolve(node, scope, |node, scope| {
+        let mut files = vec![];
+
+        if let Some(Value::Object(properties)) = node.get("properties") {
+            for (name, property) in properties {
+                if is_binary(property, scope, resolver)? {
+                    files.push(name.clone());
+                }
+            }
+        }
+
+        Ok(files)
+    })
+}
+
+fn get_encoding(media: &Map<String, Value>) -> Vec<Encoding> {
+    match media.get("encoding") {
+        Some(Value::Object(encoding)) => encoding
+            .iter()
+            .map(|(property, e)| Encoding {
+                property: property.clone(),
+                content_type: e
+                    .get("contentType")
+                    .and_then(|v| v.as_str())
+                    .map(|v| v.to_string()),
+                style: e
+                    .get("style")
+                    .and_then(|v| v.as_str())
+                    .map(|v| v.to_string()),
+                explode: e.get("explode").and_then(|v| v.as_bool()),
+                allow_reserved: e
+                    .get("allowReserved")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
 impl Openapi {
     pub fn set_content_type(mut self, content_type: &str) -> Self {
         self.endpoints.iter_mut().for_each(|f| {
@@ -342,3 +617,387 @@ impl Openapi {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn extract_endpoints(schema: Schema) -> Value {
+        let client = reqwest::blocking::Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        let openapi = extract(
+            &schema,
+            &storage,
+            OpenapiExtractOptions {
+                wrappers: false,
+                nested_arrays_as_models: false,
+                optional_and_nullable_as_models: false,
+                keep_schema: tools::Filter::new(&[]).unwrap(),
+                single_enum_as_const: false,
+                extension_prefix: None,
+                inflections: Default::default(),
+                identifier_policy: Default::default(),
+                decimal_formats: vec![],
+                formats: Default::default(),
+            },
+        )
+        .unwrap();
+
+        serde_json::to_value(&openapi.endpoints).unwrap()
+    }
+
+    #[test]
+    fn test_multipart_request_body_files() {
+        let schema = Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "paths": {
+                "/avatars": {
+                    "post": {
+                        "operationId": "uploadAvatar",
+                        "requestBody": {
+                            "required": true,
+                            "content": {
+                                "multipart/form-data": {
+                                    "schema": {
+                                        "type": "object",
+                                        "properties": {
+                                            "name": { "type": "string" },
+                                            "file": { "type": "string", "format": "binary" },
+                                            "thumbnails": {
+                                                "type": "array",
+                                                "items": { "type": "string", "format": "binary" }
+                                            }
+                                        }
+                                    },
+                                    "encoding": {
+                                        "file": { "contentType": "image/png" }
+                                    }
+                                }
+                            }
+                        },
+                        "responses": {}
+                    }
+                }
+            }
+        }));
+
+        let endpoints = extract_endpoints(schema);
+        let body = endpoints.pointer("/0/requestbody").unwrap();
+
+        assert_eq!(body["required"], json!(true));
+
+        let media = &body["models"]["default"];
+        assert_eq!(media["contentType"], json!("multipart/form-data"));
+        assert_eq!(media["isBinary"], json!(false));
+        assert_eq!(media["files"], json!(["file", "thumbnails"]));
+        assert_eq!(media["encoding"][0]["property"], json!("file"));
+        assert_eq!(media["encoding"][0]["contentType"], json!("image/png"));
+    }
+
+    #[test]
+    fn test_component_refs_resolved_without_dereference() {
+        let schema = Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "paths": {
+                "/users": {
+                    "get": {
+                        "operationId": "listUsers",
+                        "parameters": [{ "$ref": "#/components/parameters/Page" }],
+                        "responses": {
+                            "200": { "$ref": "#/components/responses/Users" }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "Page": {
+                        "in": "query",
+                        "name": "page",
+                        "schema": { "type": "integer" }
+                    }
+                },
+                "headers": {
+                    "RateLimit": {
+                        "schema": { "type": "integer" }
+                    }
+                },
+                "responses": {
+                    "Users": {
+                        "description": "list of users",
+                        "headers": {
+                            "X-Rate-Limit": { "$ref": "#/components/headers/RateLimit" },
+                            "Content-Type": { "schema": { "type": "string" } }
+                        },
+                        "content": {
+                            "application/json": {
+                                "schema": { "type": "array", "items": { "type": "string" } }
+                            }
+                        }
+                    }
+                }
+            }
+        }));
+
+        let endpoints = extract_endpoints(schema);
+
+        let parameter = endpoints.pointer("/0/parameters/query/0").unwrap();
+        assert_eq!(parameter["name"], json!("page"));
+        assert_eq!(parameter["reference"], json!("/components/parameters/Page"));
+
+        let response = endpoints.pointer("/0/responses/success").unwrap();
+        assert_eq!(response["reference"], json!("/components/responses/Users"));
+        assert_eq!(response["description"], json!("list of users"));
+
+        let headers = response["headers"].as_array().unwrap();
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers[0]["name"], json!("X-Rate-Limit"));
+        assert_eq!(headers[0]["kind"], json!("header"));
+        assert_eq!(headers[0]["model"]["type"], json!("integer"));
+        assert_eq!(
+            headers[0]["reference"],
+            json!("/components/headers/RateLimit")
+        );
+    }
+
+    #[test]
+    fn test_binary_response() {
+        let schema = Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "paths": {
+                "/avatars/{id}": {
+                    "get": {
+                        "operationId": "getAvatar",
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "image/png": {},
+                                    "image/jpeg": { "schema": { "type": "string", "format": "binary" } }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/reports/{id}": {
+                    "get": {
+                        "operationId": "getReport",
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/pdf": { "schema": { "type": "string", "format": "binary" } }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/users": {
+                    "get": {
+                        "operationId": "listUsers",
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": { "schema": { "type": "string" } }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }));
+
+        let endpoints = extract_endpoints(schema);
+
+        assert_eq!(endpoints[0]["is_binary_response"], json!(true));
+        assert_eq!(endpoints[0]["binary_content_type"], json!("image/png"));
+        assert_eq!(
+            endpoints.pointer("/0/responses/success/models/all/0/model/type"),
+            Some(&json!("binary"))
+        );
+
+        assert_eq!(endpoints[1]["is_binary_response"], json!(true));
+        assert_eq!(
+            endpoints[1]["binary_content_type"],
+            json!("application/pdf")
+        );
+
+        assert_eq!(endpoints[2]["is_binary_response"], json!(false));
+        assert_eq!(endpoints[2]["binary_content_type"], json!(null));
+    }
+
+    #[test]
+    fn test_binary_request_body_keeps_model() {
+        let schema = Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "paths": {
+                "/avatars/{id}": {
+                    "put": {
+                        "operationId": "putAvatar",
+                        "requestBody": {
+                            "content": {
+                                "image/png": { "schema": { "type": "string", "format": "binary" } }
+                            }
+                        },
+                        "responses": {}
+                    }
+                }
+            }
+        }));
+
+        let endpoints = extract_endpoints(schema);
+
+        assert_eq!(
+            endpoints.pointer("/0/requestbody/models/all/0/model/type"),
+            Some(&json!("string"))
+        );
+        assert_eq!(
+            endpoints.pointer("/0/requestbody/models/all/0/isBinary"),
+            Some(&json!(true))
+        );
+        assert_eq!(endpoints[0]["is_binary_response"], json!(false));
+    }
+
+    #[test]
+    fn test_streaming_response() {
+        let schema = Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "paths": {
+                "/events": {
+                    "get": {
+                        "operationId": "watchEvents",
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "type": "array", "items": { "type": "string" } }
+                                    },
+                                    "text/event-stream": {
+                                        "itemSchema": { "type": "integer" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/logs": {
+                    "get": {
+                        "operationId": "tailLogs",
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/x-ndjson": { "schema": { "type": "string" } }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }));
+
+        let endpoints = extract_endpoints(schema);
+
+        assert_eq!(endpoints[0]["streaming"], json!(true));
+        assert_eq!(
+            endpoints[0]["stream"]["contentType"],
+            json!("text/event-stream")
+        );
+        assert_eq!(endpoints[0]["stream"]["isStreaming"], json!(true));
+        assert_eq!(endpoints[0]["stream"]["model"]["type"], json!("integer"));
+
+        assert_eq!(endpoints[1]["streaming"], json!(true));
+        assert_eq!(
+            endpoints[1]["stream"]["contentType"],
+            json!("application/x-ndjson")
+        );
+        assert_eq!(endpoints[1]["stream"]["model"]["type"], json!("string"));
+    }
+
+    #[test]
+    fn test_xml_content_and_metadata() {
+        let schema = Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "paths": {
+                "/orders": {
+                    "get": {
+                        "operationId": "listOrders",
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "text/plain": { "schema": { "type": "string" } },
+                                    "application/xml": {
+                                        "schema": { "$ref": "#/components/schemas/Order" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Order": {
+                        "type": "object",
+                        "xml": { "name": "order", "namespace": "urn:orders" },
+                        "properties": {
+                            "id": { "type": "integer", "xml": { "attribute": true } },
+                            "items": {
+                                "type": "array",
+                                "xml": { "name": "items", "wrapped": true },
+                                "items": { "type": "string", "xml": { "name": "item" } }
+                            }
+                        }
+                    }
+                }
+            }
+        }));
+
+        let client = reqwest::blocking::Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+        let openapi = extract(
+            &schema,
+            &storage,
+            OpenapiExtractOptions {
+                wrappers: false,
+                nested_arrays_as_models: false,
+                optional_and_nullable_as_models: false,
+                keep_schema: tools::Filter::new(&[]).unwrap(),
+                single_enum_as_const: false,
+                extension_prefix: None,
+                inflections: Default::default(),
+                identifier_policy: Default::default(),
+                decimal_formats: vec![],
+                formats: Default::default(),
+            },
+        )
+        .unwrap();
+
+        let endpoints = serde_json::to_value(&openapi.endpoints).unwrap();
+        let media = endpoints
+            .pointer("/0/responses/success/models/default")
+            .unwrap();
+        assert_eq!(media["contentType"], json!("application/xml"));
+        assert_eq!(media["isXml"], json!(true));
+
+        let models = serde_json::to_value(&openapi.models).unwrap();
+        let order = models["models"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|m| m["attributes"]["xml"]["name"] == json!("order"))
+            .unwrap();
+        assert_eq!(order["attributes"]["xml"]["namespace"], json!("urn:orders"));
+        assert_eq!(
+            order["object"]["properties"][0]["xml"]["attribute"],
+            json!(true)
+        );
+        assert_eq!(
+            order["object"]["properties"][1]["xml"]["wrapped"],
+            json!(true)
+        );
+        assert_eq!(
+            order["object"]["properties"][1]["model"]["xml"]["name"],
+            json!("item")
+        );
+    }
+}
@@ -6,13 +6,13 @@ use crate::{
     resolver::SchemaResolver,
     scope::Space,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Map;
 use serde_json::Value;
 
 use crate::scope::SchemaScope;
 
-#[derive(Serialize, Default, Clone)]
+#[derive(Serialize, Deserialize, Default, Clone)]
 pub struct Parameters {
     #[serde(rename = "path")]
     pub path: Vec<Parameter>,
@@ -30,7 +30,7 @@ pub struct Parameters {
     pub all: Vec<Parameter>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Parameter {
     #[serde(rename = "model")]
     pub model: Option<FlatModel>,
@@ -52,6 +52,12 @@ pub struct Parameter {
 
     #[serde(rename = "kind")]
     pub kind: String,
+
+    #[serde(rename = "reference")]
+    pub reference: Option<String>,
+
+    #[serde(rename = "docs")]
+    pub docs: crate::codegen::docs::Docs,
 }
 
 pub fn extract(
@@ -104,6 +110,8 @@ pub fn extract_parameter(
     resolver: &SchemaResolver,
     options: &JsonSchemaExtractOptions,
 ) -> Result<Parameter, Error> {
+    let reference = super::get_reference(node);
+
     resolver.resolve(node, scope, |node, scope| match node {
         Value::Object(data) => {
             let kind = data
@@ -134,9 +142,18 @@ pub fn extract_parameter(
                     .unwrap()
             });
 
-            let schema = data.get("schema").ok_or_else(|| {
-                Error::CodegenInvalidEndpointProperty("schema".to_string(), scope.to_string())
-            })?;
+            // parameter may be described by content map instead of schema
+            let schema = data
+                .get("schema")
+                .or_else(|| {
+                    data.get("content")
+                        .and_then(|c| c.as_object())
+                        .and_then(|c| c.values().next())
+                        .and_then(|m| m.get("schema"))
+                })
+                .ok_or_else(|| {
+                    Error::CodegenInvalidEndpointProperty("schema".to_string(), scope.to_string())
+                })?;
 
             let required = data
                 .get("required")
@@ -162,6 +179,8 @@ pub fn extract_parameter(
                 explode,
                 style,
                 model: Some(model?),
+                reference: reference.clone(),
+                docs: crate::codegen::docs::Docs::new(data),
             })
         }
         _ => Err(Error::CodegenInvalidEndpointProperty(
@@ -184,9 +203,19 @@ impl Parameters {
         }
     }
 
+    // operation level parameters override path level ones with the same name and location
     pub fn merge(&mut self, parameters: &Parameters) {
         for param in parameters.all.iter() {
+            if self.contains(&param.name, &param.kind) {
+                log::debug!("parameter {} in {} overridden", param.name, param.kind);
+                continue;
+            }
+
             self.add(param.clone());
         }
     }
+
+    pub fn contains(&self, name: &str, kind: &str) -> bool {
+        self.all.iter().any(|p| p.name == name && p.kind == kind)
+    }
 }
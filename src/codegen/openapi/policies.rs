@@ -0,0 +1,346 @@
+use inflector::Inflector;
+use jsonschema::{Draft, JSONSchema};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::{error::Error, scope::SchemaScope};
+
+static RATE_LIMIT: &str = "x-rate-limit";
+static RETRY: &str = "x-retry";
+static TIMEOUT: &str = "x-timeout-ms";
+static IDEMPOTENT: &str = "x-idempotent";
+
+// safe to repeat by http semantics, unless operation says otherwise
+static IDEMPOTENT_METHODS: &[&str] = &["get", "head", "put", "delete", "options", "trace"];
+
+// client side defaults of operation
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Policies {
+    pub rate_limit: Option<RateLimit>,
+    pub retry: Option<Retry>,
+
+    // milliseconds
+    pub timeout: Option<u64>,
+    pub idempotent: bool,
+
+    // retry is declared and repeating request is safe
+    pub retryable: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RateLimit {
+    pub limit: u64,
+
+    // window in seconds, may be defined as 30s, 1m, 1h or 1d
+    #[serde(deserialize_with = "window")]
+    pub window: u64,
+
+    pub burst: Option<u64>,
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Retry {
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u64,
+
+    #[serde(default = "default_backoff")]
+    pub backoff: String,
+
+    // milliseconds
+    #[serde(default = "default_initial_delay")]
+    pub initial_delay: u64,
+    pub max_delay: Option<u64>,
+
+    #[serde(default = "default_statuses")]
+    pub statuses: Vec<u64>,
+}
+
+fn default_max_attempts() -> u64 {
+    3
+}
+
+fn default_backoff() -> String {
+    "exponential".to_string()
+}
+
+fn default_initial_delay() -> u64 {
+    100
+}
+
+fn default_statuses() -> Vec<u64> {
+    vec![429, 502, 503, 504]
+}
+
+fn window<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Number(n) => n
+            .as_u64()
+            .ok_or_else(|| serde::de::Error::custom("window has to be positive integer")),
+        Value::String(s) => {
+            let (value, unit) = s.split_at(s.len() - 1);
+            let multiplier = match unit {
+                "s" => 1,
+                "m" => 60,
+                "h" => 3600,
+                "d" => 86400,
+                _ => return Err(serde::de::Error::custom("unknown window unit")),
+            };
+
+            value
+                .parse::<u64>()
+                .map_err(serde::de::Error::custom)?
+                .checked_mul(multiplier)
+                .ok_or_else(|| serde::de::Error::custom("window is too long"))
+        }
+        _ => Err(serde::de::Error::custom("invalid window")),
+    }
+}
+
+fn schema(extension: &str) -> Value {
+    match extension {
+        "x-rate-limit" => json!({
+            "type": "object",
+            "required": ["limit", "window"],
+            "additionalProperties": false,
+            "properties": {
+                "limit": { "type": "integer", "minimum": 1 },
+                "window": {
+                    "oneOf": [
+                        { "type": "integer", "minimum": 1 },
+                        { "type": "string", "pattern": "^[0-9]+[smhd]$" }
+                    ]
+                },
+                "burst": { "type": "integer", "minimum": 1 },
+                "scope": { "type": "string" }
+            }
+        }),
+        _ => json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "max_attempts": { "type": "integer", "minimum": 1 },
+                "backoff": { "enum": ["constant", "linear", "exponential"] },
+                "initial_delay": { "type": "integer", "minimum": 0 },
+                "max_delay": { "type": "integer", "minimum": 0 },
+                "statuses": {
+                    "type": "array",
+                    "items": { "type": "integer", "minimum": 100, "maximum": 599 }
+                }
+            }
+        }),
+    }
+}
+
+// x-rate-limit object merged with x-rate-limit-* fields, ex. x-rate-limit-burst
+fn collect(data: &Map<String, Value>, extension: &str) -> Option<Value> {
+    let mut result = match data.get(extension) {
+        Some(Value::Object(map)) => map.clone(),
+        // reported by validation
+        Some(other) => return Some(other.clone()),
+        None => Map::new(),
+    };
+
+    for (key, value) in data {
+        if let Some(field) = key
+            .strip_prefix(extension)
+            .and_then(|f| f.strip_prefix('-'))
+        {
+            result.insert(field.to_snake_case(), value.clone());
+        }
+    }
+
+    if result.is_empty() && !data.contains_key(extension) {
+        None
+    } else {
+        Some(Value::Object(result))
+    }
+}
+
+fn validate(extension: &str, value: &Value, scope: &SchemaScope) -> Result<(), Error> {
+    let schema = schema(extension);
+    let validator = JSONSchema::options()
+        .with_draft(Draft::Draft7)
+        .compile(&schema)
+        .unwrap();
+
+    let result = validator.validate(value);
+
+    match result {
+        Err(errors) => {
+            let messages = errors.map(|e| e.to_string()).collect::<Vec<_>>();
+
+            Err(Error::CodegenInvalidPolicy(
+                format!("{}: {}", extension, messages.join(", ")),
+                scope.to_string(),
+            ))
+        }
+        Ok(_) => Ok(()),
+    }
+}
+
+fn parse<T: serde::de::DeserializeOwned>(
+    data: &Map<String, Value>,
+    extension: &str,
+    scope: &SchemaScope,
+) -> Result<Option<T>, Error> {
+    match collect(data, extension) {
+        Some(value) => {
+            validate(extension, &value, scope)?;
+
+            serde_json::from_value(value).map(Some).map_err(|e| {
+                Error::CodegenInvalidPolicy(format!("{}: {}", extension, e), scope.to_string())
+            })
+        }
+        None => Ok(None),
+    }
+}
+
+pub fn extract(
+    data: &Map<String, Value>,
+    method: &str,
+    scope: &SchemaScope,
+) -> Result<Policies, Error> {
+    let invalid = |extension: &str, value: &Value| {
+        Error::CodegenInvalidPolicy(format!("{}: {}", extension, value), scope.to_string())
+    };
+
+    let timeout = match data.get(TIMEOUT) {
+        Some(value) => Some(
+            value
+                .as_u64()
+                .filter(|t| *t > 0)
+                .ok_or_else(|| invalid(TIMEOUT, value))?,
+        ),
+        None => None,
+    };
+
+    let idempotent = match data.get(IDEMPOTENT) {
+        Some(value) => value.as_bool().ok_or_else(|| invalid(IDEMPOTENT, value))?,
+        None => IDEMPOTENT_METHODS.contains(&method.to_lowercase().as_str()),
+    };
+
+    let retry: Option<Retry> = parse(data, RETRY, scope)?;
+    if retry.is_some() && !idempotent {
+        log::warn!("{}: retry of operation which is not idempotent", scope);
+    }
+
+    // other tools use own shapes of x-rate-limit (ex. string `100/m`), those are skipped
+    let rate_limit = match collect(data, RATE_LIMIT) {
+        Some(Value::Object(map)) if map.contains_key("limit") || map.contains_key("window") => {
+            parse(data, RATE_LIMIT, scope)?
+        }
+        Some(other) => {
+            log::warn!("{}: unknown shape of {}: {}", scope, RATE_LIMIT, other);
+            None
+        }
+        None => None,
+    };
+
+    Ok(Policies {
+        rate_limit,
+        retryable: retry.is_some() && idempotent,
+        retry,
+        timeout,
+        idempotent,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_policies() {
+        let scope = SchemaScope::default();
+
+        let operation = json!({
+            "x-rate-limit": { "limit": 100, "window": "1m" },
+            "x-rate-limit-burst": 20,
+            "x-retry": { "backoff": "linear", "statuses": [503] },
+            "x-retry-max-attempts": 5
+        });
+
+        let policies = extract(operation.as_object().unwrap(), "get", &scope).unwrap();
+
+        let rate_limit = policies.rate_limit.unwrap();
+        assert_eq!(rate_limit.limit, 100);
+        assert_eq!(rate_limit.window, 60);
+        assert_eq!(rate_limit.burst, Some(20));
+
+        let retry = policies.retry.unwrap();
+        assert_eq!(retry.max_attempts, 5);
+        assert_eq!(retry.backoff, "linear");
+        assert_eq!(retry.initial_delay, 100);
+        assert_eq!(retry.statuses, vec![503]);
+
+        let empty = extract(json!({}).as_object().unwrap(), "get", &scope).unwrap();
+        assert_eq!(empty.rate_limit.is_none(), true);
+        assert_eq!(empty.retry.is_none(), true);
+
+        let invalid = json!({ "x-retry": { "backoff": "random" } });
+        assert_eq!(
+            extract(invalid.as_object().unwrap(), "get", &scope).is_err(),
+            true
+        );
+
+        let missing = json!({ "x-rate-limit-limit": 10 });
+        assert_eq!(
+            extract(missing.as_object().unwrap(), "get", &scope).is_err(),
+            true
+        );
+
+        let too_long = json!({ "x-rate-limit": { "limit": 1, "window": "18446744073709551615d" } });
+        assert_eq!(
+            extract(too_long.as_object().unwrap(), "get", &scope).is_err(),
+            true
+        );
+
+        for unknown in &[
+            json!({ "x-rate-limit": "100/m" }),
+            json!({ "x-rate-limit": { "requests": 100, "period": "minute" } }),
+        ] {
+            let policies = extract(unknown.as_object().unwrap(), "get", &scope).unwrap();
+            assert_eq!(policies.rate_limit.is_none(), true);
+        }
+    }
+
+    #[test]
+    fn test_extract_client_policies() {
+        let scope = SchemaScope::default();
+
+        let operation = json!({ "x-timeout-ms": 2500, "x-retry": {} });
+        let policies = extract(operation.as_object().unwrap(), "get", &scope).unwrap();
+        assert_eq!(policies.timeout, Some(2500));
+        assert!(policies.idempotent);
+        assert!(policies.retryable);
+
+        // post is not repeated, unless operation is marked idempotent
+        let policies = extract(operation.as_object().unwrap(), "post", &scope).unwrap();
+        assert!(!policies.idempotent);
+        assert!(!policies.retryable);
+
+        let operation = json!({ "x-idempotent": true, "x-retry": {} });
+        let policies = extract(operation.as_object().unwrap(), "post", &scope).unwrap();
+        assert!(policies.retryable);
+        assert_eq!(policies.timeout, None);
+
+        let operation = json!({ "x-idempotent": false });
+        let policies = extract(operation.as_object().unwrap(), "put", &scope).unwrap();
+        assert!(!policies.idempotent);
+
+        for invalid in [
+            json!({ "x-timeout-ms": "1s" }),
+            json!({ "x-timeout-ms": 0 }),
+            json!({ "x-idempotent": "yes" }),
+        ] {
+            assert!(matches!(
+                extract(invalid.as_object().unwrap(), "get", &scope),
+                Err(Error::CodegenInvalidPolicy(_, _))
+            ));
+        }
+    }
+}
@@ -4,11 +4,11 @@ use crate::{
     resolver::SchemaResolver,
     scope::SchemaScope,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Map;
 use serde_json::Value;
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RequestBody {
     #[serde(rename = "models")]
     pub models: Option<super::MediaModelsContainer>,
@@ -18,6 +18,9 @@ pub struct RequestBody {
 
     #[serde(rename = "description")]
     pub description: Option<String>,
+
+    #[serde(rename = "docs")]
+    pub docs: crate::codegen::docs::Docs,
 }
 
 pub fn extract(
@@ -63,7 +66,7 @@ pub fn extract_requestbody(
 
             scope.glue("request").glue("body");
 
-            let model = super::get_content(data, scope, mcontainer, resolver, options)
+            let model = super::get_content(data, scope, mcontainer, resolver, options, false)
                 .map_or(Ok(None), |v| v.map(Some));
 
             scope.reduce(2);
@@ -72,6 +75,7 @@ pub fn extract_requestbody(
                 models: model?,
                 description,
                 required,
+                docs: crate::codegen::docs::Docs::new(data),
             }))
         }
         _ => Err(Error::CodegenInvalidEndpointProperty(
@@ -7,20 +7,21 @@ use crate::{
     resolver::SchemaResolver,
     scope::SchemaScope,
 };
-use serde::Serialize;
+use inflector::Inflector;
+use serde::{Deserialize, Serialize};
 use serde_json::Map;
 use serde_json::Value;
 
 use super::parameters::Parameter;
 
-#[derive(Debug, Serialize, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Responses {
     pub success: Option<Response>,
     pub all: Vec<Response>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub status_code: u32,
@@ -30,6 +31,51 @@ pub struct Response {
     pub description: Option<String>,
 
     pub headers: Option<Vec<Parameter>>,
+
+    // typed structure of declared headers, named after operation and status code
+    pub headers_model: Option<ResponseHeaders>,
+
+    // operations which may follow this response
+    pub links: Vec<super::links::Link>,
+
+    pub reference: Option<String>,
+
+    pub docs: crate::codegen::docs::Docs,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseHeaders {
+    pub name: String,
+    pub headers: Vec<Parameter>,
+    pub has_required: bool,
+}
+
+impl Responses {
+    // pagination or rate limit headers may be returned as struct next to body model
+    pub fn with_headers_models(mut self, operation: &str) -> Self {
+        for response in self.all.iter_mut().chain(self.success.iter_mut()) {
+            response.headers_model =
+                response
+                    .headers
+                    .as_ref()
+                    .filter(|h| !h.is_empty())
+                    .map(|headers| ResponseHeaders {
+                        name: format!(
+                            "{}{}Headers",
+                            operation.to_pascal_case(),
+                            match response.status_code {
+                                0 => "Default".to_string(),
+                                code => code.to_string(),
+                            }
+                        ),
+                        headers: headers.clone(),
+                        has_required: headers.iter().any(|h| h.required),
+                    });
+        }
+
+        self
+    }
 }
 
 pub fn extract(
@@ -142,6 +188,8 @@ pub fn extract_response(
     resolver: &SchemaResolver,
     options: &JsonSchemaExtractOptions,
 ) -> Result<Response, Error> {
+    let reference = super::get_reference(node);
+
     resolver.resolve(node, scope, |node, scope| match node {
         Value::Object(data) => {
             log::trace!("{}", scope);
@@ -165,7 +213,7 @@ pub fn extract_response(
 
             scope.glue(&status_code.to_string());
 
-            let model = super::get_content(data, scope, mcontainer, resolver, options)
+            let model = super::get_content(data, scope, mcontainer, resolver, options, true)
                 .map_or(Ok(None), |v| v.map(Some));
 
             scope.pop();
@@ -177,13 +225,21 @@ pub fn extract_response(
                         let mut headers: Vec<Parameter> = vec![];
 
                         for (name, param) in headers_map {
-                            headers.push(extract_parameter(
+                            // content-type header is described by content map
+                            if name.eq_ignore_ascii_case("content-type") {
+                                continue;
+                            }
+
+                            let mut header = extract_parameter(
                                 &as_header_node(name, param, scope, resolver)?,
                                 scope,
                                 mcontainer,
                                 resolver,
                                 options,
-                            )?);
+                            )?;
+                            header.reference = super::get_reference(param);
+
+                            headers.push(header);
                         }
 
                         Ok(headers)
@@ -195,11 +251,26 @@ pub fn extract_response(
                 })
                 .map_or(Ok(None), |v| v.map(Some))?;
 
+            let links = match data.get("links") {
+                Some(links) => {
+                    scope.property("links");
+                    let links = super::links::extract(links, scope, resolver)?;
+                    scope.pop();
+
+                    links
+                }
+                None => vec![],
+            };
+
             Ok(Response {
                 models: model?,
                 headers,
+                headers_model: None,
+                links,
                 description,
                 status_code,
+                reference: reference.clone(),
+                docs: crate::codegen::docs::Docs::new(data),
             })
         }
         _ => Err(Error::CodegenInvalidEndpointProperty(
@@ -215,11 +286,11 @@ fn as_header_node(
     scope: &mut SchemaScope,
     resolver: &SchemaResolver,
 ) -> Result<Value, Error> {
-    resolver.resolve(node, scope, |node, _scope| {
+    resolver.resolve(node, scope, |node, scope| {
         let mut parameter = node.clone();
 
         let obj = parameter.as_object_mut().ok_or_else(|| {
-            Error::CodegenInvalidEndpointProperty(format!("header:{}", name), "todo".to_string())
+            Error::CodegenInvalidEndpointProperty(format!("header:{}", name), scope.to_string())
         })?;
 
         obj.insert("in".to_string(), Value::String("header".to_string()));
@@ -274,6 +345,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_headers_models() {
+        let schema = json!({
+            "200": {
+                "description": "Page of users",
+                "headers": {
+                    "X-Total-Count": { "required": true, "schema": { "type": "integer" } },
+                    "Link": { "schema": { "type": "string" } },
+                    "Content-Type": { "schema": { "type": "string" } }
+                }
+            },
+            "default": {
+                "description": "Error",
+                "headers": { "X-Rate-Limit-Remaining": { "schema": { "type": "integer" } } }
+            },
+            "404": { "description": "Not found" }
+        });
+
+        let mut mcontainer = ModelContainer::default();
+        let mut scope = SchemaScope::default();
+        let resolver = SchemaResolver::empty();
+        let options = JsonSchemaExtractOptions::default();
+
+        let responses =
+            extract_responses(&schema, &mut scope, &mut mcontainer, &resolver, &options)
+                .unwrap()
+                .with_headers_models("listUsers");
+
+        let success = responses.success.unwrap().headers_model.unwrap();
+        assert_eq!(success.name, "ListUsers200Headers");
+        assert_eq!(
+            success
+                .headers
+                .iter()
+                .map(|h| h.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["X-Total-Count", "Link"]
+        );
+        assert!(success.has_required);
+
+        let default = responses.all[1].headers_model.as_ref().unwrap();
+        assert_eq!(default.name, "ListUsersDefaultHeaders");
+        assert!(!default.has_required);
+
+        assert!(responses.all[2].headers_model.is_none());
+    }
+
     #[test]
     fn test_no_unique_model() {
         let schema = json!({
@@ -0,0 +1,150 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::endpoint::Endpoint;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Segment {
+    pub value: String,
+
+    // set when the whole segment is a parameter, ex. {id}
+    pub parameter: Option<String>,
+
+    pub position: usize,
+}
+
+// tokenized path, pattern is an anchored regex with one capture group per parameter
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Route {
+    pub segments: Vec<Segment>,
+    pub parameters: Vec<String>,
+    pub pattern: String,
+    pub is_static: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RouteMethod {
+    pub method: String,
+    pub operation: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RouteGroup {
+    pub path: String,
+    pub route: Route,
+    pub methods: Vec<RouteMethod>,
+}
+
+lazy_static! {
+    static ref PARAMETER: Regex = Regex::new(r"\{([^}]+)\}").unwrap();
+}
+
+// printf-like path with parameters replaced by placeholders, ex. /users/{}/groups
+pub fn path_format(path: &str) -> String {
+    PARAMETER.replace_all(path, "{}").to_string()
+}
+
+impl Route {
+    pub fn new(path: &str) -> Self {
+        let segments = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .enumerate()
+            .map(|(position, value)| Segment {
+                value: value.to_string(),
+                parameter: PARAMETER
+                    .captures(value)
+                    .filter(|c| c.get(0).map(|m| m.as_str()) == Some(value))
+                    .map(|c| c[1].to_string()),
+                position,
+            })
+            .collect::<Vec<_>>();
+
+        let parameters = PARAMETER
+            .captures_iter(path)
+            .map(|c| c[1].to_string())
+            .collect::<Vec<_>>();
+
+        let mut pattern = String::from("^");
+        let mut last = 0;
+        for m in PARAMETER.find_iter(path) {
+            pattern.push_str(&regex::escape(&path[last..m.start()]));
+            pattern.push_str("([^/]+)");
+            last = m.end();
+        }
+        pattern.push_str(&regex::escape(&path[last..]));
+        pattern.push('$');
+
+        Self {
+            is_static: parameters.is_empty(),
+            segments,
+            parameters,
+            pattern,
+        }
+    }
+
+    // literal segments are more specific than parameters and have to be matched first
+    fn priority(&self) -> Vec<(bool, &str)> {
+        self.segments
+            .iter()
+            .map(|s| (s.parameter.is_some(), s.value.as_str()))
+            .collect()
+    }
+}
+
+pub fn group(endpoints: &[Endpoint]) -> Vec<RouteGroup> {
+    let mut groups: Vec<RouteGroup> = vec![];
+
+    for endpoint in endpoints {
+        let method = RouteMethod {
+            method: endpoint.get_method().to_string(),
+            operation: endpoint.get_operation().to_string(),
+        };
+
+        match groups.iter_mut().find(|g| g.path == endpoint.get_path()) {
+            Some(group) => group.methods.push(method),
+            None => groups.push(RouteGroup {
+                path: endpoint.get_path().to_string(),
+                route: endpoint.get_route().clone(),
+                methods: vec![method],
+            }),
+        }
+    }
+
+    groups.sort_by(|a, b| a.route.priority().cmp(&b.route.priority()));
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_from_path() {
+        let route = Route::new("/users/{id}/files/{name}.json");
+
+        assert_eq!(route.parameters, vec!["id", "name"]);
+        assert_eq!(route.pattern, r"^/users/([^/]+)/files/([^/]+)\.json$");
+        assert_eq!(route.segments[1].parameter, Some("id".to_string()));
+        assert_eq!(route.segments[3].parameter, None);
+        assert_eq!(route.segments[3].position, 3);
+        assert_eq!(false, route.is_static);
+
+        assert_eq!(
+            true,
+            Regex::new(&route.pattern)
+                .unwrap()
+                .is_match("/users/1/files/a.json")
+        );
+    }
+
+    #[test]
+    fn test_literal_routes_first() {
+        let mut routes = vec![Route::new("/users/{id}"), Route::new("/users/me")];
+
+        routes.sort_by(|a, b| a.priority().cmp(&b.priority()));
+
+        assert_eq!(routes[0].segments[1].value, "me");
+    }
+}
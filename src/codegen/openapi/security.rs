@@ -1,8 +1,8 @@
 use crate::{error::Error, scope::SchemaScope};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(Debug, Serialize, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct SecuritySchemes {
     #[serde(rename = "default")]
     pub default: Vec<SecurityScheme>,
@@ -25,7 +25,7 @@ impl SecuritySchemes {
     }
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SecurityScheme {
     #[serde(rename = "scheme_name")]
     pub scheme_name: String,
@@ -96,11 +96,7 @@ pub fn extract_defaults(
             for (i, scheme) in scheme_names.iter().enumerate() {
                 scope.index(i);
 
-                let security_scheme = extract_default(scheme, scontainer)?;
-
-                if let Some(sec_scheme) = security_scheme {
-                    security_schemes.push(sec_scheme);
-                }
+                security_schemes.extend(extract_default(scheme, scontainer)?);
 
                 scope.pop();
             }
@@ -111,23 +107,22 @@ pub fn extract_defaults(
     }
 }
 
+// all schemes of requirement object are required together (ex. session cookie and csrf header)
 pub fn extract_default(
     node: &Value,
     scontainer: &mut SecuritySchemes,
-) -> Result<Option<SecurityScheme>, Error> {
+) -> Result<Vec<SecurityScheme>, Error> {
     match node {
-        Value::Object(data) => {
-            let mut security_scheme: Option<SecurityScheme> = None;
-            for (scheme_name, _) in data {
-                security_scheme = scontainer
-                    .clone()
+        Value::Object(data) => Ok(data
+            .keys()
+            .filter_map(|scheme_name| {
+                scontainer
                     .all
-                    .into_iter()
-                    .find(|scheme| scheme.scheme_name == *scheme_name);
-            }
-
-            Ok(security_scheme)
-        }
+                    .iter()
+                    .find(|scheme| scheme.scheme_name == *scheme_name)
+                    .cloned()
+            })
+            .collect()),
         _ => Err(Error::CodegenInvalidSecuritySchemeFormat),
     }
 }
@@ -0,0 +1,197 @@
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fs::{self, Permissions},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::error::Error;
+
+// destination of rendered files, disk by default
+pub trait Output {
+    fn write(&self, path: &Path, content: &[u8]) -> Result<(), Error>;
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>, Error>;
+
+    // explicit mode wins over permissions of source file (ex. executable bit of scripts)
+    fn set_permissions(
+        &self,
+        _path: &Path,
+        _source: Option<&Path>,
+        _mode: Option<u32>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    // formatting commands work on files written to disk only
+    fn on_disk(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Clone)]
+pub struct Backend(Rc<dyn Output>);
+
+impl Backend {
+    pub fn new(output: impl Output + 'static) -> Self {
+        Self(Rc::new(output))
+    }
+}
+
+impl Default for Backend {
+    fn default() -> Self {
+        Self::new(Disk)
+    }
+}
+
+impl std::fmt::Debug for Backend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Backend(on_disk: {})", self.0.on_disk())
+    }
+}
+
+impl std::ops::Deref for Backend {
+    type Target = dyn Output;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+pub struct Disk;
+
+impl Output for Disk {
+    fn write(&self, path: &Path, content: &[u8]) -> Result<(), Error> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).map_err(|e| Error::CodegenFileError(e.to_string()))?;
+        }
+
+        fs::write(path, content).map_err(|e| Error::CodegenFileError(e.to_string()))
+    }
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>, Error> {
+        fs::read(path).map_err(|e| Error::CodegenFileError(e.to_string()))
+    }
+
+    fn set_permissions(
+        &self,
+        path: &Path,
+        source: Option<&Path>,
+        mode: Option<u32>,
+    ) -> Result<(), Error> {
+        let permissions = match (mode, source) {
+            (Some(mode), _) => {
+                let mut permissions = fs::metadata(path)
+                    .map_err(|e| Error::CodegenFileError(e.to_string()))?
+                    .permissions();
+                apply_mode(&mut permissions, mode);
+                permissions
+            }
+            (None, Some(source)) => fs::metadata(source)
+                .map_err(|e| Error::CodegenFileError(e.to_string()))?
+                .permissions(),
+            (None, None) => return Ok(()),
+        };
+
+        fs::set_permissions(path, permissions).map_err(|e| Error::CodegenFileError(e.to_string()))
+    }
+
+    fn on_disk(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(unix)]
+fn apply_mode(permissions: &mut Permissions, mode: u32) {
+    use std::os::unix::fs::PermissionsExt;
+    permissions.set_mode(mode);
+}
+
+#[cfg(not(unix))]
+fn apply_mode(permissions: &mut Permissions, mode: u32) {
+    permissions.set_readonly(mode & 0o222 == 0);
+}
+
+// collects rendered files instead of writing them, clones share collected files
+#[derive(Clone, Default)]
+pub struct Memory {
+    files: Rc<RefCell<BTreeMap<PathBuf, Vec<u8>>>>,
+}
+
+impl Memory {
+    pub fn files(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        self.files
+            .borrow()
+            .iter()
+            .map(|(path, content)| (path.clone(), content.clone()))
+            .collect()
+    }
+}
+
+impl Output for Memory {
+    fn write(&self, path: &Path, content: &[u8]) -> Result<(), Error> {
+        self.files
+            .borrow_mut()
+            .insert(path.to_path_buf(), content.to_vec());
+
+        Ok(())
+    }
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>, Error> {
+        self.files
+            .borrow()
+            .get(path)
+            .cloned()
+            .ok_or_else(|| Error::CodegenFileError(format!("not rendered: {:?}", path)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::{create_container, header::Header, renderer, templates::TemplateType};
+    use crate::discovery::Discovered;
+    use crate::schema::Schema;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_in_memory() {
+        let mut discovered = Discovered::default();
+        discovered.templates.insert(
+            "models.j2".to_string(),
+            "{# type=models,filename=%options.name%/models.rs,min_version=0.0.0 #}\nmodels: {{ models | length }}".to_string(),
+        );
+
+        let memory = Memory::default();
+        let schema = Schema::from_json(json!({"info": {"version": "1.0.0"}}));
+
+        let target =
+            std::env::temp_dir().join(format!("schema-tools-memory-test-{}", std::process::id()));
+        let target_dir = target.to_string_lossy().to_string();
+
+        renderer::create(
+            discovered,
+            &[TemplateType::Models],
+            create_container(&[("name".to_string(), json!("client"))]),
+        )
+        .unwrap()
+        .with_header(Some(Header::new(
+            &schema,
+            Some("generated".to_string()),
+            true,
+        )))
+        .with_output(memory.clone())
+        .models(Default::default(), &target_dir, &Some("false".to_string()))
+        .unwrap();
+
+        assert_eq!(
+            memory.files(),
+            vec![(
+                target.join("client/models.rs"),
+                b"// generated\n\nmodels: 0".to_vec()
+            )]
+        );
+        assert_eq!(target.exists(), false);
+    }
+}
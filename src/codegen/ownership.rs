@@ -0,0 +1,376 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::openapi::Openapi;
+use super::symbols::SymbolTable;
+use crate::error::Error;
+use crate::scope::Space;
+
+pub static OWNER_EXTENSION: &str = "x-owner";
+
+// team or list of teams, ex. `@org/identity`
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Owners {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Owners {
+    fn list(&self) -> Vec<String> {
+        match self {
+            Self::One(owner) => vec![owner.clone()],
+            Self::Many(owners) => owners.clone(),
+        }
+    }
+}
+
+// teams of tags, default team owns operations without x-owner and mapped tag
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct OwnersConfig {
+    #[serde(default)]
+    pub tags: BTreeMap<String, Owners>,
+    pub default: Option<Owners>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct OwnedFile {
+    // relative to target directory
+    pub file: String,
+    pub owners: Vec<String>,
+    pub operations: Vec<String>,
+    pub models: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct OwnershipReport {
+    pub files: Vec<OwnedFile>,
+
+    // operations nobody owns, their files are left out of CODEOWNERS
+    pub unowned: Vec<String>,
+}
+
+#[derive(Default)]
+struct Entry {
+    owners: BTreeSet<String>,
+    operations: BTreeSet<String>,
+    models: BTreeSet<String>,
+}
+
+impl OwnersConfig {
+    pub fn from_schema(schema: &crate::schema::Schema) -> Result<Self, Error> {
+        serde_json::from_value(schema.get_body().clone()).map_err(Error::SerdeJsonError)
+    }
+
+    // x-owner of operation wins over teams of its tags
+    fn owners(
+        &self,
+        operation: &str,
+        extension: Option<&Value>,
+        tags: &[String],
+    ) -> Result<Vec<String>, Error> {
+        let mut owners = match extension {
+            Some(value) => serde_json::from_value::<Owners>(value.clone())
+                .map_err(|_| Error::CodegenInvalidOwner(operation.to_string(), value.to_string()))?
+                .list(),
+            None => tags
+                .iter()
+                .filter_map(|t| self.tags.get(t))
+                .flat_map(|o| o.list())
+                .collect(),
+        };
+
+        if owners.is_empty() {
+            owners = self.default.as_ref().map(|o| o.list()).unwrap_or_default();
+        }
+
+        // teams repeated by many tags are listed once, in order of first mention
+        let mut seen = HashSet::new();
+        owners.retain(|o| seen.insert(o.clone()));
+
+        Ok(owners)
+    }
+}
+
+// files of endpoints are owned by teams of their operations, files of models by teams of operations using them
+pub fn report(
+    table: &SymbolTable,
+    openapi: &Openapi,
+    config: &OwnersConfig,
+) -> Result<OwnershipReport, Error> {
+    let mut owners: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut report = OwnershipReport::default();
+
+    for endpoint in &openapi.endpoints {
+        let operation = endpoint.get_operation();
+        let teams = config.owners(
+            operation,
+            endpoint.get_extension(OWNER_EXTENSION),
+            endpoint.get_tags(),
+        )?;
+
+        if teams.is_empty() {
+            report.unowned.push(operation.to_string());
+        }
+
+        owners.insert(operation.to_string(), teams);
+    }
+
+    let mut files: BTreeMap<String, Entry> = BTreeMap::new();
+
+    for (operation, symbol) in &table.endpoints {
+        let entry = files.entry(symbol.file.clone()).or_default();
+        entry.operations.insert(operation.clone());
+        entry
+            .owners
+            .extend(owners.get(operation).cloned().unwrap_or_default());
+    }
+
+    for model in openapi.models.models() {
+        let name = match model.name() {
+            Ok(name) => name,
+            Err(_) => continue,
+        };
+        let symbol = match table.models.get(name) {
+            Some(symbol) => symbol,
+            None => continue,
+        };
+
+        let entry = files.entry(symbol.file.clone()).or_default();
+        entry.models.insert(name.to_string());
+
+        for space in &model.spaces.list {
+            if let Space::Operation(operation) = space {
+                entry
+                    .owners
+                    .extend(owners.get(operation).cloned().unwrap_or_default());
+            }
+        }
+    }
+
+    report.files = files
+        .into_iter()
+        .map(|(file, entry)| OwnedFile {
+            file,
+            owners: entry.owners.into_iter().collect(),
+            operations: entry.operations.into_iter().collect(),
+            models: entry.models.into_iter().collect(),
+        })
+        .collect();
+
+    Ok(report)
+}
+
+// CODEOWNERS paths are anchored at working tree of repository holding target directory,
+// at current directory when it's not versioned
+pub fn repository_root(target: &Path) -> Option<PathBuf> {
+    git2::Repository::discover(target)
+        .ok()
+        .and_then(|r| r.workdir().map(|w| w.to_path_buf()))
+        .or_else(|| std::env::current_dir().ok())
+        .and_then(|r| std::fs::canonicalize(r).ok())
+}
+
+// CODEOWNERS lines of owned files, target directory is given relative to root
+pub fn codeowners(report: &OwnershipReport, target: &Path, root: &Path) -> Result<String, Error> {
+    let relative = target.strip_prefix(root).map_err(|_| {
+        Error::CodegenOwnershipOutsideRoot(target.display().to_string(), root.display().to_string())
+    })?;
+
+    let prefix = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/");
+    let mut result = String::from("# generated by schematools, do not edit\n");
+
+    for file in report.files.iter().filter(|f| !f.owners.is_empty()) {
+        let path = if prefix.is_empty() {
+            format!("/{}", file.file)
+        } else {
+            format!("/{}/{}", prefix, file.file)
+        };
+
+        result.push_str(&format!(
+            "{} {}\n",
+            path.replace(' ', "\\ "),
+            file.owners.join(" ")
+        ));
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::symbols::Symbol;
+    use serde_json::json;
+
+    #[test]
+    fn test_ownership_report() {
+        let schema = crate::schema::Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "info": { "title": "api", "version": "1.0.0" },
+            "paths": {
+                "/users": {
+                    "get": {
+                        "operationId": "listUsers",
+                        "tags": ["users"],
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": { "application/json": { "schema": { "$ref": "#/components/schemas/User" } } }
+                            }
+                        }
+                    }
+                },
+                "/users/{id}/orders": {
+                    "get": {
+                        "operationId": "listUserOrders",
+                        "tags": ["users"],
+                        "x-owner": ["@org/shop", "@org/identity", "@org/shop"],
+                        "parameters": [{ "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }],
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": { "application/json": { "schema": { "$ref": "#/components/schemas/User" } } }
+                            }
+                        }
+                    }
+                },
+                "/health": {
+                    "get": { "operationId": "health", "responses": { "204": { "description": "ok" } } }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": { "type": "object", "properties": { "name": { "type": "string" } } }
+                }
+            }
+        }));
+        let client = reqwest::blocking::Client::new();
+        let storage = crate::storage::SchemaStorage::new(&schema, &client);
+
+        let openapi = crate::codegen::openapi::extract(
+            &schema,
+            &storage,
+            crate::codegen::openapi::OpenapiExtractOptions {
+                wrappers: false,
+                optional_and_nullable_as_models: false,
+                nested_arrays_as_models: false,
+                keep_schema: crate::tools::Filter::new(&[]).unwrap(),
+                single_enum_as_const: false,
+                extension_prefix: None,
+                inflections: Default::default(),
+                identifier_policy: Default::default(),
+                decimal_formats: vec![],
+                formats: Default::default(),
+            },
+        )
+        .unwrap();
+
+        let symbol = |file: &str| Symbol {
+            file: file.to_string(),
+            module: file.trim_end_matches(".rs").to_string(),
+        };
+
+        let mut table = SymbolTable::default();
+        table
+            .endpoints
+            .insert("listUsers".to_string(), symbol("users.rs"));
+        table
+            .endpoints
+            .insert("listUserOrders".to_string(), symbol("users.rs"));
+        table
+            .endpoints
+            .insert("health".to_string(), symbol("health.rs"));
+        table.models.insert("User".to_string(), symbol("models.rs"));
+
+        let config: OwnersConfig = serde_json::from_value(json!({
+            "tags": { "users": "@org/identity", "admin": ["@org/identity"] }
+        }))
+        .unwrap();
+
+        let report = report(&table, &openapi, &config).unwrap();
+        assert_eq!(report.unowned, vec!["health"]);
+        assert_eq!(
+            report.files,
+            vec![
+                OwnedFile {
+                    file: "health.rs".to_string(),
+                    owners: vec![],
+                    operations: vec!["health".to_string()],
+                    models: vec![],
+                },
+                OwnedFile {
+                    file: "models.rs".to_string(),
+                    owners: vec!["@org/identity".to_string(), "@org/shop".to_string()],
+                    operations: vec![],
+                    models: vec!["User".to_string()],
+                },
+                OwnedFile {
+                    file: "users.rs".to_string(),
+                    owners: vec!["@org/identity".to_string(), "@org/shop".to_string()],
+                    operations: vec!["listUserOrders".to_string(), "listUsers".to_string()],
+                    models: vec![],
+                },
+            ]
+        );
+
+        assert_eq!(
+            config
+                .owners(
+                    "listUserOrders",
+                    Some(&json!(["@org/shop", "@org/identity", "@org/shop"])),
+                    &[]
+                )
+                .unwrap(),
+            vec!["@org/shop", "@org/identity"]
+        );
+        assert_eq!(
+            config
+                .owners(
+                    "listUsers",
+                    None,
+                    &["users".to_string(), "admin".to_string()]
+                )
+                .unwrap(),
+            vec!["@org/identity"]
+        );
+
+        assert_eq!(
+            codeowners(
+                &report,
+                Path::new("/work/api/src/client"),
+                Path::new("/work/api")
+            )
+            .unwrap(),
+            "# generated by schematools, do not edit\n\
+             /src/client/models.rs @org/identity @org/shop\n\
+             /src/client/users.rs @org/identity @org/shop\n"
+        );
+        assert!(matches!(
+            codeowners(&report, Path::new("/tmp/client"), Path::new("/work/api")),
+            Err(Error::CodegenOwnershipOutsideRoot(_, _))
+        ));
+
+        let mut directory = std::env::temp_dir();
+        directory.push(format!(
+            "schema-tools-ownership-test-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(directory.join("src/client")).unwrap();
+        git2::Repository::init(&directory).unwrap();
+
+        assert_eq!(
+            repository_root(&directory.join("src/client")),
+            std::fs::canonicalize(&directory).ok()
+        );
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,412 @@
+use std::collections::HashMap;
+
+use crate::discovery::Discovered;
+
+// built-in markdown api reference templates used by `codegen docs` when no template is given
+static DOCS: &[(&str, &str)] = &[
+    (
+        "macros.md.j2",
+        include_str!("../../resources/templates/docs/macros.md.j2"),
+    ),
+    (
+        "tag.md.j2",
+        include_str!("../../resources/templates/docs/tag.md.j2"),
+    ),
+    (
+        "models.md.j2",
+        include_str!("../../resources/templates/docs/models.md.j2"),
+    ),
+    (
+        "changelog.md.j2",
+        include_str!("../../resources/templates/docs/changelog.md.j2"),
+    ),
+    (
+        "readme.md.j2",
+        include_str!("../../resources/templates/docs/readme.md.j2"),
+    ),
+];
+
+// built-in contract test templates used by `codegen contract-tests`, one pack per framework
+static PYTEST: &[(&str, &str)] = &[
+    (
+        "conftest.py.j2",
+        include_str!("../../resources/templates/contract/pytest/conftest.py.j2"),
+    ),
+    (
+        "test.py.j2",
+        include_str!("../../resources/templates/contract/pytest/test.py.j2"),
+    ),
+];
+
+static JEST: &[(&str, &str)] = &[
+    (
+        "setup.js.j2",
+        include_str!("../../resources/templates/contract/jest/setup.js.j2"),
+    ),
+    (
+        "test.js.j2",
+        include_str!("../../resources/templates/contract/jest/test.js.j2"),
+    ),
+];
+
+// built-in request validation middleware templates used by `codegen validation-middleware`
+static AXUM: &[(&str, &str)] = &[
+    (
+        "core.rs.j2",
+        include_str!("../../resources/templates/validation/core.rs.j2"),
+    ),
+    (
+        "validation.rs.j2",
+        include_str!("../../resources/templates/validation/axum/validation.rs.j2"),
+    ),
+];
+
+static ACTIX: &[(&str, &str)] = &[
+    (
+        "core.rs.j2",
+        include_str!("../../resources/templates/validation/core.rs.j2"),
+    ),
+    (
+        "validation.rs.j2",
+        include_str!("../../resources/templates/validation/actix/validation.rs.j2"),
+    ),
+];
+
+// built-in client configuration template used by `codegen client-policies`
+static RUST_POLICIES: &[(&str, &str)] = &[(
+    "policies.rs.j2",
+    include_str!("../../resources/templates/policies/rust/policies.rs.j2"),
+)];
+
+fn discovered(pack: &[(&str, &str)]) -> Discovered {
+    Discovered {
+        templates: pack
+            .iter()
+            .map(|(name, content)| (name.to_string(), content.to_string()))
+            .collect(),
+        files: HashMap::new(),
+        sources: HashMap::new(),
+        manifests: vec![],
+    }
+}
+
+pub fn docs() -> Discovered {
+    discovered(DOCS)
+}
+
+pub fn contract(framework: &str) -> Discovered {
+    match framework {
+        "jest" => discovered(JEST),
+        _ => discovered(PYTEST),
+    }
+}
+
+pub fn validation(framework: &str) -> Discovered {
+    match framework {
+        "actix" => discovered(ACTIX),
+        _ => discovered(AXUM),
+    }
+}
+
+pub fn policies() -> Discovered {
+    discovered(RUST_POLICIES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::{output::Memory, renderer, templates::TemplateType};
+    use serde_json::json;
+
+    #[test]
+    fn test_docs_pack_is_valid() {
+        let templates = crate::codegen::templates::get(docs()).unwrap();
+
+        assert_eq!(
+            true,
+            templates.includes(&[crate::codegen::templates::TemplateType::Endpoints])
+        );
+        assert_eq!(templates.list.len(), 4);
+    }
+
+    #[test]
+    fn test_docs_pack_renders_sample_spec() {
+        let spec = json!({
+            "openapi": "3.0.0",
+            "info": { "title": "api", "version": "1.0.0" },
+            "paths": {
+                "/users/{id}": {
+                    "get": {
+                        "operationId": "getUser",
+                        "tags": ["users"],
+                        "parameters": [
+                            { "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }
+                        ],
+                        "responses": {
+                            "200": {
+                                "description": "found user",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/User" }
+                                    }
+                                }
+                            },
+                            "404": { "description": "missing user" }
+                        }
+                    },
+                    "put": {
+                        "operationId": "updateUser",
+                        "tags": ["users"],
+                        "security": [{ "token": [] }],
+                        "parameters": [
+                            { "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }
+                        ],
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/User" }
+                                }
+                            }
+                        },
+                        "responses": { "204": { "description": "updated" } }
+                    }
+                }
+            },
+            "components": {
+                "securitySchemes": {
+                    "token": { "type": "http", "scheme": "bearer" }
+                },
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "properties": { "name": { "type": "string" } }
+                    }
+                }
+            }
+        });
+
+        let schema = crate::schema::Schema::from_json(spec);
+        let client = reqwest::blocking::Client::new();
+        let storage = crate::storage::SchemaStorage::new(&schema, &client);
+
+        let openapi = crate::codegen::openapi::extract(
+            &schema,
+            &storage,
+            crate::codegen::openapi::OpenapiExtractOptions {
+                wrappers: false,
+                optional_and_nullable_as_models: false,
+                nested_arrays_as_models: false,
+                keep_schema: crate::tools::Filter::new(&[]).unwrap(),
+                single_enum_as_const: false,
+                extension_prefix: None,
+                inflections: Default::default(),
+                identifier_policy: Default::default(),
+                decimal_formats: vec![],
+                formats: Default::default(),
+            },
+        )
+        .unwrap();
+
+        let memory = Memory::default();
+        renderer::create(
+            docs(),
+            &[TemplateType::Endpoints],
+            crate::codegen::create_container(&[]),
+        )
+        .unwrap()
+        .with_output(memory.clone())
+        .openapi(openapi, "docs", &None)
+        .unwrap();
+
+        let files = memory.files();
+        let (_, content) = files
+            .iter()
+            .find(|(path, _)| path.ends_with("users.md"))
+            .unwrap();
+        let content = String::from_utf8(content.clone()).unwrap();
+
+        assert_eq!(true, content.contains("`GET /users/{id}`"));
+        assert_eq!(true, content.contains("| `id` | path | string | yes |"));
+        assert_eq!(
+            true,
+            content.contains("| 200 | [User](models.md#user) | found user |")
+        );
+        assert_eq!(true, content.contains("| 404 |  | missing user |"));
+        assert_eq!(true, content.contains("Security: `token`"));
+        assert_eq!(
+            true,
+            content.contains("- `application/json`: [User](models.md#user)")
+        );
+    }
+
+    #[test]
+    fn test_contract_packs_are_valid() {
+        for framework in crate::codegen::contract::CONTRACT_FRAMEWORKS {
+            let templates = crate::codegen::templates::get(contract(framework)).unwrap();
+
+            assert_eq!(
+                true,
+                templates.includes(&[crate::codegen::templates::TemplateType::Endpoints])
+            );
+            assert_eq!(templates.list.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_validation_packs_are_valid() {
+        for framework in crate::codegen::validation::VALIDATION_FRAMEWORKS {
+            let templates = crate::codegen::templates::get(validation(framework)).unwrap();
+
+            assert_eq!(
+                true,
+                templates.includes(&[crate::codegen::templates::TemplateType::Endpoints])
+            );
+            assert_eq!(templates.list.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_axum_validation_pack_limits_body() {
+        let schema = crate::schema::Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "info": { "title": "api", "version": "1.0.0" },
+            "paths": {
+                "/users": {
+                    "post": {
+                        "requestBody": {
+                            "content": {
+                                "application/json": { "schema": { "type": "object" } }
+                            }
+                        },
+                        "responses": { "204": { "description": "created" } }
+                    }
+                }
+            }
+        }));
+        let client = reqwest::blocking::Client::new();
+        let storage = crate::storage::SchemaStorage::new(&schema, &client);
+
+        let render = |options: &[(String, serde_json::Value)]| {
+            let openapi = crate::codegen::openapi::extract(
+                &schema,
+                &storage,
+                crate::codegen::openapi::OpenapiExtractOptions {
+                    wrappers: false,
+                    optional_and_nullable_as_models: false,
+                    nested_arrays_as_models: false,
+                    keep_schema: crate::tools::Filter::new(&[]).unwrap(),
+                    single_enum_as_const: false,
+                    extension_prefix: None,
+                    inflections: Default::default(),
+                    identifier_policy: Default::default(),
+                    decimal_formats: vec![],
+                    formats: Default::default(),
+                },
+            )
+            .unwrap();
+
+            let mut container = crate::codegen::create_container(options);
+            container.data.insert(
+                "validation".to_string(),
+                serde_json::Value::Object(crate::codegen::validation::rules(schema.get_body())),
+            );
+
+            let memory = Memory::default();
+            renderer::create(validation("axum"), &[TemplateType::Endpoints], container)
+                .unwrap()
+                .with_output(memory.clone())
+                .openapi(openapi, "src", &None)
+                .unwrap();
+
+            String::from_utf8(memory.files().into_iter().next().unwrap().1).unwrap()
+        };
+
+        let content = render(&[]);
+        assert!(content.contains("pub const BODY_LIMIT: usize = 2097152;"));
+        assert!(!content.contains("usize::MAX"));
+
+        let content = render(&[("body_limit".to_string(), json!(1024))]);
+        assert!(content.contains("pub const BODY_LIMIT: usize = 1024;"));
+    }
+
+    #[test]
+    fn test_policies_pack_renders_operation_configs() {
+        let schema = crate::schema::Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "info": { "title": "api", "version": "1.0.0" },
+            "paths": {
+                "/users": {
+                    "get": {
+                        "operationId": "listUsers",
+                        "x-timeout-ms": 2500,
+                        "x-retry": { "backoff": "linear", "max_delay": 1000, "statuses": [503] },
+                        "responses": { "200": { "description": "ok" } }
+                    },
+                    "post": {
+                        "operationId": "createUser",
+                        "x-retry": {},
+                        "responses": { "201": { "description": "created" } }
+                    }
+                }
+            }
+        }));
+        let client = reqwest::blocking::Client::new();
+        let storage = crate::storage::SchemaStorage::new(&schema, &client);
+
+        let openapi = crate::codegen::openapi::extract(
+            &schema,
+            &storage,
+            crate::codegen::openapi::OpenapiExtractOptions {
+                wrappers: false,
+                optional_and_nullable_as_models: false,
+                nested_arrays_as_models: false,
+                keep_schema: crate::tools::Filter::new(&[]).unwrap(),
+                single_enum_as_const: false,
+                extension_prefix: None,
+                inflections: Default::default(),
+                identifier_policy: Default::default(),
+                decimal_formats: vec![],
+                formats: Default::default(),
+            },
+        )
+        .unwrap();
+
+        let memory = Memory::default();
+        renderer::create(
+            policies(),
+            &[TemplateType::Endpoints],
+            crate::codegen::create_container(&[]),
+        )
+        .unwrap()
+        .with_output(memory.clone())
+        .openapi(openapi, "src", &None)
+        .unwrap();
+
+        let files = memory.files();
+        assert!(files[0].0.ends_with("policies.rs"));
+        let content = String::from_utf8(files[0].1.clone()).unwrap();
+
+        assert!(content.contains(
+            r#"pub const LIST_USERS: OperationConfig = OperationConfig {
+    timeout: Some(Duration::from_millis(2500)),
+    idempotent: true,
+    retry: Some(RetryConfig {
+        max_attempts: 3,
+        backoff: Backoff::Linear,
+        initial_delay: Duration::from_millis(100),
+        max_delay: Some(Duration::from_millis(1000)),
+        statuses: &[503],
+    }),
+};"#
+        ));
+
+        // post is not repeated without x-idempotent
+        assert!(content.contains(
+            r#"pub const CREATE_USER: OperationConfig = OperationConfig {
+    timeout: None,
+    idempotent: false,
+    retry: None,
+};"#
+        ));
+    }
+}
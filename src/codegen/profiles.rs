@@ -0,0 +1,110 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use crate::discovery::Discovered;
+use crate::error::Error;
+use crate::schema::{path_to_url, Schema};
+
+#[derive(Debug, Deserialize, Default)]
+struct Manifest {
+    #[serde(default)]
+    profiles: HashMap<String, Map<String, Value>>,
+}
+
+// named sets of options, profiles of project config override ones of templates
+#[derive(Debug, Default)]
+pub struct Profiles {
+    profiles: HashMap<String, Map<String, Value>>,
+}
+
+impl Profiles {
+    pub fn load(discovered: &Discovered, config: &Option<String>) -> Result<Self, Error> {
+        let mut profiles = Self::default();
+
+        let sources = discovered
+            .manifests
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .chain(config.iter().cloned());
+
+        for source in sources {
+            let schema = Schema::load_url(path_to_url(source.clone())?)?;
+            let manifest: Manifest = serde_json::from_value(schema.get_body().clone())
+                .map_err(|e| Error::CodegenInvalidProfiles(format!("{}: {}", source, e)))?;
+
+            profiles.profiles.extend(manifest.profiles);
+        }
+
+        Ok(profiles)
+    }
+
+    // options of selected profiles in order, command line options win
+    pub fn apply(
+        &self,
+        names: &[String],
+        options: &[(String, Value)],
+    ) -> Result<Vec<(String, Value)>, Error> {
+        let mut result: Vec<(String, Value)> = vec![];
+
+        for name in names {
+            let profile = self
+                .profiles
+                .get(name)
+                .ok_or_else(|| Error::CodegenUnknownProfile(name.clone()))?;
+
+            log::info!("using profile: {}", name);
+            result.extend(profile.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+
+        result.extend(options.iter().cloned());
+
+        // last occurrence of option wins
+        let mut merged: Vec<(String, Value)> = vec![];
+        for (key, value) in result {
+            merged.retain(|(k, _)| k != &key);
+            merged.push((key, value));
+        }
+
+        Ok(merged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_apply_profiles() {
+        let manifest: Manifest = serde_json::from_value(json!({
+            "profiles": {
+                "async": { "async": true, "runtime": "tokio" },
+                "tracing": { "tracing": true, "runtime": "async-std" }
+            }
+        }))
+        .unwrap();
+
+        let profiles = Profiles {
+            profiles: manifest.profiles,
+        };
+
+        let options = profiles
+            .apply(
+                &["async".to_string(), "tracing".to_string()],
+                &[("async".to_string(), json!("false"))],
+            )
+            .unwrap();
+
+        assert_eq!(
+            options,
+            vec![
+                ("tracing".to_string(), json!(true)),
+                ("runtime".to_string(), json!("async-std")),
+                ("async".to_string(), json!("false")),
+            ]
+        );
+
+        assert_eq!(profiles.apply(&["sync".to_string()], &[]).is_err(), true);
+    }
+}
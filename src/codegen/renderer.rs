@@ -1,10 +1,17 @@
 use crate::{discovery::Discovered, error::Error};
+use std::sync::{Arc, RwLock};
 use tera::Tera;
 
 pub struct Renderer {
     pub tera: Tera,
     pub templates: super::templates::Templates,
     pub container: super::CodegenContainer,
+    pub header: Option<super::header::Header>,
+    pub symbols: bool,
+    pub group_by: Option<super::grouping::GroupKey>,
+
+    // filled before rendering, read by import_path function
+    table: Arc<RwLock<super::symbols::SymbolTable>>,
 }
 
 // todo: refactor, it should allocate templates only once if same templates are used
@@ -16,12 +23,18 @@ pub fn create(
     let mut tera = Tera::default();
 
     // todo: more borrowing, less allocating
-    tera.add_raw_templates(discovered.templates.clone())
+    let sources = discovered.templates.clone();
+    let imports = super::imports::Imports::load(&discovered)?;
+    let templates = super::templates::get(discovered)?;
+
+    tera.add_raw_templates(templates.bodies(sources))
         .map_err(Error::CodegenTemplatesParseError)?;
 
     super::filters::register(&mut tera);
 
-    let templates = super::templates::get(discovered)?;
+    let table = Arc::new(RwLock::new(super::symbols::SymbolTable::default()));
+    imports.register(&mut tera, table.clone());
+
     if !templates.includes(required) {
         return Err(Error::CodegenMissingRequiredTemplates);
     }
@@ -30,17 +43,67 @@ pub fn create(
         tera,
         templates,
         container,
+        header: None,
+        symbols: false,
+        group_by: None,
+        table,
     })
 }
 
 impl Renderer {
+    pub fn with_header(mut self, header: Option<super::header::Header>) -> Self {
+        self.header = header;
+        self
+    }
+
+    // symbol table of all generated models and endpoints is given to every template
+    pub fn with_symbols(mut self, value: bool) -> Self {
+        self.symbols = value;
+        self
+    }
+
+    // endpoints grouped by key are given to every template as `groups`
+    pub fn with_group_by(mut self, value: Option<super::grouping::GroupKey>) -> Self {
+        self.group_by = value;
+        self
+    }
+
+    pub fn with_strict(mut self, strict: Option<super::strict::Strictness>) -> Self {
+        self.container.strict = strict;
+        self
+    }
+
+    pub fn with_collisions(mut self, on_collision: super::collision::OnCollision) -> Self {
+        self.container.outputs.on_collision = on_collision;
+        self
+    }
+
+    // library users may collect files in memory (super::output::Memory) instead of writing them
+    pub fn with_output(mut self, output: impl super::output::Output + 'static) -> Self {
+        self.container.output = super::output::Backend::new(output);
+        self
+    }
+
+    pub fn with_inflections(
+        mut self,
+        inflections: crate::process::name::word::Inflections,
+    ) -> Self {
+        super::filters::register_inflections(&mut self.tera, inflections);
+        self
+    }
+
     pub fn models(
         &self,
         models: super::jsonschema::ModelContainer,
         target_dir: &str,
         format: &Option<String>,
     ) -> Result<(), Error> {
-        let files = self
+        let format = self.format_command(format);
+        let mut plan = super::templates::RenderPlan::new(&models, &[]);
+        let container = self.prepare(&models, None)?;
+
+        // index templates are rendered last, when all modules are known
+        let mut files = self
             .templates
             .list
             .iter()
@@ -51,24 +114,44 @@ impl Renderer {
                 )
             })
             .collect::<Vec<_>>();
+        files.extend(
+            self.templates
+                .list
+                .iter()
+                .filter(|t| matches!(t, super::templates::Template::Index(_))),
+        );
 
+        let mut written = 0;
         for template in files {
             let files = match template {
-                super::templates::Template::File(t) => t.copy(target_dir),
+                super::templates::Template::File(t) => t.copy(target_dir, &container),
                 super::templates::Template::Models(t) => {
-                    t.render(&self.tera, target_dir, &models, &self.container)
+                    t.render(&self.tera, target_dir, &models, &container, &mut plan)
                 }
                 super::templates::Template::Static(t) => {
-                    t.render(&self.tera, target_dir, &self.container)
+                    t.render(&self.tera, target_dir, &container)
+                }
+                super::templates::Template::Index(t) => {
+                    t.render(&self.tera, target_dir, &plan, &container)
                 }
                 _ => Ok(vec![]),
             }?;
 
+            plan.add_files(target_dir, &files);
+
+            if let Some(header) = &self.header {
+                header.inject(&files, &*container.output)?;
+            }
+
+            written += files.len();
+
             if let Some(command) = format {
                 template.format(command, files)?
             }
         }
 
+        self.summary(written);
+
         Ok(())
     }
 
@@ -78,26 +161,58 @@ impl Renderer {
         target_dir: &str,
         format: &Option<String>,
     ) -> Result<(), Error> {
+        let format = self.format_command(format);
         let mut files: Vec<Vec<String>> = vec![];
+        let mut plan = super::templates::RenderPlan::new(&openapi.models, &openapi.endpoints);
+        let container = self.prepare(&openapi.models, Some(&openapi))?;
 
         for template in &self.templates.list {
             files.push(match template {
-                super::templates::Template::File(t) => t.copy(target_dir),
+                super::templates::Template::File(t) => t.copy(target_dir, &container),
                 super::templates::Template::Static(t) => {
-                    t.render(&self.tera, target_dir, &self.container)
+                    t.render(&self.tera, target_dir, &container)
                 }
                 super::templates::Template::Endpoints(t) => {
-                    t.render(&self.tera, target_dir, &openapi, &self.container)
+                    t.render(&self.tera, target_dir, &openapi, &container, &mut plan)
                 }
                 super::templates::Template::Tags(t) => {
-                    t.render(&self.tera, target_dir, &openapi, &self.container)
+                    t.render(&self.tera, target_dir, &openapi, &container)
                 }
-                super::templates::Template::Models(t) => {
-                    t.render(&self.tera, target_dir, &openapi.models, &self.container)
+                super::templates::Template::Models(t) => t.render(
+                    &self.tera,
+                    target_dir,
+                    &openapi.models,
+                    &container,
+                    &mut plan,
+                ),
+                super::templates::Template::Index(_) | super::templates::Template::Samples(_) => {
+                    Ok(vec![])
                 }
             }?);
         }
 
+        for list in &files {
+            plan.add_files(target_dir, list);
+        }
+
+        // index templates are rendered last, when all modules are known
+        for (i, template) in self.templates.list.iter().enumerate() {
+            if let super::templates::Template::Index(t) = template {
+                files[i] = t.render(&self.tera, target_dir, &plan, &container)?;
+            }
+        }
+
+        if let Some(header) = &self.header {
+            for (i, list) in files.iter().enumerate() {
+                if !matches!(
+                    self.templates.list.get(i),
+                    Some(super::templates::Template::File(_))
+                ) {
+                    header.inject(list, &*container.output)?;
+                }
+            }
+        }
+
         if let Some(command) = format {
             for (i, list) in files.iter().enumerate() {
                 let template = &self.templates.list.get(i).unwrap();
@@ -106,6 +221,182 @@ impl Renderer {
             }
         }
 
+        self.summary(files.iter().map(|list| list.len()).sum());
+
         Ok(())
     }
+
+    // owners of files which will be rendered for openapi, known before rendering
+    pub fn ownership(
+        &self,
+        openapi: &super::openapi::Openapi,
+        config: &super::ownership::OwnersConfig,
+    ) -> Result<super::ownership::OwnershipReport, Error> {
+        let table = super::symbols::SymbolTable::build(
+            &self.templates,
+            &openapi.models,
+            Some(openapi),
+            &self.container,
+        )?;
+
+        super::ownership::report(&table, openapi, config)
+    }
+
+    // code samples of all samples templates, in order of templates
+    pub fn samples(
+        &self,
+        openapi: &super::openapi::Openapi,
+    ) -> Result<Vec<super::samples::Sample>, Error> {
+        let mut samples = vec![];
+
+        for template in &self.templates.list {
+            if let super::templates::Template::Samples(t) = template {
+                samples.extend(t.render(&self.tera, openapi, &self.container)?);
+            }
+        }
+
+        Ok(samples)
+    }
+
+    // symbols are computed before rendering, so templates may import from files rendered later
+    fn prepare(
+        &self,
+        models: &super::jsonschema::ModelContainer,
+        openapi: Option<&super::openapi::Openapi>,
+    ) -> Result<super::CodegenContainer, Error> {
+        let mut container = self.container.clone();
+
+        if self.symbols {
+            let table =
+                super::symbols::SymbolTable::build(&self.templates, models, openapi, &container)?;
+            container
+                .data
+                .insert("symbols".to_string(), serde_json::to_value(&table).unwrap());
+
+            *self.table.write().unwrap() = table;
+        }
+
+        if let (Some(key), Some(openapi)) = (&self.group_by, openapi) {
+            container.data.insert(
+                "groups".to_string(),
+                serde_json::to_value(key.groups(openapi)).unwrap(),
+            );
+        }
+
+        Ok(container)
+    }
+
+    // formatting commands need files written to disk
+    fn format_command<'a>(&self, format: &'a Option<String>) -> Option<&'a String> {
+        if format.is_some() && !self.container.output.on_disk() {
+            log::warn!("Formatting skipped, rendered files are not written to disk");
+            return None;
+        }
+
+        format.as_ref()
+    }
+
+    fn summary(&self, written: usize) {
+        let skipped = self.container.skipped.borrow();
+
+        log::info!(
+            "rendered {} files, skipped {} empty files",
+            written,
+            skipped.len()
+        );
+
+        for file in skipped.iter() {
+            log::info!("skipped empty: {}", file);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::{output::Memory, templates::TemplateType};
+    use serde_json::json;
+
+    #[test]
+    fn test_render_index_after_grouped_endpoints() {
+        let spec = json!({
+            "openapi": "3.0.0",
+            "info": { "title": "api", "version": "1.0.0" },
+            "paths": {
+                "/users": {
+                    "get": { "operationId": "listUsers", "tags": ["users"], "responses": {} }
+                },
+                "/teams": {
+                    "get": { "operationId": "listTeams", "tags": ["teams"], "responses": {} }
+                }
+            }
+        });
+
+        let mut discovered = Discovered::default();
+        discovered.templates.insert(
+            "client.j2".to_string(),
+            concat!(
+                "{# type=endpoints,filename=api/%tag%.rs,group_by=tag,min_version=0.0.0 #}\n",
+                "{% for endpoint in endpoints %}fn {{ endpoint.operation | snakecase }}() {}\n{% endfor %}"
+            )
+            .to_string(),
+        );
+        discovered.templates.insert(
+            "mod.j2".to_string(),
+            concat!(
+                "{# type=index,filename=api/mod.rs,min_version=0.0.0 #}\n",
+                "{% for module in modules %}pub mod {{ module.name | snakecase }}; // {{ module.files | join(sep=\",\") }}\n{% endfor %}",
+                "// files: {{ files | length }}, endpoints: {{ endpoints | length }}"
+            )
+            .to_string(),
+        );
+
+        let schema = crate::schema::Schema::from_json(spec);
+        let client = reqwest::blocking::Client::new();
+        let storage = crate::storage::SchemaStorage::new(&schema, &client);
+
+        let openapi = crate::codegen::openapi::extract(
+            &schema,
+            &storage,
+            crate::codegen::openapi::OpenapiExtractOptions {
+                wrappers: false,
+                optional_and_nullable_as_models: false,
+                nested_arrays_as_models: false,
+                keep_schema: crate::tools::Filter::new(&[]).unwrap(),
+                single_enum_as_const: false,
+                extension_prefix: None,
+                inflections: Default::default(),
+                identifier_policy: Default::default(),
+                decimal_formats: vec![],
+                formats: Default::default(),
+            },
+        )
+        .unwrap();
+
+        let memory = Memory::default();
+        create(
+            discovered,
+            &[TemplateType::Endpoints],
+            crate::codegen::create_container(&[]),
+        )
+        .unwrap()
+        .with_output(memory.clone())
+        .openapi(openapi, "out", &None)
+        .unwrap();
+
+        let files = memory.files();
+        assert_eq!(
+            files
+                .iter()
+                .map(|(path, _)| path.to_str().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["out/api/Teams.rs", "out/api/Users.rs", "out/api/mod.rs"]
+        );
+
+        // index sees modules and files of all other templates, relative to target directory
+        assert_eq!(
+            String::from_utf8(files[2].1.clone()).unwrap(),
+            "\npub mod teams; // api/Teams.rs\npub mod users; // api/Users.rs\n// files: 2, endpoints: 2"
+        );
+    }
 }
@@ -0,0 +1,153 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+
+// snippet of one operation rendered by samples template
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Sample {
+    pub path: String,
+    pub method: String,
+    pub lang: String,
+    pub label: String,
+    pub source: String,
+}
+
+// samples replace entries of the same lang and label, so repeated injection is idempotent
+pub fn inject(spec: &mut Value, samples: &[Sample], extension: &str) -> usize {
+    let mut injected = 0;
+
+    for sample in samples {
+        let operation = match spec
+            .get_mut("paths")
+            .and_then(|p| p.get_mut(&sample.path))
+            .and_then(|p| p.get_mut(&sample.method))
+            .and_then(|o| o.as_object_mut())
+        {
+            Some(operation) => operation,
+            None => {
+                log::warn!("{} {}: operation not found", sample.method, sample.path);
+                continue;
+            }
+        };
+
+        let entries = operation.entry(extension).or_insert_with(|| json!([]));
+        if !entries.is_array() {
+            *entries = json!([]);
+        }
+
+        let list = entries.as_array_mut().unwrap();
+        let entry = json!({
+            "lang": sample.lang,
+            "label": sample.label,
+            "source": sample.source,
+        });
+
+        match list
+            .iter_mut()
+            .find(|e| e.get("lang") == entry.get("lang") && e.get("label") == entry.get("label"))
+        {
+            Some(existing) => *existing = entry,
+            None => list.push(entry),
+        }
+
+        injected += 1;
+    }
+
+    injected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::templates;
+    use crate::discovery::Discovered;
+
+    fn spec() -> Value {
+        json!({
+            "openapi": "3.0.0",
+            "info": { "title": "api", "version": "1.0.0" },
+            "paths": {
+                "/users": {
+                    "get": {
+                        "operationId": "listUsers",
+                        "responses": { "200": { "description": "ok" } },
+                        "x-codeSamples": [{ "lang": "shell", "label": "curl", "source": "curl /users" }]
+                    },
+                    "delete": {
+                        "operationId": "deleteUsers",
+                        "responses": { "204": { "description": "deleted" } }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_render_and_inject_samples() {
+        let mut discovered = Discovered::default();
+        discovered.templates.insert(
+            "samples/python.j2".to_string(),
+            concat!(
+                "{# type=samples,lang=python,label=Python SDK,min_version=0.0.0 #}\n",
+                "{% if endpoint.method != \"delete\" %}client.{{ endpoint.operation | snakecase }}()\n{% endif %}\n"
+            )
+            .to_string(),
+        );
+
+        let schema = crate::schema::Schema::from_json(spec());
+        let client = reqwest::blocking::Client::new();
+        let storage = crate::storage::SchemaStorage::new(&schema, &client);
+
+        let openapi = crate::codegen::openapi::extract(
+            &schema,
+            &storage,
+            crate::codegen::openapi::OpenapiExtractOptions {
+                wrappers: false,
+                optional_and_nullable_as_models: false,
+                nested_arrays_as_models: false,
+                keep_schema: crate::tools::Filter::new(&[]).unwrap(),
+                single_enum_as_const: false,
+                extension_prefix: None,
+                inflections: Default::default(),
+                identifier_policy: Default::default(),
+                decimal_formats: vec![],
+                formats: Default::default(),
+            },
+        )
+        .unwrap();
+
+        let renderer = crate::codegen::renderer::create(
+            discovered,
+            &[templates::TemplateType::Samples],
+            crate::codegen::create_container(&[]),
+        )
+        .unwrap();
+
+        let samples = renderer.samples(&openapi).unwrap();
+        assert_eq!(
+            samples,
+            vec![Sample {
+                path: "/users".to_string(),
+                method: "get".to_string(),
+                lang: "python".to_string(),
+                label: "Python SDK".to_string(),
+                source: "client.list_users()".to_string(),
+            }]
+        );
+
+        let mut result = spec();
+        assert_eq!(inject(&mut result, &samples, "x-codeSamples"), 1);
+        assert_eq!(inject(&mut result, &samples, "x-codeSamples"), 1);
+
+        assert_eq!(
+            result["paths"]["/users"]["get"]["x-codeSamples"],
+            json!([
+                { "lang": "shell", "label": "curl", "source": "curl /users" },
+                { "lang": "python", "label": "Python SDK", "source": "client.list_users()" }
+            ])
+        );
+        assert_eq!(
+            result["paths"]["/users"]["delete"].get("x-codeSamples"),
+            None
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use serde_json::Value;
+
+// null values are removed from render context, so templates using them fail
+// instead of rendering empty strings, guarded usage ({% if %}, default filter) still works
+#[derive(Debug, Clone, Default)]
+pub struct Strictness {
+    // dotted paths (or their suffixes) of intentionally optional values, ex. docs.description
+    allowed: Vec<String>,
+}
+
+impl Strictness {
+    pub fn new(allowed: &[String]) -> Self {
+        Self {
+            allowed: allowed.to_vec(),
+        }
+    }
+
+    pub fn apply(&self, value: &mut Value) {
+        self.walk(value, "");
+    }
+
+    fn walk(&self, value: &mut Value, path: &str) {
+        match value {
+            Value::Object(map) => {
+                map.retain(|key, value| {
+                    !value.is_null() || self.is_allowed(&format!("{}.{}", path, key))
+                });
+
+                for (key, child) in map.iter_mut() {
+                    self.walk(child, &format!("{}.{}", path, key));
+                }
+            }
+            // items of lists share path of list
+            Value::Array(list) => {
+                for child in list {
+                    self.walk(child, path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn is_allowed(&self, path: &str) -> bool {
+        self.allowed
+            .iter()
+            .any(|a| path.ends_with(&format!(".{}", a)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_strict_context() {
+        let mut context = json!({
+            "endpoint": {
+                "description": null,
+                "docs": { "title": null, "description": null },
+                "parameters": [{ "name": "id", "description": null }]
+            },
+            "options": { "version": null }
+        });
+
+        Strictness::new(&[
+            "docs.description".to_string(),
+            "options.version".to_string(),
+        ])
+        .apply(&mut context);
+
+        assert_eq!(
+            context,
+            json!({
+                "endpoint": {
+                    "docs": { "description": null },
+                    "parameters": [{ "name": "id" }]
+                },
+                "options": { "version": null }
+            })
+        );
+
+        let mut tera = tera::Tera::default();
+        tera.add_raw_templates(vec![
+            ("plain", "{{ endpoint.description }}"),
+            (
+                "guarded",
+                "{% if endpoint.description %}x{% endif %}{{ endpoint.docs.description }}",
+            ),
+        ])
+        .unwrap();
+
+        let ctx = tera::Context::from_value(context).unwrap();
+        assert_eq!(tera.render("plain", &ctx).is_err(), true);
+        assert_eq!(tera.render("guarded", &ctx).unwrap(), "");
+    }
+}
@@ -0,0 +1,168 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::jsonschema::ModelContainer;
+use super::openapi::Openapi;
+use super::templates::{Template, Templates};
+use super::CodegenContainer;
+use crate::error::Error;
+
+// file declaring model or endpoint, relative to target directory
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Symbol {
+    pub file: String,
+
+    // file without extension, templates turn it into import path of their language
+    pub module: String,
+}
+
+// everything which will be generated, known before first file is rendered
+#[derive(Debug, Default, Serialize, Clone)]
+pub struct SymbolTable {
+    pub models: BTreeMap<String, Symbol>,
+    pub endpoints: BTreeMap<String, Symbol>,
+}
+
+impl SymbolTable {
+    // first template declaring a name wins, ex. models file before validators of the same models
+    pub fn build(
+        templates: &Templates,
+        models: &ModelContainer,
+        openapi: Option<&Openapi>,
+        container: &CodegenContainer,
+    ) -> Result<Self, Error> {
+        let mut table = Self::default();
+
+        for template in &templates.list {
+            match template {
+                Template::Models(t) => {
+                    for (name, file) in t.symbols(models, container)? {
+                        add(&mut table.models, name, file);
+                    }
+                }
+                Template::Endpoints(t) => {
+                    if let Some(openapi) = openapi {
+                        for (operation, file) in t.symbols(openapi, container)? {
+                            add(&mut table.endpoints, operation, file);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(table)
+    }
+}
+
+fn add(symbols: &mut BTreeMap<String, Symbol>, name: String, file: String) {
+    let module = Path::new(&file)
+        .with_extension("")
+        .to_string_lossy()
+        .to_string();
+
+    symbols.entry(name).or_insert(Symbol { file, module });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::Discovered;
+    use serde_json::json;
+
+    #[test]
+    fn test_symbols_of_grouped_templates() {
+        let spec = json!({
+            "openapi": "3.0.0",
+            "info": { "title": "api", "version": "1.0.0" },
+            "paths": {
+                "/users": {
+                    "get": {
+                        "operationId": "listUsers",
+                        "tags": ["users"],
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/User" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "properties": { "address": { "$ref": "#/components/schemas/Address" } }
+                    },
+                    "Address": {
+                        "type": "object",
+                        "properties": { "street": { "type": "string" } }
+                    }
+                }
+            }
+        });
+
+        let mut discovered = Discovered::default();
+        discovered.templates.insert(
+            "models.j2".to_string(),
+            "{# type=models,filename=src/models/%tag%.rs,group_by=tag,min_version=0.0.0 #}\n"
+                .to_string(),
+        );
+        discovered.templates.insert(
+            "client.j2".to_string(),
+            "{# type=endpoints,filename=src/client/%tag%.rs,group_by=tag,min_version=0.0.0 #}\n"
+                .to_string(),
+        );
+
+        let schema = crate::schema::Schema::from_json(spec);
+        let client = reqwest::blocking::Client::new();
+        let storage = crate::storage::SchemaStorage::new(&schema, &client);
+
+        let openapi = crate::codegen::openapi::extract(
+            &schema,
+            &storage,
+            crate::codegen::openapi::OpenapiExtractOptions {
+                wrappers: false,
+                optional_and_nullable_as_models: false,
+                nested_arrays_as_models: false,
+                keep_schema: crate::tools::Filter::new(&[]).unwrap(),
+                single_enum_as_const: false,
+                extension_prefix: None,
+                inflections: Default::default(),
+                identifier_policy: Default::default(),
+                decimal_formats: vec![],
+                formats: Default::default(),
+            },
+        )
+        .unwrap();
+
+        let templates = crate::codegen::templates::get(discovered).unwrap();
+        let table = SymbolTable::build(
+            &templates,
+            &openapi.models,
+            Some(&openapi),
+            &crate::codegen::create_container(&[]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            table.endpoints.get("listUsers"),
+            Some(&Symbol {
+                file: "src/client/Users.rs".to_string(),
+                module: "src/client/Users".to_string(),
+            })
+        );
+
+        // shared models live in common module, endpoints of users tag import them from there
+        assert_eq!(
+            table.models.get("Address").map(|s| s.module.as_str()),
+            Some("src/models/Common")
+        );
+    }
+}
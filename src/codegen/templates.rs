@@ -4,7 +4,11 @@ use tera::Context;
 use tera::Tera;
 
 use crate::{discovery::Discovered, error::Error, tools};
-use std::{collections::HashMap, fs::File, io::Write, path::PathBuf, process::Command};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
 use super::openapi::Openapi;
 use inflector::Inflector;
@@ -20,7 +24,9 @@ pub enum Template {
     Endpoints(EndpointsTemplate),
     Tags(TagsTemplate),
     Static(StaticTemplate),
+    Index(IndexTemplate),
     File(FileTemplate),
+    Samples(SamplesTemplate),
 }
 
 #[derive(Debug)]
@@ -30,6 +36,7 @@ pub struct EndpointsTemplate {
     content_type: String,
     condition: Option<Condition>,
     group_by: GroupBy,
+    on_empty: OnEmpty,
 }
 
 #[derive(Debug)]
@@ -38,6 +45,7 @@ pub struct TagsTemplate {
     filename: Filename,
     content_type: String,
     condition: Option<Condition>,
+    on_empty: OnEmpty,
 }
 
 #[derive(Debug)]
@@ -45,6 +53,90 @@ pub struct ModelsTemplate {
     relative: PathBuf,
     filename: Filename,
     condition: Option<Condition>,
+    group_by: GroupBy,
+    on_empty: OnEmpty,
+}
+
+// rendered after all other templates, receives whole render plan
+#[derive(Debug)]
+pub struct IndexTemplate {
+    relative: PathBuf,
+    filename: Filename,
+    condition: Option<Condition>,
+    on_empty: OnEmpty,
+}
+
+// rendered once per endpoint into code sample injected to specification, not written to disk
+#[derive(Debug)]
+pub struct SamplesTemplate {
+    relative: PathBuf,
+    lang: String,
+    label: String,
+    content_type: String,
+    condition: Option<Condition>,
+}
+
+// models used by several tags (or none) are grouped in this module
+static COMMON_MODULE: &str = "common";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Module {
+    name: String,
+    files: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct RenderPlan {
+    modules: Vec<Module>,
+    files: Vec<String>,
+    models: super::jsonschema::ModelContainer,
+    endpoints: Vec<super::openapi::endpoint::Endpoint>,
+}
+
+fn relative_files<'a>(
+    target_dir: &'a str,
+    files: &'a [String],
+) -> impl Iterator<Item = String> + 'a {
+    files.iter().map(move |f| {
+        f.strip_prefix(target_dir)
+            .map(|s| s.trim_start_matches('/'))
+            .unwrap_or(f)
+            .to_string()
+    })
+}
+
+impl RenderPlan {
+    pub fn new(
+        models: &super::jsonschema::ModelContainer,
+        endpoints: &[super::openapi::endpoint::Endpoint],
+    ) -> Self {
+        Self {
+            modules: vec![],
+            files: vec![],
+            models: models.clone(),
+            endpoints: endpoints.to_vec(),
+        }
+    }
+
+    pub fn add_files(&mut self, target_dir: &str, files: &[String]) {
+        for file in relative_files(target_dir, files) {
+            if !self.files.contains(&file) {
+                self.files.push(file);
+            }
+        }
+    }
+
+    pub fn add(&mut self, name: String, target_dir: &str, files: &[String]) {
+        let relative = relative_files(target_dir, files);
+
+        match self.modules.iter_mut().find(|m| m.name == name) {
+            Some(module) => module.files.extend(relative),
+            None => self.modules.push(Module {
+                name,
+                files: relative.collect(),
+            }),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -52,6 +144,11 @@ pub struct StaticTemplate {
     relative: PathBuf,
     filename: Filename,
     condition: Option<Condition>,
+    on_empty: OnEmpty,
+    // header line is not rendered, so output may start with shebang
+    templated: bool,
+    mode: Option<u32>,
+    source: Option<PathBuf>,
 }
 
 #[derive(Debug)]
@@ -71,6 +168,8 @@ pub struct TagContainer {
 
 pub trait Group {
     fn process(&self, openapi: &mut Openapi, container: &mut super::CodegenContainer);
+
+    fn module(&self) -> Option<String>;
 }
 
 #[derive(Debug)]
@@ -83,6 +182,15 @@ pub struct FileTemplate {
 pub enum TemplateType {
     Models,
     Endpoints,
+    Samples,
+}
+
+// what to do with rendered file which is empty or whitespace only
+#[derive(Debug, Clone, PartialEq)]
+pub enum OnEmpty {
+    Skip,
+    Write,
+    Error,
 }
 
 #[derive(Debug, Clone)]
@@ -100,6 +208,20 @@ impl Filename {
     }
 }
 
+impl OnEmpty {
+    pub fn from(config: &HashMap<&str, Value>) -> Result<Self, Error> {
+        match config.get("on_empty").and_then(|v| v.as_str()) {
+            None | Some("skip") => Ok(Self::Skip),
+            Some("write") => Ok(Self::Write),
+            Some("error") => Ok(Self::Error),
+            Some(other) => Err(Error::CodegenFileHeaderParseError(format!(
+                "on_empty: {}",
+                other
+            ))),
+        }
+    }
+}
+
 impl Condition {
     pub fn from(kv: &str) -> Result<Self, Error> {
         Ok(Self { kv: kv.to_string() })
@@ -154,6 +276,11 @@ impl Group for TagGroup {
         openapi
             .endpoints
             .retain(|s| s.get_tags().contains(&self.tag));
+        openapi.routes = super::openapi::router::group(&openapi.endpoints);
+    }
+
+    fn module(&self) -> Option<String> {
+        Some(self.tag.to_pascal_case())
     }
 }
 
@@ -196,15 +323,43 @@ impl Group for GroupType {
             Self::NoGroup => {}
         }
     }
+
+    fn module(&self) -> Option<String> {
+        match &self {
+            Self::TagGroup(t) => t.module(),
+            Self::NoGroup => None,
+        }
+    }
 }
 
 impl Templates {
+    // bodies registered in tera, header of static-templated files is dropped
+    pub fn bodies(&self, mut sources: HashMap<String, String>) -> HashMap<String, String> {
+        for template in &self.list {
+            if let Template::Static(t) = template {
+                if t.templated {
+                    let relative = t.relative.to_string_lossy().to_string();
+
+                    if let Some(content) = sources.get_mut(&relative) {
+                        *content = content
+                            .find('\n')
+                            .map(|pos| content[pos + 1..].to_string())
+                            .unwrap_or_default();
+                    }
+                }
+            }
+        }
+
+        sources
+    }
+
     pub fn includes(&self, types: &[TemplateType]) -> bool {
         self.list
             .iter()
             .filter_map(|t| match *t {
                 Template::Models(_) => Some(TemplateType::Models),
                 Template::Endpoints(_) => Some(TemplateType::Endpoints),
+                Template::Samples(_) => Some(TemplateType::Samples),
                 _ => None,
             })
             .filter(|f| types.contains(f))
@@ -218,7 +373,11 @@ impl Template {
         Template::File(FileTemplate { relative, path })
     }
 
-    fn from_content(relative: String, content: String) -> Result<Self, Error> {
+    fn from_content(
+        relative: String,
+        content: String,
+        source: Option<PathBuf>,
+    ) -> Result<Self, Error> {
         let first = content.lines().next();
 
         if let Some(line) = first {
@@ -259,7 +418,14 @@ impl Template {
                     "endpoints" => EndpointsTemplate::from(PathBuf::from(relative), &params),
                     "models" => ModelsTemplate::from(PathBuf::from(relative), &params),
                     "tags" => TagsTemplate::from(PathBuf::from(relative), &params),
-                    "static" => StaticTemplate::from(PathBuf::from(relative), &params),
+                    "static" => {
+                        StaticTemplate::from(PathBuf::from(relative), &params, false, source)
+                    }
+                    "static-templated" => {
+                        StaticTemplate::from(PathBuf::from(relative), &params, true, source)
+                    }
+                    "index" => IndexTemplate::from(PathBuf::from(relative), &params),
+                    "samples" => SamplesTemplate::from(PathBuf::from(relative), &params),
                     _ => Err(Error::CodegenFileHeaderRequired("type".to_string())),
                 })
                 .unwrap()
@@ -294,25 +460,44 @@ impl Template {
 }
 
 impl StaticTemplate {
-    pub fn from(relative: PathBuf, config: &HashMap<&str, Value>) -> Result<Template, Error> {
-        let filename = Filename::from(
-            config
-                .get("filename")
-                .ok_or_else(|| Error::CodegenFileHeaderRequired("filename".to_string()))?
-                .as_str()
-                .unwrap()
+    pub fn from(
+        relative: PathBuf,
+        config: &HashMap<&str, Value>,
+        templated: bool,
+        source: Option<PathBuf>,
+    ) -> Result<Template, Error> {
+        // static-templated file lands next to its template by default, ex. gradlew.j2 -> gradlew
+        let filename = match config.get("filename") {
+            Some(filename) => filename.as_str().unwrap().to_string(),
+            None if templated => relative
+                .to_string_lossy()
+                .trim_end_matches(".j2")
                 .to_string(),
-        );
+            None => return Err(Error::CodegenFileHeaderRequired("filename".to_string())),
+        };
 
         let condition = config
             .get("if")
             .map(|s| Condition::from(s.as_str().unwrap()))
             .map_or(Ok(None), |v| v.map(Some))?;
 
+        let mode = config
+            .get("mode")
+            .and_then(|v| v.as_str())
+            .map(|m| {
+                u32::from_str_radix(m, 8)
+                    .map_err(|_| Error::CodegenFileHeaderParseError(format!("mode: {}", m)))
+            })
+            .map_or(Ok(None), |v| v.map(Some))?;
+
         Ok(Template::Static(Self {
             relative,
-            filename,
+            filename: Filename::from(filename),
             condition,
+            on_empty: OnEmpty::from(config)?,
+            templated,
+            mode,
+            source,
         }))
     }
 
@@ -328,7 +513,7 @@ impl StaticTemplate {
             .map(|s| s.check(container))
             .unwrap_or(true)
         {
-            process_render(
+            let files = process_render(
                 tera,
                 serde_json::json!({}),
                 PathBuf::from(format!(
@@ -338,7 +523,18 @@ impl StaticTemplate {
                 )),
                 self.relative.clone(),
                 container,
-            )
+                &self.on_empty,
+            )?;
+
+            for file in &files {
+                container.output.set_permissions(
+                    Path::new(file),
+                    self.source.as_deref(),
+                    self.mode,
+                )?;
+            }
+
+            Ok(files)
         } else {
             log::info!("Template skipped due to condition: {:?}", self.relative);
 
@@ -379,6 +575,7 @@ impl EndpointsTemplate {
             content_type,
             condition,
             group_by,
+            on_empty: OnEmpty::from(config)?,
         }))
     }
 
@@ -388,6 +585,7 @@ impl EndpointsTemplate {
         target_dir: &str,
         openapi: &super::openapi::Openapi,
         container: &super::CodegenContainer,
+        plan: &mut RenderPlan,
     ) -> Result<Vec<String>, Error> {
         let mut result = vec![];
 
@@ -411,7 +609,7 @@ impl EndpointsTemplate {
                 .unwrap_or(true)
             {
                 // render
-                result.append(&mut process_render(
+                let mut files = process_render(
                     tera,
                     openapi,
                     PathBuf::from(format!(
@@ -421,7 +619,14 @@ impl EndpointsTemplate {
                     )),
                     self.relative.clone(),
                     &container,
-                )?)
+                    &self.on_empty,
+                )?;
+
+                if let Some(module) = group.module() {
+                    plan.add(module, target_dir, &files);
+                }
+
+                result.append(&mut files);
             } else {
                 log::info!("Template skipped due to condition: {:?}", self.relative);
             }
@@ -429,6 +634,41 @@ impl EndpointsTemplate {
 
         Ok(result)
     }
+
+    // operations with files declaring them, as render would group them
+    pub fn symbols(
+        &self,
+        openapi: &super::openapi::Openapi,
+        container: &super::CodegenContainer,
+    ) -> Result<Vec<(String, String)>, Error> {
+        let mut result = vec![];
+
+        for group in self.group_by.split(openapi) {
+            let mut openapi = openapi.clone();
+            let mut container = container.clone();
+
+            group.process(&mut openapi, &mut container);
+
+            if !self
+                .condition
+                .as_ref()
+                .map(|s| s.check(&container))
+                .unwrap_or(true)
+            {
+                continue;
+            }
+
+            let file = self.filename.resolve(&container)?;
+            result.extend(
+                openapi
+                    .endpoints
+                    .iter()
+                    .map(|e| (e.get_operation().to_string(), file.clone())),
+            );
+        }
+
+        Ok(result)
+    }
 }
 
 impl TagsTemplate {
@@ -457,6 +697,7 @@ impl TagsTemplate {
             filename,
             content_type,
             condition,
+            on_empty: OnEmpty::from(config)?,
         }))
     }
 
@@ -508,6 +749,7 @@ impl TagsTemplate {
                 )),
                 self.relative.clone(),
                 &container,
+                &self.on_empty,
             )
         } else {
             log::info!("Template skipped due to condition: {:?}", self.relative);
@@ -532,10 +774,17 @@ impl ModelsTemplate {
             .map(|s| Condition::from(s.as_str().unwrap()))
             .map_or(Ok(None), |v| v.map(Some))?;
 
+        let group_by = config
+            .get("group_by")
+            .map(|s| GroupBy::from(s.as_str().unwrap()))
+            .unwrap_or_else(|| Ok(GroupBy::default()))?;
+
         Ok(Template::Models(Self {
             relative,
             filename,
             condition,
+            group_by,
+            on_empty: OnEmpty::from(config)?,
         }))
     }
 
@@ -545,6 +794,82 @@ impl ModelsTemplate {
         target_dir: &str,
         models: &super::jsonschema::ModelContainer,
         container: &super::CodegenContainer,
+        plan: &mut RenderPlan,
+    ) -> Result<Vec<String>, Error> {
+        if self.group_by.kind.is_none() {
+            return self.render_group(tera, target_dir, models, container);
+        }
+
+        let mut result = vec![];
+
+        for (tag, models) in models.split_by_tag(COMMON_MODULE) {
+            let mut container = container.clone();
+            container
+                .data
+                .insert("tag".to_string(), Value::String(tag.to_pascal_case()));
+
+            let mut files = self.render_group(tera, target_dir, &models, &container)?;
+            plan.add(tag.to_pascal_case(), target_dir, &files);
+
+            result.append(&mut files);
+        }
+
+        Ok(result)
+    }
+
+    // models with files declaring them, as render would group them
+    pub fn symbols(
+        &self,
+        models: &super::jsonschema::ModelContainer,
+        container: &super::CodegenContainer,
+    ) -> Result<Vec<(String, String)>, Error> {
+        let groups = match self.group_by.kind {
+            Some(_) => models
+                .split_by_tag(COMMON_MODULE)
+                .into_iter()
+                .map(|(tag, models)| (Some(tag), models))
+                .collect(),
+            None => vec![(None, models.clone())],
+        };
+
+        let mut result = vec![];
+
+        for (tag, models) in groups {
+            let mut container = container.clone();
+            if let Some(tag) = tag {
+                container
+                    .data
+                    .insert("tag".to_string(), Value::String(tag.to_pascal_case()));
+            }
+
+            if !self
+                .condition
+                .as_ref()
+                .map(|s| s.check(&container))
+                .unwrap_or(true)
+            {
+                continue;
+            }
+
+            let file = self.filename.resolve(&container)?;
+            result.extend(
+                models
+                    .models()
+                    .iter()
+                    .filter_map(|m| m.name().ok())
+                    .map(|name| (name.to_string(), file.clone())),
+            );
+        }
+
+        Ok(result)
+    }
+
+    fn render_group(
+        &self,
+        tera: &Tera,
+        target_dir: &str,
+        models: &super::jsonschema::ModelContainer,
+        container: &super::CodegenContainer,
     ) -> Result<Vec<String>, Error> {
         if self
             .condition
@@ -562,6 +887,7 @@ impl ModelsTemplate {
                 )),
                 self.relative.clone(),
                 container,
+                &self.on_empty,
             )
         } else {
             log::info!("Template skipped due to condition: {:?}", self.relative);
@@ -571,20 +897,178 @@ impl ModelsTemplate {
     }
 }
 
+impl IndexTemplate {
+    pub fn from(relative: PathBuf, config: &HashMap<&str, Value>) -> Result<Template, Error> {
+        let filename = Filename::from(
+            config
+                .get("filename")
+                .ok_or_else(|| Error::CodegenFileHeaderRequired("filename".to_string()))?
+                .as_str()
+                .unwrap()
+                .to_string(),
+        );
+
+        let condition = config
+            .get("if")
+            .map(|s| Condition::from(s.as_str().unwrap()))
+            .map_or(Ok(None), |v| v.map(Some))?;
+
+        Ok(Template::Index(Self {
+            relative,
+            filename,
+            condition,
+            on_empty: OnEmpty::from(config)?,
+        }))
+    }
+
+    pub fn render(
+        &self,
+        tera: &Tera,
+        target_dir: &str,
+        plan: &RenderPlan,
+        container: &super::CodegenContainer,
+    ) -> Result<Vec<String>, Error> {
+        if self
+            .condition
+            .as_ref()
+            .map(|s| s.check(container))
+            .unwrap_or(true)
+        {
+            process_render(
+                tera,
+                plan,
+                PathBuf::from(format!(
+                    "{}/{}",
+                    target_dir,
+                    self.filename.resolve(container)?
+                )),
+                self.relative.clone(),
+                container,
+                &self.on_empty,
+            )
+        } else {
+            log::info!("Template skipped due to condition: {:?}", self.relative);
+
+            Ok(vec![])
+        }
+    }
+}
+
+impl SamplesTemplate {
+    pub fn from(relative: PathBuf, config: &HashMap<&str, Value>) -> Result<Template, Error> {
+        let lang = config
+            .get("lang")
+            .ok_or_else(|| Error::CodegenFileHeaderRequired("lang".to_string()))?
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let label = config
+            .get("label")
+            .map(|s| s.as_str().unwrap().to_string())
+            .unwrap_or_else(|| lang.clone());
+
+        let content_type = config
+            .get("content_type")
+            .map(|s| s.as_str().unwrap().to_string())
+            .unwrap_or_else(|| "application/json".to_string());
+
+        let condition = config
+            .get("if")
+            .map(|s| Condition::from(s.as_str().unwrap()))
+            .map_or(Ok(None), |v| v.map(Some))?;
+
+        Ok(Template::Samples(Self {
+            relative,
+            lang,
+            label,
+            content_type,
+            condition,
+        }))
+    }
+
+    // endpoint is available as `endpoint` next to whole openapi, empty samples are skipped
+    pub fn render(
+        &self,
+        tera: &Tera,
+        openapi: &super::openapi::Openapi,
+        container: &super::CodegenContainer,
+    ) -> Result<Vec<super::samples::Sample>, Error> {
+        let openapi = openapi.clone().set_content_type(&self.content_type);
+        let mut result = vec![];
+
+        for endpoint in &openapi.endpoints {
+            let mut container = container.clone();
+            container.data.insert(
+                "endpoint".to_string(),
+                serde_json::to_value(endpoint).unwrap(),
+            );
+
+            if !self
+                .condition
+                .as_ref()
+                .map(|s| s.check(&container))
+                .unwrap_or(true)
+            {
+                continue;
+            }
+
+            let source = tera
+                .render(
+                    &self.relative.to_string_lossy(),
+                    &render_context(&openapi, &container),
+                )
+                .map_err(Error::CodegenTemplateError)?;
+
+            if source.trim().is_empty() {
+                log::info!(
+                    "{} {}: empty {} sample skipped",
+                    endpoint.get_method(),
+                    endpoint.get_path(),
+                    self.lang
+                );
+                continue;
+            }
+
+            result.push(super::samples::Sample {
+                path: endpoint.get_path().to_string(),
+                method: endpoint.get_method().to_string(),
+                lang: self.lang.clone(),
+                label: self.label.clone(),
+                // header line leaves empty line behind
+                source: source
+                    .trim_start_matches(['\r', '\n'])
+                    .trim_end()
+                    .to_string(),
+            });
+        }
+
+        Ok(result)
+    }
+}
+
 impl FileTemplate {
-    pub fn copy(&self, target_dir: &str) -> Result<Vec<String>, Error> {
-        let target = PathBuf::from(format!("{}/{}", target_dir, self.relative));
+    pub fn copy(
+        &self,
+        target_dir: &str,
+        container: &super::CodegenContainer,
+    ) -> Result<Vec<String>, Error> {
+        // relative path may contain placeholders, ex. src/%options.package%/py.typed
+        let relative = tools::fill_parameters(&self.relative, container)?;
+        let target = container.outputs.claim(
+            PathBuf::from(format!("{}/{}", target_dir, relative)),
+            &self.relative,
+        )?;
 
         log::info!("Copying: {:?}", target);
 
-        let mut directory = target.clone();
-        directory.pop();
-
-        std::fs::create_dir_all(directory).map_err(|e| Error::CodegenFileError(e.to_string()))?;
+        let content =
+            std::fs::read(&self.path).map_err(|e| Error::CodegenFileError(e.to_string()))?;
 
-        std::fs::copy(self.path.clone(), target.clone())
-            .map(|_| ())
-            .map_err(|e| Error::CodegenFileError(e.to_string()))?;
+        container.output.write(&target, &content)?;
+        container
+            .output
+            .set_permissions(&target, Some(&self.path), None)?;
 
         Ok(vec![target.to_string_lossy().to_string()])
     }
@@ -594,7 +1078,8 @@ pub fn get(discovered: Discovered) -> Result<Templates, Error> {
     let mut list: Vec<Template> = vec![];
 
     for (relative, content) in discovered.templates {
-        let result = Template::from_content(relative.clone(), content);
+        let source = discovered.sources.get(&relative).cloned();
+        let result = Template::from_content(relative.clone(), content, source);
 
         match result {
             Ok(template) => {
@@ -621,40 +1106,224 @@ pub fn get(discovered: Discovered) -> Result<Templates, Error> {
     Ok(Templates { list })
 }
 
+// rendered data with container (options, tag, ...) on top
+fn render_context(data: impl Serialize, container: &super::CodegenContainer) -> Context {
+    let mut data = serde_json::to_value(data).unwrap();
+    if let Some(strict) = &container.strict {
+        strict.apply(&mut data);
+    }
+
+    let mut ctx = Context::from_serialize(data).unwrap();
+
+    let mut data = serde_json::to_value(container).unwrap();
+    if let Some(strict) = &container.strict {
+        strict.apply(&mut data);
+    }
+
+    for (key, value) in data.as_object().unwrap() {
+        ctx.insert(key, value);
+    }
+
+    ctx
+}
+
 fn process_render(
     tera: &Tera,
-    data: (impl Serialize + Clone),
+    data: impl Serialize + Clone,
     target: PathBuf,
     relative: PathBuf,
     container: &super::CodegenContainer,
+    on_empty: &OnEmpty,
 ) -> Result<Vec<String>, Error> {
-    let mut ctx = Context::from_serialize(serde_json::to_value(data).unwrap()).unwrap();
-
-    let data = serde_json::to_value(container).unwrap();
-    for (key, value) in data.as_object().unwrap() {
-        ctx.insert(key, value);
-    }
-
     let result = tera
-        .render(&relative.to_string_lossy(), &ctx)
+        .render(
+            &relative.to_string_lossy(),
+            &render_context(data, container),
+        )
         .map_err(Error::CodegenTemplateError)?;
 
     if result.trim().is_empty() {
-        return Ok(vec![]);
+        match on_empty {
+            OnEmpty::Skip => {
+                log::info!("Skipping empty: {:?}", target);
+
+                container
+                    .skipped
+                    .borrow_mut()
+                    .push(target.to_string_lossy().to_string());
+                return Ok(vec![]);
+            }
+            OnEmpty::Error => {
+                return Err(Error::CodegenEmptyOutput(
+                    target.to_string_lossy().to_string(),
+                ))
+            }
+            OnEmpty::Write => {}
+        }
     }
 
+    let target = container
+        .outputs
+        .claim(target, &relative.to_string_lossy())?;
+
     log::info!("Rendering: {:?}", target);
 
-    let mut directory = target.clone();
-    directory.pop();
+    container.output.write(&target, result.as_bytes())?;
 
-    std::fs::create_dir_all(directory).map_err(|e| Error::CodegenFileError(e.to_string()))?;
+    Ok(vec![target.to_string_lossy().to_string()])
+}
 
-    let mut file =
-        File::create(target.clone()).map_err(|e| Error::CodegenFileError(e.to_string()))?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    file.write_all(result.as_bytes())
-        .map_err(|e| Error::CodegenFileError(e.to_string()))?;
+    #[test]
+    fn test_empty_output_policy() {
+        let mut tera = Tera::default();
+        tera.add_raw_template("empty.j2", "  \n").unwrap();
 
-    Ok(vec![target.to_string_lossy().to_string()])
+        let mut directory = std::env::temp_dir();
+        directory.push(format!("schema-tools-empty-test-{}", std::process::id()));
+        let target = directory.join("placeholder.txt");
+
+        let container = super::super::CodegenContainer::default();
+        let render = |config: &str| {
+            let config = super::super::format(config).unwrap();
+
+            process_render(
+                &tera,
+                serde_json::json!({}),
+                target.clone(),
+                PathBuf::from("empty.j2"),
+                &container,
+                &OnEmpty::from(&config)?,
+            )
+        };
+
+        assert_eq!(render("").unwrap().len(), 0);
+        assert_eq!(container.skipped.borrow().len(), 1);
+        assert_eq!(target.exists(), false);
+
+        assert_eq!(render("on_empty=error").is_err(), true);
+        assert_eq!(render("on_empty=unknown").is_err(), true);
+
+        assert_eq!(render("on_empty=write").unwrap().len(), 1);
+        assert_eq!(target.exists(), true);
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_file_templated_path() {
+        let mut directory = std::env::temp_dir();
+        directory.push(format!("schema-tools-file-test-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let source = directory.join("py.typed");
+        std::fs::write(&source, "").unwrap();
+
+        let template = FileTemplate {
+            relative: "src/%options.package.snake%/py.typed".to_string(),
+            path: source,
+        };
+
+        let mut container = super::super::CodegenContainer::default();
+        container
+            .options
+            .insert("package".to_string(), Value::from("MyClient"));
+
+        let target = directory.join("target");
+        let files = template
+            .copy(&target.to_string_lossy(), &container)
+            .unwrap();
+
+        assert_eq!(
+            files,
+            vec![format!(
+                "{}/src/my_client/py.typed",
+                target.to_string_lossy()
+            )]
+        );
+        assert_eq!(target.join("src/my_client/py.typed").exists(), true);
+
+        // missing option fails instead of creating literal directory
+        container.options.clear();
+        assert_eq!(
+            template
+                .copy(&target.to_string_lossy(), &container)
+                .is_err(),
+            true
+        );
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_static_templated_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut directory = std::env::temp_dir();
+        directory.push(format!("schema-tools-static-test-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let content =
+            "# {# type=static-templated,min_version=0.0.0 #}\n#!/bin/sh\necho {{ options.name }}\n";
+        let source = directory.join("gradlew.j2");
+        std::fs::write(&source, content).unwrap();
+        std::fs::set_permissions(&source, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut discovered = Discovered::default();
+        discovered
+            .templates
+            .insert("gradlew.j2".to_string(), content.to_string());
+        discovered
+            .sources
+            .insert("gradlew.j2".to_string(), source.clone());
+        discovered.templates.insert(
+            "run.sh.j2".to_string(),
+            "{# type=static,filename=run.sh,mode=700,min_version=0.0.0 #}\necho run\n".to_string(),
+        );
+
+        let sources = discovered.templates.clone();
+        let templates = get(discovered).unwrap();
+
+        let mut tera = Tera::default();
+        tera.add_raw_templates(templates.bodies(sources)).unwrap();
+
+        let mut container = super::super::CodegenContainer::default();
+        container
+            .options
+            .insert("name".to_string(), Value::from("app"));
+
+        let target = directory.join("target");
+        let mut files = vec![];
+        for template in &templates.list {
+            if let Template::Static(t) = template {
+                files.extend(
+                    t.render(&tera, &target.to_string_lossy(), &container)
+                        .unwrap(),
+                );
+            }
+        }
+        assert_eq!(files.len(), 2);
+
+        let gradlew = target.join("gradlew");
+        assert_eq!(
+            std::fs::read_to_string(&gradlew).unwrap(),
+            "#!/bin/sh\necho app\n"
+        );
+        assert_eq!(
+            std::fs::metadata(&gradlew).unwrap().permissions().mode() & 0o777,
+            0o755
+        );
+
+        let run = target.join("run.sh");
+        assert_eq!(
+            std::fs::metadata(&run).unwrap().permissions().mode() & 0o777,
+            0o700
+        );
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
 }
@@ -0,0 +1,220 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::contract::{inline, resolve};
+use crate::tools::HTTP_METHODS;
+
+pub static VALIDATION_FRAMEWORKS: &[&str] = &["axum", "actix"];
+
+// constraints of single operation, rendered into request validation middleware
+#[derive(Debug, Serialize, Clone)]
+pub struct ValidationRule {
+    // anchored regex matching request path, path parameters are captured in order
+    pub pattern: String,
+    pub path_parameters: Vec<String>,
+    pub parameters: Vec<ParameterRule>,
+    pub body: Option<BodyRule>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ParameterRule {
+    // header names are lowercase, as servers give them
+    pub name: String,
+    pub location: String,
+    pub required: bool,
+    pub schema: Value,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BodyRule {
+    pub required: bool,
+    pub content_type: String,
+    pub schema: Value,
+}
+
+// validation rules keyed by lowercase method and path (ex. `get /users/{id}`) of endpoint
+pub fn rules(root: &Value) -> Map<String, Value> {
+    let mut result = Map::new();
+
+    let paths = match root.get("paths") {
+        Some(Value::Object(paths)) => paths,
+        _ => return result,
+    };
+
+    for (path, item) in paths {
+        let item = resolve(root, item);
+
+        for method in HTTP_METHODS {
+            let operation = match item.get(*method) {
+                Some(operation) => operation,
+                None => continue,
+            };
+
+            let rule = rule(root, path, item, operation);
+            result.insert(
+                format!("{} {}", method, path),
+                serde_json::to_value(rule).unwrap(),
+            );
+        }
+    }
+
+    result
+}
+
+fn rule(root: &Value, path: &str, item: &Value, operation: &Value) -> ValidationRule {
+    let (pattern, path_parameters) = pattern(path);
+    let mut parameters: Vec<ParameterRule> = vec![];
+
+    // operation parameters take precedence over path item ones
+    for parameter in [operation, item]
+        .iter()
+        .filter_map(|n| n.get("parameters").and_then(|p| p.as_array()))
+        .flatten()
+        .map(|p| resolve(root, p))
+    {
+        let location = match parameter.get("in").and_then(|i| i.as_str()) {
+            Some(location) => location.to_string(),
+            None => continue,
+        };
+
+        let name = parameter.get("name").and_then(|n| n.as_str()).unwrap_or("");
+        let name = match location.as_str() {
+            "header" => name.to_lowercase(),
+            _ => name.to_string(),
+        };
+
+        if parameters
+            .iter()
+            .any(|p| p.name == name && p.location == location)
+        {
+            continue;
+        }
+
+        parameters.push(ParameterRule {
+            required: location == "path" || parameter.get("required") == Some(&Value::Bool(true)),
+            schema: parameter
+                .get("schema")
+                .map(|s| inline(root, s, &mut vec![]))
+                .unwrap_or_else(|| Value::Object(Map::new())),
+            name,
+            location,
+        });
+    }
+
+    let body = operation
+        .get("requestBody")
+        .map(|b| resolve(root, b))
+        .and_then(|body| {
+            let content = body.get("content").and_then(|c| c.as_object())?;
+            let (content_type, media) = content
+                .iter()
+                .find(|(t, _)| t.as_str() == "application/json")
+                .or_else(|| content.iter().next())?;
+
+            Some(BodyRule {
+                required: body.get("required") == Some(&Value::Bool(true)),
+                content_type: content_type.clone(),
+                schema: media
+                    .get("schema")
+                    .map(|s| inline(root, s, &mut vec![]))
+                    .unwrap_or_else(|| Value::Object(Map::new())),
+            })
+        });
+
+    ValidationRule {
+        pattern,
+        path_parameters,
+        parameters,
+        body,
+    }
+}
+
+// `/users/{id}` becomes `^/users/([^/]+)$` with `id` captured
+fn pattern(path: &str) -> (String, Vec<String>) {
+    let mut pattern = String::from("^");
+    let mut names = vec![];
+    let mut rest = path;
+
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => break,
+        };
+
+        pattern.push_str(&regex::escape(&rest[..start]));
+        pattern.push_str("([^/]+)");
+        names.push(rest[start + 1..end].to_string());
+        rest = &rest[end + 1..];
+    }
+
+    pattern.push_str(&regex::escape(rest));
+    pattern.push('$');
+
+    (pattern, names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validation_rules() {
+        let root = json!({
+            "paths": {
+                "/users/{id}.json": {
+                    "parameters": [{ "$ref": "#/components/parameters/Id" }],
+                    "put": {
+                        "parameters": [
+                            { "name": "dry", "in": "query", "required": true, "schema": { "type": "boolean" } },
+                            { "name": "X-Request-Id", "in": "header", "schema": { "type": "string", "format": "uuid" } }
+                        ],
+                        "requestBody": {
+                            "required": true,
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/User" }
+                                }
+                            }
+                        },
+                        "responses": { "204": { "description": "Updated" } }
+                    }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "Id": { "name": "id", "in": "path", "required": true, "schema": { "type": "integer", "minimum": 1 } }
+                },
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": { "name": { "type": "string", "maxLength": 64 } }
+                    }
+                }
+            }
+        });
+
+        let result = rules(&root);
+        let rule = &result["put /users/{id}.json"];
+
+        assert_eq!(rule["pattern"], json!(r"^/users/([^/]+)\.json$"));
+        assert_eq!(rule["path_parameters"], json!(["id"]));
+        assert_eq!(
+            rule["parameters"],
+            json!([
+                { "name": "dry", "location": "query", "required": true, "schema": { "type": "boolean" } },
+                { "name": "x-request-id", "location": "header", "required": false, "schema": { "type": "string", "format": "uuid" } },
+                { "name": "id", "location": "path", "required": true, "schema": { "type": "integer", "minimum": 1 } }
+            ])
+        );
+        assert_eq!(
+            rule["body"],
+            json!({
+                "required": true,
+                "content_type": "application/json",
+                "schema": root["components"]["schemas"]["User"]
+            })
+        );
+    }
+}
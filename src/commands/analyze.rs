@@ -0,0 +1,413 @@
+use std::fmt::Display;
+
+use clap::Clap;
+use reqwest::blocking::Client;
+
+use crate::analyze::compat::{self, Policy};
+use crate::analyze::payload::{PayloadAnalyzer, BUDGET_MODES};
+use crate::analyze::replay::{self, Replayer};
+use crate::analyze::sensitive;
+use crate::analyze::usage::{self, Usage};
+use crate::error::Error;
+use crate::git_revision;
+use crate::process::annotate::SENSITIVE_EXTENSIONS;
+use crate::process::deprecation;
+use crate::schema::{path_to_url, Schema};
+
+use super::GetSchemaCommand;
+
+#[derive(Clap, Debug)]
+pub struct Opts {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+impl Display for Opts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.command {
+            Command::Payload(_) => write!(f, "payload"),
+            Command::Compat(_) => write!(f, "compat"),
+            Command::Deprecated(_) => write!(f, "deprecated"),
+            Command::Sensitive(_) => write!(f, "sensitive"),
+            Command::Replay(_) => write!(f, "replay"),
+            Command::Usage(_) => write!(f, "usage"),
+        }
+    }
+}
+
+#[derive(Clap, Debug)]
+enum Command {
+    #[clap(
+        about = "Estimates typical and worst case payload sizes of operations",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Payload(PayloadOpts),
+
+    #[clap(
+        about = "Checks changes between specifications against compatibility policy",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Compat(CompatOpts),
+
+    #[clap(
+        about = "Lists deprecated elements with their sunset dates",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Deprecated(DeprecatedOpts),
+
+    #[clap(
+        about = "Lists operations with responses containing fields annotated as sensitive",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Sensitive(SensitiveOpts),
+
+    #[clap(
+        about = "Replays recorded payloads against new version of schema and summarizes failures per field",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Replay(ReplayOpts),
+
+    #[clap(
+        about = "Joins usage report with specification, lists operations and properties with number of calls",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Usage(UsageOpts),
+}
+
+#[derive(Clap, Debug)]
+struct PayloadOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    file: String,
+
+    #[clap(long, about = "Maximal payload size in bytes, fails when exceeded")]
+    budget: Option<u64>,
+
+    #[clap(long, about = "Estimate compared with budget, unbounded worst case always exceeds budget", possible_values = BUDGET_MODES, default_value = "typical")]
+    budget_on: String,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+struct CompatOpts {
+    #[clap(
+        long,
+        required_unless_present = "at-rev",
+        about = "Path to json/yaml file with original specification, default: modified one at --at-rev"
+    )]
+    original: Option<String>,
+
+    #[clap(
+        long = "at-rev",
+        about = "Git revision (commit, branch, tag) to read original from, default: original is not versioned"
+    )]
+    at_rev: Option<String>,
+
+    #[clap(long, about = "Path to json/yaml file with modified specification")]
+    modified: String,
+
+    #[clap(
+        long,
+        about = "Path to json/yaml file with policy, default: no breaking changes"
+    )]
+    policy: Option<String>,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+struct DeprecatedOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    file: String,
+
+    #[clap(
+        long,
+        about = "Date (YYYY-MM-DD) to check sunset against, default: today"
+    )]
+    at: Option<String>,
+
+    #[clap(long, about = "Lists only elements past their sunset date")]
+    expired: bool,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+struct SensitiveOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    file: String,
+
+    #[clap(
+        long,
+        about = "Annotation marking sensitive data, default: x-pii and x-secret",
+        number_of_values = 1
+    )]
+    extension: Vec<String>,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+struct ReplayOpts {
+    #[clap(
+        long,
+        about = "Directory (or file) with recorded payloads: .json, .yaml, .ndjson or .jsonl"
+    )]
+    old_examples: String,
+
+    #[clap(
+        long,
+        about = "Path to json/yaml file with new version of json-schema or openapi specification"
+    )]
+    schema: String,
+
+    #[clap(
+        long,
+        about = "Json pointer of schema payloads are validated against, ex. /components/schemas/Order, default: whole document"
+    )]
+    pointer: Option<String>,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+struct UsageOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    file: String,
+
+    #[clap(
+        long,
+        about = "Path to json/yaml usage report, map of json pointer (or METHOD /path) to number of calls"
+    )]
+    usage: String,
+
+    #[clap(long, about = "Lists only operations and properties never called")]
+    unused: bool,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+impl GetSchemaCommand for Opts {
+    fn get_schema(&self, client: &Client) -> Result<Schema, Error> {
+        match &self.command {
+            Command::Payload(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::Compat(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.modified.clone())?, client)
+            }
+            Command::Deprecated(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::Sensitive(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::Replay(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.schema.clone())?, client)
+            }
+            Command::Usage(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+        }
+    }
+}
+
+impl Opts {
+    pub fn run(&self, schema: &Schema, client: &Client) -> Result<(), Error> {
+        match &self.command {
+            Command::Payload(opts) => {
+                let estimates = PayloadAnalyzer::options()
+                    .with_budget(opts.budget)
+                    .with_budget_on_worst(opts.budget_on == "worst")
+                    .process(schema.get_body());
+
+                opts.output
+                    .show(&serde_json::to_value(&estimates).map_err(Error::SerdeJsonError)?);
+
+                let exceeded = estimates.iter().filter(|e| e.exceeded).collect::<Vec<_>>();
+                for estimate in &exceeded {
+                    log::error!(
+                        "{} {} ({}): payload exceeds budget",
+                        estimate.operation,
+                        estimate.payload,
+                        estimate.content_type
+                    );
+                }
+
+                if exceeded.is_empty() {
+                    Ok(())
+                } else {
+                    Err(Error::AnalyzePayloadBudgetExceeded(exceeded.len()))
+                }
+            }
+            Command::Compat(opts) => {
+                let original = Schema::load_url_with_client(
+                    git_revision::original(&opts.original, &opts.modified, &opts.at_rev)?,
+                    client,
+                )?;
+
+                let policy = match &opts.policy {
+                    Some(path) => Policy::from_schema(&Schema::load_url_with_client(
+                        path_to_url(path.clone())?,
+                        client,
+                    )?)?,
+                    None => Policy::default(),
+                };
+
+                let changes = compat::check(original.get_body(), schema.get_body(), &policy);
+
+                opts.output
+                    .show(&serde_json::to_value(&changes).map_err(Error::SerdeJsonError)?);
+
+                let rejected = changes.iter().filter(|c| !c.allowed).collect::<Vec<_>>();
+                for change in &rejected {
+                    log::error!(
+                        "{}: {} is not allowed for {}",
+                        change.path,
+                        change.kind,
+                        change.audiences.join(", ")
+                    );
+                }
+
+                if rejected.is_empty() {
+                    Ok(())
+                } else {
+                    Err(Error::AnalyzeIncompatibleChanges(rejected.len()))
+                }
+            }
+            Command::Deprecated(opts) => {
+                let at = opts.at.clone().unwrap_or_else(deprecation::today);
+                deprecation::check_date(&at)?;
+
+                let deprecated = deprecation::list(schema.get_body(), &at)
+                    .into_iter()
+                    .filter(|d| d.expired || !opts.expired)
+                    .collect::<Vec<_>>();
+
+                opts.output
+                    .show(&serde_json::to_value(&deprecated).map_err(Error::SerdeJsonError)?);
+
+                Ok(())
+            }
+            Command::Sensitive(opts) => {
+                let extensions = if opts.extension.is_empty() {
+                    SENSITIVE_EXTENSIONS.iter().map(|e| e.to_string()).collect()
+                } else {
+                    opts.extension.clone()
+                };
+
+                let fields = sensitive::report(schema.get_body(), &extensions);
+
+                opts.output
+                    .show(&serde_json::to_value(&fields).map_err(Error::SerdeJsonError)?);
+
+                Ok(())
+            }
+            Command::Replay(opts) => {
+                let payloads = replay::corpus(std::path::Path::new(&opts.old_examples))?;
+                let report =
+                    Replayer::new(schema.get_body(), opts.pointer.as_deref().unwrap_or(""))?
+                        .replay(&payloads);
+
+                opts.output
+                    .show(&serde_json::to_value(&report).map_err(Error::SerdeJsonError)?);
+
+                for field in &report.fields {
+                    log::error!(
+                        "{}: {} failures ({})",
+                        field.field,
+                        field.failures,
+                        field.messages.join(", ")
+                    );
+                }
+
+                if report.failed == 0 {
+                    Ok(())
+                } else {
+                    Err(Error::AnalyzeReplayFailures(report.failed))
+                }
+            }
+            Command::Usage(opts) => {
+                let usage = Usage::from_schema(&Schema::load_url_with_client(
+                    path_to_url(opts.usage.clone())?,
+                    client,
+                )?)?;
+
+                let mut report = usage::report(schema.get_body(), &usage);
+                for pointer in &report.unknown {
+                    log::warn!("{}: not found in specification", pointer);
+                }
+
+                if opts.unused {
+                    report.entries.retain(|e| e.count == 0);
+                }
+
+                opts.output
+                    .show(&serde_json::to_value(&report).map_err(Error::SerdeJsonError)?);
+
+                Ok(())
+            }
+        }
+    }
+}
+
+pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
+    let schema = opts.get_schema(client)?;
+
+    match &opts.command {
+        Command::Payload(o) => {
+            o.verbose.start()?;
+
+            opts.run(&schema, client)
+        }
+        Command::Compat(o) => {
+            o.verbose.start()?;
+
+            opts.run(&schema, client)
+        }
+        Command::Deprecated(o) => {
+            o.verbose.start()?;
+
+            opts.run(&schema, client)
+        }
+        Command::Sensitive(o) => {
+            o.verbose.start()?;
+
+            opts.run(&schema, client)
+        }
+        Command::Replay(o) => {
+            o.verbose.start()?;
+
+            opts.run(&schema, client)
+        }
+        Command::Usage(o) => {
+            o.verbose.start()?;
+
+            opts.run(&schema, client)
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use clap::Clap;
+use reqwest::blocking::Client;
+use walkdir::WalkDir;
+
+use crate::convert::jsonschema::JsonSchemaAssembler;
+use crate::error::Error;
+use crate::schema::{path_to_url, Schema};
+
+use super::GetSchemaCommand;
+
+static SCHEMA_EXTENSIONS: &[&str] = &["json", "yaml", "yml"];
+
+#[derive(Clap, Debug)]
+pub struct Opts {
+    #[clap(long, about = "Directory with json/yaml files of json schemas")]
+    schemas: String,
+
+    #[clap(long, about = "Path to json/yaml file with base openapi specification")]
+    base: String,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+impl GetSchemaCommand for Opts {
+    fn get_schema(&self, client: &Client) -> Result<Schema, Error> {
+        Schema::load_url_with_client(path_to_url(self.base.clone())?, client)
+    }
+}
+
+impl Opts {
+    pub fn run(&self, schema: &mut Schema, client: &Client) -> Result<(), Error> {
+        let mut files = WalkDir::new(&self.schemas)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .filter(|p| {
+                p.extension()
+                    .map(|e| SCHEMA_EXTENSIONS.contains(&e.to_string_lossy().as_ref()))
+                    .unwrap_or(false)
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+
+        let mut assembler = JsonSchemaAssembler::options();
+
+        for path in files {
+            let relative = path
+                .strip_prefix(Path::new(&self.schemas))
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+
+            log::info!("Assembling: {}", relative);
+
+            assembler.with_schema(
+                relative,
+                Schema::load_url_with_client(
+                    path_to_url(path.to_string_lossy().to_string())?,
+                    client,
+                )?,
+            );
+        }
+
+        assembler.process(schema)
+    }
+}
+
+pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
+    let mut schema = opts.get_schema(client)?;
+
+    opts.verbose.start()?;
+    opts.run(&mut schema, client)?;
+    opts.output.show(schema.get_body());
+
+    Ok(())
+}
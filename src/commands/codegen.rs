@@ -5,12 +5,17 @@ use std::{fmt::Display, time::Instant};
 
 use crate::{
     discovery::Discovery,
+    process::name::word::Inflections,
     schema::{path_to_url, Schema},
     storage::SchemaStorage,
 };
 use clap::Clap;
 
 use crate::codegen;
+use crate::codegen::collision::{OnCollision, COLLISION_POLICIES};
+use crate::codegen::contract::CONTRACT_FRAMEWORKS;
+use crate::codegen::identifier::{IdentifierPolicy, IDENTIFIER_POLICIES};
+use crate::codegen::validation::VALIDATION_FRAMEWORKS;
 use crate::error::Error;
 
 use super::GetSchemaCommand;
@@ -26,6 +31,12 @@ impl Display for Opts {
         match &self.command {
             Command::JsonSchema(_) => write!(f, "jsonschema"),
             Command::Openapi(_) => write!(f, "openapi"),
+            Command::Docs(_) => write!(f, "docs"),
+            Command::ContractTests(_) => write!(f, "contract_tests"),
+            Command::ValidationMiddleware(_) => write!(f, "validation_middleware"),
+            Command::ClientPolicies(_) => write!(f, "client_policies"),
+            Command::Matrix(_) => write!(f, "matrix"),
+            Command::CodeSamples(_) => write!(f, "code_samples"),
         }
     }
 }
@@ -40,6 +51,42 @@ pub enum Command {
 
     #[clap(about = "Openapi", author = "Kacper S. <kacper@stasik.eu>")]
     Openapi(OpenapiOpts),
+
+    #[clap(
+        about = "Renders markdown api reference of openapi specification",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Docs(DocsOpts),
+
+    #[clap(
+        about = "Renders contract tests of every operation of openapi specification",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    ContractTests(ContractTestsOpts),
+
+    #[clap(
+        about = "Renders request validation middleware enforcing constraints of openapi specification",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    ValidationMiddleware(ValidationMiddlewareOpts),
+
+    #[clap(
+        about = "Renders client configuration of every operation of openapi specification",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    ClientPolicies(ClientPoliciesOpts),
+
+    #[clap(
+        about = "Renders all targets of project config from single extraction of openapi specification",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Matrix(MatrixOpts),
+
+    #[clap(
+        about = "Renders per-operation code samples and injects them into openapi specification",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    CodeSamples(CodeSamplesOpts),
 }
 
 #[derive(Clap, Debug)]
@@ -69,6 +116,22 @@ pub struct JsonSchemaOpts {
     #[clap(long, about = "Treat nested arrays as models")]
     pub nested_arrays_as_models: bool,
 
+    #[clap(long, about = "Treat single value enums as constants")]
+    pub single_enum_as_const: bool,
+
+    #[clap(long, about = "Prefix of vendor extensions, default: x-schematools")]
+    pub extension_prefix: Option<String>,
+
+    #[clap(long, about = "Handling of non ascii characters in identifiers", possible_values = IDENTIFIER_POLICIES, parse(try_from_str), default_value = "keep")]
+    pub identifier_policy: IdentifierPolicy,
+
+    #[clap(
+        long,
+        about = "Format of numbers generated as decimal type (ex. money), decimal is always included",
+        number_of_values = 1
+    )]
+    pub decimal_format: Vec<String>,
+
     #[clap(long, about = "Schema base name if title is absent")]
     pub base_name: Option<String>,
 
@@ -88,6 +151,42 @@ pub struct JsonSchemaOpts {
     #[clap(long, about = "Code formatting command")]
     pub format: Option<String>,
 
+    #[clap(flatten)]
+    cache: CacheOpts,
+
+    #[clap(flatten)]
+    strict: StrictOpts,
+
+    #[clap(long, about = "Handling of templates rendering the same file", possible_values = COLLISION_POLICIES, parse(try_from_str), default_value = "error")]
+    on_collision: OnCollision,
+
+    #[clap(
+        long,
+        about = "Give every template symbol table of generated models and endpoints with their files"
+    )]
+    symbols: bool,
+
+    #[clap(
+        long,
+        about = "Render into staging directory and move files to target directory only when codegen succeeds"
+    )]
+    atomic: bool,
+
+    #[clap(flatten)]
+    header: HeaderOpts,
+
+    #[clap(flatten)]
+    profile: ProfileOpts,
+
+    #[clap(flatten)]
+    inflections: crate::commands::InflectionOpts,
+
+    #[clap(flatten)]
+    formats: crate::commands::FormatOpts,
+
+    #[clap(flatten)]
+    interpolate: crate::commands::InterpolateOpts,
+
     #[clap(short = 'o', parse(try_from_str = super::get_options), number_of_values = 1)]
     options: Vec<(String, Value)>,
 
@@ -112,6 +211,22 @@ pub struct OpenapiOpts {
     #[clap(long, about = "Treat nested arrays as models")]
     pub nested_arrays_as_models: bool,
 
+    #[clap(long, about = "Treat single value enums as constants")]
+    pub single_enum_as_const: bool,
+
+    #[clap(long, about = "Prefix of vendor extensions, default: x-schematools")]
+    pub extension_prefix: Option<String>,
+
+    #[clap(long, about = "Handling of non ascii characters in identifiers", possible_values = IDENTIFIER_POLICIES, parse(try_from_str), default_value = "keep")]
+    pub identifier_policy: IdentifierPolicy,
+
+    #[clap(
+        long,
+        about = "Format of numbers generated as decimal type (ex. money), decimal is always included",
+        number_of_values = 1
+    )]
+    pub decimal_format: Vec<String>,
+
     #[clap(
         long,
         about = "Keep schema condition (allows access to original json schema in selected nodes)",
@@ -135,6 +250,97 @@ pub struct OpenapiOpts {
     #[clap(long, about = "Code formatting command")]
     pub format: Option<String>,
 
+    #[clap(flatten)]
+    cache: CacheOpts,
+
+    #[clap(flatten)]
+    strict: StrictOpts,
+
+    #[clap(long, about = "Handling of templates rendering the same file", possible_values = COLLISION_POLICIES, parse(try_from_str), default_value = "error")]
+    on_collision: OnCollision,
+
+    #[clap(
+        long,
+        about = "Give every template symbol table of generated models and endpoints with their files"
+    )]
+    symbols: bool,
+
+    #[clap(
+        long,
+        about = "Give every template endpoints grouped by key (tag, x-<extension>, path-prefix:<segments>) as groups",
+        parse(try_from_str)
+    )]
+    group_by: Option<codegen::grouping::GroupKey>,
+
+    #[clap(
+        long,
+        about = "Render into staging directory and move files to target directory only when codegen succeeds"
+    )]
+    atomic: bool,
+
+    #[clap(
+        long,
+        about = "Path to json/yaml file with teams owning tags (tags, default), x-owner of operation wins"
+    )]
+    owners: Option<String>,
+
+    #[clap(
+        long,
+        about = "Path to CODEOWNERS file written with teams owning generated files"
+    )]
+    codeowners: Option<String>,
+
+    #[clap(long, about = "Path to json report of teams owning generated files")]
+    owners_report: Option<String>,
+
+    #[clap(flatten)]
+    header: HeaderOpts,
+
+    #[clap(flatten)]
+    profile: ProfileOpts,
+
+    #[clap(flatten)]
+    inflections: crate::commands::InflectionOpts,
+
+    #[clap(flatten)]
+    formats: crate::commands::FormatOpts,
+
+    #[clap(flatten)]
+    interpolate: crate::commands::InterpolateOpts,
+
+    #[clap(short = 'o', parse(try_from_str = super::get_options), number_of_values = 1)]
+    options: Vec<(String, Value)>,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct DocsOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    pub file: String,
+
+    #[clap(
+        long,
+        about = "Directory with templates, name:: prefix if pointing to registry, default: built-in markdown templates"
+    )]
+    template: Vec<String>,
+
+    #[clap(
+        long,
+        about = "Target directory where generated files should be places"
+    )]
+    target_dir: String,
+
+    #[clap(long, about = "Path to changelog appended to reference")]
+    changelog: Option<String>,
+
+    #[clap(long, about = "Prefix of vendor extensions, default: x-schematools")]
+    pub extension_prefix: Option<String>,
+
+    #[clap(flatten)]
+    inflections: crate::commands::InflectionOpts,
+
     #[clap(short = 'o', parse(try_from_str = super::get_options), number_of_values = 1)]
     options: Vec<(String, Value)>,
 
@@ -142,6 +348,361 @@ pub struct OpenapiOpts {
     verbose: crate::commands::Verbosity,
 }
 
+#[derive(Clap, Debug)]
+pub struct ContractTestsOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    pub file: String,
+
+    #[clap(long, about = "Test framework of built-in templates", possible_values = CONTRACT_FRAMEWORKS, default_value = "pytest")]
+    framework: String,
+
+    #[clap(
+        long,
+        about = "Directory with templates replacing built-in ones, name:: prefix if pointing to registry"
+    )]
+    template: Vec<String>,
+
+    #[clap(
+        long,
+        about = "Target directory where generated files should be places"
+    )]
+    target_dir: String,
+
+    #[clap(long, about = "Prefix of vendor extensions, default: x-schematools")]
+    pub extension_prefix: Option<String>,
+
+    #[clap(flatten)]
+    inflections: crate::commands::InflectionOpts,
+
+    #[clap(flatten)]
+    formats: crate::commands::FormatOpts,
+
+    #[clap(flatten)]
+    interpolate: crate::commands::InterpolateOpts,
+
+    #[clap(short = 'o', parse(try_from_str = super::get_options), number_of_values = 1)]
+    options: Vec<(String, Value)>,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct ValidationMiddlewareOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    pub file: String,
+
+    #[clap(long, about = "Server framework of built-in templates", possible_values = VALIDATION_FRAMEWORKS, default_value = "axum")]
+    framework: String,
+
+    #[clap(
+        long,
+        about = "Directory with templates replacing built-in ones, name:: prefix if pointing to registry"
+    )]
+    template: Vec<String>,
+
+    #[clap(
+        long,
+        about = "Target directory where generated files should be places"
+    )]
+    target_dir: String,
+
+    #[clap(long, about = "Prefix of vendor extensions, default: x-schematools")]
+    pub extension_prefix: Option<String>,
+
+    #[clap(flatten)]
+    inflections: crate::commands::InflectionOpts,
+
+    #[clap(flatten)]
+    interpolate: crate::commands::InterpolateOpts,
+
+    #[clap(short = 'o', parse(try_from_str = super::get_options), number_of_values = 1)]
+    options: Vec<(String, Value)>,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct ClientPoliciesOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    pub file: String,
+
+    #[clap(
+        long,
+        about = "Directory with templates replacing built-in ones, name:: prefix if pointing to registry"
+    )]
+    template: Vec<String>,
+
+    #[clap(
+        long,
+        about = "Target directory where generated files should be places"
+    )]
+    target_dir: String,
+
+    #[clap(long, about = "Prefix of vendor extensions, default: x-schematools")]
+    pub extension_prefix: Option<String>,
+
+    #[clap(flatten)]
+    inflections: crate::commands::InflectionOpts,
+
+    #[clap(flatten)]
+    interpolate: crate::commands::InterpolateOpts,
+
+    #[clap(short = 'o', parse(try_from_str = super::get_options), number_of_values = 1)]
+    options: Vec<(String, Value)>,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct MatrixOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    pub file: String,
+
+    #[clap(
+        long,
+        about = "Path to json/yaml project config with targets and profiles"
+    )]
+    config: String,
+
+    #[clap(
+        long,
+        about = "Name of target to render, default: all targets",
+        number_of_values = 1
+    )]
+    target: Vec<String>,
+
+    #[clap(
+        long,
+        about = "Wrap mixed to special wrap object which should allow to customize deserialization"
+    )]
+    wrappers: bool,
+
+    #[clap(long, about = "Treat optional an nullable fields as models")]
+    pub optional_and_nullable_as_models: bool,
+
+    #[clap(long, about = "Treat nested arrays as models")]
+    pub nested_arrays_as_models: bool,
+
+    #[clap(long, about = "Treat single value enums as constants")]
+    pub single_enum_as_const: bool,
+
+    #[clap(long, about = "Prefix of vendor extensions, default: x-schematools")]
+    pub extension_prefix: Option<String>,
+
+    #[clap(long, about = "Handling of non ascii characters in identifiers", possible_values = IDENTIFIER_POLICIES, parse(try_from_str), default_value = "keep")]
+    pub identifier_policy: IdentifierPolicy,
+
+    #[clap(
+        long,
+        about = "Format of numbers generated as decimal type (ex. money), decimal is always included",
+        number_of_values = 1
+    )]
+    pub decimal_format: Vec<String>,
+
+    #[clap(
+        long,
+        about = "Keep schema condition (allows access to original json schema in selected nodes)",
+        required = false
+    )]
+    keep_schema: Vec<String>,
+
+    #[clap(flatten)]
+    cache: CacheOpts,
+
+    #[clap(flatten)]
+    strict: StrictOpts,
+
+    #[clap(long, about = "Handling of templates rendering the same file", possible_values = COLLISION_POLICIES, parse(try_from_str), default_value = "error")]
+    on_collision: OnCollision,
+
+    #[clap(
+        long,
+        about = "Give every template symbol table of generated models and endpoints with their files"
+    )]
+    symbols: bool,
+
+    #[clap(
+        long,
+        about = "Give every template endpoints grouped by key (tag, x-<extension>, path-prefix:<segments>) as groups",
+        parse(try_from_str)
+    )]
+    group_by: Option<codegen::grouping::GroupKey>,
+
+    #[clap(
+        long,
+        about = "Render into staging directory and move files to target directory only when codegen succeeds"
+    )]
+    atomic: bool,
+
+    #[clap(flatten)]
+    header: HeaderOpts,
+
+    #[clap(flatten)]
+    inflections: crate::commands::InflectionOpts,
+
+    #[clap(flatten)]
+    formats: crate::commands::FormatOpts,
+
+    #[clap(flatten)]
+    interpolate: crate::commands::InterpolateOpts,
+
+    #[clap(short = 'o', parse(try_from_str = super::get_options), number_of_values = 1)]
+    options: Vec<(String, Value)>,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct CodeSamplesOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    pub file: String,
+
+    #[clap(
+        long,
+        about = "Directory with templates (samples templates are used), name:: prefix if pointing to registry",
+        required = true
+    )]
+    template: Vec<String>,
+
+    #[clap(
+        long,
+        about = "Extension holding samples of operation",
+        default_value = "x-codeSamples"
+    )]
+    extension: String,
+
+    #[clap(long, about = "Prefix of vendor extensions, default: x-schematools")]
+    pub extension_prefix: Option<String>,
+
+    #[clap(flatten)]
+    inflections: crate::commands::InflectionOpts,
+
+    #[clap(flatten)]
+    formats: crate::commands::FormatOpts,
+
+    #[clap(flatten)]
+    interpolate: crate::commands::InterpolateOpts,
+
+    #[clap(short = 'o', parse(try_from_str = super::get_options), number_of_values = 1)]
+    options: Vec<(String, Value)>,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct ProfileOpts {
+    #[clap(
+        long,
+        about = "Named set of options defined in templates manifest or profile config, applied in order",
+        number_of_values = 1
+    )]
+    profile: Vec<String>,
+
+    #[clap(
+        long,
+        about = "Path to json/yaml project config with profiles overriding ones of templates"
+    )]
+    profile_config: Option<String>,
+}
+
+impl ProfileOpts {
+    pub fn options(
+        &self,
+        discovered: &crate::discovery::Discovered,
+        options: &[(String, Value)],
+    ) -> Result<Vec<(String, Value)>, Error> {
+        codegen::profiles::Profiles::load(discovered, &self.profile_config)?
+            .apply(&self.profile, options)
+    }
+}
+
+#[derive(Clap, Debug)]
+pub struct CacheOpts {
+    #[clap(
+        long,
+        about = "Reuse extracted models and endpoints of previous runs with the same specification and options"
+    )]
+    cache: bool,
+
+    #[clap(
+        long,
+        about = "Directory of extraction cache, implies --cache, default: schema-tools/codegen in temp directory"
+    )]
+    cache_dir: Option<String>,
+}
+
+impl CacheOpts {
+    pub fn create(&self) -> codegen::cache::Cache {
+        codegen::cache::Cache::new(self.cache, &self.cache_dir)
+    }
+}
+
+#[derive(Clap, Debug)]
+pub struct StrictOpts {
+    #[clap(
+        long,
+        about = "Fail on null or undefined variables used in templates instead of rendering empty strings"
+    )]
+    strict_templates: bool,
+
+    #[clap(
+        long,
+        about = "Path of intentionally optional value in strict mode, ex. docs.description",
+        number_of_values = 1
+    )]
+    allow_undefined: Vec<String>,
+}
+
+impl StrictOpts {
+    pub fn create(&self) -> Option<codegen::strict::Strictness> {
+        if self.strict_templates {
+            Some(codegen::strict::Strictness::new(&self.allow_undefined))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clap, Debug)]
+pub struct HeaderOpts {
+    #[clap(long, about = "Inject provenance header into generated files")]
+    header: bool,
+
+    #[clap(
+        long,
+        about = "Provenance header template, available: %version%, %spec.url%, %spec.version%, %spec.hash%, %timestamp%"
+    )]
+    header_template: Option<String>,
+
+    #[clap(
+        long,
+        about = "Skip volatile fields (timestamp) of provenance header to keep output reproducible"
+    )]
+    header_skip_volatile: bool,
+}
+
+impl HeaderOpts {
+    pub fn create(&self, schema: &Schema) -> Option<codegen::header::Header> {
+        if self.header {
+            Some(codegen::header::Header::new(
+                schema,
+                self.header_template.clone(),
+                self.header_skip_volatile,
+            ))
+        } else {
+            None
+        }
+    }
+}
+
 impl GetSchemaCommand for Opts {
     fn get_schema(&self, client: &Client) -> Result<Schema, Error> {
         match &self.command {
@@ -152,11 +713,56 @@ impl GetSchemaCommand for Opts {
                     .map(|s| path_to_url(s.clone()))
                     .collect::<Result<Vec<_>, _>>()?;
 
-                Schema::load_urls_with_client(urls, client)
+                let mut schema = Schema::load_urls_with_client(urls, client)?;
+                opts.interpolate.apply(&mut schema, &opts.options)?;
+
+                Ok(schema)
             }
             Command::Openapi(opts) => {
+                let mut schema =
+                    Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)?;
+                opts.interpolate.apply(&mut schema, &opts.options)?;
+
+                Ok(schema)
+            }
+            Command::Docs(opts) => {
                 Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
             }
+            Command::ContractTests(opts) => {
+                let mut schema =
+                    Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)?;
+                opts.interpolate.apply(&mut schema, &opts.options)?;
+
+                Ok(schema)
+            }
+            Command::ValidationMiddleware(opts) => {
+                let mut schema =
+                    Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)?;
+                opts.interpolate.apply(&mut schema, &opts.options)?;
+
+                Ok(schema)
+            }
+            Command::ClientPolicies(opts) => {
+                let mut schema =
+                    Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)?;
+                opts.interpolate.apply(&mut schema, &opts.options)?;
+
+                Ok(schema)
+            }
+            Command::Matrix(opts) => {
+                let mut schema =
+                    Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)?;
+                opts.interpolate.apply(&mut schema, &opts.options)?;
+
+                Ok(schema)
+            }
+            Command::CodeSamples(opts) => {
+                let mut schema =
+                    Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)?;
+                opts.interpolate.apply(&mut schema, &opts.options)?;
+
+                Ok(schema)
+            }
         }
     }
 }
@@ -172,9 +778,17 @@ impl Opts {
             Command::JsonSchema(opts) => {
                 let timing_extraction = Instant::now();
 
-                let models = codegen::jsonschema::extract(
-                    schema,
+                let discovered = discovery.resolve(&opts.template)?;
+                let options = opts.profile.options(&discovered, &opts.options)?;
+
+                let mut inflections = opts.inflections.create()?;
+                inflections.merge(Inflections::from_options(
+                    options.iter().map(|(k, v)| (k, v)),
+                ));
+
+                let models = opts.cache.create().extract(
                     storage,
+                    "jsonschema",
                     JsonSchemaExtractOptions {
                         wrappers: opts.wrappers,
                         optional_and_nullable_as_models: opts.optional_and_nullable_as_models,
@@ -182,7 +796,14 @@ impl Opts {
                         base_name: opts.base_name.clone(),
                         allow_list: true,
                         keep_schema: crate::tools::Filter::new(&opts.keep_schema)?,
+                        single_enum_as_const: opts.single_enum_as_const,
+                        extension_prefix: opts.extension_prefix.clone(),
+                        inflections: inflections.clone(),
+                        identifier_policy: opts.identifier_policy.clone(),
+                        decimal_formats: opts.decimal_format.clone(),
+                        formats: opts.formats.create()?,
                     },
+                    |options| codegen::jsonschema::extract(schema, storage, options),
                 )?;
 
                 log::info!(
@@ -193,12 +814,19 @@ impl Opts {
                 let timing_rendering = Instant::now();
 
                 let renderer = codegen::renderer::create(
-                    discovery.resolve(&opts.template)?,
+                    discovered,
                     &[codegen::templates::TemplateType::Models],
-                    codegen::create_container(&opts.options),
-                )?;
+                    codegen::create_container(&options),
+                )?
+                .with_header(opts.header.create(schema))
+                .with_strict(opts.strict.create())
+                .with_collisions(opts.on_collision.clone())
+                .with_symbols(opts.symbols)
+                .with_inflections(inflections);
 
-                renderer.models(models, &opts.target_dir, &opts.format)?;
+                let staging = codegen::atomic::Staging::new(opts.atomic, &opts.target_dir)?;
+                renderer.models(models, &staging.dir(), &opts.format)?;
+                staging.commit()?;
 
                 log::info!(
                     "\x1b[1;4mrendering took: {:.2?}\x1b[0m",
@@ -210,15 +838,30 @@ impl Opts {
             Command::Openapi(opts) => {
                 let timing_extraction = Instant::now();
 
-                let openapi = codegen::openapi::extract(
-                    schema,
+                let discovered = discovery.resolve(&opts.template)?;
+                let options = opts.profile.options(&discovered, &opts.options)?;
+
+                let mut inflections = opts.inflections.create()?;
+                inflections.merge(Inflections::from_options(
+                    options.iter().map(|(k, v)| (k, v)),
+                ));
+
+                let openapi = opts.cache.create().extract(
                     storage,
+                    "openapi",
                     codegen::openapi::OpenapiExtractOptions {
                         wrappers: opts.wrappers,
                         optional_and_nullable_as_models: opts.optional_and_nullable_as_models,
                         nested_arrays_as_models: opts.nested_arrays_as_models,
                         keep_schema: crate::tools::Filter::new(&opts.keep_schema)?,
+                        single_enum_as_const: opts.single_enum_as_const,
+                        extension_prefix: opts.extension_prefix.clone(),
+                        inflections: inflections.clone(),
+                        identifier_policy: opts.identifier_policy.clone(),
+                        decimal_formats: opts.decimal_format.clone(),
+                        formats: opts.formats.create()?,
                     },
+                    |options| codegen::openapi::extract(schema, storage, options),
                 )?;
 
                 log::info!(
@@ -229,21 +872,394 @@ impl Opts {
                 let timing_rendering = Instant::now();
 
                 let renderer = codegen::renderer::create(
-                    discovery.resolve(&opts.template)?,
+                    discovered,
                     &[
                         codegen::templates::TemplateType::Models,
                         codegen::templates::TemplateType::Endpoints,
                     ],
-                    codegen::create_container(&opts.options),
-                )?;
+                    codegen::create_container(&options),
+                )?
+                .with_header(opts.header.create(schema))
+                .with_strict(opts.strict.create())
+                .with_collisions(opts.on_collision.clone())
+                .with_symbols(opts.symbols)
+                .with_group_by(opts.group_by.clone())
+                .with_inflections(inflections);
 
-                renderer.openapi(openapi, &opts.target_dir, &opts.format)?;
+                let ownership = if opts.codeowners.is_some() || opts.owners_report.is_some() {
+                    let config = match &opts.owners {
+                        Some(path) => codegen::ownership::OwnersConfig::from_schema(
+                            &Schema::load_url(path_to_url(path.clone())?)?,
+                        )?,
+                        None => codegen::ownership::OwnersConfig::default(),
+                    };
+
+                    Some(renderer.ownership(&openapi, &config)?)
+                } else {
+                    None
+                };
+
+                let staging = codegen::atomic::Staging::new(opts.atomic, &opts.target_dir)?;
+                renderer.openapi(openapi, &staging.dir(), &opts.format)?;
+                staging.commit()?;
+
+                if let Some(report) = ownership {
+                    for operation in &report.unowned {
+                        log::warn!("{}: operation has no owner", operation);
+                    }
+
+                    let write = |path: &String, content: String| {
+                        std::fs::write(path, content)
+                            .map_err(|e| Error::CodegenOwnershipWriteError(path.clone(), e))
+                    };
+
+                    if let Some(path) = &opts.codeowners {
+                        let target = std::fs::canonicalize(&opts.target_dir).map_err(|e| {
+                            Error::CodegenOwnershipWriteError(opts.target_dir.clone(), e)
+                        })?;
+                        let root = codegen::ownership::repository_root(&target)
+                            .unwrap_or_else(|| target.clone());
+
+                        write(
+                            path,
+                            codegen::ownership::codeowners(&report, &target, &root)?,
+                        )?;
+                    }
+
+                    if let Some(path) = &opts.owners_report {
+                        write(
+                            path,
+                            serde_json::to_string_pretty(&report).map_err(Error::SerdeJsonError)?,
+                        )?;
+                    }
+                }
 
                 log::info!(
                     "\x1b[1;4mrendering took: {:.2?}\x1b[0m",
                     timing_rendering.elapsed()
                 );
 
+                Ok(())
+            }
+            Command::Docs(opts) => {
+                let mut inflections = opts.inflections.create()?;
+                inflections.merge(Inflections::from_options(
+                    opts.options.iter().map(|(k, v)| (k, v)),
+                ));
+
+                let openapi = codegen::openapi::extract(
+                    schema,
+                    storage,
+                    codegen::openapi::OpenapiExtractOptions {
+                        wrappers: false,
+                        optional_and_nullable_as_models: false,
+                        nested_arrays_as_models: false,
+                        keep_schema: crate::tools::Filter::new(&[])?,
+                        single_enum_as_const: false,
+                        extension_prefix: opts.extension_prefix.clone(),
+                        inflections: inflections.clone(),
+                        identifier_policy: IdentifierPolicy::default(),
+                        decimal_formats: vec![],
+                        formats: crate::formats::Formats::default(),
+                    },
+                )?;
+
+                let discovered = if opts.template.is_empty() {
+                    codegen::pack::docs()
+                } else {
+                    discovery.resolve(&opts.template)?
+                };
+
+                let mut container = codegen::create_container(&opts.options);
+                if let Some(path) = &opts.changelog {
+                    container.data.insert(
+                        "changelog".to_string(),
+                        Value::String(
+                            std::fs::read_to_string(path).map_err(Error::DiscoveryReadFile)?,
+                        ),
+                    );
+                }
+
+                let renderer = codegen::renderer::create(
+                    discovered,
+                    &[codegen::templates::TemplateType::Endpoints],
+                    container,
+                )?
+                .with_inflections(inflections);
+
+                renderer.openapi(openapi, &opts.target_dir, &None)
+            }
+            Command::ContractTests(opts) => {
+                let mut inflections = opts.inflections.create()?;
+                inflections.merge(Inflections::from_options(
+                    opts.options.iter().map(|(k, v)| (k, v)),
+                ));
+
+                let formats = opts.formats.create()?;
+
+                // cases are built from original specification, before models are extracted
+                let cases = codegen::contract::cases(schema.get_body(), &formats);
+
+                let openapi = codegen::openapi::extract(
+                    schema,
+                    storage,
+                    codegen::openapi::OpenapiExtractOptions {
+                        wrappers: false,
+                        optional_and_nullable_as_models: false,
+                        nested_arrays_as_models: false,
+                        keep_schema: crate::tools::Filter::new(&[])?,
+                        single_enum_as_const: false,
+                        extension_prefix: opts.extension_prefix.clone(),
+                        inflections: inflections.clone(),
+                        identifier_policy: IdentifierPolicy::default(),
+                        decimal_formats: vec![],
+                        formats,
+                    },
+                )?;
+
+                let discovered = if opts.template.is_empty() {
+                    codegen::pack::contract(&opts.framework)
+                } else {
+                    discovery.resolve(&opts.template)?
+                };
+
+                let mut container = codegen::create_container(&opts.options);
+                container
+                    .data
+                    .insert("contract".to_string(), Value::Object(cases));
+
+                let renderer = codegen::renderer::create(
+                    discovered,
+                    &[codegen::templates::TemplateType::Endpoints],
+                    container,
+                )?
+                .with_inflections(inflections);
+
+                renderer.openapi(openapi, &opts.target_dir, &None)
+            }
+            Command::ValidationMiddleware(opts) => {
+                let mut inflections = opts.inflections.create()?;
+                inflections.merge(Inflections::from_options(
+                    opts.options.iter().map(|(k, v)| (k, v)),
+                ));
+
+                // rules are built from original specification, before models are extracted
+                let rules = codegen::validation::rules(schema.get_body());
+
+                let openapi = codegen::openapi::extract(
+                    schema,
+                    storage,
+                    codegen::openapi::OpenapiExtractOptions {
+                        wrappers: false,
+                        optional_and_nullable_as_models: false,
+                        nested_arrays_as_models: false,
+                        keep_schema: crate::tools::Filter::new(&[])?,
+                        single_enum_as_const: false,
+                        extension_prefix: opts.extension_prefix.clone(),
+                        inflections: inflections.clone(),
+                        identifier_policy: IdentifierPolicy::default(),
+                        decimal_formats: vec![],
+                        formats: crate::formats::Formats::default(),
+                    },
+                )?;
+
+                let discovered = if opts.template.is_empty() {
+                    codegen::pack::validation(&opts.framework)
+                } else {
+                    discovery.resolve(&opts.template)?
+                };
+
+                let mut container = codegen::create_container(&opts.options);
+                container
+                    .data
+                    .insert("validation".to_string(), Value::Object(rules));
+
+                let renderer = codegen::renderer::create(
+                    discovered,
+                    &[codegen::templates::TemplateType::Endpoints],
+                    container,
+                )?
+                .with_inflections(inflections);
+
+                renderer.openapi(openapi, &opts.target_dir, &None)
+            }
+            Command::ClientPolicies(opts) => {
+                let mut inflections = opts.inflections.create()?;
+                inflections.merge(Inflections::from_options(
+                    opts.options.iter().map(|(k, v)| (k, v)),
+                ));
+
+                let openapi = codegen::openapi::extract(
+                    schema,
+                    storage,
+                    codegen::openapi::OpenapiExtractOptions {
+                        wrappers: false,
+                        optional_and_nullable_as_models: false,
+                        nested_arrays_as_models: false,
+                        keep_schema: crate::tools::Filter::new(&[])?,
+                        single_enum_as_const: false,
+                        extension_prefix: opts.extension_prefix.clone(),
+                        inflections: inflections.clone(),
+                        identifier_policy: IdentifierPolicy::default(),
+                        decimal_formats: vec![],
+                        formats: crate::formats::Formats::default(),
+                    },
+                )?;
+
+                let discovered = if opts.template.is_empty() {
+                    codegen::pack::policies()
+                } else {
+                    discovery.resolve(&opts.template)?
+                };
+
+                let renderer = codegen::renderer::create(
+                    discovered,
+                    &[codegen::templates::TemplateType::Endpoints],
+                    codegen::create_container(&opts.options),
+                )?
+                .with_inflections(inflections);
+
+                renderer.openapi(openapi, &opts.target_dir, &None)
+            }
+            Command::Matrix(opts) => {
+                let config = Schema::load_url(path_to_url(opts.config.clone())?)?;
+                let targets = codegen::matrix::targets(&config, &opts.target)?;
+
+                // targets are moved in place when all of them are rendered
+                let mut staged = vec![];
+
+                // extraction is shared by targets with the same inflection options
+                let mut extractions: Vec<(Vec<(String, Value)>, codegen::openapi::Openapi)> =
+                    vec![];
+
+                for (name, target) in targets {
+                    let timing_rendering = Instant::now();
+
+                    let discovered = discovery.resolve(&target.template)?;
+
+                    // profiles < target options < command line options
+                    let mut options = target.options();
+                    options.extend(opts.options.iter().cloned());
+                    let options =
+                        codegen::profiles::Profiles::load(&discovered, &Some(opts.config.clone()))?
+                            .apply(&target.profile, &options)?;
+
+                    let inflection_options = options
+                        .iter()
+                        .filter(|(k, _)| k == "inflection" || k.starts_with("inflection."))
+                        .cloned()
+                        .collect::<Vec<_>>();
+
+                    let mut inflections = opts.inflections.create()?;
+                    inflections.merge(Inflections::from_options(
+                        inflection_options.iter().map(|(k, v)| (k, v)),
+                    ));
+
+                    let openapi = match extractions
+                        .iter()
+                        .find(|(key, _)| *key == inflection_options)
+                    {
+                        Some((_, openapi)) => openapi.clone(),
+                        None => {
+                            let timing_extraction = Instant::now();
+
+                            let openapi = opts.cache.create().extract(
+                                storage,
+                                "openapi",
+                                codegen::openapi::OpenapiExtractOptions {
+                                    wrappers: opts.wrappers,
+                                    optional_and_nullable_as_models: opts
+                                        .optional_and_nullable_as_models,
+                                    nested_arrays_as_models: opts.nested_arrays_as_models,
+                                    keep_schema: crate::tools::Filter::new(&opts.keep_schema)?,
+                                    single_enum_as_const: opts.single_enum_as_const,
+                                    extension_prefix: opts.extension_prefix.clone(),
+                                    inflections: inflections.clone(),
+                                    identifier_policy: opts.identifier_policy.clone(),
+                                    decimal_formats: opts.decimal_format.clone(),
+                                    formats: opts.formats.create()?,
+                                },
+                                |options| codegen::openapi::extract(schema, storage, options),
+                            )?;
+
+                            log::info!(
+                                "\x1b[1;4mextraction took: {:.2?}\x1b[0m",
+                                timing_extraction.elapsed()
+                            );
+
+                            extractions.push((inflection_options, openapi.clone()));
+                            openapi
+                        }
+                    };
+
+                    let renderer = codegen::renderer::create(
+                        discovered,
+                        &[
+                            codegen::templates::TemplateType::Models,
+                            codegen::templates::TemplateType::Endpoints,
+                        ],
+                        codegen::create_container(&options),
+                    )?
+                    .with_header(opts.header.create(schema))
+                    .with_strict(opts.strict.create())
+                    .with_collisions(opts.on_collision.clone())
+                    .with_symbols(opts.symbols)
+                    .with_group_by(opts.group_by.clone())
+                    .with_inflections(inflections);
+
+                    let staging = codegen::atomic::Staging::new(opts.atomic, &target.target_dir)?;
+                    renderer.openapi(openapi, &staging.dir(), &target.format)?;
+                    staged.push(staging);
+
+                    log::info!(
+                        "\x1b[1;4m{} rendering took: {:.2?}\x1b[0m",
+                        name,
+                        timing_rendering.elapsed()
+                    );
+                }
+
+                for staging in staged {
+                    staging.commit()?;
+                }
+
+                Ok(())
+            }
+            Command::CodeSamples(opts) => {
+                let mut inflections = opts.inflections.create()?;
+                inflections.merge(Inflections::from_options(
+                    opts.options.iter().map(|(k, v)| (k, v)),
+                ));
+
+                let openapi = codegen::openapi::extract(
+                    schema,
+                    storage,
+                    codegen::openapi::OpenapiExtractOptions {
+                        wrappers: false,
+                        optional_and_nullable_as_models: false,
+                        nested_arrays_as_models: false,
+                        keep_schema: crate::tools::Filter::new(&[])?,
+                        single_enum_as_const: false,
+                        extension_prefix: opts.extension_prefix.clone(),
+                        inflections: inflections.clone(),
+                        identifier_policy: IdentifierPolicy::default(),
+                        decimal_formats: vec![],
+                        formats: opts.formats.create()?,
+                    },
+                )?;
+
+                let renderer = codegen::renderer::create(
+                    discovery.resolve(&opts.template)?,
+                    &[codegen::templates::TemplateType::Samples],
+                    codegen::create_container(&opts.options),
+                )?
+                .with_inflections(inflections);
+
+                // samples are injected into specification as loaded, not into extracted one
+                let samples = renderer.samples(&openapi)?;
+                let injected =
+                    codegen::samples::inject(schema.get_body_mut(), &samples, &opts.extension);
+                log::info!("injected {} code samples", injected);
+
                 Ok(())
             }
         }
@@ -266,5 +1282,38 @@ pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
 
             opts.run(&mut schema, &discovery, storage)
         }
+        Command::Docs(o) => {
+            o.verbose.start()?;
+
+            opts.run(&mut schema, &discovery, storage)
+        }
+        Command::ContractTests(o) => {
+            o.verbose.start()?;
+
+            opts.run(&mut schema, &discovery, storage)
+        }
+        Command::ValidationMiddleware(o) => {
+            o.verbose.start()?;
+
+            opts.run(&mut schema, &discovery, storage)
+        }
+        Command::ClientPolicies(o) => {
+            o.verbose.start()?;
+
+            opts.run(&mut schema, &discovery, storage)
+        }
+        Command::Matrix(o) => {
+            o.verbose.start()?;
+
+            opts.run(&mut schema, &discovery, storage)
+        }
+        Command::CodeSamples(o) => {
+            o.verbose.start()?;
+
+            opts.run(&mut schema, &discovery, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
     }
 }
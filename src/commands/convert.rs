@@ -0,0 +1,158 @@
+use std::fmt::Display;
+
+use clap::Clap;
+use reqwest::blocking::Client;
+
+use crate::convert::crd::{self, CRD_SCOPES};
+use crate::convert::terraform;
+use crate::error::Error;
+use crate::process::dereference;
+use crate::schema::{path_to_url, Schema};
+use crate::storage::SchemaStorage;
+
+use super::GetSchemaCommand;
+
+#[derive(Clap, Debug)]
+pub struct Opts {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+impl Display for Opts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.command {
+            Command::Crd(_) => write!(f, "crd"),
+            Command::Terraform(_) => write!(f, "terraform"),
+        }
+    }
+}
+
+#[derive(Clap, Debug)]
+enum Command {
+    #[clap(
+        about = "Wraps json schema of resource spec into kubernetes CustomResourceDefinition",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Crd(CrdOpts),
+
+    #[clap(
+        about = "Converts json schema models into terraform plugin framework provider specification (experimental)",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Terraform(TerraformOpts),
+}
+
+#[derive(Clap, Debug)]
+struct CrdOpts {
+    #[clap(about = "Path to json/yaml file with json schema of resource spec")]
+    file: String,
+
+    #[clap(long, about = "Api group of resource, ex. example.com")]
+    group: String,
+
+    #[clap(long, about = "Kind of resource, ex. Widget")]
+    kind: String,
+
+    #[clap(long, about = "Version of resource", default_value = "v1")]
+    version: String,
+
+    #[clap(
+        long,
+        about = "Plural name of resource, default: pluralized lowercase kind"
+    )]
+    plural: Option<String>,
+
+    #[clap(long, about = "Scope of resource", possible_values = CRD_SCOPES, default_value = "Namespaced")]
+    scope: String,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+struct TerraformOpts {
+    #[clap(about = "Path to json/yaml file with json schema or openapi specification")]
+    file: String,
+
+    #[clap(long, about = "Name of terraform provider")]
+    provider: String,
+
+    #[clap(
+        long,
+        about = "Version of provider specification",
+        default_value = "0.1"
+    )]
+    spec_version: String,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+impl GetSchemaCommand for Opts {
+    fn get_schema(&self, client: &Client) -> Result<Schema, Error> {
+        match &self.command {
+            Command::Crd(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::Terraform(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+        }
+    }
+}
+
+impl Opts {
+    pub fn run(&self, schema: &mut Schema, storage: &SchemaStorage) -> Result<(), Error> {
+        match &self.command {
+            Command::Crd(opts) => {
+                // structural schemas cannot contain references
+                dereference::Dereferencer::options()
+                    .with_create_internal_references(false)
+                    .process(schema, storage)?;
+
+                crd::Crd::options(opts.group.clone(), opts.kind.clone())
+                    .with_version(opts.version.clone())
+                    .with_plural(opts.plural.clone())
+                    .with_scope(opts.scope.clone())
+                    .process(schema)
+            }
+            Command::Terraform(opts) => {
+                dereference::Dereferencer::options()
+                    .with_create_internal_references(false)
+                    .process(schema, storage)?;
+
+                terraform::Terraform::options(opts.provider.clone())
+                    .with_version(opts.spec_version.clone())
+                    .process(schema)
+            }
+        }
+    }
+}
+
+pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
+    let mut schema = opts.get_schema(client)?;
+    let storage = &SchemaStorage::new(&schema, client);
+
+    match &opts.command {
+        Command::Crd(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Terraform(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use clap::Clap;
+use reqwest::blocking::Client;
+use std::fs;
+
+use crate::edit::{self, ValueType, VALUE_TYPES};
+use crate::error::Error;
+use crate::schema::{path_to_url, Format, Schema};
+
+#[derive(Clap, Debug)]
+pub struct SetOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification or json schema")]
+    file: String,
+
+    #[clap(about = "Json pointer of value (ex. /info/version), `-` appends to array")]
+    pointer: String,
+
+    #[clap(about = "New value")]
+    value: String,
+
+    #[clap(short, long = "type", about = "Type of value, auto keeps type of existing string and parses others as json", possible_values = VALUE_TYPES, default_value = "auto")]
+    type_: String,
+
+    #[clap(flatten)]
+    target: Target,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct UnsetOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification or json schema")]
+    file: String,
+
+    #[clap(about = "Json pointer of removed value (ex. /info/x-internal)")]
+    pointer: String,
+
+    #[clap(flatten)]
+    target: Target,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+struct Target {
+    #[clap(
+        long,
+        about = "Path of output file, default edited file is overwritten"
+    )]
+    to_file: Option<String>,
+
+    #[clap(
+        long,
+        about = "Print edited specification to stdout instead of writing file"
+    )]
+    stdout: bool,
+}
+
+impl Target {
+    // edited file keeps its format, serialized the same way as other outputs of schematools
+    fn write(&self, file: &str, schema: &Schema) -> Result<(), Error> {
+        let result = match schema.get_format() {
+            Format::Yaml => schema.to_string_pretty(None)?,
+            Format::Json => format!("{}\n", schema.to_string_pretty(None)?),
+        };
+
+        if self.stdout {
+            print!("{}", result);
+            return Ok(());
+        }
+
+        let path = self.to_file.as_deref().unwrap_or(file);
+        fs::write(path, result).map_err(|e| Error::EditWriteError(format!("{}: {}", path, e)))
+    }
+}
+
+fn load(file: &str, client: &Client) -> Result<Schema, Error> {
+    Schema::load_url_with_client(path_to_url(file.to_string())?, client)
+}
+
+pub fn execute_set(opts: SetOpts, client: &Client) -> Result<(), Error> {
+    let type_ = opts.type_.parse::<ValueType>()?;
+    let mut schema = load(&opts.file, client)?;
+
+    opts.verbose.start()?;
+
+    let existing = schema
+        .get_body()
+        .pointer(opts.pointer.trim_start_matches('#'))
+        .cloned();
+    let value = type_.coerce(&opts.value, existing.as_ref())?;
+
+    edit::set(schema.get_body_mut(), &opts.pointer, value)?;
+    opts.target.write(&opts.file, &schema)
+}
+
+pub fn execute_unset(opts: UnsetOpts, client: &Client) -> Result<(), Error> {
+    let mut schema = load(&opts.file, client)?;
+
+    opts.verbose.start()?;
+
+    edit::unset(schema.get_body_mut(), &opts.pointer)?;
+    opts.target.write(&opts.file, &schema)
+}
@@ -0,0 +1,154 @@
+use clap::Clap;
+use console::{style, Key, Term};
+use reqwest::blocking::Client;
+
+use crate::error::Error;
+use crate::explore::{clipboard_sequence, Explorer};
+use crate::process::{dereference, merge_allof};
+use crate::schema::{path_to_url, Schema};
+use crate::storage::SchemaStorage;
+
+use super::GetSchemaCommand;
+
+static HELP: &str =
+    "up/down move  enter/right open  left parent  r follow $ref  b back  v preview  y copy pointer  q quit";
+
+#[derive(Clap, Debug)]
+pub struct Opts {
+    #[clap(about = "Path to json/yaml file with openapi specification or json schema")]
+    file: String,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+impl GetSchemaCommand for Opts {
+    fn get_schema(&self, client: &Client) -> Result<Schema, Error> {
+        Schema::load_url_with_client(path_to_url(self.file.clone())?, client)
+    }
+}
+
+// preview shows what codegen sees: internal references kept, allOfs merged
+fn processed(schema: &Schema, storage: &SchemaStorage) -> Schema {
+    let mut processed = schema.clone();
+
+    let result = dereference::Dereferencer::options()
+        .with_create_internal_references(true)
+        .process(&mut processed, storage)
+        .and_then(|_| merge_allof::Merger::options().process(&mut processed, storage));
+
+    if let Err(e) = result {
+        log::warn!("preview is not available: {}", e);
+        return schema.clone();
+    }
+
+    processed
+}
+
+fn render(term: &Term, explorer: &Explorer, preview: bool, status: &str) -> std::io::Result<()> {
+    let (height, width) = term.size();
+    let rows = (height as usize).saturating_sub(4).max(1);
+
+    term.clear_screen()?;
+    term.write_line(&style(explorer.pointer()).bold().to_string())?;
+
+    if preview {
+        let content = match explorer.preview() {
+            Some(value) => serde_json::to_string_pretty(value).unwrap(),
+            None => "node does not exist after dereference and merge".to_string(),
+        };
+
+        for line in content.lines().take(rows) {
+            term.write_line(&console::truncate_str(line, width as usize, "…"))?;
+        }
+    } else {
+        let entries = explorer.entries();
+        let offset = explorer.selected.saturating_sub(rows - 1);
+
+        for (i, entry) in entries.iter().enumerate().skip(offset).take(rows) {
+            let line = console::truncate_str(
+                &format!("{}  {}", entry.key, style(&entry.summary).dim()),
+                width as usize,
+                "…",
+            )
+            .to_string();
+
+            if i == explorer.selected {
+                term.write_line(&style(line).reverse().to_string())?;
+            } else {
+                term.write_line(&line)?;
+            }
+        }
+    }
+
+    term.write_line("")?;
+    term.write_line(&style(status).yellow().to_string())?;
+    term.write_str(&style(HELP).dim().to_string())
+}
+
+fn explore(term: &Term, explorer: &mut Explorer) -> std::io::Result<()> {
+    let mut preview = false;
+    let mut status = String::new();
+
+    loop {
+        render(term, explorer, preview, &status)?;
+        status.clear();
+
+        match term.read_key()? {
+            Key::ArrowUp | Key::Char('k') => explorer.up(),
+            Key::ArrowDown | Key::Char('j') => explorer.down(),
+            Key::Enter | Key::ArrowRight | Key::Char('l') => {
+                preview = false;
+                explorer.enter();
+            }
+            Key::Backspace | Key::ArrowLeft | Key::Char('h') => {
+                preview = false;
+                explorer.parent();
+            }
+            Key::Char('r') => match explorer.follow() {
+                Ok(reference) => status = format!("followed {}", reference),
+                Err(message) => status = message,
+            },
+            Key::Char('b') => {
+                status = match explorer.back() {
+                    true => String::new(),
+                    false => "no reference followed".to_string(),
+                }
+            }
+            Key::Char('v') => preview = !preview,
+            Key::Char('y') => {
+                term.write_str(&clipboard_sequence(&explorer.pointer()))?;
+                status = format!("copied {}", explorer.pointer());
+            }
+            Key::Char('q') | Key::Escape => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
+    let schema = opts.get_schema(client)?;
+    let storage = SchemaStorage::new(&schema, client);
+
+    opts.verbose.start()?;
+
+    let term = Term::stdout();
+    if !term.is_term() {
+        return Err(Error::ExploreTerminalError(
+            "stdout is not a terminal".to_string(),
+        ));
+    }
+
+    let processed = processed(&schema, &storage);
+    let mut explorer = Explorer::new(schema.get_body().clone(), processed.get_body().clone());
+
+    let result = term
+        .hide_cursor()
+        .and_then(|_| explore(&term, &mut explorer));
+
+    // cursor is restored even when terminal failed
+    term.show_cursor()
+        .and_then(|_| term.clear_screen())
+        .and(result)
+        .map_err(|e| Error::ExploreTerminalError(e.to_string()))
+}
@@ -0,0 +1,137 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+use clap::Clap;
+use reqwest::blocking::Client;
+
+use crate::convert::jsonschema::{JsonSchemaExtractor, JSONSCHEMA_DRAFTS};
+use crate::error::Error;
+use crate::process::localize;
+use crate::schema::{path_to_url, Schema};
+
+use super::GetSchemaCommand;
+
+#[derive(Clap, Debug)]
+pub struct Opts {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+impl Display for Opts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.command {
+            Command::Schemas(_) => write!(f, "schemas"),
+            Command::Texts(_) => write!(f, "texts"),
+        }
+    }
+}
+
+#[derive(Clap, Debug)]
+enum Command {
+    #[clap(
+        about = "Exports components/schemas of openapi as standalone json schema files",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Schemas(SchemasOpts),
+
+    #[clap(
+        about = "Exports titles, summaries and descriptions as translation bundle keyed by json pointer",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Texts(TextsOpts),
+}
+
+#[derive(Clap, Debug)]
+struct SchemasOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    file: String,
+
+    #[clap(long, about = "Directory where json schema files should be placed")]
+    out: String,
+
+    #[clap(long, about = "Json schema draft of exported files", possible_values = JSONSCHEMA_DRAFTS, default_value = "draft-07")]
+    draft: String,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+struct TextsOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    file: String,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+impl GetSchemaCommand for Opts {
+    fn get_schema(&self, client: &Client) -> Result<Schema, Error> {
+        match &self.command {
+            Command::Schemas(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::Texts(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+        }
+    }
+}
+
+impl Opts {
+    pub fn run(&self, schema: &Schema) -> Result<(), Error> {
+        match &self.command {
+            Command::Schemas(opts) => {
+                let files = JsonSchemaExtractor::options()
+                    .with_draft(opts.draft.clone())
+                    .process(schema)?;
+
+                std::fs::create_dir_all(&opts.out)
+                    .map_err(|e| Error::CodegenFileError(e.to_string()))?;
+
+                for (filename, body) in files {
+                    let mut path = PathBuf::from(&opts.out);
+                    path.push(filename);
+
+                    log::info!("Extracting: {:?}", path);
+
+                    let mut file =
+                        File::create(path).map_err(|e| Error::CodegenFileError(e.to_string()))?;
+                    file.write_all(serde_json::to_string_pretty(&body).unwrap().as_bytes())
+                        .map_err(|e| Error::CodegenFileError(e.to_string()))?;
+                }
+
+                Ok(())
+            }
+            Command::Texts(opts) => {
+                opts.output.show(&serde_json::Value::Object(localize::texts(
+                    schema.get_body(),
+                )));
+
+                Ok(())
+            }
+        }
+    }
+}
+
+pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
+    let schema = opts.get_schema(client)?;
+
+    match &opts.command {
+        Command::Schemas(o) => {
+            o.verbose.start()?;
+
+            opts.run(&schema)
+        }
+        Command::Texts(o) => {
+            o.verbose.start()?;
+
+            opts.run(&schema)
+        }
+    }
+}
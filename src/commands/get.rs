@@ -0,0 +1,59 @@
+use clap::Clap;
+use reqwest::blocking::Client;
+
+use crate::error::Error;
+use crate::query::{resolve_refs, Query};
+use crate::resolver::SchemaResolver;
+use crate::schema::{path_to_url, Schema};
+use crate::storage::SchemaStorage;
+
+use super::GetSchemaCommand;
+
+#[derive(Clap, Debug)]
+pub struct Opts {
+    #[clap(about = "Path to json/yaml file with openapi specification or json schema")]
+    file: String,
+
+    #[clap(
+        about = "Json pointer (ex. /components/schemas/User, * matches any key) or jsonpath (ex. $.paths.*.get.operationId)"
+    )]
+    query: String,
+
+    #[clap(
+        long,
+        about = "Inline references of found nodes, recursive references are left intact"
+    )]
+    resolve_refs: bool,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+impl GetSchemaCommand for Opts {
+    fn get_schema(&self, client: &Client) -> Result<Schema, Error> {
+        Schema::load_url_with_client(path_to_url(self.file.clone())?, client)
+    }
+}
+
+pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
+    let query = Query::parse(&opts.query)?;
+    let schema = opts.get_schema(client)?;
+
+    opts.verbose.start()?;
+
+    let mut result = query.get(schema.get_body())?;
+
+    if opts.resolve_refs {
+        let storage = SchemaStorage::new(&schema, client);
+        let resolver = SchemaResolver::new(&schema, &storage);
+
+        result = resolve_refs(&result, &resolver, &mut vec![]);
+    }
+
+    opts.output.show(&result);
+
+    Ok(())
+}
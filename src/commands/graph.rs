@@ -0,0 +1,62 @@
+use std::fs::File;
+use std::io::Write;
+
+use clap::Clap;
+use reqwest::blocking::Client;
+
+use crate::analyze::graph::{Graph, GRAPH_FORMATS};
+use crate::error::Error;
+use crate::schema::{path_to_url, Schema};
+
+use super::GetSchemaCommand;
+
+#[derive(Clap, Debug)]
+pub struct Opts {
+    #[clap(about = "Path to json/yaml file with openapi specification or json schema")]
+    file: String,
+
+    #[clap(long, about = "Format of graph", possible_values = GRAPH_FORMATS, default_value = "dot")]
+    format: String,
+
+    #[clap(long, about = "Path of output file, default output to stdout")]
+    to_file: Option<String>,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+impl GetSchemaCommand for Opts {
+    fn get_schema(&self, client: &Client) -> Result<Schema, Error> {
+        Schema::load_url_with_client(path_to_url(self.file.clone())?, client)
+    }
+}
+
+impl Opts {
+    pub fn run(&self, schema: &Schema) -> String {
+        let graph = Graph::new(schema.get_body());
+
+        match self.format.as_str() {
+            "json" => serde_json::to_string_pretty(&graph).unwrap(),
+            _ => graph.to_dot(),
+        }
+    }
+}
+
+pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
+    let schema = opts.get_schema(client)?;
+
+    opts.verbose.start()?;
+    let result = opts.run(&schema);
+
+    match &opts.to_file {
+        Some(filename) => {
+            let mut file =
+                File::create(filename).map_err(|e| Error::CodegenFileError(e.to_string()))?;
+            file.write_all(result.as_bytes())
+                .map_err(|e| Error::CodegenFileError(e.to_string()))?;
+        }
+        None => println!("{}", result),
+    }
+
+    Ok(())
+}
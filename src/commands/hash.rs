@@ -0,0 +1,62 @@
+use clap::Clap;
+use reqwest::blocking::Client;
+use serde_json::{json, Map, Value};
+
+use crate::error::Error;
+use crate::hash;
+use crate::schema::{path_to_url, Schema};
+
+use super::GetSchemaCommand;
+
+// locations of reusable schemas in openapi and json schema documents
+static SCHEMA_CONTAINERS: &[&str] = &["/components/schemas", "/$defs", "/definitions"];
+
+#[derive(Clap, Debug)]
+pub struct Opts {
+    #[clap(about = "Path to json/yaml file with openapi specification or json schema")]
+    file: String,
+
+    #[clap(long, about = "Include fingerprint of every reusable schema")]
+    per_schema: bool,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+impl GetSchemaCommand for Opts {
+    fn get_schema(&self, client: &Client) -> Result<Schema, Error> {
+        Schema::load_url_with_client(path_to_url(self.file.clone())?, client)
+    }
+}
+
+impl Opts {
+    pub fn run(&self, schema: &Schema) -> Value {
+        let body = schema.get_body();
+        let mut result = json!({ "hash": hash::fingerprint(body) });
+
+        if self.per_schema {
+            let schemas = SCHEMA_CONTAINERS
+                .iter()
+                .filter_map(|pointer| body.pointer(pointer).and_then(|v| v.as_object()))
+                .flat_map(|schemas| schemas.iter())
+                .map(|(name, schema)| (name.clone(), json!(hash::fingerprint(schema))))
+                .collect::<Map<String, Value>>();
+
+            result["schemas"] = Value::Object(schemas);
+        }
+
+        result
+    }
+}
+
+pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
+    let schema = opts.get_schema(client)?;
+
+    opts.verbose.start()?;
+    opts.output.show(&opts.run(&schema));
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use std::fmt::Display;
+
+use clap::Clap;
+use reqwest::blocking::Client;
+
+use crate::error::Error;
+use crate::git_revision;
+use crate::process;
+use crate::schema::{path_to_url, Schema};
+
+use super::GetSchemaCommand;
+
+#[derive(Clap, Debug)]
+pub struct Opts {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+impl Display for Opts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.command {
+            Command::Create(_) => write!(f, "create"),
+            Command::Apply(_) => write!(f, "apply"),
+        }
+    }
+}
+
+#[derive(Clap, Debug)]
+enum Command {
+    #[clap(
+        about = "Records changes of modified schema as minimal json patch",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Create(CreateOpts),
+
+    #[clap(
+        about = "Applies json patch file to schema",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Apply(ApplyOpts),
+}
+
+#[derive(Clap, Debug)]
+struct CreateOpts {
+    #[clap(
+        long,
+        required_unless_present = "at-rev",
+        about = "Path to json/yaml file with original schema, default: modified one at --at-rev"
+    )]
+    original: Option<String>,
+
+    #[clap(
+        long = "at-rev",
+        about = "Git revision (commit, branch, tag) to read original from, default: original is not versioned"
+    )]
+    at_rev: Option<String>,
+
+    #[clap(long, about = "Path to json/yaml file with modified schema")]
+    modified: String,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+struct ApplyOpts {
+    #[clap(about = "Path to json/yaml file with schema")]
+    file: String,
+
+    #[clap(long, about = "Path to json/yaml file with json patch")]
+    patch: String,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+impl GetSchemaCommand for Opts {
+    fn get_schema(&self, client: &Client) -> Result<Schema, Error> {
+        match &self.command {
+            Command::Create(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.modified.clone())?, client)
+            }
+            Command::Apply(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+        }
+    }
+}
+
+impl Opts {
+    pub fn run(&self, schema: &mut Schema, client: &Client) -> Result<(), Error> {
+        match &self.command {
+            Command::Create(opts) => {
+                let original = Schema::load_url_with_client(
+                    git_revision::original(&opts.original, &opts.modified, &opts.at_rev)?,
+                    client,
+                )?;
+
+                let result = process::patch::diff(original.get_body(), schema.get_body());
+                schema.get_body_mut().clone_from(&result);
+
+                Ok(())
+            }
+            Command::Apply(opts) => {
+                let patch_file =
+                    Schema::load_url_with_client(path_to_url(opts.patch.clone())?, client)?;
+
+                process::patch::apply(schema.get_body_mut(), patch_file.get_body())
+            }
+        }
+    }
+}
+
+pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
+    let mut schema = opts.get_schema(client)?;
+
+    match &opts.command {
+        Command::Create(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, client)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Apply(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, client)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+    }
+}
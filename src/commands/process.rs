@@ -7,13 +7,26 @@ use clap::Clap;
 use reqwest::blocking::Client;
 use std::str::FromStr;
 
+use crate::analyze::usage;
 use crate::error::Error;
-use crate::process::{bump_openapi, dereference, merge_allof, merge_openapi, name, patch};
+use crate::git_revision;
+use crate::process::report::{ReportFormat, REPORT_FORMATS};
+use crate::process::{
+    annotate, bump_openapi, compose, conditionals, const_to_enum, cut_version, deduplicate,
+    deprecation, dereference, import, inject, interpolate, localize, merge_allof, merge_openapi,
+    name, overlay, patch, redact, rename_properties,
+};
 use crate::schema::{path_to_url, Schema};
 
 static BUMP_OPENAPI_KIND: &[&str] = &["x-version"];
 #[derive(Clap, Debug)]
 pub struct Opts {
+    #[clap(
+        long,
+        about = "Fail when step changes specification, ex. committed specification which should be processed already"
+    )]
+    pub expect_no_change: bool,
+
     #[clap(subcommand)]
     pub command: Command,
 }
@@ -27,6 +40,22 @@ impl Display for Opts {
             Command::Dereference(_) => write!(f, "dereference"),
             Command::Name(_) => write!(f, "name"),
             Command::Patch(_) => write!(f, "patch"),
+            Command::ConstToEnum(_) => write!(f, "const_to_enum"),
+            Command::SimplifyConditionals(_) => write!(f, "simplify_conditionals"),
+            Command::Overlay(_) => write!(f, "overlay"),
+            Command::Deprecate(_) => write!(f, "deprecate"),
+            Command::StripDeprecated(_) => write!(f, "strip_deprecated"),
+            Command::Localize(_) => write!(f, "localize"),
+            Command::PropagateAnnotations(_) => write!(f, "propagate_annotations"),
+            Command::Redact(_) => write!(f, "redact"),
+            Command::Interpolate(_) => write!(f, "interpolate"),
+            Command::Import(_) => write!(f, "import"),
+            Command::InjectParameters(_) => write!(f, "inject_parameters"),
+            Command::Deduplicate(_) => write!(f, "deduplicate"),
+            Command::RenameProperties(_) => write!(f, "rename-properties"),
+            Command::Compose(_) => write!(f, "compose"),
+            Command::CutVersion(_) => write!(f, "cut-version"),
+            Command::AnnotateUsage(_) => write!(f, "annotate-usage"),
         }
     }
 }
@@ -68,6 +97,102 @@ pub enum Command {
         author = "Kacper S. <kacper@stasik.eu>"
     )]
     Patch(PatchOpts),
+
+    #[clap(
+        about = "Replaces const keywords with single value enums",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    ConstToEnum(ConstToEnumOpts),
+
+    #[clap(
+        about = "Evaluates decidable if/then/else conditions, lowers others to oneOf",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    SimplifyConditionals(SimplifyConditionalsOpts),
+
+    #[clap(
+        about = "Applies ordered overlay files to schema",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Overlay(OverlayOpts),
+
+    #[clap(
+        about = "Marks operations or schemas as deprecated with sunset date",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Deprecate(DeprecateOpts),
+
+    #[clap(
+        about = "Removes deprecated elements with sunset before given date",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    StripDeprecated(StripDeprecatedOpts),
+
+    #[clap(
+        about = "Replaces titles, summaries and descriptions with translations of given locale",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Localize(LocalizeOpts),
+
+    #[clap(
+        about = "Copies sensitive data annotations of referenced schemas onto fields using them",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    PropagateAnnotations(PropagateAnnotationsOpts),
+
+    #[clap(
+        about = "Removes internal endpoints, extensions, secrets and servers from specification",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Redact(RedactOpts),
+
+    #[clap(
+        about = "Expands environment variables and options placeholders in values of schema",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Interpolate(InterpolateOpts),
+
+    #[clap(
+        about = "Imports components of vendor specification with prefixed names",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Import(ImportOpts),
+
+    #[clap(
+        about = "Injects declared parameters into every selected operation of specification",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    InjectParameters(InjectParametersOpts),
+
+    #[clap(
+        about = "Extracts identical inline schemas into components referenced from every occurrence",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Deduplicate(DeduplicateOpts),
+
+    #[clap(
+        about = "Renames properties of all schemas to one case with their required lists and examples",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    RenameProperties(RenamePropertiesOpts),
+
+    #[clap(
+        about = "Composes openapi fragments (ex. emitted from code annotations) into one specification",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Compose(ComposeOpts),
+
+    #[clap(
+        about = "Produces specification of one api version from source annotated with x-since and x-removed-in",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    CutVersion(CutVersionOpts),
+
+    #[clap(
+        about = "Annotates operations and properties with number of calls from usage report",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    AnnotateUsage(AnnotateUsageOpts),
 }
 
 #[derive(Clap, Debug)]
@@ -99,8 +224,18 @@ pub struct BumpOpenapiOpts {
     #[clap(about = "Path to json/yaml file")]
     pub file: String,
 
-    #[clap(long, about = "Path to previos version of openapi specification")]
-    original: String,
+    #[clap(
+        long,
+        required_unless_present = "at-rev",
+        about = "Path to previos version of openapi specification, default: file at --at-rev"
+    )]
+    original: Option<String>,
+
+    #[clap(
+        long = "at-rev",
+        about = "Git revision (commit, branch, tag) to read original from, default: original is not versioned"
+    )]
+    at_rev: Option<String>,
 
     #[clap(short, long, about = "Type of bump", possible_values = BUMP_OPENAPI_KIND, parse(try_from_str), default_value = "x-version")]
     kind: String,
@@ -127,6 +262,46 @@ pub struct MergeAllOfOpts {
     )]
     filter: Vec<String>,
 
+    #[clap(
+        long,
+        about = "Keep allOf of schemas mapped by discriminator or extending schema with discriminator"
+    )]
+    keep_discriminated: bool,
+
+    #[clap(
+        long,
+        about = "Json pointer of subtree where allOfs are merged (ex. /components/schemas/*), `**` matches any depth",
+        number_of_values = 1
+    )]
+    scope: Vec<String>,
+
+    #[clap(
+        long,
+        about = "Json pointer of subtree where allOfs are left intact, wildcards like in scope",
+        number_of_values = 1
+    )]
+    exclude: Vec<String>,
+
+    #[clap(
+        long,
+        about = "How constraints present in many allOf elements are combined, intersect keeps all of them",
+        possible_values = merge_allof::CONSTRAINT_STRATEGIES,
+        parse(try_from_str),
+        default_value = "intersect"
+    )]
+    constraint_strategy: merge_allof::ConstraintStrategy,
+
+    #[clap(flatten)]
+    limits: crate::commands::LimitOpts,
+
+    #[clap(
+        long,
+        about = "Print summary of processing to stderr",
+        possible_values = REPORT_FORMATS,
+        parse(try_from_str)
+    )]
+    report: Option<ReportFormat>,
+
     #[clap(flatten)]
     output: crate::commands::Output,
 
@@ -151,6 +326,29 @@ pub struct DereferenceOpts {
     #[clap(long, about = "List of hostnames to skip dereference")]
     skip_references: Vec<String>,
 
+    #[clap(
+        long,
+        about = "Annotates each inlined schema with x-origin-ref holding reference it replaced"
+    )]
+    annotate_origin: bool,
+
+    #[clap(
+        long,
+        about = "Path of json file relating pointers of output to files and pointers they come from"
+    )]
+    source_map: Option<String>,
+
+    #[clap(flatten)]
+    limits: crate::commands::LimitOpts,
+
+    #[clap(
+        long,
+        about = "Print summary of processing to stderr",
+        possible_values = REPORT_FORMATS,
+        parse(try_from_str)
+    )]
+    report: Option<ReportFormat>,
+
     #[clap(flatten)]
     output: crate::commands::Output,
 
@@ -179,6 +377,12 @@ pub struct NameOpts {
     #[clap(long, about = "Base name of parsed schema")]
     base_name: Option<String>,
 
+    #[clap(long, about = "Prefix of vendor extensions, default: x-schematools")]
+    extension_prefix: Option<String>,
+
+    #[clap(flatten)]
+    inflections: crate::commands::InflectionOpts,
+
     #[clap(flatten)]
     output: crate::commands::Output,
 
@@ -201,135 +405,843 @@ pub struct PatchOpts {
     verbose: crate::commands::Verbosity,
 }
 
-impl GetSchemaCommand for Opts {
-    fn get_schema(&self, client: &Client) -> Result<Schema, Error> {
-        match &self.command {
-            Command::MergeAllOf(opts) => {
-                let urls = opts
-                    .file
-                    .iter()
-                    .map(|s| path_to_url(s.clone()))
-                    .collect::<Result<Vec<_>, _>>()?;
+#[derive(Clap, Debug)]
+pub struct ConstToEnumOpts {
+    #[clap(about = "Path to json/yaml file with schema")]
+    file: String,
 
-                Schema::load_urls(urls)
-            }
-            Command::MergeOpenapi(opts) => {
-                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
-            }
-            Command::BumpOpenapi(opts) => {
-                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
-            }
-            Command::Dereference(opts) => {
-                let urls = opts
-                    .file
-                    .iter()
-                    .map(|s| path_to_url(s.clone()))
-                    .collect::<Result<Vec<_>, _>>()?;
+    #[clap(long, about = "Do not add type inferred from const value")]
+    skip_infer_type: bool,
 
-                Schema::load_urls_with_client(urls, client)
-            }
-            Command::Name(opts) => {
-                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
-            }
-            Command::Patch(opts) => {
-                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
-            }
-        }
-    }
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
 }
 
-impl Opts {
-    pub fn run(&self, schema: &mut Schema, storage: &SchemaStorage) -> Result<(), Error> {
-        match &self.command {
-            Command::MergeAllOf(opts) => {
-                merge_allof::Merger::options()
-                    .with_leave_invalid_properties(opts.leave_invalid_properties)
-                    .with_filter(tools::Filter::new(&opts.filter)?)
-                    .process(schema, storage);
-                Ok(())
-            }
-            Command::MergeOpenapi(opts) => {
-                let merge = Schema::load_url(path_to_url(opts.with.clone())?)?;
+#[derive(Clap, Debug)]
+pub struct SimplifyConditionalsOpts {
+    #[clap(about = "Path to json/yaml file with schema")]
+    file: String,
 
-                merge_openapi::Merger::options(merge)
-                    .with_retag(opts.retag.clone())
-                    .with_add_version(opts.add_version.clone())
-                    .process(schema)
-            }
-            Command::BumpOpenapi(opts) => {
-                let original = Schema::load_url(path_to_url(opts.original.clone())?)?;
+    #[clap(long, about = "Keep conditions which cannot be evaluated statically")]
+    skip_lowering: bool,
 
-                bump_openapi::Bumper::options(original)
-                    .with_kind(bump_openapi::BumpKind::from_str(&opts.kind).unwrap())
-                    .process(schema)
-            }
-            Command::Dereference(opts) => {
-                dereference::Dereferencer::options()
-                    .with_skip_root_internal_references(opts.skip_root_internal_references)
-                    .with_create_internal_references(opts.create_internal_references)
-                    .with_skip_references(opts.skip_references.clone())
-                    .process(schema, storage);
-                Ok(())
-            }
-            Command::Name(opts) => {
-                //name::JsonSchemaNamer::options()
-                //    .with_base_name(opts.base_name.clone())
-                //    .with_overwrite(opts.overwrite)
-                //    .process(schema)
+    #[clap(long, about = "Prefix of vendor extensions, default: x-schematools")]
+    extension_prefix: Option<String>,
 
-                name::OpenapiNamer::options()
-                    .with_resource_method_version(opts.resource_method_version)
-                    .with_overwrite(opts.overwrite)
-                    .with_overwrite_ambigous(opts.overwrite_ambigous)
-                    .process(schema)
-            }
-            Command::Patch(opts) => patch::execute(schema, &opts.action),
-        }
-    }
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
 }
 
-pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
-    let mut schema = opts.get_schema(client)?;
-    let storage = &SchemaStorage::new(&schema, client);
+#[derive(Clap, Debug)]
+pub struct OverlayOpts {
+    #[clap(about = "Path to json/yaml file with base schema")]
+    file: String,
 
-    // tood: ...
-    match &opts.command {
-        Command::MergeAllOf(o) => {
-            o.verbose.start()?;
-            opts.run(&mut schema, storage)?; // todo: ...
-            o.output.show(schema.get_body());
+    #[clap(
+        long,
+        about = "Path to json/yaml overlay file, applied in given order",
+        number_of_values = 1,
+        required = true
+    )]
+    overlay: Vec<String>,
 
-            Ok(())
-        }
-        Command::MergeOpenapi(o) => {
-            o.verbose.start()?;
-            opts.run(&mut schema, storage)?;
-            o.output.show(schema.get_body());
+    #[clap(long, about = "Append array items instead of replacing arrays")]
+    append_arrays: bool,
 
-            Ok(())
-        }
-        Command::BumpOpenapi(o) => {
-            o.verbose.start()?;
-            opts.run(&mut schema, storage)?;
-            o.output.show(schema.get_body());
+    #[clap(flatten)]
+    output: crate::commands::Output,
 
-            Ok(())
-        }
-        Command::Dereference(o) => {
-            o.verbose.start()?;
-            opts.run(&mut schema, storage)?;
-            o.output.show(schema.get_body());
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
 
-            Ok(())
-        }
-        Command::Name(o) => {
-            o.verbose.start()?;
-            opts.run(&mut schema, storage)?;
-            o.output.show(schema.get_body());
+#[derive(Clap, Debug)]
+pub struct DeprecateOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    file: String,
 
-            Ok(())
-        }
-        Command::Patch(o) => {
+    #[clap(
+        long,
+        about = "Json pointer or operation (ex. \"GET /users\") to deprecate",
+        number_of_values = 1,
+        required = true
+    )]
+    target: Vec<String>,
+
+    #[clap(long, about = "Sunset date (YYYY-MM-DD) stored as x-sunset")]
+    sunset: Option<String>,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct StripDeprecatedOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    file: String,
+
+    #[clap(long, about = "Sunset date (YYYY-MM-DD), default: today")]
+    before: Option<String>,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct LocalizeOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    file: String,
+
+    #[clap(long, about = "Directory with translation bundles named after locales")]
+    bundle_dir: String,
+
+    #[clap(long, about = "Locale of translation, ex. de")]
+    locale: String,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct PropagateAnnotationsOpts {
+    #[clap(about = "Path to json/yaml file with schema")]
+    file: String,
+
+    #[clap(
+        long,
+        about = "Annotation to propagate, default: x-pii and x-secret",
+        number_of_values = 1
+    )]
+    extension: Vec<String>,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct RedactOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    file: String,
+
+    #[clap(long, about = "Path to json/yaml file with redaction config")]
+    config: String,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct InjectParametersOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    file: String,
+
+    #[clap(
+        long,
+        about = "Path to json/yaml file with injected parameters and operations selector"
+    )]
+    config: String,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct DeduplicateOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    file: String,
+
+    #[clap(
+        long,
+        about = "Minimal number of identical inline schemas extracted to component",
+        default_value = "2"
+    )]
+    min_occurrences: usize,
+
+    #[clap(
+        long,
+        about = "Print summary of processing to stderr",
+        possible_values = REPORT_FORMATS,
+        parse(try_from_str)
+    )]
+    report: Option<ReportFormat>,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct RenamePropertiesOpts {
+    #[clap(about = "Path to json/yaml file with schema")]
+    file: String,
+
+    #[clap(
+        long,
+        about = "Case of property names",
+        possible_values = rename_properties::PROPERTY_CASES,
+        parse(try_from_str)
+    )]
+    case: rename_properties::PropertyCase,
+
+    #[clap(
+        long,
+        about = "Record original name of renamed property in x-original-name"
+    )]
+    record_original: bool,
+
+    #[clap(
+        long,
+        about = "Print summary of processing to stderr",
+        possible_values = REPORT_FORMATS,
+        parse(try_from_str)
+    )]
+    report: Option<ReportFormat>,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct ComposeOpts {
+    #[clap(
+        about = "Paths to json/yaml files with fragments",
+        multiple_values = true
+    )]
+    fragment: Vec<String>,
+
+    #[clap(
+        long,
+        about = "Path to json/yaml file with top level keys (ex. info, servers) of composed specification"
+    )]
+    base: Option<String>,
+
+    #[clap(long, about = "Skip validation of composed specification")]
+    skip_validation: bool,
+
+    #[clap(
+        long,
+        about = "Print summary of processing to stderr",
+        possible_values = REPORT_FORMATS,
+        parse(try_from_str)
+    )]
+    report: Option<ReportFormat>,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct CutVersionOpts {
+    #[clap(about = "Path to json/yaml file with annotated specification")]
+    file: String,
+
+    #[clap(long, about = "Api version (ex. 2, 2.1) of produced specification")]
+    version: String,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct AnnotateUsageOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    file: String,
+
+    #[clap(
+        long,
+        about = "Path to json/yaml usage report, map of json pointer (or METHOD /path) to number of calls"
+    )]
+    usage: String,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct InterpolateOpts {
+    #[clap(about = "Path to json/yaml file with schema")]
+    file: String,
+
+    #[clap(
+        long,
+        about = "Fail on unset variables instead of leaving placeholders intact"
+    )]
+    strict: bool,
+
+    #[clap(
+        long,
+        about = "Value of %options.*% placeholder in key=value format",
+        parse(try_from_str = super::get_options),
+        number_of_values = 1
+    )]
+    option: Vec<(String, serde_json::Value)>,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+pub struct ImportOpts {
+    #[clap(about = "Path to json/yaml file with openapi specification")]
+    file: String,
+
+    #[clap(
+        long,
+        about = "Path to json/yaml file with vendor specification, recorded in x-imported-from"
+    )]
+    from: String,
+
+    #[clap(long, about = "Prefix of imported component names, ex. Vendor_")]
+    prefix: Option<String>,
+
+    #[clap(
+        long,
+        about = "Imported component in kind/name format (ex. schemas/Address) with its dependencies, default: all",
+        number_of_values = 1
+    )]
+    component: Vec<String>,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+impl GetSchemaCommand for Opts {
+    fn get_schema(&self, client: &Client) -> Result<Schema, Error> {
+        match &self.command {
+            Command::MergeAllOf(opts) => {
+                let urls = opts
+                    .file
+                    .iter()
+                    .map(|s| path_to_url(s.clone()))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Schema::load_urls(urls)
+            }
+            Command::MergeOpenapi(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::BumpOpenapi(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::Dereference(opts) => {
+                let urls = opts
+                    .file
+                    .iter()
+                    .map(|s| path_to_url(s.clone()))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Schema::load_urls_with_client(urls, client)
+            }
+            Command::Name(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::Patch(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::ConstToEnum(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::SimplifyConditionals(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::Overlay(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::Deprecate(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::StripDeprecated(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::Localize(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::PropagateAnnotations(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::Redact(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::InjectParameters(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::Interpolate(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::Import(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::Deduplicate(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::RenameProperties(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::CutVersion(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::AnnotateUsage(opts) => {
+                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+            }
+            Command::Compose(opts) => match &opts.base {
+                Some(base) => Schema::load_url_with_client(path_to_url(base.clone())?, client),
+                None => Ok(Schema::from_json(serde_json::json!({}))),
+            },
+        }
+    }
+}
+
+impl Opts {
+    // every step reports whether it changed specification, processing twice changes nothing
+    pub fn run(&self, schema: &mut Schema, storage: &SchemaStorage) -> Result<(), Error> {
+        let original = schema.get_body().clone();
+
+        self.run_step(schema, storage)?;
+
+        let changes = crate::process::changes(&original, schema.get_body());
+        if changes.is_empty() {
+            log::info!("{}: no changes", self);
+            return Ok(());
+        }
+
+        log::info!("{}: {} changes", self, changes.len());
+
+        if self.expect_no_change {
+            let mut summary = changes.iter().take(5).cloned().collect::<Vec<_>>();
+            if changes.len() > summary.len() {
+                summary.push(format!("and {} more", changes.len() - summary.len()));
+            }
+
+            return Err(Error::ProcessUnexpectedChange(format!(
+                "{} ({})",
+                self,
+                summary.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn run_step(&self, schema: &mut Schema, storage: &SchemaStorage) -> Result<(), Error> {
+        match &self.command {
+            Command::MergeAllOf(opts) => {
+                let report = merge_allof::Merger::options()
+                    .with_leave_invalid_properties(opts.leave_invalid_properties)
+                    .with_filter(tools::Filter::new(&opts.filter)?)
+                    .with_constraint_strategy(opts.constraint_strategy.clone())
+                    .with_keep_discriminated(opts.keep_discriminated)
+                    .with_scopes(opts.scope.clone())
+                    .with_excludes(opts.exclude.clone())
+                    .with_limits(opts.limits.create())
+                    .process(schema, storage)?;
+
+                if let Some(format) = &opts.report {
+                    report.print(format);
+                }
+
+                Ok(())
+            }
+            Command::MergeOpenapi(opts) => {
+                let merge = Schema::load_url(path_to_url(opts.with.clone())?)?;
+
+                merge_openapi::Merger::options(merge)
+                    .with_retag(opts.retag.clone())
+                    .with_add_version(opts.add_version.clone())
+                    .process(schema)
+            }
+            Command::BumpOpenapi(opts) => {
+                let original = Schema::load_url(git_revision::original(
+                    &opts.original,
+                    &opts.file,
+                    &opts.at_rev,
+                )?)?;
+
+                bump_openapi::Bumper::options(original)
+                    .with_kind(bump_openapi::BumpKind::from_str(&opts.kind).unwrap())
+                    .process(schema)
+            }
+            Command::Dereference(opts) => {
+                let (report, source_map) = dereference::Dereferencer::options()
+                    .with_skip_root_internal_references(opts.skip_root_internal_references)
+                    .with_create_internal_references(opts.create_internal_references)
+                    .with_skip_references(opts.skip_references.clone())
+                    .with_annotate_origin(opts.annotate_origin)
+                    .with_limits(opts.limits.create())
+                    .process_with_source_map(schema, storage)?;
+
+                if let Some(path) = &opts.source_map {
+                    source_map.save(path)?;
+                }
+
+                if let Some(format) = &opts.report {
+                    report.print(format);
+                }
+
+                Ok(())
+            }
+            Command::Name(opts) => {
+                //name::JsonSchemaNamer::options()
+                //    .with_base_name(opts.base_name.clone())
+                //    .with_overwrite(opts.overwrite)
+                //    .process(schema)
+
+                name::OpenapiNamer::options()
+                    .with_resource_method_version(opts.resource_method_version)
+                    .with_overwrite(opts.overwrite)
+                    .with_overwrite_ambigous(opts.overwrite_ambigous)
+                    .with_extension_prefix(opts.extension_prefix.clone())
+                    .with_inflections(opts.inflections.create()?)
+                    .process(schema)
+            }
+            Command::Patch(opts) => patch::execute(schema, &opts.action),
+            Command::ConstToEnum(opts) => {
+                const_to_enum::Converter::options()
+                    .with_infer_type(!opts.skip_infer_type)
+                    .process(schema);
+                Ok(())
+            }
+            Command::SimplifyConditionals(opts) => {
+                conditionals::Simplifier::options()
+                    .with_lower(!opts.skip_lowering)
+                    .with_extension_prefix(opts.extension_prefix.clone())
+                    .process(schema);
+                Ok(())
+            }
+            Command::Overlay(opts) => {
+                let mut overlayer = overlay::Overlayer::options();
+
+                for file in &opts.overlay {
+                    overlayer.with_overlay(Schema::load_url(path_to_url(file.clone())?)?);
+                }
+
+                overlayer
+                    .with_append_arrays(opts.append_arrays)
+                    .process(schema)
+            }
+            Command::Deprecate(opts) => {
+                let mut marker = deprecation::Marker::options();
+
+                for target in &opts.target {
+                    marker.with_target(target.clone());
+                }
+
+                marker.with_sunset(opts.sunset.clone()).process(schema)
+            }
+            Command::StripDeprecated(opts) => {
+                let before = opts.before.clone().unwrap_or_else(deprecation::today);
+
+                for pointer in deprecation::Stripper::options(before).process(schema)? {
+                    log::info!("{}: removed", pointer);
+                }
+
+                Ok(())
+            }
+            Command::Localize(opts) => {
+                let path = ["json", "yaml", "yml"]
+                    .iter()
+                    .map(|e| {
+                        std::path::Path::new(&opts.bundle_dir)
+                            .join(format!("{}.{}", opts.locale, e))
+                    })
+                    .find(|p| p.is_file())
+                    .ok_or_else(|| Error::LocalizeBundleNotFound(opts.locale.clone()))?;
+
+                let bundle = Schema::load_url(path_to_url(path.to_string_lossy().to_string())?)?;
+
+                localize::Localizer::options(&bundle)?.process(schema);
+                Ok(())
+            }
+            Command::PropagateAnnotations(opts) => {
+                annotate::Propagator::options()
+                    .with_extensions(opts.extension.clone())
+                    .process(schema);
+                Ok(())
+            }
+            Command::Redact(opts) => {
+                let config = Schema::load_url(path_to_url(opts.config.clone())?)?;
+
+                redact::Redactor::options(&config)?.process(schema)
+            }
+            Command::InjectParameters(opts) => {
+                let config = Schema::load_url(path_to_url(opts.config.clone())?)?;
+
+                inject::Injector::options(&config)?.process(schema)
+            }
+            Command::Interpolate(opts) => interpolate::Interpolator::options()
+                .with_strict(opts.strict)
+                .with_parameters(opts.option.clone())
+                .process(schema),
+            Command::Import(opts) => {
+                let vendor = Schema::load_url(path_to_url(opts.from.clone())?)?;
+
+                import::Importer::options(vendor, opts.from.clone())
+                    .with_prefix(opts.prefix.clone().unwrap_or_default())
+                    .with_components(opts.component.clone())
+                    .process(schema)
+                    .map(|_| ())
+            }
+            Command::Deduplicate(opts) => {
+                let report = deduplicate::Deduplicator::options()
+                    .with_min_occurrences(opts.min_occurrences)
+                    .process(schema)?;
+
+                if let Some(format) = &opts.report {
+                    report.print(format);
+                }
+
+                Ok(())
+            }
+            Command::CutVersion(opts) => {
+                for pointer in
+                    cut_version::VersionCutter::options(opts.version.clone()).process(schema)?
+                {
+                    log::info!("{}: removed", pointer);
+                }
+
+                Ok(())
+            }
+            Command::AnnotateUsage(opts) => {
+                let usage = usage::Usage::from_schema(&Schema::load_url(path_to_url(
+                    opts.usage.clone(),
+                )?)?)?;
+
+                let annotated = usage::annotate(schema.get_body_mut(), &usage);
+                log::info!("{} operations and properties annotated", annotated);
+
+                Ok(())
+            }
+            Command::Compose(opts) => {
+                let fragments = opts
+                    .fragment
+                    .iter()
+                    .map(|f| Ok((f.clone(), Schema::load_url(path_to_url(f.clone())?)?)))
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                let report = compose::Composer::options(fragments).process(schema)?;
+
+                if let Some(format) = &opts.report {
+                    report.print(format);
+                }
+
+                if opts.skip_validation {
+                    Ok(())
+                } else {
+                    crate::validate::validate_openapi(schema, None)
+                }
+            }
+            Command::RenameProperties(opts) => {
+                let report = rename_properties::PropertyRenamer::options(opts.case.clone())
+                    .with_record_original(opts.record_original)
+                    .process(schema)?;
+
+                if let Some(format) = &opts.report {
+                    report.print(format);
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
+    let mut schema = opts.get_schema(client)?;
+    let storage = &SchemaStorage::new(&schema, client);
+
+    // tood: ...
+    match &opts.command {
+        Command::MergeAllOf(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?; // todo: ...
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::MergeOpenapi(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::BumpOpenapi(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Dereference(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Name(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Patch(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::ConstToEnum(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::SimplifyConditionals(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Overlay(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Deprecate(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::StripDeprecated(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Localize(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::PropagateAnnotations(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Redact(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::InjectParameters(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Interpolate(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Import(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Deduplicate(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::CutVersion(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::AnnotateUsage(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Compose(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::RenameProperties(o) => {
             o.verbose.start()?;
             opts.run(&mut schema, storage)?;
             o.output.show(schema.get_body());
@@ -0,0 +1,81 @@
+use clap::Clap;
+use reqwest::blocking::Client;
+use serde_json::json;
+
+use crate::error::Error;
+use crate::publish;
+use crate::schema::{path_to_url, Schema};
+use crate::validate;
+
+use super::GetSchemaCommand;
+
+#[derive(Clap, Debug)]
+pub struct Opts {
+    #[clap(about = "Path to json/yaml file with processed specification")]
+    file: String,
+
+    #[clap(
+        long,
+        about = "Registry url: http(s)://host/path, s3://bucket/prefix or git+file:///path/to/repository"
+    )]
+    registry: String,
+
+    #[clap(long, about = "Version tag of published specification")]
+    tag: String,
+
+    #[clap(long, about = "Name of published specification, file name by default")]
+    name: Option<String>,
+
+    #[clap(long, about = "Validate and print provenance without uploading")]
+    dry_run: bool,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+impl GetSchemaCommand for Opts {
+    fn get_schema(&self, client: &Client) -> Result<Schema, Error> {
+        Schema::load_url_with_client(path_to_url(self.file.clone())?, client)
+    }
+}
+
+impl Opts {
+    fn name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            std::path::Path::new(&self.file)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+    }
+}
+
+pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
+    let schema = opts.get_schema(client)?;
+
+    opts.verbose.start()?;
+
+    if schema.get_body().get("openapi").is_some() {
+        validate::validate_openapi(&schema, None)?;
+    } else {
+        validate::validate_jsonschema(&schema)?;
+    }
+
+    let artifact = publish::Artifact::new(&schema, &opts.name(), &opts.tag, &opts.file)?;
+
+    let locations = if opts.dry_run {
+        artifact.files().into_iter().map(|(file, _)| file).collect()
+    } else {
+        publish::create(&opts.registry, client)?.publish(&artifact)?
+    };
+
+    opts.output.show(&json!({
+        "provenance": artifact.provenance,
+        "locations": locations,
+    }));
+
+    Ok(())
+}
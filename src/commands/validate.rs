@@ -4,8 +4,14 @@ use clap::Clap;
 use reqwest::blocking::Client;
 
 use crate::error::Error;
+use crate::process::source_map::SourceMap;
 use crate::schema::{path_to_url, Schema};
+use crate::spec_registry;
 use crate::validate;
+use crate::validate::baseline::{BaselineMode, BASELINE_MODES};
+use crate::validate::composite::{self, Service};
+use crate::validate::conventions::Conventions;
+use crate::validate::semantic::{Severity, SEVERITIES};
 
 use super::GetSchemaCommand;
 
@@ -20,6 +26,7 @@ impl Display for Opts {
         match &self.command {
             Command::Openapi(_) => write!(f, "openapi"),
             Command::JsonSchema(_) => write!(f, "jsonschema"),
+            Command::Composite(_) => write!(f, "composite"),
         }
     }
 }
@@ -37,6 +44,12 @@ enum Command {
         author = "Kacper S. <kacper@stasik.eu>"
     )]
     JsonSchema(JsonSchemaOpts),
+
+    #[clap(
+        about = "Validates set of specifications referencing each other with registry references",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Composite(CompositeOpts),
 }
 
 #[derive(Clap, Debug)]
@@ -47,6 +60,63 @@ struct OpenapiOpts {
     #[clap(long, about = "Should continue on error")]
     pub continue_on_error: bool,
 
+    #[clap(
+        long,
+        about = "Run semantic checks (operationId uniqueness, path parameters, references, security schemes, unused components and tags)"
+    )]
+    pub semantic: bool,
+
+    #[clap(
+        long,
+        about = "Path to json/yaml file with organization conventions (standard headers) checked on every operation"
+    )]
+    pub conventions: Option<String>,
+
+    #[clap(
+        long,
+        about = "Path to source map written by dereference, errors point to original files"
+    )]
+    pub source_map: Option<String>,
+
+    #[clap(
+        long,
+        about = "Lowest severity of findings failing validation, findings below are only logged",
+        possible_values = SEVERITIES,
+        parse(try_from_str),
+        default_value = "error"
+    )]
+    pub fail_on: Severity,
+
+    #[clap(
+        long,
+        about = "Gather all errors in one pass and print grouped summary"
+    )]
+    pub all_errors: bool,
+
+    #[clap(
+        long,
+        about = "Write current findings to baseline or fail only on findings absent in baseline",
+        possible_values = BASELINE_MODES,
+        parse(try_from_str)
+    )]
+    pub baseline: Option<BaselineMode>,
+
+    #[clap(
+        long,
+        about = "Path of baseline file",
+        default_value = ".schematools-baseline.json"
+    )]
+    pub baseline_file: String,
+
+    #[clap(long, about = "Prefix of vendor extensions, default: x-schematools")]
+    pub extension_prefix: Option<String>,
+
+    #[clap(flatten)]
+    formats: crate::commands::FormatOpts,
+
+    #[clap(flatten)]
+    interpolate: crate::commands::InterpolateOpts,
+
     #[clap(flatten)]
     verbose: crate::commands::Verbosity,
 }
@@ -59,18 +129,121 @@ struct JsonSchemaOpts {
     #[clap(long, about = "Should continue on error")]
     pub continue_on_error: bool,
 
+    #[clap(
+        long,
+        about = "Gather all errors in one pass and print grouped summary"
+    )]
+    pub all_errors: bool,
+
+    #[clap(flatten)]
+    interpolate: crate::commands::InterpolateOpts,
+
     #[clap(flatten)]
     verbose: crate::commands::Verbosity,
 }
 
+#[derive(Clap, Debug)]
+struct CompositeOpts {
+    #[clap(
+        about = "Paths to json/yaml files of specifications, named with file name or prefix (ex. orders-api=orders/openapi.yaml)",
+        required = true,
+        multiple_values = true
+    )]
+    file: Vec<String>,
+
+    #[clap(long, about = "Should continue on error")]
+    pub continue_on_error: bool,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+impl CompositeOpts {
+    // first specification is the one processed (ex. by chain), others are loaded
+    fn services(&self, schema: &Schema) -> Result<Vec<Service>, Error> {
+        let mut services = vec![];
+
+        for (i, argument) in self.file.iter().enumerate() {
+            let (name, path) = Service::name_of(argument);
+            let schema = match i {
+                0 => schema.clone(),
+                _ => Schema::load_url(path_to_url(path)?)?,
+            };
+
+            let name = name.unwrap_or_else(|| {
+                schema
+                    .get_url()
+                    .path_segments()
+                    .and_then(|mut s| s.next_back())
+                    .and_then(|f| f.split('.').next())
+                    .unwrap_or_default()
+                    .to_string()
+            });
+
+            services.push(Service { name, schema });
+        }
+
+        Ok(services)
+    }
+
+    fn run(&self, schema: &Schema) -> Result<(), Error> {
+        let dependencies = composite::check(&self.services(schema)?, |reference| {
+            Schema::load_url(spec_registry::resolve(&format!(
+                "{}@{}",
+                reference.name, reference.range
+            ))?)
+        });
+
+        self.output
+            .show(&serde_json::to_value(&dependencies).map_err(Error::SerdeJsonError)?);
+
+        let broken = dependencies
+            .iter()
+            .filter(|d| !d.problems.is_empty())
+            .collect::<Vec<_>>();
+        for dependency in &broken {
+            for problem in &dependency.problems {
+                log::error!(
+                    "{} -> {}: {}",
+                    dependency.consumer,
+                    dependency.provider,
+                    problem
+                );
+            }
+        }
+
+        if broken.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::ValidationBrokenDependencies(broken.len()))
+        }
+    }
+}
+
 impl GetSchemaCommand for Opts {
     fn get_schema(&self, client: &Client) -> Result<Schema, Error> {
         match &self.command {
             Command::Openapi(opts) => {
-                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+                let mut schema =
+                    Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)?;
+                opts.interpolate.apply(&mut schema, &[])?;
+
+                Ok(schema)
             }
             Command::JsonSchema(opts) => {
-                Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)
+                let mut schema =
+                    Schema::load_url_with_client(path_to_url(opts.file.clone())?, client)?;
+                opts.interpolate.apply(&mut schema, &[])?;
+
+                Ok(schema)
+            }
+            Command::Composite(opts) => {
+                let (_, path) = Service::name_of(&opts.file[0]);
+
+                Schema::load_url_with_client(path_to_url(path)?, client)
             }
         }
     }
@@ -78,9 +251,66 @@ impl GetSchemaCommand for Opts {
 
 impl Opts {
     pub fn run(&self, schema: &mut Schema) -> Result<(), Error> {
+        let conventions = match &self.command {
+            Command::Openapi(o) => o
+                .conventions
+                .as_deref()
+                .map(Conventions::load)
+                .transpose()?,
+            Command::JsonSchema(_) | Command::Composite(_) => None,
+        };
+
+        let sources = match &self.command {
+            Command::Openapi(o) => o.source_map.as_deref().map(SourceMap::load).transpose()?,
+            Command::JsonSchema(_) | Command::Composite(_) => None,
+        };
+
         match &self.command {
-            Command::Openapi(_) => validate::validate_openapi(schema),
+            Command::Openapi(o) if o.baseline.is_some() => validate::validate_openapi_baseline(
+                schema,
+                o.semantic,
+                &o.formats.create()?,
+                conventions.as_ref(),
+                sources.as_ref(),
+                o.baseline.as_ref().unwrap(),
+                &o.baseline_file,
+                &o.fail_on,
+                &o.extension_prefix,
+            ),
+            Command::Openapi(o) if o.all_errors => validate::finish_report(
+                schema,
+                validate::report_openapi(
+                    schema,
+                    o.semantic,
+                    &o.formats.create()?,
+                    conventions.as_ref(),
+                    sources.as_ref(),
+                    &o.fail_on,
+                    &o.extension_prefix,
+                ),
+            ),
+            Command::JsonSchema(o) if o.all_errors => {
+                validate::finish_report(schema, validate::report_jsonschema(schema))
+            }
+            Command::Openapi(o) => {
+                validate::validate_openapi(schema, sources.as_ref()).and_then(|_| {
+                    if o.semantic || conventions.is_some() {
+                        validate::validate_openapi_semantic(
+                            schema,
+                            o.semantic,
+                            &o.formats.create()?,
+                            conventions.as_ref(),
+                            sources.as_ref(),
+                            &o.fail_on,
+                            &o.extension_prefix,
+                        )
+                    } else {
+                        Ok(())
+                    }
+                })
+            }
             Command::JsonSchema(_) => validate::validate_jsonschema(schema),
+            Command::Composite(o) => o.run(schema),
         }
         .map(|r| {
             log::info!("\x1b[0;32mSuccessful validation!\x1b[0m");
@@ -101,6 +331,7 @@ impl Opts {
         match &self.command {
             Command::Openapi(o) => o.continue_on_error,
             Command::JsonSchema(o) => o.continue_on_error,
+            Command::Composite(o) => o.continue_on_error,
         }
     }
 }
@@ -117,5 +348,9 @@ pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
             o.verbose.start()?;
             opts.run(&mut schema)
         }
+        Command::Composite(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema)
+        }
     }
 }
@@ -0,0 +1,172 @@
+use clap::Clap;
+use reqwest::blocking::Client;
+
+use crate::error::Error;
+use crate::workspace::{self, Outcome, Workspace};
+
+#[derive(Clap, Debug)]
+pub struct Opts {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Clap, Debug)]
+enum Command {
+    #[clap(
+        about = "Shows validation status, processed-ness and codegen targets of every specification",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Status(StatusOpts),
+
+    #[clap(
+        about = "Runs chain of every specification configured in its directory",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Apply(ApplyOpts),
+}
+
+#[derive(Clap, Debug)]
+struct StatusOpts {
+    #[clap(
+        about = "Root directory of workspace with optional schematools-workspace.yaml",
+        default_value = "."
+    )]
+    root: String,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clap, Debug)]
+struct ApplyOpts {
+    #[clap(
+        about = "Root directory of workspace with optional schematools-workspace.yaml",
+        default_value = "."
+    )]
+    root: String,
+
+    #[clap(
+        long,
+        about = "Apply only specifications with outdated codegen targets"
+    )]
+    stale_only: bool,
+
+    #[clap(
+        long,
+        about = "Should continue with specifications not depending on the failed one"
+    )]
+    continue_on_error: bool,
+
+    #[clap(
+        long,
+        short,
+        about = "Number of specifications processed in parallel",
+        default_value = "1"
+    )]
+    jobs: usize,
+
+    #[clap(
+        long,
+        about = "Directory of chain logs of every specification, default: logs in cache_dir of workspace or schema-tools/workspace in temp directory"
+    )]
+    log_dir: Option<String>,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+impl StatusOpts {
+    fn run(&self) -> Result<(), Error> {
+        let workspace = Workspace::load(std::path::Path::new(&self.root))?;
+        let statuses = workspace.statuses()?;
+
+        self.output
+            .show(&serde_json::to_value(&statuses).map_err(Error::SerdeJsonError)?);
+
+        Ok(())
+    }
+}
+
+impl ApplyOpts {
+    fn log_dir(&self, workspace: &Workspace) -> std::path::PathBuf {
+        match (&self.log_dir, workspace.cache_dir()) {
+            (Some(directory), _) => directory.into(),
+            (None, Some(cache)) => cache.join("logs"),
+            (None, None) => std::env::temp_dir().join("schema-tools").join("workspace"),
+        }
+    }
+
+    // specifications are applied after the ones they depend on
+    fn run(&self) -> Result<(), Error> {
+        let workspace = Workspace::load(std::path::Path::new(&self.root))?;
+        let members = workspace.members()?;
+        let dependencies = workspace.dependencies(&members)?;
+        let logs = self.log_dir(&workspace);
+
+        let outcomes = workspace::execute(&dependencies, self.jobs, self.continue_on_error, |i| {
+            let member = &members[i];
+            if member.config.chain.is_empty()
+                || (self.stale_only && workspace.status(member).processed)
+            {
+                log::info!("{}: skipped", member.spec.display());
+                return Outcome::Skipped;
+            }
+
+            let log = workspace.log(&logs, member);
+            log::info!(
+                "{}: applying, log: {}",
+                member.spec.display(),
+                log.display()
+            );
+
+            match workspace.apply(member, self.verbose.verbose, &log) {
+                Ok(_) => {
+                    log::info!("{}: applied", member.spec.display());
+                    Outcome::Applied
+                }
+                Err(e) => {
+                    log::error!("{}, log: {}", e, log.display());
+                    Outcome::Failed
+                }
+            }
+        });
+
+        for (member, _) in members
+            .iter()
+            .zip(&outcomes)
+            .filter(|(_, o)| **o == Outcome::Blocked)
+        {
+            log::warn!("{}: not applied", member.spec.display());
+        }
+
+        let count = |outcome: Outcome| outcomes.iter().filter(|o| **o == outcome).count();
+        println!(
+            "applied: {}, skipped: {}, failed: {}, blocked: {}",
+            count(Outcome::Applied),
+            count(Outcome::Skipped),
+            count(Outcome::Failed),
+            count(Outcome::Blocked)
+        );
+
+        match count(Outcome::Failed) {
+            0 => Ok(()),
+            failed => Err(Error::WorkspaceApplyFailed(failed)),
+        }
+    }
+}
+
+pub fn execute(opts: Opts, _client: &Client) -> Result<(), Error> {
+    match &opts.command {
+        Command::Status(o) => {
+            o.verbose.start()?;
+            o.run()
+        }
+        Command::Apply(o) => {
+            o.verbose.start()?;
+            o.run()
+        }
+    }
+}
@@ -0,0 +1,388 @@
+use serde_json::{json, Map, Value};
+
+use crate::error::Error;
+use crate::process::name::word;
+use crate::schema::Schema;
+use crate::tools::pointer_escape;
+
+pub static CRD_SCOPES: &[&str] = &["Namespaced", "Cluster"];
+
+// keywords which are not part of kubernetes JSONSchemaProps
+static UNSUPPORTED_KEYWORDS: &[&str] = &[
+    "$schema",
+    "$id",
+    "$comment",
+    "definitions",
+    "$defs",
+    "readOnly",
+    "writeOnly",
+    "contentMediaType",
+    "contentEncoding",
+];
+
+static KUBERNETES_EXTENSION: &str = "x-kubernetes-";
+
+pub struct Crd;
+
+pub struct CrdOptions {
+    pub group: String,
+    pub kind: String,
+    pub version: String,
+    pub plural: Option<String>,
+    pub scope: String,
+}
+
+impl Crd {
+    pub fn options(group: String, kind: String) -> CrdOptions {
+        CrdOptions {
+            group,
+            kind,
+            version: "v1".to_string(),
+            plural: None,
+            scope: "Namespaced".to_string(),
+        }
+    }
+}
+
+impl CrdOptions {
+    pub fn with_version(&mut self, value: String) -> &mut Self {
+        self.version = value;
+        self
+    }
+
+    pub fn with_plural(&mut self, value: Option<String>) -> &mut Self {
+        self.plural = value;
+        self
+    }
+
+    pub fn with_scope(&mut self, value: String) -> &mut Self {
+        self.scope = value;
+        self
+    }
+
+    // replaces json schema of resource spec with CustomResourceDefinition manifest
+    pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
+        let mut spec = schema.get_body().clone();
+        let mut violations = vec![];
+
+        structural(&mut spec, &mut String::new(), &mut violations);
+
+        if !violations.is_empty() {
+            for violation in &violations {
+                log::error!("{}", violation);
+            }
+
+            return Err(Error::ConvertCrdNotStructural(violations.len()));
+        }
+
+        let singular = self.kind.to_lowercase();
+        let plural = self
+            .plural
+            .clone()
+            .unwrap_or_else(|| word::pluralize(singular.clone()));
+
+        let result = json!({
+            "apiVersion": "apiextensions.k8s.io/v1",
+            "kind": "CustomResourceDefinition",
+            "metadata": {
+                "name": format!("{}.{}", plural, self.group)
+            },
+            "spec": {
+                "group": self.group,
+                "names": {
+                    "kind": self.kind,
+                    "listKind": format!("{}List", self.kind),
+                    "plural": plural,
+                    "singular": singular
+                },
+                "scope": self.scope,
+                "versions": [{
+                    "name": self.version,
+                    "served": true,
+                    "storage": true,
+                    "schema": {
+                        "openAPIV3Schema": {
+                            "type": "object",
+                            "properties": {
+                                "apiVersion": { "type": "string" },
+                                "kind": { "type": "string" },
+                                "metadata": { "type": "object" },
+                                "spec": spec
+                            }
+                        }
+                    }
+                }]
+            }
+        });
+
+        schema.get_body_mut().clone_from(&result);
+
+        Ok(())
+    }
+}
+
+// rewrites schema to kubernetes structural schema, remaining problems are reported as violations
+fn structural(node: &mut Value, pointer: &mut String, violations: &mut Vec<String>) {
+    let map = match node {
+        Value::Object(map) => map,
+        _ => return,
+    };
+
+    if map.contains_key("$ref") {
+        violations.push(format!(
+            "{}: $ref is not allowed, dereference schema first",
+            pointer
+        ));
+        return;
+    }
+
+    map.retain(|key, _| {
+        !UNSUPPORTED_KEYWORDS.contains(&key.as_str())
+            && (!key.starts_with("x-") || key.starts_with(KUBERNETES_EXTENSION))
+    });
+
+    if let Some(value) = map.remove("const") {
+        map.insert("enum".to_string(), json!([value]));
+    }
+
+    if let Some(Value::Array(examples)) = map.remove("examples") {
+        if let Some(first) = examples.into_iter().next() {
+            map.entry("example").or_insert(first);
+        }
+    }
+
+    normalize_type(map, pointer, violations);
+
+    // unknown fields are pruned by kubernetes, closed object needs no additionalProperties
+    if map.contains_key("properties") && map.get("additionalProperties") == Some(&json!(false)) {
+        map.remove("additionalProperties");
+    }
+
+    if map.contains_key("properties") && map.contains_key("additionalProperties") {
+        violations.push(format!(
+            "{}: properties and additionalProperties are mutually exclusive",
+            pointer
+        ));
+    }
+
+    for (key, value) in map.iter_mut() {
+        let len = pointer.len();
+        pointer.push('/');
+        pointer.push_str(&pointer_escape(key));
+
+        match key.as_str() {
+            "properties" | "patternProperties" => {
+                if let Value::Object(properties) = value {
+                    for (name, property) in properties.iter_mut() {
+                        let len = pointer.len();
+                        pointer.push('/');
+                        pointer.push_str(&pointer_escape(name));
+                        structural(property, pointer, violations);
+                        pointer.truncate(len);
+                    }
+                }
+            }
+            "items" | "additionalProperties" | "not" => structural(value, pointer, violations),
+            "allOf" | "anyOf" | "oneOf" => {
+                if let Value::Array(list) = value {
+                    for (i, item) in list.iter_mut().enumerate() {
+                        let len = pointer.len();
+                        pointer.push_str(&format!("/{}", i));
+                        junctor(item, pointer, violations);
+                        pointer.truncate(len);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        pointer.truncate(len);
+    }
+}
+
+fn normalize_type(map: &mut Map<String, Value>, pointer: &str, violations: &mut Vec<String>) {
+    // type: [string, null] is expressed by nullable
+    if let Some(Value::Array(types)) = map.get("type").cloned() {
+        let (nulls, rest): (Vec<_>, Vec<_>) =
+            types.into_iter().partition(|t| t.as_str() == Some("null"));
+
+        match &rest[..] {
+            [single] => {
+                map.insert("type".to_string(), single.clone());
+            }
+            _ => violations.push(format!("{}: multiple types are not allowed", pointer)),
+        }
+
+        if !nulls.is_empty() {
+            map.insert("nullable".to_string(), Value::Bool(true));
+        }
+    }
+
+    if map.contains_key("type")
+        || map.contains_key("x-kubernetes-int-or-string")
+        || map.contains_key("x-kubernetes-preserve-unknown-fields")
+    {
+        return;
+    }
+
+    if map.contains_key("properties") || map.contains_key("additionalProperties") {
+        map.insert("type".to_string(), json!("object"));
+    } else if map.contains_key("items") {
+        map.insert("type".to_string(), json!("array"));
+    } else if let Some(Value::Array(values)) = map.get("enum") {
+        let inferred = values.first().and_then(|v| match v {
+            Value::String(_) => Some("string"),
+            Value::Bool(_) => Some("boolean"),
+            Value::Number(n) if n.is_i64() || n.is_u64() => Some("integer"),
+            Value::Number(_) => Some("number"),
+            _ => None,
+        });
+
+        match inferred {
+            Some(t) => {
+                map.insert("type".to_string(), json!(t));
+            }
+            None => violations.push(format!("{}: type is required", pointer)),
+        }
+    } else if !["allOf", "anyOf", "oneOf", "not"]
+        .iter()
+        .any(|k| map.contains_key(*k))
+    {
+        violations.push(format!("{}: type is required", pointer));
+    }
+}
+
+// schemas inside allOf/anyOf/oneOf may only constrain values already specified by the outer schema
+fn junctor(node: &mut Value, pointer: &mut String, violations: &mut Vec<String>) {
+    if let Value::Object(map) = node {
+        for key in &[
+            "type",
+            "additionalProperties",
+            "description",
+            "default",
+            "nullable",
+        ] {
+            if map.contains_key(*key) {
+                violations.push(format!(
+                    "{}: {} is not allowed inside value validation",
+                    pointer, key
+                ));
+            }
+        }
+
+        if let Some(Value::Object(properties)) = map.get_mut("properties") {
+            for (name, property) in properties.iter_mut() {
+                let len = pointer.len();
+                pointer.push_str(&format!("/properties/{}", pointer_escape(name)));
+                junctor(property, pointer, violations);
+                pointer.truncate(len);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crd_manifest() {
+        let mut schema = Schema::from_json(json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "Widget",
+            "x-internal": true,
+            "properties": {
+                "size": { "type": ["integer", "null"] },
+                "mode": { "const": "fast" },
+                "labels": { "additionalProperties": { "type": "string" } },
+                "raw": { "x-kubernetes-preserve-unknown-fields": true }
+            }
+        }));
+
+        Crd::options("example.com".to_string(), "Widget".to_string())
+            .process(&mut schema)
+            .unwrap();
+
+        let body = schema.get_body();
+        assert_eq!(body["metadata"]["name"], json!("widgets.example.com"));
+        assert_eq!(body["spec"]["names"]["listKind"], json!("WidgetList"));
+
+        let spec = &body["spec"]["versions"][0]["schema"]["openAPIV3Schema"]["properties"]["spec"];
+        assert_eq!(
+            spec,
+            &json!({
+                "title": "Widget",
+                "type": "object",
+                "properties": {
+                    "size": { "type": "integer", "nullable": true },
+                    "mode": { "enum": ["fast"], "type": "string" },
+                    "labels": { "type": "object", "additionalProperties": { "type": "string" } },
+                    "raw": { "x-kubernetes-preserve-unknown-fields": true }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_closed_object() {
+        let mut schema = Schema::from_json(json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "name": { "type": "string" },
+                "labels": { "type": "object", "additionalProperties": false }
+            }
+        }));
+
+        Crd::options("example.com".to_string(), "Widget".to_string())
+            .process(&mut schema)
+            .unwrap();
+
+        let spec = &schema.get_body()["spec"]["versions"][0]["schema"]["openAPIV3Schema"]
+            ["properties"]["spec"];
+        assert_eq!(
+            spec,
+            &json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "labels": { "type": "object", "additionalProperties": false }
+                }
+            })
+        );
+
+        let mut schema = Schema::from_json(json!({
+            "type": "object",
+            "additionalProperties": { "type": "string" },
+            "properties": { "name": { "type": "string" } }
+        }));
+
+        let result =
+            Crd::options("example.com".to_string(), "Widget".to_string()).process(&mut schema);
+
+        assert_eq!(
+            true,
+            matches!(result, Err(Error::ConvertCrdNotStructural(1)))
+        );
+    }
+
+    #[test]
+    fn test_not_structural_schema() {
+        let mut schema = Schema::from_json(json!({
+            "type": "object",
+            "properties": {
+                "a": { "description": "untyped" },
+                "b": { "$ref": "#/definitions/b" },
+                "c": { "type": "object", "anyOf": [{ "type": "object" }] }
+            }
+        }));
+
+        let result =
+            Crd::options("example.com".to_string(), "Widget".to_string()).process(&mut schema);
+
+        assert_eq!(
+            true,
+            matches!(result, Err(Error::ConvertCrdNotStructural(3)))
+        );
+    }
+}
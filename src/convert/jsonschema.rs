@@ -0,0 +1,427 @@
+use serde_json::{json, Map, Value};
+
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::tools::{pointer_escape, pointer_unescape};
+
+pub static JSONSCHEMA_DRAFTS: &[&str] = &["draft-07", "2019-09", "2020-12"];
+
+static COMPONENTS_PREFIX: &str = "#/components/schemas/";
+
+// openapi only keywords without json schema equivalent
+static OPENAPI_KEYWORDS: &[&str] = &["discriminator", "xml", "externalDocs"];
+
+pub struct JsonSchemaExtractor;
+
+pub struct JsonSchemaExtractorOptions {
+    pub draft: String,
+    pub extension: String,
+}
+
+impl JsonSchemaExtractor {
+    pub fn options() -> JsonSchemaExtractorOptions {
+        JsonSchemaExtractorOptions {
+            draft: "draft-07".to_string(),
+            extension: "json".to_string(),
+        }
+    }
+}
+
+impl JsonSchemaExtractorOptions {
+    pub fn with_draft(&mut self, value: String) -> &mut Self {
+        self.draft = value;
+        self
+    }
+
+    pub fn with_extension(&mut self, value: String) -> &mut Self {
+        self.extension = value;
+        self
+    }
+
+    pub fn filename(&self, name: &str) -> String {
+        format!("{}.{}", name, self.extension)
+    }
+
+    // returns standalone json schema (with filename) of every components/schemas entry
+    pub fn process(&self, schema: &Schema) -> Result<Vec<(String, Value)>, Error> {
+        let uri = match self.draft.as_str() {
+            "draft-07" => "http://json-schema.org/draft-07/schema#",
+            "2019-09" => "https://json-schema.org/draft/2019-09/schema",
+            "2020-12" => "https://json-schema.org/draft/2020-12/schema",
+            draft => return Err(Error::ConvertInvalidDraft(draft.to_string())),
+        };
+
+        let schemas = match schema.get_body().pointer("/components/schemas") {
+            Some(Value::Object(schemas)) => schemas,
+            _ => return Ok(vec![]),
+        };
+
+        Ok(schemas
+            .iter()
+            .map(|(name, model)| {
+                let mut model = model.clone();
+                self.convert(&mut model, name);
+
+                let mut result = Map::new();
+                result.insert("$schema".to_string(), json!(uri));
+
+                match model {
+                    Value::Object(map) => {
+                        if !map.contains_key("title") {
+                            result.insert("title".to_string(), json!(name));
+                        }
+                        result.extend(map);
+                    }
+                    // boolean schemas cannot be annotated
+                    value => return (self.filename(name), value),
+                }
+
+                (self.filename(name), Value::Object(result))
+            })
+            .collect())
+    }
+
+    fn convert(&self, node: &mut Value, current: &str) {
+        let mut nullable_reference = false;
+
+        match node {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get_mut("$ref") {
+                    if let Some(rewritten) = self.reference(reference, current) {
+                        *reference = rewritten;
+                    } else if reference.starts_with('#') {
+                        log::warn!(
+                            "{}: reference {} is not pointing to schema component",
+                            current,
+                            reference
+                        );
+                    }
+                }
+
+                map.retain(|key, _| !OPENAPI_KEYWORDS.contains(&key.as_str()));
+
+                // openapi 3.0 nullable is expressed by null type
+                if let Some(Value::Bool(nullable)) = map.remove("nullable") {
+                    if nullable {
+                        match map.get_mut("type") {
+                            Some(Value::String(type_)) => {
+                                let type_ = type_.clone();
+                                map.insert("type".to_string(), json!([type_, "null"]));
+                            }
+                            Some(Value::Array(types)) if !types.contains(&json!("null")) => {
+                                types.push(json!("null"))
+                            }
+                            Some(_) => {}
+                            // referenced or composed schema cannot be extended with null type
+                            None => {
+                                nullable_reference =
+                                    map.contains_key("$ref") || map.contains_key("allOf")
+                            }
+                        }
+                    }
+                }
+
+                if let Some(example) = map.remove("example") {
+                    map.entry("examples").or_insert_with(|| json!([example]));
+                }
+
+                for (key, value) in map.iter_mut() {
+                    match key.as_str() {
+                        // values, not schemas
+                        "enum" | "const" | "default" | "examples" => {}
+                        // keys are names, not keywords
+                        "properties" | "patternProperties" | "definitions" | "$defs" => {
+                            if let Value::Object(children) = value {
+                                for child in children.values_mut() {
+                                    self.convert(child, current);
+                                }
+                            }
+                        }
+                        _ => self.convert(value, current),
+                    }
+                }
+            }
+            Value::Array(list) => {
+                for value in list {
+                    self.convert(value, current);
+                }
+            }
+            _ => {}
+        }
+
+        if nullable_reference {
+            *node = json!({ "anyOf": [node.take(), { "type": "null" }] });
+        }
+    }
+
+    // #/components/schemas/Name/... -> Name.json#/...
+    fn reference(&self, reference: &str, current: &str) -> Option<String> {
+        let rest = reference.strip_prefix(COMPONENTS_PREFIX)?;
+
+        let (name, pointer) = match rest.find('/') {
+            Some(position) => (&rest[..position], &rest[position..]),
+            None => (rest, ""),
+        };
+        let name = pointer_unescape(name);
+
+        let file = if name == current {
+            String::new()
+        } else {
+            self.filename(&name)
+        };
+
+        Some(if pointer.is_empty() && !file.is_empty() {
+            file
+        } else {
+            format!("{}#{}", file, pointer)
+        })
+    }
+}
+
+pub struct JsonSchemaAssembler;
+
+pub struct JsonSchemaAssemblerOptions {
+    // relative path of file and its content
+    pub schemas: Vec<(String, Schema)>,
+}
+
+impl JsonSchemaAssembler {
+    pub fn options() -> JsonSchemaAssemblerOptions {
+        JsonSchemaAssemblerOptions { schemas: vec![] }
+    }
+}
+
+impl JsonSchemaAssemblerOptions {
+    pub fn with_schema(&mut self, relative: String, schema: Schema) -> &mut Self {
+        self.schemas.push((relative, schema));
+        self
+    }
+
+    // imports standalone json schemas into components/schemas of base specification
+    pub fn process(&self, base: &mut Schema) -> Result<(), Error> {
+        let names = self
+            .schemas
+            .iter()
+            .map(|(relative, _)| (normalize(relative), component_name(relative)))
+            .collect::<Vec<_>>();
+
+        let body = base.get_body_mut();
+        if body.pointer("/components/schemas").is_none() {
+            body.as_object_mut()
+                .ok_or(Error::InvalidOpenapiSchemaError)?
+                .entry("components")
+                .or_insert_with(|| json!({}))
+                .as_object_mut()
+                .ok_or(Error::InvalidOpenapiSchemaError)?
+                .insert("schemas".to_string(), json!({}));
+        }
+
+        let components = body
+            .pointer_mut("/components/schemas")
+            .and_then(|c| c.as_object_mut())
+            .ok_or(Error::InvalidOpenapiSchemaError)?;
+
+        for ((relative, schema), (_, name)) in self.schemas.iter().zip(names.iter()) {
+            if components.contains_key(name) {
+                return Err(Error::ConvertDuplicatedSchema(name.clone()));
+            }
+
+            let mut model = schema.get_body().clone();
+            if let Value::Object(map) = &mut model {
+                map.remove("$schema");
+                map.remove("$id");
+            }
+
+            localize(&mut model, &normalize(relative), name, &names);
+            components.insert(name.clone(), model);
+        }
+
+        Ok(())
+    }
+}
+
+fn component_name(relative: &str) -> String {
+    let filename = relative.rsplit('/').next().unwrap_or(relative);
+
+    match filename.rfind('.') {
+        Some(position) if position > 0 => filename[..position].to_string(),
+        _ => filename.to_string(),
+    }
+}
+
+// resolves . and .. segments of relative path
+fn normalize(path: &str) -> String {
+    let mut parts: Vec<&str> = vec![];
+
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+
+    parts.join("/")
+}
+
+// rewrites references between files to local pointers of components
+fn localize(node: &mut Value, relative: &str, current: &str, names: &[(String, String)]) {
+    match node {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get_mut("$ref") {
+                let (path, pointer) = match reference.find('#') {
+                    Some(position) => (&reference[..position], &reference[position + 1..]),
+                    None => (reference.as_str(), ""),
+                };
+
+                let target = if path.is_empty() {
+                    Some(current.to_string())
+                } else if path.contains("://") {
+                    None
+                } else {
+                    let directory = relative.rfind('/').map(|p| &relative[..p]).unwrap_or("");
+                    let resolved = normalize(&format!("{}/{}", directory, path));
+
+                    names
+                        .iter()
+                        .find(|(file, _)| file == &resolved)
+                        .map(|(_, name)| name.clone())
+                };
+
+                match target {
+                    Some(name) => {
+                        *reference =
+                            format!("{}{}{}", COMPONENTS_PREFIX, pointer_escape(&name), pointer)
+                    }
+                    None => log::warn!("{}: external reference {} kept", relative, reference),
+                }
+            }
+
+            for (key, value) in map.iter_mut() {
+                if !matches!(key.as_str(), "enum" | "const" | "default" | "examples") {
+                    localize(value, relative, current, names);
+                }
+            }
+        }
+        Value::Array(list) => {
+            for value in list {
+                localize(value, relative, current, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_components() {
+        let schema = Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "discriminator": { "propertyName": "kind" },
+                        "properties": {
+                            "name": { "type": "string", "nullable": true, "example": "John" },
+                            "group": { "$ref": "#/components/schemas/Group" },
+                            "parent": { "$ref": "#/components/schemas/User" },
+                            "kind": { "$ref": "#/components/schemas/Group/properties/kind" },
+                            "manager": { "$ref": "#/components/schemas/User", "nullable": true },
+                            "team": {
+                                "allOf": [{ "$ref": "#/components/schemas/Group" }],
+                                "nullable": true
+                            }
+                        }
+                    },
+                    "Group": {
+                        "title": "Users group",
+                        "type": "object",
+                        "properties": { "kind": { "type": "string", "enum": ["a", "b"] } }
+                    }
+                }
+            }
+        }));
+
+        let result = JsonSchemaExtractor::options()
+            .with_draft("2020-12".to_string())
+            .process(&schema)
+            .unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].0, "User.json");
+        assert_eq!(
+            result[0].1,
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "title": "User",
+                "type": "object",
+                "properties": {
+                    "name": { "type": ["string", "null"], "examples": ["John"] },
+                    "group": { "$ref": "Group.json" },
+                    "parent": { "$ref": "#" },
+                    "kind": { "$ref": "Group.json#/properties/kind" },
+                    "manager": { "anyOf": [{ "$ref": "#" }, { "type": "null" }] },
+                    "team": {
+                        "anyOf": [{ "allOf": [{ "$ref": "Group.json" }] }, { "type": "null" }]
+                    }
+                }
+            })
+        );
+        assert_eq!(result[1].1["title"], json!("Users group"));
+    }
+
+    #[test]
+    fn test_assemble_components() {
+        let mut base = Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "components": { "schemas": { "Error": { "type": "string" } } }
+        }));
+
+        JsonSchemaAssembler::options()
+            .with_schema(
+                "users/User.json".to_string(),
+                Schema::from_json(json!({
+                    "$schema": "http://json-schema.org/draft-07/schema#",
+                    "type": "object",
+                    "properties": {
+                        "group": { "$ref": "../Group.json" },
+                        "kind": { "$ref": "../Group.json#/properties/kind" },
+                        "parent": { "$ref": "#" }
+                    }
+                })),
+            )
+            .with_schema(
+                "Group.json".to_string(),
+                Schema::from_json(json!({ "type": "object" })),
+            )
+            .process(&mut base)
+            .unwrap();
+
+        assert_eq!(
+            base.get_body()["components"]["schemas"]["User"],
+            json!({
+                "type": "object",
+                "properties": {
+                    "group": { "$ref": "#/components/schemas/Group" },
+                    "kind": { "$ref": "#/components/schemas/Group/properties/kind" },
+                    "parent": { "$ref": "#/components/schemas/User" }
+                }
+            })
+        );
+
+        let result = JsonSchemaAssembler::options()
+            .with_schema("Error.json".to_string(), Schema::from_json(json!({})))
+            .process(&mut base);
+
+        assert_eq!(
+            true,
+            matches!(result, Err(Error::ConvertDuplicatedSchema(_)))
+        );
+    }
+}
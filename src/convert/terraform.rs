@@ -0,0 +1,376 @@
+use inflector::Inflector;
+use serde_json::{json, Map, Value};
+
+use crate::error::Error;
+use crate::schema::Schema;
+
+static VALIDATORS_PACKAGE: &str = "github.com/hashicorp/terraform-plugin-framework-validators";
+
+pub struct Terraform;
+
+pub struct TerraformOptions {
+    pub provider: String,
+    pub version: String,
+}
+
+impl Terraform {
+    pub fn options(provider: String) -> TerraformOptions {
+        TerraformOptions {
+            provider,
+            version: "0.1".to_string(),
+        }
+    }
+}
+
+impl TerraformOptions {
+    pub fn with_version(&mut self, value: String) -> &mut Self {
+        self.version = value;
+        self
+    }
+
+    // replaces json schema models (or openapi components) with terraform plugin framework provider code specification
+    pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
+        log::warn!("terraform conversion is experimental, review generated specification");
+
+        let body = schema.get_body();
+
+        let models = match body.pointer("/components/schemas") {
+            Some(Value::Object(schemas)) => schemas
+                .iter()
+                .map(|(name, model)| (name.clone(), model.clone()))
+                .collect::<Vec<_>>(),
+            _ => vec![(
+                body.get("title")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or(&self.provider)
+                    .to_string(),
+                body.clone(),
+            )],
+        };
+
+        let resources = models
+            .iter()
+            .filter(|(_, model)| model.get("properties").is_some())
+            .map(|(name, model)| {
+                let mut pointer = format!("/{}", name);
+                let mut resource = json!({
+                    "name": format!("{}_{}", self.provider.to_snake_case(), name.to_snake_case()),
+                    "schema": {
+                        "attributes": attributes(model, &mut pointer)
+                    }
+                });
+
+                if let Some(description) = model.get("description") {
+                    resource["schema"]["description"] = description.clone();
+                }
+
+                resource
+            })
+            .collect::<Vec<_>>();
+
+        let result = json!({
+            "provider": {
+                "name": self.provider.to_snake_case()
+            },
+            "resources": resources,
+            "version": self.version
+        });
+
+        schema.get_body_mut().clone_from(&result);
+
+        Ok(())
+    }
+}
+
+fn attributes(model: &Value, pointer: &mut String) -> Vec<Value> {
+    let required = model
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let properties = match model.get("properties") {
+        Some(Value::Object(properties)) => properties,
+        _ => return vec![],
+    };
+
+    properties
+        .iter()
+        .filter_map(|(name, property)| {
+            let len = pointer.len();
+            pointer.push_str(&format!("/{}", name));
+
+            let attribute = attribute(property, required.contains(&name.as_str()), pointer);
+
+            if attribute.is_none() {
+                log::warn!("{}: unsupported attribute skipped", pointer);
+            }
+
+            pointer.truncate(len);
+
+            attribute.map(|mut definition| {
+                let mut result = Map::new();
+                result.insert("name".to_string(), json!(name.to_snake_case()));
+                result.append(&mut definition);
+                Value::Object(result)
+            })
+        })
+        .collect()
+}
+
+fn attribute(property: &Value, required: bool, pointer: &mut String) -> Option<Map<String, Value>> {
+    let map = property.as_object()?;
+
+    let (kind, mut details) = match terraform_type(map)? {
+        "object" if map.contains_key("properties") => (
+            "single_nested",
+            json!({ "attributes": attributes(property, pointer) }),
+        ),
+        "object" => match map.get("additionalProperties") {
+            Some(Value::Object(value)) if value.contains_key("properties") => (
+                "map_nested",
+                json!({ "nested_object": { "attributes": attributes(&map["additionalProperties"], pointer) } }),
+            ),
+            Some(value @ Value::Object(_)) => {
+                ("map", json!({ "element_type": element_type(value)? }))
+            }
+            _ => return None,
+        },
+        "array" => {
+            let items = map.get("items")?;
+            if items.get("properties").is_some() {
+                (
+                    "list_nested",
+                    json!({ "nested_object": { "attributes": attributes(items, pointer) } }),
+                )
+            } else {
+                ("list", json!({ "element_type": element_type(items)? }))
+            }
+        }
+        primitive => (primitive, json!({})),
+    };
+
+    let behavior = if map.get("readOnly") == Some(&Value::Bool(true)) {
+        "computed"
+    } else if required {
+        "required"
+    } else if map.contains_key("default") {
+        "computed_optional"
+    } else {
+        "optional"
+    };
+
+    details["computed_optional_required"] = json!(behavior);
+
+    if let Some(description) = map.get("description") {
+        details["description"] = description.clone();
+    }
+
+    if map.get("writeOnly") == Some(&Value::Bool(true))
+        || map.get("format") == Some(&json!("password"))
+    {
+        details["sensitive"] = json!(true);
+    }
+
+    let validators = validators(kind, map);
+    if !validators.is_empty() {
+        details["validators"] = json!(validators);
+    }
+
+    let mut result = Map::new();
+    result.insert(kind.to_string(), details);
+
+    Some(result)
+}
+
+// maps json schema type to terraform attribute type, objects and arrays are resolved by caller
+fn terraform_type(map: &Map<String, Value>) -> Option<&'static str> {
+    let type_ = match map.get("type") {
+        Some(Value::String(t)) => t.as_str(),
+        Some(Value::Array(types)) => {
+            let types = types
+                .iter()
+                .filter_map(|t| t.as_str())
+                .filter(|t| *t != "null")
+                .collect::<Vec<_>>();
+
+            match types[..] {
+                [single] => single,
+                _ => return None,
+            }
+        }
+        _ if map.contains_key("properties") || map.contains_key("additionalProperties") => "object",
+        _ if map.contains_key("items") => "array",
+        _ => return None,
+    };
+
+    match type_ {
+        "string" => Some("string"),
+        "integer" => Some("int64"),
+        "number" => Some("float64"),
+        "boolean" => Some("bool"),
+        "object" => Some("object"),
+        "array" => Some("array"),
+        _ => None,
+    }
+}
+
+fn element_type(node: &Value) -> Option<Value> {
+    let map = node.as_object()?;
+
+    match terraform_type(map)? {
+        "object" => None,
+        "array" => Some(json!({ "list": { "element_type": element_type(map.get("items")?)? } })),
+        primitive => Some(json!({ primitive: {} })),
+    }
+}
+
+fn validators(kind: &str, map: &Map<String, Value>) -> Vec<Value> {
+    let package = match kind {
+        "string" => "stringvalidator",
+        "int64" => "int64validator",
+        "float64" => "float64validator",
+        "list" | "list_nested" => "listvalidator",
+        "map" | "map_nested" => "mapvalidator",
+        _ => return vec![],
+    };
+
+    let number = |key: &str| {
+        map.get(key)
+            .filter(|v| v.is_number())
+            .map(|v| v.to_string())
+    };
+
+    let (min, max) = match kind {
+        "string" => (number("minLength"), number("maxLength")),
+        "list" | "list_nested" => (number("minItems"), number("maxItems")),
+        "map" | "map_nested" => (number("minProperties"), number("maxProperties")),
+        _ => (number("minimum"), number("maximum")),
+    };
+
+    let (between, at_least, at_most) = match kind {
+        "string" => ("LengthBetween", "LengthAtLeast", "LengthAtMost"),
+        "int64" | "float64" => ("Between", "AtLeast", "AtMost"),
+        _ => ("SizeBetween", "SizeAtLeast", "SizeAtMost"),
+    };
+
+    let mut definitions = vec![];
+
+    match (min, max) {
+        (Some(min), Some(max)) => definitions.push(format!("{}({}, {})", between, min, max)),
+        (Some(min), None) => definitions.push(format!("{}({})", at_least, min)),
+        (None, Some(max)) => definitions.push(format!("{}({})", at_most, max)),
+        (None, None) => {}
+    }
+
+    if let Some(Value::Array(values)) = map.get("enum") {
+        if kind == "string" || kind == "int64" || kind == "float64" {
+            definitions.push(format!(
+                "OneOf({})",
+                values
+                    .iter()
+                    .filter(|v| !v.is_null())
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+    }
+
+    let mut result = definitions
+        .into_iter()
+        .map(|definition| {
+            json!({
+                "custom": {
+                    "imports": [{ "path": format!("{}/{}", VALIDATORS_PACKAGE, package) }],
+                    "schema_definition": format!("{}.{}", package, definition)
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if let (Some(Value::String(pattern)), "string") = (map.get("pattern"), kind) {
+        result.push(json!({
+            "custom": {
+                "imports": [
+                    { "path": "regexp" },
+                    { "path": format!("{}/{}", VALIDATORS_PACKAGE, package) }
+                ],
+                "schema_definition": format!(
+                    "{}.RegexMatches(regexp.MustCompile({}), \"\")",
+                    package,
+                    Value::String(pattern.clone())
+                )
+            }
+        }));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terraform_resources() {
+        let mut schema = Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "components": {
+                "schemas": {
+                    "Widget": {
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": {
+                            "id": { "type": "string", "readOnly": true },
+                            "name": { "type": "string", "minLength": 1, "maxLength": 64 },
+                            "sizeLimit": { "type": "integer", "default": 10, "enum": [10, 20] },
+                            "tags": { "type": "array", "items": { "type": "string" } },
+                            "owner": {
+                                "type": "object",
+                                "properties": { "email": { "type": "string" } }
+                            }
+                        }
+                    },
+                    "Status": { "type": "string" }
+                }
+            }
+        }));
+
+        Terraform::options("acme".to_string())
+            .process(&mut schema)
+            .unwrap();
+
+        let body = schema.get_body();
+        assert_eq!(body["provider"]["name"], json!("acme"));
+        assert_eq!(body["resources"].as_array().unwrap().len(), 1);
+        assert_eq!(body["resources"][0]["name"], json!("acme_widget"));
+
+        let attributes = &body["resources"][0]["schema"]["attributes"];
+        assert_eq!(
+            attributes[0],
+            json!({ "name": "id", "string": { "computed_optional_required": "computed" } })
+        );
+        assert_eq!(
+            attributes[1]["string"]["validators"][0]["custom"]["schema_definition"],
+            json!("stringvalidator.LengthBetween(1, 64)")
+        );
+        assert_eq!(attributes[2]["name"], json!("size_limit"));
+        assert_eq!(
+            attributes[2]["int64"]["computed_optional_required"],
+            json!("computed_optional")
+        );
+        assert_eq!(
+            attributes[2]["int64"]["validators"][0]["custom"]["schema_definition"],
+            json!("int64validator.OneOf(10, 20)")
+        );
+        assert_eq!(
+            attributes[3]["list"]["element_type"],
+            json!({ "string": {} })
+        );
+        assert_eq!(
+            attributes[4]["single_nested"]["attributes"][0],
+            json!({ "name": "email", "string": { "computed_optional_required": "optional" } })
+        );
+    }
+}
@@ -6,8 +6,16 @@ use walkdir::WalkDir;
 pub struct Discovered {
     pub templates: HashMap<String, String>,
     pub files: HashMap<String, PathBuf>,
+
+    // source paths of templates, permissions of rendered files are taken from them
+    pub sources: HashMap<String, PathBuf>,
+
+    // manifests of template directories, not copied to target directory
+    pub manifests: Vec<PathBuf>,
 }
 
+pub static MANIFESTS: &[&str] = &["manifest.yaml", "manifest.yml", "manifest.json"];
+
 #[derive(Debug, Default)]
 pub struct Discovery {
     registries: HashMap<String, Registry>,
@@ -21,6 +29,8 @@ impl Discovery {
     pub fn resolve(&self, tpls: &[String]) -> Result<Discovered, Error> {
         let mut templates: HashMap<String, String> = HashMap::new();
         let mut files: HashMap<String, PathBuf> = HashMap::new();
+        let mut sources: HashMap<String, PathBuf> = HashMap::new();
+        let mut manifests: Vec<PathBuf> = vec![];
 
         // -----------------------+
         // formats:               |
@@ -69,9 +79,12 @@ impl Discovery {
                     entry.clone().into_path()
                 };
 
-                if relative.ends_with(".j2") {
-                    let content = fs::read_to_string(path).map_err(Error::DiscoveryReadFile)?;
+                if MANIFESTS.contains(&relative.as_ref()) {
+                    manifests.push(path);
+                } else if relative.ends_with(".j2") {
+                    let content = fs::read_to_string(&path).map_err(Error::DiscoveryReadFile)?;
                     templates.insert(relative.to_string(), content);
+                    sources.insert(relative.to_string(), path);
                 } else {
                     // full path
                     files.insert(relative.to_string(), path);
@@ -79,7 +92,12 @@ impl Discovery {
             }
         }
 
-        Ok(Discovered { templates, files })
+        Ok(Discovered {
+            templates,
+            files,
+            sources,
+            manifests,
+        })
     }
 }
 
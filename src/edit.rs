@@ -0,0 +1,251 @@
+use serde_json::Value;
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::tools::{pointer_escape, pointer_unescape};
+
+pub static VALUE_TYPES: &[&str] = &[
+    "auto", "string", "number", "integer", "boolean", "null", "json",
+];
+
+// how value given as text in command line is converted before it is set
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueType {
+    Auto,
+    String,
+    Number,
+    Integer,
+    Boolean,
+    Null,
+    Json,
+}
+
+impl FromStr for ValueType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "string" => Ok(Self::String),
+            "number" => Ok(Self::Number),
+            "integer" => Ok(Self::Integer),
+            "boolean" => Ok(Self::Boolean),
+            "null" => Ok(Self::Null),
+            "json" => Ok(Self::Json),
+            _ => Err(Error::EditInvalidValueType(s.to_string())),
+        }
+    }
+}
+
+impl ValueType {
+    // auto keeps strings as strings (so `1.0` stays a version), other text is parsed as json
+    pub fn coerce(&self, raw: &str, existing: Option<&Value>) -> Result<Value, Error> {
+        let invalid = |type_: &str| Error::EditInvalidValue {
+            value: raw.to_string(),
+            type_: type_.to_string(),
+        };
+
+        match self {
+            Self::Auto => match existing {
+                Some(Value::String(_)) => Ok(Value::String(raw.to_string())),
+                _ => Ok(
+                    serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+                ),
+            },
+            Self::String => Ok(Value::String(raw.to_string())),
+            Self::Number => match serde_json::from_str(raw) {
+                Ok(Value::Number(n)) => Ok(Value::Number(n)),
+                _ => Err(invalid("number")),
+            },
+            Self::Integer => raw
+                .parse::<i64>()
+                .map(Value::from)
+                .map_err(|_| invalid("integer")),
+            Self::Boolean => raw
+                .parse::<bool>()
+                .map(Value::Bool)
+                .map_err(|_| invalid("boolean")),
+            Self::Null => Ok(Value::Null),
+            Self::Json => serde_json::from_str(raw).map_err(|_| invalid("json")),
+        }
+    }
+}
+
+fn tokens(pointer: &str) -> Result<Vec<String>, Error> {
+    let pointer = pointer.trim_start_matches('#');
+
+    match pointer.strip_prefix('/') {
+        Some(path) => Ok(path.split('/').map(pointer_unescape).collect()),
+        None => Err(Error::EditInvalidPointer(pointer.to_string())),
+    }
+}
+
+// sets value by json pointer, missing objects on the way are created and `-` appends to array
+pub fn set(root: &mut Value, pointer: &str, value: Value) -> Result<(), Error> {
+    let tokens = tokens(pointer)?;
+    let (last, parents) = tokens.split_last().unwrap();
+
+    let mut node = root;
+    for token in parents {
+        node = match node {
+            Value::Object(map) => map
+                .entry(token.clone())
+                .or_insert_with(|| Value::Object(Default::default())),
+            Value::Array(list) => {
+                let index = index(token, list.len(), pointer)?;
+                if index == list.len() {
+                    list.push(Value::Object(Default::default()));
+                }
+                &mut list[index]
+            }
+            _ => return Err(Error::EditInvalidPointer(pointer.to_string())),
+        };
+    }
+
+    match node {
+        Value::Object(map) => {
+            map.insert(last.clone(), value);
+        }
+        Value::Array(list) => {
+            let index = index(last, list.len(), pointer)?;
+            if index == list.len() {
+                list.push(value);
+            } else {
+                list[index] = value;
+            }
+        }
+        _ => return Err(Error::EditInvalidPointer(pointer.to_string())),
+    }
+
+    Ok(())
+}
+
+// removes value by json pointer and returns it, order of remaining keys is kept
+pub fn unset(root: &mut Value, pointer: &str) -> Result<Value, Error> {
+    let tokens = tokens(pointer)?;
+    let (last, parents) = tokens.split_last().unwrap();
+
+    let parent_pointer = parents
+        .iter()
+        .map(|t| format!("/{}", pointer_escape(t)))
+        .collect::<String>();
+    let not_found = || Error::QueryNotFound(pointer.to_string());
+
+    match root.pointer_mut(&parent_pointer) {
+        Some(Value::Object(map)) if map.contains_key(last) => {
+            let mut removed = Value::Null;
+            *map = std::mem::take(map)
+                .into_iter()
+                .filter_map(|(k, v)| {
+                    if &k == last {
+                        removed = v;
+                        None
+                    } else {
+                        Some((k, v))
+                    }
+                })
+                .collect();
+            Ok(removed)
+        }
+        Some(Value::Array(list)) => match last.parse::<usize>() {
+            Ok(i) if i < list.len() => Ok(list.remove(i)),
+            _ => Err(not_found()),
+        },
+        _ => Err(not_found()),
+    }
+}
+
+fn index(token: &str, len: usize, pointer: &str) -> Result<usize, Error> {
+    match token {
+        "-" => Ok(len),
+        token => match token.parse::<usize>() {
+            Ok(i) if i <= len => Ok(i),
+            _ => Err(Error::EditInvalidPointer(pointer.to_string())),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_set_and_unset() {
+        let mut spec = json!({
+            "openapi": "3.0.0",
+            "info": { "title": "api", "version": "1.0.0", "description": "x" },
+            "tags": [{ "name": "users" }]
+        });
+
+        set(&mut spec, "/info/version", json!("2.3.0")).unwrap();
+        set(&mut spec, "/info/x-owner/team", json!("core")).unwrap();
+        set(&mut spec, "/tags/-", json!({ "name": "groups" })).unwrap();
+        set(&mut spec, "/paths/~1users", json!({})).unwrap();
+
+        assert!(matches!(
+            set(&mut spec, "/openapi/version", json!(1)),
+            Err(Error::EditInvalidPointer(_))
+        ));
+        assert!(matches!(
+            set(&mut spec, "/tags/5", json!(1)),
+            Err(Error::EditInvalidPointer(_))
+        ));
+
+        assert_eq!(unset(&mut spec, "/info/title").unwrap(), json!("api"));
+        assert_eq!(
+            unset(&mut spec, "#/tags/0").unwrap(),
+            json!({ "name": "users" })
+        );
+        assert!(matches!(
+            unset(&mut spec, "/info/title"),
+            Err(Error::QueryNotFound(_))
+        ));
+
+        assert_eq!(
+            serde_json::to_string(&spec).unwrap(),
+            serde_json::to_string(&json!({
+                "openapi": "3.0.0",
+                "info": { "version": "2.3.0", "description": "x", "x-owner": { "team": "core" } },
+                "tags": [{ "name": "groups" }],
+                "paths": { "/users": {} }
+            }))
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_coerce() {
+        let string = json!("1.0");
+        let number = json!(1);
+
+        assert_eq!(
+            ValueType::Auto.coerce("2.0", Some(&string)).unwrap(),
+            json!("2.0")
+        );
+        assert_eq!(
+            ValueType::Auto.coerce("2.0", Some(&number)).unwrap(),
+            json!(2.0)
+        );
+        assert_eq!(ValueType::Auto.coerce("true", None).unwrap(), json!(true));
+        assert_eq!(
+            ValueType::Auto.coerce("2.3.0", None).unwrap(),
+            json!("2.3.0")
+        );
+        assert_eq!(
+            ValueType::String.coerce("true", None).unwrap(),
+            json!("true")
+        );
+        assert_eq!(ValueType::Integer.coerce("10", None).unwrap(), json!(10));
+        assert_eq!(
+            ValueType::Json.coerce(r#"{"a": [1]}"#, None).unwrap(),
+            json!({ "a": [1] })
+        );
+        assert_eq!(ValueType::Null.coerce("", None).unwrap(), json!(null));
+
+        assert!(ValueType::Integer.coerce("1.5", None).is_err());
+        assert!(ValueType::Boolean.coerce("yes", None).is_err());
+        assert!(ValueType::Number.coerce("\"1\"", None).is_err());
+        assert!("text".parse::<ValueType>().is_err());
+    }
+}
@@ -17,6 +17,9 @@ pub enum Error {
     #[error("Cannot deserialize yaml document: {0}")]
     DeserializeYamlError(serde_yaml::Error),
 
+    #[error("Cannot serialize yaml document: {0}")]
+    SerializeYamlError(serde_yaml::Error),
+
     #[error("Filter {0} is incorrect")]
     IncorrectFilterError(String),
 
@@ -65,9 +68,120 @@ pub enum Error {
     #[error("Cannot name model: {0}")]
     CodegenCannotNameModelError(String),
 
+    #[error("Identifier contains non ascii characters: {0}")]
+    CodegenInvalidIdentifier(String),
+
     #[error("Json Patch error occured: {0}")]
     JsonPatchError(json_patch::PatchError),
 
+    #[error("Schema is not structural, {0} violations found")]
+    ConvertCrdNotStructural(usize),
+
+    #[error("Not supported json schema draft: {0}")]
+    ConvertInvalidDraft(String),
+
+    #[error("Schema {0} is defined more than once")]
+    ConvertDuplicatedSchema(String),
+
+    #[error("Payload budget exceeded by {0} operations")]
+    AnalyzePayloadBudgetExceeded(usize),
+
+    #[error("{0} changes are not allowed by compatibility policy")]
+    AnalyzeIncompatibleChanges(usize),
+
+    #[error("Invalid compatibility policy: {0}")]
+    AnalyzeInvalidPolicy(String),
+
+    #[error("{0} recorded payloads are not valid against schema")]
+    AnalyzeReplayFailures(usize),
+
+    #[error("Cannot read recorded payloads: {0}")]
+    AnalyzeReplayCorpus(String),
+
+    #[error("Invalid schema of replay: {0}")]
+    AnalyzeReplayInvalidSchema(String),
+
+    #[error("Invalid usage report: {0}")]
+    UsageInvalidReport(String),
+
+    #[error("Cannot mark as deprecated, target not found: {0}")]
+    DeprecationInvalidTarget(String),
+
+    #[error("Invalid date {0}, expected YYYY-MM-DD")]
+    DeprecationInvalidDate(String),
+
+    #[error("Translation bundle should be a map of json pointers to texts: {0}")]
+    LocalizeInvalidBundle(String),
+
+    #[error("Cannot find translation bundle for locale {0}")]
+    LocalizeBundleNotFound(String),
+
+    #[error("Invalid secret pattern of redaction: {0}")]
+    RedactInvalidPattern(String),
+
+    #[error("Reference {0} points to redacted element")]
+    RedactDanglingReference(String),
+
+    #[error("Invalid query {0}, expected json pointer or jsonpath")]
+    QueryInvalid(String),
+
+    #[error("Nothing found by query {0}")]
+    QueryNotFound(String),
+
+    #[error("Terminal error of explorer: {0}")]
+    ExploreTerminalError(String),
+
+    #[error("Invalid pointer to edit {0}, expected json pointer of existing container")]
+    EditInvalidPointer(String),
+
+    #[error("Invalid value type: {0}")]
+    EditInvalidValueType(String),
+
+    #[error("Value {value} is not {type_}")]
+    EditInvalidValue { value: String, type_: String },
+
+    #[error("Cannot write edited file: {0}")]
+    EditWriteError(String),
+
+    #[error("Invalid constraint strategy of allOf merge: {0}")]
+    MergeAllOfInvalidStrategy(String),
+
+    #[error("Cannot merge allOf of {0}: {1}")]
+    MergeAllOfIncompatible(String, String),
+
+    #[error("Processing limit exceeded: {0}")]
+    ProcessLimitExceeded(String),
+
+    #[error("Processing changed specification expected to be processed already: {0}")]
+    ProcessUnexpectedChange(String),
+
+    #[error("Invalid report format: {0}")]
+    ProcessInvalidReportFormat(String),
+
+    #[error("Unset variables of interpolation: {0}")]
+    InterpolateUnsetVariable(String),
+
+    #[error("Imported component {0} collides with existing one")]
+    ImportCollision(String),
+
+    #[error("Cannot find imported component {0}, expected kind/name (ex. schemas/Address)")]
+    ImportComponentNotFound(String),
+
+    #[error("Cannot import components into {0}")]
+    ImportInvalidTarget(String),
+
+    #[error("Invalid path pattern of injection: {0}")]
+    InjectInvalidPattern(String),
+
+    #[error("Injected parameter has no name and location or cannot be resolved: {0}")]
+    InjectInvalidParameter(String),
+
+    #[error("Invalid overlay target: {0}")]
+    OverlayInvalidTarget(String),
+
+    #[error("Invalid patch operation: {0}")]
+    PatchInvalidOperation(String),
+
     #[error("Cannot fill parameters: {0}")]
     CannotFillParameters(String),
 
@@ -86,6 +200,15 @@ pub enum Error {
     #[error("Codegen file error: {0}")]
     CodegenFileError(String),
 
+    #[error("Codegen rendered empty file: {0}")]
+    CodegenEmptyOutput(String),
+
+    #[error("Codegen file {0} rendered by both {1} and {2}")]
+    CodegenFileCollision(String, String, String),
+
+    #[error("Codegen invalid collision policy: {0}")]
+    CodegenInvalidCollisionPolicy(String),
+
     #[error("Codegen template error: {0:?}")]
     CodegenTemplateError(tera::Error),
 
@@ -104,6 +227,33 @@ pub enum Error {
     #[error("Endpoint format is invalid")]
     CodegenInvalidEndpointFormat,
 
+    #[error("Invalid policy extension {0}: {1}")]
+    CodegenInvalidPolicy(String, String),
+
+    #[error("Codegen profile not found: {0}")]
+    CodegenUnknownProfile(String),
+
+    #[error("Codegen invalid profiles: {0}")]
+    CodegenInvalidProfiles(String),
+
+    #[error("Codegen invalid import styles: {0}")]
+    CodegenInvalidImports(String),
+
+    #[error("Codegen unknown import style: {0}")]
+    CodegenUnknownImportStyle(String),
+
+    #[error("Codegen target not found: {0}")]
+    CodegenUnknownTarget(String),
+
+    #[error("Invalid owner {0}: {1}")]
+    CodegenInvalidOwner(String, String),
+
+    #[error("Cannot write ownership output {0}: {1}")]
+    CodegenOwnershipWriteError(String, std::io::Error),
+
+    #[error("Cannot anchor CODEOWNERS paths, target directory {0} is outside of {1}")]
+    CodegenOwnershipOutsideRoot(String, String),
+
     #[error("Security scheme format is invalid")]
     CodegenInvalidSecuritySchemeFormat,
 
@@ -143,9 +293,27 @@ pub enum Error {
     #[error("Cannot guess base name of schema")]
     NamingBaseNameNotFound,
 
+    #[error("Invalid inflection {0}, expected singular=plural")]
+    NamingInvalidInflection(String),
+
+    #[error("Invalid format {0}: {1}")]
+    InvalidFormat(String, String),
+
     #[error("Json schema is invalid: {0}")]
     JsonSchemaInvalid(String),
 
+    #[error("Invalid baseline mode {0}, expected write or check")]
+    ValidationInvalidBaselineMode(String),
+
+    #[error("Baseline file error: {0}")]
+    ValidationBaselineError(String),
+
+    #[error("Invalid severity: {0}")]
+    ValidationInvalidSeverity(String),
+
+    #[error("{0} cross-service dependencies are broken")]
+    ValidationBrokenDependencies(usize),
+
     #[error("Cannot validate schema {0}")]
     SchemaValidation(String),
 
@@ -188,6 +356,81 @@ pub enum Error {
     #[error("Derefence critical issue: {0}")]
     DereferenceError(String),
 
+    #[error("Source map file error: {0}")]
+    SourceMapError(String),
+
+    #[error("Cannot add extracted schemas to specification: {0}")]
+    DeduplicateInvalidTarget(String),
+
+    #[error("Invalid property case: {0}")]
+    RenamePropertiesInvalidCase(String),
+
+    #[error("Renamed properties collide: {0}")]
+    RenamePropertiesCollision(String),
+
+    #[error("Invalid fragment: {0}")]
+    ComposeInvalidFragment(String),
+
+    #[error("Fragments conflict: {0}")]
+    ComposeConflict(String),
+
+    #[error("Composed specification has unresolved reference: {0}")]
+    ComposeUnresolvedReference(String),
+
+    #[error("Invalid publish registry: {0}")]
+    PublishInvalidRegistry(String),
+
+    #[error("Invalid name or tag of published specification: {0}")]
+    PublishInvalidArtifact(String),
+
+    #[error("Cannot publish specification: {0}")]
+    PublishFailed(String),
+
+    #[error("Cannot commit published specification: {0}")]
+    PublishGitError(git2::Error),
+
+    #[error("Invalid api version: {0}")]
+    CutVersionInvalidVersion(String),
+
+    #[error("Reference {0} points to element removed in cut version")]
+    CutVersionDanglingReference(String),
+
+    #[error("Specification registry is not configured, set {0}")]
+    SpecRegistryNotConfigured(String),
+
+    #[error("Invalid registry reference: {0}, expected name@range (ex. orders-api@^2)")]
+    SpecRegistryInvalidReference(String),
+
+    #[error("No published version matches {0}")]
+    SpecRegistryNoMatchingVersion(String),
+
+    #[error("Cannot fetch specification from registry: {0}")]
+    SpecRegistryError(String),
+
+    #[error("Cannot load file at git revision: {0}")]
+    GitRevisionError(String),
+
+    #[error("Invalid http client configuration: {0}")]
+    HttpClientConfig(String),
+
+    #[error("Http request failed: {0}")]
+    HttpRequestError(reqwest::Error),
+
+    #[error("Budget of {0} http requests exceeded")]
+    HttpBudgetExceeded(usize),
+
+    #[error("Cannot read archive: {0}")]
+    ArchiveError(String),
+
+    #[error("Invalid workspace config: {0}")]
+    WorkspaceConfigError(String),
+
+    #[error("Cannot apply workspace to {0}: {1}")]
+    WorkspaceApplyError(String, String),
+
+    #[error("Workspace apply failed for {0} specifications")]
+    WorkspaceApplyFailed(usize),
+
     #[error("De/serialization error: {0}")]
     SerdeJsonError(serde_json::Error),
 }
@@ -0,0 +1,299 @@
+use serde_json::Value;
+
+use crate::tools::{pointer_escape, pointer_unescape};
+
+// child of explored node with one line summary of its value
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    pub key: String,
+    pub summary: String,
+}
+
+// browsing state of specification, original and processed (dereferenced, merged) documents
+pub struct Explorer {
+    root: Value,
+    processed: Value,
+    path: Vec<String>,
+    pub selected: usize,
+
+    // positions left by following references
+    history: Vec<(Vec<String>, usize)>,
+}
+
+impl Explorer {
+    pub fn new(root: Value, processed: Value) -> Self {
+        Self {
+            root,
+            processed,
+            path: vec![],
+            selected: 0,
+            history: vec![],
+        }
+    }
+
+    pub fn pointer(&self) -> String {
+        let tokens = self
+            .path
+            .iter()
+            .map(|t| format!("/{}", pointer_escape(t)))
+            .collect::<String>();
+
+        format!("#{}", tokens)
+    }
+
+    pub fn node(&self) -> &Value {
+        self.root
+            .pointer(&self.pointer()[1..])
+            .unwrap_or(&Value::Null)
+    }
+
+    // merged and dereferenced view of current node, none when processing removed it
+    pub fn preview(&self) -> Option<&Value> {
+        self.processed.pointer(&self.pointer()[1..])
+    }
+
+    pub fn entries(&self) -> Vec<Entry> {
+        match self.node() {
+            Value::Object(map) => map
+                .iter()
+                .map(|(k, v)| Entry {
+                    key: k.clone(),
+                    summary: summary(v),
+                })
+                .collect(),
+            Value::Array(list) => list
+                .iter()
+                .enumerate()
+                .map(|(i, v)| Entry {
+                    key: i.to_string(),
+                    summary: summary(v),
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    pub fn up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn down(&mut self) {
+        if self.selected + 1 < self.entries().len() {
+            self.selected += 1;
+        }
+    }
+
+    // enters selected child when it is an object or array
+    pub fn enter(&mut self) -> bool {
+        let key = match self.entries().into_iter().nth(self.selected) {
+            Some(entry) => entry.key,
+            None => return false,
+        };
+
+        let child = match self.node() {
+            Value::Object(map) => map.get(&key),
+            Value::Array(list) => key.parse::<usize>().ok().and_then(|i| list.get(i)),
+            _ => None,
+        };
+
+        match child {
+            Some(Value::Object(_)) | Some(Value::Array(_)) => {
+                self.path.push(key);
+                self.selected = 0;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn parent(&mut self) {
+        if let Some(key) = self.path.pop() {
+            self.selected = self
+                .entries()
+                .iter()
+                .position(|e| e.key == key)
+                .unwrap_or(0);
+        }
+    }
+
+    // jumps to local reference of selected child or of current node
+    pub fn follow(&mut self) -> Result<String, String> {
+        let selected =
+            self.entries()
+                .into_iter()
+                .nth(self.selected)
+                .and_then(|e| match self.node() {
+                    Value::Object(map) => map.get(&e.key).cloned(),
+                    Value::Array(list) => e
+                        .key
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| list.get(i).cloned()),
+                    _ => None,
+                });
+
+        let reference = selected
+            .as_ref()
+            .and_then(reference)
+            .or_else(|| reference(self.node()))
+            .ok_or_else(|| "no reference to follow".to_string())?;
+
+        let pointer = match reference.strip_prefix('#') {
+            Some(pointer) if self.root.pointer(pointer).is_some() => pointer.to_string(),
+            Some(_) => return Err(format!("{} not found", reference)),
+            None => return Err(format!("{} is not a local reference", reference)),
+        };
+
+        self.history.push((self.path.clone(), self.selected));
+        self.path = pointer.split('/').skip(1).map(pointer_unescape).collect();
+        self.selected = 0;
+
+        Ok(reference)
+    }
+
+    // returns to position before last followed reference
+    pub fn back(&mut self) -> bool {
+        match self.history.pop() {
+            Some((path, selected)) => {
+                self.path = path;
+                self.selected = selected;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn reference(node: &Value) -> Option<String> {
+    node.get("$ref")
+        .and_then(|r| r.as_str())
+        .map(|r| r.to_string())
+}
+
+fn summary(value: &Value) -> String {
+    match value {
+        Value::Object(map) => match reference(value) {
+            Some(reference) => format!("-> {}", reference),
+            None => match map.get("type").and_then(|t| t.as_str()) {
+                Some(type_) => format!("{{{}}} {}", map.len(), type_),
+                None => format!("{{{}}}", map.len()),
+            },
+        },
+        Value::Array(list) => format!("[{}]", list.len()),
+        value => value.to_string(),
+    }
+}
+
+// clipboard content set with OSC 52 escape sequence, supported by most terminals
+pub fn clipboard_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut result = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                result.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_explorer_navigation() {
+        let root = json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": { "200": { "$ref": "#/components/responses/Users" } }
+                    }
+                }
+            },
+            "components": {
+                "responses": {
+                    "Users": { "description": "list", "content": {} }
+                }
+            }
+        });
+        let processed = json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": { "200": { "description": "list", "content": {} } }
+                    }
+                }
+            }
+        });
+
+        let mut explorer = Explorer::new(root, processed);
+        assert_eq!(
+            explorer.entries(),
+            vec![
+                Entry {
+                    key: "paths".to_string(),
+                    summary: "{1}".to_string()
+                },
+                Entry {
+                    key: "components".to_string(),
+                    summary: "{1}".to_string()
+                },
+            ]
+        );
+
+        assert!(explorer.enter());
+        assert!(explorer.enter());
+        assert!(explorer.enter());
+        assert!(explorer.enter());
+        assert_eq!(explorer.pointer(), "#/paths/~1users/get/responses");
+        assert_eq!(
+            explorer.entries()[0].summary,
+            "-> #/components/responses/Users"
+        );
+
+        assert!(explorer.enter());
+        assert_eq!(
+            explorer.preview(),
+            Some(&json!({ "description": "list", "content": {} }))
+        );
+
+        assert_eq!(
+            explorer.follow(),
+            Ok("#/components/responses/Users".to_string())
+        );
+        assert_eq!(explorer.pointer(), "#/components/responses/Users");
+        assert_eq!(explorer.preview(), None);
+        assert!(!explorer.enter());
+
+        assert!(explorer.back());
+        assert_eq!(explorer.pointer(), "#/paths/~1users/get/responses/200");
+
+        explorer.parent();
+        explorer.parent();
+        assert_eq!(explorer.pointer(), "#/paths/~1users/get");
+        assert_eq!(explorer.selected, 0);
+    }
+
+    #[test]
+    fn test_clipboard_sequence() {
+        assert_eq!(clipboard_sequence("#/a"), "\x1b]52;c;Iy9h\x07");
+        assert_eq!(base64(b"ab"), "YWI=");
+        assert_eq!(base64(b"a"), "YQ==");
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::Error;
+
+// semantic kind of string format, pattern and example are used to check and generate values
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Format {
+    pub kind: String,
+
+    #[serde(default)]
+    pub pattern: Option<String>,
+
+    #[serde(default)]
+    pub example: Option<String>,
+}
+
+impl Format {
+    fn new(kind: &str, pattern: Option<&str>, example: &str) -> Self {
+        Self {
+            kind: kind.to_string(),
+            pattern: pattern.map(|p| p.to_string()),
+            example: Some(example.to_string()),
+        }
+    }
+}
+
+// formats known to codegen, example generation and validation, extended by users
+#[derive(Debug, Clone)]
+pub struct Formats {
+    formats: BTreeMap<String, Format>,
+
+    // compiled once, values are checked against them many times
+    patterns: BTreeMap<String, Regex>,
+}
+
+impl Default for Formats {
+    fn default() -> Self {
+        let formats = vec![
+            (
+                "date-time",
+                Format::new(
+                    "datetime",
+                    Some(
+                        r"^\d{4}-\d{2}-\d{2}[Tt ]\d{2}:\d{2}:\d{2}(\.\d+)?([Zz]|[+-]\d{2}:\d{2})$",
+                    ),
+                    "2020-01-01T00:00:00Z",
+                ),
+            ),
+            (
+                "date",
+                Format::new("date", Some(r"^\d{4}-\d{2}-\d{2}$"), "2020-01-01"),
+            ),
+            (
+                "time",
+                Format::new(
+                    "time",
+                    Some(r"^\d{2}:\d{2}:\d{2}(\.\d+)?([Zz]|[+-]\d{2}:\d{2})?$"),
+                    "00:00:00",
+                ),
+            ),
+            (
+                "duration",
+                Format::new(
+                    "duration",
+                    Some(r"^P(\d+Y)?(\d+M)?(\d+W)?(\d+D)?(T(\d+H)?(\d+M)?(\d+(\.\d+)?S)?)?$"),
+                    "P1D",
+                ),
+            ),
+            (
+                "uuid",
+                Format::new(
+                    "uuid",
+                    Some(
+                        r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$",
+                    ),
+                    "00000000-0000-0000-0000-000000000000",
+                ),
+            ),
+            (
+                "email",
+                Format::new("email", Some(r"^[^@\s]+@[^@\s]+$"), "user@example.com"),
+            ),
+            (
+                "uri",
+                Format::new(
+                    "uri",
+                    Some(r"^[a-zA-Z][a-zA-Z0-9+.-]*:"),
+                    "https://example.com",
+                ),
+            ),
+            (
+                "url",
+                Format::new(
+                    "uri",
+                    Some(r"^[a-zA-Z][a-zA-Z0-9+.-]*:"),
+                    "https://example.com",
+                ),
+            ),
+            ("hostname", Format::new("hostname", None, "example.com")),
+            (
+                "ipv4",
+                Format::new("ipv4", Some(r"^(\d{1,3}\.){3}\d{1,3}$"), "127.0.0.1"),
+            ),
+            ("ipv6", Format::new("ipv6", None, "::1")),
+            ("byte", Format::new("base64", None, "ZXhhbXBsZQ==")),
+        ];
+
+        let mut defaults = Self {
+            formats: BTreeMap::new(),
+            patterns: BTreeMap::new(),
+        };
+
+        for (name, format) in formats {
+            defaults
+                .add(name, format)
+                .expect("built-in format pattern is valid");
+        }
+
+        defaults
+    }
+}
+
+impl Formats {
+    // user formats replace built-in ones, patterns are checked upfront
+    pub fn add(&mut self, name: &str, format: Format) -> Result<(), Error> {
+        match &format.pattern {
+            Some(pattern) => {
+                let regex = Regex::new(pattern)
+                    .map_err(|e| Error::InvalidFormat(name.to_string(), e.to_string()))?;
+                self.patterns.insert(name.to_string(), regex);
+            }
+            None => {
+                self.patterns.remove(name);
+            }
+        }
+
+        self.formats.insert(name.to_string(), format);
+        Ok(())
+    }
+
+    pub fn from_map(map: &Map<String, Value>) -> Result<Self, Error> {
+        let mut formats = Self::default();
+
+        for (name, value) in map {
+            let format = serde_json::from_value::<Format>(value.clone())
+                .map_err(|e| Error::InvalidFormat(name.clone(), e.to_string()))?;
+
+            formats.add(name, format)?;
+        }
+
+        Ok(formats)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Format> {
+        self.formats.get(name)
+    }
+
+    pub fn kind(&self, name: &str) -> Option<String> {
+        self.get(name).map(|f| f.kind.clone())
+    }
+
+    pub fn example(&self, name: &str) -> Option<String> {
+        self.get(name).and_then(|f| f.example.clone())
+    }
+
+    // none when format is unknown or has no pattern
+    pub fn matches(&self, name: &str, value: &str) -> Option<bool> {
+        self.patterns.get(name).map(|r| r.is_match(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_custom_formats() {
+        let formats = Formats::from_map(
+            json!({
+                "iban": {
+                    "kind": "iban",
+                    "pattern": "^[A-Z]{2}[0-9]{2}[A-Z0-9]{11,30}$",
+                    "example": "DE89370400440532013000"
+                },
+                "email": { "kind": "contact" }
+            })
+            .as_object()
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(formats.kind("iban"), Some("iban".to_string()));
+        assert_eq!(formats.kind("email"), Some("contact".to_string()));
+        assert_eq!(formats.kind("uuid"), Some("uuid".to_string()));
+        assert_eq!(formats.kind("unknown"), None);
+
+        assert_eq!(
+            formats.matches("iban", "DE89370400440532013000"),
+            Some(true)
+        );
+        assert_eq!(formats.matches("iban", "89370400440532013000"), Some(false));
+        assert_eq!(formats.matches("date", "2020-13"), Some(false));
+        assert_eq!(formats.matches("email", "anything"), None);
+
+        assert!(Formats::from_map(
+            json!({"x": {"kind": "x", "pattern": "("}})
+                .as_object()
+                .unwrap()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,178 @@
+use std::path::{Path, PathBuf};
+use url::Url;
+
+use crate::error::Error;
+
+// files of past revisions are addressed as `git+rev://<commit>/<absolute path>`,
+// so relative references are resolved at the same revision
+pub static SCHEME: &str = "git+rev";
+
+// url of file at revision (commit, branch, tag, ex. `HEAD~2`), file does not have to exist in working tree
+pub fn url(path: &str, revision: &str) -> Result<Url, Error> {
+    let path = absolute(Path::new(path))?;
+    let repository = repository(&path)?;
+
+    let commit = repository
+        .revparse_single(revision)
+        .and_then(|o| o.peel_to_commit())
+        .map_err(|e| Error::GitRevisionError(format!("{}: {}", revision, e.message())))?;
+
+    let mut url = Url::parse(&format!("{}://{}", SCHEME, commit.id()))
+        .map_err(|_| Error::GitRevisionError(revision.to_string()))?;
+    url.set_path(&path.to_string_lossy());
+
+    Ok(url)
+}
+
+// previous version of specification for commands comparing two of them: `--original` path
+// (or the compared file itself) taken from `--at-rev` revision when it's given
+pub fn original(
+    original: &Option<String>,
+    current: &str,
+    revision: &Option<String>,
+) -> Result<Url, Error> {
+    let path = original.clone().unwrap_or_else(|| current.to_string());
+
+    match revision {
+        Some(_) if path == "-" => Err(Error::SchemaAsReference),
+        Some(revision) => url(&path, revision),
+        None => crate::schema::path_to_url(path),
+    }
+}
+
+// content of file at revision, read from object database without checkout
+pub fn read(url: &Url) -> Result<Vec<u8>, Error> {
+    // path is decoded the same way as of file urls
+    let path = Url::parse(&format!("file://{}", url.path()))
+        .ok()
+        .and_then(|u| u.to_file_path().ok())
+        .ok_or_else(|| Error::GitRevisionError(url.to_string()))?;
+    let commit = url
+        .host_str()
+        .ok_or_else(|| Error::GitRevisionError(url.to_string()))?;
+
+    let repository = repository(&path)?;
+    let workdir = repository
+        .workdir()
+        .and_then(|w| w.canonicalize().ok())
+        .ok_or_else(|| Error::GitRevisionError(format!("{}: bare repository", url)))?;
+    let relative = path
+        .strip_prefix(&workdir)
+        .map_err(|_| Error::GitRevisionError(format!("{}: outside of repository", url)))?;
+
+    let failed = |e: git2::Error| Error::GitRevisionError(format!("{}: {}", url, e.message()));
+
+    let blob = git2::Oid::from_str(commit)
+        .and_then(|id| repository.find_commit(id))
+        .and_then(|c| c.tree())
+        .and_then(|t| t.get_path(relative))
+        .and_then(|e| e.to_object(&repository))
+        .and_then(|o| o.peel_to_blob())
+        .map_err(failed)?;
+
+    Ok(blob.content().to_vec())
+}
+
+// canonical path, file itself may be gone from working tree
+fn absolute(path: &Path) -> Result<PathBuf, Error> {
+    let path = std::env::current_dir()
+        .map(|d| d.join(path))
+        .map_err(|e| Error::GitRevisionError(e.to_string()))?;
+
+    if let Ok(path) = path.canonicalize() {
+        return Ok(path);
+    }
+
+    match (path.parent().map(|p| p.canonicalize()), path.file_name()) {
+        (Some(Ok(parent)), Some(name)) => Ok(parent.join(name)),
+        _ => Err(Error::SchemaInvalidPath {
+            path: path.display().to_string(),
+        }),
+    }
+}
+
+fn repository(path: &Path) -> Result<git2::Repository, Error> {
+    let directory = path
+        .ancestors()
+        .find(|p| p.is_dir())
+        .unwrap_or_else(|| Path::new("/"));
+
+    git2::Repository::discover(directory)
+        .map_err(|_| Error::GitRevisionError(format!("{}: not in git repository", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Schema;
+    use serde_json::json;
+
+    fn commit(repository: &git2::Repository, file: &str, content: &str) {
+        let directory = repository.workdir().unwrap();
+        std::fs::create_dir_all(directory.join(file).parent().unwrap()).unwrap();
+        std::fs::write(directory.join(file), content).unwrap();
+
+        let mut index = repository.index().unwrap();
+        index.add_path(Path::new(file)).unwrap();
+        index.write().unwrap();
+
+        let tree = repository.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("test", "test@localhost").unwrap();
+        let parent = repository.head().ok().and_then(|h| h.peel_to_commit().ok());
+
+        repository
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                file,
+                &tree,
+                &parent.iter().collect::<Vec<_>>(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_load_at_revision() {
+        let mut directory = std::env::temp_dir();
+        directory.push(format!("schema-tools-revision-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        let repository = git2::Repository::init(&directory).unwrap();
+
+        commit(
+            &repository,
+            "api/openapi.yaml",
+            "openapi: 3.0.0\ninfo:\n  version: 1.0.0\n",
+        );
+        commit(
+            &repository,
+            "api/openapi.yaml",
+            "openapi: 3.0.0\ninfo:\n  version: 1.1.0\n",
+        );
+        std::fs::write(
+            directory.join("api/openapi.yaml"),
+            "openapi: 3.0.0\ninfo:\n  version: 2.0.0\n",
+        )
+        .unwrap();
+
+        let file = directory.join("api/openapi.yaml");
+        let version = |revision: &str| {
+            let url = url(file.to_str().unwrap(), revision).unwrap();
+            assert_eq!(url.scheme(), SCHEME);
+
+            Schema::load_url(url).unwrap().get_body()["info"]["version"].clone()
+        };
+
+        assert_eq!(version("HEAD"), json!("1.1.0"));
+        assert_eq!(version("HEAD~1"), json!("1.0.0"));
+        assert!(matches!(
+            url(file.to_str().unwrap(), "v9.9.9"),
+            Err(Error::GitRevisionError(_))
+        ));
+
+        let missing = url(directory.join("api/missing.yaml").to_str().unwrap(), "HEAD").unwrap();
+        assert!(matches!(read(&missing), Err(Error::GitRevisionError(_))));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -6,8 +6,10 @@ use std::{
 use walkdir::{DirEntry, WalkDir};
 
 use digest::{Digest, Output};
+use serde_json::{Map, Value};
 
 use crate::error::Error;
+use crate::tools::SchemaWalk;
 
 pub fn calculate<D: Digest>(path: &Path) -> Result<Output<D>, Error> {
     let metadata = fs::metadata(path).map_err(Error::HashCalculationError)?;
@@ -74,3 +76,100 @@ fn is_hidden(entry: &DirEntry, path: &Path) -> bool {
         .map(|s| s.starts_with('.'))
         .unwrap_or(false)
 }
+
+// keywords whose array values are sets, order of items has no meaning
+static UNORDERED_KEYWORDS: &[&str] = &["required", "type"];
+
+// canonical form of schema: object keys sorted, set-like arrays sorted and deduplicated,
+// arrays of example and default data keep their order
+pub fn canonical(value: &Value) -> Value {
+    canonical_node(value, Some(SchemaWalk::default()))
+}
+
+// walk is none inside instance data
+fn canonical_node(value: &Value, walk: Option<SchemaWalk>) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut keys = map.keys().collect::<Vec<_>>();
+            keys.sort();
+
+            let mut result = Map::new();
+            for key in keys {
+                let inner = walk.filter(|w| !w.is_instance(key)).map(|w| w.enter(key));
+                let mut child = canonical_node(&map[key], inner);
+
+                let unordered = walk.map(|w| !w.has_names()).unwrap_or(false)
+                    && UNORDERED_KEYWORDS.contains(&key.as_str());
+
+                if let (true, Value::Array(list)) = (unordered, &mut child) {
+                    list.sort_by_key(|v| v.to_string());
+                    list.dedup();
+                }
+
+                result.insert(key.clone(), child);
+            }
+
+            Value::Object(result)
+        }
+        Value::Array(list) => Value::Array(
+            list.iter()
+                .map(|v| canonical_node(v, walk.map(|_| SchemaWalk::default())))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+// sha256 of canonical form, key reordering does not change fingerprint
+pub fn fingerprint(value: &Value) -> String {
+    format!(
+        "{:x}",
+        sha2::Sha256::digest(serde_json::to_string(&canonical(value)).unwrap().as_bytes())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_fingerprint_ignores_order() {
+        let left = json!({
+            "type": "object",
+            "required": ["a", "b"],
+            "properties": { "a": { "type": "string" }, "b": { "type": ["integer", "null"] } }
+        });
+        let right = json!({
+            "properties": { "b": { "type": ["null", "integer"] }, "a": { "type": "string" } },
+            "required": ["b", "a"],
+            "type": "object"
+        });
+
+        assert_eq!(fingerprint(&left), fingerprint(&right));
+    }
+
+    #[test]
+    fn test_fingerprint_keeps_enum_order() {
+        let left = json!({ "enum": ["a", "b"] });
+        let right = json!({ "enum": ["b", "a"] });
+
+        assert_ne!(fingerprint(&left), fingerprint(&right));
+    }
+
+    #[test]
+    fn test_fingerprint_keeps_data_order() {
+        let left = json!({
+            "properties": { "required": { "type": "object" } },
+            "default": { "required": ["a", "b"] },
+            "example": { "type": ["a", "b"] }
+        });
+        let right = json!({
+            "properties": { "required": { "type": "object" } },
+            "default": { "required": ["b", "a"] },
+            "example": { "type": ["b", "a"] }
+        });
+
+        assert_ne!(fingerprint(&left), fingerprint(&right));
+    }
+}
@@ -0,0 +1,409 @@
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::{
+    CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER,
+};
+use reqwest::{Certificate, Identity, Proxy, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, RwLock};
+use std::time::Duration;
+
+use crate::error::Error;
+
+pub static CLIENT_CERT_PASSWORD_VARIABLE: &str = "SCHEMATOOLS_CLIENT_CERT_PASSWORD";
+
+// settings of http client used for loading, dereferencing, registry and publishing
+#[derive(Debug, Clone, Default)]
+pub struct HttpConfig {
+    pub timeout: Option<Duration>,
+    pub retries: u32,
+    pub backoff: Duration,
+    pub proxy: Option<String>,
+    pub ca_bundle: Option<PathBuf>,
+    pub client_cert: Option<PathBuf>,
+    pub client_cert_password: Option<String>,
+
+    // requests in flight to one host, unlimited by default
+    pub per_host: Option<usize>,
+
+    // requests (retries included) allowed in whole run
+    pub budget: Option<usize>,
+
+    // directory of fetched documents revalidated with etag and last-modified
+    pub cache: Option<PathBuf>,
+}
+
+// state shared by all requests of process
+#[derive(Default)]
+pub struct Limiter {
+    hosts: Mutex<HashMap<String, usize>>,
+    released: Condvar,
+    sent: AtomicUsize,
+}
+
+// request slot of host, given back when dropped
+struct Slot<'a> {
+    limiter: &'a Limiter,
+    host: String,
+}
+
+impl Limiter {
+    fn acquire(&self, host: &str, limit: Option<usize>) -> Slot<'_> {
+        let mut hosts = self.hosts.lock().unwrap();
+
+        if let Some(limit) = limit {
+            while hosts.get(host).copied().unwrap_or_default() >= limit.max(1) {
+                hosts = self.released.wait(hosts).unwrap();
+            }
+        }
+        *hosts.entry(host.to_string()).or_default() += 1;
+
+        Slot {
+            limiter: self,
+            host: host.to_string(),
+        }
+    }
+
+    fn count(&self, budget: Option<usize>) -> Result<(), Error> {
+        let sent = self.sent.fetch_add(1, Ordering::SeqCst);
+
+        match budget {
+            Some(budget) if sent >= budget => Err(Error::HttpBudgetExceeded(budget)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Drop for Slot<'_> {
+    fn drop(&mut self) {
+        if let Some(count) = self.limiter.hosts.lock().unwrap().get_mut(&self.host) {
+            *count -= 1;
+        }
+        self.limiter.released.notify_all();
+    }
+}
+
+// body of successful GET with its content type, possibly taken from cache
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Fetched {
+    pub url: String,
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+
+    // raw content, stored next to metadata in cache
+    #[serde(skip)]
+    pub body: Vec<u8>,
+}
+
+impl Fetched {
+    fn path(directory: &Path, url: &str, extension: &str) -> PathBuf {
+        directory.join(format!("{:x}.{}", md5::compute(url), extension))
+    }
+
+    fn load(directory: &Path, url: &str) -> Option<Self> {
+        let content = std::fs::read_to_string(Self::path(directory, url, "json")).ok()?;
+        let body = std::fs::read(Self::path(directory, url, "body")).ok()?;
+
+        serde_json::from_str::<Self>(&content)
+            .ok()
+            .filter(|f| f.url == url)
+            .map(|f| Self { body, ..f })
+    }
+
+    fn save(&self, directory: &Path) {
+        let path = Self::path(directory, &self.url, "json");
+
+        // cache is an optimization only, document was fetched anyway
+        if let Err(e) = std::fs::create_dir_all(directory)
+            .and_then(|_| std::fs::write(Self::path(directory, &self.url, "body"), &self.body))
+            .and_then(|_| std::fs::write(&path, serde_json::to_string(self).unwrap()))
+        {
+            log::warn!("{}: {}", path.display(), e);
+        }
+    }
+}
+
+lazy_static! {
+    static ref CONFIG: RwLock<HttpConfig> = RwLock::new(HttpConfig::default());
+    static ref LIMITER: Limiter = Limiter::default();
+}
+
+// configuration given on command line is used by clients created anywhere later on
+pub fn configure(config: HttpConfig) {
+    *CONFIG.write().unwrap() = config;
+}
+
+pub fn client() -> Result<Client, Error> {
+    CONFIG.read().unwrap().client()
+}
+
+pub fn send(request: RequestBuilder) -> Result<Response, Error> {
+    let config = CONFIG.read().unwrap().clone();
+
+    config.send(&LIMITER, request)
+}
+
+pub fn get(client: &Client, url: &str) -> Result<Fetched, Error> {
+    let config = CONFIG.read().unwrap().clone();
+
+    config.get(&LIMITER, client, url)
+}
+
+impl HttpConfig {
+    pub fn client(&self) -> Result<Client, Error> {
+        let invalid = |what: &str, e: &dyn std::fmt::Display| {
+            Error::HttpClientConfig(format!("{}: {}", what, e))
+        };
+
+        let mut builder = Client::builder();
+
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(Proxy::all(proxy).map_err(|e| invalid(proxy, &e))?);
+        }
+
+        if let Some(path) = &self.ca_bundle {
+            let what = path.display().to_string();
+            let bundle = std::fs::read_to_string(path).map_err(|e| invalid(&what, &e))?;
+
+            // bundle holds many certificates, each one is added separately
+            for pem in bundle
+                .split_inclusive("-----END CERTIFICATE-----")
+                .filter(|p| p.contains("-----BEGIN CERTIFICATE-----"))
+            {
+                builder = builder.add_root_certificate(
+                    Certificate::from_pem(pem.trim().as_bytes()).map_err(|e| invalid(&what, &e))?,
+                );
+            }
+        }
+
+        if let Some(path) = &self.client_cert {
+            let what = path.display().to_string();
+            let der = std::fs::read(path).map_err(|e| invalid(&what, &e))?;
+
+            builder = builder.identity(
+                Identity::from_pkcs12_der(
+                    &der,
+                    self.client_cert_password.as_deref().unwrap_or_default(),
+                )
+                .map_err(|e| invalid(&what, &e))?,
+            );
+        }
+
+        builder.build().map_err(|e| invalid("client", &e))
+    }
+
+    // request is repeated on connection errors, timeouts, 429 and 5xx responses with exponential
+    // backoff, unless server tells how long to wait with retry-after
+    pub fn send(&self, limiter: &Limiter, request: RequestBuilder) -> Result<Response, Error> {
+        let host = request
+            .try_clone()
+            .and_then(|r| r.build().ok())
+            .and_then(|r| r.url().host_str().map(|h| h.to_string()))
+            .unwrap_or_default();
+
+        let mut attempt = 0;
+
+        loop {
+            limiter.count(self.budget)?;
+            let slot = limiter.acquire(&host, self.per_host);
+
+            // streamed bodies cannot be sent again
+            let current = match request.try_clone() {
+                Some(current) if attempt < self.retries => current,
+                _ => return request.send().map_err(Error::HttpRequestError),
+            };
+
+            let result = current.send();
+            drop(slot);
+
+            let mut delay = self.backoff * 2u32.saturating_pow(attempt);
+
+            match result {
+                Ok(response)
+                    if response.status().is_server_error()
+                        || response.status() == StatusCode::TOO_MANY_REQUESTS =>
+                {
+                    log::warn!("{}: {}, retrying", response.url(), response.status());
+
+                    if let Some(seconds) = response
+                        .headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|v| v.parse::<u64>().ok())
+                    {
+                        delay = Duration::from_secs(seconds);
+                    }
+                }
+                Err(e) if e.is_timeout() || e.is_connect() => {
+                    log::warn!("{}, retrying", e);
+                }
+                result => return result.map_err(Error::HttpRequestError),
+            }
+
+            std::thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+
+    // cached documents are sent back only when server answers 304 to conditional request
+    pub fn get(&self, limiter: &Limiter, client: &Client, url: &str) -> Result<Fetched, Error> {
+        let cached = self
+            .cache
+            .as_ref()
+            .and_then(|directory| Fetched::load(directory, url));
+
+        let mut request = client.get(url);
+        if let Some(cached) = &cached {
+            if let Some(etag) = &cached.etag {
+                request = request.header(IF_NONE_MATCH, etag.as_str());
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified.as_str());
+            }
+        }
+
+        let response = self.send(limiter, request)?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = cached {
+                log::debug!("{}: not modified, cached copy used", url);
+                return Ok(cached);
+            }
+        }
+
+        let headers = response.headers().clone();
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+
+        let fetched = Fetched {
+            url: url.to_string(),
+            status: response.status().as_u16(),
+            content_type: header(CONTENT_TYPE),
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            body: response.bytes().map_err(Error::HttpRequestError)?.to_vec(),
+        };
+
+        if let Some(directory) = &self.cache {
+            if fetched.status == 200 && (fetched.etag.is_some() || fetched.last_modified.is_some())
+            {
+                fetched.save(directory);
+            }
+        }
+
+        Ok(fetched)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    // answers with given responses one by one, returns received requests
+    fn serve(responses: Vec<&'static str>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/spec.json", listener.local_addr().unwrap());
+
+        let server = std::thread::spawn(move || {
+            let mut requests = vec![];
+
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+
+                // request has no body, it ends with empty line
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    request.push_str(&line.to_lowercase());
+                    line.clear();
+                }
+                requests.push(request);
+
+                write!(stream, "{}", response).unwrap();
+            }
+
+            requests
+        });
+
+        (url, server)
+    }
+
+    #[test]
+    fn test_retry_on_server_error() {
+        let (url, server) = serve(vec![
+            "HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+            "HTTP/1.1 429 Too Many Requests\r\nretry-after: 0\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{}",
+        ]);
+
+        let config = HttpConfig {
+            retries: 2,
+            backoff: Duration::from_millis(1),
+            ..HttpConfig::default()
+        };
+        let client = config.client().unwrap();
+
+        let response = config.send(&Limiter::default(), client.get(&url)).unwrap();
+        assert!(response.status().is_success());
+        assert_eq!(response.text().unwrap(), "{}");
+        server.join().unwrap();
+
+        let config = HttpConfig {
+            ca_bundle: Some(PathBuf::from("missing-bundle.pem")),
+            ..HttpConfig::default()
+        };
+        assert!(matches!(config.client(), Err(Error::HttpClientConfig(_))));
+    }
+
+    #[test]
+    fn test_conditional_request_and_budget() {
+        let mut directory = std::env::temp_dir();
+        directory.push(format!("schema-tools-http-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+
+        let (url, server) = serve(vec![
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\netag: \"v1\"\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{}",
+            "HTTP/1.1 304 Not Modified\r\netag: \"v1\"\r\nconnection: close\r\n\r\n",
+        ]);
+
+        let config = HttpConfig {
+            budget: Some(2),
+            cache: Some(directory.clone()),
+            ..HttpConfig::default()
+        };
+        let client = config.client().unwrap();
+        let limiter = Limiter::default();
+
+        let first = config.get(&limiter, &client, &url).unwrap();
+        let second = config.get(&limiter, &client, &url).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(second.body, b"{}");
+        assert_eq!(second.content_type.as_deref(), Some("application/json"));
+
+        let requests = server.join().unwrap();
+        assert!(!requests[0].contains("if-none-match"));
+        assert!(requests[1].contains("if-none-match: \"v1\""));
+
+        assert!(matches!(
+            config.get(&limiter, &client, &url),
+            Err(Error::HttpBudgetExceeded(2))
+        ));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
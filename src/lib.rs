@@ -0,0 +1,29 @@
+#[macro_use]
+extern crate lazy_static;
+
+pub mod analyze;
+pub mod archive;
+pub mod codegen;
+pub mod commands;
+pub mod convert;
+pub mod discovery;
+pub mod edit;
+pub mod error;
+pub mod explore;
+pub mod formats;
+pub mod git_revision;
+pub mod hash;
+pub mod http;
+pub mod process;
+pub mod publish;
+pub mod query;
+pub mod resolver;
+pub mod schema;
+pub mod scope;
+pub mod spec_registry;
+pub mod storage;
+pub mod tools;
+pub mod validate;
+pub mod workspace;
+
+pub const VERSION: &str = "0.6.0";
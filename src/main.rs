@@ -1,22 +1,5 @@
-#[macro_use]
-extern crate lazy_static;
-
 use clap::Clap;
-
-pub mod codegen;
-pub mod commands;
-pub mod discovery;
-pub mod error;
-pub mod hash;
-pub mod process;
-pub mod resolver;
-pub mod schema;
-pub mod scope;
-pub mod storage;
-pub mod tools;
-pub mod validate;
-
-const VERSION: &str = "0.6.0";
+use schematools::{commands, VERSION};
 
 #[derive(Clap)]
 #[clap(version = VERSION, author = "Kacper S. <kacper@stasik.eu>")]
@@ -24,6 +7,9 @@ const VERSION: &str = "0.6.0";
 struct Opts {
     #[clap(subcommand)]
     command: Command,
+
+    #[clap(flatten)]
+    http: commands::HttpOpts,
 }
 
 #[derive(Clap)]
@@ -49,24 +35,130 @@ enum Command {
     )]
     Codegen(commands::codegen::Opts),
 
+    #[clap(
+        version = VERSION,
+        about = "Json patch creation and application",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Patch(commands::patch::Opts),
+
+    #[clap(
+        version = VERSION,
+        about = "Conversion of schemas to other formats",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Convert(commands::convert::Opts),
+
+    #[clap(
+        version = VERSION,
+        about = "Extraction of schema parts to standalone files",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Extract(commands::extract::Opts),
+
+    #[clap(
+        version = VERSION,
+        about = "Assembles openapi components from standalone json schema files",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Assemble(commands::assemble::Opts),
+
+    #[clap(
+        version = VERSION,
+        about = "Semantic fingerprint of schema",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Hash(commands::hash::Opts),
+
+    #[clap(
+        version = VERSION,
+        about = "Reference graph of components and operations",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Graph(commands::graph::Opts),
+
+    #[clap(
+        version = VERSION,
+        about = "Analysis of openapi specification",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Analyze(commands::analyze::Opts),
+
+    #[clap(
+        version = VERSION,
+        about = "Interactive terminal explorer of specification",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Explore(commands::explore::Opts),
+
+    #[clap(
+        version = VERSION,
+        about = "Extraction of sub-documents by json pointer or jsonpath",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Get(commands::get::Opts),
+
+    #[clap(
+        version = VERSION,
+        about = "Sets value of specification by json pointer in place",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Set(commands::edit::SetOpts),
+
+    #[clap(
+        version = VERSION,
+        about = "Removes value of specification by json pointer in place",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Unset(commands::edit::UnsetOpts),
+
     #[clap(
         version = VERSION,
         about = "Chain different operations in one process",
         author = "Kacper S. <kacper@stasik.eu>"
     )]
     Chain(commands::chain::Opts),
+
+    #[clap(
+        version = VERSION,
+        about = "Validates, normalizes and uploads specification with its provenance to registry",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Publish(commands::publish::Opts),
+
+    #[clap(
+        version = VERSION,
+        about = "Status and processing of all specifications of monorepo",
+        author = "Kacper S. <kacper@stasik.eu>"
+    )]
+    Workspace(commands::workspace::Opts),
 }
 
 fn main() {
-    let opts: Opts = Opts::parse();
-    let client = reqwest::blocking::Client::new();
+    let Opts { command, http } = Opts::parse();
 
-    let result = match opts.command {
+    let result = http.create().and_then(|client| match command {
         Command::Process(opts) => commands::process::execute(opts, &client),
         Command::Codegen(opts) => commands::codegen::execute(opts, &client),
         Command::Validate(opts) => commands::validate::execute(opts, &client),
+        Command::Patch(opts) => commands::patch::execute(opts, &client),
+        Command::Convert(opts) => commands::convert::execute(opts, &client),
+        Command::Extract(opts) => commands::extract::execute(opts, &client),
+        Command::Assemble(opts) => commands::assemble::execute(opts, &client),
+        Command::Hash(opts) => commands::hash::execute(opts, &client),
+        Command::Graph(opts) => commands::graph::execute(opts, &client),
+        Command::Analyze(opts) => commands::analyze::execute(opts, &client),
+        Command::Explore(opts) => commands::explore::execute(opts, &client),
+        Command::Get(opts) => commands::get::execute(opts, &client),
+        Command::Set(opts) => commands::edit::execute_set(opts, &client),
+        Command::Unset(opts) => commands::edit::execute_unset(opts, &client),
         Command::Chain(opts) => commands::chain::execute(opts, &client),
-    };
+        Command::Publish(opts) => commands::publish::execute(opts, &client),
+        Command::Workspace(opts) => commands::workspace::execute(opts, &client),
+    });
+
+    // exit skips destructors, unpacked archives are removed before
+    schematools::archive::cleanup();
 
     std::process::exit(match result {
         Ok(_) => 0,
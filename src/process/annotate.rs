@@ -0,0 +1,165 @@
+use serde_json::{Map, Value};
+
+use crate::schema::Schema;
+use crate::tools::SchemaWalk;
+
+// extensions marking sensitive data
+pub static SENSITIVE_EXTENSIONS: &[&str] = &["x-pii", "x-secret"];
+
+pub struct Propagator;
+
+pub struct PropagatorOptions {
+    pub extensions: Vec<String>,
+}
+
+impl Propagator {
+    pub fn options() -> PropagatorOptions {
+        PropagatorOptions {
+            extensions: SENSITIVE_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+}
+
+impl PropagatorOptions {
+    pub fn with_extensions(&mut self, value: Vec<String>) -> &mut Self {
+        if !value.is_empty() {
+            self.extensions = value;
+        }
+        self
+    }
+
+    // copies annotations of referenced, composed (allOf) and item schemas onto fields using them
+    pub fn process(&self, schema: &mut Schema) {
+        let root = schema.get_body().clone();
+        propagate(
+            schema.get_body_mut(),
+            SchemaWalk::default(),
+            &root,
+            &self.extensions,
+        );
+    }
+}
+
+// annotations of schema including those inherited through $ref, allOf and items
+pub fn annotations(
+    root: &Value,
+    node: &Value,
+    extensions: &[String],
+    stack: &mut Vec<String>,
+) -> Map<String, Value> {
+    let mut result = Map::new();
+
+    let map = match node {
+        Value::Object(map) => map,
+        _ => return result,
+    };
+
+    for extension in extensions {
+        if let Some(value) = map.get(extension) {
+            result.insert(extension.clone(), value.clone());
+        }
+    }
+
+    let mut inherited = vec![];
+    if let Some(Value::String(reference)) = map.get("$ref") {
+        if !stack.contains(reference) {
+            if let Some(target) = reference.strip_prefix('#').and_then(|p| root.pointer(p)) {
+                stack.push(reference.clone());
+                inherited.push(annotations(root, target, extensions, stack));
+                stack.pop();
+            }
+        }
+    }
+    if let Some(Value::Array(parts)) = map.get("allOf") {
+        for part in parts {
+            inherited.push(annotations(root, part, extensions, stack));
+        }
+    }
+    if let Some(items) = map.get("items") {
+        inherited.push(annotations(root, items, extensions, stack));
+    }
+
+    // own annotations take precedence
+    for annotations in inherited {
+        for (key, value) in annotations {
+            result.entry(key).or_insert(value);
+        }
+    }
+
+    result
+}
+
+fn propagate(node: &mut Value, walk: SchemaWalk, root: &Value, extensions: &[String]) {
+    // maps of names (ex. properties) are not schemas, they get no annotations
+    let inherited = if walk.has_names() {
+        Map::new()
+    } else {
+        annotations(root, node, extensions, &mut vec![])
+    };
+
+    match node {
+        Value::Object(map) => {
+            for (key, value) in inherited {
+                map.entry(key).or_insert(value);
+            }
+
+            for (key, value) in map.iter_mut() {
+                if !walk.is_instance(key) {
+                    propagate(value, walk.enter(key), root, extensions);
+                }
+            }
+        }
+        Value::Array(list) => {
+            for value in list {
+                propagate(value, SchemaWalk::default(), root, extensions);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_propagate_annotations() {
+        let mut schema = Schema::from_json(json!({
+            "components": {
+                "schemas": {
+                    "Email": { "type": "string", "x-pii": "contact" },
+                    "Token": { "type": "string", "x-secret": true },
+                    "User": {
+                        "type": "object",
+                        "properties": {
+                            "email": { "$ref": "#/components/schemas/Email" },
+                            "emails": { "type": "array", "items": { "$ref": "#/components/schemas/Email" } },
+                            "token": { "allOf": [{ "$ref": "#/components/schemas/Token" }] },
+                            "login": { "$ref": "#/components/schemas/Email", "x-pii": "login" },
+                            "name": { "type": "string" },
+                            "items": { "$ref": "#/components/schemas/Token" },
+                            "default": { "$ref": "#/components/schemas/Token" }
+                        }
+                    }
+                }
+            }
+        }));
+
+        Propagator::options().process(&mut schema);
+
+        let properties = &schema.get_body()["components"]["schemas"]["User"]["properties"];
+        assert_eq!(properties["email"]["x-pii"], json!("contact"));
+        assert_eq!(properties["emails"]["x-pii"], json!("contact"));
+        assert_eq!(properties["token"]["x-secret"], json!(true));
+        assert_eq!(properties["login"]["x-pii"], json!("login"));
+        assert_eq!(properties["name"].get("x-pii"), None);
+        assert_eq!(properties["default"]["x-secret"], json!(true));
+        assert_eq!(properties["items"]["x-secret"], json!(true));
+        assert_eq!(properties.get("x-secret"), None);
+        assert_eq!(
+            schema.get_body()["components"]["schemas"]["User"].get("x-pii"),
+            None
+        );
+    }
+}
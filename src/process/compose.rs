@@ -0,0 +1,352 @@
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+use crate::error::Error;
+use crate::process::report::ProcessReport;
+use crate::schema::Schema;
+use crate::tools::pointer_escape;
+
+pub struct Composer;
+
+pub struct ComposerOptions {
+    // name (path) of fragment with its content
+    pub fragments: Vec<(String, Schema)>,
+}
+
+impl Composer {
+    pub fn options(fragments: Vec<(String, Schema)>) -> ComposerOptions {
+        ComposerOptions { fragments }
+    }
+}
+
+// where each composed node came from, conflicts name both sides
+struct Origins {
+    origins: BTreeMap<String, String>,
+}
+
+impl Origins {
+    fn place(
+        &mut self,
+        target: &mut Map<String, Value>,
+        key: &str,
+        value: &Value,
+        pointer: String,
+        fragment: &str,
+        report: &mut ProcessReport,
+    ) -> Result<(), Error> {
+        match target.get(key) {
+            None => {
+                target.insert(key.to_string(), value.clone());
+                self.origins.insert(pointer, fragment.to_string());
+                report.count("nodes composed");
+            }
+            Some(existing) if existing == value => {
+                log::debug!("{}: duplicate in {} skipped", pointer, fragment);
+                report.count("duplicates skipped");
+            }
+            Some(_) => {
+                let first = self
+                    .origins
+                    .get(&pointer)
+                    .map(|s| s.as_str())
+                    .unwrap_or("base");
+
+                return Err(Error::ComposeConflict(format!(
+                    "{} differs in {} and {}",
+                    pointer, first, fragment
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ComposerOptions {
+    // fragments are composed in order of their names, so result does not depend on order of arguments;
+    // top level keys of base (ex. info, servers) win over those of fragments
+    pub fn process(&self, schema: &mut Schema) -> Result<ProcessReport, Error> {
+        let mut report = ProcessReport::new("compose");
+        let mut origins = Origins {
+            origins: BTreeMap::new(),
+        };
+
+        let root = schema
+            .get_body_mut()
+            .as_object_mut()
+            .ok_or_else(|| Error::ComposeInvalidFragment("base is not an object".to_string()))?;
+        let base = root.keys().cloned().collect::<Vec<_>>();
+
+        // tags of base are keyed by name like tags of fragments
+        if let Some(Value::Array(tags)) = root.get("tags") {
+            let tags = tags
+                .iter()
+                .filter_map(|t| Some((t.get("name")?.as_str()?.to_string(), t.clone())))
+                .collect::<Map<_, _>>();
+            root.insert("tags".to_string(), Value::Object(tags));
+        }
+
+        let mut fragments = self.fragments.iter().collect::<Vec<_>>();
+        fragments.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (name, fragment) in fragments {
+            let body = fragment.get_body().as_object().ok_or_else(|| {
+                Error::ComposeInvalidFragment(format!("{} is not an object", name))
+            })?;
+
+            for (key, value) in body {
+                match key.as_str() {
+                    "paths" => {
+                        for (path, item) in entries(value, name, "/paths")? {
+                            let pointer = format!("/paths/{}", pointer_escape(path));
+                            let target = section(root, &["paths", path])?;
+
+                            for (key, value) in entries(item, name, &pointer)? {
+                                let pointer = format!("{}/{}", pointer, pointer_escape(key));
+                                origins.place(target, key, value, pointer, name, &mut report)?;
+                            }
+                        }
+                    }
+                    "components" => {
+                        for (kind, set) in entries(value, name, "/components")? {
+                            let pointer = format!("/components/{}", pointer_escape(kind));
+                            let target = section(root, &["components", kind])?;
+
+                            for (key, value) in entries(set, name, &pointer)? {
+                                let pointer = format!("{}/{}", pointer, pointer_escape(key));
+                                origins.place(target, key, value, pointer, name, &mut report)?;
+                            }
+                        }
+                    }
+                    "tags" => {
+                        let tags = value.as_array().ok_or_else(|| {
+                            Error::ComposeInvalidFragment(format!("{}: /tags", name))
+                        })?;
+
+                        let target = section(root, &["tags"])?;
+                        for tag in tags {
+                            let key =
+                                tag.get("name").and_then(|n| n.as_str()).ok_or_else(|| {
+                                    Error::ComposeInvalidFragment(format!(
+                                        "{}: tag without name",
+                                        name
+                                    ))
+                                })?;
+
+                            let pointer = format!("/tags/{}", pointer_escape(key));
+                            origins.place(target, key, tag, pointer, name, &mut report)?;
+                        }
+                    }
+                    _ if base.contains(key) => {
+                        log::debug!("/{}: taken from base, {} skipped", key, name);
+                    }
+                    _ => origins.place(root, key, value, format!("/{}", key), name, &mut report)?,
+                }
+            }
+        }
+
+        sort(root);
+
+        let body = schema.get_body();
+        if let Some(reference) = dangling(body, body) {
+            return Err(Error::ComposeUnresolvedReference(reference));
+        }
+
+        Ok(report)
+    }
+}
+
+fn entries<'a>(
+    value: &'a Value,
+    fragment: &str,
+    pointer: &str,
+) -> Result<&'a Map<String, Value>, Error> {
+    value
+        .as_object()
+        .ok_or_else(|| Error::ComposeInvalidFragment(format!("{}: {}", fragment, pointer)))
+}
+
+// object under path of keys, created when missing
+fn section<'a>(
+    root: &'a mut Map<String, Value>,
+    keys: &[&str],
+) -> Result<&'a mut Map<String, Value>, Error> {
+    let mut current = root;
+
+    for key in keys {
+        current = current
+            .entry(key.to_string())
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .ok_or_else(|| Error::ComposeInvalidFragment(format!("base: /{}", keys.join("/"))))?;
+    }
+
+    Ok(current)
+}
+
+// paths, components and tags are sorted by name, tags are collected in section keyed by name
+fn sort(root: &mut Map<String, Value>) {
+    let sorted = |map: &mut Map<String, Value>| {
+        let mut entries = std::mem::take(map).into_iter().collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        map.extend(entries);
+    };
+
+    if let Some(Value::Object(paths)) = root.get_mut("paths") {
+        sorted(paths);
+    }
+
+    if let Some(Value::Object(components)) = root.get_mut("components") {
+        sorted(components);
+
+        for set in components.values_mut() {
+            if let Value::Object(set) = set {
+                sorted(set);
+            }
+        }
+    }
+
+    if let Some(Value::Object(tags)) = root.get_mut("tags") {
+        sorted(tags);
+
+        let list = tags.values().cloned().collect::<Vec<_>>();
+        root.insert("tags".to_string(), Value::Array(list));
+    }
+}
+
+fn dangling(node: &Value, root: &Value) -> Option<String> {
+    match node {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get("$ref") {
+                if let Some(pointer) = reference.strip_prefix('#') {
+                    if root.pointer(pointer).is_none() {
+                        return Some(reference.clone());
+                    }
+                }
+            }
+
+            map.values().find_map(|v| dangling(v, root))
+        }
+        Value::Array(list) => list.iter().find_map(|v| dangling(v, root)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn users() -> (String, Schema) {
+        (
+            "users.yaml".to_string(),
+            Schema::from_json(json!({
+                "openapi": "3.0.0",
+                "paths": {
+                    "/users": {
+                        "get": {
+                            "tags": ["users"],
+                            "responses": {
+                                "200": {
+                                    "description": "ok",
+                                    "content": { "application/json": { "schema": { "$ref": "#/components/schemas/User" } } }
+                                }
+                            }
+                        }
+                    }
+                },
+                "components": {
+                    "schemas": {
+                        "User": { "type": "object", "properties": { "id": { "$ref": "#/components/schemas/Id" } } },
+                        "Id": { "type": "string" }
+                    }
+                },
+                "tags": [{ "name": "users" }]
+            })),
+        )
+    }
+
+    fn admin() -> (String, Schema) {
+        (
+            "admin.yaml".to_string(),
+            Schema::from_json(json!({
+                "openapi": "3.0.0",
+                "paths": {
+                    "/users": {
+                        "delete": { "responses": { "204": { "description": "deleted" } } }
+                    },
+                    "/admin": {
+                        "get": { "tags": ["admin"], "responses": { "204": { "description": "ok" } } }
+                    }
+                },
+                "components": { "schemas": { "Id": { "type": "string" } } },
+                "tags": [{ "name": "users" }, { "name": "admin" }]
+            })),
+        )
+    }
+
+    #[test]
+    fn test_compose() {
+        let compose = |fragments| {
+            let mut schema = Schema::from_json(json!({
+                "openapi": "3.1.0",
+                "info": { "title": "api", "version": "1.0.0" }
+            }));
+            Composer::options(fragments).process(&mut schema).unwrap();
+            schema.get_body().clone()
+        };
+
+        let result = compose(vec![users(), admin()]);
+        assert_eq!(result, compose(vec![admin(), users()]));
+        assert_eq!(
+            serde_json::to_string(&result).unwrap(),
+            serde_json::to_string(&json!({
+                "openapi": "3.1.0",
+                "info": { "title": "api", "version": "1.0.0" },
+                "paths": {
+                    "/admin": {
+                        "get": { "tags": ["admin"], "responses": { "204": { "description": "ok" } } }
+                    },
+                    "/users": {
+                        "delete": { "responses": { "204": { "description": "deleted" } } },
+                        "get": users().1.get_body()["paths"]["/users"]["get"]
+                    }
+                },
+                "components": {
+                    "schemas": {
+                        "Id": { "type": "string" },
+                        "User": users().1.get_body()["components"]["schemas"]["User"]
+                    }
+                },
+                "tags": [{ "name": "admin" }, { "name": "users" }]
+            }))
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_compose_conflict() {
+        let conflicting = (
+            "ids.yaml".to_string(),
+            Schema::from_json(json!({
+                "components": { "schemas": { "Id": { "type": "integer" } } }
+            })),
+        );
+
+        let result = Composer::options(vec![users(), conflicting])
+            .process(&mut Schema::from_json(json!({})));
+        assert!(matches!(
+            result,
+            Err(Error::ComposeConflict(c)) if c == "/components/schemas/Id differs in ids.yaml and users.yaml"
+        ));
+
+        let dangling = (
+            "orders.yaml".to_string(),
+            Schema::from_json(json!({
+                "paths": { "/orders": { "$ref": "#/components/pathItems/Orders" } }
+            })),
+        );
+
+        let result = Composer::options(vec![dangling]).process(&mut Schema::from_json(json!({})));
+        assert!(matches!(result, Err(Error::ComposeUnresolvedReference(_))));
+    }
+}
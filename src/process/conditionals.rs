@@ -0,0 +1,308 @@
+use serde_json::{json, Map, Value};
+
+use crate::schema::Schema;
+use crate::tools;
+
+pub struct Simplifier;
+
+pub struct SimplifierOptions {
+    pub lower: bool,
+    pub extension_prefix: Option<String>,
+}
+
+impl Simplifier {
+    pub fn options() -> SimplifierOptions {
+        SimplifierOptions {
+            lower: true,
+            extension_prefix: None,
+        }
+    }
+}
+
+impl SimplifierOptions {
+    pub fn with_lower(&mut self, value: bool) -> &mut Self {
+        self.lower = value;
+        self
+    }
+
+    pub fn with_extension_prefix(&mut self, value: Option<String>) -> &mut Self {
+        self.extension_prefix = value;
+        self
+    }
+
+    pub fn process(&self, schema: &mut Schema) {
+        tools::walk_schemas_mut(schema.get_body_mut(), &mut |map| simplify(map, self));
+    }
+}
+
+// keywords of condition which do not affect its result
+static ANNOTATIONS: &[&str] = &["title", "description", "$comment"];
+
+fn simplify(map: &mut Map<String, Value>, options: &SimplifierOptions) {
+    let condition = match map.remove("if") {
+        Some(c) => c,
+        None => return,
+    };
+    let then = map.remove("then");
+    let otherwise = map.remove("else");
+
+    match decide(map, &condition) {
+        Some(true) => extend(map, then),
+        Some(false) => extend(map, otherwise),
+        None if options.lower => {
+            // exactly one of variants is valid, so they are exclusive like oneOf requires
+            let variants = json!([
+                variant(condition.clone(), then, "then", options),
+                variant(json!({ "not": condition }), otherwise, "else", options),
+            ]);
+
+            if map.contains_key("oneOf") {
+                extend(map, Some(json!({ "oneOf": variants })));
+            } else {
+                map.insert("oneOf".to_string(), variants);
+            }
+        }
+        None => {
+            log::info!("condition cannot be evaluated statically, leaving it");
+
+            map.insert("if".to_string(), condition);
+            if let Some(then) = then {
+                map.insert("then".to_string(), then);
+            }
+            if let Some(otherwise) = otherwise {
+                map.insert("else".to_string(), otherwise);
+            }
+        }
+    }
+}
+
+fn variant(
+    condition: Value,
+    branch: Option<Value>,
+    name: &str,
+    options: &SimplifierOptions,
+) -> Value {
+    let mut variant = match branch {
+        Some(branch) if branch != Value::Bool(true) => json!({ "allOf": [condition, branch] }),
+        _ => json!({ "allOf": [condition] }),
+    };
+
+    variant.as_object_mut().unwrap().insert(
+        tools::extension(&options.extension_prefix, "condition"),
+        json!(name),
+    );
+
+    variant
+}
+
+fn extend(map: &mut Map<String, Value>, branch: Option<Value>) {
+    let branch = match branch {
+        Some(Value::Bool(true)) | None => return,
+        Some(b) => b,
+    };
+
+    match map.get_mut("allOf") {
+        Some(Value::Array(all_of)) => all_of.push(branch),
+        _ => {
+            map.insert("allOf".to_string(), json!([branch]));
+        }
+    }
+}
+
+// some(true) when condition holds for every instance of schema, some(false) when it never does
+fn decide(schema: &Map<String, Value>, condition: &Value) -> Option<bool> {
+    let condition = match condition {
+        Value::Bool(b) => return Some(*b),
+        Value::Object(c) => c,
+        _ => return None,
+    };
+
+    let required = schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let mut results = vec![];
+    for (keyword, value) in condition {
+        match keyword.as_str() {
+            "properties" => {
+                for (name, expected) in value.as_object()? {
+                    results.push(decide_property(
+                        schema,
+                        name,
+                        expected,
+                        required.contains(&name.as_str()),
+                    ));
+                }
+            }
+            "required" => {
+                let names = value.as_array()?;
+                if names
+                    .iter()
+                    .all(|n| n.as_str().map(|n| required.contains(&n)).unwrap_or(false))
+                {
+                    results.push(Some(true));
+                } else {
+                    results.push(None);
+                }
+            }
+            k if ANNOTATIONS.contains(&k) => {}
+            _ => results.push(None),
+        }
+    }
+
+    if results.contains(&Some(false)) {
+        Some(false)
+    } else if results.iter().all(|r| *r == Some(true)) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+// missing property passes properties keyword, so mismatch decides only required ones
+fn decide_property(
+    schema: &Map<String, Value>,
+    name: &str,
+    expected: &Value,
+    required: bool,
+) -> Option<bool> {
+    let actual = values(schema.get("properties")?.get(name)?)?;
+    let expected = values(expected)?;
+
+    if actual.iter().all(|v| expected.contains(v)) {
+        Some(true)
+    } else if required && !actual.iter().any(|v| expected.contains(v)) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn values(schema: &Value) -> Option<Vec<Value>> {
+    if let Some(value) = schema.get("const") {
+        return Some(vec![value.clone()]);
+    }
+
+    schema.get("enum").and_then(|e| e.as_array()).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplify_conditionals() {
+        let mut schema = Schema::from_json(json!({
+            "type": "object",
+            "properties": {
+                "card": {
+                    "type": "object",
+                    "required": ["kind"],
+                    "properties": { "kind": { "const": "card" } },
+                    "if": { "properties": { "kind": { "enum": ["card", "wallet"] } } },
+                    "then": { "required": ["number"] },
+                    "else": { "required": ["iban"] }
+                },
+                "transfer": {
+                    "type": "object",
+                    "required": ["kind"],
+                    "properties": { "kind": { "const": "transfer" } },
+                    "if": { "properties": { "kind": { "const": "card" } } },
+                    "then": { "required": ["number"] },
+                    "else": { "required": ["iban"] }
+                },
+                "payment": {
+                    "type": "object",
+                    "properties": { "kind": { "enum": ["card", "transfer"] } },
+                    "if": { "properties": { "kind": { "const": "card" } } },
+                    "then": { "required": ["number"] }
+                }
+            }
+        }));
+
+        Simplifier::options().process(&mut schema);
+
+        let body = schema.get_body();
+        assert_eq!(
+            body.pointer("/properties/card/allOf"),
+            Some(&json!([{ "required": ["number"] }]))
+        );
+        assert_eq!(
+            body.pointer("/properties/transfer/allOf"),
+            Some(&json!([{ "required": ["iban"] }]))
+        );
+        assert_eq!(body.pointer("/properties/transfer/if"), None);
+
+        // kind may be missing or any of values
+        assert_eq!(
+            body.pointer("/properties/payment/oneOf"),
+            Some(&json!([
+                {
+                    "allOf": [
+                        { "properties": { "kind": { "const": "card" } } },
+                        { "required": ["number"] }
+                    ],
+                    "x-schematools-condition": "then"
+                },
+                {
+                    "allOf": [{ "not": { "properties": { "kind": { "const": "card" } } } }],
+                    "x-schematools-condition": "else"
+                }
+            ]))
+        );
+
+        // properties named like keywords are left intact
+        let mut schema = Schema::from_json(json!({
+            "type": "object",
+            "properties": {
+                "if": { "type": "string" },
+                "then": { "type": "string" }
+            }
+        }));
+
+        Simplifier::options().process(&mut schema);
+
+        assert_eq!(
+            schema.get_body()["properties"],
+            json!({
+                "if": { "type": "string" },
+                "then": { "type": "string" }
+            })
+        );
+
+        let mut schema = Schema::from_json(json!({
+            "if": { "type": "string" },
+            "then": { "minLength": 1 }
+        }));
+
+        Simplifier::options().with_lower(false).process(&mut schema);
+
+        assert_eq!(
+            schema.get_body(),
+            &json!({
+                "if": { "type": "string" },
+                "then": { "minLength": 1 }
+            })
+        );
+
+        let mut schema = Schema::from_json(json!({
+            "if": { "type": "string" },
+            "then": { "minLength": 1 }
+        }));
+
+        Simplifier::options()
+            .with_extension_prefix(Some("x-acme".to_string()))
+            .process(&mut schema);
+
+        assert_eq!(
+            schema.get_body()["oneOf"][0]["x-acme-condition"],
+            json!("then")
+        );
+        assert_eq!(
+            schema.get_body()["oneOf"][1]["x-acme-condition"],
+            json!("else")
+        );
+    }
+}
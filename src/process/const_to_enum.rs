@@ -0,0 +1,91 @@
+use serde_json::{Map, Value};
+
+use crate::schema::Schema;
+use crate::tools;
+
+pub struct Converter;
+
+pub struct ConverterOptions {
+    pub infer_type: bool,
+}
+
+impl Converter {
+    pub fn options() -> ConverterOptions {
+        ConverterOptions { infer_type: true }
+    }
+}
+
+impl ConverterOptions {
+    pub fn with_infer_type(&mut self, value: bool) -> &mut Self {
+        self.infer_type = value;
+        self
+    }
+
+    pub fn process(&self, schema: &mut Schema) {
+        tools::walk_schemas_mut(schema.get_body_mut(), &mut |map| convert(map, self));
+    }
+}
+
+fn convert(map: &mut Map<String, Value>, options: &ConverterOptions) {
+    let value = match map.get("const") {
+        Some(v @ Value::String(_)) | Some(v @ Value::Number(_)) | Some(v @ Value::Bool(_)) => {
+            v.clone()
+        }
+        _ => return,
+    };
+
+    if map.contains_key("enum") {
+        log::warn!("const and enum defined together, skipping");
+        return;
+    }
+
+    if options.infer_type && !map.contains_key("type") {
+        let type_ = match &value {
+            Value::String(_) => "string",
+            Value::Number(n) if n.is_f64() => "number",
+            Value::Number(_) => "integer",
+            _ => "boolean",
+        };
+
+        map.insert("type".to_string(), Value::String(type_.to_string()));
+    }
+
+    map.remove("const");
+    map.insert("enum".to_string(), Value::Array(vec![value]));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_const_converted_to_enum() {
+        let mut schema = Schema::from_json(json!({
+            "type": "object",
+            "properties": {
+                "kind": { "const": "user" },
+                "version": { "type": "number", "const": 2 },
+                "const": { "type": "string" },
+                "default": { "const": true }
+            },
+            "example": { "kind": { "const": "user" } }
+        }));
+
+        Converter::options().process(&mut schema);
+
+        assert_eq!(
+            schema.get_body(),
+            &json!({
+                "type": "object",
+                "properties": {
+                    "kind": { "type": "string", "enum": ["user"] },
+                    "version": { "type": "number", "enum": [2] },
+                    "const": { "type": "string" },
+                    "default": { "type": "boolean", "enum": [true] }
+                },
+                "example": { "kind": { "const": "user" } }
+            })
+        );
+    }
+}
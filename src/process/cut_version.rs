@@ -0,0 +1,377 @@
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::tools::{pointer_escape, walk_schemas_mut, SchemaWalk, HTTP_METHODS};
+
+pub static SINCE_EXTENSION: &str = "x-since";
+pub static REMOVED_IN_EXTENSION: &str = "x-removed-in";
+
+pub struct VersionCutter;
+
+pub struct VersionCutterOptions {
+    pub version: String,
+}
+
+impl VersionCutter {
+    pub fn options(version: String) -> VersionCutterOptions {
+        VersionCutterOptions { version }
+    }
+}
+
+impl VersionCutterOptions {
+    // removes every element introduced after given version or removed in it (or before),
+    // gating annotations are dropped from the rest, returns pointers of removed elements
+    pub fn process(&self, schema: &mut Schema) -> Result<Vec<String>, Error> {
+        let target = version(&Value::String(self.version.clone()))
+            .ok_or_else(|| Error::CutVersionInvalidVersion(self.version.clone()))?;
+
+        let mut removed = vec![];
+        cut(
+            schema.get_body_mut(),
+            SchemaWalk::default(),
+            &target,
+            "",
+            &mut removed,
+        )?;
+
+        // path items left without any operation
+        if let Some(Value::Object(paths)) = schema.get_body_mut().get_mut("paths") {
+            paths.retain(|path, item| {
+                let prefix = format!("/paths/{}/", pointer_escape(path));
+
+                HTTP_METHODS.iter().any(|m| item.get(*m).is_some())
+                    || !removed.iter().any(|r| r.starts_with(&prefix))
+            });
+        }
+
+        // concrete specification cannot point to elements it no longer has
+        let mut dangling = None;
+        walk_schemas_mut(schema.get_body_mut(), &mut |map| {
+            if let Some(Value::String(reference)) = map.get("$ref") {
+                let target = reference.strip_prefix('#').unwrap_or_default();
+
+                if dangling.is_none()
+                    && removed
+                        .iter()
+                        .any(|r| target == r || target.starts_with(&format!("{}/", r)))
+                {
+                    dangling = Some(reference.clone());
+                }
+            }
+        });
+
+        match dangling {
+            Some(reference) => Err(Error::CutVersionDanglingReference(reference)),
+            None => Ok(removed),
+        }
+    }
+}
+
+// dotted numbers (ex. `2`, `2.1`), trailing zeros do not matter
+fn version(value: &Value) -> Option<Vec<u64>> {
+    let text = match value {
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.trim_start_matches('v').to_string(),
+        _ => return None,
+    };
+
+    let mut parts = text
+        .split('.')
+        .map(|p| p.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    while parts.len() > 1 && parts.last() == Some(&0) {
+        parts.pop();
+    }
+
+    Some(parts)
+}
+
+fn gated_out(node: &Value, target: &[u64]) -> Result<bool, Error> {
+    let bound = |extension: &str| match node.get(extension) {
+        Some(value) => version(value)
+            .map(Some)
+            .ok_or_else(|| Error::CutVersionInvalidVersion(format!("{}: {}", extension, value))),
+        None => Ok(None),
+    };
+
+    let since = bound(SINCE_EXTENSION)?;
+    let removed_in = bound(REMOVED_IN_EXTENSION)?;
+
+    Ok(since.map(|s| s.as_slice() > target).unwrap_or(false)
+        || removed_in.map(|r| r.as_slice() <= target).unwrap_or(false))
+}
+
+// returns names of removed keys
+fn cut(
+    node: &mut Value,
+    walk: SchemaWalk,
+    target: &[u64],
+    pointer: &str,
+    removed: &mut Vec<String>,
+) -> Result<Vec<String>, Error> {
+    let mut gone = vec![];
+
+    match node {
+        Value::Object(map) => {
+            for (key, value) in map.iter() {
+                // maps of names are never gated, their keys are not annotations
+                let element = !walk.is_instance(key) && !walk.enter(key).has_names();
+
+                if value.is_object() && element && gated_out(value, target)? {
+                    gone.push(key.clone());
+                }
+            }
+
+            for key in &gone {
+                map.remove(key);
+                removed.push(format!("{}/{}", pointer, pointer_escape(key)));
+            }
+
+            // annotations hold versions, properties named like them hold schemas
+            if !walk.has_names() {
+                for extension in &[SINCE_EXTENSION, REMOVED_IN_EXTENSION] {
+                    map.remove(*extension);
+                }
+            }
+
+            let mut properties = vec![];
+            for (key, value) in map.iter_mut() {
+                if walk.is_instance(key) {
+                    continue;
+                }
+
+                let child = walk.enter(key);
+                let names = cut(
+                    value,
+                    child,
+                    target,
+                    &format!("{}/{}", pointer, pointer_escape(key)),
+                    removed,
+                )?;
+
+                if key == "properties" && child.has_names() {
+                    properties.extend(names);
+                }
+            }
+
+            if walk.has_names() {
+                return Ok(gone);
+            }
+
+            if let Some(Value::Array(required)) = map.get_mut("required") {
+                required.retain(|r| !properties.iter().any(|p| r == p));
+            }
+        }
+        Value::Array(list) => {
+            // pointers are reported with indexes of original document
+            let mut kept = vec![];
+            for (i, mut value) in std::mem::take(list).into_iter().enumerate() {
+                let pointer = format!("{}/{}", pointer, i);
+
+                if gated_out(&value, target)? {
+                    removed.push(pointer);
+                } else {
+                    cut(&mut value, SchemaWalk::default(), target, &pointer, removed)?;
+                    kept.push(value);
+                }
+            }
+            *list = kept;
+        }
+        _ => {}
+    }
+
+    Ok(gone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec() -> Schema {
+        Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "paths": {
+                "/users": {
+                    "get": {
+                        "parameters": [
+                            { "name": "trace", "in": "header", "x-removed-in": 2 },
+                            { "name": "page", "in": "query" },
+                            {
+                                "name": "cursor",
+                                "in": "query",
+                                "x-since": 2,
+                                "schema": {
+                                    "type": "object",
+                                    "properties": { "token": { "type": "string", "x-since": 3 } }
+                                }
+                            }
+                        ],
+                        "responses": { "200": { "description": "ok" } }
+                    }
+                },
+                "/legacy": {
+                    "x-removed-in": "2.0",
+                    "get": { "responses": { "200": { "description": "ok" } } }
+                },
+                "/teams": {
+                    "get": { "x-since": "3", "responses": { "200": { "description": "ok" } } }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "required": ["id", "login", "email"],
+                        "properties": {
+                            "id": { "type": "integer" },
+                            "login": { "type": "string", "x-removed-in": 2 },
+                            "email": { "type": "string", "x-since": "1.1" }
+                        }
+                    }
+                }
+            }
+        }))
+    }
+
+    #[test]
+    fn test_cut_version() {
+        let mut schema = spec();
+        let removed = VersionCutter::options("2".to_string())
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(
+            removed,
+            vec![
+                "/paths/~1legacy",
+                "/paths/~1users/get/parameters/0",
+                "/paths/~1users/get/parameters/2/schema/properties/token",
+                "/paths/~1teams/get",
+                "/components/schemas/User/properties/login",
+            ]
+        );
+        assert_eq!(
+            schema.get_body(),
+            &json!({
+                "openapi": "3.0.0",
+                "paths": {
+                    "/users": {
+                        "get": {
+                            "parameters": [
+                                { "name": "page", "in": "query" },
+                                {
+                                    "name": "cursor",
+                                    "in": "query",
+                                    "schema": { "type": "object", "properties": {} }
+                                }
+                            ],
+                            "responses": { "200": { "description": "ok" } }
+                        }
+                    }
+                },
+                "components": {
+                    "schemas": {
+                        "User": {
+                            "type": "object",
+                            "required": ["id", "email"],
+                            "properties": {
+                                "id": { "type": "integer" },
+                                "email": { "type": "string" }
+                            }
+                        }
+                    }
+                }
+            })
+        );
+
+        let mut schema = spec();
+        VersionCutter::options("1".to_string())
+            .process(&mut schema)
+            .unwrap();
+
+        let body = schema.get_body();
+        assert!(body.pointer("/paths/~1legacy/get").is_some());
+        assert_eq!(
+            body.pointer("/paths/~1users/get/parameters")
+                .and_then(|p| p.as_array())
+                .map(|p| p.len()),
+            Some(2)
+        );
+        assert!(body
+            .pointer("/components/schemas/User/properties/email")
+            .is_none());
+
+        assert!(matches!(
+            VersionCutter::options("two".to_string()).process(&mut spec()),
+            Err(Error::CutVersionInvalidVersion(_))
+        ));
+    }
+
+    #[test]
+    fn test_cut_referenced_component() {
+        let mut schema = Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/Legacy/properties/id" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Legacy": {
+                        "x-removed-in": 2,
+                        "type": "object",
+                        "properties": { "id": { "type": "integer" } }
+                    }
+                }
+            }
+        }));
+
+        assert!(matches!(
+            VersionCutter::options("2".to_string()).process(&mut schema),
+            Err(Error::CutVersionDanglingReference(r)) if r == "#/components/schemas/Legacy/properties/id"
+        ));
+    }
+
+    #[test]
+    fn test_cut_property_named_like_keyword() {
+        let mut schema = Schema::from_json(json!({
+            "type": "object",
+            "required": ["default", "x-since"],
+            "properties": {
+                "default": { "type": "string", "x-removed-in": 2 },
+                "x-since": { "type": "string", "x-since": 1 }
+            }
+        }));
+
+        let removed = VersionCutter::options("2".to_string())
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(removed, vec!["/properties/default"]);
+        assert_eq!(
+            schema.get_body(),
+            &json!({
+                "type": "object",
+                "required": ["x-since"],
+                "properties": {
+                    "x-since": { "type": "string" }
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,336 @@
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+use crate::error::Error;
+use crate::hash;
+use crate::process::report::ProcessReport;
+use crate::schema::Schema;
+use crate::scope::SchemaScope;
+use crate::tools::{pointer_escape, SchemaWalk};
+
+pub struct Deduplicator;
+
+pub struct DeduplicatorOptions {
+    pub min_occurrences: usize,
+}
+
+impl Deduplicator {
+    pub fn options() -> DeduplicatorOptions {
+        DeduplicatorOptions { min_occurrences: 2 }
+    }
+}
+
+struct Occurrence {
+    pointer: String,
+    name: Option<String>,
+    size: usize,
+}
+
+impl DeduplicatorOptions {
+    pub fn with_min_occurrences(&mut self, value: usize) -> &mut Self {
+        self.min_occurrences = value;
+        self
+    }
+
+    // largest duplicates are extracted first, so nested ones are replaced only once
+    pub fn process(&self, schema: &mut Schema) -> Result<ProcessReport, Error> {
+        let mut report = ProcessReport::new("deduplicate");
+
+        loop {
+            let root = schema.get_body_mut();
+
+            let components = root
+                .pointer("/components/schemas")
+                .and_then(|s| s.as_object())
+                .map(|s| {
+                    s.iter()
+                        .map(|(name, s)| (hash::fingerprint(s), name.clone()))
+                        .collect::<BTreeMap<_, _>>()
+                })
+                .unwrap_or_default();
+
+            let mut groups: BTreeMap<String, Vec<Occurrence>> = BTreeMap::new();
+            collect(
+                root,
+                String::new(),
+                None,
+                SchemaWalk::default(),
+                &mut groups,
+            );
+
+            let found = groups
+                .into_iter()
+                .filter(|(fingerprint, occurrences)| {
+                    components.contains_key(fingerprint)
+                        || occurrences.len() >= self.min_occurrences
+                })
+                .max_by_key(|(_, occurrences)| occurrences[0].size);
+
+            let (fingerprint, occurrences) = match found {
+                Some(found) => found,
+                None => break,
+            };
+
+            let name = match components.get(&fingerprint) {
+                Some(name) => name.clone(),
+                None => {
+                    let first = &occurrences[0];
+                    let node = root.pointer(&first.pointer).cloned().unwrap_or_default();
+
+                    let name = unique_name(
+                        root.pointer("/components/schemas"),
+                        &component_name(&node, first.name.as_deref()),
+                    );
+
+                    let schemas = root
+                        .as_object_mut()
+                        .ok_or_else(|| {
+                            Error::DeduplicateInvalidTarget("root is not an object".to_string())
+                        })?
+                        .entry("components")
+                        .or_insert_with(|| json!({}))
+                        .as_object_mut()
+                        .ok_or_else(|| {
+                            Error::DeduplicateInvalidTarget(
+                                "components are not an object".to_string(),
+                            )
+                        })?
+                        .entry("schemas")
+                        .or_insert_with(|| json!({}))
+                        .as_object_mut()
+                        .ok_or_else(|| {
+                            Error::DeduplicateInvalidTarget(
+                                "components/schemas are not an object".to_string(),
+                            )
+                        })?;
+
+                    log::info!("components/schemas/{}: extracted", name);
+                    schemas.insert(name.clone(), node);
+                    report.count("schemas extracted");
+
+                    name
+                }
+            };
+
+            let reference = format!("#/components/schemas/{}", pointer_escape(&name));
+
+            for occurrence in occurrences {
+                log::debug!("{}: replaced by {}", occurrence.pointer, reference);
+
+                if let Some(node) = root.pointer_mut(&occurrence.pointer) {
+                    *node = json!({ "$ref": reference });
+                    report.count("occurrences replaced");
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+// inline schemas worth extracting, trivial ones (ex. `{ "type": "string" }`) are skipped
+fn is_candidate(node: &Value) -> bool {
+    match node {
+        Value::Object(map) => {
+            !map.contains_key("$ref")
+                && ["properties", "enum", "allOf", "oneOf", "anyOf"]
+                    .iter()
+                    .any(|k| map.contains_key(*k))
+        }
+        _ => false,
+    }
+}
+
+fn collect(
+    node: &Value,
+    pointer: String,
+    name: Option<&str>,
+    walk: SchemaWalk,
+    groups: &mut BTreeMap<String, Vec<Occurrence>>,
+) {
+    match node {
+        Value::Object(map) => {
+            for (key, value) in map {
+                if walk.is_instance(key) || (!walk.has_names() && key.starts_with("x-")) {
+                    continue;
+                }
+
+                let child = format!("{}/{}", pointer, pointer_escape(key));
+
+                // keys of maps of names (ex. properties) name schemas they hold,
+                // component schemas are definitions already, only their content is compared
+                let name = if walk.has_names() {
+                    Some(key.as_str())
+                } else {
+                    name
+                };
+                let inline = pointer != "/components/schemas";
+
+                collect_schema(value, child, name, walk.enter(key), groups, inline);
+            }
+        }
+        Value::Array(list) => {
+            for (i, value) in list.iter().enumerate() {
+                let child = format!("{}/{}", pointer, i);
+                collect_schema(value, child, name, SchemaWalk::default(), groups, true);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_schema(
+    node: &Value,
+    pointer: String,
+    name: Option<&str>,
+    walk: SchemaWalk,
+    groups: &mut BTreeMap<String, Vec<Occurrence>>,
+    inline: bool,
+) {
+    if inline && !walk.has_names() && is_candidate(node) {
+        groups
+            .entry(hash::fingerprint(node))
+            .or_default()
+            .push(Occurrence {
+                pointer: pointer.clone(),
+                name: name.map(|n| n.to_string()),
+                size: node.to_string().len(),
+            });
+    }
+
+    collect(node, pointer, name, walk, groups);
+}
+
+// title of schema or name of property it was found under
+fn component_name(node: &Value, name: Option<&str>) -> String {
+    let namer = SchemaScope::default().namer();
+
+    node.get("title")
+        .and_then(|t| t.as_str())
+        .or(name)
+        .map(|n| namer.convert(n))
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| "Schema".to_string())
+}
+
+fn unique_name(schemas: Option<&Value>, name: &str) -> String {
+    let taken = |n: &str| schemas.and_then(|s| s.get(n)).is_some();
+
+    if !taken(name) {
+        return name.to_string();
+    }
+
+    (2..)
+        .map(|i| format!("{}{}", name, i))
+        .find(|n| !taken(n))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deduplicate() {
+        let address = json!({
+            "type": "object",
+            "properties": {
+                "street": { "type": "string" },
+                "country": { "type": "string", "enum": ["PL", "DE"] }
+            }
+        });
+        let country = json!({ "type": "string", "enum": ["PL", "DE"] });
+
+        let mut schema = Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "type": "object",
+                                            "properties": {
+                                                "address": address,
+                                                "example": { "type": "string" }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Country": country,
+                    "Order": {
+                        "type": "object",
+                        "properties": { "shipping": address, "billing": address }
+                    }
+                }
+            }
+        }));
+
+        let report = Deduplicator::options().process(&mut schema).unwrap();
+
+        assert_eq!(
+            schema.get_body()["components"]["schemas"],
+            json!({
+                "Country": country,
+                "Order": {
+                    "type": "object",
+                    "properties": {
+                        "shipping": { "$ref": "#/components/schemas/Address" },
+                        "billing": { "$ref": "#/components/schemas/Address" }
+                    }
+                },
+                "Address": {
+                    "type": "object",
+                    "properties": {
+                        "street": { "type": "string" },
+                        "country": { "$ref": "#/components/schemas/Country" }
+                    }
+                }
+            })
+        );
+        assert_eq!(
+            schema.get_body().pointer(
+                "/paths/~1users/get/responses/200/content/application~1json/schema/properties/address"
+            ),
+            Some(&json!({ "$ref": "#/components/schemas/Address" }))
+        );
+        assert_eq!(report.counters["schemas extracted"], 1);
+        assert_eq!(report.counters["occurrences replaced"], 4);
+
+        // nothing left to extract
+        let report = Deduplicator::options().process(&mut schema).unwrap();
+        assert!(report.counters.is_empty());
+    }
+
+    #[test]
+    fn test_deduplicate_skips_instances() {
+        let value = json!({ "items": { "type": "object", "properties": { "id": 1 } } });
+
+        let mut schema = Schema::from_json(json!({
+            "components": {
+                "schemas": {
+                    "Filter": { "type": "object", "default": value },
+                    "Sort": { "type": "object", "example": value }
+                }
+            }
+        }));
+
+        let report = Deduplicator::options().process(&mut schema).unwrap();
+
+        assert!(report.counters.is_empty());
+        assert_eq!(
+            schema.get_body()["components"]["schemas"]["Filter"]["default"],
+            value
+        );
+    }
+}
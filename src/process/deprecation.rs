@@ -0,0 +1,540 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::tools::{pointer_escape, pointer_unescape, SchemaWalk, HTTP_METHODS};
+
+pub static SUNSET_EXTENSION: &str = "x-sunset";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Deprecated {
+    pub pointer: String,
+    pub sunset: Option<String>,
+    pub expired: bool,
+}
+
+pub struct Marker;
+
+pub struct MarkerOptions {
+    pub targets: Vec<String>,
+    pub sunset: Option<String>,
+}
+
+impl Marker {
+    pub fn options() -> MarkerOptions {
+        MarkerOptions {
+            targets: vec![],
+            sunset: None,
+        }
+    }
+}
+
+impl MarkerOptions {
+    // json pointer or operation in form of `GET /path`
+    pub fn with_target(&mut self, value: String) -> &mut Self {
+        self.targets.push(value);
+        self
+    }
+
+    pub fn with_sunset(&mut self, value: Option<String>) -> &mut Self {
+        self.sunset = value;
+        self
+    }
+
+    pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
+        if let Some(sunset) = &self.sunset {
+            check_date(sunset)?;
+        }
+
+        for target in &self.targets {
+            let node = schema
+                .get_body_mut()
+                .pointer_mut(&pointer(target))
+                .and_then(|n| n.as_object_mut())
+                .ok_or_else(|| Error::DeprecationInvalidTarget(target.clone()))?;
+
+            node.insert("deprecated".to_string(), json!(true));
+            if let Some(sunset) = &self.sunset {
+                node.insert(SUNSET_EXTENSION.to_string(), json!(sunset));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Stripper;
+
+pub struct StripperOptions {
+    pub before: String,
+}
+
+impl Stripper {
+    pub fn options(before: String) -> StripperOptions {
+        StripperOptions { before }
+    }
+}
+
+impl StripperOptions {
+    // removes operations, properties, parameters and components with sunset before given date,
+    // returns pointers of removed elements
+    pub fn process(&self, schema: &mut Schema) -> Result<Vec<String>, Error> {
+        check_date(&self.before)?;
+
+        let original = schema.get_body().clone();
+        let mut removed = vec![];
+        strip(
+            schema.get_body_mut(),
+            false,
+            SchemaWalk::default(),
+            &self.before,
+            "",
+            &mut removed,
+        );
+
+        // path items left without any operation
+        if let Some(Value::Object(paths)) = schema.get_body_mut().get_mut("paths") {
+            paths.retain(|path, item| {
+                let prefix = format!("/paths/{}/", pointer_escape(path));
+
+                HTTP_METHODS.iter().any(|m| item.get(*m).is_some())
+                    || !removed.iter().any(|r| r.starts_with(&prefix))
+            });
+        }
+
+        // expired components still referenced by remaining elements are kept (stripped inside),
+        // kept component may keep other ones
+        while let Some(component) = removed
+            .iter()
+            .find(|p| is_component(p) && is_referenced(schema.get_body(), p))
+            .cloned()
+        {
+            log::warn!("{}: expired component is still referenced, kept", component);
+
+            removed.retain(|r| *r != component && !r.starts_with(&format!("{}/", component)));
+
+            let mut value = original.pointer(&component).cloned().unwrap_or(Value::Null);
+            strip(
+                &mut value,
+                false,
+                SchemaWalk::default(),
+                &self.before,
+                &component,
+                &mut removed,
+            );
+
+            let (group, name) = component.rsplit_once('/').unwrap();
+            if let Some(Value::Object(map)) = schema.get_body_mut().pointer_mut(group) {
+                map.insert(pointer_unescape(name), value);
+            }
+        }
+
+        Ok(removed)
+    }
+}
+
+// /components/{group}/{name}
+fn is_component(pointer: &str) -> bool {
+    pointer.starts_with("/components/") && pointer.matches('/').count() == 3
+}
+
+fn is_referenced(root: &Value, pointer: &str) -> bool {
+    let mut references = vec![];
+    collect_references(root, SchemaWalk::default(), &mut references);
+
+    let reference = format!("#{}", pointer);
+    references
+        .iter()
+        .any(|r| *r == reference || r.starts_with(&format!("{}/", reference)))
+}
+
+fn collect_references<'a>(node: &'a Value, walk: SchemaWalk, references: &mut Vec<&'a str>) {
+    match node {
+        Value::Object(map) => {
+            if let (false, Some(Value::String(reference))) = (walk.has_names(), map.get("$ref")) {
+                references.push(reference);
+            }
+
+            for (key, value) in map {
+                if !walk.is_instance(key) {
+                    collect_references(value, walk.enter(key), references);
+                }
+            }
+        }
+        Value::Array(list) => {
+            for value in list {
+                collect_references(value, SchemaWalk::default(), references);
+            }
+        }
+        _ => {}
+    }
+}
+
+// every deprecated element, expired when its sunset is before given date
+pub fn list(root: &Value, at: &str) -> Vec<Deprecated> {
+    let mut result = vec![];
+    collect(root, at, "", SchemaWalk::default(), &mut result);
+    result
+}
+
+fn collect(node: &Value, at: &str, pointer: &str, walk: SchemaWalk, result: &mut Vec<Deprecated>) {
+    match node {
+        Value::Object(map) => {
+            if !walk.has_names() && map.get("deprecated") == Some(&Value::Bool(true)) {
+                let sunset = map
+                    .get(SUNSET_EXTENSION)
+                    .and_then(|s| s.as_str())
+                    .map(|s| s.to_string());
+
+                result.push(Deprecated {
+                    pointer: pointer.to_string(),
+                    expired: sunset.as_deref().map(|s| s < at).unwrap_or(false),
+                    sunset,
+                });
+            }
+
+            for (key, value) in map {
+                if !walk.is_instance(key) {
+                    let pointer = format!("{}/{}", pointer, pointer_escape(key));
+                    collect(value, at, &pointer, walk.enter(key), result);
+                }
+            }
+        }
+        Value::Array(list) => {
+            for (i, value) in list.iter().enumerate() {
+                let pointer = format!("{}/{}", pointer, i);
+                collect(value, at, &pointer, SchemaWalk::default(), result);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn expired(node: &Value, before: &str) -> bool {
+    node.get("deprecated") == Some(&Value::Bool(true))
+        && node
+            .get(SUNSET_EXTENSION)
+            .and_then(|s| s.as_str())
+            .map(|s| s < before)
+            .unwrap_or(false)
+}
+
+// returns names of removed children, container children are removable elements
+fn strip(
+    node: &mut Value,
+    container: bool,
+    walk: SchemaWalk,
+    before: &str,
+    pointer: &str,
+    removed: &mut Vec<String>,
+) -> Vec<String> {
+    let mut gone = vec![];
+
+    match node {
+        Value::Object(map) => {
+            if container {
+                gone = map
+                    .iter()
+                    .filter(|(_, v)| expired(v, before))
+                    .map(|(k, _)| k.clone())
+                    .collect();
+
+                for key in &gone {
+                    map.remove(key);
+                    removed.push(format!("{}/{}", pointer, pointer_escape(key)));
+                }
+            }
+
+            let mut properties = vec![];
+            for (key, value) in map.iter_mut() {
+                if walk.is_instance(key) {
+                    continue;
+                }
+
+                let child = walk.enter(key);
+                let is_properties = key == "properties" && child.has_names();
+                let container = is_properties || pointer == "/paths" || pointer == "/components";
+                let names = strip(
+                    value,
+                    container,
+                    child,
+                    before,
+                    &format!("{}/{}", pointer, pointer_escape(key)),
+                    removed,
+                );
+
+                if is_properties {
+                    properties.extend(names);
+                }
+            }
+
+            if walk.has_names() {
+                return gone;
+            }
+
+            if let Some(Value::Array(required)) = map.get_mut("required") {
+                required.retain(|r| !properties.iter().any(|p| r == p));
+            }
+        }
+        Value::Array(list) => {
+            if pointer.ends_with("/parameters") {
+                let mut index = 0;
+                list.retain(|p| {
+                    let keep = !expired(p, before);
+                    if !keep {
+                        removed.push(format!("{}/{}", pointer, index));
+                    }
+                    index += 1;
+                    keep
+                });
+            }
+
+            for (i, value) in list.iter_mut().enumerate() {
+                let pointer = format!("{}/{}", pointer, i);
+                strip(
+                    value,
+                    false,
+                    SchemaWalk::default(),
+                    before,
+                    &pointer,
+                    removed,
+                );
+            }
+        }
+        _ => {}
+    }
+
+    gone
+}
+
+fn pointer(target: &str) -> String {
+    match target.split_once(' ') {
+        Some((method, path)) if !target.starts_with('/') => {
+            format!(
+                "/paths/{}/{}",
+                pointer_escape(path.trim()),
+                method.to_lowercase()
+            )
+        }
+        _ => target.to_string(),
+    }
+}
+
+// dates are compared as YYYY-MM-DD strings
+pub fn check_date(date: &str) -> Result<(), Error> {
+    let valid = date.len() == 10
+        && date.chars().enumerate().all(|(i, c)| match i {
+            4 | 7 => c == '-',
+            _ => c.is_ascii_digit(),
+        });
+
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::DeprecationInvalidDate(date.to_string()))
+    }
+}
+
+// current utc date, see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+pub fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86400)
+        .unwrap_or(0) as i64;
+
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> Schema {
+        Schema::from_json(json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "parameters": [
+                            { "name": "page", "in": "query" },
+                            { "name": "offset", "in": "query", "deprecated": true, "x-sunset": "2020-01-01" }
+                        ]
+                    },
+                    "delete": { "deprecated": true, "x-sunset": "2020-01-01" }
+                },
+                "/legacy": {
+                    "get": { "deprecated": true, "x-sunset": "2020-01-01" }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "required": ["name", "login"],
+                        "properties": {
+                            "name": { "type": "string" },
+                            "login": { "type": "string", "deprecated": true, "x-sunset": "2020-01-01" },
+                            "email": { "type": "string", "deprecated": true, "x-sunset": "2030-01-01" }
+                        }
+                    }
+                }
+            }
+        }))
+    }
+
+    #[test]
+    fn test_list_expired() {
+        let result = list(spec().get_body(), "2025-01-01");
+
+        assert_eq!(result.len(), 5);
+        assert_eq!(result.iter().filter(|d| d.expired).count(), 4);
+        assert_eq!(result[0].pointer, "/paths/~1users/get/parameters/1");
+    }
+
+    #[test]
+    fn test_strip_expired() {
+        let mut schema = spec();
+
+        let removed = Stripper::options("2025-01-01".to_string())
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(removed.len(), 4);
+        assert_eq!(
+            schema.get_body()["paths"],
+            json!({
+                "/users": {
+                    "get": {
+                        "parameters": [{ "name": "page", "in": "query" }]
+                    }
+                }
+            })
+        );
+        assert_eq!(
+            schema.get_body()["components"]["schemas"]["User"]["required"],
+            json!(["name"])
+        );
+        assert_eq!(
+            schema.get_body()["components"]["schemas"]["User"]["properties"]["email"]["x-sunset"],
+            json!("2030-01-01")
+        );
+    }
+
+    #[test]
+    fn test_strip_keeps_referenced_components() {
+        let mut schema = Schema::from_json(json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "200": { "$ref": "#/components/responses/Users" }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "responses": {
+                    "Users": {
+                        "deprecated": true,
+                        "x-sunset": "2020-01-01",
+                        "content": {
+                            "application/json": {
+                                "schema": { "$ref": "#/components/schemas/User" }
+                            }
+                        }
+                    }
+                },
+                "schemas": {
+                    "User": {
+                        "deprecated": true,
+                        "x-sunset": "2020-01-01",
+                        "properties": {
+                            "login": { "type": "string", "deprecated": true, "x-sunset": "2020-01-01" }
+                        }
+                    },
+                    "Old": { "deprecated": true, "x-sunset": "2020-01-01" }
+                }
+            }
+        }));
+
+        let removed = Stripper::options("2025-01-01".to_string())
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(
+            removed,
+            vec![
+                "/components/schemas/Old",
+                "/components/schemas/User/properties/login"
+            ]
+        );
+        assert_eq!(
+            schema.get_body()["components"]["schemas"]["User"]["properties"],
+            json!({})
+        );
+        assert_eq!(
+            schema.get_body()["components"]["responses"]["Users"].is_object(),
+            true
+        );
+    }
+
+    #[test]
+    fn test_strip_property_named_like_keyword() {
+        let mut schema = Schema::from_json(json!({
+            "type": "object",
+            "required": ["default", "name"],
+            "properties": {
+                "default": { "type": "string", "deprecated": true, "x-sunset": "2020-01-01" },
+                "name": { "type": "string", "default": { "deprecated": true } }
+            }
+        }));
+
+        assert_eq!(
+            list(schema.get_body(), "2025-01-01")
+                .iter()
+                .map(|d| d.pointer.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/properties/default"]
+        );
+
+        let removed = Stripper::options("2025-01-01".to_string())
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(removed, vec!["/properties/default"]);
+        assert_eq!(schema.get_body()["required"], json!(["name"]));
+    }
+
+    #[test]
+    fn test_mark_operation() {
+        let mut schema = spec();
+
+        Marker::options()
+            .with_target("POST /users".to_string())
+            .process(&mut schema)
+            .unwrap_err();
+
+        Marker::options()
+            .with_target("GET /users".to_string())
+            .with_sunset(Some("2025-06-01".to_string()))
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(
+            schema.get_body()["paths"]["/users"]["get"]["x-sunset"],
+            json!("2025-06-01")
+        );
+    }
+}
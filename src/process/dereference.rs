@@ -1,14 +1,20 @@
 use std::collections::HashMap;
 
 use crate::error::Error;
+use crate::process::limits::{Budget, Limits};
+use crate::process::report::ProcessReport;
+use crate::process::source_map::{Origin, SourceMap};
 use crate::resolver::SchemaResolver;
 use crate::schema::Schema;
 use crate::scope::SchemaScope;
-use crate::storage::{ref_to_url, SchemaStorage};
+use crate::storage::{url_to_ref, SchemaStorage, DYNAMIC_REFS};
+use crate::tools::pointer_escape;
 
 use reqwest::Url;
 use serde_json::Value;
 
+pub static ORIGIN_EXTENSION: &str = "x-origin-ref";
+
 pub struct Dereferencer;
 
 pub struct DereferencerContext {
@@ -16,15 +22,26 @@ pub struct DereferencerContext {
     pub scope: SchemaScope,
     pub resolved: HashMap<String, String>,
     pub depth: i64,
+
+    // documents entered through references, outermost first
+    pub dynamic: Vec<Url>,
+
+    pub budget: Budget,
+    pub report: ProcessReport,
+    pub source_map: SourceMap,
 }
 
 impl DereferencerContext {
-    pub fn new(base: &Url) -> Self {
+    pub fn new(base: &Url, budget: Budget) -> Self {
         Self {
             base: base.clone(),
             scope: SchemaScope::default(),
             resolved: HashMap::new(),
             depth: 0,
+            dynamic: vec![base.clone()],
+            budget,
+            report: ProcessReport::new("dereference"),
+            source_map: SourceMap::new(base.to_string()),
         }
     }
 }
@@ -34,6 +51,8 @@ pub struct DereferencerOptions {
     pub skip_root_internal_references: bool,
     pub create_internal_references: bool,
     pub skip_references: Vec<String>,
+    pub annotate_origin: bool,
+    pub limits: Limits,
 }
 
 impl DereferencerOptions {
@@ -52,14 +71,44 @@ impl DereferencerOptions {
         self
     }
 
-    pub fn process(&self, schema: &mut Schema, storage: &SchemaStorage) {
+    // inlined subtrees keep reference they replaced in `x-origin-ref`
+    pub fn with_annotate_origin(&mut self, value: bool) -> &mut Self {
+        self.annotate_origin = value;
+        self
+    }
+
+    pub fn with_limits(&mut self, value: Limits) -> &mut Self {
+        self.limits = value;
+        self
+    }
+
+    pub fn process(
+        &self,
+        schema: &mut Schema,
+        storage: &SchemaStorage,
+    ) -> Result<ProcessReport, Error> {
+        self.process_with_source_map(schema, storage)
+            .map(|(report, _)| report)
+    }
+
+    // source map relates pointers of inlined parts to files and pointers they come from
+    pub fn process_with_source_map(
+        &self,
+        schema: &mut Schema,
+        storage: &SchemaStorage,
+    ) -> Result<(ProcessReport, SourceMap), Error> {
+        self.limits.check_size(schema.get_body())?;
+
         let original = schema.clone(); // todo: clone?
-        let mut dctx = DereferencerContext::new(schema.get_url());
+        let mut dctx =
+            DereferencerContext::new(schema.get_url(), self.limits.budget(schema.get_body()));
 
         let root = schema.get_body_mut();
         let resolver = SchemaResolver::new(&original, storage);
 
-        process_node(root, self, &mut dctx, &resolver);
+        process_node(root, self, &mut dctx, &resolver)?;
+
+        Ok((dctx.report, dctx.source_map))
     }
 }
 
@@ -69,6 +118,8 @@ impl Dereferencer {
             skip_root_internal_references: false,
             create_internal_references: true,
             skip_references: vec![],
+            annotate_origin: false,
+            limits: Limits::default(),
         }
     }
 }
@@ -79,16 +130,27 @@ fn process_ref(
     options: &DereferencerOptions,
     ctx: &mut DereferencerContext,
     resolver: &SchemaResolver,
-) {
-    assert!(ctx.depth < 50, "Infinite reference occured!");
+) -> Result<(), Error> {
+    options
+        .limits
+        .check_depth(ctx.depth as usize, &ctx.scope.to_string())?;
 
-    match ref_to_url(&ctx.base, &reference) {
+    match resolver.locate(&reference) {
         Some(mut url) => {
+            // storage keeps references of resolved documents absolute, annotation is written
+            // relative to document containing the reference
+            let written = match Url::parse(&reference) {
+                Ok(_) => url_to_ref(ctx.dynamic.last().unwrap_or(&ctx.base), &url),
+                Err(_) => reference.clone(),
+            };
+
             let reference = url.to_string();
+            let fragment = url.fragment().unwrap_or_default().to_string();
             url.set_fragment(None);
 
             if options.skip_root_internal_references && ctx.depth == 1 && ctx.base == url {
-                return;
+                ctx.report.count("references skipped");
+                return Ok(());
             }
 
             if options
@@ -96,7 +158,8 @@ fn process_ref(
                 .iter()
                 .any(|hostname| url.to_string().contains(hostname))
             {
-                return;
+                ctx.report.count("references skipped");
+                return Ok(());
             }
 
             // resolve
@@ -113,22 +176,53 @@ fn process_ref(
                 Some(mut s) => {
                     log::debug!("{}.$ref", ctx.scope);
 
+                    // expansion of schema with dynamic references depends on dynamic scope
+                    let reference = if has_dynamic_refs(&s) {
+                        let mut scope: Vec<&Url> = vec![];
+                        for u in ctx.dynamic.iter().chain(std::iter::once(&url)) {
+                            if !scope.contains(&u) {
+                                scope.push(u);
+                            }
+                        }
+
+                        format!("{} {:?}", reference, scope)
+                    } else {
+                        reference
+                    };
+
                     // skip internal reference if already resolved
                     if options.create_internal_references {
                         if let Some(internal_path) = ctx.resolved.get(&reference) {
                             log::debug!("{}: referencing to -> #{}", ctx.scope, internal_path);
 
                             *root = serde_json::json!({ "$ref": format!("#{}", internal_path) });
+                            ctx.report.count("internal references created");
 
-                            return;
+                            return Ok(());
                         } else {
                             ctx.resolved.insert(reference, ctx.scope.to_string());
                         }
                     }
 
-                    process_node(&mut s, options, ctx, resolver);
+                    ctx.budget.expand(&s, &ctx.scope.to_string())?;
+
+                    ctx.source_map.add(
+                        pointer(&ctx.scope),
+                        Origin {
+                            file: url.to_string(),
+                            pointer: fragment,
+                        },
+                    );
+
+                    ctx.dynamic.push(url);
+                    process_node(&mut s, options, ctx, resolver)?;
+                    ctx.dynamic.pop();
 
                     if let Some(result) = s.as_object_mut() {
+                        if options.annotate_origin {
+                            result.insert(ORIGIN_EXTENSION.to_string(), Value::String(written));
+                        }
+
                         for (key, value) in root.as_object().unwrap() {
                             if key == "$ref" {
                                 continue;
@@ -138,13 +232,22 @@ fn process_ref(
                         }
                     }
 
-                    *root = s
+                    *root = s;
+                    ctx.report.count("references inlined");
                 }
-                None => log::warn!("{}.$ref has to be a string", ctx.scope),
+                None => ctx.report.warn(
+                    "unresolved-reference",
+                    format!("{}.$ref has to be a string", ctx.scope),
+                ),
             }
         }
-        None => log::warn!("Cannot parse reference: {}", ctx.scope),
+        None => ctx.report.warn(
+            "invalid-reference",
+            format!("Cannot parse reference: {}", ctx.scope),
+        ),
     }
+
+    Ok(())
 }
 
 pub fn parse_url(reference: String) -> Result<(Option<String>, Option<String>), Error> {
@@ -171,17 +274,35 @@ fn process_node(
     options: &DereferencerOptions,
     ctx: &mut DereferencerContext,
     resolver: &SchemaResolver,
-) {
+) -> Result<(), Error> {
     match root {
         Value::Object(ref mut map) => {
             if let Some(Value::String(reference)) = map.get_mut("$ref") {
                 ctx.depth += 1;
-                process_ref(reference.clone(), root, options, ctx, resolver);
+                process_ref(reference.clone(), root, options, ctx, resolver)?;
                 ctx.depth -= 1;
+            } else if let Some((keyword, reference)) = dynamic_ref(map) {
+                match resolver.resolve_dynamic(keyword, &reference, &ctx.dynamic) {
+                    Some(resolved) => {
+                        log::debug!("{}.{} -> {}", ctx.scope, keyword, resolved);
+
+                        map.remove(keyword);
+                        map.insert("$ref".to_string(), Value::String(resolved.clone()));
+                        ctx.report.count("dynamic references resolved");
+
+                        ctx.depth += 1;
+                        process_ref(resolved, root, options, ctx, resolver)?;
+                        ctx.depth -= 1;
+                    }
+                    None => ctx.report.warn(
+                        "unresolved-dynamic-reference",
+                        format!("{}.{}: cannot resolve {}", ctx.scope, keyword, reference),
+                    ),
+                }
             } else {
                 for (property, value) in map.into_iter() {
                     ctx.scope.any(property);
-                    process_node(value, options, ctx, resolver);
+                    process_node(value, options, ctx, resolver)?;
                     ctx.scope.pop();
                 }
             }
@@ -189,12 +310,39 @@ fn process_node(
         Value::Array(a) => {
             for (index, x) in a.iter_mut().enumerate() {
                 ctx.scope.index(index);
-                process_node(x, options, ctx, resolver);
+                process_node(x, options, ctx, resolver)?;
                 ctx.scope.pop();
             }
         }
         _ => {}
     }
+
+    Ok(())
+}
+
+fn pointer(scope: &SchemaScope) -> String {
+    scope
+        .segments()
+        .iter()
+        .map(|s| format!("/{}", pointer_escape(s)))
+        .collect()
+}
+
+fn dynamic_ref(map: &serde_json::Map<String, Value>) -> Option<(&'static str, String)> {
+    DYNAMIC_REFS
+        .iter()
+        .find_map(|keyword| match map.get(*keyword) {
+            Some(Value::String(reference)) => Some((*keyword, reference.clone())),
+            _ => None,
+        })
+}
+
+fn has_dynamic_refs(node: &Value) -> bool {
+    match node {
+        Value::Object(map) => dynamic_ref(map).is_some() || map.values().any(has_dynamic_refs),
+        Value::Array(list) => list.iter().any(has_dynamic_refs),
+        _ => false,
+    }
 }
 
 #[cfg(test)]
@@ -209,17 +357,197 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Infinite reference occured!")]
     fn test_infinite_ref() {
         let mut spec = spec_from_file("resources/test/json-schemas/07-with-infinite-ref.json");
 
         let client = reqwest::blocking::Client::new();
         let ss = SchemaStorage::new(&spec, &client);
 
-        Dereferencer::options()
+        let result = Dereferencer::options()
             .with_create_internal_references(false)
             .with_skip_root_internal_references(false)
             .process(&mut spec, &ss);
+
+        match result {
+            Err(Error::ProcessLimitExceeded(message)) => {
+                assert!(message.contains("infinite reference"))
+            }
+            _ => panic!("Infinite reference occured!"),
+        }
+    }
+
+    #[test]
+    fn test_expansion_limit() {
+        let refs =
+            |to: &str| json!({ "allOf": vec![json!({ "$ref": format!("#/$defs/{}", to) }); 10] });
+        let document = json!({
+            "$defs": {
+                "l0": { "type": "string" },
+                "l1": refs("l0"),
+                "l2": refs("l1"),
+                "l3": refs("l2"),
+                "l4": refs("l3"),
+            },
+            "properties": { "laughs": { "$ref": "#/$defs/l4" } }
+        });
+        let mut spec = Schema::from_json(document.clone());
+
+        let client = reqwest::blocking::Client::new();
+        let ss = SchemaStorage::new(&spec, &client);
+
+        let result = Dereferencer::options()
+            .with_create_internal_references(false)
+            .with_limits(Limits {
+                max_expansion: Some(100),
+                ..Limits::default()
+            })
+            .process(&mut spec, &ss);
+
+        assert!(matches!(result, Err(Error::ProcessLimitExceeded(_))));
+
+        // shared references are not expanded again
+        let mut spec = Schema::from_json(document);
+        assert!(Dereferencer::options().process(&mut spec, &ss).is_ok());
+    }
+
+    #[test]
+    fn test_dynamic_references() {
+        let mut spec = spec_from_file("resources/test/json-schemas/26-dynamic-references.json");
+
+        let client = reqwest::blocking::Client::new();
+        let ss = SchemaStorage::new(&spec, &client);
+
+        Dereferencer::options().process(&mut spec, &ss).unwrap();
+
+        let body = spec.get_body();
+
+        // the same generic list is expanded with item of each envelope
+        assert_eq!(
+            body.pointer("/properties/users/properties/items/items/properties/name"),
+            Some(&json!({ "type": "string" }))
+        );
+        assert_eq!(
+            body.pointer("/properties/groups/properties/items/items/properties/id"),
+            Some(&json!({ "type": "integer" }))
+        );
+
+        // recursion ends with internal reference
+        assert_eq!(
+            body.pointer("/properties/tree/properties/children/items"),
+            Some(&json!({ "$ref": "#/properties/tree" }))
+        );
+    }
+
+    #[test]
+    fn test_ids_and_anchors() {
+        let mut spec = spec_from_file("resources/test/json-schemas/27-ids-and-anchors.json");
+
+        let client = reqwest::blocking::Client::new();
+        let ss = SchemaStorage::new(&spec, &client);
+
+        Dereferencer::options()
+            .with_create_internal_references(false)
+            .process(&mut spec, &ss)
+            .unwrap();
+
+        let body = spec.get_body();
+        let status = json!({ "$anchor": "status", "type": "string", "enum": ["new", "paid"] });
+        let country = json!({ "$anchor": "country", "type": "string", "minLength": 2 });
+
+        // anchor of root resource
+        assert_eq!(body.pointer("/properties/status"), Some(&status));
+
+        // embedded resource, resolved against $id of document
+        assert_eq!(
+            body.pointer("/properties/shipping/$id"),
+            Some(&json!("address.json"))
+        );
+        assert_eq!(body.pointer("/properties/country"), Some(&country));
+        assert_eq!(
+            body.pointer("/properties/street"),
+            Some(&json!({ "type": "string" }))
+        );
+
+        // anchor resolved against base of embedded resource
+        assert_eq!(
+            body.pointer("/properties/shipping/properties/country"),
+            Some(&country)
+        );
+
+        // other file references resources of entry document by $id
+        assert_eq!(
+            body.pointer("/properties/customer/properties/billing/$id"),
+            Some(&json!("address.json"))
+        );
+        assert_eq!(
+            body.pointer("/properties/customer/properties/lastOrder"),
+            Some(&status)
+        );
+    }
+
+    #[test]
+    fn test_source_map() {
+        let mut spec = spec_from_file("resources/test/json-schemas/27-ids-and-anchors.json");
+
+        let client = reqwest::blocking::Client::new();
+        let ss = SchemaStorage::new(&spec, &client);
+
+        let (_, map) = Dereferencer::options()
+            .with_create_internal_references(false)
+            .process_with_source_map(&mut spec, &ss)
+            .unwrap();
+
+        let origin = map.lookup("/properties/customer/properties/name/type");
+        assert!(origin.file.ends_with("28-customer.json"));
+        assert_eq!(origin.pointer, "/properties/name/type");
+
+        // embedded resource points to its place in entry document
+        let origin = map.lookup("/properties/street/type");
+        assert!(origin.file.ends_with("27-ids-and-anchors.json"));
+        assert_eq!(origin.pointer, "/$defs/address/properties/street/type");
+    }
+
+    #[test]
+    fn test_annotate_origin() {
+        let mut spec = Schema::from_json(json!({
+            "$defs": {
+                "name": { "type": "string" },
+                "user": {
+                    "type": "object",
+                    "properties": { "name": { "$ref": "#/$defs/name" } }
+                }
+            },
+            "properties": {
+                "owner": { "$ref": "#/$defs/user", "description": "owner" },
+                "tags": { "type": "array" }
+            }
+        }));
+
+        let client = reqwest::blocking::Client::new();
+        let ss = SchemaStorage::new(&spec, &client);
+
+        Dereferencer::options()
+            .with_create_internal_references(false)
+            .with_annotate_origin(true)
+            .process(&mut spec, &ss)
+            .unwrap();
+
+        // references are annotated relative to document containing them
+        assert_eq!(
+            spec.get_body().pointer("/properties/owner"),
+            Some(&json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "x-origin-ref": "#/$defs/name" }
+                },
+                "description": "owner",
+                "x-origin-ref": "#/$defs/user"
+            }))
+        );
+        assert_eq!(
+            spec.get_body().pointer("/properties/tags"),
+            Some(&json!({ "type": "array" }))
+        );
     }
 
     #[test]
@@ -229,7 +557,7 @@ mod tests {
         let client = reqwest::blocking::Client::new();
         let ss = SchemaStorage::new(&spec, &client);
 
-        Dereferencer::options().process(&mut spec, &ss);
+        Dereferencer::options().process(&mut spec, &ss).unwrap();
 
         let expected = json!({
             "$id": "https://example.com/arrays.schema.json",
@@ -260,7 +588,7 @@ mod tests {
 
         let client = reqwest::blocking::Client::new();
         let ss = SchemaStorage::new(&spec, &client);
-        Dereferencer::options().process(&mut spec, &ss);
+        Dereferencer::options().process(&mut spec, &ss).unwrap();
 
         let expected = json!({
           "$id": "https://example.com/arrays.schema.json",
@@ -311,10 +639,22 @@ mod tests {
         let client = reqwest::blocking::Client::new();
         let ss = SchemaStorage::new(&spec, &client);
 
-        Dereferencer::options()
+        let report = Dereferencer::options()
             .with_create_internal_references(true)
             .with_skip_root_internal_references(true)
-            .process(&mut spec, &ss);
+            .process(&mut spec, &ss)
+            .unwrap();
+
+        assert_eq!(
+            report.counters,
+            vec![
+                ("internal references created".to_string(), 9),
+                ("references inlined".to_string(), 6),
+                ("references skipped".to_string(), 1)
+            ]
+            .into_iter()
+            .collect()
+        );
 
         let expected = json!({
           "$id": "https://example.com/arrays.schema.json",
@@ -414,7 +754,7 @@ mod tests {
         let client = reqwest::blocking::Client::new();
         let ss = SchemaStorage::new(&spec, &client);
 
-        Dereferencer::options().process(&mut spec, &ss);
+        Dereferencer::options().process(&mut spec, &ss).unwrap();
 
         let expected = json!({
           "$id": "https://example.com/arrays.schema.json",
@@ -445,7 +785,7 @@ mod tests {
         let client = reqwest::blocking::Client::new();
         let ss = SchemaStorage::new(&spec, &client);
 
-        Dereferencer::options().process(&mut spec, &ss);
+        Dereferencer::options().process(&mut spec, &ss).unwrap();
 
         let expected = json!({
           "$id": "https://example.com/arrays.schema.json",
@@ -500,7 +840,8 @@ mod tests {
 
         Dereferencer::options()
             .with_skip_references(vec!["json.schemastore.org".to_string()])
-            .process(&mut spec, &ss);
+            .process(&mut spec, &ss)
+            .unwrap();
 
         let expected = json!({
           "$id": "https://example.com/arrays.schema.json",
@@ -526,7 +867,7 @@ mod tests {
         let client = reqwest::blocking::Client::new();
         let ss = SchemaStorage::new(&spec, &client);
 
-        Dereferencer::options().process(&mut spec, &ss);
+        Dereferencer::options().process(&mut spec, &ss).unwrap();
 
         let expected = json!({
           "$id": "https://example.com/arrays.schema.json",
@@ -556,7 +897,7 @@ mod tests {
         let client = reqwest::blocking::Client::new();
         let ss = SchemaStorage::new(&spec, &client);
 
-        Dereferencer::options().process(&mut spec, &ss);
+        Dereferencer::options().process(&mut spec, &ss).unwrap();
 
         let expected = json!({
             "$id": "https://example.com/arrays.schema.json",
@@ -0,0 +1,379 @@
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+
+use crate::{
+    error::Error,
+    schema::Schema,
+    tools::{pointer_escape, pointer_unescape, SchemaWalk},
+};
+
+pub static PROVENANCE_EXTENSION: &str = "x-imported-from";
+
+pub struct Importer;
+
+pub struct ImporterOptions {
+    pub prefix: String,
+    pub components: Vec<String>,
+    pub source: String,
+    pub schema: Schema,
+}
+
+impl Importer {
+    pub fn options(schema: Schema, source: String) -> ImporterOptions {
+        ImporterOptions {
+            prefix: String::new(),
+            components: vec![],
+            source,
+            schema,
+        }
+    }
+}
+
+impl ImporterOptions {
+    pub fn with_prefix(&mut self, value: String) -> &mut Self {
+        self.prefix = value;
+        self
+    }
+
+    // selected components in kind/name format (ex. schemas/Address), all components by default
+    pub fn with_components(&mut self, value: Vec<String>) -> &mut Self {
+        self.components = value;
+        self
+    }
+
+    // copies selected components with everything they reference, returns imported names
+    pub fn process(&self, schema: &mut Schema) -> Result<Vec<String>, Error> {
+        let vendor = self.schema.get_body();
+        let imported = self.dependencies(vendor)?;
+
+        let components = schema
+            .get_body_mut()
+            .as_object_mut()
+            .ok_or_else(|| Error::ImportInvalidTarget("root is not an object".to_string()))?
+            .entry("components")
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .ok_or_else(|| {
+                Error::ImportInvalidTarget("components are not an object".to_string())
+            })?;
+
+        let mut copies = vec![];
+        for (kind, name) in &imported {
+            let mut node = vendor["components"][kind][name].clone();
+            self.rewrite_refs(&mut node, SchemaWalk::default());
+
+            let provenance = json!({ "source": self.source, "name": name });
+            if let Value::Object(map) = &mut node {
+                map.insert(PROVENANCE_EXTENSION.to_string(), provenance.clone());
+            }
+
+            // component imported before from the same source is replaced, others are kept
+            let target = format!("{}{}", self.prefix, name);
+            match components.get(kind).and_then(|set| set.get(&target)) {
+                Some(existing) if existing.get(PROVENANCE_EXTENSION) != Some(&provenance) => {
+                    return Err(Error::ImportCollision(format!("{}/{}", kind, target)));
+                }
+                _ => copies.push((kind.clone(), target, node)),
+            }
+        }
+
+        let mut result = vec![];
+        for (kind, target, node) in copies {
+            let set = components
+                .entry(kind.clone())
+                .or_insert_with(|| json!({}))
+                .as_object_mut()
+                .ok_or_else(|| Error::ImportInvalidTarget(format!("components/{}", kind)))?;
+
+            log::info!("components/{}/{}: imported", kind, target);
+            set.insert(target.clone(), node);
+            result.push(format!("{}/{}", kind, target));
+        }
+
+        Ok(result)
+    }
+
+    fn dependencies(&self, vendor: &Value) -> Result<BTreeSet<(String, String)>, Error> {
+        let all = vendor
+            .get("components")
+            .and_then(|c| c.as_object())
+            .cloned()
+            .unwrap_or_default();
+
+        let mut pending: Vec<(String, String)> = if self.components.is_empty() {
+            all.iter()
+                .filter_map(|(kind, set)| set.as_object().map(|s| (kind, s)))
+                .flat_map(|(kind, set)| set.keys().map(move |name| (kind.clone(), name.clone())))
+                .collect()
+        } else {
+            self.components
+                .iter()
+                .map(|c| match c.split_once('/') {
+                    Some((kind, name)) => Ok((kind.to_string(), name.to_string())),
+                    None => Err(Error::ImportComponentNotFound(c.clone())),
+                })
+                .collect::<Result<_, _>>()?
+        };
+
+        let mut found = BTreeSet::new();
+        while let Some((kind, name)) = pending.pop() {
+            if found.contains(&(kind.clone(), name.clone())) {
+                continue;
+            }
+
+            let node = all
+                .get(&kind)
+                .and_then(|set| set.get(&name))
+                .ok_or_else(|| Error::ImportComponentNotFound(format!("{}/{}", kind, name)))?;
+
+            collect_refs(node, SchemaWalk::default(), &mut pending);
+            found.insert((kind, name));
+        }
+
+        Ok(found)
+    }
+
+    fn rewrite_refs(&self, node: &mut Value, walk: SchemaWalk) {
+        match node {
+            Value::Object(map) => {
+                if !walk.has_names() {
+                    if let Some(Value::String(reference)) = map.get_mut("$ref") {
+                        match component_ref(reference) {
+                            Some((kind, name)) => *reference = self.reference(&kind, &name),
+                            None => log::warn!("{}: reference is not imported", reference),
+                        }
+                    }
+
+                    // mapping targets are references or bare names of schemas
+                    if let Some(Value::Object(mapping)) = map
+                        .get_mut("discriminator")
+                        .and_then(|d| d.get_mut("mapping"))
+                    {
+                        for target in mapping.values_mut() {
+                            if let Value::String(target) = target {
+                                match mapping_ref(target) {
+                                    Some((kind, name)) if target.contains('/') => {
+                                        *target = self.reference(&kind, &name)
+                                    }
+                                    Some((_, name)) => *target = format!("{}{}", self.prefix, name),
+                                    None => log::warn!("{}: mapping is not imported", target),
+                                }
+                            }
+                        }
+                    }
+                }
+
+                for (key, value) in map.iter_mut() {
+                    if !walk.is_instance(key) {
+                        self.rewrite_refs(value, walk.enter(key));
+                    }
+                }
+            }
+            Value::Array(list) => {
+                for value in list.iter_mut() {
+                    self.rewrite_refs(value, SchemaWalk::default());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn reference(&self, kind: &str, name: &str) -> String {
+        format!(
+            "#/components/{}/{}",
+            kind,
+            pointer_escape(&format!("{}{}", self.prefix, name))
+        )
+    }
+}
+
+fn collect_refs(node: &Value, walk: SchemaWalk, result: &mut Vec<(String, String)>) {
+    match node {
+        Value::Object(map) => {
+            if !walk.has_names() {
+                if let Some(component) = map
+                    .get("$ref")
+                    .and_then(|r| r.as_str())
+                    .and_then(component_ref)
+                {
+                    result.push(component);
+                }
+
+                if let Some(Value::Object(mapping)) =
+                    map.get("discriminator").and_then(|d| d.get("mapping"))
+                {
+                    result.extend(
+                        mapping
+                            .values()
+                            .filter_map(|v| v.as_str())
+                            .filter_map(mapping_ref),
+                    );
+                }
+            }
+
+            for (key, value) in map {
+                if !walk.is_instance(key) {
+                    collect_refs(value, walk.enter(key), result);
+                }
+            }
+        }
+        Value::Array(list) => list
+            .iter()
+            .for_each(|v| collect_refs(v, SchemaWalk::default(), result)),
+        _ => {}
+    }
+}
+
+fn mapping_ref(target: &str) -> Option<(String, String)> {
+    if target.contains('/') {
+        component_ref(target)
+    } else {
+        Some(("schemas".to_string(), target.to_string()))
+    }
+}
+
+fn component_ref(reference: &str) -> Option<(String, String)> {
+    let path = reference.strip_prefix("#/components/")?;
+    let (kind, name) = path.split_once('/')?;
+
+    if name.contains('/') {
+        return None;
+    }
+
+    Some((kind.to_string(), pointer_unescape(name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vendor() -> Schema {
+        Schema::from_json(json!({
+            "components": {
+                "schemas": {
+                    "Address": {
+                        "type": "object",
+                        "properties": { "country": { "$ref": "#/components/schemas/Country" } }
+                    },
+                    "Country": { "type": "string" },
+                    "Unused": { "type": "integer" }
+                },
+                "responses": {
+                    "Error": { "description": "error" }
+                }
+            }
+        }))
+    }
+
+    #[test]
+    fn test_import() {
+        let mut schema = Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "components": { "schemas": { "Address": { "type": "string" } } }
+        }));
+
+        let mut importer = Importer::options(vendor(), "vendor.yaml".to_string());
+        importer
+            .with_prefix("Vendor_".to_string())
+            .with_components(vec!["schemas/Address".to_string()]);
+
+        assert_eq!(
+            importer.process(&mut schema).unwrap(),
+            vec!["schemas/Vendor_Address", "schemas/Vendor_Country"]
+        );
+
+        let expected = json!({
+            "openapi": "3.0.0",
+            "components": {
+                "schemas": {
+                    "Address": { "type": "string" },
+                    "Vendor_Address": {
+                        "type": "object",
+                        "properties": { "country": { "$ref": "#/components/schemas/Vendor_Country" } },
+                        "x-imported-from": { "source": "vendor.yaml", "name": "Address" }
+                    },
+                    "Vendor_Country": {
+                        "type": "string",
+                        "x-imported-from": { "source": "vendor.yaml", "name": "Country" }
+                    }
+                }
+            }
+        });
+        assert_eq!(schema.get_body(), &expected);
+
+        // repeated import replaces components of the same provenance
+        importer.process(&mut schema).unwrap();
+        assert_eq!(schema.get_body(), &expected);
+    }
+
+    #[test]
+    fn test_import_follows_discriminator_mapping() {
+        let vendor = Schema::from_json(json!({
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "kind": { "type": "string", "enum": ["Cat", "#/components/schemas/Dog"] },
+                            "$ref": { "type": "string" }
+                        },
+                        "discriminator": {
+                            "propertyName": "kind",
+                            "mapping": { "cat": "Cat", "dog": "#/components/schemas/Dog" }
+                        }
+                    },
+                    "Cat": { "type": "object" },
+                    "Dog": { "type": "object" }
+                }
+            }
+        }));
+
+        let mut schema = Schema::from_json(json!({}));
+        let imported = Importer::options(vendor, "vendor.yaml".to_string())
+            .with_prefix("Vendor_".to_string())
+            .with_components(vec!["schemas/Pet".to_string()])
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(
+            imported,
+            vec![
+                "schemas/Vendor_Cat",
+                "schemas/Vendor_Dog",
+                "schemas/Vendor_Pet"
+            ]
+        );
+
+        // enum values and properties named like keywords are not references
+        let pet = &schema.get_body()["components"]["schemas"]["Vendor_Pet"];
+        assert_eq!(
+            pet["discriminator"]["mapping"],
+            json!({ "cat": "Vendor_Cat", "dog": "#/components/schemas/Vendor_Dog" })
+        );
+        assert_eq!(
+            pet["properties"]["kind"]["enum"],
+            json!(["Cat", "#/components/schemas/Dog"])
+        );
+        assert_eq!(pet["properties"]["$ref"], json!({ "type": "string" }));
+    }
+
+    #[test]
+    fn test_import_collision() {
+        let mut schema = Schema::from_json(json!({
+            "components": { "schemas": { "Country": { "type": "string" } } }
+        }));
+
+        let result = Importer::options(vendor(), "vendor.yaml".to_string())
+            .with_components(vec!["schemas/Address".to_string()])
+            .process(&mut schema);
+        assert!(matches!(result, Err(Error::ImportCollision(c)) if c == "schemas/Country"));
+        assert!(schema.get_body()["components"]["schemas"]
+            .get("Address")
+            .is_none());
+
+        let result = Importer::options(vendor(), "vendor.yaml".to_string())
+            .with_components(vec!["schemas/Missing".to_string()])
+            .process(&mut schema);
+        assert!(matches!(result, Err(Error::ImportComponentNotFound(_))));
+    }
+}
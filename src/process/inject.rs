@@ -0,0 +1,254 @@
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::tools::HTTP_METHODS;
+
+#[derive(Debug, Deserialize, Default)]
+struct Config {
+    // parameter objects or references to components, added to every selected operation
+    #[serde(default)]
+    parameters: Vec<Value>,
+
+    #[serde(default)]
+    select: Selector,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct Selector {
+    // operations with any of tags, all by default
+    #[serde(default)]
+    tags: Vec<String>,
+
+    // patterns matching path, all by default
+    #[serde(default)]
+    paths: Vec<String>,
+
+    #[serde(default)]
+    methods: Vec<String>,
+
+    // operations in form of `GET /path` which are skipped
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+pub struct Injector;
+
+pub struct InjectorOptions {
+    config: Config,
+    paths: Vec<Regex>,
+}
+
+impl Injector {
+    pub fn options(config: &Schema) -> Result<InjectorOptions, Error> {
+        let config: Config =
+            serde_json::from_value(config.get_body().clone()).map_err(Error::SerdeJsonError)?;
+
+        let paths = config
+            .select
+            .paths
+            .iter()
+            .map(|s| Regex::new(s).map_err(|_| Error::InjectInvalidPattern(s.clone())))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(InjectorOptions { config, paths })
+    }
+}
+
+impl InjectorOptions {
+    // parameters already declared by operation or its path item (same name and location) are skipped
+    pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
+        let root = schema.get_body().clone();
+
+        let injected = self
+            .config
+            .parameters
+            .iter()
+            .map(|p| {
+                identity(&root, p)
+                    .map(|id| (id, p))
+                    .ok_or_else(|| Error::InjectInvalidParameter(serde_json::to_string(p).unwrap()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let paths = match schema.get_body_mut().get_mut("paths") {
+            Some(Value::Object(paths)) => paths,
+            _ => return Ok(()),
+        };
+
+        for (path, item) in paths.iter_mut() {
+            let item = match item {
+                Value::Object(item) => item,
+                _ => continue,
+            };
+
+            let shared = declared(&root, item.get("parameters"));
+
+            for (method, operation) in item.iter_mut() {
+                if !HTTP_METHODS.contains(&method.as_str())
+                    || !self.is_selected(path, method, operation)
+                {
+                    continue;
+                }
+
+                let operation = match operation {
+                    Value::Object(operation) => operation,
+                    _ => continue,
+                };
+
+                let mut existing = declared(&root, operation.get("parameters"));
+                existing.extend(shared.iter().cloned());
+
+                let missing = injected
+                    .iter()
+                    .filter(|(id, _)| !existing.contains(id))
+                    .collect::<Vec<_>>();
+                if missing.is_empty() {
+                    continue;
+                }
+
+                if let Value::Array(list) = operation
+                    .entry("parameters")
+                    .or_insert_with(|| Value::Array(vec![]))
+                {
+                    for ((in_, name), parameter) in missing {
+                        log::info!(
+                            "{} {}: {} {} injected",
+                            method.to_uppercase(),
+                            path,
+                            in_,
+                            name
+                        );
+                        list.push((*parameter).clone());
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_selected(&self, path: &str, method: &str, operation: &Value) -> bool {
+        let select = &self.config.select;
+
+        let tagged = select.tags.is_empty()
+            || match operation.get("tags") {
+                Some(Value::Array(tags)) => tags
+                    .iter()
+                    .filter_map(|t| t.as_str())
+                    .any(|t| select.tags.iter().any(|s| s == t)),
+                _ => false,
+            };
+
+        let excluded = select.exclude.iter().any(|e| match e.split_once(' ') {
+            Some((m, p)) => m.eq_ignore_ascii_case(method) && p == path,
+            None => false,
+        });
+
+        tagged
+            && !excluded
+            && (self.paths.is_empty() || self.paths.iter().any(|r| r.is_match(path)))
+            && (select.methods.is_empty()
+                || select
+                    .methods
+                    .iter()
+                    .any(|m| m.eq_ignore_ascii_case(method)))
+    }
+}
+
+// location and name of parameter, names of headers are case insensitive
+fn identity(root: &Value, parameter: &Value) -> Option<(String, String)> {
+    let parameter = match parameter.get("$ref").and_then(|r| r.as_str()) {
+        Some(reference) => root.pointer(reference.strip_prefix('#')?)?,
+        None => parameter,
+    };
+
+    let in_ = parameter.get("in")?.as_str()?.to_string();
+    let name = parameter.get("name")?.as_str()?;
+
+    let name = if in_ == "header" {
+        name.to_lowercase()
+    } else {
+        name.to_string()
+    };
+
+    Some((in_, name))
+}
+
+fn declared(root: &Value, parameters: Option<&Value>) -> Vec<(String, String)> {
+    match parameters {
+        Some(Value::Array(list)) => list.iter().filter_map(|p| identity(root, p)).collect(),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_inject() {
+        let mut schema = Schema::from_json(json!({
+            "paths": {
+                "/users": {
+                    "parameters": [{ "name": "tenant", "in": "query" }],
+                    "get": { "tags": ["users"] },
+                    "post": {
+                        "tags": ["users"],
+                        "parameters": [{ "$ref": "#/components/parameters/RequestId" }]
+                    }
+                },
+                "/health": {
+                    "get": { "tags": ["users"] }
+                },
+                "/admin": {
+                    "get": { "tags": ["admin"] }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "RequestId": { "name": "X-Request-Id", "in": "header" }
+                }
+            }
+        }));
+
+        let config = Schema::from_json(json!({
+            "parameters": [
+                { "name": "x-request-id", "in": "header", "schema": { "type": "string" } },
+                { "name": "tenant", "in": "query" }
+            ],
+            "select": { "tags": ["users"], "exclude": ["GET /health"] }
+        }));
+
+        let injector = Injector::options(&config).unwrap();
+        injector.process(&mut schema).unwrap();
+        injector.process(&mut schema).unwrap();
+
+        let paths = &schema.get_body()["paths"];
+        assert_eq!(
+            paths["/users"]["get"]["parameters"],
+            json!([{ "name": "x-request-id", "in": "header", "schema": { "type": "string" } }])
+        );
+        assert_eq!(
+            paths["/users"]["post"]["parameters"],
+            json!([{ "$ref": "#/components/parameters/RequestId" }])
+        );
+        assert_eq!(paths["/health"]["get"].get("parameters"), None);
+        assert_eq!(paths["/admin"]["get"].get("parameters"), None);
+    }
+
+    #[test]
+    fn test_inject_invalid_parameter() {
+        let config = Schema::from_json(json!({
+            "parameters": [{ "$ref": "#/components/parameters/Missing" }]
+        }));
+
+        let result = Injector::options(&config)
+            .unwrap()
+            .process(&mut Schema::from_json(json!({ "paths": {} })));
+        assert!(matches!(result, Err(Error::InjectInvalidParameter(_))));
+    }
+}
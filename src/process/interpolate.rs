@@ -0,0 +1,172 @@
+use regex::{Captures, Regex};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+use crate::error::Error;
+use crate::schema::Schema;
+
+lazy_static! {
+    // `$${` escapes placeholder, `${NAME:-default}` falls back to default when variable is unset
+    static ref PLACEHOLDER: Regex = Regex::new(
+        r"\$\$\{|\$\{([A-Za-z_][A-Za-z0-9_]*)(?::-([^}]*))?\}|%options\.([A-Za-z0-9_.\-]+)%"
+    )
+    .unwrap();
+}
+
+pub struct Interpolator;
+
+pub struct InterpolatorOptions {
+    pub strict: bool,
+    pub parameters: Vec<(String, Value)>,
+}
+
+impl Interpolator {
+    pub fn options() -> InterpolatorOptions {
+        InterpolatorOptions {
+            strict: false,
+            parameters: vec![],
+        }
+    }
+}
+
+impl InterpolatorOptions {
+    pub fn with_strict(&mut self, value: bool) -> &mut Self {
+        self.strict = value;
+        self
+    }
+
+    pub fn with_parameters(&mut self, value: Vec<(String, Value)>) -> &mut Self {
+        self.parameters = value;
+        self
+    }
+
+    // expands placeholders in string values, keys of maps are left as they are
+    pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
+        let mut missing = BTreeSet::new();
+        process_node(schema.get_body_mut(), self, &mut missing);
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let missing = missing.into_iter().collect::<Vec<_>>().join(", ");
+        if self.strict {
+            Err(Error::InterpolateUnsetVariable(missing))
+        } else {
+            log::warn!("not interpolated, unset: {}", missing);
+            Ok(())
+        }
+    }
+
+    fn parameter(&self, name: &str) -> Option<String> {
+        self.parameters
+            .iter()
+            .rev()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| match v {
+                Value::String(s) => s.clone(),
+                v => v.to_string(),
+            })
+    }
+}
+
+fn process_node(node: &mut Value, options: &InterpolatorOptions, missing: &mut BTreeSet<String>) {
+    match node {
+        Value::String(s) if s.contains('$') || s.contains('%') => {
+            *s = interpolate(s, options, missing);
+        }
+        Value::Object(map) => {
+            for (_, value) in map.iter_mut() {
+                process_node(value, options, missing);
+            }
+        }
+        Value::Array(list) => {
+            for value in list.iter_mut() {
+                process_node(value, options, missing);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn interpolate(
+    text: &str,
+    options: &InterpolatorOptions,
+    missing: &mut BTreeSet<String>,
+) -> String {
+    PLACEHOLDER
+        .replace_all(text, |c: &Captures| {
+            let found = if let Some(name) = c.get(1) {
+                std::env::var(name.as_str())
+                    .ok()
+                    .or_else(|| c.get(2).map(|d| d.as_str().to_string()))
+                    .ok_or_else(|| name.as_str().to_string())
+            } else if let Some(name) = c.get(3) {
+                options
+                    .parameter(name.as_str())
+                    .ok_or_else(|| format!("options.{}", name.as_str()))
+            } else {
+                Ok("${".to_string())
+            };
+
+            found.unwrap_or_else(|name| {
+                missing.insert(name);
+                c[0].to_string()
+            })
+        })
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_interpolate() {
+        std::env::set_var("SCHEMATOOLS_TEST_HOST", "api.example.com");
+
+        let mut schema = Schema::from_json(json!({
+            "servers": [{ "url": "https://${SCHEMATOOLS_TEST_HOST}/%options.version%" }],
+            "info": {
+                "contact": { "email": "${SCHEMATOOLS_TEST_EMAIL:-team@example.com}" },
+                "description": "literal $${SCHEMATOOLS_TEST_HOST}, 100% ${}"
+            },
+            "${SCHEMATOOLS_TEST_HOST}": 1
+        }));
+
+        Interpolator::options()
+            .with_parameters(vec![("version".to_string(), json!("v2"))])
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(
+            schema.get_body(),
+            &json!({
+                "servers": [{ "url": "https://api.example.com/v2" }],
+                "info": {
+                    "contact": { "email": "team@example.com" },
+                    "description": "literal ${SCHEMATOOLS_TEST_HOST}, 100% ${}"
+                },
+                "${SCHEMATOOLS_TEST_HOST}": 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_interpolate_unset() {
+        let body = json!({ "servers": [{ "url": "${SCHEMATOOLS_TEST_UNSET}/%options.base%" }] });
+
+        let mut schema = Schema::from_json(body.clone());
+        Interpolator::options().process(&mut schema).unwrap();
+        assert_eq!(schema.get_body(), &body);
+
+        let result = Interpolator::options()
+            .with_strict(true)
+            .process(&mut schema);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Unset variables of interpolation: SCHEMATOOLS_TEST_UNSET, options.base"
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use serde_json::Value;
+
+use crate::error::Error;
+
+// guards of steps expanding documents, exceeded limit fails processing instead of eating memory
+#[derive(Debug, Clone)]
+pub struct Limits {
+    // bytes of serialized input document
+    pub max_size: Option<usize>,
+
+    // references (or allOf elements) resolved within each other
+    pub max_depth: usize,
+
+    // nodes added by expansion relative to nodes of input document
+    pub max_expansion: Option<usize>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_size: None,
+            max_depth: 50,
+            max_expansion: Some(1000),
+        }
+    }
+}
+
+impl Limits {
+    pub fn check_size(&self, document: &Value) -> Result<(), Error> {
+        if let Some(max) = self.max_size {
+            let size = serde_json::to_vec(document).map(|v| v.len()).unwrap_or(0);
+
+            if size > max {
+                return Err(Error::ProcessLimitExceeded(format!(
+                    "document has {} bytes, allowed {}",
+                    size, max
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn check_depth(&self, depth: usize, scope: &str) -> Result<(), Error> {
+        if depth > self.max_depth {
+            return Err(Error::ProcessLimitExceeded(format!(
+                "references nested deeper than {} at {}, infinite reference?",
+                self.max_depth, scope
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn budget(&self, document: &Value) -> Budget {
+        Budget {
+            input: nodes(document),
+            added: 0,
+            max_expansion: self.max_expansion,
+        }
+    }
+}
+
+// nodes added by expansion so far, checked after every expansion
+pub struct Budget {
+    input: usize,
+    added: usize,
+    max_expansion: Option<usize>,
+}
+
+impl Budget {
+    pub fn expand(&mut self, value: &Value, scope: &str) -> Result<(), Error> {
+        self.added += nodes(value);
+
+        match self.max_expansion {
+            Some(factor) if self.added > self.input.max(1) * factor => {
+                Err(Error::ProcessLimitExceeded(format!(
+                    "expansion at {} exceeds {} times size of input document ({} nodes)",
+                    scope, factor, self.input
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+pub fn nodes(value: &Value) -> usize {
+    match value {
+        Value::Object(map) => 1 + map.values().map(nodes).sum::<usize>(),
+        Value::Array(list) => 1 + list.iter().map(nodes).sum::<usize>(),
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_limits() {
+        let document = json!({ "a": [1, 2, { "b": null }] });
+        assert_eq!(nodes(&document), 6);
+
+        let limits = Limits {
+            max_size: Some(10),
+            max_depth: 2,
+            max_expansion: Some(2),
+        };
+
+        assert!(limits.check_size(&document).is_err());
+        assert!(limits.check_size(&json!([1])).is_ok());
+        assert!(limits.check_depth(2, "#").is_ok());
+        assert!(limits.check_depth(3, "#").is_err());
+
+        let mut budget = limits.budget(&document);
+        assert!(budget.expand(&document, "#/a").is_ok());
+        assert!(budget.expand(&document, "#/a").is_ok());
+        assert!(budget.expand(&json!(1), "#/a").is_err());
+    }
+}
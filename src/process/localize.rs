@@ -0,0 +1,145 @@
+use serde_json::{Map, Value};
+
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::tools::{pointer_escape, SchemaWalk};
+
+// keywords holding human readable texts
+pub static TEXT_KEYWORDS: &[&str] = &["title", "summary", "description"];
+
+pub struct Localizer;
+
+pub struct LocalizerOptions {
+    pub bundle: Map<String, Value>,
+}
+
+impl Localizer {
+    pub fn options(bundle: &Schema) -> Result<LocalizerOptions, Error> {
+        match bundle.get_body() {
+            Value::Object(map) => Ok(LocalizerOptions {
+                bundle: map.clone(),
+            }),
+            _ => Err(Error::LocalizeInvalidBundle(bundle.get_url().to_string())),
+        }
+    }
+}
+
+impl LocalizerOptions {
+    // replaces texts with their translations, untranslated texts are kept
+    pub fn process(&self, schema: &mut Schema) {
+        let root = schema.get_body_mut();
+
+        for (pointer, text) in &self.bundle {
+            match (root.pointer_mut(pointer), text) {
+                (Some(Value::String(original)), Value::String(translated)) => {
+                    *original = translated.clone()
+                }
+                _ => log::warn!("{}: translation does not match any text", pointer),
+            }
+        }
+
+        for pointer in texts(root).keys() {
+            if !self.bundle.contains_key(pointer) {
+                log::info!("{}: missing translation", pointer);
+            }
+        }
+    }
+}
+
+// every title, summary and description keyed by json pointer
+pub fn texts(root: &Value) -> Map<String, Value> {
+    let mut result = Map::new();
+    collect(root, "", SchemaWalk::default(), &mut result);
+    result
+}
+
+fn collect(node: &Value, pointer: &str, walk: SchemaWalk, result: &mut Map<String, Value>) {
+    match node {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let path = format!("{}/{}", pointer, pointer_escape(key));
+
+                match value {
+                    Value::String(_)
+                        if !walk.has_names() && TEXT_KEYWORDS.contains(&key.as_str()) =>
+                    {
+                        result.insert(path, value.clone());
+                    }
+                    _ if walk.is_instance(key) => {}
+                    _ => collect(value, &path, walk.enter(key), result),
+                }
+            }
+        }
+        Value::Array(list) => {
+            for (i, value) in list.iter().enumerate() {
+                let pointer = format!("{}/{}", pointer, i);
+                collect(value, &pointer, SchemaWalk::default(), result);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_extract_and_localize() {
+        let mut schema = Schema::from_json(json!({
+            "info": { "title": "Users" },
+            "paths": {
+                "/users": {
+                    "get": {
+                        "summary": "List users",
+                        "parameters": [{ "name": "page", "description": "Page number" }]
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "properties": {
+                            "title": { "type": "string", "description": "Honorific" },
+                            "default": { "type": "boolean", "description": "Primary account" }
+                        },
+                        "example": { "description": "not a text" }
+                    }
+                }
+            }
+        }));
+
+        assert_eq!(
+            texts(schema.get_body()),
+            json!({
+                "/info/title": "Users",
+                "/paths/~1users/get/summary": "List users",
+                "/paths/~1users/get/parameters/0/description": "Page number",
+                "/components/schemas/User/properties/title/description": "Honorific",
+                "/components/schemas/User/properties/default/description": "Primary account"
+            })
+            .as_object()
+            .unwrap()
+            .clone()
+        );
+
+        let bundle = Schema::from_json(json!({
+            "/info/title": "Benutzer",
+            "/paths/~1users/get/summary": "Benutzer auflisten",
+            "/paths/~1groups/get/summary": "Gruppen auflisten"
+        }));
+
+        Localizer::options(&bundle).unwrap().process(&mut schema);
+
+        assert_eq!(schema.get_body()["info"]["title"], json!("Benutzer"));
+        assert_eq!(
+            schema.get_body()["paths"]["/users"]["get"]["summary"],
+            json!("Benutzer auflisten")
+        );
+        assert_eq!(
+            schema.get_body()["paths"]["/users"]["get"]["parameters"][0]["description"],
+            json!("Page number")
+        );
+    }
+}
@@ -1,14 +1,52 @@
-use serde_json::Value;
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::str::FromStr;
 
 use crate::{
-    resolver::SchemaResolver, schema::Schema, scope::SchemaScope, storage::SchemaStorage, tools,
+    error::Error,
+    process::limits::{Budget, Limits},
+    process::report::ProcessReport,
+    resolver::SchemaResolver,
+    schema::Schema,
+    scope::SchemaScope,
+    storage::SchemaStorage,
+    tools,
 };
 
+pub static CONSTRAINT_STRATEGIES: &[&str] = &["intersect", "overwrite"];
+
+// how validation keywords present in more than one element of allOf are combined
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstraintStrategy {
+    // merged schema accepts only values valid against all elements
+    Intersect,
+
+    // value of the last element wins
+    Overwrite,
+}
+
+impl FromStr for ConstraintStrategy {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<ConstraintStrategy, Self::Err> {
+        match input {
+            "intersect" => Ok(Self::Intersect),
+            "overwrite" => Ok(Self::Overwrite),
+            s => Err(Error::MergeAllOfInvalidStrategy(s.to_string())),
+        }
+    }
+}
+
 pub struct Merger;
 
 pub struct MergerOptions {
     pub leave_invalid_properties: bool,
     pub filter: tools::Filter,
+    pub limits: Limits,
+    pub constraint_strategy: ConstraintStrategy,
+    pub keep_discriminated: bool,
+    pub scopes: Vec<String>,
+    pub excludes: Vec<String>,
 }
 
 impl MergerOptions {
@@ -22,13 +60,84 @@ impl MergerOptions {
         self
     }
 
-    pub fn process(&self, schema: &mut Schema, storage: &SchemaStorage) {
+    pub fn with_limits(&mut self, value: Limits) -> &mut Self {
+        self.limits = value;
+        self
+    }
+
+    // json pointers of merged subtrees, `*` matches one token and `**` any number of them
+    pub fn with_scopes(&mut self, value: Vec<String>) -> &mut Self {
+        self.scopes = value;
+        self
+    }
+
+    pub fn with_excludes(&mut self, value: Vec<String>) -> &mut Self {
+        self.excludes = value;
+        self
+    }
+
+    // allOfs out of scope are left untouched, whole document is in scope by default
+    fn in_scope(&self, scope: &SchemaScope) -> bool {
+        let segments = scope.segments();
+
+        (self.scopes.is_empty() || self.scopes.iter().any(|p| pointer_matches(p, &segments)))
+            && !self.excludes.iter().any(|p| pointer_matches(p, &segments))
+    }
+
+    // allOfs of subtypes in discriminator hierarchies are left intact
+    pub fn with_keep_discriminated(&mut self, value: bool) -> &mut Self {
+        self.keep_discriminated = value;
+        self
+    }
+
+    pub fn with_constraint_strategy(&mut self, value: ConstraintStrategy) -> &mut Self {
+        self.constraint_strategy = value;
+        self
+    }
+
+    fn combine(&self, a: &mut Value, b: Value, scope: &SchemaScope) -> Result<(), Error> {
+        match self.constraint_strategy {
+            ConstraintStrategy::Intersect => merge_schemas(a, b, &scope.to_string()),
+            ConstraintStrategy::Overwrite => {
+                merge_values(a, b);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn process(
+        &self,
+        schema: &mut Schema,
+        storage: &SchemaStorage,
+    ) -> Result<ProcessReport, Error> {
+        self.limits.check_size(schema.get_body())?;
+
         let resolver = SchemaResolver::new(schema, storage);
+        let mut budget = self.limits.budget(schema.get_body());
 
         let root = schema.get_body_mut();
         let mut scope = SchemaScope::default();
 
-        process_node(root, self, &mut scope, &resolver);
+        let mut report = ProcessReport::new("merge-all-of");
+
+        let hierarchy = if self.keep_discriminated {
+            Hierarchy::find(root)
+        } else {
+            Hierarchy::default()
+        };
+
+        process_node(
+            root,
+            self,
+            &hierarchy,
+            &mut scope,
+            &resolver,
+            &mut budget,
+            &mut report,
+            0,
+        )?;
+
+        Ok(report)
     }
 }
 
@@ -37,51 +146,200 @@ impl Merger {
         MergerOptions {
             leave_invalid_properties: false,
             filter: tools::Filter::default(),
+            limits: Limits::default(),
+            constraint_strategy: ConstraintStrategy::Intersect,
+            keep_discriminated: false,
+            scopes: vec![],
+            excludes: vec![],
+        }
+    }
+}
+
+// pattern matches node and all its descendants
+fn pointer_matches(pattern: &str, segments: &[String]) -> bool {
+    fn matches(pattern: &[String], segments: &[String]) -> bool {
+        match pattern.split_first() {
+            None => true,
+            Some((token, rest)) if token == "**" => {
+                (0..=segments.len()).any(|skip| matches(rest, &segments[skip..]))
+            }
+            Some((token, rest)) => match segments.split_first() {
+                Some((segment, remaining)) => {
+                    (token == "*" || token == segment) && matches(rest, remaining)
+                }
+                None => false,
+            },
+        }
+    }
+
+    let pattern = pattern
+        .trim_start_matches('#')
+        .split('/')
+        .filter(|t| !t.is_empty())
+        .map(tools::pointer_unescape)
+        .collect::<Vec<_>>();
+
+    matches(&pattern, segments)
+}
+
+// schemas taking part in discriminated inheritance
+#[derive(Default)]
+struct Hierarchy {
+    // references to schemas with discriminator
+    parents: HashSet<String>,
+
+    // paths of schemas mapped by discriminators
+    subtypes: HashSet<String>,
+}
+
+impl Hierarchy {
+    fn find(root: &Value) -> Self {
+        let mut hierarchy = Self::default();
+        hierarchy.collect(root, tools::SchemaWalk::default(), &mut String::new());
+        hierarchy
+    }
+
+    fn collect(&mut self, node: &Value, walk: tools::SchemaWalk, pointer: &mut String) {
+        match node {
+            Value::Object(map) => {
+                if let Some(discriminator) = map.get("discriminator").filter(|_| !walk.has_names())
+                {
+                    self.parents.insert(format!("#{}", pointer));
+
+                    if let Some(Value::Object(mapping)) = discriminator.get("mapping") {
+                        for target in mapping.values().filter_map(|t| t.as_str()) {
+                            // plain names point to components of openapi, subtypes of other
+                            // documents are not merged as part of this one
+                            let path = match target.split_once('#') {
+                                Some(("", path)) => path.to_string(),
+                                Some(_) => {
+                                    log::debug!("{}: external subtype skipped", target);
+                                    continue;
+                                }
+                                None => format!("/components/schemas/{}", target),
+                            };
+                            self.subtypes.insert(tools::pointer_unescape(&path));
+                        }
+                    }
+                }
+
+                for (key, value) in map {
+                    if walk.is_instance(key) {
+                        continue;
+                    }
+
+                    let len = pointer.len();
+                    pointer.push('/');
+                    pointer.push_str(&tools::pointer_escape(key));
+                    self.collect(value, walk.enter(key), pointer);
+                    pointer.truncate(len);
+                }
+            }
+            Value::Array(list) => {
+                for (index, value) in list.iter().enumerate() {
+                    let len = pointer.len();
+                    pointer.push_str(&format!("/{}", index));
+                    self.collect(value, tools::SchemaWalk::default(), pointer);
+                    pointer.truncate(len);
+                }
+            }
+            _ => {}
         }
     }
+
+    // subtype is mapped by discriminator or extends schema with discriminator
+    fn is_subtype(&self, scope: &SchemaScope, schemas: &[Value]) -> bool {
+        self.subtypes.contains(&scope.to_string())
+            || schemas.iter().any(|s| {
+                matches!(s.get("$ref").and_then(|r| r.as_str()), Some(r) if self.parents.contains(r))
+            })
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn process_merge(
     root: &mut Value,
     options: &MergerOptions,
+    hierarchy: &Hierarchy,
     scope: &mut SchemaScope,
     resolver: &SchemaResolver,
-) {
+    budget: &mut Budget,
+    report: &mut ProcessReport,
+    depth: usize,
+) -> Result<(), Error> {
     if !options.filter.check(root, true) {
-        return log::info!("allOf skipped because of filter");
+        log::info!("allOf skipped because of filter");
+        report.count("allOfs skipped by filter");
+        return Ok(());
     }
 
+    // elements are resolved and merged recursively, cyclic allOf never ends
+    options.limits.check_depth(depth, &scope.to_string())?;
+
     match root.as_object_mut().unwrap().get_mut("allOf").unwrap() {
+        // scope applies to document itself, resolved elements of merged allOf are processed whole
+        Value::Array(_) if depth == 0 && !options.in_scope(scope) => {
+            report.count("allOfs out of scope");
+        }
+        Value::Array(schemas) if hierarchy.is_subtype(scope, schemas) => {
+            log::info!("{}: allOf of discriminated subtype kept", scope);
+            report.count("allOfs of discriminated subtypes kept");
+        }
         Value::Array(schemas) => {
             let size = schemas.len();
 
             if size == 0 {
-                return log::warn!("allOf needs to be not empty array");
+                report.warn(
+                    "empty-all-of",
+                    format!("{}: allOf needs to be not empty array", scope),
+                );
+                return Ok(());
             }
 
             let first = if size == 1 {
-                log::warn!("allOf with one element, skipping");
+                report.warn(
+                    "single-all-of",
+                    format!("{}: allOf with one element, skipping", scope),
+                );
                 schemas.get_mut(0).unwrap().clone()
             } else {
                 log::debug!("{}.allOf", scope);
 
-                let mut first = resolver
-                    .resolve(schemas.get_mut(0).unwrap(), scope, |v, ss| {
-                        let mut node = v.clone();
-                        process_node(&mut node, options, ss, resolver);
-                        Ok(node)
-                    })
-                    .unwrap();
+                let mut first = resolver.resolve(schemas.get_mut(0).unwrap(), scope, |v, ss| {
+                    budget.expand(v, &ss.to_string())?;
+
+                    let mut node = v.clone();
+                    process_node(
+                        &mut node,
+                        options,
+                        hierarchy,
+                        ss,
+                        resolver,
+                        budget,
+                        report,
+                        depth + 1,
+                    )?;
+                    Ok(node)
+                })?;
 
                 for n in 1..size {
-                    let value = resolver
-                        .resolve(schemas.get_mut(n).unwrap(), scope, |v, ss| {
-                            let mut node = v.clone();
-                            process_node(&mut node, options, ss, resolver);
-                            Ok(node)
-                        })
-                        .unwrap();
-                    merge_values(&mut first, value);
+                    let value = resolver.resolve(schemas.get_mut(n).unwrap(), scope, |v, ss| {
+                        budget.expand(v, &ss.to_string())?;
+
+                        let mut node = v.clone();
+                        process_node(
+                            &mut node,
+                            options,
+                            hierarchy,
+                            ss,
+                            resolver,
+                            budget,
+                            report,
+                            depth + 1,
+                        )?;
+                        Ok(node)
+                    })?;
+                    options.combine(&mut first, value, scope)?;
                 }
 
                 first
@@ -89,7 +347,8 @@ fn process_merge(
 
             // todo: leave_invalid_properties vs
             root.as_object_mut().unwrap().remove("allOf");
-            merge_values(root, first);
+            options.combine(root, first, scope)?;
+            report.count("allOfs merged");
         }
 
         Value::Null => {}
@@ -98,14 +357,21 @@ fn process_merge(
         Value::String(_) => {}
         Value::Object(_) => {}
     }
+
+    Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn process_node(
     root: &mut Value,
     options: &MergerOptions,
+    hierarchy: &Hierarchy,
     scope: &mut SchemaScope,
     resolver: &SchemaResolver,
-) {
+    budget: &mut Budget,
+    report: &mut ProcessReport,
+    depth: usize,
+) -> Result<(), Error> {
     match root {
         Value::Object(ref mut map) => {
             // todo: allOf deep
@@ -113,28 +379,36 @@ fn process_node(
             {
                 for (property, value) in map.into_iter() {
                     scope.any(property);
-                    process_node(value, options, scope, resolver);
+                    process_node(
+                        value, options, hierarchy, scope, resolver, budget, report, depth,
+                    )?;
                     scope.pop();
                 }
             }
 
             // process allOf
             if map.contains_key("allOf") {
-                process_merge(root, options, scope, resolver)
+                process_merge(
+                    root, options, hierarchy, scope, resolver, budget, report, depth,
+                )?;
             }
         }
         Value::Array(a) => {
             for (index, x) in a.iter_mut().enumerate() {
                 scope.index(index);
-                process_node(x, options, scope, resolver);
+                process_node(
+                    x, options, hierarchy, scope, resolver, budget, report, depth,
+                )?;
                 scope.pop();
             }
         }
         _ => {}
     }
+
+    Ok(())
 }
 
-fn merge_values(a: &mut Value, b: Value) {
+pub fn merge_values(a: &mut Value, b: Value) {
     match (a, b) {
         (a @ &mut Value::Object(_), Value::Object(b)) => {
             let a = a.as_object_mut().unwrap();
@@ -154,6 +428,252 @@ fn merge_values(a: &mut Value, b: Value) {
     }
 }
 
+// validation keywords are combined so merged schema accepts only values valid against both,
+// others are merged like plain values
+pub fn merge_schemas(a: &mut Value, b: Value, scope: &str) -> Result<(), Error> {
+    let (a, b) = match (a, b) {
+        (Value::Object(a), Value::Object(b)) => (a, b),
+        (a, b) => {
+            merge_values(a, b);
+            return Ok(());
+        }
+    };
+
+    let incompatible = |reason: String| Error::MergeAllOfIncompatible(scope.to_string(), reason);
+    let mut residual = vec![];
+
+    let flags = [
+        (
+            "exclusiveMaximum",
+            exclusive_flag(a, &b, "maximum", "exclusiveMaximum", true),
+        ),
+        (
+            "exclusiveMinimum",
+            exclusive_flag(a, &b, "minimum", "exclusiveMinimum", false),
+        ),
+    ];
+
+    for (key, value) in b {
+        let current = match a.get_mut(&key) {
+            Some(current) if current != &value => current,
+            Some(_) => continue,
+            None => {
+                a.insert(key, value);
+                continue;
+            }
+        };
+
+        match key.as_str() {
+            "type" => match intersect_types(current, &value) {
+                Some(types) => *current = types,
+                None => {
+                    return Err(incompatible(format!(
+                        "type {} and {} have nothing in common",
+                        current, value
+                    )))
+                }
+            },
+            "maximum" | "maxLength" | "maxItems" | "maxProperties" | "maxContains" => {
+                *current = bound(current, value, true)
+            }
+            "minimum" | "minLength" | "minItems" | "minProperties" | "minContains" => {
+                *current = bound(current, value, false)
+            }
+            "exclusiveMaximum" | "exclusiveMinimum" => match (&*current, value) {
+                // openapi 3.0 flags are set together with their bounds below
+                (Value::Bool(_), Value::Bool(_)) => {}
+                (_, value) => *current = bound(current, value, key == "exclusiveMaximum"),
+            },
+            "uniqueItems" => *current = Value::Bool(current == &Value::Bool(true) || value == true),
+            "enum" => match (&*current, value) {
+                (Value::Array(c), Value::Array(v)) => {
+                    let common = c
+                        .iter()
+                        .filter(|e| v.contains(e))
+                        .cloned()
+                        .collect::<Vec<_>>();
+
+                    if common.is_empty() {
+                        return Err(incompatible(format!(
+                            "enum {} and {} have nothing in common",
+                            current,
+                            Value::Array(v)
+                        )));
+                    }
+
+                    *current = Value::Array(common)
+                }
+                (_, value) => *current = value,
+            },
+            // closed object wins, open one does not restrict the other
+            "additionalProperties" if current == &Value::Bool(false) || value == false => {
+                *current = Value::Bool(false)
+            }
+            "additionalProperties" if current == &Value::Bool(true) => *current = value,
+            "additionalProperties" if value == true => {}
+            "const" | "format" => {
+                return Err(incompatible(format!(
+                    "{} {} and {} differ",
+                    key, current, value
+                )))
+            }
+            "multipleOf" => match (current.as_u64(), value.as_u64()) {
+                (Some(c), Some(v)) if c > 0 && v > 0 => *current = Value::from(c / gcd(c, v) * v),
+                _ => residual.push(json!({ key: value })),
+            },
+            // patterns cannot be joined into one expression portable between engines
+            "pattern" | "not" => residual.push(json!({ key: value })),
+            "items" | "additionalProperties" | "contains" | "propertyNames"
+                if current.is_object() && value.is_object() =>
+            {
+                merge_schemas(current, value, &format!("{}.{}", scope, key))?
+            }
+            "properties" | "patternProperties" => match (current, value) {
+                (Value::Object(current), Value::Object(value)) => {
+                    for (name, property) in value {
+                        match current.get_mut(&name) {
+                            Some(existing) => {
+                                merge_schemas(existing, property, &format!("{}.{}", scope, name))?
+                            }
+                            None => {
+                                current.insert(name, property);
+                            }
+                        }
+                    }
+                }
+                (current, value) => merge_values(current, value),
+            },
+            _ => merge_values(current, value),
+        }
+    }
+
+    for (key, flag) in &flags {
+        if let Some(flag) = flag {
+            a.insert(key.to_string(), Value::Bool(*flag));
+        }
+    }
+
+    for (min, max) in &[
+        ("minimum", "maximum"),
+        ("minLength", "maxLength"),
+        ("minItems", "maxItems"),
+        ("minProperties", "maxProperties"),
+        ("minContains", "maxContains"),
+    ] {
+        if let (Some(lower), Some(upper)) = (
+            a.get(*min).and_then(|v| v.as_f64()),
+            a.get(*max).and_then(|v| v.as_f64()),
+        ) {
+            if lower > upper {
+                return Err(incompatible(format!(
+                    "{} {} is greater than {} {}",
+                    min, lower, max, upper
+                )));
+            }
+        }
+    }
+
+    if !residual.is_empty() {
+        merge_values(
+            a.entry("allOf").or_insert_with(|| json!([])),
+            Value::Array(residual),
+        );
+    }
+
+    Ok(())
+}
+
+// integer is the only common part of integer and number
+fn intersect_types(a: &Value, b: &Value) -> Option<Value> {
+    let types = |v: &Value| -> Vec<String> {
+        match v {
+            Value::String(s) => vec![s.clone()],
+            Value::Array(list) => list
+                .iter()
+                .filter_map(|t| t.as_str().map(|t| t.to_string()))
+                .collect(),
+            _ => vec![],
+        }
+    };
+
+    let (a, b) = (types(a), types(b));
+    let result = a
+        .iter()
+        .filter_map(|t| {
+            if b.contains(t) {
+                Some(t.clone())
+            } else if (t == "integer" && b.iter().any(|o| o == "number"))
+                || (t == "number" && b.iter().any(|o| o == "integer"))
+            {
+                Some("integer".to_string())
+            } else {
+                None
+            }
+        })
+        .fold(vec![], |mut result: Vec<String>, t| {
+            if !result.contains(&t) {
+                result.push(t);
+            }
+            result
+        });
+
+    match result.len() {
+        0 => None,
+        1 => Some(Value::String(result[0].clone())),
+        _ => Some(Value::Array(
+            result.into_iter().map(Value::String).collect(),
+        )),
+    }
+}
+
+// openapi 3.0 exclusive flag belongs to bound next to it, so merged flag is the one of stricter bound
+fn exclusive_flag(
+    a: &Map<String, Value>,
+    b: &Map<String, Value>,
+    bound: &str,
+    flag: &str,
+    upper: bool,
+) -> Option<bool> {
+    // numeric exclusive bounds of json schema are combined like other bounds
+    let (flag_a, flag_b) = (a.get(flag), b.get(flag));
+    if (flag_a.is_none() && flag_b.is_none())
+        || flag_a.iter().chain(flag_b.iter()).any(|f| !f.is_boolean())
+    {
+        return None;
+    }
+
+    let (flag_a, flag_b) = (
+        flag_a.and_then(|f| f.as_bool()).unwrap_or(false),
+        flag_b.and_then(|f| f.as_bool()).unwrap_or(false),
+    );
+
+    match (
+        a.get(bound).and_then(|v| v.as_f64()),
+        b.get(bound).and_then(|v| v.as_f64()),
+    ) {
+        (Some(x), Some(y)) if x != y => Some(if (x < y) == upper { flag_a } else { flag_b }),
+        (Some(_), None) => Some(flag_a),
+        (None, Some(_)) => Some(flag_b),
+        _ => Some(flag_a || flag_b),
+    }
+}
+
+// lower of upper bounds or higher of lower bounds, non numeric values are replaced
+fn bound(current: &Value, value: Value, upper: bool) -> Value {
+    match (current.as_f64(), value.as_f64()) {
+        (Some(c), Some(v)) if (c < v) == upper => current.clone(),
+        _ => value,
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -245,9 +765,10 @@ mod tests {
         let client = reqwest::blocking::Client::new();
         let ss = SchemaStorage::new(&schema, &client);
 
-        Merger::options().process(&mut schema, &ss);
+        let report = Merger::options().process(&mut schema, &ss).unwrap();
 
         assert_eq!(schema.get_body().to_string(), expected.to_string());
+        assert_eq!(report.counters.get("allOfs merged"), Some(&4));
     }
 
     #[test]
@@ -299,7 +820,7 @@ mod tests {
         let client = reqwest::blocking::Client::new();
         let ss = SchemaStorage::new(&schema, &client);
 
-        Merger::options().process(&mut schema, &ss);
+        Merger::options().process(&mut schema, &ss).unwrap();
 
         assert_eq!(schema.get_body().to_string(), expected.to_string());
     }
@@ -341,7 +862,7 @@ mod tests {
         let client = reqwest::blocking::Client::new();
         let ss = SchemaStorage::new(&schema, &client);
 
-        Merger::options().process(&mut schema, &ss);
+        Merger::options().process(&mut schema, &ss).unwrap();
 
         assert_eq!(schema.get_body().to_string(), expected.to_string());
     }
@@ -393,7 +914,7 @@ mod tests {
         let client = reqwest::blocking::Client::new();
         let ss = SchemaStorage::new(&schema, &client);
 
-        Merger::options().process(&mut schema, &ss);
+        Merger::options().process(&mut schema, &ss).unwrap();
 
         assert_eq!(schema.get_body().to_string(), expected.to_string());
     }
@@ -465,7 +986,7 @@ mod tests {
         let client = reqwest::blocking::Client::new();
         let ss = SchemaStorage::new(&schema, &client);
 
-        Merger::options().process(&mut schema, &&ss);
+        Merger::options().process(&mut schema, &ss).unwrap();
 
         assert_eq!(schema.get_body().to_string(), expected.to_string());
     }
@@ -590,7 +1111,11 @@ mod tests {
         let client = reqwest::blocking::Client::new();
         let ss = SchemaStorage::new(&schema, &client);
 
-        Merger::options().process(&mut schema, &ss);
+        // disjoint enums are extended only by previous behaviour
+        Merger::options()
+            .with_constraint_strategy(ConstraintStrategy::Overwrite)
+            .process(&mut schema, &ss)
+            .unwrap();
 
         assert_eq!(schema.get_body().to_string(), expected.to_string());
     }
@@ -634,8 +1159,297 @@ mod tests {
         let client = reqwest::blocking::Client::new();
         let ss = SchemaStorage::new(&schema, &client);
 
-        Merger::options().process(&mut schema, &ss);
+        Merger::options().process(&mut schema, &ss).unwrap();
 
         assert_eq!(schema.get_body().to_string(), expected.to_string());
     }
+    #[test]
+    fn test_cyclic_all_of_fails() {
+        let value = json!({
+            "definitions": {
+                "a": { "allOf": [{ "$ref": "#/definitions/b" }, { "type": "object" }] },
+                "b": { "allOf": [{ "$ref": "#/definitions/a" }, { "type": "object" }] }
+            }
+        });
+
+        let mut schema = Schema::from_json(value);
+
+        let client = reqwest::blocking::Client::new();
+        let ss = SchemaStorage::new(&schema, &client);
+
+        assert!(matches!(
+            Merger::options().process(&mut schema, &ss),
+            Err(Error::ProcessLimitExceeded(_))
+        ));
+    }
+
+    #[test]
+    fn test_merge_non_object() {
+        let value = json!({
+            "definitions": {
+                "list": {
+                    "allOf": [
+                        { "type": "array", "items": { "type": "string", "maxLength": 20 }, "maxItems": 50 },
+                        { "maxItems": 10, "minItems": 1, "items": { "maxLength": 10, "minLength": 2 } }
+                    ]
+                },
+                "code": {
+                    "maxLength": 8,
+                    "allOf": [
+                        { "type": ["string", "null"], "pattern": "^[A-Z]+$", "minLength": 2 },
+                        { "type": "string", "pattern": "^.{4}$", "minLength": 4 }
+                    ]
+                },
+                "amount": {
+                    "allOf": [
+                        { "type": "number", "minimum": 0, "multipleOf": 2 },
+                        { "type": "integer", "minimum": 1, "maximum": 100, "multipleOf": 3 }
+                    ]
+                },
+                "status": {
+                    "allOf": [
+                        { "type": "string", "enum": ["active", "blocked", "deleted"] },
+                        { "enum": ["deleted", "active"] }
+                    ]
+                },
+                "closed": {
+                    "allOf": [
+                        { "type": "object", "additionalProperties": { "type": "string" } },
+                        { "additionalProperties": false }
+                    ]
+                },
+                "open": {
+                    "allOf": [
+                        { "type": "object", "additionalProperties": true },
+                        { "additionalProperties": { "type": "string" } }
+                    ]
+                }
+            }
+        });
+
+        let mut schema = Schema::from_json(value);
+
+        let client = reqwest::blocking::Client::new();
+        let ss = SchemaStorage::new(&schema, &client);
+
+        Merger::options().process(&mut schema, &ss).unwrap();
+
+        assert_eq!(
+            schema.get_body()["definitions"],
+            json!({
+                "list": {
+                    "type": "array",
+                    "items": { "type": "string", "maxLength": 10, "minLength": 2 },
+                    "maxItems": 10,
+                    "minItems": 1
+                },
+                "code": {
+                    "maxLength": 8,
+                    "type": "string",
+                    "pattern": "^[A-Z]+$",
+                    "minLength": 4,
+                    "allOf": [{ "pattern": "^.{4}$" }]
+                },
+                "amount": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "multipleOf": 6,
+                    "maximum": 100
+                },
+                "status": { "type": "string", "enum": ["active", "deleted"] },
+                "closed": { "type": "object", "additionalProperties": false },
+                "open": { "type": "object", "additionalProperties": { "type": "string" } }
+            })
+        );
+    }
+
+    #[test]
+    fn test_exclusive_flags_follow_bounds() {
+        let mut schema = Schema::from_json(json!({
+            "definitions": {
+                "maximum": {
+                    "allOf": [
+                        { "type": "number", "maximum": 10, "exclusiveMaximum": true },
+                        { "maximum": 5 }
+                    ]
+                },
+                "minimum": {
+                    "allOf": [
+                        { "type": "number", "minimum": 0, "exclusiveMinimum": true },
+                        { "minimum": -1, "exclusiveMinimum": false }
+                    ]
+                },
+                "equal": {
+                    "allOf": [
+                        { "type": "number", "maximum": 5 },
+                        { "maximum": 5, "exclusiveMaximum": true }
+                    ]
+                }
+            }
+        }));
+
+        let client = reqwest::blocking::Client::new();
+        let ss = SchemaStorage::new(&schema, &client);
+
+        Merger::options().process(&mut schema, &ss).unwrap();
+
+        assert_eq!(
+            schema.get_body()["definitions"],
+            json!({
+                "maximum": { "type": "number", "maximum": 5, "exclusiveMaximum": false },
+                "minimum": { "type": "number", "minimum": 0, "exclusiveMinimum": true },
+                "equal": { "type": "number", "maximum": 5, "exclusiveMaximum": true }
+            })
+        );
+    }
+
+    #[test]
+    fn test_merge_incompatible_fails() {
+        for (a, b) in [
+            (json!({ "type": "string" }), json!({ "type": "array" })),
+            (
+                json!({ "type": "string", "format": "date" }),
+                json!({ "format": "email" }),
+            ),
+            (json!({ "minLength": 5 }), json!({ "maxLength": 3 })),
+            (json!({ "enum": ["a", "b"] }), json!({ "enum": ["c"] })),
+        ] {
+            let mut schema = Schema::from_json(json!({ "allOf": [a, b] }));
+
+            let client = reqwest::blocking::Client::new();
+            let ss = SchemaStorage::new(&schema, &client);
+
+            assert!(matches!(
+                Merger::options().process(&mut schema, &ss),
+                Err(Error::MergeAllOfIncompatible(_, _))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_overwrite_constraint_strategy() {
+        let mut schema = Schema::from_json(json!({
+            "allOf": [
+                { "type": "string", "minLength": 5, "pattern": "^a" },
+                { "type": "integer", "maxLength": 3, "pattern": "^b" }
+            ]
+        }));
+
+        let client = reqwest::blocking::Client::new();
+        let ss = SchemaStorage::new(&schema, &client);
+
+        Merger::options()
+            .with_constraint_strategy(ConstraintStrategy::Overwrite)
+            .process(&mut schema, &ss)
+            .unwrap();
+
+        assert_eq!(
+            schema.get_body(),
+            &json!({ "type": "integer", "minLength": 5, "pattern": "^b", "maxLength": 3 })
+        );
+    }
+
+    #[test]
+    fn test_keep_discriminated() {
+        let value = json!({
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": { "kind": { "type": "string" } },
+                        "discriminator": {
+                            "propertyName": "kind",
+                            "mapping": {
+                                "cat": "#/components/schemas/Cat",
+                                "dog": "Dog",
+                                "fish": "fish.yaml#/components/schemas/Plain"
+                            }
+                        }
+                    },
+                    "Cat": {
+                        "allOf": [{ "$ref": "#/components/schemas/Base" }, { "type": "object" }]
+                    },
+                    "Dog": {
+                        "allOf": [{ "$ref": "#/components/schemas/Base" }, { "type": "object" }]
+                    },
+                    "Bird": {
+                        "allOf": [{ "$ref": "#/components/schemas/Pet" }, { "type": "object" }]
+                    },
+                    "Base": {
+                        "type": "object",
+                        "example": { "discriminator": { "mapping": { "plain": "Plain" } } }
+                    },
+                    "Plain": {
+                        "allOf": [{ "$ref": "#/components/schemas/Base" }, { "type": "object" }]
+                    }
+                }
+            }
+        });
+
+        let mut schema = Schema::from_json(value.clone());
+
+        let client = reqwest::blocking::Client::new();
+        let ss = SchemaStorage::new(&schema, &client);
+
+        let report = Merger::options()
+            .with_keep_discriminated(true)
+            .process(&mut schema, &ss)
+            .unwrap();
+
+        let schemas = &schema.get_body()["components"]["schemas"];
+        for name in &["Cat", "Dog", "Bird"] {
+            assert_eq!(schemas[name], value["components"]["schemas"][name]);
+        }
+        // neither examples nor subtypes of other documents make local schema a subtype
+        assert_eq!(schemas["Plain"]["allOf"], Value::Null);
+        assert_eq!(
+            report.counters.get("allOfs of discriminated subtypes kept"),
+            Some(&3)
+        );
+    }
+
+    #[test]
+    fn test_scoped_merge() {
+        let all_of = json!({ "allOf": [{ "type": "object" }, { "required": ["id"] }] });
+        let value = json!({
+            "paths": { "/users": { "get": { "schema": all_of } } },
+            "components": {
+                "schemas": { "User": all_of, "Legacy": all_of, "Group": { "properties": { "users": all_of } } }
+            }
+        });
+
+        let mut schema = Schema::from_json(value);
+
+        let client = reqwest::blocking::Client::new();
+        let ss = SchemaStorage::new(&schema, &client);
+
+        let report = Merger::options()
+            .with_scopes(vec!["#/components/schemas".to_string()])
+            .with_excludes(vec![
+                "/components/schemas/Legacy".to_string(),
+                "/**/users".to_string(),
+            ])
+            .process(&mut schema, &ss)
+            .unwrap();
+
+        let body = schema.get_body();
+        let merged = json!({ "type": "object", "required": ["id"] });
+        assert_eq!(body["components"]["schemas"]["User"], merged);
+        assert_eq!(body["components"]["schemas"]["Legacy"], all_of);
+        assert_eq!(
+            body["components"]["schemas"]["Group"]["properties"]["users"],
+            all_of
+        );
+        assert_eq!(body["paths"]["/users"]["get"]["schema"], all_of);
+        assert_eq!(report.counters.get("allOfs out of scope"), Some(&3));
+
+        assert!(pointer_matches(
+            "/paths/~1users/*",
+            &["paths".to_string(), "/users".to_string(), "get".to_string()]
+        ));
+        assert!(!pointer_matches(
+            "/paths/*/post",
+            &["paths".to_string(), "/users".to_string(), "get".to_string()]
+        ));
+    }
 }
@@ -1,15 +1,49 @@
+pub mod annotate;
 pub mod bump_openapi;
+pub mod compose;
+pub mod conditionals;
+pub mod const_to_enum;
+pub mod cut_version;
+pub mod deduplicate;
+pub mod deprecation;
 pub mod dereference;
+pub mod import;
+pub mod inject;
+pub mod interpolate;
+pub mod limits;
+pub mod localize;
 pub mod merge_allof;
 pub mod merge_openapi;
 pub mod name;
+pub mod overlay;
 pub mod patch;
+pub mod redact;
+pub mod rename_properties;
+pub mod report;
+pub mod source_map;
 
 use reqwest::Url;
 use serde_json::Value;
 
+// operations and json pointers of changes made by processing step, empty when step was a no-op
+pub fn changes(original: &Value, processed: &Value) -> Vec<String> {
+    match patch::diff(original, processed) {
+        Value::Array(operations) => operations
+            .iter()
+            .map(|o| {
+                format!(
+                    "{} {}",
+                    o["op"].as_str().unwrap_or_default(),
+                    o["path"].as_str().unwrap_or_default()
+                )
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
 pub fn rel_to_absolute_refs(url: &Url, mut data: Value) -> Value {
-    if url.scheme() == "file" {
+    if url.scheme() == "file" || url.scheme() == crate::git_revision::SCHEME {
         let mut prefix = url.clone();
         prefix.path_segments_mut().unwrap().pop();
 
@@ -76,3 +110,85 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Schema;
+    use crate::storage::SchemaStorage;
+    use serde_json::json;
+
+    type Step<'a> = Box<dyn Fn(&mut Schema) + 'a>;
+
+    #[test]
+    fn test_steps_are_idempotent() {
+        let body = json!({
+            "openapi": "3.0.0",
+            "paths": {
+                "/users": {
+                    "get": {
+                        "tags": ["users"],
+                        "x-owner": "team",
+                        "responses": { "200": { "description": "ok" } }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Kind": { "const": "user" },
+                    "User": {
+                        "allOf": [
+                            { "type": "object", "properties": { "id": { "type": "integer", "maxLength": 3 } } },
+                            { "required": ["id"], "properties": { "id": { "maxLength": 2 } } }
+                        ],
+                        "if": true,
+                        "then": { "required": ["kind"] }
+                    }
+                }
+            }
+        });
+
+        let client = reqwest::blocking::Client::new();
+        let inject = Schema::from_json(json!({
+            "parameters": [{ "name": "X-Request-Id", "in": "header" }]
+        }));
+        let redact = Schema::from_json(json!({ "extensions": ["x-owner"] }));
+
+        let steps: Vec<Step> = vec![
+            Box::new(|s| {
+                let storage = SchemaStorage::new(s, &client);
+                merge_allof::Merger::options().process(s, &storage).unwrap();
+            }),
+            Box::new(|s| const_to_enum::Converter::options().process(s)),
+            Box::new(|s| conditionals::Simplifier::options().process(s)),
+            Box::new(|s| {
+                redact::Redactor::options(&redact)
+                    .unwrap()
+                    .process(s)
+                    .unwrap()
+            }),
+            Box::new(|s| {
+                inject::Injector::options(&inject)
+                    .unwrap()
+                    .process(s)
+                    .unwrap()
+            }),
+        ];
+
+        for (i, step) in steps.iter().enumerate() {
+            let mut schema = Schema::from_json(body.clone());
+
+            step(&mut schema);
+            let processed = schema.get_body().clone();
+            assert!(!changes(&body, &processed).is_empty(), "step {}", i);
+
+            step(&mut schema);
+            assert_eq!(
+                changes(&processed, schema.get_body()),
+                Vec::<String>::new(),
+                "step {}",
+                i
+            );
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use super::word::{pluralize, singularize};
+use super::word::Inflections;
 
 use crate::error::Error;
 use inflector::Inflector;
@@ -10,6 +10,7 @@ pub struct Endpoint {
     method: String,
     resources: Vec<String>,
     identifiers: Vec<String>,
+    inflections: Inflections,
 }
 
 impl Endpoint {
@@ -56,9 +57,15 @@ impl Endpoint {
             method,
             resources,
             identifiers,
+            inflections: Inflections::default(),
         })
     }
 
+    pub fn with_inflections(mut self, inflections: &Inflections) -> Self {
+        self.inflections = inflections.clone();
+        self
+    }
+
     pub fn get_operation_id(&self, resource_method_version: bool) -> String {
         let mut parts: Vec<String> = vec![];
 
@@ -90,12 +97,12 @@ impl Endpoint {
                 {
                     if i < self.identifiers.len() {
                         // has identifier
-                        singularize(processed)
+                        self.inflections.singularize(processed)
                     } else {
                         match self.method.as_str() {
-                            "post" => singularize(processed),
+                            "post" => self.inflections.singularize(processed),
                             "get" => processed,
-                            _ => pluralize(processed),
+                            _ => self.inflections.pluralize(processed),
                         }
                     }
                 }
@@ -163,4 +170,18 @@ mod tests {
             expected
         );
     }
+
+    #[test]
+    fn test_operation_name_with_inflections() {
+        let mut inflections = Inflections::default();
+        inflections.add("criterion", "criteria");
+
+        assert_eq!(
+            Endpoint::new("get".to_string(), "criteria/{id}".to_string())
+                .unwrap()
+                .with_inflections(&inflections)
+                .get_operation_id(false),
+            "getCriterion".to_string()
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{error::Error, scope::SchemaNamingStrategy};
+use crate::{error::Error, scope::SchemaNamingStrategy, tools};
 use serde_json::Map;
 use serde_json::Value;
 
@@ -11,6 +11,7 @@ pub struct JsonSchemaNamerOptions {
     overwrite_ambigous: bool,
     base_name: Option<String>,
     naming_strategy: SchemaNamingStrategy,
+    extension_prefix: Option<String>,
 }
 
 impl JsonSchemaNamer {
@@ -20,6 +21,7 @@ impl JsonSchemaNamer {
             overwrite_ambigous: false,
             base_name: None,
             naming_strategy: SchemaNamingStrategy::Default,
+            extension_prefix: None,
         }
     }
 }
@@ -45,6 +47,11 @@ impl JsonSchemaNamerOptions {
         self
     }
 
+    pub fn with_extension_prefix(&mut self, value: Option<String>) -> &mut Self {
+        self.extension_prefix = value;
+        self
+    }
+
     pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
         let root = schema.get_body_mut();
 
@@ -55,6 +62,7 @@ impl JsonSchemaNamerOptions {
                 overwrite: self.overwrite,
                 overwrite_ambigous: self.overwrite_ambigous,
                 base_name: self.base_name.clone(), // .or_else(|| Some("AnonymousType".to_string())),
+                extension_prefix: self.extension_prefix.clone(),
             },
         )
     }
@@ -64,6 +72,7 @@ pub struct NamerOptions {
     pub overwrite: bool,
     pub overwrite_ambigous: bool,
     pub base_name: Option<String>,
+    pub extension_prefix: Option<String>,
 }
 
 pub fn name_schema(
@@ -155,6 +164,13 @@ fn get_title(
 ) -> Result<Option<String>, Error> {
     let mut title = map.get("title").map(|t| t.as_str().unwrap().to_string());
 
+    if let Some(Value::String(name)) = map.get(&tools::extension(&options.extension_prefix, "name"))
+    {
+        log::debug!("{} -> {} (forced by extension)", scope, name);
+
+        return Ok(Some(name.clone()));
+    }
+
     if scope.is_empty() {
         if title.is_none() || options.overwrite {
             title = options.base_name.clone();
@@ -314,4 +330,31 @@ mod tests {
 
         assert_eq!(schema.get_body().to_string(), expected.to_string());
     }
+
+    #[test]
+    fn test_name_override_extension() {
+        let value = json!({
+            "type": "object",
+            "properties": {
+                "awkward": {
+                    "type": "object",
+                    "x-schematools-name": "Better",
+                    "properties": {}
+                }
+            }
+        });
+
+        let mut schema = Schema::from_json(value);
+
+        JsonSchemaNamer::options()
+            .with_overwrite(true)
+            .with_base_name(Some("Root".to_string()))
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(
+            schema.get_body().pointer("/properties/awkward/title"),
+            Some(&json!("Better"))
+        );
+    }
 }
@@ -3,7 +3,7 @@ use crate::process::name::jsonschema;
 use crate::{schema::Schema, scope::SchemaNamingStrategy, scope::SchemaScope, tools};
 use serde_json::Value;
 
-use super::endpoint;
+use super::{endpoint, word::Inflections};
 
 pub struct OpenapiNamer;
 
@@ -12,6 +12,8 @@ pub struct OpenapiNamerOptions {
     pub overwrite: bool,
     pub overwrite_ambigous: bool,
     pub naming_strategy: SchemaNamingStrategy,
+    pub extension_prefix: Option<String>,
+    pub inflections: Inflections,
 }
 
 impl OpenapiNamer {
@@ -21,6 +23,8 @@ impl OpenapiNamer {
             overwrite: false,
             overwrite_ambigous: false,
             naming_strategy: SchemaNamingStrategy::Default,
+            extension_prefix: None,
+            inflections: Inflections::default(),
         }
     }
 }
@@ -46,6 +50,16 @@ impl OpenapiNamerOptions {
         self
     }
 
+    pub fn with_extension_prefix(&mut self, value: Option<String>) -> &mut Self {
+        self.extension_prefix = value;
+        self
+    }
+
+    pub fn with_inflections(&mut self, value: Inflections) -> &mut Self {
+        self.inflections = value;
+        self
+    }
+
     pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
         let root = schema.get_body_mut();
 
@@ -66,6 +80,7 @@ impl OpenapiNamerOptions {
                             overwrite: self.overwrite,
                             overwrite_ambigous: self.overwrite_ambigous,
                             base_name: None,
+                            extension_prefix: self.extension_prefix.clone(),
                         },
                     )?;
 
@@ -91,6 +106,7 @@ impl OpenapiNamerOptions {
                             overwrite: self.overwrite,
                             overwrite_ambigous: self.overwrite_ambigous,
                             base_name: None,
+                            extension_prefix: self.extension_prefix.clone(),
                         },
                     )?;
 
@@ -116,6 +132,7 @@ impl OpenapiNamerOptions {
                             overwrite: self.overwrite,
                             overwrite_ambigous: self.overwrite_ambigous,
                             base_name: None,
+                            extension_prefix: self.extension_prefix.clone(),
                         },
                     )?;
 
@@ -132,12 +149,28 @@ impl OpenapiNamerOptions {
             "/path:paths/any:*/any:*",
             |node, parts, ctx| {
                 if let [endpoint, method] = parts {
+                    // path item level fields like parameters, summary or servers
+                    if !tools::HTTP_METHODS.contains(&method.as_str()) {
+                        return Ok(());
+                    }
+
                     let details = node.as_object_mut().unwrap();
 
+                    if let Some(Value::String(name)) =
+                        details.get(&tools::extension(&self.extension_prefix, "name"))
+                    {
+                        log::debug!("{}/operationId -> {} (forced by extension)", ctx, name);
+                        let name = name.clone();
+                        details.insert("operationId".to_string(), Value::String(name));
+
+                        return Ok(());
+                    }
+
                     match endpoint::Endpoint::new(method.to_string(), endpoint.to_string()) {
                         Ok(endpoint) => {
-                            let operation_id =
-                                endpoint.get_operation_id(self.resource_method_version);
+                            let operation_id = endpoint
+                                .with_inflections(&self.inflections)
+                                .get_operation_id(self.resource_method_version);
 
                             if !details.contains_key("operationId") || self.overwrite {
                                 log::debug!("{}/operationId -> {}", ctx, operation_id);
@@ -1,5 +1,6 @@
 #![allow(clippy::trivial_regex)]
 use regex::Regex;
+use serde_json::{Map, Value};
 
 // create replacement tuple
 macro_rules! crtpl {
@@ -66,6 +67,123 @@ lazy_static! {
     ];
 }
 
+// user defined irregular words, singular -> plural (ex. criterion -> criteria)
+#[derive(Debug, Clone, Default)]
+pub struct Inflections {
+    irregular: Vec<(String, String)>,
+}
+
+impl Inflections {
+    pub fn add(&mut self, singular: &str, plural: &str) -> &mut Self {
+        self.irregular
+            .push((singular.to_lowercase(), plural.to_lowercase()));
+        self
+    }
+
+    // accepts both `inflection.criterion=criteria` and `inflection={"criterion": "criteria"}`,
+    // singular word is always the key like in `--inflection` and inflections file
+    pub fn from_options<'a, I>(options: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a String, &'a Value)>,
+    {
+        let mut inflections = Self::default();
+
+        for (key, value) in options {
+            match (key.strip_prefix("inflection."), value) {
+                (Some(singular), Value::String(plural)) => {
+                    inflections.add(singular, plural);
+                }
+                (None, Value::Object(map)) if key == "inflection" => {
+                    inflections.merge(Self::from_map(map));
+                }
+                _ => {}
+            }
+        }
+
+        inflections
+    }
+
+    pub fn from_map(map: &Map<String, Value>) -> Self {
+        let mut inflections = Self::default();
+
+        for (singular, plural) in map {
+            if let Value::String(plural) = plural {
+                inflections.add(singular, plural);
+            }
+        }
+
+        inflections
+    }
+
+    pub fn merge(&mut self, other: Inflections) -> &mut Self {
+        self.irregular.extend(other.irregular);
+        self
+    }
+
+    pub fn pluralize(&self, word: String) -> String {
+        for (singular, plural) in &self.irregular {
+            if let Some(w) = replace_suffix(&word, plural, plural) {
+                return w;
+            }
+
+            if let Some(w) = replace_suffix(&word, singular, plural) {
+                return w;
+            }
+        }
+
+        pluralize(word)
+    }
+
+    pub fn singularize(&self, word: String) -> String {
+        for (singular, plural) in &self.irregular {
+            if let Some(w) = replace_suffix(&word, singular, singular) {
+                return w;
+            }
+
+            if let Some(w) = replace_suffix(&word, plural, singular) {
+                return w;
+            }
+        }
+
+        singularize(word)
+    }
+}
+
+// replaces last segment of word (camelCase, kebab-case or snake_case) keeping its case
+fn replace_suffix(word: &str, from: &str, to: &str) -> Option<String> {
+    let lower = word.to_lowercase();
+    if !lower.ends_with(from) {
+        return None;
+    }
+
+    let pos = word.len() - from.len();
+    if lower.len() != word.len() || !word.is_char_boundary(pos) {
+        return None;
+    }
+
+    let (prefix, segment) = word.split_at(pos);
+
+    let at_boundary = prefix.is_empty()
+        || prefix.ends_with('-')
+        || prefix.ends_with('_')
+        || segment.starts_with(char::is_uppercase);
+
+    if !at_boundary {
+        return None;
+    }
+
+    let replacement = if segment.starts_with(char::is_uppercase) {
+        let mut c = to.chars();
+        c.next()
+            .map(|f| f.to_uppercase().collect::<String>() + c.as_str())
+            .unwrap_or_default()
+    } else {
+        to.to_string()
+    };
+
+    Some(format!("{}{}", prefix, replacement))
+}
+
 pub fn singularize(word: String) -> String {
     for (re, replacement) in SINGULAR_LIST.iter() {
         if re.is_match(&word) {
@@ -117,4 +235,37 @@ mod tests {
     fn test_singularize(word: String, expected: String) {
         assert_eq!(singularize(word), expected);
     }
+
+    #[test_case( "criteria".to_string(), "criterion".to_string() )]
+    #[test_case( "userCriteria".to_string(), "userCriterion".to_string() )]
+    #[test_case( "Status".to_string(), "Status".to_string() )]
+    #[test_case( "users".to_string(), "user".to_string() )]
+    fn test_singularize_with_inflections(word: String, expected: String) {
+        let mut inflections = Inflections::default();
+        inflections
+            .add("criterion", "criteria")
+            .add("status", "status");
+
+        assert_eq!(inflections.singularize(word), expected);
+    }
+
+    #[test]
+    fn test_inflections_from_options() {
+        let mut options = std::collections::HashMap::new();
+        options.insert(
+            "inflection.criterion".to_string(),
+            Value::String("criteria".to_string()),
+        );
+
+        let inflections = Inflections::from_options(&options);
+
+        assert_eq!(
+            inflections.pluralize("criterion".to_string()),
+            "criteria".to_string()
+        );
+        assert_eq!(
+            inflections.singularize("criteria".to_string()),
+            "criterion".to_string()
+        );
+    }
 }
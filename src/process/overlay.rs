@@ -0,0 +1,270 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::merge_allof::merge_values;
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::scope::SchemaScope;
+use crate::tools;
+
+pub struct Overlayer;
+
+pub struct OverlayerOptions {
+    pub overlays: Vec<Schema>,
+    pub append_arrays: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Overlay {
+    actions: Vec<Action>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Action {
+    target: String,
+    update: Option<Value>,
+
+    #[serde(default)]
+    remove: bool,
+}
+
+impl Overlayer {
+    pub fn options() -> OverlayerOptions {
+        OverlayerOptions {
+            overlays: vec![],
+            append_arrays: false,
+        }
+    }
+}
+
+impl OverlayerOptions {
+    pub fn with_overlay(&mut self, overlay: Schema) -> &mut Self {
+        self.overlays.push(overlay);
+        self
+    }
+
+    pub fn with_append_arrays(&mut self, value: bool) -> &mut Self {
+        self.append_arrays = value;
+        self
+    }
+
+    pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
+        for overlay in &self.overlays {
+            let overlay: Overlay = serde_json::from_value(overlay.get_body().clone())
+                .map_err(Error::SerdeJsonError)?;
+
+            for action in overlay.actions {
+                apply(schema.get_body_mut(), &action, self.append_arrays)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn apply(root: &mut Value, action: &Action, append_arrays: bool) -> Result<(), Error> {
+    let mut parts = selector(&action.target)?;
+
+    if action.remove {
+        let last = parts
+            .pop()
+            .ok_or_else(|| Error::OverlayInvalidTarget(action.target.clone()))?;
+        let key = tools::pointer_unescape(
+            last.split_once(':')
+                .map(|(_, k)| k)
+                .unwrap_or_else(|| last.as_str()),
+        );
+
+        each(root, &parts, |node| {
+            if let Value::Object(map) = node {
+                if key == "*" {
+                    map.clear();
+                } else {
+                    map.remove(&key);
+                }
+            }
+        })
+    } else if let Some(update) = &action.update {
+        each(root, &parts, |node| {
+            if append_arrays {
+                merge_values(node, update.clone())
+            } else {
+                merge(node, update.clone())
+            }
+        })
+    } else {
+        Ok(())
+    }
+}
+
+// objects are merged recursively, anything else (including arrays) is replaced like in overlay spec
+fn merge(node: &mut Value, update: Value) {
+    match (node, update) {
+        (Value::Object(node), Value::Object(update)) => {
+            for (k, v) in update {
+                merge(node.entry(k).or_insert(Value::Null), v);
+            }
+        }
+        (node, update) => *node = update,
+    }
+}
+
+fn each<F>(root: &mut Value, parts: &[String], mut f: F) -> Result<(), Error>
+where
+    F: FnMut(&mut Value),
+{
+    if parts.is_empty() {
+        f(root);
+        return Ok(());
+    }
+
+    tools::each_node_mut(
+        root,
+        &mut SchemaScope::default(),
+        &parts.join("/"),
+        |node, _parts, scope| {
+            log::trace!("{}: overlay", scope);
+
+            f(node);
+            Ok(())
+        },
+    )
+}
+
+// simple jsonpath ($.paths['/users'].*) or each_node selector (/path:paths/any:*)
+fn selector(target: &str) -> Result<Vec<String>, Error> {
+    let rest = match target.strip_prefix('$') {
+        Some(rest) => rest,
+        None => {
+            return Ok(target
+                .split('/')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect())
+        }
+    };
+
+    let invalid = || Error::OverlayInvalidTarget(target.to_string());
+    let mut parts = vec![];
+    let mut chars = rest.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let name = match c {
+            '.' => {
+                let mut name = String::new();
+                while let Some(n) = chars.peek() {
+                    if *n == '.' || *n == '[' {
+                        break;
+                    }
+                    name.push(*n);
+                    chars.next();
+                }
+                name
+            }
+            '[' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(n) => name.push(n),
+                        None => return Err(invalid()),
+                    }
+                }
+                name.trim()
+                    .trim_matches(|c| c == '\'' || c == '"')
+                    .to_string()
+            }
+            _ => return Err(invalid()),
+        };
+
+        if name.is_empty() {
+            return Err(invalid());
+        }
+
+        parts.push(format!("any:{}", tools::pointer_escape(&name)));
+    }
+
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_overlays_applied_in_order() {
+        let mut schema = Schema::from_json(json!({
+            "info": { "title": "Users", "version": "1.0.0" },
+            "servers": [{ "url": "https://api.example.com" }],
+            "paths": {
+                "/users": { "get": { "summary": "List" } },
+                "/debug": { "get": { "summary": "Debug" } }
+            }
+        }));
+
+        let staging = Schema::from_json(json!({
+            "overlay": "1.0.0",
+            "actions": [
+                { "target": "$.info", "update": { "title": "Users (staging)" } },
+                { "target": "$.paths['/users'].*", "update": { "x-internal": true } },
+                { "target": "$", "update": { "servers": [{ "url": "https://staging.example.com" }] } }
+            ]
+        }));
+
+        let dev = Schema::from_json(json!({
+            "overlay": "1.0.0",
+            "actions": [
+                { "target": "/any:paths/any:~1debug", "remove": true },
+                { "target": "$.info", "update": { "title": "Users (dev)" } }
+            ]
+        }));
+
+        Overlayer::options()
+            .with_overlay(staging)
+            .with_overlay(dev)
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(
+            schema.get_body(),
+            &json!({
+                "info": { "title": "Users (dev)", "version": "1.0.0" },
+                "servers": [{ "url": "https://staging.example.com" }],
+                "paths": {
+                    "/users": { "get": { "summary": "List", "x-internal": true } }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_overlay_appends_arrays() {
+        let mut schema = Schema::from_json(json!({
+            "servers": [{ "url": "https://api.example.com" }]
+        }));
+
+        let staging = Schema::from_json(json!({
+            "overlay": "1.0.0",
+            "actions": [
+                { "target": "$", "update": { "servers": [{ "url": "https://staging.example.com" }] } }
+            ]
+        }));
+
+        Overlayer::options()
+            .with_overlay(staging)
+            .with_append_arrays(true)
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(
+            schema.get_body(),
+            &json!({
+                "servers": [
+                    { "url": "https://api.example.com" },
+                    { "url": "https://staging.example.com" }
+                ]
+            })
+        );
+    }
+}
@@ -1,10 +1,12 @@
 use crate::schema::Schema;
+use crate::scope::SchemaScope;
+use crate::tools;
 use crate::{error::Error, schema::path_to_url};
 
 use clap::Clap;
-use json_patch::{diff, from_value, patch};
-use serde::Serialize;
-use serde_json::Value;
+use json_patch::{from_value, patch};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
 
 static OPERATION: &[&str] = &["add", "remove", "replace"];
 
@@ -50,8 +52,7 @@ pub fn execute(schema: &mut Schema, action: &Action) -> Result<(), Error> {
             let original = Schema::load_url(path_to_url(c.original.clone())?)?;
             let body = schema.get_body_mut();
 
-            let result = serde_json::to_value(diff(original.get_body(), body))
-                .map_err(Error::SerdeJsonError)?;
+            let result = diff(original.get_body(), body);
 
             body.clone_from(&result);
 
@@ -59,14 +60,369 @@ pub fn execute(schema: &mut Schema, action: &Action) -> Result<(), Error> {
         }
         Action::Apply(c) => {
             let patch_file = Schema::load_url(path_to_url(c.patch.clone())?)?;
-            let p = from_value(patch_file.get_body().clone()).map_err(Error::SerdeJsonError)?;
 
-            patch(schema.get_body_mut(), &p).map_err(Error::JsonPatchError)
+            apply(schema.get_body_mut(), patch_file.get_body())
         }
         Action::Inline(i) => {
-            let p = from_value(serde_json::json!([i])).map_err(Error::SerdeJsonError)?;
+            let p = from_value(json!([i])).map_err(Error::SerdeJsonError)?;
 
             patch(schema.get_body_mut(), &p).map_err(Error::JsonPatchError)
         }
     }
 }
+
+// operation applied to every node matched by each_node selector, ex. /path:paths/any:*/any:*
+#[derive(Debug, Deserialize)]
+struct SelectorOperation {
+    op: String,
+    select: String,
+    path: String,
+    value: Option<Value>,
+
+    #[serde(default)]
+    when: Map<String, Value>,
+}
+
+impl SelectorOperation {
+    fn matches(&self, node: &Value) -> bool {
+        self.when.iter().all(|(key, expected)| {
+            let actual = if key.starts_with('/') {
+                node.pointer(key)
+            } else {
+                node.get(key)
+            };
+
+            match actual {
+                Some(value) if value == expected => true,
+                Some(Value::Array(list)) => list.contains(expected),
+                _ => false,
+            }
+        })
+    }
+
+    fn apply(&self, root: &mut Value) -> Result<(), Error> {
+        let (parent, key) = match self.path.rfind('/') {
+            Some(pos) => (
+                &self.path[..pos],
+                tools::pointer_unescape(&self.path[pos + 1..]),
+            ),
+            None => return Err(Error::PatchInvalidOperation(self.path.clone())),
+        };
+
+        tools::each_node_mut(
+            root,
+            &mut SchemaScope::default(),
+            &self.select,
+            |node, _parts, scope| {
+                if !self.matches(node) {
+                    return Ok(());
+                }
+
+                log::trace!("{}: {} {}", scope, self.op, self.path);
+
+                let missing = || {
+                    Error::PatchInvalidOperation(format!("{} {}: {}", self.op, self.path, scope))
+                };
+                let value = || self.value.clone().unwrap_or(Value::Null);
+
+                // same semantics as rfc 6902 operation applied to the matched node
+                match (self.op.as_str(), node.pointer_mut(parent)) {
+                    ("add", Some(Value::Object(map))) => {
+                        map.insert(key.clone(), value());
+                    }
+                    ("add", Some(Value::Array(list))) if key == "-" => list.push(value()),
+                    ("add", Some(Value::Array(list))) => match key.parse::<usize>() {
+                        Ok(index) if index <= list.len() => list.insert(index, value()),
+                        _ => return Err(missing()),
+                    },
+                    ("replace", Some(Value::Object(map))) => {
+                        *map.get_mut(&key).ok_or_else(missing)? = value();
+                    }
+                    ("replace", Some(Value::Array(list))) => {
+                        *key.parse::<usize>()
+                            .ok()
+                            .and_then(|i| list.get_mut(i))
+                            .ok_or_else(missing)? = value();
+                    }
+                    ("remove", Some(Value::Object(map))) => {
+                        map.remove(&key).ok_or_else(missing)?;
+                    }
+                    ("remove", Some(Value::Array(list))) => match key.parse::<usize>() {
+                        Ok(index) if index < list.len() => {
+                            list.remove(index);
+                        }
+                        _ => return Err(missing()),
+                    },
+                    ("add", _) | ("replace", _) | ("remove", _) => return Err(missing()),
+                    (op, _) => return Err(Error::PatchInvalidOperation(op.to_string())),
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+// rfc 6902 operations extended with bulk operations containing select keyword
+pub fn apply(root: &mut Value, operations: &Value) -> Result<(), Error> {
+    let operations = operations
+        .as_array()
+        .ok_or_else(|| Error::PatchInvalidOperation("patch has to be an array".to_string()))?;
+
+    for operation in operations {
+        if operation.get("select").is_some() {
+            let selector: SelectorOperation =
+                serde_json::from_value(operation.clone()).map_err(Error::SerdeJsonError)?;
+
+            selector.apply(root)?;
+        } else {
+            let p = from_value(json!([operation])).map_err(Error::SerdeJsonError)?;
+
+            patch(root, &p).map_err(Error::JsonPatchError)?;
+        }
+    }
+
+    Ok(())
+}
+
+enum Edit<'a> {
+    Keep,
+    Remove,
+    Insert(&'a Value),
+    Change(&'a Value, &'a Value),
+}
+
+// minimal json patch, arrays are aligned so insertions do not replace following elements
+pub fn diff(original: &Value, modified: &Value) -> Value {
+    let mut operations = vec![];
+
+    diff_node(original, modified, &mut String::new(), &mut operations);
+
+    Value::Array(operations)
+}
+
+fn diff_node(
+    original: &Value,
+    modified: &Value,
+    pointer: &mut String,
+    operations: &mut Vec<Value>,
+) {
+    if original == modified {
+        return;
+    }
+
+    match (original, modified) {
+        (Value::Object(from), Value::Object(to)) => {
+            for key in from.keys().filter(|k| !to.contains_key(*k)) {
+                operations.push(json!({"op": "remove", "path": child(pointer, key)}));
+            }
+
+            for (key, value) in to {
+                match from.get(key) {
+                    Some(previous) => {
+                        let len = pointer.len();
+                        pointer.push_str(&child("", key));
+                        diff_node(previous, value, pointer, operations);
+                        pointer.truncate(len);
+                    }
+                    None => operations
+                        .push(json!({"op": "add", "path": child(pointer, key), "value": value})),
+                }
+            }
+        }
+        (Value::Array(from), Value::Array(to)) => {
+            let mut index = 0;
+
+            for edit in align(from, to) {
+                match edit {
+                    Edit::Keep => index += 1,
+                    Edit::Remove => operations
+                        .push(json!({"op": "remove", "path": format!("{}/{}", pointer, index)})),
+                    Edit::Insert(value) => {
+                        let path = format!("{}/{}", pointer, index);
+
+                        operations.push(json!({"op": "add", "path": path, "value": value}));
+                        index += 1;
+                    }
+                    Edit::Change(previous, value) => {
+                        let len = pointer.len();
+                        pointer.push_str(&format!("/{}", index));
+                        diff_node(previous, value, pointer, operations);
+                        pointer.truncate(len);
+                        index += 1;
+                    }
+                }
+            }
+        }
+        _ => operations.push(json!({"op": "replace", "path": pointer.clone(), "value": modified})),
+    }
+}
+
+fn child(pointer: &str, key: &str) -> String {
+    format!("{}/{}", pointer, tools::pointer_escape(key))
+}
+
+// longest common subsequence, adjacent removals and insertions are paired as changes
+fn align<'a>(from: &'a [Value], to: &'a [Value]) -> Vec<Edit<'a>> {
+    let (n, m) = (from.len(), to.len());
+    let mut lengths = vec![vec![0usize; m + 1]; n + 1];
+
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if from[i] == to[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = vec![];
+    let mut removed: Vec<&Value> = vec![];
+    let mut inserted: Vec<&Value> = vec![];
+    let (mut i, mut j) = (0, 0);
+
+    while i < n || j < m {
+        if i < n && j < m && from[i] == to[j] {
+            pair(&mut removed, &mut inserted, &mut edits);
+            edits.push(Edit::Keep);
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lengths[i][j + 1] >= lengths[i + 1][j]) {
+            inserted.push(&to[j]);
+            j += 1;
+        } else {
+            removed.push(&from[i]);
+            i += 1;
+        }
+    }
+    pair(&mut removed, &mut inserted, &mut edits);
+
+    edits
+}
+
+fn pair<'a>(
+    removed: &mut Vec<&'a Value>,
+    inserted: &mut Vec<&'a Value>,
+    edits: &mut Vec<Edit<'a>>,
+) {
+    let paired = removed.len().min(inserted.len());
+
+    for (previous, value) in removed.iter().zip(inserted.iter()) {
+        edits.push(Edit::Change(previous, value));
+    }
+    edits.extend(removed.iter().skip(paired).map(|_| Edit::Remove));
+    edits.extend(inserted.iter().skip(paired).map(|v| Edit::Insert(v)));
+
+    removed.clear();
+    inserted.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn test_apply_selector_operations() {
+        let mut schema = json!({
+            "paths": {
+                "/users": {
+                    "get": { "tags": ["admin"] },
+                    "post": { "tags": ["users"] }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": { "properties": { "id": {}, "debug": {} } },
+                    "Group": { "properties": { "id": {} } }
+                }
+            }
+        });
+
+        let operations = json!([
+            {
+                "op": "add",
+                "select": "/path:paths/any:*/any:*",
+                "when": { "tags": "admin" },
+                "path": "/x-internal",
+                "value": true
+            },
+            {
+                "op": "remove",
+                "select": "/any:components/any:schemas/definition:*",
+                "when": { "/properties/debug": {} },
+                "path": "/properties/debug"
+            },
+            {
+                "op": "remove",
+                "select": "/path:paths/any:*/any:*",
+                "path": "/tags/0"
+            },
+            { "op": "add", "path": "/info", "value": { "title": "Users" } }
+        ]);
+
+        apply(&mut schema, &operations).unwrap();
+
+        assert_eq!(schema["paths"]["/users"]["get"]["x-internal"], json!(true));
+        assert_eq!(schema["paths"]["/users"]["post"].get("x-internal"), None);
+        assert_eq!(
+            schema["components"]["schemas"]["User"]["properties"],
+            json!({ "id": {} })
+        );
+        assert_eq!(schema["info"]["title"], json!("Users"));
+        assert_eq!(schema["paths"]["/users"]["post"]["tags"], json!([]));
+
+        // missing key fails like rfc 6902 operation
+        for operation in &[
+            json!({ "op": "remove", "select": "/path:paths/any:*/any:*", "path": "/summary" }),
+            json!({ "op": "replace", "select": "/path:paths/any:*/any:*", "path": "/tags/5", "value": "x" }),
+            json!({ "op": "add", "select": "/path:paths/any:*/any:*", "path": "/missing/x", "value": 1 }),
+        ] {
+            assert_eq!(
+                true,
+                matches!(
+                    apply(&mut schema, &json!([operation])),
+                    Err(Error::PatchInvalidOperation(_))
+                )
+            );
+        }
+    }
+
+    #[test_case(
+        json!({"a": 1, "b": [1, 2]}),
+        json!({"a": 1, "b": [0, 1, 2], "c": true}),
+        json!([
+            {"op": "add", "path": "/b/0", "value": 0},
+            {"op": "add", "path": "/c", "value": true}
+        ]);
+        "insert in front of array"
+    )]
+    #[test_case(
+        json!({"paths": {"/a": {"get": {"summary": "old", "tags": ["x"]}}}}),
+        json!({"paths": {"/a": {"get": {"summary": "new"}}}}),
+        json!([
+            {"op": "remove", "path": "/paths/~1a/get/tags"},
+            {"op": "replace", "path": "/paths/~1a/get/summary", "value": "new"}
+        ]);
+        "nested changes"
+    )]
+    #[test_case(
+        json!([{"name": "a"}, {"name": "b"}, {"name": "c"}]),
+        json!([{"name": "a"}, {"name": "B"}]),
+        json!([
+            {"op": "replace", "path": "/1/name", "value": "B"},
+            {"op": "remove", "path": "/2"}
+        ]);
+        "changed and removed elements"
+    )]
+    fn test_diff(original: Value, modified: Value, expected: Value) {
+        let result = diff(&original, &modified);
+
+        assert_eq!(result, expected);
+
+        let mut patched = original;
+        patch(&mut patched, &from_value(result).unwrap()).unwrap();
+        assert_eq!(patched, modified);
+    }
+}
@@ -0,0 +1,480 @@
+use std::collections::HashSet;
+
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::analyze::graph::Graph;
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::tools::{pointer_unescape, SchemaWalk, HTTP_METHODS};
+
+#[derive(Debug, Deserialize, Default)]
+struct Config {
+    // operations with any of tags are removed
+    #[serde(default)]
+    tags: Vec<String>,
+
+    // operations, properties and components with any of extensions set to true are removed
+    #[serde(default)]
+    flags: Vec<String>,
+
+    // extensions removed everywhere, trailing * matches prefix
+    #[serde(default)]
+    extensions: Vec<String>,
+
+    // string examples matching any of patterns are replaced
+    #[serde(default)]
+    secrets: Vec<String>,
+
+    #[serde(default)]
+    replacement: String,
+
+    #[serde(default)]
+    servers: Vec<ServerRule>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServerRule {
+    // prefix of server url
+    from: String,
+
+    // new prefix, server is removed when missing
+    to: Option<String>,
+}
+
+pub struct Redactor;
+
+pub struct RedactorOptions {
+    config: Config,
+    secrets: Vec<Regex>,
+}
+
+impl Redactor {
+    pub fn options(config: &Schema) -> Result<RedactorOptions, Error> {
+        let config: Config =
+            serde_json::from_value(config.get_body().clone()).map_err(Error::SerdeJsonError)?;
+
+        let secrets = config
+            .secrets
+            .iter()
+            .map(|s| Regex::new(s).map_err(|_| Error::RedactInvalidPattern(s.clone())))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(RedactorOptions { config, secrets })
+    }
+}
+
+impl RedactorOptions {
+    pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
+        let reachable_before = reachable(schema.get_body());
+
+        let root = schema
+            .get_body_mut()
+            .as_object_mut()
+            .ok_or(Error::InvalidOpenapiSchemaError)?;
+
+        if let Some(Value::Object(paths)) = root.get_mut("paths") {
+            let mut emptied = vec![];
+
+            for (path, item) in paths.iter_mut() {
+                if let Value::Object(item) = item {
+                    item.retain(|key, operation| {
+                        let internal =
+                            HTTP_METHODS.contains(&key.as_str()) && self.is_internal(operation);
+                        if internal {
+                            log::info!("{} {}: removed", key.to_uppercase(), path);
+                        }
+                        !internal
+                    });
+
+                    if !HTTP_METHODS.iter().any(|m| item.contains_key(*m))
+                        && !item.contains_key("$ref")
+                    {
+                        emptied.push(path.clone());
+                    }
+                }
+            }
+
+            for path in emptied {
+                paths.remove(&path);
+            }
+        }
+
+        if let Some(Value::Array(tags)) = root.get_mut("tags") {
+            tags.retain(|t| match t.get("name").and_then(|n| n.as_str()) {
+                Some(name) => !self.config.tags.iter().any(|r| r == name),
+                None => true,
+            });
+        }
+
+        let mut root = Value::Object(std::mem::take(root));
+        self.redact(&mut root, SchemaWalk::default());
+        prune(&mut root, &reachable_before);
+        *schema.get_body_mut() = root;
+
+        let mut references = vec![];
+        collect_references(schema.get_body(), SchemaWalk::default(), &mut references);
+
+        match references.into_iter().find(|r| {
+            r.strip_prefix('#')
+                .map(|p| schema.get_body().pointer(p).is_none())
+                .unwrap_or(false)
+        }) {
+            Some(reference) => Err(Error::RedactDanglingReference(reference)),
+            None => Ok(()),
+        }
+    }
+
+    fn is_internal(&self, operation: &Value) -> bool {
+        let tagged = match operation.get("tags") {
+            Some(Value::Array(tags)) => tags
+                .iter()
+                .filter_map(|t| t.as_str())
+                .any(|t| self.config.tags.iter().any(|r| r == t)),
+            _ => false,
+        };
+
+        tagged || self.is_flagged(operation)
+    }
+
+    fn is_flagged(&self, node: &Value) -> bool {
+        self.config
+            .flags
+            .iter()
+            .any(|f| node.get(f) == Some(&Value::Bool(true)))
+    }
+
+    fn is_stripped(&self, key: &str) -> bool {
+        self.config
+            .extensions
+            .iter()
+            .any(|e| match e.strip_suffix('*') {
+                Some(prefix) => key.starts_with(prefix),
+                None => key == e,
+            })
+    }
+
+    fn redact(&self, node: &mut Value, walk: SchemaWalk) {
+        match node {
+            Value::Object(map) => {
+                if walk.has_names() {
+                    for (key, value) in map.iter_mut() {
+                        self.redact(value, walk.enter(key));
+                    }
+                    return;
+                }
+
+                map.retain(|key, _| !self.is_stripped(key));
+
+                let mut removed = vec![];
+                for (key, value) in map.iter_mut() {
+                    match key.as_str() {
+                        "example" | "examples" => self.blank(value),
+                        _ if walk.is_instance(key) => {}
+                        "servers" => self.rewrite(value),
+                        "properties" => {
+                            removed = self.remove_flagged(value);
+                            self.redact(value, walk.enter(key));
+                        }
+                        // flagged components, parameters and responses
+                        "schemas" | "parameters" | "responses" | "requestBodies" => {
+                            self.remove_flagged(value);
+                            self.redact(value, walk.enter(key));
+                        }
+                        _ => self.redact(value, walk.enter(key)),
+                    }
+                }
+
+                if let Some(Value::Array(required)) = map.get_mut("required") {
+                    required.retain(|r| !removed.iter().any(|p| r == p));
+                }
+            }
+            Value::Array(list) => {
+                for value in list {
+                    self.redact(value, SchemaWalk::default());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // returns names of removed entries
+    fn remove_flagged(&self, node: &mut Value) -> Vec<String> {
+        match node {
+            Value::Object(map) => {
+                let removed = map
+                    .iter()
+                    .filter(|(_, v)| self.is_flagged(v))
+                    .map(|(k, _)| k.clone())
+                    .collect::<Vec<_>>();
+
+                for key in &removed {
+                    map.remove(key);
+                }
+
+                removed
+            }
+            Value::Array(list) => {
+                list.retain(|v| !self.is_flagged(v));
+                vec![]
+            }
+            _ => vec![],
+        }
+    }
+
+    fn blank(&self, node: &mut Value) {
+        match node {
+            Value::String(text) if self.secrets.iter().any(|s| s.is_match(text)) => {
+                *text = self.config.replacement.clone();
+            }
+            Value::Object(map) => {
+                map.retain(|key, _| !self.is_stripped(key));
+                for value in map.values_mut() {
+                    self.blank(value);
+                }
+            }
+            Value::Array(list) => {
+                for value in list {
+                    self.blank(value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn rewrite(&self, node: &mut Value) {
+        if let Value::Array(servers) = node {
+            servers.retain(|server| {
+                let url = server.get("url").and_then(|u| u.as_str()).unwrap_or("");
+                !self
+                    .config
+                    .servers
+                    .iter()
+                    .any(|r| r.to.is_none() && url.starts_with(&r.from))
+            });
+
+            for server in servers.iter_mut() {
+                if let Some(Value::String(url)) = server.get_mut("url") {
+                    if let Some(rule) = self
+                        .config
+                        .servers
+                        .iter()
+                        .find(|r| url.starts_with(&r.from))
+                    {
+                        if let Some(to) = &rule.to {
+                            *url = format!("{}{}", to, &url[rule.from.len()..]);
+                        }
+                    }
+                }
+
+                if let Value::Object(map) = server {
+                    map.retain(|key, _| !self.is_stripped(key));
+                }
+            }
+        }
+    }
+}
+
+// components used by operations, directly or transitively
+fn reachable(root: &Value) -> HashSet<String> {
+    let graph = Graph::new(root);
+
+    let mut reachable: HashSet<&str> = graph
+        .nodes
+        .iter()
+        .filter(|n| n.kind == "operation")
+        .map(|n| n.id.as_str())
+        .collect();
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for edge in &graph.edges {
+            if reachable.contains(edge.from.as_str()) && reachable.insert(edge.to.as_str()) {
+                changed = true;
+            }
+        }
+    }
+
+    reachable.into_iter().map(String::from).collect()
+}
+
+// components used only by redacted elements are removed, components unused before are kept
+fn prune(root: &mut Value, reachable_before: &HashSet<String>) {
+    let reachable_after = reachable(root);
+
+    for id in reachable_before.difference(&reachable_after) {
+        let pointer = id.trim_start_matches('#');
+
+        if let Some((parent, name)) = pointer
+            .strip_prefix("/components/")
+            .and_then(|_| pointer.rsplit_once('/'))
+        {
+            if let Some(Value::Object(map)) = root.pointer_mut(parent) {
+                if map.remove(&pointer_unescape(name)).is_some() {
+                    log::info!("{}: removed, not used anymore", pointer);
+                }
+            }
+        }
+    }
+}
+
+fn collect_references(node: &Value, walk: SchemaWalk, references: &mut Vec<String>) {
+    match node {
+        Value::Object(map) => {
+            if let (false, Some(Value::String(reference))) = (walk.has_names(), map.get("$ref")) {
+                references.push(reference.clone());
+            }
+
+            for (key, value) in map {
+                if !walk.is_instance(key) {
+                    collect_references(value, walk.enter(key), references);
+                }
+            }
+        }
+        Value::Array(list) => {
+            for value in list {
+                collect_references(value, SchemaWalk::default(), references);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_internal_spec() {
+        let mut schema = Schema::from_json(json!({
+            "servers": [
+                { "url": "https://api.internal.example.com/v1" },
+                { "url": "http://localhost:8080" }
+            ],
+            "tags": [{ "name": "users" }, { "name": "admin" }],
+            "paths": {
+                "/users": {
+                    "get": {
+                        "tags": ["users"],
+                        "x-owner": "team-a",
+                        "parameters": [
+                            { "name": "debug", "in": "query", "x-internal": true },
+                            { "name": "page", "in": "query" }
+                        ]
+                    },
+                    "delete": { "tags": ["admin"] }
+                },
+                "/debug": {
+                    "get": {
+                        "x-internal": true,
+                        "responses": { "200": { "$ref": "#/components/responses/Debug" } }
+                    }
+                }
+            },
+            "components": {
+                "responses": {
+                    "Debug": {
+                        "content": {
+                            "application/json": {
+                                "schema": { "$ref": "#/components/schemas/Trace" }
+                            }
+                        }
+                    }
+                },
+                "schemas": {
+                    "Trace": { "type": "object" },
+                    "Spare": { "type": "object" },
+                    "User": {
+                        "type": "object",
+                        "required": ["name", "score"],
+                        "properties": {
+                            "name": { "type": "string" },
+                            "score": { "type": "number", "x-internal": true }
+                        },
+                        "example": { "name": "John", "token": "Bearer abc.def" }
+                    },
+                    "Audit": { "type": "object", "x-internal": true }
+                }
+            }
+        }));
+
+        let config = Schema::from_json(json!({
+            "tags": ["admin"],
+            "flags": ["x-internal"],
+            "extensions": ["x-owner*"],
+            "secrets": ["^Bearer "],
+            "replacement": "<redacted>",
+            "servers": [
+                { "from": "https://api.internal.example.com", "to": "https://api.example.com" },
+                { "from": "http://localhost" }
+            ]
+        }));
+
+        Redactor::options(&config)
+            .unwrap()
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(
+            schema.get_body(),
+            &json!({
+                "servers": [{ "url": "https://api.example.com/v1" }],
+                "tags": [{ "name": "users" }],
+                "paths": {
+                    "/users": {
+                        "get": {
+                            "tags": ["users"],
+                            "parameters": [{ "name": "page", "in": "query" }]
+                        }
+                    }
+                },
+                "components": {
+                    "responses": {},
+                    "schemas": {
+                        "Spare": { "type": "object" },
+                        "User": {
+                            "type": "object",
+                            "required": ["name"],
+                            "properties": { "name": { "type": "string" } },
+                            "example": { "name": "John", "token": "<redacted>" }
+                        }
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_redact_fails_on_dangling_reference() {
+        let mut schema = Schema::from_json(json!({
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "User": {
+                        "properties": {
+                            "audit": { "$ref": "#/components/schemas/Audit" },
+                            "default": { "type": "string", "default": { "x-internal": true } }
+                        }
+                    },
+                    "Audit": { "type": "object", "x-internal": true }
+                }
+            }
+        }));
+
+        let config = Schema::from_json(json!({ "flags": ["x-internal"] }));
+
+        let result = Redactor::options(&config).unwrap().process(&mut schema);
+
+        assert_eq!(
+            true,
+            matches!(result, Err(Error::RedactDanglingReference(r)) if r == "#/components/schemas/Audit")
+        );
+        assert_eq!(
+            schema.get_body()["components"]["schemas"]["User"]["properties"]["default"]["default"],
+            json!({ "x-internal": true })
+        );
+    }
+}
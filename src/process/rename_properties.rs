@@ -0,0 +1,441 @@
+use inflector::Inflector;
+use serde_json::{Map, Value};
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::process::report::ProcessReport;
+use crate::schema::Schema;
+use crate::tools::{pointer_escape, SchemaWalk};
+
+pub static ORIGINAL_NAME_EXTENSION: &str = "x-original-name";
+
+pub static PROPERTY_CASES: &[&str] = &[
+    "snake_case",
+    "camelCase",
+    "PascalCase",
+    "kebab-case",
+    "SCREAMING_SNAKE_CASE",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyCase {
+    Snake,
+    Camel,
+    Pascal,
+    Kebab,
+    ScreamingSnake,
+}
+
+impl FromStr for PropertyCase {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<PropertyCase, Self::Err> {
+        match input {
+            "snake_case" => Ok(Self::Snake),
+            "camelCase" => Ok(Self::Camel),
+            "PascalCase" => Ok(Self::Pascal),
+            "kebab-case" => Ok(Self::Kebab),
+            "SCREAMING_SNAKE_CASE" => Ok(Self::ScreamingSnake),
+            s => Err(Error::RenamePropertiesInvalidCase(s.to_string())),
+        }
+    }
+}
+
+impl PropertyCase {
+    pub fn convert(&self, name: &str) -> String {
+        match self {
+            Self::Snake => name.to_snake_case(),
+            Self::Camel => name.to_camel_case(),
+            Self::Pascal => name.to_pascal_case(),
+            Self::Kebab => name.to_kebab_case(),
+            Self::ScreamingSnake => name.to_screaming_snake_case(),
+        }
+    }
+}
+
+pub struct PropertyRenamer;
+
+pub struct PropertyRenamerOptions {
+    pub case: PropertyCase,
+    pub record_original: bool,
+}
+
+impl PropertyRenamer {
+    pub fn options(case: PropertyCase) -> PropertyRenamerOptions {
+        PropertyRenamerOptions {
+            case,
+            record_original: false,
+        }
+    }
+}
+
+impl PropertyRenamerOptions {
+    // renamed properties keep original name under x-original-name
+    pub fn with_record_original(&mut self, value: bool) -> &mut Self {
+        self.record_original = value;
+        self
+    }
+
+    // examples are renamed following schema they illustrate, so keys of maps are kept intact
+    pub fn process(&self, schema: &mut Schema) -> Result<ProcessReport, Error> {
+        let mut report = ProcessReport::new("rename-properties");
+        let root = schema.get_body().clone();
+
+        self.walk(
+            schema.get_body_mut(),
+            &root,
+            String::new(),
+            SchemaWalk::default(),
+            &mut report,
+        )?;
+
+        Ok(report)
+    }
+
+    fn walk(
+        &self,
+        node: &mut Value,
+        root: &Value,
+        pointer: String,
+        walk: SchemaWalk,
+        report: &mut ProcessReport,
+    ) -> Result<(), Error> {
+        let map = match node {
+            Value::Object(map) => map,
+            Value::Array(list) => {
+                for (i, value) in list.iter_mut().enumerate() {
+                    let child = format!("{}/{}", pointer, i);
+                    self.walk(value, root, child, SchemaWalk::default(), report)?;
+                }
+                return Ok(());
+            }
+            _ => return Ok(()),
+        };
+
+        // keys of maps of names are never taken for keywords
+        if !walk.has_names() {
+            // schema is renamed after its examples, they are matched against original names
+            self.rename_examples(map, root, report);
+            self.rename_properties(map, &pointer, report)?;
+        }
+
+        for (key, value) in map.iter_mut() {
+            if walk.is_instance(key) || (!walk.has_names() && key.starts_with("x-")) {
+                continue;
+            }
+
+            let child = format!("{}/{}", pointer, pointer_escape(key));
+            self.walk(value, root, child, walk.enter(key), report)?;
+        }
+
+        Ok(())
+    }
+
+    fn rename_properties(
+        &self,
+        map: &mut Map<String, Value>,
+        pointer: &str,
+        report: &mut ProcessReport,
+    ) -> Result<(), Error> {
+        let properties = match map.get_mut("properties") {
+            Some(Value::Object(properties)) => properties,
+            _ => return Ok(()),
+        };
+
+        let mut renamed = Map::new();
+        let mut names = vec![];
+
+        for (name, mut value) in std::mem::take(properties) {
+            let target = self.case.convert(&name);
+
+            if renamed.contains_key(&target) {
+                return Err(Error::RenamePropertiesCollision(format!(
+                    "{}/properties: {} and another property become {}",
+                    pointer, name, target
+                )));
+            }
+
+            if target != name {
+                log::info!("{}/properties/{}: renamed to {}", pointer, name, target);
+                report.count("properties renamed");
+
+                if self.record_original {
+                    if let Value::Object(property) = &mut value {
+                        property
+                            .entry(ORIGINAL_NAME_EXTENSION)
+                            .or_insert_with(|| Value::String(name.clone()));
+                    }
+                }
+
+                names.push((name, target.clone()));
+            }
+
+            renamed.insert(target, value);
+        }
+
+        *properties = renamed;
+
+        if names.is_empty() {
+            return Ok(());
+        }
+
+        let rename = |value: &mut Value| {
+            if let Value::String(s) = value {
+                if let Some((_, target)) = names.iter().find(|(name, _)| name == s) {
+                    *s = target.clone();
+                }
+            }
+        };
+
+        if let Some(Value::Array(required)) = map.get_mut("required") {
+            required.iter_mut().for_each(rename);
+        }
+
+        if let Some(property) = map
+            .get_mut("discriminator")
+            .and_then(|d| d.get_mut("propertyName"))
+        {
+            rename(property);
+        }
+
+        Ok(())
+    }
+
+    fn rename_examples(
+        &self,
+        map: &mut Map<String, Value>,
+        root: &Value,
+        report: &mut ProcessReport,
+    ) {
+        if !map.contains_key("example") && !map.contains_key("examples") {
+            return;
+        }
+
+        // media types and parameters describe their examples with schema, schemas themselves
+        let schema = match map.get("schema") {
+            Some(schema) => schema.clone(),
+            None => Value::Object(map.clone()),
+        };
+
+        if let Some(example) = map.get_mut("example") {
+            self.rename_example(example, &schema, root, report);
+        }
+
+        match map.get_mut("examples") {
+            Some(Value::Array(list)) => {
+                for example in list.iter_mut() {
+                    self.rename_example(example, &schema, root, report);
+                }
+            }
+            Some(Value::Object(examples)) => {
+                for example in examples.values_mut() {
+                    if let Some(value) = example.get_mut("value") {
+                        self.rename_example(value, &schema, root, report);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn rename_example(
+        &self,
+        value: &mut Value,
+        schema: &Value,
+        root: &Value,
+        report: &mut ProcessReport,
+    ) {
+        let schema = resolve(schema, root);
+
+        match value {
+            Value::Object(map) => {
+                let mut renamed = Map::new();
+
+                for (key, mut item) in std::mem::take(map) {
+                    match property(schema, key.as_str(), root) {
+                        Some(property) => {
+                            self.rename_example(&mut item, property, root, report);
+
+                            let target = self.case.convert(&key);
+                            if target != key {
+                                report.count("example keys renamed");
+                            }
+                            renamed.insert(target, item);
+                        }
+                        None => {
+                            if let Some(additional) = schema.get("additionalProperties") {
+                                self.rename_example(&mut item, additional, root, report);
+                            }
+                            renamed.insert(key, item);
+                        }
+                    }
+                }
+
+                *map = renamed;
+            }
+            Value::Array(list) => {
+                if let Some(items) = schema.get("items") {
+                    for item in list.iter_mut() {
+                        self.rename_example(item, items, root, report);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// property schema declared directly or by any of composed schemas
+fn property<'a>(schema: &'a Value, name: &str, root: &'a Value) -> Option<&'a Value> {
+    let schema = resolve(schema, root);
+
+    if let Some(property) = schema.get("properties").and_then(|p| p.get(name)) {
+        return Some(property);
+    }
+
+    ["allOf", "oneOf", "anyOf"]
+        .iter()
+        .filter_map(|k| schema.get(*k).and_then(|l| l.as_array()))
+        .flatten()
+        .find_map(|s| property(s, name, root))
+}
+
+// local references only, schemas of other documents are not renamed either
+fn resolve<'a>(schema: &'a Value, root: &'a Value) -> &'a Value {
+    let mut schema = schema;
+
+    // bounded, so cyclic references end
+    for _ in 0..32 {
+        match schema
+            .get("$ref")
+            .and_then(|r| r.as_str())
+            .and_then(|r| r.strip_prefix('#'))
+            .and_then(|p| root.pointer(p))
+        {
+            Some(target) => schema = target,
+            None => break,
+        }
+    }
+
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rename_properties() {
+        let mut schema = Schema::from_json(json!({
+            "openapi": "3.0.0",
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "type": "array", "items": { "$ref": "#/components/schemas/User" } },
+                                        "example": [{ "userId": 1, "homeAddress": { "zipCode": "00-001" } }]
+                                    }
+                                }
+                            },
+                            "default": {
+                                "description": "error",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "type": "object", "properties": { "errorCode": { "type": "string" } } }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "required": ["userId"],
+                        "properties": {
+                            "userId": { "type": "integer" },
+                            "homeAddress": { "$ref": "#/components/schemas/Address" },
+                            "labels": {
+                                "type": "object",
+                                "additionalProperties": { "type": "string" },
+                                "example": { "teamName": "core" }
+                            }
+                        }
+                    },
+                    "Address": {
+                        "type": "object",
+                        "properties": { "zipCode": { "type": "string" } },
+                        "example": { "zipCode": "00-001" }
+                    }
+                }
+            }
+        }));
+
+        let report = PropertyRenamer::options(PropertyCase::Snake)
+            .with_record_original(true)
+            .process(&mut schema)
+            .unwrap();
+
+        let body = schema.get_body();
+        assert_eq!(
+            body["components"]["schemas"]["User"],
+            json!({
+                "type": "object",
+                "required": ["user_id"],
+                "properties": {
+                    "user_id": { "type": "integer", "x-original-name": "userId" },
+                    "home_address": {
+                        "$ref": "#/components/schemas/Address",
+                        "x-original-name": "homeAddress"
+                    },
+                    "labels": {
+                        "type": "object",
+                        "additionalProperties": { "type": "string" },
+                        "example": { "teamName": "core" }
+                    }
+                }
+            })
+        );
+        assert_eq!(
+            body["components"]["schemas"]["Address"]["example"],
+            json!({ "zip_code": "00-001" })
+        );
+        assert_eq!(
+            body["paths"]["/users"]["get"]["responses"]["200"]["content"]["application/json"]
+                ["example"],
+            json!([{ "user_id": 1, "home_address": { "zip_code": "00-001" } }])
+        );
+        // response named like keyword is walked too
+        assert_eq!(
+            body["paths"]["/users"]["get"]["responses"]["default"]["content"]["application/json"]
+                ["schema"]["properties"],
+            json!({ "error_code": { "type": "string", "x-original-name": "errorCode" } })
+        );
+        assert_eq!(report.counters["properties renamed"], 4);
+
+        // names are converted already
+        let report = PropertyRenamer::options(PropertyCase::Snake)
+            .with_record_original(true)
+            .process(&mut schema)
+            .unwrap();
+        assert!(report.counters.is_empty());
+    }
+
+    #[test]
+    fn test_rename_properties_collision() {
+        let mut schema = Schema::from_json(json!({
+            "type": "object",
+            "properties": { "userId": {}, "user_id": {} }
+        }));
+
+        let result = PropertyRenamer::options(PropertyCase::Snake).process(&mut schema);
+        assert!(matches!(result, Err(Error::RenamePropertiesCollision(_))));
+    }
+}
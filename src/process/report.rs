@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::error::Error;
+
+pub static REPORT_FORMATS: &[&str] = &["text", "json"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReportFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for ReportFormat {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<ReportFormat, Self::Err> {
+        match input {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            s => Err(Error::ProcessInvalidReportFormat(s.to_string())),
+        }
+    }
+}
+
+// summary of what processing step did to specification, printed on demand
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct ProcessReport {
+    pub step: String,
+    pub counters: BTreeMap<String, usize>,
+    pub warnings: BTreeMap<String, usize>,
+}
+
+impl ProcessReport {
+    pub fn new(step: &str) -> Self {
+        Self {
+            step: step.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn count(&mut self, counter: &str) {
+        *self.counters.entry(counter.to_string()).or_insert(0) += 1;
+    }
+
+    // warnings are still logged, report keeps number of them by category
+    pub fn warn(&mut self, category: &str, message: String) {
+        log::warn!("{}", message);
+
+        *self.warnings.entry(category.to_string()).or_insert(0) += 1;
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let width = self
+            .counters
+            .keys()
+            .chain(self.warnings.keys())
+            .map(|k| k.len())
+            .max()
+            .unwrap_or(0);
+
+        let mut lines = vec![format!("\x1b[1;4m{} report\x1b[0m", self.step)];
+
+        for (counter, value) in &self.counters {
+            lines.push(format!(
+                "  {:width$}  \x1b[0;32m{:>6}\x1b[0m",
+                counter,
+                value,
+                width = width
+            ));
+        }
+
+        for (category, value) in &self.warnings {
+            lines.push(format!(
+                "  {:width$}  \x1b[0;33m{:>6}\x1b[0m warnings",
+                category,
+                value,
+                width = width
+            ));
+        }
+
+        if self.counters.is_empty() && self.warnings.is_empty() {
+            lines.push("  nothing changed".to_string());
+        }
+
+        lines
+    }
+
+    // stdout is reserved for processed document
+    pub fn print(&self, format: &ReportFormat) {
+        match format {
+            ReportFormat::Text => {
+                for line in self.lines() {
+                    eprintln!("{}", line);
+                }
+            }
+            ReportFormat::Json => eprintln!("{}", serde_json::to_string(self).unwrap()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_report() {
+        let mut report = ProcessReport::new("dereference");
+        report.count("refs inlined");
+        report.count("refs inlined");
+        report.count("refs skipped");
+        report.warn("unresolved-reference", "cannot resolve".to_string());
+
+        assert_eq!(
+            report.lines(),
+            vec![
+                "\x1b[1;4mdereference report\x1b[0m",
+                "  refs inlined          \x1b[0;32m     2\x1b[0m",
+                "  refs skipped          \x1b[0;32m     1\x1b[0m",
+                "  unresolved-reference  \x1b[0;33m     1\x1b[0m warnings",
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            serde_json::json!({
+                "step": "dereference",
+                "counters": { "refs inlined": 2, "refs skipped": 1 },
+                "warnings": { "unresolved-reference": 1 }
+            })
+        );
+    }
+}
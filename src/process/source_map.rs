@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::error::Error;
+
+// place in original documents where part of processed document comes from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Origin {
+    pub file: String,
+    pub pointer: String,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}#{}", self.file, self.pointer)
+    }
+}
+
+// pointers of processed document which were inlined from other places, anything else comes from base
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SourceMap {
+    base: String,
+    entries: BTreeMap<String, Origin>,
+}
+
+impl SourceMap {
+    pub fn new(base: String) -> Self {
+        Self {
+            base,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, pointer: String, origin: Origin) {
+        self.entries.insert(pointer, origin);
+    }
+
+    // the closest inlined ancestor of pointer decides its origin
+    pub fn lookup(&self, pointer: &str) -> Origin {
+        let mut prefix = pointer;
+
+        loop {
+            if let Some(origin) = self.entries.get(prefix) {
+                return Origin {
+                    file: origin.file.clone(),
+                    pointer: format!("{}{}", origin.pointer, &pointer[prefix.len()..]),
+                };
+            }
+
+            match prefix.rfind('/') {
+                Some(i) => prefix = &prefix[..i],
+                None => break,
+            }
+        }
+
+        Origin {
+            file: self.base.clone(),
+            pointer: pointer.to_string(),
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| Error::SourceMapError(format!("{}: {}", path, e)))?;
+
+        serde_json::from_str(&content)
+            .map_err(|e| Error::SourceMapError(format!("{}: {}", path, e)))
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        let content = serde_json::to_string_pretty(self).map_err(Error::SerdeJsonError)?;
+
+        std::fs::write(path, content).map_err(|e| Error::SourceMapError(format!("{}: {}", path, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        let mut map = SourceMap::new("file:///api.yaml".to_string());
+        map.add(
+            "/paths/~1users/get/responses/200".to_string(),
+            Origin {
+                file: "file:///responses.yaml".to_string(),
+                pointer: "/UserList".to_string(),
+            },
+        );
+        map.add(
+            "/paths/~1users/get/responses/200/content/application~1json/schema".to_string(),
+            Origin {
+                file: "file:///user.yaml".to_string(),
+                pointer: "".to_string(),
+            },
+        );
+
+        assert_eq!(
+            map.lookup("/paths/~1users/get/responses/200/description")
+                .to_string(),
+            "file:///responses.yaml#/UserList/description"
+        );
+        assert_eq!(
+            map.lookup("/paths/~1users/get/responses/200/content/application~1json/schema/type")
+                .to_string(),
+            "file:///user.yaml#/type"
+        );
+        assert_eq!(
+            map.lookup("/paths/~1users/get/responses/2000").to_string(),
+            "file:///api.yaml#/paths/~1users/get/responses/2000"
+        );
+        assert_eq!(map.lookup("").to_string(), "file:///api.yaml#");
+    }
+}
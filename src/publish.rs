@@ -0,0 +1,402 @@
+use reqwest::blocking::Client;
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use url::Url;
+
+use crate::error::Error;
+use crate::hash;
+use crate::schema::Schema;
+
+pub static TOKEN_VARIABLE: &str = "SCHEMATOOLS_PUBLISH_TOKEN";
+
+// where and how published specification came to be
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Provenance {
+    pub name: String,
+    pub tag: String,
+    pub hash: String,
+    pub source: String,
+    pub revision: Option<String>,
+    pub tool: String,
+    pub published_at: u64,
+}
+
+// normalized specification with its provenance, stored as `<name>/<tag>/spec.json` and `provenance.json`
+pub struct Artifact {
+    pub spec: Value,
+    pub provenance: Provenance,
+}
+
+impl Artifact {
+    pub fn new(schema: &Schema, name: &str, tag: &str, source: &str) -> Result<Self, Error> {
+        if [name, tag]
+            .iter()
+            .any(|s| s.is_empty() || s.contains('/') || s.contains(".."))
+        {
+            return Err(Error::PublishInvalidArtifact(format!("{}/{}", name, tag)));
+        }
+
+        let spec = hash::canonical(schema.get_body());
+
+        Ok(Self {
+            provenance: Provenance {
+                name: name.to_string(),
+                tag: tag.to_string(),
+                hash: hash::fingerprint(&spec),
+                source: source.to_string(),
+                revision: revision(source),
+                tool: format!("schematools {}", crate::VERSION),
+                published_at: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or_default(),
+            },
+            spec,
+        })
+    }
+
+    // files relative to registry root
+    pub fn files(&self) -> Vec<(String, String)> {
+        let prefix = format!("{}/{}", self.provenance.name, self.provenance.tag);
+
+        vec![
+            (
+                format!("{}/spec.json", prefix),
+                serde_json::to_string_pretty(&self.spec).unwrap(),
+            ),
+            (
+                format!("{}/provenance.json", prefix),
+                serde_json::to_string_pretty(&self.provenance).unwrap(),
+            ),
+        ]
+    }
+
+    // registries served over http or s3 cannot list directories, so tags are kept in index
+    pub fn index_file(&self) -> String {
+        format!("{}/index.json", self.provenance.name)
+    }
+
+    // tag is added to list of already published ones
+    pub fn index(&self, published: Option<&str>) -> Result<String, Error> {
+        let mut tags: Vec<String> = match published {
+            Some(content) => serde_json::from_str(content)
+                .map_err(|e| Error::PublishFailed(format!("{}: {}", self.index_file(), e)))?,
+            None => vec![],
+        };
+
+        if !tags.contains(&self.provenance.tag) {
+            tags.push(self.provenance.tag.clone());
+        }
+
+        Ok(serde_json::to_string_pretty(&tags).unwrap())
+    }
+}
+
+// commit of repository holding source file, when it's versioned with git
+fn revision(source: &str) -> Option<String> {
+    let path = std::fs::canonicalize(source).ok()?;
+    let repository = git2::Repository::discover(path.parent()?).ok()?;
+    let commit = repository.head().ok()?.peel_to_commit().ok()?;
+
+    Some(commit.id().to_string())
+}
+
+pub trait Publisher {
+    // returns locations of uploaded files
+    fn publish(&self, artifact: &Artifact) -> Result<Vec<String>, Error>;
+}
+
+// registry is chosen by scheme: http(s)://, s3://bucket/prefix or git+file:///path/to/repository
+pub fn create(registry: &str, client: &Client) -> Result<Box<dyn Publisher>, Error> {
+    let url =
+        Url::parse(registry).map_err(|_| Error::PublishInvalidRegistry(registry.to_string()))?;
+
+    match url.scheme() {
+        "http" | "https" => Ok(Box::new(HttpPublisher {
+            client: client.clone(),
+            url,
+            token: std::env::var(TOKEN_VARIABLE).ok(),
+        })),
+        "s3" => Ok(Box::new(S3Publisher { url })),
+        "git+file" => Ok(Box::new(GitPublisher {
+            path: PathBuf::from(url.path()),
+        })),
+        _ => Err(Error::PublishInvalidRegistry(registry.to_string())),
+    }
+}
+
+fn join(base: &str, file: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), file)
+}
+
+pub struct HttpPublisher {
+    client: Client,
+    url: Url,
+    token: Option<String>,
+}
+
+impl HttpPublisher {
+    fn put(&self, file: &str, content: String) -> Result<String, Error> {
+        let location = join(self.url.as_str(), file);
+        log::info!("{}: uploading", location);
+
+        let mut request = self
+            .client
+            .put(&location)
+            .header("Content-Type", "application/json")
+            .body(content);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = crate::http::send(request)
+            .map_err(|e| Error::PublishFailed(format!("{}: {}", location, e)))?;
+        if !response.status().is_success() {
+            return Err(Error::PublishFailed(format!(
+                "{}: {}",
+                location,
+                response.status()
+            )));
+        }
+
+        Ok(location)
+    }
+
+    // none when file was not published yet
+    fn get(&self, file: &str) -> Result<Option<String>, Error> {
+        let location = join(self.url.as_str(), file);
+
+        let mut request = self.client.get(&location);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = crate::http::send(request)
+            .map_err(|e| Error::PublishFailed(format!("{}: {}", location, e)))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(Error::PublishFailed(format!(
+                "{}: {}",
+                location,
+                response.status()
+            )));
+        }
+
+        response
+            .text()
+            .map(Some)
+            .map_err(|e| Error::PublishFailed(format!("{}: {}", location, e)))
+    }
+}
+
+impl Publisher for HttpPublisher {
+    fn publish(&self, artifact: &Artifact) -> Result<Vec<String>, Error> {
+        let mut locations = vec![];
+
+        for (file, content) in artifact.files() {
+            locations.push(self.put(&file, content)?);
+        }
+
+        // index is updated last, so listed tags are always complete
+        let index = artifact.index(self.get(&artifact.index_file())?.as_deref())?;
+        locations.push(self.put(&artifact.index_file(), index)?);
+
+        Ok(locations)
+    }
+}
+
+// uploads with aws cli, so credentials are resolved like in every other aws tool
+pub struct S3Publisher {
+    url: Url,
+}
+
+impl S3Publisher {
+    fn put(&self, file: &str, content: String) -> Result<String, Error> {
+        let location = join(self.url.as_str(), file);
+        log::info!("{}: uploading", location);
+
+        let mut child = Command::new("aws")
+            .args([
+                "s3",
+                "cp",
+                "-",
+                &location,
+                "--content-type",
+                "application/json",
+            ])
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::PublishFailed(format!("aws: {}", e)))?;
+
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(content.as_bytes())
+            .map_err(|e| Error::PublishFailed(format!("{}: {}", location, e)))?;
+
+        let status = child
+            .wait()
+            .map_err(|e| Error::PublishFailed(format!("{}: {}", location, e)))?;
+        if !status.success() {
+            return Err(Error::PublishFailed(format!("{}: {}", location, status)));
+        }
+
+        Ok(location)
+    }
+}
+
+impl Publisher for S3Publisher {
+    fn publish(&self, artifact: &Artifact) -> Result<Vec<String>, Error> {
+        let mut locations = vec![];
+
+        for (file, content) in artifact.files() {
+            locations.push(self.put(&file, content)?);
+        }
+
+        // index is updated last, so listed tags are always complete
+        let index_location = join(self.url.as_str(), &artifact.index_file());
+        let published = s3_read(&index_location).map_err(Error::PublishFailed)?;
+        let index = artifact.index(published.as_deref())?;
+        locations.push(self.put(&artifact.index_file(), index)?);
+
+        Ok(locations)
+    }
+}
+
+// content of s3 object read with aws cli, none when object does not exist
+pub fn s3_read(location: &str) -> Result<Option<String>, String> {
+    let output = Command::new("aws")
+        .args(["s3", "cp", location, "-"])
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| format!("aws: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        // missing key is reported as 404 of HeadObject
+        if stderr.contains("(404)") {
+            return Ok(None);
+        }
+
+        return Err(format!("{}: {}", location, stderr.trim()));
+    }
+
+    String::from_utf8(output.stdout)
+        .map(Some)
+        .map_err(|e| format!("{}: {}", location, e))
+}
+
+// writes files to working tree of repository and commits them on current branch
+pub struct GitPublisher {
+    path: PathBuf,
+}
+
+impl Publisher for GitPublisher {
+    fn publish(&self, artifact: &Artifact) -> Result<Vec<String>, Error> {
+        let repository = git2::Repository::open(&self.path).map_err(Error::PublishGitError)?;
+        let mut index = repository.index().map_err(Error::PublishGitError)?;
+
+        let mut locations = vec![];
+        for (file, content) in artifact.files() {
+            let path = self.path.join(&file);
+            log::info!("{}: writing", path.display());
+
+            std::fs::create_dir_all(path.parent().unwrap())
+                .and_then(|_| std::fs::write(&path, content))
+                .map_err(|e| Error::PublishFailed(format!("{}: {}", path.display(), e)))?;
+
+            index
+                .add_path(Path::new(&file))
+                .map_err(Error::PublishGitError)?;
+            locations.push(path.display().to_string());
+        }
+        index.write().map_err(Error::PublishGitError)?;
+
+        let tree = repository
+            .find_tree(index.write_tree().map_err(Error::PublishGitError)?)
+            .map_err(Error::PublishGitError)?;
+        let signature = repository
+            .signature()
+            .or_else(|_| git2::Signature::now("schematools", "schematools@localhost"))
+            .map_err(Error::PublishGitError)?;
+        let parent = repository.head().ok().and_then(|h| h.peel_to_commit().ok());
+
+        repository
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                &format!(
+                    "Publish {} {} ({})",
+                    artifact.provenance.name, artifact.provenance.tag, artifact.provenance.hash
+                ),
+                &tree,
+                &parent.iter().collect::<Vec<_>>(),
+            )
+            .map_err(Error::PublishGitError)?;
+
+        Ok(locations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_publish_git() {
+        let schema = Schema::from_json(json!({
+            "paths": {},
+            "openapi": "3.0.0",
+            "info": { "version": "1.4.0", "title": "api" }
+        }));
+
+        let artifact = Artifact::new(&schema, "api", "v1.4.0", "spec.yaml").unwrap();
+        assert_eq!(
+            serde_json::to_string(&artifact.spec).unwrap(),
+            r#"{"info":{"title":"api","version":"1.4.0"},"openapi":"3.0.0","paths":{}}"#
+        );
+        assert_eq!(
+            artifact.provenance.hash,
+            hash::fingerprint(schema.get_body())
+        );
+
+        let mut directory = std::env::temp_dir();
+        directory.push(format!("schema-tools-publish-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        git2::Repository::init(&directory).unwrap();
+
+        let publisher = create(
+            &format!("git+file://{}", directory.display()),
+            &Client::new(),
+        )
+        .unwrap();
+        publisher.publish(&artifact).unwrap();
+
+        let repository = git2::Repository::open(&directory).unwrap();
+        let commit = repository.head().unwrap().peel_to_commit().unwrap();
+        assert!(commit.message().unwrap().starts_with("Publish api v1.4.0"));
+        assert!(directory.join("api/v1.4.0/spec.json").exists());
+        assert!(directory.join("api/v1.4.0/provenance.json").exists());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert!(matches!(
+            Artifact::new(&schema, "api", "../v1", "spec.yaml"),
+            Err(Error::PublishInvalidArtifact(_))
+        ));
+        assert!(matches!(
+            create("ftp://registry", &Client::new()),
+            Err(Error::PublishInvalidRegistry(_))
+        ));
+    }
+}
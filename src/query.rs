@@ -0,0 +1,337 @@
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::resolver::SchemaResolver;
+use crate::scope::SchemaScope;
+use crate::tools::pointer_unescape;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+
+    // recursive descent of jsonpath (`..key` or `..*`)
+    Descendant(Option<String>),
+}
+
+// json pointer (`/components/schemas/User`, `*` matches any key) or jsonpath subset
+// (`$.paths.*.get.operationId`, `$..['$ref']`, `$.tags[0]`)
+#[derive(Debug, PartialEq)]
+pub struct Query {
+    query: String,
+    segments: Vec<Segment>,
+    jsonpath: bool,
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Result<Self, Error> {
+        let jsonpath = query.starts_with('$');
+        let segments = if let Some(path) = query.strip_prefix('$') {
+            jsonpath_segments(path).ok_or_else(|| Error::QueryInvalid(query.to_string()))?
+        } else {
+            pointer_segments(query.trim_start_matches('#'))
+                .ok_or_else(|| Error::QueryInvalid(query.to_string()))?
+        };
+
+        Ok(Self {
+            query: query.to_string(),
+            segments,
+            jsonpath,
+        })
+    }
+
+    // jsonpath and wildcards of pointer match many nodes, results are returned as array
+    pub fn is_single(&self) -> bool {
+        !self.jsonpath
+            && self
+                .segments
+                .iter()
+                .all(|s| matches!(s, Segment::Key(_) | Segment::Index(_)))
+    }
+
+    pub fn find<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        let mut current = vec![root];
+
+        for segment in &self.segments {
+            let mut next = vec![];
+
+            for node in current {
+                matches(node, segment, &mut next);
+            }
+
+            current = next;
+        }
+
+        current
+    }
+
+    // single value of pointer is required to exist, array of matches may be empty
+    pub fn get(&self, root: &Value) -> Result<Value, Error> {
+        let found = self.find(root);
+
+        if self.is_single() {
+            found
+                .first()
+                .map(|v| (*v).clone())
+                .ok_or_else(|| Error::QueryNotFound(self.query.clone()))
+        } else {
+            Ok(Value::Array(found.into_iter().cloned().collect()))
+        }
+    }
+}
+
+// references of extracted node are inlined, recursive ones are left as references
+pub fn resolve_refs(node: &Value, resolver: &SchemaResolver, stack: &mut Vec<String>) -> Value {
+    match node {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get("$ref") {
+                let url = resolver.locate(reference);
+                let location = url
+                    .as_ref()
+                    .map(|u| u.to_string())
+                    .unwrap_or_else(|| reference.clone());
+
+                // storage keeps references absolute, recursive one is written back relative
+                if stack.contains(&location) {
+                    let mut recursive = map.clone();
+                    if let Some(url) = url {
+                        recursive
+                            .insert("$ref".to_string(), Value::String(resolver.relative(&url)));
+                    }
+                    return Value::Object(recursive);
+                }
+
+                stack.push(location);
+                let mut resolved = resolver
+                    .resolve_once(node, &mut SchemaScope::default(), |resolved, _| {
+                        if resolved == node {
+                            Ok(node.clone())
+                        } else {
+                            Ok(resolve_refs(resolved, resolver, stack))
+                        }
+                    })
+                    .unwrap_or_else(|_| node.clone());
+                stack.pop();
+
+                // keywords next to reference override referenced ones
+                if let Value::Object(result) = &mut resolved {
+                    for (key, value) in map.iter().filter(|(k, _)| *k != "$ref") {
+                        result.insert(key.clone(), resolve_refs(value, resolver, stack));
+                    }
+                }
+
+                return resolved;
+            }
+
+            Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), resolve_refs(v, resolver, stack)))
+                    .collect(),
+            )
+        }
+        Value::Array(list) => Value::Array(
+            list.iter()
+                .map(|v| resolve_refs(v, resolver, stack))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+fn matches<'a>(node: &'a Value, segment: &Segment, result: &mut Vec<&'a Value>) {
+    match (segment, node) {
+        (Segment::Key(key), Value::Object(map)) => result.extend(map.get(key)),
+        (Segment::Key(key), Value::Array(list)) => {
+            result.extend(key.parse::<usize>().ok().and_then(|i| list.get(i)))
+        }
+        (Segment::Index(index), Value::Array(list)) => result.extend(list.get(*index)),
+        (Segment::Wildcard, Value::Object(map)) => result.extend(map.values()),
+        (Segment::Wildcard, Value::Array(list)) => result.extend(list.iter()),
+        (Segment::Descendant(key), _) => {
+            let children: Vec<&Value> = match node {
+                Value::Object(map) => map.values().collect(),
+                Value::Array(list) => list.iter().collect(),
+                _ => vec![],
+            };
+
+            match key {
+                Some(key) => matches(node, &Segment::Key(key.clone()), result),
+                None => result.extend(children.iter()),
+            }
+
+            for child in children {
+                matches(child, segment, result);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn pointer_segments(query: &str) -> Option<Vec<Segment>> {
+    if query.is_empty() {
+        return Some(vec![]);
+    }
+
+    query.strip_prefix('/').map(|path| {
+        path.split('/')
+            .map(|token| match token {
+                "*" => Segment::Wildcard,
+                token => Segment::Key(pointer_unescape(token)),
+            })
+            .collect()
+    })
+}
+
+fn jsonpath_segments(path: &str) -> Option<Vec<Segment>> {
+    let mut segments = vec![];
+    let mut rest = path;
+
+    while !rest.is_empty() {
+        let descendant = rest.starts_with("..");
+        if descendant {
+            rest = &rest[1..];
+        }
+
+        let (segment, remaining) = if let Some(r) = rest.strip_prefix('.') {
+            let end = r.find(['.', '[']).unwrap_or(r.len());
+            match &r[..end] {
+                "" if descendant => (Segment::Wildcard, &r[end..]),
+                "" => return None,
+                "*" => (Segment::Wildcard, &r[end..]),
+                key => (Segment::Key(key.to_string()), &r[end..]),
+            }
+        } else if let Some(r) = rest.strip_prefix('[') {
+            let end = r.find(']')?;
+            let inner = r[..end].trim();
+            let segment = if inner == "*" {
+                Segment::Wildcard
+            } else if let Ok(index) = inner.parse::<usize>() {
+                Segment::Index(index)
+            } else {
+                let quoted = inner
+                    .strip_prefix('\'')
+                    .and_then(|i| i.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|i| i.strip_suffix('"')))?;
+                Segment::Key(quoted.to_string())
+            };
+            (segment, &r[end + 1..])
+        } else {
+            return None;
+        };
+
+        segments.push(match (descendant, segment) {
+            (true, Segment::Key(key)) => Segment::Descendant(Some(key)),
+            (true, _) => Segment::Descendant(None),
+            (false, segment) => segment,
+        });
+        rest = remaining;
+    }
+
+    Some(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_query() {
+        let spec = json!({
+            "tags": [{ "name": "users" }, { "name": "groups" }],
+            "paths": {
+                "/users": { "get": { "operationId": "listUsers" }, "post": { "operationId": "createUser" } },
+                "/groups": { "get": { "operationId": "listGroups" } }
+            },
+            "components": {
+                "schemas": {
+                    "User": { "type": "object", "properties": { "group": { "$ref": "#/components/schemas/Group" } } },
+                    "Group": { "type": "object" }
+                }
+            }
+        });
+
+        let get = |query: &str| Query::parse(query).unwrap().get(&spec);
+
+        assert_eq!(
+            get("/components/schemas/Group").unwrap(),
+            json!({ "type": "object" })
+        );
+        assert_eq!(
+            get("#/paths/~1users/get/operationId").unwrap(),
+            json!("listUsers")
+        );
+        assert_eq!(get("/tags/1/name").unwrap(), json!("groups"));
+        assert!(matches!(
+            get("/components/schemas/Missing"),
+            Err(Error::QueryNotFound(_))
+        ));
+
+        assert_eq!(
+            get("/paths/*/get/operationId").unwrap(),
+            json!(["listUsers", "listGroups"])
+        );
+        assert_eq!(
+            get("$.paths.*.*.operationId").unwrap(),
+            json!(["listUsers", "createUser", "listGroups"])
+        );
+        assert_eq!(
+            get("$.paths['/users'].post.operationId").unwrap(),
+            json!(["createUser"])
+        );
+        assert_eq!(get("$.tags[0].name").unwrap(), json!(["users"]));
+        assert_eq!(get("$.info").unwrap(), json!([]));
+        assert_eq!(
+            get("$..['$ref']").unwrap(),
+            json!(["#/components/schemas/Group"])
+        );
+        assert_eq!(get("$.tags[*].name").unwrap(), json!(["users", "groups"]));
+
+        assert!(Query::parse("components").is_err());
+        assert!(Query::parse("$.tags[0").is_err());
+    }
+
+    #[test]
+    fn test_resolve_refs() {
+        let schema = crate::schema::Schema::from_json(json!({
+            "definitions": {
+                "Node": {
+                    "type": "object",
+                    "properties": {
+                        "children": { "type": "array", "items": { "$ref": "#/definitions/Node" } },
+                        "meta": { "$ref": "#/definitions/Meta", "description": "node meta" }
+                    }
+                },
+                "Meta": { "type": "object", "description": "meta" }
+            }
+        }));
+
+        let client = reqwest::blocking::Client::new();
+        let storage = crate::storage::SchemaStorage::new(&schema, &client);
+        let resolver = SchemaResolver::new(&schema, &storage);
+
+        let found = Query::parse("/definitions/Node/properties")
+            .unwrap()
+            .get(schema.get_body())
+            .unwrap();
+
+        // recursive reference is left relative to document
+        assert_eq!(
+            resolve_refs(&found, &resolver, &mut vec![]),
+            json!({
+                "children": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "children": { "type": "array", "items": { "$ref": "#/definitions/Node" } },
+                            "meta": { "type": "object", "description": "node meta" }
+                        }
+                    }
+                },
+                "meta": { "type": "object", "description": "node meta" }
+            })
+        );
+    }
+}
@@ -2,7 +2,7 @@ use crate::{error::Error, storage::SchemaStorage};
 use reqwest::Url;
 use serde_json::Value;
 
-use crate::{schema::Schema, scope::SchemaScope};
+use crate::{schema::Schema, scope::SchemaScope, tools::pointer_escape};
 
 pub struct SchemaResolver<'a> {
     url: Url,
@@ -38,7 +38,7 @@ impl<'a> SchemaResolver<'a> {
         match self.storage {
             Some(storage) => match node.as_object().unwrap().get("$ref").unwrap() {
                 Value::String(reference) => {
-                    let mut url = super::storage::ref_to_url(&self.url, reference).unwrap();
+                    let mut url = self.locate(reference).unwrap();
 
                     let copy = url.clone();
                     let pointer = copy.fragment();
@@ -95,7 +95,7 @@ impl<'a> SchemaResolver<'a> {
         match self.storage {
             Some(storage) => match node.as_object().unwrap().get("$ref").unwrap() {
                 Value::String(reference) => {
-                    let mut url = super::storage::ref_to_url(&self.url, reference).unwrap();
+                    let mut url = self.locate(reference).unwrap();
 
                     let copy = url.clone();
                     let pointer = copy.fragment();
@@ -132,6 +132,108 @@ impl<'a> SchemaResolver<'a> {
             None => f(node, scope),
         }
     }
+
+    // reference written in schema document, $id base uri and anchors are taken from storage
+    pub fn locate(&self, reference: &str) -> Option<Url> {
+        if let Some(storage) = self.storage {
+            if let Some(location) = storage
+                .aliases
+                .get(&(self.url.clone(), reference.to_string()))
+            {
+                return Some(location.clone());
+            }
+        }
+
+        super::storage::ref_to_url(&self.url, reference)
+    }
+
+    // reference to location relative to resolved document
+    pub fn relative(&self, location: &Url) -> String {
+        super::storage::url_to_ref(&self.url, location)
+    }
+
+    // concrete reference of $dynamicRef / $recursiveRef, scope lists documents entered
+    // during dereference (outermost first), documents are resources of dynamic anchors
+    pub fn resolve_dynamic(&self, keyword: &str, reference: &str, scope: &[Url]) -> Option<String> {
+        let storage = self.storage?;
+        let current = scope.last().unwrap_or(&self.url);
+
+        let mut url = super::storage::ref_to_url(current, reference)?;
+        let fragment = url.fragment().map(|f| f.to_string());
+        url.set_fragment(None);
+
+        let body = storage.schemas.get(&url)?.get_body();
+
+        match keyword {
+            "$recursiveRef" => {
+                let recursive =
+                    |body: &Value| body.get("$recursiveAnchor") == Some(&Value::Bool(true));
+
+                if recursive(body) {
+                    if let Some(outermost) = scope.iter().find(|u| {
+                        storage
+                            .schemas
+                            .get(u)
+                            .map(|s| recursive(s.get_body()))
+                            .unwrap_or(false)
+                    }) {
+                        return Some(outermost.to_string());
+                    }
+                }
+
+                Some(url.to_string())
+            }
+            "$dynamicRef" => match fragment {
+                Some(name) if !name.is_empty() && !name.starts_with('/') => {
+                    // initial target has to be dynamic anchor to look into dynamic scope
+                    if let Some(pointer) = find_anchor(body, "$dynamicAnchor", &name, "") {
+                        let outermost = scope.iter().find_map(|u| {
+                            storage.schemas.get(u).and_then(|s| {
+                                find_anchor(s.get_body(), "$dynamicAnchor", &name, "")
+                                    .map(|p| pointer_url(u, &p))
+                            })
+                        });
+
+                        return outermost.or_else(|| Some(pointer_url(&url, &pointer)));
+                    }
+
+                    find_anchor(body, "$anchor", &name, "").map(|p| pointer_url(&url, &p))
+                }
+                // json pointer behaves like $ref
+                _ => super::storage::ref_to_url(current, reference).map(|u| u.to_string()),
+            },
+            _ => None,
+        }
+    }
+}
+
+fn pointer_url(url: &Url, pointer: &str) -> String {
+    if pointer.is_empty() {
+        url.to_string()
+    } else {
+        format!("{}#{}", url, pointer)
+    }
+}
+
+// json pointer of subschema declaring anchor
+fn find_anchor(node: &Value, keyword: &str, name: &str, path: &str) -> Option<String> {
+    match node {
+        Value::Object(map) => {
+            if map.get(keyword).and_then(|v| v.as_str()) == Some(name) {
+                return Some(path.to_string());
+            }
+
+            map.iter().find_map(|(key, value)| {
+                let key = pointer_escape(key);
+                find_anchor(value, keyword, name, &format!("{}/{}", path, key))
+            })
+        }
+        Value::Array(list) => list
+            .iter()
+            .enumerate()
+            .find_map(|(i, value)| find_anchor(value, keyword, name, &format!("{}/{}", path, i))),
+        _ => None,
+    }
 }
 
 #[cfg(test)]
@@ -172,4 +274,21 @@ mod tests {
             println!("hashmap: {}", a)
         } */
     }
+
+    #[test]
+    fn test_relative_reference() {
+        let schema = Schema::from_json(serde_json::json!({}));
+        let storage = SchemaStorage::new(&schema, &reqwest::blocking::Client::new());
+        let resolver = SchemaResolver::new(&schema, &storage);
+
+        let location = resolver.locate("#/definitions/Node").unwrap();
+        assert_eq!(resolver.relative(&location), "#/definitions/Node");
+
+        let base = Url::parse("file:///specs/api/openapi.yaml").unwrap();
+        let external = Url::parse("file:///specs/common/errors.yaml#/Error").unwrap();
+        assert_eq!(
+            crate::storage::url_to_ref(&base, &external),
+            "../common/errors.yaml#/Error"
+        );
+    }
 }
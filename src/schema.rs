@@ -7,50 +7,55 @@ use url::Url;
 use crate::error::Error;
 use crate::process;
 
+// format of loaded document, schema is serialized back to it by default
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Json,
+    Yaml,
+}
+
 #[derive(Debug, Clone)]
 pub struct Schema {
     body: Value,
     url: Url,
+    format: Format,
 }
 
 impl Schema {
     pub fn load_url(url: Url) -> Result<Schema, Error> {
-        let client = reqwest::blocking::Client::new();
+        let client = crate::http::client()?;
         Self::load_url_with_client(url, &client)
     }
 
     pub fn load_url_with_client(url: Url, client: &Client) -> Result<Schema, Error> {
         log::info!("loading: {}", url);
 
-        let (content_type, response) = match url.scheme() {
+        let (content_type, content) = match url.scheme() {
             "file" => {
-                let content = fs::read_to_string(url.path()).map_err(|_| Error::SchemaLoad {
+                let content = fs::read(url.path()).map_err(|_| Error::SchemaLoad {
+                    url: url.to_string(),
+                })?;
+
+                Ok((None, content))
+            }
+            s if s == crate::git_revision::SCHEME => Ok((None, crate::git_revision::read(&url)?)),
+            s if s == crate::spec_registry::SCHEME => {
+                let location = crate::spec_registry::resolve(url.path())?;
+                let content = fs::read(location.path()).map_err(|_| Error::SchemaLoad {
                     url: url.to_string(),
                 })?;
 
                 Ok((None, content))
             }
             "http" | "https" => {
-                let response =
-                    client
-                        .get(&url.to_string())
-                        .send()
-                        .map_err(|error| Error::SchemaHttpLoad {
-                            url: url.to_string(),
-                            reason: error.to_string(),
-                        })?;
-
-                let content_type = response
-                    .headers()
-                    .get("content-type")
-                    .ok_or_else(|| Error::SchemaHttpLoad {
+                let fetched = crate::http::get(client, url.as_str()).map_err(|error| {
+                    Error::SchemaHttpLoad {
                         url: url.to_string(),
-                        reason: "Cannot get content-type header".to_string(),
-                    })?
-                    .to_str()
-                    .unwrap();
+                        reason: error.to_string(),
+                    }
+                })?;
 
-                Ok((Some(content_type.to_string()), response.text().unwrap()))
+                Ok((fetched.content_type, fetched.body))
             }
             s => Err(Error::SchemaLoadInvalidScheme {
                 url: url.to_string(),
@@ -58,50 +63,40 @@ impl Schema {
             }),
         }?;
 
-        let extension = url
+        let file = url
             .path_segments()
-            .map(|c| c.collect::<Vec<_>>())
-            .unwrap()
-            .last()
-            .unwrap()
-            .split('.')
-            .last();
-
-        let is_yaml_extension = if let Some(s) = extension {
-            s.contains("yaml")
+            .and_then(|mut c| c.next_back())
+            .unwrap_or_default();
+        let extension = file.trim_end_matches(".gz").rsplit('.').next();
+
+        let incorrect_type = || Error::SchemaLoadIncorrectType {
+            url: url.to_string(),
+            content_type: content_type.clone().unwrap_or_default(),
+            extension: extension.unwrap_or("").to_string(),
+        };
+
+        // compressed documents are recognized by content, whatever they are served as
+        let content = if crate::archive::is_gzip(&content) {
+            crate::archive::gunzip(&content)?
         } else {
-            false
+            content
         };
+        let response = String::from_utf8(content).map_err(|_| incorrect_type())?;
 
-        let body = if content_type.clone().unwrap_or_default().contains("yaml") || is_yaml_extension
-        {
-            let mut docs = serde_yaml::Deserializer::from_str(response.as_ref())
-                .into_iter()
-                .map(|d| Value::deserialize(d).map_err(Error::DeserializeYamlError))
-                .collect::<Result<Vec<_>, _>>()?;
+        let format = detect(content_type.as_deref(), extension, &response);
 
-            match docs.len() {
-                0 => Err(Error::SchemaLoadIncorrectType {
-                    url: url.to_string(),
-                    content_type: content_type.unwrap_or_default(),
-                    extension: extension.unwrap_or("").to_string(),
-                }),
-                1 => Ok(docs.remove(0)),
-                _ => Ok(docs.into_iter().collect::<Value>()),
-            }?
-        } else {
-            serde_json::from_str(response.as_ref()).map_err(|_| Error::SchemaLoadIncorrectType {
-                url: url.to_string(),
-                content_type: content_type.unwrap_or_default(),
-                extension: extension.unwrap_or("").to_string(),
-            })?
+        let body = match format {
+            Format::Yaml => parse_yaml(response.as_ref())?.ok_or_else(incorrect_type)?,
+            Format::Json => {
+                serde_json::from_str(response.as_ref()).map_err(|_| incorrect_type())?
+            }
         };
 
-        Ok(Schema { body, url })
+        Ok(Schema { body, url, format })
     }
 
     pub fn load_urls(urls: Vec<Url>) -> Result<Schema, Error> {
-        let client = reqwest::blocking::Client::new();
+        let client = crate::http::client()?;
 
         Self::load_urls_with_client(urls, &client)
     }
@@ -120,6 +115,7 @@ impl Schema {
         Ok(Schema {
             body: serde_json::json!(bodies),
             url: Url::parse("schema://inline").unwrap(),
+            format: Format::Json,
         })
     }
 
@@ -127,6 +123,35 @@ impl Schema {
         Schema {
             body,
             url: Url::parse("schema://inline").unwrap(),
+            format: Format::Json,
+        }
+    }
+
+    pub fn from_json_str(content: &str) -> Result<Schema, Error> {
+        let body = serde_json::from_str(content).map_err(Error::SerdeJsonError)?;
+
+        Ok(Self::from_json(body))
+    }
+
+    // multiple documents of yaml stream become an array like in loaded files
+    pub fn from_yaml_str(content: &str) -> Result<Schema, Error> {
+        let body = parse_yaml(content)?.unwrap_or(Value::Null);
+
+        Ok(Schema {
+            format: Format::Yaml,
+            ..Self::from_json(body)
+        })
+    }
+
+    pub fn get_format(&self) -> Format {
+        self.format
+    }
+
+    // format of input is used when not given
+    pub fn to_string_pretty(&self, format: Option<Format>) -> Result<String, Error> {
+        match format.unwrap_or(self.format) {
+            Format::Json => serde_json::to_string_pretty(&self.body).map_err(Error::SerdeJsonError),
+            Format::Yaml => serde_yaml::to_string(&self.body).map_err(Error::SerializeYamlError),
         }
     }
 
@@ -143,9 +168,46 @@ impl Schema {
     }
 }
 
+// content type wins, then extension (of `.yaml.gz` too), then content itself
+fn detect(content_type: Option<&str>, extension: Option<&str>, content: &str) -> Format {
+    let content_type = content_type.unwrap_or_default();
+
+    if content_type.contains("yaml") {
+        Format::Yaml
+    } else if content_type.contains("json") {
+        Format::Json
+    } else {
+        match extension {
+            Some("yaml") | Some("yml") => Format::Yaml,
+            Some("json") => Format::Json,
+            _ if content.trim_start().starts_with(&['{', '['][..]) => Format::Json,
+            _ => Format::Yaml,
+        }
+    }
+}
+
+fn parse_yaml(content: &str) -> Result<Option<Value>, Error> {
+    let mut docs = serde_yaml::Deserializer::from_str(content)
+        .into_iter()
+        .map(|d| Value::deserialize(d).map_err(Error::DeserializeYamlError))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(match docs.len() {
+        0 => None,
+        1 => Some(docs.remove(0)),
+        _ => Some(docs.into_iter().collect::<Value>()),
+    })
+}
+
 pub fn path_to_url(path: String) -> Result<Url, Error> {
     if path == "-" {
         return Err(Error::SchemaAsReference);
+    } else if let Some(reference) = path.strip_prefix(crate::spec_registry::PREFIX) {
+        return crate::spec_registry::resolve(reference);
+    } else if let Some((archive, inner)) = crate::archive::split(&path) {
+        let root = crate::archive::unpack(std::path::Path::new(archive), inner)?;
+
+        return Url::from_file_path(&root).map_err(|_| Error::SchemaInvalidPath { path });
     } else if path.starts_with("http") {
         // todo: support http path in cli, reconsider different schemes support
         return Url::parse(&path).map_err(|_| Error::SchemaInvalidPath { path });
@@ -185,6 +247,112 @@ mod tests {
         assert_eq!(spec.is_ok(), true);
     }
 
+    #[test]
+    fn test_format_sniffing() {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let mut directory = std::env::temp_dir();
+        directory.push(format!("schema-tools-sniffing-test-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+
+        let mut encoder = GzEncoder::new(vec![], flate2::Compression::default());
+        encoder.write_all(b"openapi: 3.0.0\n").unwrap();
+        fs::write(directory.join("spec.yaml.gz"), encoder.finish().unwrap()).unwrap();
+        fs::write(directory.join("spec"), "{\"openapi\": \"3.0.0\"}").unwrap();
+        fs::write(directory.join("spec.txt"), "openapi: 3.0.0\n").unwrap();
+
+        for (file, format) in &[
+            ("spec.yaml.gz", Format::Yaml),
+            ("spec", Format::Json),
+            ("spec.txt", Format::Yaml),
+        ] {
+            let schema =
+                Schema::load_url(Url::from_file_path(directory.join(file)).unwrap()).unwrap();
+            assert_eq!(schema.get_format(), *format);
+            assert_eq!(schema.get_body()["openapi"], "3.0.0");
+        }
+
+        assert_eq!(
+            detect(Some("application/x-yaml"), Some("json"), "{}"),
+            Format::Yaml
+        );
+        assert_eq!(detect(Some("text/plain"), Some("yml"), "{}"), Format::Yaml);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_registry_references() {
+        let mut directory = std::env::temp_dir();
+        directory.push(format!(
+            "schema-tools-registry-refs-test-{}",
+            std::process::id()
+        ));
+
+        let path = directory.join("orders-api").join("v2.1.0");
+        fs::create_dir_all(&path).unwrap();
+        fs::write(
+            path.join("spec.json"),
+            r#"{"openapi": "3.0.0", "components": {"schemas": {"Order": {"type": "object"}}}}"#,
+        )
+        .unwrap();
+        fs::write(
+            directory.join("cart.yaml"),
+            "components: {schemas: {Cart: {$ref: 'registry:orders-api@^2#/components/schemas/Order'}}}\n",
+        )
+        .unwrap();
+
+        std::env::set_var(
+            crate::spec_registry::REGISTRY_VARIABLE,
+            Url::from_directory_path(&directory).unwrap().as_str(),
+        );
+
+        let schema =
+            Schema::load_url(Url::from_file_path(directory.join("cart.yaml")).unwrap()).unwrap();
+        let client = crate::http::client().unwrap();
+        let storage = crate::storage::SchemaStorage::new(&schema, &client);
+
+        let provider = &storage.schemas[&Url::parse("registry:orders-api@^2").unwrap()];
+        assert_eq!(
+            provider
+                .get_body()
+                .pointer("/components/schemas/Order/type"),
+            Some(&serde_json::json!("object"))
+        );
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_format_round_trip() {
+        let yaml = Schema::from_yaml_str("openapi: 3.0.0\ninfo:\n  title: api\n").unwrap();
+        assert_eq!(yaml.get_format(), Format::Yaml);
+        assert_eq!(
+            yaml.get_body(),
+            &serde_json::json!({ "openapi": "3.0.0", "info": { "title": "api" } })
+        );
+        assert_eq!(
+            Schema::from_yaml_str(&yaml.to_string_pretty(None).unwrap())
+                .unwrap()
+                .get_body(),
+            yaml.get_body()
+        );
+
+        let json = yaml.to_string_pretty(Some(Format::Json)).unwrap();
+        assert_eq!(
+            json,
+            "{\n  \"openapi\": \"3.0.0\",\n  \"info\": {\n    \"title\": \"api\"\n  }\n}"
+        );
+
+        let json = Schema::from_json_str(&json).unwrap();
+        assert_eq!(json.get_format(), Format::Json);
+        assert_eq!(json.get_body(), yaml.get_body());
+
+        assert!(Schema::from_json_str("openapi: 3.0.0").is_err());
+        assert!(Schema::from_yaml_str("a: [").is_err());
+    }
+
     #[test_case( "./not-existing.json".to_string() ; "relative" )]
     #[test_case( "../not-existing.json".to_string() ; "relative2" )]
     #[test_case( "not-existing.json".to_string(); "relative3" )]
@@ -1,7 +1,7 @@
 use std::fmt;
 
 use regex::Regex;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::error::Error;
 
@@ -18,7 +18,7 @@ pub struct SchemaScope {
     spaces: Vec<Space>,
 }
 
-#[derive(Clone, Debug, Serialize, Eq, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub enum Space {
     Tag(String),
     Operation(String),
@@ -289,6 +289,21 @@ impl SchemaScope {
         self.spaces.clone()
     }
 
+    // unescaped tokens of json pointer, entities and references are skipped
+    pub fn segments(&self) -> Vec<String> {
+        self.scope
+            .iter()
+            .filter_map(|s| match s {
+                SchemaScopeType::Property(v)
+                | SchemaScopeType::Any(v)
+                | SchemaScopeType::Form(v)
+                | SchemaScopeType::Definition(v) => Some(v.clone()),
+                SchemaScopeType::Index(i) => Some(i.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn namer(&mut self) -> BasicNamer {
         BasicNamer {
             parts: self.scope.clone(),
@@ -0,0 +1,397 @@
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+use crate::error::Error;
+use crate::hash;
+use crate::publish;
+
+pub static REGISTRY_VARIABLE: &str = "SCHEMATOOLS_REGISTRY";
+
+// spec arguments in form of `registry:orders-api@^2` are fetched from registry
+pub static PREFIX: &str = "registry:";
+
+// the same form as url of `$ref` (ex. `registry:orders-api@^2#/components/schemas/Order`)
+pub static SCHEME: &str = "registry";
+
+// name of published specification with range of accepted versions
+#[derive(Debug)]
+pub struct Reference {
+    pub name: String,
+    pub range: semver::VersionReq,
+}
+
+impl std::str::FromStr for Reference {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, range) = s.split_once('@').unwrap_or((s, "*"));
+
+        if name.is_empty() || name.contains('/') || name.contains("..") {
+            return Err(Error::SpecRegistryInvalidReference(s.to_string()));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            range: semver::VersionReq::parse(range)
+                .map_err(|_| Error::SpecRegistryInvalidReference(s.to_string()))?,
+        })
+    }
+}
+
+// registry is configured with SCHEMATOOLS_REGISTRY, the same url specifications are published to
+pub fn resolve(reference: &str) -> Result<Url, Error> {
+    let registry = std::env::var(REGISTRY_VARIABLE)
+        .map_err(|_| Error::SpecRegistryNotConfigured(REGISTRY_VARIABLE.to_string()))?;
+    let registry = Url::parse(&registry).map_err(|_| Error::PublishInvalidRegistry(registry))?;
+
+    resolve_in(&registry, &reference.parse()?, &cache()?)
+}
+
+// cache belongs to user, so specifications cannot be planted there by anyone else
+fn cache() -> Result<PathBuf, Error> {
+    let directory = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))
+        .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))
+        .ok_or_else(|| {
+            Error::SpecRegistryError("no cache directory, set HOME or XDG_CACHE_HOME".to_string())
+        })?;
+
+    Ok(directory.join("schema-tools").join("specs"))
+}
+
+pub fn resolve_in(registry: &Url, reference: &Reference, cache: &Path) -> Result<Url, Error> {
+    let tags = match tags(registry, &reference.name) {
+        Ok(tags) => tags,
+        Err(e) => {
+            // published versions never change, so cached ones are good enough when registry is down
+            log::warn!("{}: {}, using cached versions", reference.name, e);
+            directories(&cache.join(&reference.name))
+        }
+    };
+
+    let tag = best(&tags, &reference.range).ok_or_else(|| {
+        Error::SpecRegistryNoMatchingVersion(format!("{}@{}", reference.name, reference.range))
+    })?;
+    log::info!("{}@{}: resolved {}", reference.name, reference.range, tag);
+
+    let path = match local(registry) {
+        Some(root) => root.join(&reference.name).join(&tag).join("spec.json"),
+        None => {
+            let directory = cache.join(&reference.name).join(&tag);
+
+            if !cached(&directory) {
+                let url = format!(
+                    "{}/{}/{}",
+                    registry.as_str().trim_end_matches('/'),
+                    reference.name,
+                    tag
+                );
+                log::debug!("{}: fetching", url);
+
+                let spec = get(&format!("{}/spec.json", url))?;
+                let provenance = get(&format!("{}/provenance.json", url))?;
+                verify(&spec, &provenance)
+                    .map_err(|e| Error::SpecRegistryError(format!("{}/spec.json: {}", url, e)))?;
+
+                std::fs::create_dir_all(&directory)
+                    .and_then(|_| std::fs::write(directory.join("spec.json"), spec))
+                    .and_then(|_| std::fs::write(directory.join("provenance.json"), provenance))
+                    .map_err(|e| {
+                        Error::SpecRegistryError(format!("{}: {}", directory.display(), e))
+                    })?;
+            }
+
+            directory.join("spec.json")
+        }
+    };
+
+    Url::from_file_path(&path).map_err(|_| Error::SchemaInvalidPath {
+        path: path.display().to_string(),
+    })
+}
+
+// cached specification is used only when it still has hash of its provenance
+fn cached(directory: &Path) -> bool {
+    let read = |file| std::fs::read_to_string(directory.join(file));
+
+    match (read("spec.json"), read("provenance.json")) {
+        (Ok(spec), Ok(provenance)) => match verify(&spec, &provenance) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("{}: {}, fetching again", directory.display(), e);
+                false
+            }
+        },
+        _ => false,
+    }
+}
+
+fn verify(spec: &str, provenance: &str) -> Result<(), String> {
+    let spec = serde_json::from_str::<Value>(spec).map_err(|e| e.to_string())?;
+    let expected = serde_json::from_str::<Value>(provenance)
+        .map_err(|e| e.to_string())?
+        .get("hash")
+        .and_then(|h| h.as_str())
+        .map(|h| h.to_string())
+        .ok_or_else(|| "provenance has no hash".to_string())?;
+
+    let actual = hash::fingerprint(&spec);
+    if actual != expected {
+        return Err(format!(
+            "hash {} does not match provenance {}",
+            actual, expected
+        ));
+    }
+
+    Ok(())
+}
+
+// highest version matching range, tags may be prefixed with `v`
+fn best(tags: &[String], range: &semver::VersionReq) -> Option<String> {
+    tags.iter()
+        .filter_map(|t| {
+            semver::Version::parse(t.trim_start_matches('v'))
+                .ok()
+                .map(|v| (v, t))
+        })
+        .filter(|(v, _)| range.matches(v))
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, t)| t.clone())
+}
+
+// published tags come from `<name>/index.json` (list of tags) or directories of local registry
+fn tags(registry: &Url, name: &str) -> Result<Vec<String>, Error> {
+    match local(registry) {
+        Some(root) => Ok(directories(&root.join(name))),
+        None => {
+            let url = format!(
+                "{}/{}/index.json",
+                registry.as_str().trim_end_matches('/'),
+                name
+            );
+
+            serde_json::from_str(&get(&url)?)
+                .map_err(|e| Error::SpecRegistryError(format!("{}: {}", url, e)))
+        }
+    }
+}
+
+fn local(registry: &Url) -> Option<PathBuf> {
+    match registry.scheme() {
+        "file" | "git+file" => Some(PathBuf::from(registry.path())),
+        _ => None,
+    }
+}
+
+fn directories(path: &Path) -> Vec<String> {
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn get(url: &str) -> Result<String, Error> {
+    // s3 registries are read with aws cli, like they are published
+    if url.starts_with("s3://") {
+        return publish::s3_read(url)
+            .map_err(Error::SpecRegistryError)?
+            .ok_or_else(|| Error::SpecRegistryError(format!("{}: not found", url)));
+    }
+
+    let fetched = crate::http::get(&crate::http::client()?, url)
+        .map_err(|e| Error::SpecRegistryError(format!("{}: {}", url, e)))?;
+
+    if !(200..300).contains(&fetched.status) {
+        return Err(Error::SpecRegistryError(format!(
+            "{}: {}",
+            url, fetched.status
+        )));
+    }
+
+    String::from_utf8(fetched.body).map_err(|e| Error::SpecRegistryError(format!("{}: {}", url, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    // http registry keeping uploaded files in memory, one request per connection
+    fn serve() -> (String, Arc<Mutex<HashMap<String, String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/registry", listener.local_addr().unwrap());
+        let files = Arc::new(Mutex::new(HashMap::<String, String>::new()));
+
+        let stored = files.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request = String::new();
+                reader.read_line(&mut request).unwrap();
+                let mut parts = request.split_whitespace();
+                let (method, path) = (parts.next().unwrap(), parts.next().unwrap().to_string());
+
+                let mut length = 0;
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    line.clear();
+                }
+
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+
+                let mut files = stored.lock().unwrap();
+                let response = match method {
+                    "PUT" => {
+                        files.insert(path, String::from_utf8(body).unwrap());
+                        "HTTP/1.1 201 Created\r\ncontent-length: 0".to_string()
+                    }
+                    _ => match files.get(&path) {
+                        Some(content) => format!(
+                            "HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n{}",
+                            content.len(),
+                            content
+                        ),
+                        None => "HTTP/1.1 404 Not Found\r\ncontent-length: 0".to_string(),
+                    },
+                };
+                let (head, body) = response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+                write!(stream, "{}\r\nconnection: close\r\n\r\n{}", head, body).unwrap();
+            }
+        });
+
+        (url, files)
+    }
+
+    #[test]
+    fn test_resolve_published_http_registry() {
+        let (url, files) = serve();
+        let registry = Url::parse(&url).unwrap();
+        let client = crate::http::client().unwrap();
+
+        let mut cache = std::env::temp_dir();
+        cache.push(format!(
+            "schema-tools-registry-http-test-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&cache);
+
+        for version in &["1.4.0", "1.5.0", "2.0.0"] {
+            let schema = crate::schema::Schema::from_json(json!({
+                "openapi": "3.0.0",
+                "info": { "version": version, "title": "orders" },
+                "paths": {}
+            }));
+            let artifact = publish::Artifact::new(
+                &schema,
+                "orders-api",
+                &format!("v{}", version),
+                "spec.yaml",
+            )
+            .unwrap();
+
+            publish::create(&url, &client)
+                .unwrap()
+                .publish(&artifact)
+                .unwrap();
+        }
+
+        assert_eq!(
+            serde_json::from_str::<Value>(
+                &files.lock().unwrap()["/registry/orders-api/index.json"]
+            )
+            .unwrap(),
+            json!(["v1.4.0", "v1.5.0", "v2.0.0"])
+        );
+
+        let resolve = |reference: &str| resolve_in(&registry, &reference.parse().unwrap(), &cache);
+        let spec = |url: Url| {
+            serde_json::from_str::<Value>(
+                &std::fs::read_to_string(url.to_file_path().unwrap()).unwrap(),
+            )
+            .unwrap()
+        };
+
+        let resolved = resolve("orders-api@^1").unwrap();
+        assert_eq!(
+            resolved,
+            Url::from_file_path(cache.join("orders-api/v1.5.0/spec.json")).unwrap()
+        );
+        assert_eq!(spec(resolved.clone())["info"]["version"], json!("1.5.0"));
+
+        // tampered copy is fetched again
+        std::fs::write(resolved.to_file_path().unwrap(), r#"{"openapi":"3.0.0"}"#).unwrap();
+        assert_eq!(
+            spec(resolve("orders-api@^1").unwrap())["info"]["version"],
+            json!("1.5.0")
+        );
+
+        // registry serving spec other than published one is rejected
+        files.lock().unwrap().insert(
+            "/registry/orders-api/v2.0.0/spec.json".to_string(),
+            r#"{"openapi":"3.0.0"}"#.to_string(),
+        );
+        assert!(matches!(
+            resolve("orders-api@^2"),
+            Err(Error::SpecRegistryError(_))
+        ));
+
+        std::fs::remove_dir_all(&cache).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_local_registry() {
+        let mut directory = std::env::temp_dir();
+        directory.push(format!("schema-tools-registry-test-{}", std::process::id()));
+
+        for tag in &["v1.0.0", "v2.0.0", "v2.3.1", "2.10.0-beta.1", "v3.0.0"] {
+            let path = directory.join("orders-api").join(tag);
+            std::fs::create_dir_all(&path).unwrap();
+            std::fs::write(path.join("spec.json"), "{}").unwrap();
+        }
+
+        let registry = Url::from_directory_path(&directory).unwrap();
+        let resolve = |reference: &str| {
+            resolve_in(
+                &registry,
+                &reference.parse().unwrap(),
+                &directory.join("cache"),
+            )
+        };
+
+        assert_eq!(
+            resolve("orders-api@^2").unwrap(),
+            Url::from_file_path(directory.join("orders-api/v2.3.1/spec.json")).unwrap()
+        );
+        assert_eq!(
+            resolve("orders-api").unwrap(),
+            Url::from_file_path(directory.join("orders-api/v3.0.0/spec.json")).unwrap()
+        );
+        assert!(matches!(
+            resolve("orders-api@^4"),
+            Err(Error::SpecRegistryNoMatchingVersion(_))
+        ));
+        assert!(matches!(
+            "../orders@^1".parse::<Reference>(),
+            Err(Error::SpecRegistryInvalidReference(_))
+        ));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -1,33 +1,77 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::schema::Schema;
+use crate::tools::pointer_escape;
 use reqwest::{blocking::Client, Url};
 use serde_json::Value;
 
+// references resolved against dynamic scope (2020-12 and 2019-09)
+pub static DYNAMIC_REFS: &[&str] = &["$dynamicRef", "$recursiveRef"];
+
 pub struct SchemaStorage {
     pub schemas: HashMap<Url, Schema>,
+
+    // references which do not resolve relative to url of their document ($id base uri, $anchor),
+    // keyed by document and reference as written
+    pub aliases: HashMap<(Url, String), Url>,
 }
 
 impl SchemaStorage {
     pub fn new(schema: &Schema, client: &Client) -> Self {
-        Self {
-            // saves also schema to storage
-            // replaces all refs to absolutes
-            schemas: extract_schemas(&[schema], client),
-        }
+        // saves also schema to storage
+        // replaces all refs to absolutes
+        let (schemas, aliases) = extract_schemas(&[schema], client);
+
+        Self { schemas, aliases }
     }
 
     pub fn new_multi(schemas: &[&Schema], client: &Client) -> Self {
-        Self {
-            schemas: extract_schemas(schemas, client),
+        let (schemas, aliases) = extract_schemas(schemas, client);
+
+        Self { schemas, aliases }
+    }
+}
+
+// canonical uris of schema resources ($id) and anchors pointing to document url with json pointer
+#[derive(Default)]
+struct Ids {
+    locations: HashMap<Url, Url>,
+
+    // uris declared in several places (ex. copied $id) are resolved relative to document
+    ambiguous: HashSet<Url>,
+}
+
+impl Ids {
+    fn insert(&mut self, id: Url, location: Url) {
+        match self.locations.get(&id) {
+            Some(existing) if *existing != location => {
+                log::debug!("{} declared by {} and {}", id, existing, location);
+                self.ambiguous.insert(id);
+            }
+            Some(_) => {}
+            None => {
+                self.locations.insert(id, location);
+            }
+        }
+    }
+
+    fn get(&self, id: &Url) -> Option<&Url> {
+        if self.ambiguous.contains(id) {
+            None
+        } else {
+            self.locations.get(id)
         }
     }
 }
 
-fn extract_schemas(schemas: &[&Schema], client: &Client) -> HashMap<Url, Schema> {
+fn extract_schemas(
+    schemas: &[&Schema],
+    client: &Client,
+) -> (HashMap<Url, Schema>, HashMap<(Url, String), Url>) {
     let mut resolved: HashMap<Url, Schema> = HashMap::new();
+    let mut ids = Ids::default();
 
-    // load everything we need
+    // given schemas are indexed first, so they may reference each other by $id
     for original in schemas {
         let url = original.get_url();
 
@@ -39,11 +83,18 @@ fn extract_schemas(schemas: &[&Schema], client: &Client) -> HashMap<Url, Schema>
         let schema = (*original).clone();
         resolved.insert(url.clone(), schema);
 
-        log::trace!("extracting: {}", url);
+        index_ids(&mut ids, url, url, "", original.get_body());
+    }
+
+    // load everything we need
+    for original in schemas {
+        log::trace!("extracting: {}", original.get_url());
 
         // resolve external references
         resolve_externals(
             &mut resolved,
+            &mut ids,
+            original.get_url(),
             original.get_url(),
             original.get_body(),
             client,
@@ -51,44 +102,150 @@ fn extract_schemas(schemas: &[&Schema], client: &Client) -> HashMap<Url, Schema>
     }
 
     // absoultize refs
-    resolved
+    let mut aliases = HashMap::new();
+    let resolved = resolved
         .into_iter()
         .map(|(url, mut schema)| {
-            absolutize_refs(&url, schema.get_body_mut());
+            absolutize_refs(&ids, &mut aliases, &url, &url, schema.get_body_mut());
 
             (url, schema)
         })
-        .collect()
+        .collect();
+
+    (resolved, aliases)
+}
+
+fn index_ids(ids: &mut Ids, document: &Url, base: &Url, pointer: &str, node: &Value) {
+    match node {
+        Value::Object(map) => {
+            let location = if pointer.is_empty() {
+                document.clone()
+            } else {
+                let mut l = document.clone();
+                l.set_fragment(Some(pointer));
+                l
+            };
+
+            let base = match map.get("$id").and_then(|id| id.as_str()) {
+                Some(id) => match ref_to_url(base, id) {
+                    Some(mut id) => {
+                        id.set_fragment(None);
+                        ids.insert(id.clone(), location.clone());
+                        id
+                    }
+                    None => base.clone(),
+                },
+                None => base.clone(),
+            };
+
+            for keyword in &["$anchor", "$dynamicAnchor"] {
+                if let Some(name) = map.get(*keyword).and_then(|a| a.as_str()) {
+                    // anchors are reachable by document url as well
+                    for resource in &[&base, document] {
+                        let mut anchor = (*resource).clone();
+                        anchor.set_fragment(Some(name));
+                        ids.insert(anchor, location.clone());
+                    }
+                }
+            }
+
+            for (key, value) in map {
+                let key = pointer_escape(key);
+                index_ids(ids, document, &base, &format!("{}/{}", pointer, key), value);
+            }
+        }
+        Value::Array(a) => {
+            for (i, x) in a.iter().enumerate() {
+                index_ids(ids, document, base, &format!("{}/{}", pointer, i), x);
+            }
+        }
+        _ => {}
+    }
+}
+
+// location of reference resolved against $id base uri, none when it's not known resource
+fn locate(ids: &Ids, base: &Url, reference: &str) -> Option<Url> {
+    let candidate = ref_to_url(base, reference)?;
+    if let Some(location) = ids.get(&candidate) {
+        return Some(location.clone());
+    }
+
+    let fragment = candidate.fragment().unwrap_or("").to_string();
+    let mut resource = candidate;
+    resource.set_fragment(None);
+
+    let mut location = ids.get(&resource)?.clone();
+
+    if fragment.is_empty() {
+        Some(location)
+    } else if fragment.starts_with('/') {
+        let pointer = format!("{}{}", location.fragment().unwrap_or(""), fragment);
+        location.set_fragment(Some(&pointer));
+        Some(location)
+    } else {
+        // unknown anchor
+        None
+    }
+}
+
+fn base_of(base: &Url, map: &serde_json::Map<String, Value>) -> Url {
+    map.get("$id")
+        .and_then(|id| id.as_str())
+        .and_then(|id| ref_to_url(base, id))
+        .map(|mut id| {
+            id.set_fragment(None);
+            id
+        })
+        .unwrap_or_else(|| base.clone())
 }
 
 fn resolve_externals(
     resolved: &mut HashMap<Url, Schema>,
+    ids: &mut Ids,
+    document: &Url,
     base: &Url,
     schema: &Value,
     client: &Client,
 ) {
     match schema {
         Value::Object(ref map) => {
+            let base = base_of(base, map);
+
             if let Some(Value::String(reference)) = map.get("$ref") {
-                if let Some(file) = ref_to_file_url(base, reference) {
-                    try_resolve_external(resolved, file, client);
+                if locate(ids, &base, reference).is_none() {
+                    if let Some(file) = ref_to_file_url(document, reference) {
+                        try_resolve_external(resolved, ids, file, client);
+                    }
                 }
             } else {
+                for keyword in DYNAMIC_REFS {
+                    if let Some(Value::String(reference)) = map.get(*keyword) {
+                        if let Some(file) = ref_to_file_url(document, reference) {
+                            try_resolve_external(resolved, ids, file, client);
+                        }
+                    }
+                }
+
                 for (_, value) in map.into_iter() {
-                    resolve_externals(resolved, base, value, client);
+                    resolve_externals(resolved, ids, document, &base, value, client);
                 }
             }
         }
         Value::Array(a) => {
             for (_, x) in a.iter().enumerate() {
-                resolve_externals(resolved, base, x, client);
+                resolve_externals(resolved, ids, document, base, x, client);
             }
         }
         _ => {}
     };
 }
 
-fn try_resolve_external(resolved: &mut HashMap<Url, Schema>, file: Url, client: &Client) {
+fn try_resolve_external(
+    resolved: &mut HashMap<Url, Schema>,
+    ids: &mut Ids,
+    file: Url,
+    client: &Client,
+) {
     if resolved.contains_key(&file) {
         return;
     }
@@ -96,25 +253,70 @@ fn try_resolve_external(resolved: &mut HashMap<Url, Schema>, file: Url, client:
     let schema = Schema::load_url_with_client(file.clone(), client).unwrap();
     resolved.insert(file, schema.clone());
 
-    resolve_externals(resolved, schema.get_url(), schema.get_body(), client);
+    let url = schema.get_url();
+    index_ids(ids, url, url, "", schema.get_body());
+    resolve_externals(resolved, ids, url, url, schema.get_body(), client);
 }
 
-fn absolutize_refs(current: &Url, root: &mut Value) {
+fn absolutize_refs(
+    ids: &Ids,
+    aliases: &mut HashMap<(Url, String), Url>,
+    document: &Url,
+    base: &Url,
+    root: &mut Value,
+) {
     match root {
         Value::Object(ref mut map) => {
+            let base = base_of(base, map);
+
             if let Some(Value::String(reference)) = map.get_mut("$ref") {
                 // todo: not sure about unwrap
-                let mut absolute = ref_to_url(current, reference).unwrap().to_string();
+                let relative = ref_to_url(document, reference).unwrap();
+
+                // $id base uri wins, anchors are turned into json pointers
+                let located = locate(ids, &base, reference)
+                    .or_else(|| locate(ids, document, reference))
+                    .filter(|l| *l != relative);
+
+                let mut absolute = match located {
+                    Some(location) => {
+                        let key = (document.clone(), reference.clone());
+                        match aliases.get(&key) {
+                            Some(existing) if *existing != location => log::warn!(
+                                "{}: {} resolves to {} and {}",
+                                document,
+                                reference,
+                                existing,
+                                location
+                            ),
+                            _ => {
+                                aliases.insert(key, location.clone());
+                            }
+                        }
+
+                        location.to_string()
+                    }
+                    None => relative.to_string(),
+                };
+
                 std::mem::swap(reference, &mut absolute);
             } else {
+                for keyword in DYNAMIC_REFS {
+                    if let Some(Value::String(reference)) = map.get_mut(*keyword) {
+                        if let Some(absolute) = ref_to_url(document, reference) {
+                            *reference = absolute.to_string();
+                        }
+                    }
+                }
+
                 for (_, value) in map.into_iter() {
-                    absolutize_refs(current, value);
+                    absolutize_refs(ids, aliases, document, &base, value);
                 }
             }
         }
         Value::Array(ref mut a) => {
             for (_, x) in a.iter_mut().enumerate() {
-                absolutize_refs(current, x);
+                absolutize_refs(ids, aliases, document, base, x);
             }
         }
         _ => {}
@@ -131,6 +333,22 @@ pub fn ref_to_url(base: &Url, reference: &str) -> Option<Url> {
     }
 }
 
+// reverse of ref_to_url, references within the same document keep only their fragment
+pub fn url_to_ref(base: &Url, location: &Url) -> String {
+    let document = |url: &Url| {
+        let mut url = url.clone();
+        url.set_fragment(None);
+        url
+    };
+
+    if document(base) == document(location) {
+        return format!("#{}", location.fragment().unwrap_or_default());
+    }
+
+    base.make_relative(location)
+        .unwrap_or_else(|| location.to_string())
+}
+
 fn ref_to_file_url(base: &Url, reference: &str) -> Option<Url> {
     ref_to_url(base, reference).map(|mut u| {
         u.set_fragment(None);
@@ -2,8 +2,118 @@ use std::str::{Chars, FromStr};
 
 use crate::error::Error;
 use crate::scope::SchemaScope;
+use inflector::Inflector;
 use serde::Serialize;
-use serde_json::Value;
+use serde_json::{Map, Value};
+
+pub static EXTENSION_PREFIX: &str = "x-schematools";
+
+// operations of path item, in order of openapi specification
+pub static HTTP_METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+// name of vendor extension, ex. x-schematools-name
+pub fn extension(prefix: &Option<String>, name: &str) -> String {
+    format!(
+        "{}-{}",
+        prefix
+            .as_deref()
+            .unwrap_or(EXTENSION_PREFIX)
+            .trim_end_matches('-'),
+        name
+    )
+}
+
+// escapes name for use as json pointer segment (rfc 6901)
+pub fn pointer_escape(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+// reverses pointer_escape, `~1` is replaced first so `~01` stays `~1`
+pub fn pointer_unescape(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+// values of those keywords are instances, not schemas
+static INSTANCE_KEYWORDS: &[&str] = &["enum", "const", "default", "example", "examples"];
+
+// keys of those maps are names (ex. property named `default`), not keywords
+static NAME_MAPS: &[&str] = &[
+    "properties",
+    "patternProperties",
+    "dependentSchemas",
+    "definitions",
+    "$defs",
+    "schemas",
+    "responses",
+    "parameters",
+    "headers",
+    "requestBodies",
+    "securitySchemes",
+    "links",
+    "callbacks",
+    "content",
+];
+
+// position of walked node in document, tells keywords from names
+#[derive(Clone, Copy, Default)]
+pub struct SchemaWalk {
+    names: bool,
+}
+
+impl SchemaWalk {
+    // keys of current object are names, not keywords
+    pub fn has_names(&self) -> bool {
+        self.names
+    }
+
+    // value of key is instance, not walked further
+    pub fn is_instance(&self, key: &str) -> bool {
+        !self.names && INSTANCE_KEYWORDS.contains(&key)
+    }
+
+    // position of value of key
+    pub fn enter(&self, key: &str) -> Self {
+        Self {
+            names: !self.names && NAME_MAPS.contains(&key),
+        }
+    }
+}
+
+// visits every object of document which is neither instance nor map of names,
+// children before their parent
+pub fn walk_schemas_mut<F>(node: &mut Value, f: &mut F)
+where
+    F: FnMut(&mut Map<String, Value>),
+{
+    walk_mut(node, SchemaWalk::default(), f)
+}
+
+fn walk_mut<F>(node: &mut Value, walk: SchemaWalk, f: &mut F)
+where
+    F: FnMut(&mut Map<String, Value>),
+{
+    match node {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if !walk.is_instance(key) {
+                    walk_mut(value, walk.enter(key), f);
+                }
+            }
+
+            if !walk.has_names() {
+                f(map);
+            }
+        }
+        Value::Array(list) => {
+            for value in list {
+                walk_mut(value, SchemaWalk::default(), f);
+            }
+        }
+        _ => {}
+    }
+}
 
 pub fn each_node_mut<F>(
     root: &mut Value,
@@ -208,19 +318,8 @@ pub fn fill_parameters(phrase: &str, data: (impl Serialize + Clone)) -> Result<S
             parameter = !parameter;
 
             if !current.is_empty() {
-                let path = format!("/{}", current.replace('.', "/"));
-
-                if let Some(value) = serde_json::json!(data).pointer(&path) {
-                    result.push_str(&match value {
-                        Value::String(s) => Ok(s.clone()),
-                        Value::Number(n) => Ok(n.to_string()),
-                        _ => Err(Error::CannotFillParameters(path)),
-                    }?);
-
-                    current.clear();
-                } else {
-                    return Err(Error::CannotFillParameters(path));
-                }
+                result.push_str(&fill_parameter(&current, &data)?);
+                current.clear();
             }
 
             continue;
@@ -234,6 +333,39 @@ pub fn fill_parameters(phrase: &str, data: (impl Serialize + Clone)) -> Result<S
     Ok(result)
 }
 
+// parameter may end with case conversion, ex. %tag.snake%
+fn fill_parameter(parameter: &str, data: &(impl Serialize + Clone)) -> Result<String, Error> {
+    let data = serde_json::json!(data);
+    let path = format!("/{}", parameter.replace('.', "/"));
+
+    let (value, case) = match data.pointer(&path) {
+        Some(value) => (value, None),
+        None => match path.rsplit_once('/') {
+            Some((parent, case)) => (
+                data.pointer(parent)
+                    .ok_or_else(|| Error::CannotFillParameters(path.clone()))?,
+                Some(case),
+            ),
+            None => return Err(Error::CannotFillParameters(path)),
+        },
+    };
+
+    let value = match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        _ => return Err(Error::CannotFillParameters(path)),
+    };
+
+    match case {
+        None => Ok(value),
+        Some("snake") => Ok(value.to_snake_case()),
+        Some("kebab") => Ok(value.to_kebab_case()),
+        Some("camel") => Ok(value.to_camel_case()),
+        Some("pascal") => Ok(value.to_pascal_case()),
+        Some(_) => Err(Error::CannotFillParameters(path)),
+    }
+}
+
 pub fn bump_suffix_number(phrase: &str) -> String {
     let chars = phrase.chars();
     let mut result: Vec<u32> = vec![];
@@ -257,11 +389,12 @@ pub fn bump_suffix_number(phrase: &str) -> String {
     }
 }
 
-#[derive(Default)]
+#[derive(Debug, Default)]
 pub struct Filter {
     conditions: Vec<ConditionSet>,
 }
 
+#[derive(Debug)]
 pub struct ConditionSet {
     conditions: Vec<Condition>,
 }
@@ -288,6 +421,7 @@ impl ConditionSet {
     }
 }
 
+#[derive(Debug)]
 struct Condition {
     pub field: String, // json pointer
     pub operator: ConditionOperator,
@@ -324,7 +458,7 @@ impl Condition {
     }
 }
 
-#[derive(Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq)]
 enum ConditionOperator {
     Eq,
     Eqq,
@@ -413,6 +547,54 @@ mod tests {
         assert_eq!(result, "some variable 10 ok 2".to_string());
     }
 
+    #[test]
+    fn test_fill_parameters_with_case() {
+        let given = serde_json::json!({ "tag": "UserAccounts" });
+
+        assert_eq!(
+            fill_parameters("api/%tag.snake%.rs", &given).unwrap(),
+            "api/user_accounts.rs".to_string()
+        );
+        assert_eq!(
+            fill_parameters("%tag.kebab%/index.ts", &given).unwrap(),
+            "user-accounts/index.ts".to_string()
+        );
+        assert_eq!(true, fill_parameters("%tag.unknown%", &given).is_err());
+    }
+
+    #[test]
+    fn test_schema_walk() {
+        let mut schema = serde_json::json!({
+            "properties": {
+                "default": { "type": "string" },
+                "if": { "default": { "type": "object" } }
+            },
+            "default": { "type": "object" },
+            "responses": { "default": { "description": "error" } }
+        });
+
+        let mut visited = vec![];
+        walk_schemas_mut(&mut schema, &mut |map| {
+            visited.push(map.keys().cloned().collect::<Vec<_>>().join(","))
+        });
+
+        assert_eq!(
+            visited,
+            vec![
+                "type",
+                "default",
+                "description",
+                "properties,default,responses"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pointer_unescape() {
+        assert_eq!(pointer_unescape("a~1b~0c~01"), "a/b~c~1");
+        assert_eq!(pointer_unescape(&pointer_escape("~1/~")), "~1/~");
+    }
+
     #[test]
     fn test_argument_extractor() {
         let given = "codegen openapi -f - --templates-dir codegen/ --format \"gofmt -w\" --target-dir pkg/client/ -o namespace=testing -o clientName=TestingClient";
@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::semantic::Finding;
+use crate::error::Error;
+use crate::tools;
+
+pub static BASELINE_MODES: &[&str] = &["write", "check"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BaselineMode {
+    Write,
+    Check,
+}
+
+impl std::str::FromStr for BaselineMode {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<BaselineMode, Self::Err> {
+        match input {
+            "write" => Ok(Self::Write),
+            "check" => Ok(Self::Check),
+            s => Err(Error::ValidationInvalidBaselineMode(s.to_string())),
+        }
+    }
+}
+
+// findings accepted at the time of writing, only new ones fail validation
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    findings: Vec<Finding>,
+}
+
+impl Baseline {
+    pub fn new(findings: Vec<Finding>) -> Self {
+        Self { findings }
+    }
+
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| Error::ValidationBaselineError(format!("{}: {}", path, e)))?;
+
+        serde_json::from_str(&content)
+            .map_err(|e| Error::ValidationBaselineError(format!("{}: {}", path, e)))
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        let content = serde_json::to_string_pretty(self).map_err(Error::SerdeJsonError)?;
+
+        std::fs::write(path, content)
+            .map_err(|e| Error::ValidationBaselineError(format!("{}: {}", path, e)))
+    }
+
+    pub fn filter(&self, findings: Vec<Finding>) -> Vec<Finding> {
+        findings
+            .into_iter()
+            .filter(|f| !self.findings.contains(f))
+            .collect()
+    }
+}
+
+// findings are ignored when any node on the pointer path lists the rule in x-schematools-ignore
+pub fn is_ignored(root: &Value, finding: &Finding, extension_prefix: &Option<String>) -> bool {
+    let key = tools::extension(extension_prefix, "ignore");
+
+    let lists = |node: &Value| {
+        node.get(&key)
+            .and_then(|v| v.as_array())
+            .map(|rules| rules.iter().any(|r| r.as_str() == Some(&finding.rule)))
+            .unwrap_or(false)
+    };
+
+    let mut node = root;
+    if lists(node) {
+        return true;
+    }
+
+    for token in finding.scope.split('/').skip(1) {
+        let token = tools::pointer_unescape(token);
+
+        node = match node {
+            Value::Object(map) => match map.get(&token) {
+                Some(n) => n,
+                None => return false,
+            },
+            Value::Array(list) => match token.parse::<usize>().ok().and_then(|i| list.get(i)) {
+                Some(n) => n,
+                None => return false,
+            },
+            _ => return false,
+        };
+
+        if lists(node) {
+            return true;
+        }
+    }
+
+    false
+}
+
+pub fn without_ignored(
+    root: &Value,
+    findings: Vec<Finding>,
+    extension_prefix: &Option<String>,
+) -> Vec<Finding> {
+    findings
+        .into_iter()
+        .filter(|f| !is_ignored(root, f, extension_prefix))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn finding(rule: &str, scope: &str) -> Finding {
+        Finding::new(rule, scope.to_string(), "message".to_string())
+    }
+
+    #[test]
+    fn test_inline_ignore() {
+        let root = json!({
+            "paths": {
+                "/a/{id}": {
+                    "x-schematools-ignore": ["unused-path-parameter"],
+                    "get": {}
+                },
+                "/b": { "get": {} }
+            }
+        });
+
+        assert_eq!(
+            true,
+            is_ignored(
+                &root,
+                &finding("unused-path-parameter", "/paths/~1a~1{id}/get"),
+                &None
+            )
+        );
+        assert_eq!(
+            false,
+            is_ignored(
+                &root,
+                &finding("duplicate-operation-id", "/paths/~1a~1{id}/get"),
+                &None
+            )
+        );
+        assert_eq!(
+            false,
+            is_ignored(
+                &root,
+                &finding("unused-path-parameter", "/paths/~1b/get"),
+                &None
+            )
+        );
+    }
+
+    #[test]
+    fn test_inline_ignore_with_extension_prefix() {
+        let root = json!({
+            "paths": {
+                "/a": {
+                    "x-acme-ignore": ["unused-path-parameter"],
+                    "get": {}
+                }
+            }
+        });
+        let prefix = Some("x-acme".to_string());
+
+        assert_eq!(
+            true,
+            is_ignored(
+                &root,
+                &finding("unused-path-parameter", "/paths/~1a/get"),
+                &prefix
+            )
+        );
+        assert_eq!(
+            false,
+            is_ignored(
+                &root,
+                &finding("unused-path-parameter", "/paths/~1a/get"),
+                &None
+            )
+        );
+    }
+
+    #[test]
+    fn test_baseline_reports_only_new_findings() {
+        let baseline = Baseline::new(vec![finding("ambiguous-path", "/paths/~1a")]);
+
+        let new = baseline.filter(vec![
+            finding("ambiguous-path", "/paths/~1a"),
+            finding("ambiguous-path", "/paths/~1b"),
+        ]);
+
+        assert_eq!(new, vec![finding("ambiguous-path", "/paths/~1b")]);
+    }
+}
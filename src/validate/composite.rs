@@ -0,0 +1,286 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::spec_registry::{self, Reference};
+use crate::tools::pointer_escape;
+
+// specification of one service of validated set, named like in registry
+pub struct Service {
+    pub name: String,
+    pub schema: Schema,
+}
+
+impl Service {
+    // argument in form of `name=path`, name defaults to file name without extension
+    pub fn name_of(argument: &str) -> (Option<String>, String) {
+        match argument.split_once('=') {
+            Some((name, path)) if !name.is_empty() && !name.contains('/') => {
+                (Some(name.to_string()), path.to_string())
+            }
+            _ => (None, argument.to_string()),
+        }
+    }
+}
+
+// references of one service to another one, in range of versions
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Dependency {
+    pub consumer: String,
+    pub provider: String,
+    pub range: String,
+    pub version: Option<String>,
+    pub source: Option<String>,
+    pub references: Vec<String>,
+    pub problems: Vec<String>,
+}
+
+// `$ref` to other service with its location in consumer
+struct Site {
+    pointer: String,
+    reference: String,
+    fragment: String,
+}
+
+// every `$ref` in form of `registry:orders-api@^2#/components/schemas/Order` is resolved against
+// specification of the set with the same name or, when there's none, the published one
+pub fn check<F>(services: &[Service], fetch: F) -> Vec<Dependency>
+where
+    F: Fn(&Reference) -> Result<Schema, Error>,
+{
+    let mut dependencies = vec![];
+
+    for service in services {
+        let mut references = vec![];
+        collect(
+            service.schema.get_body(),
+            &mut String::new(),
+            &mut references,
+        );
+
+        // references grouped by provider and range, as written
+        let mut groups: BTreeMap<(String, String), Vec<Site>> = BTreeMap::new();
+        for (pointer, reference) in references {
+            let (target, fragment) = reference[spec_registry::PREFIX.len()..]
+                .split_once('#')
+                .unwrap_or((&reference[spec_registry::PREFIX.len()..], ""));
+            let (name, range) = target.split_once('@').unwrap_or((target, "*"));
+
+            groups
+                .entry((name.to_string(), range.to_string()))
+                .or_default()
+                .push(Site {
+                    fragment: fragment.to_string(),
+                    pointer,
+                    reference: reference.clone(),
+                });
+        }
+
+        for ((name, range), references) in groups {
+            let mut dependency = Dependency {
+                consumer: service.name.clone(),
+                provider: name.clone(),
+                range: range.clone(),
+                version: None,
+                source: None,
+                references: references.iter().map(|r| r.reference.clone()).collect(),
+                problems: vec![],
+            };
+
+            let reference = match format!("{}@{}", name, range).parse::<Reference>() {
+                Ok(reference) => reference,
+                Err(e) => {
+                    dependency.problems.push(e.to_string());
+                    dependencies.push(dependency);
+                    continue;
+                }
+            };
+
+            let fetched;
+            let provider = match services.iter().find(|s| s.name == name) {
+                Some(provider) => {
+                    dependency.source = Some("set".to_string());
+                    provider.schema.get_body()
+                }
+                None => match fetch(&reference) {
+                    Ok(schema) => {
+                        dependency.source = Some("registry".to_string());
+                        fetched = schema;
+                        fetched.get_body()
+                    }
+                    Err(e) => {
+                        dependency.problems.push(e.to_string());
+                        dependencies.push(dependency);
+                        continue;
+                    }
+                },
+            };
+
+            let version = provider
+                .pointer("/info/version")
+                .and_then(|v| v.as_str())
+                .map(|v| v.to_string());
+            match version
+                .as_deref()
+                .map(|v| semver::Version::parse(v.trim_start_matches('v')))
+            {
+                Some(Ok(v)) if reference.range.matches(&v) => {}
+                Some(Ok(v)) => dependency
+                    .problems
+                    .push(format!("{} {} does not satisfy {}", name, v, range)),
+                _ => dependency
+                    .problems
+                    .push(format!("{} has no semver version in /info/version", name)),
+            }
+            dependency.version = version;
+
+            for site in &references {
+                if provider.pointer(&site.fragment).is_none() {
+                    dependency.problems.push(format!(
+                        "{}: {} does not exist",
+                        site.pointer, site.reference
+                    ));
+                }
+            }
+
+            dependencies.push(dependency);
+        }
+    }
+
+    dependencies
+}
+
+fn collect(node: &Value, pointer: &mut String, references: &mut Vec<(String, String)>) {
+    match node {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get("$ref") {
+                if reference.starts_with(spec_registry::PREFIX) {
+                    references.push((pointer.clone(), reference.clone()));
+                }
+            }
+
+            for (key, value) in map {
+                let len = pointer.len();
+                pointer.push('/');
+                pointer.push_str(&pointer_escape(key));
+                collect(value, pointer, references);
+                pointer.truncate(len);
+            }
+        }
+        Value::Array(list) => {
+            for (i, value) in list.iter().enumerate() {
+                let len = pointer.len();
+                pointer.push_str(&format!("/{}", i));
+                collect(value, pointer, references);
+                pointer.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn service(name: &str, body: Value) -> Service {
+        Service {
+            name: name.to_string(),
+            schema: Schema::from_json(body),
+        }
+    }
+
+    #[test]
+    fn test_cross_service_references() {
+        let services = vec![
+            service(
+                "checkout-api",
+                json!({
+                    "openapi": "3.0.0",
+                    "info": { "version": "1.0.0" },
+                    "components": {
+                        "schemas": {
+                            "Cart": {
+                                "properties": {
+                                    "order": { "$ref": "registry:orders-api@^2#/components/schemas/Order" },
+                                    "total": { "$ref": "registry:orders-api@^2#/components/schemas/Money" },
+                                    "user": { "$ref": "registry:users-api@^1#/components/schemas/User" },
+                                    "coupon": { "$ref": "registry:coupons-api#/components/schemas/Coupon" }
+                                }
+                            }
+                        }
+                    }
+                }),
+            ),
+            service(
+                "orders-api",
+                json!({
+                    "openapi": "3.0.0",
+                    "info": { "version": "2.3.0" },
+                    "components": { "schemas": { "Order": { "type": "object" } } }
+                }),
+            ),
+            service(
+                "users-api",
+                json!({ "openapi": "3.0.0", "info": { "version": "2.0.0" }, "components": {} }),
+            ),
+        ];
+
+        let dependencies = check(&services, |reference| {
+            Err(Error::SpecRegistryNoMatchingVersion(reference.name.clone()))
+        });
+
+        assert_eq!(
+            dependencies
+                .iter()
+                .map(|d| (d.provider.as_str(), d.source.as_deref(), d.problems.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "coupons-api",
+                    None,
+                    vec!["No published version matches coupons-api".to_string()]
+                ),
+                (
+                    "orders-api",
+                    Some("set"),
+                    vec!["/components/schemas/Cart/properties/total: registry:orders-api@^2#/components/schemas/Money does not exist".to_string()]
+                ),
+                (
+                    "users-api",
+                    Some("set"),
+                    vec![
+                        "users-api 2.0.0 does not satisfy ^1".to_string(),
+                        "/components/schemas/Cart/properties/user: registry:users-api@^1#/components/schemas/User does not exist".to_string()
+                    ]
+                ),
+            ]
+        );
+        assert!(dependencies.iter().all(|d| d.consumer == "checkout-api"));
+
+        let published = check(&services[..1], |_| {
+            Ok(Schema::from_json(json!({
+                "info": { "version": "v2.4.1" },
+                "components": { "schemas": { "Order": {}, "Money": {}, "User": {}, "Coupon": {} } }
+            })))
+        });
+        assert_eq!(published[1].version.as_deref(), Some("v2.4.1"));
+        assert_eq!(published[1].source.as_deref(), Some("registry"));
+        assert_eq!(published[2].problems.len(), 1);
+
+        assert_eq!(
+            Service::name_of("orders-api=specs/openapi.yaml"),
+            (
+                Some("orders-api".to_string()),
+                "specs/openapi.yaml".to_string()
+            )
+        );
+        assert_eq!(
+            Service::name_of("specs/orders-api.yaml"),
+            (None, "specs/orders-api.yaml".to_string())
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::schema::{path_to_url, Schema};
+use crate::tools::{pointer_escape, HTTP_METHODS};
+
+use super::semantic::{Finding, Severity};
+
+fn default_opt_out() -> String {
+    "x-opt-out-headers".to_string()
+}
+
+// organization standard headers required on operations
+#[derive(Debug, Deserialize)]
+pub struct Conventions {
+    #[serde(default)]
+    headers: Vec<StandardHeader>,
+
+    // extension of operation or path item listing headers it deliberately does not declare
+    #[serde(default = "default_opt_out")]
+    opt_out: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct StandardHeader {
+    name: String,
+
+    // methods of operations requiring header, all by default
+    #[serde(default)]
+    methods: Vec<String>,
+
+    #[serde(default)]
+    severity: Severity,
+}
+
+impl Conventions {
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let config = Schema::load_url(path_to_url(path.to_string())?)?;
+
+        serde_json::from_value(config.get_body().clone()).map_err(Error::SerdeJsonError)
+    }
+
+    pub fn check(&self, root: &Value) -> Vec<Finding> {
+        let mut findings = vec![];
+
+        let paths = match root.get("paths") {
+            Some(Value::Object(paths)) => paths,
+            _ => return findings,
+        };
+
+        for (path, item) in paths {
+            for method in HTTP_METHODS {
+                let operation = match item.get(*method).and_then(|o| o.as_object()) {
+                    Some(operation) => operation,
+                    None => continue,
+                };
+
+                let mut declared = headers(root, operation.get("parameters"));
+                declared.extend(headers(root, item.get("parameters")));
+
+                let mut opted_out = self.opted_out(operation.get(&self.opt_out));
+                opted_out.extend(self.opted_out(item.get(&self.opt_out)));
+
+                for header in &self.headers {
+                    let name = header.name.to_lowercase();
+
+                    let applies = header.methods.is_empty()
+                        || header
+                            .methods
+                            .iter()
+                            .any(|m| m.eq_ignore_ascii_case(method));
+                    if !applies || declared.contains(&name) || opted_out.contains(&name) {
+                        continue;
+                    }
+
+                    findings.push(
+                        Finding::new(
+                            "standard-header",
+                            format!("/paths/{}/{}", pointer_escape(path), method),
+                            format!(
+                                "{} {} does not declare {} header nor opts out of it with {}",
+                                method.to_uppercase(),
+                                path,
+                                header.name,
+                                self.opt_out
+                            ),
+                        )
+                        .with_severity(header.severity.clone()),
+                    );
+                }
+            }
+        }
+
+        findings
+    }
+
+    fn opted_out(&self, node: Option<&Value>) -> Vec<String> {
+        match node {
+            Some(Value::Array(list)) => list
+                .iter()
+                .filter_map(|h| h.as_str())
+                .map(|h| h.to_lowercase())
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+// lowercase names of header parameters, local references are followed
+fn headers(root: &Value, node: Option<&Value>) -> Vec<String> {
+    node.and_then(|p| p.as_array())
+        .map(|list| {
+            list.iter()
+                .filter_map(|p| match p.get("$ref").and_then(|r| r.as_str()) {
+                    Some(r) => r.strip_prefix('#').and_then(|l| root.pointer(l)),
+                    None => Some(p),
+                })
+                .filter(|p| p.get("in").and_then(|v| v.as_str()) == Some("header"))
+                .filter_map(|p| p.get("name").and_then(|v| v.as_str()))
+                .map(|n| n.to_lowercase())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_standard_headers() {
+        let conventions: Conventions = serde_json::from_value(json!({
+            "headers": [
+                { "name": "traceparent", "severity": "warning" },
+                { "name": "Idempotency-Key", "methods": ["post"] }
+            ]
+        }))
+        .unwrap();
+
+        let root = json!({
+            "paths": {
+                "/users": {
+                    "parameters": [{ "$ref": "#/components/parameters/Trace" }],
+                    "get": {},
+                    "post": {}
+                },
+                "/health": {
+                    "x-opt-out-headers": ["traceparent"],
+                    "get": {},
+                    "post": {
+                        "x-opt-out-headers": ["idempotency-key"]
+                    }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "Trace": { "name": "TraceParent", "in": "header" }
+                }
+            }
+        });
+
+        let findings = conventions
+            .check(&root)
+            .into_iter()
+            .map(|f| (f.scope, f.severity))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            findings,
+            vec![("/paths/~1users/post".to_string(), Severity::Error)]
+        );
+
+        let findings = conventions.check(&json!({
+            "paths": { "/users": { "get": { "parameters": [{ "name": "x", "in": "query" }] } } }
+        }));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Warning);
+    }
+}
@@ -0,0 +1,178 @@
+use serde_json::Value;
+
+// schema object of openapi 3.0, subset of json schema draft 4 with openapi additions
+static OPENAPI_30: &[&str] = &[
+    "$ref",
+    "title",
+    "description",
+    "type",
+    "format",
+    "enum",
+    "default",
+    "multipleOf",
+    "maximum",
+    "exclusiveMaximum",
+    "minimum",
+    "exclusiveMinimum",
+    "maxLength",
+    "minLength",
+    "pattern",
+    "maxItems",
+    "minItems",
+    "uniqueItems",
+    "maxProperties",
+    "minProperties",
+    "required",
+    "allOf",
+    "oneOf",
+    "anyOf",
+    "not",
+    "items",
+    "properties",
+    "additionalProperties",
+    "nullable",
+    "discriminator",
+    "readOnly",
+    "writeOnly",
+    "xml",
+    "externalDocs",
+    "example",
+    "deprecated",
+];
+
+// json schema 2020-12 used by openapi 3.1
+static JSONSCHEMA_2020_12: &[&str] = &[
+    "$schema",
+    "$id",
+    "$ref",
+    "$anchor",
+    "$dynamicRef",
+    "$dynamicAnchor",
+    "$vocabulary",
+    "$comment",
+    "$defs",
+    "allOf",
+    "anyOf",
+    "oneOf",
+    "not",
+    "if",
+    "then",
+    "else",
+    "dependentSchemas",
+    "prefixItems",
+    "items",
+    "contains",
+    "properties",
+    "patternProperties",
+    "additionalProperties",
+    "propertyNames",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+    "type",
+    "enum",
+    "const",
+    "multipleOf",
+    "maximum",
+    "exclusiveMaximum",
+    "minimum",
+    "exclusiveMinimum",
+    "maxLength",
+    "minLength",
+    "pattern",
+    "maxItems",
+    "minItems",
+    "uniqueItems",
+    "maxContains",
+    "minContains",
+    "maxProperties",
+    "minProperties",
+    "required",
+    "dependentRequired",
+    "title",
+    "description",
+    "default",
+    "deprecated",
+    "readOnly",
+    "writeOnly",
+    "examples",
+    "format",
+    "contentEncoding",
+    "contentMediaType",
+    "contentSchema",
+];
+
+// keywords added to json schema by openapi 3.1
+static OPENAPI_31: &[&str] = &["discriminator", "xml", "externalDocs", "example"];
+
+// keywords known in dialect of openapi document, 3.1 is assumed when version is missing
+pub fn vocabulary(root: &Value) -> Vec<&'static str> {
+    match root.get("openapi").and_then(|v| v.as_str()) {
+        Some(version) if version.starts_with("3.0") => OPENAPI_30.to_vec(),
+        _ => [JSONSCHEMA_2020_12, OPENAPI_31].concat(),
+    }
+}
+
+// closest keyword differing only by case or by few edits
+pub fn closest<'a>(keyword: &str, vocabulary: &[&'a str]) -> Option<&'a str> {
+    if let Some(found) = vocabulary.iter().find(|k| k.eq_ignore_ascii_case(keyword)) {
+        return Some(found);
+    }
+
+    let limit = (keyword.chars().count() / 4).max(1);
+
+    vocabulary
+        .iter()
+        .map(|k| (distance(keyword, k), *k))
+        .filter(|(d, _)| *d <= limit)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, k)| k)
+}
+
+fn distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            current.push(
+                (previous[j] + cost)
+                    .min(previous[j + 1] + 1)
+                    .min(current[j] + 1),
+            );
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_closest_keyword() {
+        let openapi = vocabulary(&json!({"openapi": "3.0.3"}));
+        let jsonschema = vocabulary(&json!({"openapi": "3.1.0"}));
+
+        assert_eq!(closest("requierd", &openapi), Some("required"));
+        assert_eq!(closest("Properties", &openapi), Some("properties"));
+        assert_eq!(
+            closest("additionalproperties", &openapi),
+            Some("additionalProperties")
+        );
+        assert_eq!(closest("typ", &openapi), Some("type"));
+        assert_eq!(closest("foo", &openapi), None);
+        assert_eq!(closest("$defs", &openapi), None);
+        assert_eq!(closest("const", &openapi), None);
+        assert_eq!(
+            closest("dependentRequird", &jsonschema),
+            Some("dependentRequired")
+        );
+    }
+}
@@ -1,10 +1,23 @@
-use jsonschema::{Draft, JSONSchema};
+use jsonschema::{Draft, JSONSchema, ValidationError};
 use serde_json::{from_slice, Value};
 
 use crate::error::Error;
+use crate::formats::Formats;
+use crate::process::source_map::SourceMap;
 use crate::schema::Schema;
 
-pub fn validate_openapi(schema: &Schema) -> Result<(), Error> {
+pub mod baseline;
+pub mod composite;
+pub mod conventions;
+pub mod keywords;
+pub mod report;
+pub mod semantic;
+
+use baseline::{Baseline, BaselineMode};
+use conventions::Conventions;
+use semantic::{Finding, Severity};
+
+fn openapi_errors(schema: &Schema) -> Vec<(Option<String>, String)> {
     let value = schema.get_body();
 
     let result: Result<Value, _> =
@@ -19,14 +32,223 @@ pub fn validate_openapi(schema: &Schema) -> Result<(), Error> {
     let result = specification.validate(value);
 
     match result {
-        Err(errors) => {
-            for e in errors {
-                log::error!("{}", e.to_string());
+        Err(errors) => errors.map(|e| instance_error(&e)).collect(),
+        _ => vec![],
+    }
+}
+
+// empty instance path is the document itself
+fn instance_error(e: &ValidationError) -> (Option<String>, String) {
+    let pointer = e.instance_path.to_string();
+
+    (Some(pointer).filter(|p| !p.is_empty()), e.to_string())
+}
+
+pub fn validate_openapi(schema: &Schema, sources: Option<&SourceMap>) -> Result<(), Error> {
+    let errors = openapi_errors(schema);
+
+    for (pointer, e) in &errors {
+        match (sources, pointer) {
+            (Some(sources), Some(pointer)) => {
+                log::error!("{} (from {})", e, sources.lookup(pointer))
             }
+            _ => log::error!("{}", e),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::SchemaValidation(schema.get_url().to_string()))
+    }
+}
+
+// semantic and conventions findings, both optional
+fn rule_findings(
+    schema: &Schema,
+    semantic: bool,
+    formats: &Formats,
+    conventions: Option<&Conventions>,
+) -> Vec<Finding> {
+    let mut findings = vec![];
+
+    if semantic {
+        findings.extend(semantic::check_openapi(schema, formats));
+    }
+
+    if let Some(conventions) = conventions {
+        findings.extend(conventions.check(schema.get_body()));
+    }
+
+    findings
+}
+
+// schema errors are reported as findings of `schema` rule, inline ignores are applied
+pub fn openapi_findings(
+    schema: &Schema,
+    semantic: bool,
+    formats: &Formats,
+    conventions: Option<&Conventions>,
+    extension_prefix: &Option<String>,
+) -> Vec<Finding> {
+    let mut findings = openapi_errors(schema)
+        .into_iter()
+        .map(|(pointer, message)| Finding::new("schema", pointer.unwrap_or_default(), message))
+        .collect::<Vec<_>>();
+
+    findings.extend(rule_findings(schema, semantic, formats, conventions));
+
+    baseline::without_ignored(schema.get_body(), findings, extension_prefix)
+}
+
+// messages point to original files of bundled specification
+fn with_sources(findings: Vec<Finding>, sources: Option<&SourceMap>) -> Vec<Finding> {
+    match sources {
+        Some(sources) => findings
+            .into_iter()
+            .map(|mut f| {
+                if !f.scope.is_empty() {
+                    f.message = format!("{} (from {})", f.message, sources.lookup(&f.scope));
+                }
+                f
+            })
+            .collect(),
+        None => findings,
+    }
+}
+
+fn report_findings(findings: Vec<Finding>) -> report::Report {
+    let mut report = report::Report::default();
+
+    for finding in findings {
+        let pointer = Some(finding.scope).filter(|s| !s.is_empty());
 
-            Err(Error::SchemaValidation(schema.get_url().to_string()))
+        if finding.rule == "schema" {
+            report.add(pointer, finding.message);
+        } else {
+            report.add(pointer, format!("[{}] {}", finding.rule, finding.message));
         }
-        _ => Ok(()),
+    }
+
+    report
+}
+
+// gathers all schema and optionally semantic failures in one pass
+pub fn report_openapi(
+    schema: &Schema,
+    semantic: bool,
+    formats: &Formats,
+    conventions: Option<&Conventions>,
+    sources: Option<&SourceMap>,
+    fail_on: &Severity,
+    extension_prefix: &Option<String>,
+) -> report::Report {
+    report_findings(with_sources(
+        semantic::failing(
+            openapi_findings(schema, semantic, formats, conventions, extension_prefix),
+            fail_on,
+        ),
+        sources,
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn validate_openapi_baseline(
+    schema: &Schema,
+    semantic: bool,
+    formats: &Formats,
+    conventions: Option<&Conventions>,
+    sources: Option<&SourceMap>,
+    mode: &BaselineMode,
+    path: &str,
+    fail_on: &Severity,
+    extension_prefix: &Option<String>,
+) -> Result<(), Error> {
+    let findings = openapi_findings(schema, semantic, formats, conventions, extension_prefix);
+
+    match mode {
+        BaselineMode::Write => {
+            log::info!("{} findings written to baseline {}", findings.len(), path);
+
+            Baseline::new(findings).save(path)
+        }
+        BaselineMode::Check => {
+            let new = Baseline::load(path)?.filter(findings);
+
+            finish_report(
+                schema,
+                report_findings(with_sources(semantic::failing(new, fail_on), sources)),
+            )
+        }
+    }
+}
+
+// document is checked against draft 4 metaschema first, so all errors are reported,
+// compilation stops on the first one
+pub fn report_jsonschema(schema: &Schema) -> report::Report {
+    let mut report = report::Report::default();
+
+    let result: Result<Value, _> =
+        from_slice(include_bytes!("../../resources/jsonschema/draft-04.json"));
+    let metaschema = JSONSchema::options()
+        .with_draft(Draft::Draft4)
+        .compile(&result.unwrap())
+        .unwrap();
+
+    if let Err(errors) = metaschema.validate(schema.get_body()) {
+        for (pointer, message) in errors.map(|e| instance_error(&e)) {
+            report.add(pointer, message);
+        }
+    }
+
+    if report.is_empty() {
+        if let Err(Error::SchemaCompilation { reason, .. }) = validate_jsonschema(schema) {
+            report.add(None, reason);
+        }
+    }
+
+    report
+}
+
+pub fn finish_report(schema: &Schema, report: report::Report) -> Result<(), Error> {
+    report.print();
+
+    if report.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::SchemaValidation(schema.get_url().to_string()))
+    }
+}
+
+pub fn validate_openapi_semantic(
+    schema: &Schema,
+    semantic: bool,
+    formats: &Formats,
+    conventions: Option<&Conventions>,
+    sources: Option<&SourceMap>,
+    fail_on: &Severity,
+    extension_prefix: &Option<String>,
+) -> Result<(), Error> {
+    let findings = with_sources(
+        semantic::failing(
+            baseline::without_ignored(
+                schema.get_body(),
+                rule_findings(schema, semantic, formats, conventions),
+                extension_prefix,
+            ),
+            fail_on,
+        ),
+        sources,
+    );
+
+    for finding in &findings {
+        log::error!("{}", finding);
+    }
+
+    if findings.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::SchemaValidation(schema.get_url().to_string()))
     }
 }
 
@@ -45,3 +267,28 @@ pub fn validate_jsonschema(schema: &Schema) -> Result<(), Error> {
         _ => Ok(()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_report_jsonschema_points_to_every_error() {
+        let schema = Schema::from_json(json!({
+            "type": "object",
+            "properties": {
+                "a": {"type": "strin"},
+                "b": {"minLength": -1}
+            },
+            "required": "a"
+        }));
+
+        let lines = report_jsonschema(&schema).lines();
+
+        assert!(lines.contains(&"/required:".to_string()));
+        assert!(lines.contains(&"/properties/a/type:".to_string()));
+        assert!(lines.contains(&"/properties/b/minLength:".to_string()));
+        assert_eq!(lines.last().unwrap(), "3 errors found in 3 locations");
+    }
+}
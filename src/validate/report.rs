@@ -0,0 +1,121 @@
+// failures without pointer belong to document itself
+static DOCUMENT_POINTER: &str = "#";
+
+// validation failures gathered in one pass and grouped by pointer
+pub struct Report {
+    groups: Vec<(String, Vec<String>)>,
+    max_repeated: usize,
+}
+
+impl Default for Report {
+    fn default() -> Self {
+        Self {
+            groups: vec![],
+            max_repeated: 3,
+        }
+    }
+}
+
+impl Report {
+    pub fn with_max_repeated(mut self, value: usize) -> Self {
+        self.max_repeated = value;
+        self
+    }
+
+    pub fn add(&mut self, pointer: Option<String>, message: String) {
+        let pointer = pointer.unwrap_or_else(|| DOCUMENT_POINTER.to_string());
+
+        match self.groups.iter_mut().find(|(p, _)| *p == pointer) {
+            Some((_, messages)) => messages.push(message),
+            None => self.groups.push((pointer, vec![message])),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    pub fn count(&self) -> usize {
+        self.groups.iter().map(|(_, m)| m.len()).sum()
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![];
+        let mut seen: Vec<(&str, usize)> = vec![];
+        let mut truncated = 0;
+
+        for (pointer, messages) in &self.groups {
+            let mut group = vec![];
+
+            for message in messages {
+                let occurences = match seen.iter_mut().find(|(m, _)| *m == message.as_str()) {
+                    Some((_, count)) => {
+                        *count += 1;
+                        *count
+                    }
+                    None => {
+                        seen.push((message.as_str(), 1));
+                        1
+                    }
+                };
+
+                if occurences > self.max_repeated {
+                    truncated += 1;
+                } else {
+                    group.push(format!("  - {}", message));
+                }
+            }
+
+            if !group.is_empty() {
+                lines.push(format!("{}:", pointer));
+                lines.append(&mut group);
+            }
+        }
+
+        if truncated > 0 {
+            lines.push(format!("... {} repeated messages truncated", truncated));
+        }
+
+        lines.push(format!(
+            "{} errors found in {} locations",
+            self.count(),
+            self.groups.len()
+        ));
+
+        lines
+    }
+
+    pub fn print(&self) {
+        for line in self.lines() {
+            log::error!("{}", line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_groups_and_truncates() {
+        let mut report = Report::default().with_max_repeated(1);
+
+        report.add(Some("/paths/~1a".to_string()), "invalid".to_string());
+        report.add(Some("/paths/~1b".to_string()), "invalid".to_string());
+        report.add(Some("/paths/~1a".to_string()), "missing".to_string());
+        report.add(None, "other".to_string());
+
+        assert_eq!(
+            report.lines(),
+            vec![
+                "/paths/~1a:",
+                "  - invalid",
+                "  - missing",
+                "#:",
+                "  - other",
+                "... 1 repeated messages truncated",
+                "4 errors found in 3 locations",
+            ]
+        );
+    }
+}
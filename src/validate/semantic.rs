@@ -0,0 +1,1041 @@
+use std::collections::{HashMap, HashSet};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::analyze::graph::Graph;
+use crate::error::Error;
+use crate::formats::Formats;
+use crate::schema::Schema;
+use crate::tools::{pointer_escape, SchemaWalk, HTTP_METHODS};
+
+use super::keywords;
+
+pub static SEVERITIES: &[&str] = &["info", "warning", "error"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    #[default]
+    Error,
+}
+
+impl std::str::FromStr for Severity {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Severity, Self::Err> {
+        match input {
+            "info" => Ok(Self::Info),
+            "warning" => Ok(Self::Warning),
+            "error" => Ok(Self::Error),
+            s => Err(Error::ValidationInvalidSeverity(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    pub rule: String,
+    pub scope: String,
+    pub message: String,
+
+    #[serde(default)]
+    pub severity: Severity,
+}
+
+impl Finding {
+    pub fn new(rule: &str, scope: String, message: String) -> Self {
+        Self {
+            rule: rule.to_string(),
+            scope,
+            message,
+            severity: Severity::default(),
+        }
+    }
+
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+}
+
+// severity is not part of identity, baseline entries match regardless of it
+impl PartialEq for Finding {
+    fn eq(&self, other: &Self) -> bool {
+        self.rule == other.rule && self.scope == other.scope && self.message == other.message
+    }
+}
+
+// findings below threshold are only logged
+pub fn failing(findings: Vec<Finding>, fail_on: &Severity) -> Vec<Finding> {
+    let (failing, other): (Vec<_>, Vec<_>) =
+        findings.into_iter().partition(|f| &f.severity >= fail_on);
+
+    for finding in other {
+        log::warn!("{}", finding);
+    }
+
+    failing
+}
+
+impl std::fmt::Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}: {}", self.rule, self.scope, self.message)
+    }
+}
+
+// checks which cannot be expressed by openapi json schema, all findings are returned
+pub fn check_openapi(schema: &Schema, formats: &Formats) -> Vec<Finding> {
+    let root = schema.get_body();
+    let mut findings = vec![];
+
+    check_references(
+        root,
+        root,
+        &mut String::new(),
+        SchemaWalk::default(),
+        &mut findings,
+    );
+
+    if let Some(Value::Object(paths)) = root.get("paths") {
+        check_operation_ids(paths, &mut findings);
+        check_path_parameters(root, paths, &mut findings);
+        check_ambiguous_paths(paths, &mut findings);
+    }
+
+    check_security_schemes(root, &mut findings);
+
+    if root.get("paths").is_some() {
+        check_dead_components(root, &mut findings);
+    }
+
+    check_tags(root, &mut findings);
+
+    check_unenforced_constraints(
+        root,
+        &mut String::new(),
+        SchemaWalk::default(),
+        &mut findings,
+    );
+
+    check_format_examples(
+        root,
+        formats,
+        &mut String::new(),
+        SchemaWalk::default(),
+        &mut findings,
+    );
+
+    check_unknown_keywords(
+        root,
+        &keywords::vocabulary(root),
+        &mut String::new(),
+        SchemaWalk::default(),
+        &mut findings,
+    );
+
+    findings
+}
+
+fn operations(paths: &Map<String, Value>) -> Vec<(&String, &str, &Map<String, Value>)> {
+    paths
+        .iter()
+        .flat_map(|(path, item)| {
+            HTTP_METHODS.iter().filter_map(move |method| {
+                item.get(*method)
+                    .and_then(|o| o.as_object())
+                    .map(|o| (path, *method, o))
+            })
+        })
+        .collect()
+}
+
+fn check_references(
+    root: &Value,
+    node: &Value,
+    pointer: &mut String,
+    walk: SchemaWalk,
+    findings: &mut Vec<Finding>,
+) {
+    match node {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get("$ref").filter(|_| !walk.has_names()) {
+                if let Some(local) = reference.strip_prefix('#') {
+                    if root.pointer(local).is_none() {
+                        findings.push(Finding::new(
+                            "missing-reference",
+                            pointer.clone(),
+                            format!("{} cannot be resolved", reference),
+                        ));
+                    }
+                }
+            }
+
+            for (key, value) in map {
+                // examples and defaults are data, `$ref` in them is not a reference
+                if walk.is_instance(key) {
+                    continue;
+                }
+
+                let len = pointer.len();
+                pointer.push('/');
+                pointer.push_str(&pointer_escape(key));
+                check_references(root, value, pointer, walk.enter(key), findings);
+                pointer.truncate(len);
+            }
+        }
+        Value::Array(list) => {
+            for (i, value) in list.iter().enumerate() {
+                let len = pointer.len();
+                pointer.push_str(&format!("/{}", i));
+                check_references(root, value, pointer, SchemaWalk::default(), findings);
+                pointer.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_operation_ids(paths: &Map<String, Value>, findings: &mut Vec<Finding>) {
+    let mut seen: HashMap<&str, String> = HashMap::new();
+
+    for (path, method, operation) in operations(paths) {
+        if let Some(Value::String(id)) = operation.get("operationId") {
+            let scope = format!("/paths/{}/{}", pointer_escape(path), method);
+
+            match seen.get(id.as_str()) {
+                Some(first) => findings.push(Finding::new(
+                    "duplicate-operation-id",
+                    scope,
+                    format!("operationId {} already used in {}", id, first),
+                )),
+                None => {
+                    seen.insert(id, scope);
+                }
+            }
+        }
+    }
+}
+
+// local references are followed, external parameters are not validated
+fn path_parameters<'a>(root: &'a Value, node: Option<&'a Value>) -> Vec<&'a str> {
+    node.and_then(|p| p.as_array())
+        .map(|list| {
+            list.iter()
+                .filter_map(|p| match p.get("$ref").and_then(|r| r.as_str()) {
+                    Some(r) => r.strip_prefix('#').and_then(|l| root.pointer(l)),
+                    None => Some(p),
+                })
+                .filter(|p| p.get("in").and_then(|v| v.as_str()) == Some("path"))
+                .filter_map(|p| p.get("name").and_then(|v| v.as_str()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn check_path_parameters(root: &Value, paths: &Map<String, Value>, findings: &mut Vec<Finding>) {
+    lazy_static! {
+        static ref TEMPLATE: Regex = Regex::new(r"\{([^}]+)\}").unwrap();
+    }
+
+    for (path, method, operation) in operations(paths) {
+        let scope = format!("/paths/{}/{}", pointer_escape(path), method);
+
+        let templated: HashSet<&str> = TEMPLATE
+            .captures_iter(path)
+            .filter_map(|c| c.get(1).map(|m| m.as_str()))
+            .collect();
+
+        let mut declared: HashSet<&str> =
+            path_parameters(root, paths.get(path).and_then(|p| p.get("parameters")))
+                .into_iter()
+                .collect();
+        declared.extend(path_parameters(root, operation.get("parameters")));
+
+        let mut undeclared = templated.difference(&declared).collect::<Vec<_>>();
+        undeclared.sort();
+        for name in undeclared {
+            findings.push(Finding::new(
+                "undeclared-path-parameter",
+                scope.clone(),
+                format!("path parameter {} is not declared", name),
+            ));
+        }
+
+        let mut unused = declared.difference(&templated).collect::<Vec<_>>();
+        unused.sort();
+        for name in unused {
+            findings.push(Finding::new(
+                "unused-path-parameter",
+                scope.clone(),
+                format!("path parameter {} is not part of path", name),
+            ));
+        }
+    }
+}
+
+fn check_ambiguous_paths(paths: &Map<String, Value>, findings: &mut Vec<Finding>) {
+    lazy_static! {
+        static ref TEMPLATE: Regex = Regex::new(r"\{[^}]+\}").unwrap();
+    }
+
+    let mut seen: HashMap<String, &String> = HashMap::new();
+
+    for path in paths.keys() {
+        let normalized = TEMPLATE.replace_all(path, "{}").to_string();
+
+        match seen.get(&normalized) {
+            Some(first) => findings.push(Finding::new(
+                "ambiguous-path",
+                format!("/paths/{}", pointer_escape(path)),
+                format!("path is equivalent to {}", first),
+            )),
+            None => {
+                seen.insert(normalized, path);
+            }
+        }
+    }
+}
+
+fn check_security_schemes(root: &Value, findings: &mut Vec<Finding>) {
+    let schemes = match root.pointer("/components/securitySchemes") {
+        Some(Value::Object(s)) => s,
+        _ => return,
+    };
+
+    let mut used: HashSet<String> = HashSet::new();
+    let mut mark_used = |security: Option<&Value>| {
+        if let Some(Value::Array(list)) = security {
+            for requirement in list.iter().filter_map(|r| r.as_object()) {
+                used.extend(requirement.keys().cloned());
+            }
+        }
+    };
+
+    mark_used(root.get("security"));
+    if let Some(Value::Object(paths)) = root.get("paths") {
+        for (_, _, operation) in operations(paths) {
+            mark_used(operation.get("security"));
+        }
+    }
+
+    for name in schemes.keys() {
+        if !used.contains(name) {
+            findings.push(Finding::new(
+                "unused-security-scheme",
+                format!("/components/securitySchemes/{}", pointer_escape(name)),
+                format!("security scheme {} is never used", name),
+            ));
+        }
+    }
+}
+
+// components not reachable from any operation, security schemes are referenced by name
+fn check_dead_components(root: &Value, findings: &mut Vec<Finding>) {
+    let graph = Graph::new(root);
+
+    let mut reachable: HashSet<&str> = graph
+        .nodes
+        .iter()
+        .filter(|n| n.kind == "operation")
+        .map(|n| n.id.as_str())
+        .collect();
+
+    let mut edges = graph
+        .edges
+        .iter()
+        .map(|e| (e.from.clone(), e.to.clone()))
+        .collect::<Vec<_>>();
+    edges.extend(mapping_edges(root, &graph));
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (from, to) in &edges {
+            if reachable.contains(from.as_str()) && reachable.insert(to.as_str()) {
+                changed = true;
+            }
+        }
+    }
+
+    for node in graph
+        .nodes
+        .iter()
+        .filter(|n| n.kind != "operation" && n.kind != "securitySchemes")
+        .filter(|n| !reachable.contains(n.id.as_str()))
+    {
+        let scope = node.id.trim_start_matches('#').to_string();
+
+        let finding = if node.kind == "parameters" {
+            Finding::new(
+                "unused-parameter",
+                scope,
+                format!("parameter {} is never used by operations", node.name),
+            )
+        } else if node.used_by == 0 {
+            Finding::new(
+                "unused-component",
+                scope,
+                format!("component {} is never referenced", node.name),
+            )
+        } else {
+            Finding::new(
+                "dead-component",
+                scope,
+                format!(
+                    "component {} is referenced only by unused components",
+                    node.name
+                ),
+            )
+        };
+
+        findings.push(finding.with_severity(Severity::Warning));
+    }
+}
+
+// discriminator mapping values use schemas without $ref, by pointer or by component name
+fn mapping_edges(root: &Value, graph: &Graph) -> Vec<(String, String)> {
+    let mut edges = vec![];
+
+    for node in &graph.nodes {
+        let value = match node.id.split_once(' ') {
+            Some((method, path)) => root
+                .get("paths")
+                .and_then(|p| p.get(path))
+                .and_then(|i| i.get(method.to_lowercase())),
+            None => root.pointer(node.id.trim_start_matches('#')),
+        };
+
+        let mut targets = vec![];
+        if let Some(value) = value {
+            collect_mapping(value, SchemaWalk::default(), &mut targets);
+        }
+
+        for target in targets {
+            let target = if target.starts_with('#') {
+                target
+            } else {
+                format!("#/components/schemas/{}", pointer_escape(&target))
+            };
+
+            if let Some(to) = graph.nodes.iter().find(|n| {
+                n.kind != "operation"
+                    && (target == n.id
+                        || target
+                            .strip_prefix(&n.id)
+                            .map(|rest| rest.starts_with('/'))
+                            .unwrap_or(false))
+            }) {
+                edges.push((node.id.clone(), to.id.clone()));
+            }
+        }
+    }
+
+    edges
+}
+
+fn collect_mapping(node: &Value, walk: SchemaWalk, targets: &mut Vec<String>) {
+    match node {
+        Value::Object(map) => {
+            if !walk.has_names() {
+                if let Some(Value::Object(mapping)) =
+                    map.get("discriminator").and_then(|d| d.get("mapping"))
+                {
+                    targets.extend(
+                        mapping
+                            .values()
+                            .filter_map(|v| v.as_str())
+                            .map(String::from),
+                    );
+                }
+            }
+
+            for (key, value) in map {
+                if !walk.is_instance(key) {
+                    collect_mapping(value, walk.enter(key), targets);
+                }
+            }
+        }
+        Value::Array(list) => {
+            for value in list {
+                collect_mapping(value, SchemaWalk::default(), targets);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_tags(root: &Value, findings: &mut Vec<Finding>) {
+    let declared = match root.get("tags") {
+        Some(Value::Array(tags)) => tags,
+        _ => return,
+    };
+
+    let mut used: HashSet<&str> = HashSet::new();
+    if let Some(Value::Object(paths)) = root.get("paths") {
+        for (_, _, operation) in operations(paths) {
+            if let Some(Value::Array(tags)) = operation.get("tags") {
+                used.extend(tags.iter().filter_map(|t| t.as_str()));
+            }
+        }
+    }
+
+    for (i, tag) in declared.iter().enumerate() {
+        if let Some(name) = tag.get("name").and_then(|n| n.as_str()) {
+            if !used.contains(name) {
+                findings.push(
+                    Finding::new(
+                        "unused-tag",
+                        format!("/tags/{}", i),
+                        format!("tag {} is not attached to any operation", name),
+                    )
+                    .with_severity(Severity::Warning),
+                );
+            }
+        }
+    }
+}
+
+// constraints valid for validators which generated types do not enforce
+fn check_unenforced_constraints(
+    node: &Value,
+    pointer: &mut String,
+    walk: SchemaWalk,
+    findings: &mut Vec<Finding>,
+) {
+    match node {
+        Value::Object(map) => {
+            for (key, value) in map {
+                if walk.is_instance(key) {
+                    continue;
+                }
+
+                if !walk.has_names()
+                    && crate::codegen::jsonschema::UNSUPPORTED_KEYWORDS.contains(&key.as_str())
+                {
+                    findings.push(
+                        Finding::new(
+                            "unenforced-constraint",
+                            pointer.clone(),
+                            format!("{} is not enforced by generated code", key),
+                        )
+                        .with_severity(Severity::Info),
+                    );
+                }
+
+                let len = pointer.len();
+                pointer.push('/');
+                pointer.push_str(&pointer_escape(key));
+                check_unenforced_constraints(value, pointer, walk.enter(key), findings);
+                pointer.truncate(len);
+            }
+        }
+        Value::Array(list) => {
+            for (i, value) in list.iter().enumerate() {
+                let len = pointer.len();
+                pointer.push_str(&format!("/{}", i));
+                check_unenforced_constraints(value, pointer, SchemaWalk::default(), findings);
+                pointer.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+// examples and defaults of string schemas must match pattern of their format
+fn check_format_examples(
+    node: &Value,
+    formats: &Formats,
+    pointer: &mut String,
+    walk: SchemaWalk,
+    findings: &mut Vec<Finding>,
+) {
+    match node {
+        Value::Object(map) => {
+            let format = map
+                .get("format")
+                .and_then(|f| f.as_str())
+                .filter(|_| !walk.has_names());
+
+            if let Some(format) = format {
+                for key in &["example", "examples", "default"] {
+                    let values = match map.get(*key) {
+                        Some(Value::Array(list)) if *key == "examples" => list.iter().collect(),
+                        Some(value) if *key != "examples" => vec![value],
+                        _ => vec![],
+                    };
+
+                    for value in values.into_iter().filter_map(|v| v.as_str()) {
+                        if formats.matches(format, value) == Some(false) {
+                            findings.push(
+                                Finding::new(
+                                    "invalid-format-example",
+                                    format!("{}/{}", pointer, key),
+                                    format!("{} {:?} does not match format {}", key, value, format),
+                                )
+                                .with_severity(Severity::Warning),
+                            );
+                        }
+                    }
+                }
+            }
+
+            for (key, value) in map {
+                if walk.is_instance(key) {
+                    continue;
+                }
+
+                let len = pointer.len();
+                pointer.push('/');
+                pointer.push_str(&pointer_escape(key));
+                check_format_examples(value, formats, pointer, walk.enter(key), findings);
+                pointer.truncate(len);
+            }
+        }
+        Value::Array(list) => {
+            for (i, value) in list.iter().enumerate() {
+                let len = pointer.len();
+                pointer.push_str(&format!("/{}", i));
+                check_format_examples(value, formats, pointer, SchemaWalk::default(), findings);
+                pointer.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+// subschemas of schema keywords: single schema, list of schemas or map of schemas
+static SCHEMA_KEYWORDS: &[&str] = &[
+    "items",
+    "additionalItems",
+    "additionalProperties",
+    "not",
+    "if",
+    "then",
+    "else",
+    "contains",
+    "propertyNames",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+    "contentSchema",
+    "allOf",
+    "anyOf",
+    "oneOf",
+    "prefixItems",
+    "properties",
+    "patternProperties",
+    "dependentSchemas",
+    "definitions",
+    "$defs",
+];
+
+// schemas are found under `schema` of parameters, headers and media types and in components
+fn check_unknown_keywords(
+    node: &Value,
+    vocabulary: &[&str],
+    pointer: &mut String,
+    walk: SchemaWalk,
+    findings: &mut Vec<Finding>,
+) {
+    match node {
+        Value::Object(map) => {
+            for (key, value) in map {
+                if walk.is_instance(key) {
+                    continue;
+                }
+
+                let len = pointer.len();
+                pointer.push('/');
+                pointer.push_str(&pointer_escape(key));
+
+                match (key.as_str(), value) {
+                    ("schema", _) if !walk.has_names() => {
+                        check_schema_keywords(value, vocabulary, pointer, findings)
+                    }
+                    ("schemas", Value::Object(schemas)) if pointer == "/components/schemas" => {
+                        for (name, schema) in schemas {
+                            let len = pointer.len();
+                            pointer.push('/');
+                            pointer.push_str(&pointer_escape(name));
+                            check_schema_keywords(schema, vocabulary, pointer, findings);
+                            pointer.truncate(len);
+                        }
+                    }
+                    _ => check_unknown_keywords(
+                        value,
+                        vocabulary,
+                        pointer,
+                        walk.enter(key),
+                        findings,
+                    ),
+                }
+
+                pointer.truncate(len);
+            }
+        }
+        Value::Array(list) => {
+            for (i, value) in list.iter().enumerate() {
+                let len = pointer.len();
+                pointer.push_str(&format!("/{}", i));
+                check_unknown_keywords(value, vocabulary, pointer, SchemaWalk::default(), findings);
+                pointer.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+// typos get suggestion and warning, other unknown keywords may be intended annotations
+fn check_schema_keywords(
+    node: &Value,
+    vocabulary: &[&str],
+    pointer: &mut String,
+    findings: &mut Vec<Finding>,
+) {
+    let map = match node {
+        Value::Object(map) => map,
+        _ => return,
+    };
+
+    for (key, value) in map {
+        if key.starts_with("x-") {
+            continue;
+        }
+
+        if !vocabulary.contains(&key.as_str()) {
+            let finding = match keywords::closest(key, vocabulary) {
+                Some(keyword) => Finding::new(
+                    "unknown-keyword",
+                    pointer.clone(),
+                    format!("unknown keyword {}, did you mean {}?", key, keyword),
+                )
+                .with_severity(Severity::Warning),
+                None => Finding::new(
+                    "unknown-keyword",
+                    pointer.clone(),
+                    format!("unknown keyword {}", key),
+                )
+                .with_severity(Severity::Info),
+            };
+
+            findings.push(finding);
+            continue;
+        }
+
+        if !SCHEMA_KEYWORDS.contains(&key.as_str()) {
+            continue;
+        }
+
+        let len = pointer.len();
+        pointer.push('/');
+        pointer.push_str(&pointer_escape(key));
+
+        match value {
+            Value::Array(list) => {
+                for (i, value) in list.iter().enumerate() {
+                    let len = pointer.len();
+                    pointer.push_str(&format!("/{}", i));
+                    check_schema_keywords(value, vocabulary, pointer, findings);
+                    pointer.truncate(len);
+                }
+            }
+            Value::Object(schemas) if SchemaWalk::default().enter(key).has_names() => {
+                for (name, schema) in schemas {
+                    let len = pointer.len();
+                    pointer.push('/');
+                    pointer.push_str(&pointer_escape(name));
+                    check_schema_keywords(schema, vocabulary, pointer, findings);
+                    pointer.truncate(len);
+                }
+            }
+            value => check_schema_keywords(value, vocabulary, pointer, findings),
+        }
+
+        pointer.truncate(len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_all_findings_are_reported() {
+        let schema = Schema::from_json(json!({
+            "paths": {
+                "/users/{id}": {
+                    "get": {
+                        "operationId": "getUser",
+                        "parameters": [{ "$ref": "#/components/parameters/UserId" }],
+                        "responses": {}
+                    }
+                },
+                "/users/{userId}": {
+                    "delete": {
+                        "operationId": "getUser",
+                        "parameters": [{ "in": "path", "name": "other" }],
+                        "responses": { "200": { "$ref": "#/components/responses/Missing" } }
+                    }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "UserId": { "in": "path", "name": "id", "example": { "$ref": "#/not/a/reference" } }
+                },
+                "securitySchemes": {
+                    "basic": { "type": "http", "scheme": "basic" }
+                }
+            }
+        }));
+
+        let rules = check_openapi(&schema, &Formats::default())
+            .into_iter()
+            .map(|f| f.rule)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            rules,
+            vec![
+                "missing-reference",
+                "duplicate-operation-id",
+                "undeclared-path-parameter",
+                "unused-path-parameter",
+                "ambiguous-path",
+                "unused-security-scheme",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dead_components_and_tags() {
+        let schema = Schema::from_json(json!({
+            "tags": [{ "name": "users" }, { "name": "legacy" }],
+            "paths": {
+                "/users": {
+                    "get": {
+                        "tags": ["users"],
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/User" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "Limit": { "in": "query", "name": "limit" }
+                },
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "discriminator": {
+                            "propertyName": "kind",
+                            "mapping": { "admin": "#/components/schemas/Admin", "guest": "Guest" }
+                        }
+                    },
+                    "Admin": { "type": "object" },
+                    "Guest": { "type": "object" },
+                    "Old": { "properties": { "a": { "$ref": "#/components/schemas/OldPart" } } },
+                    "OldPart": { "type": "string" }
+                }
+            }
+        }));
+
+        let findings = check_openapi(&schema, &Formats::default());
+        let rules = findings.iter().map(|f| f.rule.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(
+            rules,
+            vec![
+                "unused-parameter",
+                "unused-component",
+                "dead-component",
+                "unused-tag"
+            ]
+        );
+        assert_eq!(findings[2].scope, "/components/schemas/OldPart");
+        assert_eq!(failing(findings, &Severity::Error).len(), 0);
+    }
+
+    #[test]
+    fn test_finding_equality_ignores_severity() {
+        let finding = Finding::new("unused-tag", "/tags/0".to_string(), "unused".to_string());
+
+        assert_eq!(finding.clone().with_severity(Severity::Info), finding);
+    }
+
+    #[test]
+    fn test_unenforced_constraints() {
+        let schema = Schema::from_json(json!({
+            "components": {
+                "schemas": {
+                    "not": {
+                        "type": "object",
+                        "propertyNames": { "maxLength": 3 },
+                        "properties": {
+                            "if": { "type": "string", "not": { "const": "x" } }
+                        },
+                        "example": { "not": "example" }
+                    }
+                }
+            }
+        }));
+
+        let findings = check_openapi(&schema, &Formats::default());
+
+        assert_eq!(
+            findings
+                .iter()
+                .map(|f| (f.rule.as_str(), f.scope.as_str(), &f.severity))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "unenforced-constraint",
+                    "/components/schemas/not",
+                    &Severity::Info
+                ),
+                (
+                    "unenforced-constraint",
+                    "/components/schemas/not/properties/if",
+                    &Severity::Info
+                ),
+            ]
+        );
+    }
+    #[test]
+    fn test_invalid_format_examples() {
+        let schema = Schema::from_json(json!({
+            "components": {
+                "schemas": {
+                    "Event": {
+                        "type": "object",
+                        "properties": {
+                            "at": { "type": "string", "format": "date-time", "example": "yesterday" },
+                            "on": { "type": "string", "format": "date", "examples": ["2020-01-01", "01/01/2020"] },
+                            "id": { "type": "string", "format": "uuid", "default": "00000000-0000-0000-0000-000000000000" },
+                            "host": { "type": "string", "format": "hostname", "example": "anything" }
+                        }
+                    }
+                }
+            }
+        }));
+
+        let findings = check_openapi(&schema, &Formats::default());
+
+        assert_eq!(
+            findings
+                .iter()
+                .map(|f| (f.rule.as_str(), f.scope.as_str(), &f.severity))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "invalid-format-example",
+                    "/components/schemas/Event/properties/at/example",
+                    &Severity::Warning
+                ),
+                (
+                    "invalid-format-example",
+                    "/components/schemas/Event/properties/on/examples",
+                    &Severity::Warning
+                ),
+            ]
+        );
+    }
+    #[test]
+    fn test_unknown_keywords() {
+        let schema = Schema::from_json(json!({
+            "openapi": "3.0.3",
+            "paths": {
+                "/users": {
+                    "get": {
+                        "parameters": [
+                            { "in": "query", "name": "limit", "schema": { "type": "integer", "maximun": 10 } }
+                        ],
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/User" },
+                                        "example": { "requierd": true }
+                                    }
+                                }
+                            },
+                            "default": {
+                                "content": {
+                                    "application/json": { "schema": { "type": "integer", "minimun": 1 } }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "requierd": ["name"],
+                        "x-internal": true,
+                        "Properties": {},
+                        "properties": {
+                            "name": { "type": "string", "const": "x" },
+                            "tags": { "items": { "additionalproperties": false } }
+                        }
+                    }
+                }
+            }
+        }));
+
+        let findings = check_openapi(&schema, &Formats::default())
+            .into_iter()
+            .filter(|f| f.rule == "unknown-keyword")
+            .map(|f| (f.scope, f.message, f.severity))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            findings,
+            vec![
+                (
+                    "/paths/~1users/get/parameters/0/schema".to_string(),
+                    "unknown keyword maximun, did you mean maximum?".to_string(),
+                    Severity::Warning
+                ),
+                (
+                    "/paths/~1users/get/responses/default/content/application~1json/schema"
+                        .to_string(),
+                    "unknown keyword minimun, did you mean minimum?".to_string(),
+                    Severity::Warning
+                ),
+                (
+                    "/components/schemas/User".to_string(),
+                    "unknown keyword requierd, did you mean required?".to_string(),
+                    Severity::Warning
+                ),
+                (
+                    "/components/schemas/User".to_string(),
+                    "unknown keyword Properties, did you mean properties?".to_string(),
+                    Severity::Warning
+                ),
+                (
+                    "/components/schemas/User/properties/name".to_string(),
+                    "unknown keyword const".to_string(),
+                    Severity::Info
+                ),
+                (
+                    "/components/schemas/User/properties/tags/items".to_string(),
+                    "unknown keyword additionalproperties, did you mean additionalProperties?"
+                        .to_string(),
+                    Severity::Warning
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,877 @@
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc;
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+use crate::error::Error;
+use crate::formats::Formats;
+use crate::schema::Schema;
+use crate::spec_registry;
+use crate::validate;
+use crate::validate::semantic::Severity;
+
+pub static WORKSPACE_FILES: &[&str] = &[
+    "schematools-workspace.yaml",
+    "schematools-workspace.yml",
+    "schematools-workspace.json",
+];
+
+pub static CONFIG_FILES: &[&str] = &["schematools.yaml", "schematools.yml", "schematools.json"];
+
+// placeholder of chain commands replaced with path of specification
+pub static SPEC_PLACEHOLDER: &str = "{spec}";
+
+// never scanned, whatever globs say
+static SKIPPED_DIRECTORIES: &[&str] = &[".git", "target", "node_modules"];
+
+fn default_specs() -> Vec<String> {
+    vec!["**/openapi.{yaml,yml,json}".to_string()]
+}
+
+// workspace root config, defaults are used when there's none
+#[derive(Debug, Deserialize)]
+pub struct WorkspaceConfig {
+    #[serde(default = "default_specs")]
+    pub specs: Vec<String>,
+
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    // extraction and http caches shared by all specifications, relative to root
+    pub cache_dir: Option<String>,
+}
+
+impl Default for WorkspaceConfig {
+    fn default() -> Self {
+        Self {
+            specs: default_specs(),
+            exclude: vec![],
+            cache_dir: None,
+        }
+    }
+}
+
+// config of specifications in directory (and its subdirectories)
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct DirectoryConfig {
+    // openapi or json-schema, detected when missing
+    pub validate: Option<String>,
+
+    // chain commands run by `workspace apply` in directory of config
+    #[serde(default)]
+    pub chain: Vec<String>,
+
+    // referenced by `depends_on` and registry references, defaults to directory of specification
+    pub name: Option<String>,
+
+    // services applied before this one (ex. generating code it's built with)
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+pub struct Workspace {
+    pub root: PathBuf,
+    pub config: WorkspaceConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct Member {
+    pub name: String,
+    pub spec: PathBuf,
+    pub config_path: Option<PathBuf>,
+    pub config: DirectoryConfig,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Target {
+    pub directory: String,
+    pub exists: bool,
+    pub up_to_date: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Applied,
+    Skipped,
+    Failed,
+
+    // not applied because dependency failed
+    Blocked,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemberStatus {
+    pub spec: String,
+    pub kind: String,
+    pub config: Option<String>,
+    pub valid: bool,
+    pub errors: usize,
+    pub warnings: usize,
+    pub targets: Vec<Target>,
+    pub dependencies: Vec<String>,
+
+    // every codegen target is newer than specification and its config
+    pub processed: bool,
+}
+
+fn read<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, Error> {
+    let url = url::Url::from_file_path(path).map_err(|_| Error::SchemaInvalidPath {
+        path: path.display().to_string(),
+    })?;
+
+    serde_json::from_value(Schema::load_url(url)?.get_body().clone())
+        .map_err(|e| Error::WorkspaceConfigError(format!("{}: {}", path.display(), e)))
+}
+
+fn globs(patterns: &[String]) -> Result<GlobSet, Error> {
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        builder.add(
+            GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .build()
+                .map_err(|e| Error::WorkspaceConfigError(format!("{}: {}", pattern, e)))?,
+        );
+    }
+
+    builder
+        .build()
+        .map_err(|e| Error::WorkspaceConfigError(e.to_string()))
+}
+
+impl Workspace {
+    pub fn load(root: &Path) -> Result<Self, Error> {
+        let root = root.canonicalize().map_err(|_| Error::SchemaInvalidPath {
+            path: root.display().to_string(),
+        })?;
+
+        let config = match WORKSPACE_FILES
+            .iter()
+            .map(|f| root.join(f))
+            .find(|p| p.is_file())
+        {
+            Some(path) => read(&path)?,
+            None => WorkspaceConfig::default(),
+        };
+
+        Ok(Self { root, config })
+    }
+
+    pub fn cache_dir(&self) -> Option<PathBuf> {
+        self.config.cache_dir.as_ref().map(|d| self.root.join(d))
+    }
+
+    // specifications matching globs in order of paths, with nearest config of their directories
+    pub fn members(&self) -> Result<Vec<Member>, Error> {
+        let specs = globs(&self.config.specs)?;
+        let exclude = globs(&self.config.exclude)?;
+
+        let mut members = vec![];
+        let walker = WalkDir::new(&self.root)
+            .sort_by(|a, b| a.file_name().cmp(b.file_name()))
+            .into_iter()
+            .filter_entry(|e| {
+                !(e.file_type().is_dir()
+                    && SKIPPED_DIRECTORIES.contains(&e.file_name().to_string_lossy().as_ref()))
+            });
+
+        for entry in walker.filter_map(|e| e.ok()) {
+            let relative = entry.path().strip_prefix(&self.root).unwrap();
+
+            if entry.file_type().is_file()
+                && specs.is_match(relative)
+                && !exclude.is_match(relative)
+            {
+                let config_path = entry
+                    .path()
+                    .ancestors()
+                    .skip(1)
+                    .take_while(|d| d.starts_with(&self.root))
+                    .flat_map(|d| CONFIG_FILES.iter().map(move |f| d.join(f)))
+                    .find(|p| p.is_file());
+
+                let config = match &config_path {
+                    Some(path) => read(path)?,
+                    None => DirectoryConfig::default(),
+                };
+
+                let name = config.name.clone().unwrap_or_else(|| {
+                    relative
+                        .parent()
+                        .and_then(|p| p.file_name())
+                        .or_else(|| self.root.file_name())
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default()
+                });
+
+                members.push(Member {
+                    name,
+                    spec: relative.to_path_buf(),
+                    config_path: config_path
+                        .map(|p| p.strip_prefix(&self.root).unwrap().to_path_buf()),
+                    config,
+                });
+            }
+        }
+
+        Ok(members)
+    }
+
+    pub fn status(&self, member: &Member) -> MemberStatus {
+        let path = self.root.join(&member.spec);
+        let schema = url::Url::from_file_path(&path)
+            .map_err(|_| Error::SchemaInvalidPath {
+                path: path.display().to_string(),
+            })
+            .and_then(Schema::load_url);
+
+        let kind = match (&member.config.validate, &schema) {
+            (Some(kind), _) => kind.clone(),
+            (None, Ok(s)) if s.get_body().get("openapi").is_none() => "json-schema".to_string(),
+            _ => "openapi".to_string(),
+        };
+
+        let (errors, warnings) = match &schema {
+            Ok(schema) if kind == "json-schema" => (validate::report_jsonschema(schema).count(), 0),
+            Ok(schema) => {
+                let findings =
+                    validate::openapi_findings(schema, true, &Formats::default(), None, &None);
+                let count = |s: Severity| findings.iter().filter(|f| f.severity == s).count();
+
+                (count(Severity::Error), count(Severity::Warning))
+            }
+            Err(e) => {
+                log::error!("{}: {}", member.spec.display(), e);
+                (1, 0)
+            }
+        };
+
+        // outputs are older than anything they are generated from
+        let source = [
+            Some(path.clone()),
+            member.config_path.as_ref().map(|p| self.root.join(p)),
+        ]
+        .iter()
+        .flatten()
+        .filter_map(|p| modified(p))
+        .max();
+
+        let directory = self.directory(member);
+        let targets = targets(&member.config.chain)
+            .into_iter()
+            .map(|target| {
+                let newest = newest(&directory.join(&target));
+
+                Target {
+                    exists: newest.is_some(),
+                    up_to_date: matches!((newest, source), (Some(n), Some(s)) if n >= s),
+                    directory: target,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        MemberStatus {
+            spec: member.spec.display().to_string(),
+            kind,
+            config: member.config_path.as_ref().map(|p| p.display().to_string()),
+            valid: errors == 0,
+            errors,
+            warnings,
+            processed: targets.iter().all(|t| t.up_to_date),
+            targets,
+            dependencies: vec![],
+        }
+    }
+
+    pub fn statuses(&self) -> Result<Vec<MemberStatus>, Error> {
+        let members = self.members()?;
+        let dependencies = self.dependencies(&members)?;
+
+        Ok(members
+            .iter()
+            .zip(dependencies)
+            .map(|(member, dependencies)| MemberStatus {
+                dependencies: dependencies
+                    .into_iter()
+                    .map(|d| members[d].spec.display().to_string())
+                    .collect(),
+                ..self.status(member)
+            })
+            .collect())
+    }
+
+    // indexes of members every member depends on: `depends_on` of its config, registry references
+    // to their names and relative references to files in their directories
+    pub fn dependencies(&self, members: &[Member]) -> Result<Vec<Vec<usize>>, Error> {
+        let directories = members
+            .iter()
+            .map(|m| self.root.join(&m.spec).parent().unwrap().to_path_buf())
+            .collect::<Vec<_>>();
+        let named = |name: &str| {
+            members
+                .iter()
+                .enumerate()
+                .filter(|(_, m)| m.name == name)
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>()
+        };
+
+        let mut dependencies = vec![];
+        for (i, member) in members.iter().enumerate() {
+            let mut depends = BTreeSet::new();
+
+            for name in &member.config.depends_on {
+                let matching = named(name);
+                if matching.is_empty() {
+                    return Err(Error::WorkspaceConfigError(format!(
+                        "{}: unknown dependency {}",
+                        member.spec.display(),
+                        name
+                    )));
+                }
+
+                depends.extend(matching);
+            }
+
+            // specification which cannot be loaded fails when applied
+            let path = self.root.join(&member.spec);
+            let mut references = vec![];
+            if let Ok(schema) = url::Url::from_file_path(&path)
+                .map_err(|_| Error::SchemaInvalidPath {
+                    path: path.display().to_string(),
+                })
+                .and_then(Schema::load_url)
+            {
+                collect(schema.get_body(), &mut references);
+            }
+
+            for reference in references {
+                if let Some(target) = reference.strip_prefix(spec_registry::PREFIX) {
+                    depends.extend(named(target.split(&['@', '#'][..]).next().unwrap()));
+                } else if !reference.starts_with('#') && !reference.contains("://") {
+                    let file = reference.split('#').next().unwrap();
+
+                    // file belongs to member of the deepest directory containing it
+                    if let Ok(file) = directories[i].join(file).canonicalize() {
+                        let owner = directories
+                            .iter()
+                            .filter(|d| file.starts_with(d))
+                            .max_by_key(|d| d.components().count());
+
+                        depends.extend(
+                            directories
+                                .iter()
+                                .enumerate()
+                                .filter(|(_, d)| Some(*d) == owner)
+                                .map(|(j, _)| j),
+                        );
+                    }
+                }
+            }
+
+            depends.remove(&i);
+            dependencies.push(depends.into_iter().collect());
+        }
+
+        match cycle(&dependencies) {
+            Some(cycle) => Err(Error::WorkspaceConfigError(format!(
+                "dependency cycle between {}",
+                cycle
+                    .iter()
+                    .map(|i| members[*i].spec.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+            None => Ok(dependencies),
+        }
+    }
+
+    // directory chain of member is run in
+    pub fn directory(&self, member: &Member) -> PathBuf {
+        match &member.config_path {
+            Some(config) => self.root.join(config).parent().unwrap().to_path_buf(),
+            None => self.root.join(&member.spec).parent().unwrap().to_path_buf(),
+        }
+    }
+
+    // chain commands with path of specification relative to directory they are run in,
+    // codegen shares extraction cache of workspace
+    pub fn commands(&self, member: &Member) -> Vec<String> {
+        let directory = self.directory(member);
+        let spec = self.root.join(&member.spec);
+        let spec = spec
+            .strip_prefix(&directory)
+            .map(|p| p.to_path_buf())
+            .unwrap_or(spec);
+
+        member
+            .config
+            .chain
+            .iter()
+            .map(|command| {
+                let mut command = command.replace(SPEC_PLACEHOLDER, &spec.display().to_string());
+
+                if let Some(cache) = self.cache_dir() {
+                    if command.starts_with("codegen ") && !command.contains("--cache") {
+                        command
+                            .push_str(&format!(" --cache-dir {}", cache.join("codegen").display()));
+                    }
+                }
+
+                command
+            })
+            .collect()
+    }
+
+    // chain runs in separate process in directory of config, so paths of commands are
+    // relative to it like when the tool is run per service, output is written to log file
+    pub fn apply(&self, member: &Member, verbosity: i8, log: &Path) -> Result<(), Error> {
+        let failed = |e: &dyn std::fmt::Display| {
+            Error::WorkspaceApplyError(member.spec.display().to_string(), e.to_string())
+        };
+
+        let executable = std::env::current_exe().map_err(|e| failed(&e))?;
+        let output = std::fs::create_dir_all(log.parent().unwrap())
+            .and_then(|_| File::create(log))
+            .map_err(|e| failed(&e))?;
+
+        let mut command = Command::new(executable);
+        command
+            .current_dir(self.directory(member))
+            .stdout(output.try_clone().map_err(|e| failed(&e))?)
+            .stderr(output)
+            .arg("chain");
+
+        if verbosity > 0 {
+            command.arg(format!("-{}", "v".repeat(verbosity as usize)));
+        }
+        for chained in self.commands(member) {
+            command.arg("-c").arg(chained);
+        }
+        if let Some(cache) = self.cache_dir() {
+            command.env("SCHEMATOOLS_HTTP_CACHE", cache.join("http"));
+        }
+
+        let status = command.status().map_err(|e| failed(&e))?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(failed(&status))
+        }
+    }
+
+    // log file of member in directory of logs
+    pub fn log(&self, directory: &Path, member: &Member) -> PathBuf {
+        directory.join(format!(
+            "{}.log",
+            member.spec.display().to_string().replace('/', "_")
+        ))
+    }
+}
+
+// runs task of every node in up to `jobs` threads, node starts when all of its dependencies
+// are applied or skipped, dependents of failed nodes are blocked (and all nodes waiting
+// when `continue_on_error` is not set)
+pub fn execute<F>(
+    dependencies: &[Vec<usize>],
+    jobs: usize,
+    continue_on_error: bool,
+    task: F,
+) -> Vec<Outcome>
+where
+    F: Fn(usize) -> Outcome + Sync,
+{
+    let mut outcomes: Vec<Option<Outcome>> = vec![None; dependencies.len()];
+    let mut started = vec![false; dependencies.len()];
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::scope(|scope| {
+        let (mut running, mut failed) = (0, false);
+
+        loop {
+            let mut changed = true;
+            while changed {
+                changed = false;
+
+                for node in 0..dependencies.len() {
+                    if started[node] {
+                        continue;
+                    }
+
+                    let blocked = (failed && !continue_on_error)
+                        || dependencies[node].iter().any(|d| {
+                            matches!(outcomes[*d], Some(Outcome::Failed | Outcome::Blocked))
+                        });
+
+                    if blocked {
+                        outcomes[node] = Some(Outcome::Blocked);
+                    } else if running < jobs.max(1)
+                        && dependencies[node].iter().all(|d| outcomes[*d].is_some())
+                    {
+                        let (sender, task) = (sender.clone(), &task);
+                        scope.spawn(move || {
+                            // panicking task fails, scheduler keeps waiting only for running ones
+                            let outcome = panic::catch_unwind(AssertUnwindSafe(|| task(node)))
+                                .unwrap_or_else(|_| {
+                                    log::error!("task of member {} panicked", node);
+                                    Outcome::Failed
+                                });
+
+                            sender.send((node, outcome)).unwrap()
+                        });
+                        running += 1;
+                    } else {
+                        continue;
+                    }
+
+                    started[node] = true;
+                    changed = true;
+                }
+            }
+
+            if running == 0 {
+                break;
+            }
+
+            let (node, outcome) = receiver.recv().unwrap();
+            failed |= outcome == Outcome::Failed;
+            outcomes[node] = Some(outcome);
+            running -= 1;
+        }
+    });
+
+    outcomes
+        .into_iter()
+        .map(|o| o.unwrap_or(Outcome::Blocked))
+        .collect()
+}
+
+// nodes left when all nodes without unvisited dependencies are removed
+fn cycle(dependencies: &[Vec<usize>]) -> Option<Vec<usize>> {
+    let mut left = (0..dependencies.len()).collect::<BTreeSet<_>>();
+
+    loop {
+        let free = left
+            .iter()
+            .copied()
+            .filter(|n| dependencies[*n].iter().all(|d| !left.contains(d)))
+            .collect::<Vec<_>>();
+
+        if free.is_empty() {
+            break;
+        }
+        for node in free {
+            left.remove(&node);
+        }
+    }
+
+    if left.is_empty() {
+        None
+    } else {
+        Some(left.into_iter().collect())
+    }
+}
+
+// every `$ref` of document
+fn collect(node: &Value, references: &mut Vec<String>) {
+    match node {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get("$ref") {
+                references.push(reference.clone());
+            }
+
+            map.values().for_each(|v| collect(v, references));
+        }
+        Value::Array(list) => list.iter().for_each(|v| collect(v, references)),
+        _ => {}
+    }
+}
+
+// `--target-dir` of codegen commands
+fn targets(chain: &[String]) -> Vec<String> {
+    let mut targets = vec![];
+
+    for command in chain.iter().filter(|c| c.starts_with("codegen ")) {
+        let mut arguments = crate::tools::ArgumentsExtractor::new(command);
+
+        while let Some(argument) = arguments.next() {
+            if argument == "--target-dir" {
+                targets.extend(arguments.next());
+            } else if let Some(target) = argument.strip_prefix("--target-dir=") {
+                targets.push(target.to_string());
+            }
+        }
+    }
+
+    targets
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+// last modification of any file in directory
+fn newest(directory: &Path) -> Option<SystemTime> {
+    WalkDir::new(directory)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| modified(e.path()))
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_members_and_status() {
+        let mut root = std::env::temp_dir();
+        root.push(format!(
+            "schema-tools-workspace-test-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+
+        write(
+            "schematools-workspace.yaml",
+            "specs: ['services/*/openapi.yaml', 'events/**/*.json']\nexclude: ['services/legacy/**']\ncache_dir: .cache\n",
+        );
+        write(
+            "services/orders/openapi.yaml",
+            "openapi: 3.0.3\ninfo: {title: orders, version: 1.0.0}\npaths: {}\n",
+        );
+        write(
+            "services/orders/schematools.yaml",
+            "chain:\n  - process merge-all-of {spec}\n  - codegen openapi - --template t/ --target-dir gen/client/\n",
+        );
+        write("services/legacy/openapi.yaml", "openapi: 3.0.3\n");
+        write("services/users/openapi.yaml", "openapi: 3.0.3\npaths: {}\n");
+        write("events/user/created.json", "{\"type\": \"object\"}");
+        write("node_modules/x/openapi.yaml", "openapi: 3.0.3\n");
+
+        let workspace = Workspace::load(&root).unwrap();
+        let members = workspace.members().unwrap();
+        assert_eq!(
+            members
+                .iter()
+                .map(|m| m.spec.display().to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "events/user/created.json",
+                "services/orders/openapi.yaml",
+                "services/users/openapi.yaml",
+            ]
+        );
+
+        let statuses = members
+            .iter()
+            .map(|m| workspace.status(m))
+            .collect::<Vec<_>>();
+        assert_eq!(statuses[0].kind, "json-schema");
+        assert!(statuses[0].valid && statuses[0].processed);
+        assert!(statuses[1].valid);
+        assert_eq!(
+            statuses[1].targets,
+            vec![Target {
+                directory: "gen/client/".to_string(),
+                exists: false,
+                up_to_date: false,
+            }]
+        );
+        assert!(!statuses[1].processed);
+        assert!(!statuses[2].valid);
+
+        assert_eq!(
+            workspace.commands(&members[1]),
+            vec![
+                "process merge-all-of openapi.yaml".to_string(),
+                format!(
+                    "codegen openapi - --template t/ --target-dir gen/client/ --cache-dir {}",
+                    root.canonicalize()
+                        .unwrap()
+                        .join(".cache/codegen")
+                        .display()
+                ),
+            ]
+        );
+
+        write("services/orders/gen/client/mod.rs", "");
+        assert!(workspace.status(&members[1]).processed);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_workspace_dependencies() {
+        let mut root = std::env::temp_dir();
+        root.push(format!(
+            "schema-tools-workspace-dependencies-test-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+
+        write(
+            "schematools-workspace.yaml",
+            "specs: ['**/openapi.yaml']
+",
+        );
+        write(
+            "models/openapi.yaml",
+            "openapi: 3.0.3
+components: {schemas: {Money: {type: number}}}
+",
+        );
+        write("orders/openapi.yaml", "openapi: 3.0.3
+components: {schemas: {Total: {$ref: '../models/openapi.yaml#/components/schemas/Money'}, Item: {$ref: 'schemas/item.yaml'}}}
+");
+        write(
+            "orders/schematools.yaml",
+            "name: orders-api
+",
+        );
+        write(
+            "orders/schemas/item.yaml",
+            "type: object
+",
+        );
+        write(
+            "users/openapi.yaml",
+            "openapi: 3.0.3
+components: {schemas: {Order: {$ref: 'registry:orders-api@^1#/components/schemas/Total'}}}
+",
+        );
+        write(
+            "gateway/openapi.yaml",
+            "openapi: 3.0.3
+",
+        );
+        write(
+            "gateway/schematools.yaml",
+            "depends_on: [users]
+",
+        );
+
+        let workspace = Workspace::load(&root).unwrap();
+        let members = workspace.members().unwrap();
+        assert_eq!(
+            members.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(),
+            vec!["gateway", "models", "orders-api", "users"]
+        );
+        assert_eq!(
+            workspace.dependencies(&members).unwrap(),
+            vec![vec![3], vec![], vec![1], vec![2]]
+        );
+        assert_eq!(
+            workspace.statuses().unwrap()[0].dependencies,
+            vec!["users/openapi.yaml".to_string()]
+        );
+
+        write(
+            "models/schematools.yaml",
+            "depends_on: [gateway]
+",
+        );
+        let members = workspace.members().unwrap();
+        assert!(matches!(
+            workspace.dependencies(&members),
+            Err(Error::WorkspaceConfigError(_))
+        ));
+
+        write(
+            "models/schematools.yaml",
+            "depends_on: [billing]
+",
+        );
+        let members = workspace.members().unwrap();
+        assert!(matches!(
+            workspace.dependencies(&members),
+            Err(Error::WorkspaceConfigError(_))
+        ));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_parallel_execution() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Mutex;
+
+        let dependencies = vec![vec![], vec![0], vec![0], vec![1, 2], vec![], vec![4]];
+        let finished = Mutex::new(vec![]);
+        let (running, most) = (AtomicUsize::new(0), AtomicUsize::new(0));
+
+        let outcomes = execute(&dependencies, 2, true, |node| {
+            most.fetch_max(running.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            running.fetch_sub(1, Ordering::SeqCst);
+            finished.lock().unwrap().push(node);
+
+            match node {
+                4 => Outcome::Failed,
+                2 => Outcome::Skipped,
+                _ => Outcome::Applied,
+            }
+        });
+
+        assert_eq!(
+            outcomes,
+            vec![
+                Outcome::Applied,
+                Outcome::Applied,
+                Outcome::Skipped,
+                Outcome::Applied,
+                Outcome::Failed,
+                Outcome::Blocked
+            ]
+        );
+        assert_eq!(most.load(Ordering::SeqCst), 2);
+
+        let finished = finished.into_inner().unwrap();
+        let position = |n: usize| finished.iter().position(|f| *f == n).unwrap();
+        assert!(position(0) < position(1) && position(0) < position(2));
+        assert!(position(1) < position(3) && position(2) < position(3));
+
+        assert_eq!(
+            execute(&[vec![], vec![0], vec![]], 1, false, |_| Outcome::Failed),
+            vec![Outcome::Failed, Outcome::Blocked, Outcome::Blocked]
+        );
+    }
+
+    #[test]
+    fn test_panicking_task_fails() {
+        let outcomes = execute(&[vec![], vec![0], vec![]], 2, true, |node| {
+            if node == 0 {
+                panic!("task failed");
+            }
+            Outcome::Applied
+        });
+
+        assert_eq!(
+            outcomes,
+            vec![Outcome::Failed, Outcome::Blocked, Outcome::Applied]
+        );
+    }
+}